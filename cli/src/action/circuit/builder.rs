@@ -15,8 +15,8 @@
 #[cfg(feature = "circuit-auth-type")]
 use splinter::admin::messages::AuthorizationType;
 use splinter::admin::messages::{
-    BuilderError, CreateCircuit, CreateCircuitBuilder, SplinterNode, SplinterNodeBuilder,
-    SplinterServiceBuilder,
+    BuilderError, CreateCircuit, CreateCircuitBuilder, DurabilityType, RouteType, SplinterNode,
+    SplinterNodeBuilder, SplinterServiceBuilder, VotingPolicy,
 };
 
 use crate::error::CliError;
@@ -34,6 +34,9 @@ pub struct CreateCircuitMessageBuilder {
     authorization_type: Option<AuthorizationType>,
     application_metadata: Vec<u8>,
     comments: Option<String>,
+    voting_policy: Option<VotingPolicy>,
+    route_type: Option<RouteType>,
+    durability: Option<DurabilityType>,
 }
 
 impl CreateCircuitMessageBuilder {
@@ -47,6 +50,9 @@ impl CreateCircuitMessageBuilder {
             authorization_type: None,
             application_metadata: vec![],
             comments: None,
+            voting_policy: None,
+            route_type: None,
+            durability: None,
         }
     }
 
@@ -232,6 +238,63 @@ impl CreateCircuitMessageBuilder {
         self.comments = Some(comments.into());
     }
 
+    /// Sets the voting policy from either `unanimous`, `majority` or the number of members that
+    /// must accept a proposal.
+    pub fn set_voting_policy(&mut self, voting_policy: &str) -> Result<(), CliError> {
+        let voting_policy = match voting_policy {
+            "unanimous" => VotingPolicy::Unanimous,
+            "majority" => VotingPolicy::Majority,
+            required_votes => match required_votes.parse::<u32>() {
+                Ok(required_votes) => VotingPolicy::Threshold(required_votes),
+                Err(_) => {
+                    return Err(CliError::ActionError(format!(
+                        "Invalid voting policy {}: must be 'unanimous', 'majority' or a number \
+                         of members",
+                        voting_policy
+                    )))
+                }
+            },
+        };
+
+        self.voting_policy = Some(voting_policy);
+        Ok(())
+    }
+
+    /// Sets how messages are routed between the circuit's members, from either `any` or `relay`.
+    pub fn set_route_type(&mut self, route_type: &str) -> Result<(), CliError> {
+        let route_type = match route_type {
+            "any" => RouteType::Any,
+            "relay" => RouteType::Relay,
+            _ => {
+                return Err(CliError::ActionError(format!(
+                    "Invalid route type {}: must be 'any' or 'relay'",
+                    route_type
+                )))
+            }
+        };
+
+        self.route_type = Some(route_type);
+        Ok(())
+    }
+
+    /// Sets what happens to messages for members that are not connected, from either `none` or
+    /// `durable`.
+    pub fn set_durability(&mut self, durability: &str) -> Result<(), CliError> {
+        let durability = match durability {
+            "none" => DurabilityType::NoDurability,
+            "durable" => DurabilityType::Durable,
+            _ => {
+                return Err(CliError::ActionError(format!(
+                    "Invalid durability {}: must be 'none' or 'durable'",
+                    durability
+                )))
+            }
+        };
+
+        self.durability = Some(durability);
+        Ok(())
+    }
+
     pub fn build(mut self) -> Result<CreateCircuit, CliError> {
         let circuit_builder = self.create_circuit_builder();
 
@@ -303,6 +366,21 @@ impl CreateCircuitMessageBuilder {
             None => create_circuit_builder,
         };
 
+        let create_circuit_builder = match self.voting_policy {
+            Some(voting_policy) => create_circuit_builder.with_voting_policy(&voting_policy),
+            None => create_circuit_builder,
+        };
+
+        let create_circuit_builder = match self.route_type {
+            Some(route_type) => create_circuit_builder.with_routes(&route_type),
+            None => create_circuit_builder,
+        };
+
+        let create_circuit_builder = match self.durability {
+            Some(durability) => create_circuit_builder.with_durability(&durability),
+            None => create_circuit_builder,
+        };
+
         let create_circuit = create_circuit_builder.build().map_err(|err| {
             CliError::ActionError(format!(
                 "Failed to build circuit: {}",
@@ -156,6 +156,30 @@ impl Action for CircuitProposeAction {
             builder.set_comments(comments);
         }
 
+        if let Some(voting_policy) = args.value_of("voting_policy") {
+            builder.set_voting_policy(voting_policy)?;
+        }
+
+        if let Some(route_type) = args.value_of("route_type") {
+            builder.set_route_type(route_type)?;
+        }
+
+        if let Some(durability) = args.value_of("durability") {
+            builder.set_durability(durability)?;
+        }
+
+        let proposal_ttl = args
+            .value_of("ttl")
+            .map(|ttl| {
+                ttl.parse::<u64>().map_err(|_| {
+                    CliError::ActionError(format!(
+                        "Proposal time-to-live must be a number of seconds: {}",
+                        ttl
+                    ))
+                })
+            })
+            .transpose()?;
+
         let create_circuit = builder.build()?;
 
         let circuit_slice = CircuitSlice::from(&create_circuit);
@@ -172,8 +196,12 @@ impl Action for CircuitProposeAction {
             let requester_node = client.get_node_status()?.node_id;
             let private_key_hex = read_private_key(key)?;
 
-            let signed_payload =
-                make_signed_payload(&requester_node, &private_key_hex, create_circuit)?;
+            let signed_payload = make_signed_payload(
+                &requester_node,
+                &private_key_hex,
+                create_circuit,
+                proposal_ttl,
+            )?;
             client.submit_admin_payload(signed_payload)?;
 
             info!("The circuit proposal was submited successfully");
@@ -572,7 +600,55 @@ fn vote_on_circuit_proposal(
             circuit_hash: proposal.circuit_hash,
             vote,
         };
-        let signed_payload = make_signed_payload(&requester_node, &private_key_hex, circuit_vote)?;
+        let signed_payload =
+            make_signed_payload(&requester_node, &private_key_hex, circuit_vote, None)?;
+        client.submit_admin_payload(signed_payload)
+    } else {
+        Err(CliError::ActionError(format!(
+            "Proposal for circuit '{}' does not exist",
+            circuit_id
+        )))
+    }
+}
+
+struct CircuitCancel {
+    circuit_id: String,
+    circuit_hash: String,
+}
+
+pub struct CircuitCancelAction;
+
+impl Action for CircuitCancelAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or_else(|| CliError::RequiresArgs)?;
+        let url = args
+            .value_of("url")
+            .map(ToOwned::to_owned)
+            .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+        let key = args.value_of("private_key_file").unwrap_or("splinter");
+        let circuit_id = args
+            .value_of("circuit_id")
+            .ok_or_else(|| CliError::ActionError("'circuit-id' argument is required".into()))?;
+
+        cancel_circuit_proposal(&url, key, circuit_id)
+    }
+}
+
+fn cancel_circuit_proposal(url: &str, key: &str, circuit_id: &str) -> Result<(), CliError> {
+    let client = SplinterRestClient::new(url);
+    let private_key_hex = read_private_key(key)?;
+
+    let requester_node = client.get_node_status()?.node_id;
+    let proposal = client.fetch_proposal(circuit_id)?;
+
+    if let Some(proposal) = proposal {
+        let circuit_cancel = CircuitCancel {
+            circuit_id: circuit_id.into(),
+            circuit_hash: proposal.circuit_hash,
+        };
+        let signed_payload =
+            make_signed_payload(&requester_node, &private_key_hex, circuit_cancel, None)?;
         client.submit_admin_payload(signed_payload)
     } else {
         Err(CliError::ActionError(format!(
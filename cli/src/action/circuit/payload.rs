@@ -18,13 +18,14 @@ use sawtooth_sdk::signing::secp256k1;
 use splinter::admin::messages::CreateCircuit;
 use splinter::protos::admin::{
     CircuitCreateRequest, CircuitManagementPayload, CircuitManagementPayload_Action as Action,
-    CircuitManagementPayload_Header as Header, CircuitProposalVote, CircuitProposalVote_Vote,
+    CircuitManagementPayload_Header as Header, CircuitProposalCancel, CircuitProposalVote,
+    CircuitProposalVote_Vote,
 };
 use splinter::signing::{sawtooth, Signer};
 
 use crate::error::CliError;
 
-use super::{CircuitVote, Vote};
+use super::{CircuitCancel, CircuitVote, Vote};
 
 /// A circuit action that has a type and can be converted into a protobuf-serializable struct.
 pub trait CircuitAction<M: Message> {
@@ -39,10 +40,14 @@ pub trait ApplyToEnvelope {
 }
 
 /// Makes a signed, circuit management payload to be submitted to the Splinter REST API.
+///
+/// If a `proposal_ttl` (in seconds) is given, a proposal created by the payload expires if it has
+/// not been accepted or rejected within that time.
 pub fn make_signed_payload<M, A>(
     requester_node: &str,
     private_key: &str,
     action: A,
+    proposal_ttl: Option<u64>,
 ) -> Result<Vec<u8>, CliError>
 where
     M: Message + ApplyToEnvelope,
@@ -72,6 +77,9 @@ where
     header.set_payload_sha512(hashed_bytes.to_vec());
     header.set_requester(public_key);
     header.set_requester_node_id(requester_node.into());
+    if let Some(proposal_ttl) = proposal_ttl {
+        header.set_proposal_ttl(proposal_ttl);
+    }
     let header_bytes = header.write_to_bytes().map_err(|err| {
         CliError::ActionError(format!("Failed to serialize payload header: {}", err))
     })?;
@@ -136,3 +144,23 @@ impl ApplyToEnvelope for CircuitProposalVote {
         circuit_management_payload.set_circuit_proposal_vote(self);
     }
 }
+
+impl CircuitAction<CircuitProposalCancel> for CircuitCancel {
+    fn action_type(&self) -> Action {
+        Action::CIRCUIT_PROPOSAL_CANCEL
+    }
+
+    fn into_proto(self) -> Result<CircuitProposalCancel, CliError> {
+        let mut cancel = CircuitProposalCancel::new();
+        cancel.set_circuit_id(self.circuit_id);
+        cancel.set_circuit_hash(self.circuit_hash);
+
+        Ok(cancel)
+    }
+}
+
+impl ApplyToEnvelope for CircuitProposalCancel {
+    fn apply(self, circuit_management_payload: &mut CircuitManagementPayload) {
+        circuit_management_payload.set_circuit_proposal_cancel(self);
+    }
+}
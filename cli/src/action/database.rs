@@ -17,6 +17,10 @@ use clap::ArgMatches;
 use super::Action;
 use crate::error::CliError;
 use diesel::{connection::Connection as _, pg::PgConnection};
+#[cfg(feature = "database-migrate-admin-service-event-store")]
+use splinter::admin::service::event_store::diesel::migrations as event_store_migrations;
+#[cfg(feature = "database-migrate-admin-service-store")]
+use splinter::admin::store::diesel::migrations as admin_store_migrations;
 #[cfg(feature = "database-migrate-biome")]
 use splinter::biome::migrations::run_postgres_migrations;
 
@@ -43,6 +47,22 @@ impl Action for MigrateAction {
             CliError::ActionError(format!("Unable to run Biome migrations: {}", err))
         })?;
 
+        #[cfg(feature = "database-migrate-admin-service-event-store")]
+        event_store_migrations::run_postgres_migrations(&connection).map_err(|err| {
+            CliError::ActionError(format!(
+                "Unable to run admin service event store migrations: {}",
+                err
+            ))
+        })?;
+
+        #[cfg(feature = "database-migrate-admin-service-store")]
+        admin_store_migrations::run_postgres_migrations(&connection).map_err(|err| {
+            CliError::ActionError(format!(
+                "Unable to run admin service store migrations: {}",
+                err
+            ))
+        })?;
+
         Ok(())
     }
 }
@@ -22,6 +22,8 @@ pub mod database;
 #[cfg(feature = "health")]
 pub mod health;
 pub mod keygen;
+#[cfg(feature = "network")]
+pub mod network;
 pub mod registry;
 
 use std::collections::HashMap;
@@ -0,0 +1,69 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use reqwest::{blocking::Client, StatusCode};
+use serde::Deserialize;
+use splinter::protocol::NETWORK_PROTOCOL_VERSION;
+
+use crate::action::api::{ServerError, SplinterRestClient};
+use crate::error::CliError;
+
+impl<'a> SplinterRestClient<'a> {
+    /// Has this client's Splinter node ping the given node, which it must be connected to.
+    pub fn ping_node(&self, node_id: &str) -> Result<PingResponse, CliError> {
+        Client::new()
+            .get(&format!("{}/network/ping/{}", self.url, node_id))
+            .header("SplinterProtocolVersion", NETWORK_PROTOCOL_VERSION)
+            .send()
+            .map_err(|err| CliError::ActionError(format!("Failed to ping node: {}", err)))
+            .and_then(|res| {
+                let status = res.status();
+                if status.is_success() {
+                    res.json::<PingResponse>().map_err(|_| {
+                        CliError::ActionError(
+                            "Request was successful, but received an invalid response".into(),
+                        )
+                    })
+                } else if status == StatusCode::NOT_FOUND && res.content_length() == Some(0) {
+                    Err(CliError::ActionError(
+                        "The ping endpoint was not found. The specified splinter daemon has not \
+                         enabled this feature."
+                            .into(),
+                    ))
+                } else {
+                    let message = res
+                        .json::<ServerError>()
+                        .map_err(|_| {
+                            CliError::ActionError(format!(
+                                "Ping request failed with status code '{}', but error response \
+                                 was not valid",
+                                status
+                            ))
+                        })?
+                        .message;
+
+                    Err(CliError::ActionError(format!(
+                        "Failed to ping node: {}",
+                        message
+                    )))
+                }
+            })
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PingResponse {
+    pub node_id: String,
+    pub round_trip_time_ms: f64,
+}
@@ -0,0 +1,48 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod api;
+
+use clap::ArgMatches;
+
+use super::api::SplinterRestClient;
+use super::{Action, DEFAULT_SPLINTER_REST_API_URL, SPLINTER_REST_API_URL_ENV};
+
+use crate::error::CliError;
+
+pub struct PingAction;
+
+impl Action for PingAction {
+    fn run<'a>(&mut self, arg_matches: Option<&ArgMatches<'a>>) -> Result<(), CliError> {
+        let args = arg_matches.ok_or(CliError::RequiresArgs)?;
+
+        let url = args
+            .value_of("url")
+            .map(ToOwned::to_owned)
+            .or_else(|| std::env::var(SPLINTER_REST_API_URL_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SPLINTER_REST_API_URL.to_string());
+
+        let node_id = args
+            .value_of("node_id")
+            .ok_or_else(|| CliError::ActionError("'node-id' argument is required".into()))?;
+
+        let ping = SplinterRestClient::new(&url).ping_node(node_id)?;
+        println!(
+            "Reply from {}: time={:.3} ms",
+            ping.node_id, ping.round_trip_time_ms
+        );
+
+        Ok(())
+    }
+}
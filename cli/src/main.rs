@@ -302,6 +302,43 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
                 .takes_value(true)
                 .help("Add human-readable comments to the proposal"),
         )
+        .arg(
+            Arg::with_name("voting_policy")
+                .long("voting-policy")
+                .value_name("policy")
+                .takes_value(true)
+                .help(
+                    "Number of members that must accept proposals for the circuit: \
+                     'unanimous', 'majority' or a number (default: unanimous)",
+                ),
+        )
+        .arg(
+            Arg::with_name("route_type")
+                .long("route-type")
+                .value_name("route-type")
+                .takes_value(true)
+                .possible_values(&["any", "relay"])
+                .help(
+                    "How messages are routed between members: 'relay' allows relaying \
+                     through another member when two members are not connected \
+                     (default: any)",
+                ),
+        )
+        .arg(
+            Arg::with_name("durability")
+                .long("durability")
+                .value_name("durability")
+                .takes_value(true)
+                .possible_values(&["none", "durable"])
+                .help(
+                    "What happens to messages for members that are not connected: 'durable' \
+                     stores them until the member reconnects (default: none)",
+                ),
+        )
+        .arg(Arg::with_name("ttl").long("ttl").takes_value(true).help(
+            "Number of seconds the proposal waits for votes before it expires \
+                     (default: the proposing node's default)",
+        ))
         .arg(
             Arg::with_name("dry_run")
                 .long("dry-run")
@@ -385,6 +422,32 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
                         .help("Reject the proposal"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("cancel")
+                .about("Cancel a circuit proposal submitted by this node")
+                .arg(
+                    Arg::with_name("url")
+                        .short("U")
+                        .long("url")
+                        .takes_value(true)
+                        .help("URL of Splinter Daemon"),
+                )
+                .arg(
+                    Arg::with_name("private_key_file")
+                        .value_name("private-key-file")
+                        .short("k")
+                        .long("key")
+                        .takes_value(true)
+                        .help("Path to private key file"),
+                )
+                .arg(
+                    Arg::with_name("circuit_id")
+                        .value_name("circuit-id")
+                        .takes_value(true)
+                        .required(true)
+                        .help("ID of the proposed circuit"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("list")
                 .about("List the circuits")
@@ -605,6 +668,33 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
         );
     }
 
+    #[cfg(feature = "network")]
+    {
+        app = app.subcommand(
+            SubCommand::with_name("network")
+                .about("Provides commands to inspect the node's network")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("ping")
+                        .about("Measures the round trip time to a connected node")
+                        .arg(
+                            Arg::with_name("node_id")
+                                .value_name("node-id")
+                                .takes_value(true)
+                                .required(true)
+                                .help("ID of the node to ping"),
+                        )
+                        .arg(
+                            Arg::with_name("url")
+                                .short("U")
+                                .long("url")
+                                .help("URL of the Splinter daemon REST API")
+                                .takes_value(true),
+                        ),
+                ),
+        );
+    }
+
     #[cfg(feature = "database")]
     {
         app = app.subcommand(
@@ -671,6 +761,7 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
     let circuit_command = SubcommandActions::new()
         .with_command("propose", circuit::CircuitProposeAction)
         .with_command("vote", circuit::CircuitVoteAction)
+        .with_command("cancel", circuit::CircuitCancelAction)
         .with_command("list", circuit::CircuitListAction)
         .with_command("show", circuit::CircuitShowAction)
         .with_command("proposals", circuit::CircuitProposalsAction);
@@ -700,6 +791,15 @@ fn run<I: IntoIterator<Item = T>, T: Into<OsString> + Clone>(args: I) -> Result<
         );
     }
 
+    #[cfg(feature = "network")]
+    {
+        use action::network;
+        subcommands = subcommands.with_command(
+            "network",
+            SubcommandActions::new().with_command("ping", network::PingAction),
+        );
+    }
+
     #[cfg(feature = "database")]
     {
         use action::database;
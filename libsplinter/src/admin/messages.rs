@@ -15,7 +15,7 @@
 pub use super::service::messages::{
     is_valid_circuit_id, is_valid_service_id, AdminServiceEvent, AuthorizationType,
     CircuitProposal, CircuitProposalVote, CreateCircuit, DurabilityType, PersistenceType,
-    ProposalType, RouteType, SplinterNode, SplinterService, Vote, VoteRecord,
+    ProposalType, RouteType, SplinterNode, SplinterService, Vote, VoteRecord, VotingPolicy,
 };
 
 pub use super::service::messages::builders::{
@@ -19,3 +19,6 @@ pub mod rest_api;
 pub mod service;
 #[cfg(feature = "admin-service-store")]
 pub mod store;
+#[cfg(not(feature = "admin-service-store"))]
+#[allow(dead_code, unused_imports)]
+pub(crate) mod store;
@@ -170,7 +170,7 @@ mod tests {
     use crate::admin::{
         messages::{
             AuthorizationType, CircuitProposal, CreateCircuit, DurabilityType, PersistenceType,
-            ProposalType, RouteType, SplinterNode,
+            ProposalType, RouteType, SplinterNode, VotingPolicy,
         },
         service::proposal_store::{ProposalFilter, ProposalIter, ProposalStoreError},
     };
@@ -544,6 +544,7 @@ mod tests {
                 circuit_management_type: "mgmt_type_1".into(),
                 application_metadata: vec![],
                 comments: "mock circuit 1".into(),
+                voting_policy: VotingPolicy::Unanimous,
             },
             votes: vec![],
             requester: vec![],
@@ -567,6 +568,7 @@ mod tests {
                 circuit_management_type: "mgmt_type_2".into(),
                 application_metadata: vec![],
                 comments: "mock circuit 2".into(),
+                voting_policy: VotingPolicy::Unanimous,
             },
             votes: vec![],
             requester: vec![],
@@ -593,6 +595,7 @@ mod tests {
                 circuit_management_type: "mgmt_type_2".into(),
                 application_metadata: vec![],
                 comments: "mock circuit 3".into(),
+                voting_policy: VotingPolicy::Unanimous,
             },
             votes: vec![],
             requester: vec![],
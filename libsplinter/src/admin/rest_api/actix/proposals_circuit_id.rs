@@ -86,7 +86,7 @@ mod tests {
     use crate::admin::{
         messages::{
             AuthorizationType, CircuitProposal, CreateCircuit, DurabilityType, PersistenceType,
-            ProposalType, RouteType,
+            ProposalType, RouteType, VotingPolicy,
         },
         service::proposal_store::{ProposalFilter, ProposalIter, ProposalStoreError},
     };
@@ -190,6 +190,7 @@ mod tests {
                 circuit_management_type: "mgmt_type".into(),
                 application_metadata: vec![],
                 comments: "mock circuit".into(),
+                voting_policy: VotingPolicy::Unanimous,
             },
             votes: vec![],
             requester: vec![],
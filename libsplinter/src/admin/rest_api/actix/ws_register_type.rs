@@ -18,6 +18,7 @@ use std::collections::HashMap;
 use std::time;
 
 use crate::admin::messages::AdminServiceEvent;
+use crate::admin::service::event_store::EventRecord;
 use crate::admin::service::{
     AdminCommands, AdminServiceEventSubscriber, AdminServiceStatus, AdminSubscriberError,
 };
@@ -61,22 +62,38 @@ pub fn make_application_handler_registration_route<A: AdminCommands + Clone + 's
                     Err(_) => return Box::new(HttpResponse::BadRequest().finish().into_future()),
                 };
 
-            let (skip, last_seen_timestamp) = query
-                .remove("last")
-                .map(|since_millis| {
-                    // Since this is the last seen event, we will skip it in our since
-                    // query
-                    debug!("Catching up on events since {}", since_millis);
-                    (
-                        1usize,
-                        time::SystemTime::UNIX_EPOCH + time::Duration::from_millis(since_millis),
-                    )
-                })
-                .unwrap_or((0, time::SystemTime::UNIX_EPOCH));
-
-            let initial_events = match admin_commands
-                .get_events_since(&last_seen_timestamp, &circuit_management_type)
+            // Events may be resumed either from the ID of the last event seen, or from the time
+            // of the last event seen.
+            let (skip, initial_events) = if let Some(last_event_id) = query.remove("last_event_id")
             {
+                debug!("Catching up on events since event {}", last_event_id);
+                (
+                    0usize,
+                    admin_commands
+                        .get_events_since_id(last_event_id as i64, &circuit_management_type),
+                )
+            } else {
+                let (skip, last_seen_timestamp) = query
+                    .remove("last")
+                    .map(|since_millis| {
+                        // Since this is the last seen event, we will skip it in our since
+                        // query
+                        debug!("Catching up on events since {}", since_millis);
+                        (
+                            1usize,
+                            time::SystemTime::UNIX_EPOCH
+                                + time::Duration::from_millis(since_millis),
+                        )
+                    })
+                    .unwrap_or((0, time::SystemTime::UNIX_EPOCH));
+
+                (
+                    skip,
+                    admin_commands.get_events_since(&last_seen_timestamp, &circuit_management_type),
+                )
+            };
+
+            let initial_events = match initial_events {
                 Ok(events) => events.map(JsonAdminEvent::from),
                 Err(err) => {
                     error!(
@@ -115,24 +132,23 @@ struct WsAdminServiceEventSubscriber {
 }
 
 impl AdminServiceEventSubscriber for WsAdminServiceEventSubscriber {
-    fn handle_event(
-        &self,
-        event: &AdminServiceEvent,
-        timestamp: &time::SystemTime,
-    ) -> Result<(), AdminSubscriberError> {
-        let json_event = JsonAdminEvent {
-            timestamp: *timestamp,
-            event: event.clone(),
-        };
-        self.sender.send(json_event).map_err(|_| {
-            debug!("Dropping admin service event and unsubscribing due to websocket being closed");
-            AdminSubscriberError::Unsubscribe
-        })
+    fn handle_event_record(&self, event_record: &EventRecord) -> Result<(), AdminSubscriberError> {
+        self.sender
+            .send(JsonAdminEvent::from(event_record.clone()))
+            .map_err(|_| {
+                debug!(
+                    "Dropping admin service event and unsubscribing due to websocket being closed"
+                );
+                AdminSubscriberError::Unsubscribe
+            })
     }
 }
 
 #[derive(Debug, Serialize, Clone)]
 struct JsonAdminEvent {
+    #[serde(rename = "eventId")]
+    event_id: i64,
+
     #[serde(serialize_with = "st_as_millis")]
     timestamp: time::SystemTime,
 
@@ -140,11 +156,12 @@ struct JsonAdminEvent {
     event: AdminServiceEvent,
 }
 
-impl From<(time::SystemTime, AdminServiceEvent)> for JsonAdminEvent {
-    fn from(raw_evt: (time::SystemTime, AdminServiceEvent)) -> Self {
+impl From<EventRecord> for JsonAdminEvent {
+    fn from(event_record: EventRecord) -> Self {
         Self {
-            timestamp: raw_evt.0,
-            event: raw_evt.1,
+            event_id: event_record.event_id(),
+            timestamp: *event_record.timestamp(),
+            event: event_record.event().clone(),
         }
     }
 }
@@ -44,6 +44,8 @@ impl<'a> From<&'a CircuitProposal> for ProposalResponse<'a> {
             ProposalType::AddNode => "AddNode",
             ProposalType::RemoveNode => "RemoveNode",
             ProposalType::Destroy => "Destroy",
+            ProposalType::Abandon => "Abandon",
+            ProposalType::UpdateApplicationMetadata => "UpdateApplicationMetadata",
         };
 
         Self {
@@ -64,19 +66,22 @@ pub(crate) struct VoteResponse<'a> {
     pub public_key: &'a [u8],
     pub vote: &'a str,
     pub voter_node_id: &'a str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub previous_votes: Vec<&'a str>,
 }
 
 impl<'a> From<&'a VoteRecord> for VoteResponse<'a> {
     fn from(record: &'a VoteRecord) -> Self {
-        let vote = match record.vote {
+        let vote_str = |vote: &Vote| match vote {
             Vote::Accept => "Accept",
             Vote::Reject => "Reject",
         };
 
         Self {
             public_key: &record.public_key,
-            vote,
+            vote: vote_str(&record.vote),
             voter_node_id: &record.voter_node_id,
+            previous_votes: record.previous_votes.iter().map(vote_str).collect(),
         }
     }
 }
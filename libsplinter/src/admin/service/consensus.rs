@@ -163,7 +163,7 @@ impl ProposalManager for AdminProposalManager {
             .map_err(|_| ServiceError::PoisonedLock("the admin state lock was poisoned".into()))?;
         if let Some(circuit_payload) = shared.pop_pending_circuit_payload() {
             let (expected_hash, circuit_proposal) = shared
-                .propose_change(circuit_payload.clone())
+                .propose_change(circuit_payload.clone(), None)
                 .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
 
             // Cheating a bit here by not setting the ID properly (isn't a hash of previous_id,
@@ -197,9 +197,13 @@ impl ProposalManager for AdminProposalManager {
 
             // Send the proposal to the other services
             let mut proposed_circuit = ProposedCircuit::new();
+            if circuit_payload.has_circuit_update_add_node() {
+                proposed_circuit.set_circuit(circuit_proposal.get_circuit_proposal().clone());
+            }
             proposed_circuit.set_circuit_payload(circuit_payload);
             proposed_circuit.set_expected_hash(expected_hash.as_bytes().into());
             proposed_circuit.set_required_verifiers(required_verifiers_bytes);
+            proposed_circuit.set_proposal_expires_at(circuit_proposal.get_expires_at());
             let mut msg = AdminMessage::new();
             msg.set_message_type(AdminMessage_Type::PROPOSED_CIRCUIT);
             msg.set_proposed_circuit(proposed_circuit);
@@ -234,8 +238,11 @@ impl ProposalManager for AdminProposalManager {
             .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?
             .clone();
 
+        // use the expiration time set by the coordinator, so that the expected hash does not
+        // depend on this node's clock
+        let proposal_expires_at = shared.proposal_expiration(id);
         let (hash, _) = shared
-            .propose_change(circuit_payload)
+            .propose_change(circuit_payload, proposal_expires_at)
             .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
 
         // check if hash is the expected hash stored in summary
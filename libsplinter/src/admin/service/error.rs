@@ -15,14 +15,17 @@
 use std::error::Error;
 use std::fmt;
 
+use crate::admin::store::error::{AdminServiceStoreError, BuilderError};
 use crate::circuit;
 use crate::consensus::error::ProposalManagerError;
-use crate::orchestrator::InitializeServiceError;
+use crate::orchestrator::{InitializeServiceError, ShutdownServiceError};
 use crate::service::error::{ServiceError, ServiceSendError};
 use crate::signing;
 
 use protobuf::error;
 
+use super::event_store::AdminServiceEventStoreError;
+
 #[derive(Debug)]
 pub enum AdminServiceError {
     ServiceError(ServiceError),
@@ -162,19 +165,24 @@ pub enum AdminSharedError {
         context: String,
         source: Option<InitializeServiceError>,
     },
+    ServiceShutdownFailed {
+        context: String,
+        source: Option<ShutdownServiceError>,
+    },
     ServiceSendError(ServiceSendError),
     UnknownAction(String),
     ValidationFailed(String),
 
-    /// An error occurred while trying to add an admin service event subscriber to the service.
-    UnableToAddSubscriber(String),
+    /// An error occurred while storing or reading admin service events.
+    EventStoreError(AdminServiceEventStoreError),
 
     /// An error occured while attempting to verify a payload's signature
     SignerError(signing::error::Error),
 
     // Returned if a circuit cannot be added to splinter state
     CommitError(String),
-    UpdateProposalsError(OpenProposalError),
+    /// An error occurred while reading or writing circuits or proposals in the admin store.
+    AdminServiceStoreError(AdminServiceStoreError),
     // An error occured while trying to negotiated protocol versions
     ServiceProtocolError(String),
 }
@@ -193,13 +201,20 @@ impl Error for AdminSharedError {
                     None
                 }
             }
+            AdminSharedError::ServiceShutdownFailed { source, .. } => {
+                if let Some(ref err) = source {
+                    Some(err)
+                } else {
+                    None
+                }
+            }
             AdminSharedError::ServiceSendError(err) => Some(err),
             AdminSharedError::UnknownAction(_) => None,
             AdminSharedError::ValidationFailed(_) => None,
             AdminSharedError::SignerError(_) => None,
             AdminSharedError::CommitError(_) => None,
-            AdminSharedError::UpdateProposalsError(err) => Some(err),
-            AdminSharedError::UnableToAddSubscriber(_) => None,
+            AdminSharedError::AdminServiceStoreError(err) => Some(err),
+            AdminSharedError::EventStoreError(err) => Some(err),
             AdminSharedError::ServiceProtocolError(_) => None,
         }
     }
@@ -223,6 +238,13 @@ impl fmt::Display for AdminSharedError {
                     f.write_str(&context)
                 }
             }
+            AdminSharedError::ServiceShutdownFailed { context, source } => {
+                if let Some(ref err) = source {
+                    write!(f, "{}: {}", context, err)
+                } else {
+                    f.write_str(&context)
+                }
+            }
             AdminSharedError::ServiceSendError(err) => {
                 write!(f, "failed to send service message: {}", err)
             }
@@ -232,11 +254,11 @@ impl fmt::Display for AdminSharedError {
             AdminSharedError::ValidationFailed(msg) => write!(f, "validation failed: {}", msg),
             AdminSharedError::SignerError(ref msg) => write!(f, "Signing error: {}", msg),
             AdminSharedError::CommitError(msg) => write!(f, "unable to commit circuit: {}", msg),
-            AdminSharedError::UpdateProposalsError(err) => {
-                write!(f, "received error while update open proposal: {}", err)
+            AdminSharedError::AdminServiceStoreError(err) => {
+                write!(f, "received error from admin service store: {}", err)
             }
-            AdminSharedError::UnableToAddSubscriber(msg) => {
-                write!(f, "unable to add admin service event subscriber: {}", msg)
+            AdminSharedError::EventStoreError(err) => {
+                write!(f, "received error from admin service event store: {}", err)
             }
             AdminSharedError::ServiceProtocolError(msg) => write!(
                 f,
@@ -253,6 +275,12 @@ impl From<ServiceSendError> for AdminSharedError {
     }
 }
 
+impl From<AdminServiceEventStoreError> for AdminSharedError {
+    fn from(err: AdminServiceEventStoreError) -> Self {
+        AdminSharedError::EventStoreError(err)
+    }
+}
+
 impl From<signing::error::Error> for AdminSharedError {
     fn from(err: signing::error::Error) -> Self {
         AdminSharedError::SignerError(err)
@@ -265,9 +293,18 @@ impl From<MarshallingError> for AdminSharedError {
     }
 }
 
-impl From<OpenProposalError> for AdminSharedError {
-    fn from(err: OpenProposalError) -> Self {
-        AdminSharedError::UpdateProposalsError(err)
+impl From<AdminServiceStoreError> for AdminSharedError {
+    fn from(err: AdminServiceStoreError) -> Self {
+        AdminSharedError::AdminServiceStoreError(err)
+    }
+}
+
+impl From<BuilderError> for AdminSharedError {
+    fn from(err: BuilderError) -> Self {
+        AdminSharedError::AdminServiceStoreError(AdminServiceStoreError::OperationError {
+            context: "unable to convert to the admin store representation".into(),
+            source: Some(Box::new(err)),
+        })
     }
 }
 
@@ -378,37 +415,3 @@ impl From<error::ProtobufError> for MarshallingError {
         MarshallingError::ProtobufError(err)
     }
 }
-
-#[derive(Debug)]
-pub enum OpenProposalError {
-    WriteError(String),
-    InvalidMessageFormat(MarshallingError),
-}
-
-impl std::error::Error for OpenProposalError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            OpenProposalError::WriteError(_) => None,
-            OpenProposalError::InvalidMessageFormat(err) => Some(err),
-        }
-    }
-}
-
-impl std::fmt::Display for OpenProposalError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            OpenProposalError::WriteError(msg) => {
-                write!(f, "Unable to write to persisted storage: {}", msg)
-            }
-            OpenProposalError::InvalidMessageFormat(err) => {
-                write!(f, "Unable to convert circuit proposal: {}", err)
-            }
-        }
-    }
-}
-
-impl From<MarshallingError> for OpenProposalError {
-    fn from(err: MarshallingError) -> Self {
-        OpenProposalError::InvalidMessageFormat(err)
-    }
-}
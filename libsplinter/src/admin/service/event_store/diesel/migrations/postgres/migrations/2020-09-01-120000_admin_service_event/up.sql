@@ -0,0 +1,27 @@
+---- Copyright 2018-2020 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE IF NOT EXISTS admin_service_event (
+    id                        BIGSERIAL PRIMARY KEY,
+    circuit_management_type   TEXT NOT NULL,
+    timestamp                 BIGINT NOT NULL,
+    data                      TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_service_event_management_type
+    ON admin_service_event (circuit_management_type);
+
+CREATE INDEX IF NOT EXISTS idx_admin_service_event_timestamp
+    ON admin_service_event (timestamp);
@@ -0,0 +1,258 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Database backend support for the `AdminServiceEventStore`, powered by
+//! [`Diesel`](https://crates.io/crates/diesel).
+//!
+//! This module contains the [`DieselAdminServiceEventStore`], which provides an implementation of
+//! the [`AdminServiceEventStore`] trait.
+//!
+//! [`DieselAdminServiceEventStore`]: struct.DieselAdminServiceEventStore.html
+//! [`AdminServiceEventStore`]: ../trait.AdminServiceEventStore.html
+
+pub mod migrations;
+mod models;
+mod operations;
+mod schema;
+
+use std::time::SystemTime;
+
+use diesel::r2d2::{ConnectionManager, Pool};
+
+use crate::admin::service::messages::AdminServiceEvent;
+
+use super::{AdminServiceEventStore, AdminServiceEventStoreError, EventIter, EventRecord};
+
+use operations::add_event::AdminServiceEventStoreAddEventOperation as _;
+use operations::list_events::AdminServiceEventStoreListEventsOperation as _;
+use operations::AdminServiceEventStoreOperations;
+
+/// A database-backed `AdminServiceEventStore`, powered by
+/// [`Diesel`](https://crates.io/crates/diesel).
+pub struct DieselAdminServiceEventStore<C: diesel::Connection + 'static> {
+    connection_pool: Pool<ConnectionManager<C>>,
+}
+
+impl<C: diesel::Connection> DieselAdminServiceEventStore<C> {
+    /// Creates a new `DieselAdminServiceEventStore`.
+    ///
+    /// # Arguments
+    ///
+    ///  * `connection_pool`: connection pool for the database
+    pub fn new(connection_pool: Pool<ConnectionManager<C>>) -> Self {
+        DieselAdminServiceEventStore { connection_pool }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl Clone for DieselAdminServiceEventStore<diesel::sqlite::SqliteConnection> {
+    fn clone(&self) -> Self {
+        Self {
+            connection_pool: self.connection_pool.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl Clone for DieselAdminServiceEventStore<diesel::pg::PgConnection> {
+    fn clone(&self) -> Self {
+        Self {
+            connection_pool: self.connection_pool.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl AdminServiceEventStore for DieselAdminServiceEventStore<diesel::pg::PgConnection> {
+    fn add_event(
+        &self,
+        event: AdminServiceEvent,
+    ) -> Result<EventRecord, AdminServiceEventStoreError> {
+        AdminServiceEventStoreOperations::new(&*self.connection_pool.get()?).add_event(event)
+    }
+
+    fn list_events_since(&self, start: i64) -> Result<EventIter, AdminServiceEventStoreError> {
+        AdminServiceEventStoreOperations::new(&*self.connection_pool.get()?)
+            .list_events(start, None, None)
+    }
+
+    fn list_events_by_management_type_since(
+        &self,
+        management_type: &str,
+        start: i64,
+    ) -> Result<EventIter, AdminServiceEventStoreError> {
+        AdminServiceEventStoreOperations::new(&*self.connection_pool.get()?).list_events(
+            start,
+            Some(management_type),
+            None,
+        )
+    }
+
+    fn list_events_by_management_type_since_time(
+        &self,
+        management_type: &str,
+        since: &SystemTime,
+    ) -> Result<EventIter, AdminServiceEventStoreError> {
+        AdminServiceEventStoreOperations::new(&*self.connection_pool.get()?).list_events(
+            0,
+            Some(management_type),
+            Some(since),
+        )
+    }
+
+    fn clone_boxed(&self) -> Box<dyn AdminServiceEventStore> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl AdminServiceEventStore for DieselAdminServiceEventStore<diesel::sqlite::SqliteConnection> {
+    fn add_event(
+        &self,
+        event: AdminServiceEvent,
+    ) -> Result<EventRecord, AdminServiceEventStoreError> {
+        AdminServiceEventStoreOperations::new(&*self.connection_pool.get()?).add_event(event)
+    }
+
+    fn list_events_since(&self, start: i64) -> Result<EventIter, AdminServiceEventStoreError> {
+        AdminServiceEventStoreOperations::new(&*self.connection_pool.get()?)
+            .list_events(start, None, None)
+    }
+
+    fn list_events_by_management_type_since(
+        &self,
+        management_type: &str,
+        start: i64,
+    ) -> Result<EventIter, AdminServiceEventStoreError> {
+        AdminServiceEventStoreOperations::new(&*self.connection_pool.get()?).list_events(
+            start,
+            Some(management_type),
+            None,
+        )
+    }
+
+    fn list_events_by_management_type_since_time(
+        &self,
+        management_type: &str,
+        since: &SystemTime,
+    ) -> Result<EventIter, AdminServiceEventStoreError> {
+        AdminServiceEventStoreOperations::new(&*self.connection_pool.get()?).list_events(
+            0,
+            Some(management_type),
+            Some(since),
+        )
+    }
+
+    fn clone_boxed(&self) -> Box<dyn AdminServiceEventStore> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+pub mod tests {
+    use super::*;
+
+    use crate::admin::service::event_store::diesel::migrations::run_sqlite_migrations;
+    use crate::admin::service::messages::{self, CircuitProposal, ProposalType};
+
+    use diesel::{
+        r2d2::{ConnectionManager, Pool},
+        sqlite::SqliteConnection,
+    };
+
+    /// Add events to a SQLite-backed store and verify that they are listed with increasing IDs,
+    /// filtered by the given ID and management type.
+    #[test]
+    fn test_add_and_list_events() {
+        let store = DieselAdminServiceEventStore::new(create_connection_pool_and_migrate());
+
+        let first = store
+            .add_event(make_event("circuit_one", "default"))
+            .expect("Unable to add event");
+        let second = store
+            .add_event(make_event("gameroom_one", "gameroom"))
+            .expect("Unable to add event");
+        let third = store
+            .add_event(make_event("gameroom_two", "gameroom"))
+            .expect("Unable to add event");
+
+        assert!(first.event_id() < second.event_id());
+        assert!(second.event_id() < third.event_id());
+
+        let all_events = store
+            .list_events_since(0)
+            .expect("Unable to list events")
+            .collect::<Vec<_>>();
+        assert_eq!(vec![first, second.clone(), third.clone()], all_events);
+
+        let gameroom_events = store
+            .list_events_by_management_type_since("gameroom", second.event_id())
+            .expect("Unable to list events")
+            .collect::<Vec<_>>();
+        assert_eq!(vec![third.clone()], gameroom_events);
+
+        let gameroom_events = store
+            .list_events_by_management_type_since_time("gameroom", &SystemTime::UNIX_EPOCH)
+            .expect("Unable to list events")
+            .collect::<Vec<_>>();
+        assert_eq!(vec![second, third], gameroom_events);
+
+        let future = SystemTime::now() + std::time::Duration::from_secs(60);
+        assert!(store
+            .list_events_by_management_type_since_time("gameroom", &future)
+            .expect("Unable to list events")
+            .next()
+            .is_none());
+    }
+
+    /// Creates a connection pool for an in-memory SQLite database with only a single connection
+    /// available. Each connection is backed by a different in-memory SQLite database, so limiting
+    /// the pool to a single connection ensures that the same DB is used for all operations.
+    fn create_connection_pool_and_migrate() -> Pool<ConnectionManager<SqliteConnection>> {
+        let connection_manager = ConnectionManager::<SqliteConnection>::new(":memory:");
+        let pool = Pool::builder()
+            .max_size(1)
+            .build(connection_manager)
+            .expect("Failed to build connection pool");
+
+        run_sqlite_migrations(&*pool.get().expect("Failed to get connection for migrations"))
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    fn make_event(circuit_id: &str, management_type: &str) -> AdminServiceEvent {
+        AdminServiceEvent::ProposalSubmitted(CircuitProposal {
+            proposal_type: ProposalType::Create,
+            circuit_id: circuit_id.into(),
+            circuit_hash: "not real hash for tests".into(),
+            circuit: messages::CreateCircuit {
+                circuit_id: circuit_id.into(),
+                roster: vec![],
+                members: vec![],
+                authorization_type: messages::AuthorizationType::Trust,
+                persistence: messages::PersistenceType::Any,
+                durability: messages::DurabilityType::NoDurability,
+                routes: messages::RouteType::Any,
+                circuit_management_type: management_type.into(),
+                application_metadata: vec![],
+                comments: "mock circuit".into(),
+                voting_policy: messages::VotingPolicy::Unanimous,
+            },
+            votes: vec![],
+            requester: vec![],
+            requester_node_id: "another-node".into(),
+        })
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Database representations used to implement a diesel backend for the
+//! `AdminServiceEventStore`.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::admin::service::event_store::{AdminServiceEventStoreError, EventRecord};
+use crate::admin::service::messages::AdminServiceEvent;
+
+use super::schema::admin_service_event;
+
+/// Database model representation of a new `EventRecord`, before an ID has been assigned to it
+#[derive(Debug, PartialEq, Insertable)]
+#[table_name = "admin_service_event"]
+pub struct NewAdminServiceEventModel {
+    pub circuit_management_type: String,
+    pub timestamp: i64,
+    pub data: String,
+}
+
+impl NewAdminServiceEventModel {
+    pub fn new(
+        event: &AdminServiceEvent,
+        timestamp: &SystemTime,
+    ) -> Result<Self, AdminServiceEventStoreError> {
+        let timestamp = timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| AdminServiceEventStoreError::StorageError {
+                context: "Event timestamp is before the unix epoch".into(),
+                source: Some(Box::new(err)),
+            })?
+            .as_millis() as i64;
+
+        let data = serde_json::to_string(event).map_err(|err| {
+            AdminServiceEventStoreError::StorageError {
+                context: "Unable to serialize admin service event".into(),
+                source: Some(Box::new(err)),
+            }
+        })?;
+
+        Ok(NewAdminServiceEventModel {
+            circuit_management_type: event.proposal().circuit.circuit_management_type.clone(),
+            timestamp,
+            data,
+        })
+    }
+}
+
+/// Database model representation of a stored `EventRecord`
+#[derive(Debug, PartialEq, Queryable)]
+pub struct AdminServiceEventModel {
+    pub id: i64,
+    pub circuit_management_type: String,
+    pub timestamp: i64,
+    pub data: String,
+}
+
+impl AdminServiceEventModel {
+    pub fn into_record(self) -> Result<EventRecord, AdminServiceEventStoreError> {
+        let event = serde_json::from_str(&self.data).map_err(|err| {
+            AdminServiceEventStoreError::StorageError {
+                context: format!("Unable to deserialize admin service event {}", self.id),
+                source: Some(Box::new(err)),
+            }
+        })?;
+
+        Ok(EventRecord::new(
+            self.id,
+            UNIX_EPOCH + Duration::from_millis(self.timestamp as u64),
+            event,
+        ))
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "add event" operation for the `DieselAdminServiceEventStore`.
+
+use std::time::SystemTime;
+
+use diesel::{dsl::insert_into, prelude::*};
+
+use crate::admin::service::event_store::{
+    diesel::{
+        models::{AdminServiceEventModel, NewAdminServiceEventModel},
+        schema::admin_service_event,
+    },
+    AdminServiceEventStoreError, EventRecord,
+};
+use crate::admin::service::messages::AdminServiceEvent;
+
+use super::AdminServiceEventStoreOperations;
+
+pub(in crate::admin::service::event_store::diesel) trait AdminServiceEventStoreAddEventOperation {
+    fn add_event(
+        &self,
+        event: AdminServiceEvent,
+    ) -> Result<EventRecord, AdminServiceEventStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AdminServiceEventStoreAddEventOperation
+    for AdminServiceEventStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_event(
+        &self,
+        event: AdminServiceEvent,
+    ) -> Result<EventRecord, AdminServiceEventStoreError> {
+        let new_event = NewAdminServiceEventModel::new(&event, &SystemTime::now())?;
+
+        let id: i64 = insert_into(admin_service_event::table)
+            .values(&new_event)
+            .returning(admin_service_event::id)
+            .get_result(self.conn)
+            .map_err(|err| AdminServiceEventStoreError::QueryError {
+                context: String::from("Failed to add admin service event"),
+                source: Box::new(err),
+            })?;
+
+        into_record(id, new_event, event)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AdminServiceEventStoreAddEventOperation
+    for AdminServiceEventStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_event(
+        &self,
+        event: AdminServiceEvent,
+    ) -> Result<EventRecord, AdminServiceEventStoreError> {
+        let new_event = NewAdminServiceEventModel::new(&event, &SystemTime::now())?;
+
+        // SQLite does not support `RETURNING`, so the assigned ID is read back in the same
+        // transaction as the insert.
+        let id = self
+            .conn
+            .transaction::<i64, diesel::result::Error, _>(|| {
+                insert_into(admin_service_event::table)
+                    .values(&new_event)
+                    .execute(self.conn)?;
+
+                admin_service_event::table
+                    .select(admin_service_event::id)
+                    .order(admin_service_event::id.desc())
+                    .first(self.conn)
+            })
+            .map_err(|err| AdminServiceEventStoreError::QueryError {
+                context: String::from("Failed to add admin service event"),
+                source: Box::new(err),
+            })?;
+
+        into_record(id, new_event, event)
+    }
+}
+
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn into_record(
+    id: i64,
+    new_event: NewAdminServiceEventModel,
+    event: AdminServiceEvent,
+) -> Result<EventRecord, AdminServiceEventStoreError> {
+    // The record's timestamp is rebuilt from the stored value, so that it matches the record
+    // returned when the events are listed.
+    let record = AdminServiceEventModel {
+        id,
+        circuit_management_type: new_event.circuit_management_type,
+        timestamp: new_event.timestamp,
+        data: new_event.data,
+    }
+    .into_record()?;
+
+    Ok(EventRecord::new(id, *record.timestamp(), event))
+}
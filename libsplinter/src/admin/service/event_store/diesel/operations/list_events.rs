@@ -0,0 +1,80 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "list events" operation for the `DieselAdminServiceEventStore`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use diesel::prelude::*;
+
+use crate::admin::service::event_store::{
+    diesel::{models::AdminServiceEventModel, schema::admin_service_event},
+    AdminServiceEventStoreError, EventIter,
+};
+
+use super::AdminServiceEventStoreOperations;
+
+pub(in crate::admin::service::event_store::diesel) trait AdminServiceEventStoreListEventsOperation {
+    fn list_events(
+        &self,
+        start: i64,
+        management_type: Option<&str>,
+        since: Option<&SystemTime>,
+    ) -> Result<EventIter, AdminServiceEventStoreError>;
+}
+
+impl<'a, C> AdminServiceEventStoreListEventsOperation for AdminServiceEventStoreOperations<'a, C>
+where
+    C: diesel::Connection,
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
+    i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+{
+    fn list_events(
+        &self,
+        start: i64,
+        management_type: Option<&str>,
+        since: Option<&SystemTime>,
+    ) -> Result<EventIter, AdminServiceEventStoreError> {
+        let mut query = admin_service_event::table
+            .into_boxed()
+            .filter(admin_service_event::id.gt(start));
+
+        if let Some(management_type) = management_type {
+            query = query.filter(admin_service_event::circuit_management_type.eq(management_type));
+        }
+
+        if let Some(since) = since {
+            // Timestamps are stored in milliseconds, so round down to include events stored within
+            // the same millisecond
+            let since = since
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis() as i64)
+                .unwrap_or(0);
+            query = query.filter(admin_service_event::timestamp.ge(since));
+        }
+
+        let records = query
+            .order(admin_service_event::id.asc())
+            .load::<AdminServiceEventModel>(self.conn)
+            .map_err(|err| AdminServiceEventStoreError::QueryError {
+                context: String::from("Failed to list admin service events"),
+                source: Box::new(err),
+            })?
+            .into_iter()
+            .map(AdminServiceEventModel::into_record)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(EventIter::new(Box::new(records.into_iter())))
+    }
+}
@@ -0,0 +1,75 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum AdminServiceEventStoreError {
+    /// Represents store query failures
+    QueryError {
+        context: String,
+        source: Box<dyn Error + Send>,
+    },
+    /// Represents general failures in the store
+    StorageError {
+        context: String,
+        source: Option<Box<dyn Error + Send>>,
+    },
+}
+
+impl Error for AdminServiceEventStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AdminServiceEventStoreError::QueryError { source, .. } => Some(&**source),
+            AdminServiceEventStoreError::StorageError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            AdminServiceEventStoreError::StorageError { source: None, .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for AdminServiceEventStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdminServiceEventStoreError::QueryError { context, source } => {
+                write!(f, "failed query: {}: {}", context, source)
+            }
+            AdminServiceEventStoreError::StorageError {
+                context,
+                source: Some(source),
+            } => write!(
+                f,
+                "the underlying storage returned an error: {}: {}",
+                context, source
+            ),
+            AdminServiceEventStoreError::StorageError {
+                context,
+                source: None,
+            } => write!(f, "the underlying storage returned an error: {}", context),
+        }
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl From<diesel::r2d2::PoolError> for AdminServiceEventStoreError {
+    fn from(err: diesel::r2d2::PoolError) -> Self {
+        AdminServiceEventStoreError::StorageError {
+            context: String::from("Unable to get a database connection"),
+            source: Some(Box::new(err)),
+        }
+    }
+}
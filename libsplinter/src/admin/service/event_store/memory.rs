@@ -0,0 +1,283 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A memory-backed implementation of the `AdminServiceEventStore`. Events are lost when the
+//! store is dropped.
+
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::admin::service::mailbox::Mailbox;
+use crate::admin::service::messages::AdminServiceEvent;
+use crate::storage::sets::mem::DurableBTreeSet;
+
+use super::{AdminServiceEventStore, AdminServiceEventStoreError, EventIter, EventRecord};
+
+#[derive(Clone)]
+pub struct MemoryAdminServiceEventStore {
+    mailbox: Arc<Mutex<Mailbox>>,
+}
+
+impl MemoryAdminServiceEventStore {
+    /// Creates a new, unbounded `MemoryAdminServiceEventStore`.
+    pub fn new() -> Self {
+        Self {
+            mailbox: Arc::new(Mutex::new(Mailbox::new(DurableBTreeSet::new_boxed()))),
+        }
+    }
+
+    /// Creates a new `MemoryAdminServiceEventStore` that only keeps the most recent events. Event
+    /// IDs continue to increase after the oldest events are dropped.
+    ///
+    /// # Arguments
+    ///
+    ///  * `limit` - The maximum number of events kept in the store
+    pub fn new_with_limit(limit: NonZeroUsize) -> Self {
+        Self {
+            mailbox: Arc::new(Mutex::new(Mailbox::new(
+                DurableBTreeSet::new_boxed_with_bound(limit),
+            ))),
+        }
+    }
+
+    fn list_events<F>(
+        &self,
+        since: SystemTime,
+        filter: F,
+    ) -> Result<EventIter, AdminServiceEventStoreError>
+    where
+        F: Fn(&EventRecord) -> bool + Send + 'static,
+    {
+        let events = self
+            .mailbox
+            .lock()
+            .map_err(|_| AdminServiceEventStoreError::StorageError {
+                context: "Memory event store lock was poisoned".into(),
+                source: None,
+            })?
+            .iter_since(since)
+            .map_err(|err| AdminServiceEventStoreError::StorageError {
+                context: "Unable to list events".into(),
+                source: Some(Box::new(err)),
+            })?;
+
+        Ok(EventIter::new(Box::new(events.filter(filter))))
+    }
+}
+
+impl Default for MemoryAdminServiceEventStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AdminServiceEventStore for MemoryAdminServiceEventStore {
+    fn add_event(
+        &self,
+        event: AdminServiceEvent,
+    ) -> Result<EventRecord, AdminServiceEventStoreError> {
+        self.mailbox
+            .lock()
+            .map_err(|_| AdminServiceEventStoreError::StorageError {
+                context: "Memory event store lock was poisoned".into(),
+                source: None,
+            })?
+            .add(event)
+            .map_err(|err| AdminServiceEventStoreError::StorageError {
+                context: "Unable to add event".into(),
+                source: Some(Box::new(err)),
+            })
+    }
+
+    fn list_events_since(&self, start: i64) -> Result<EventIter, AdminServiceEventStoreError> {
+        // IDs and timestamps are in the same order in the mailbox
+        self.list_events(SystemTime::UNIX_EPOCH, move |record| {
+            record.event_id() > start
+        })
+    }
+
+    fn list_events_by_management_type_since(
+        &self,
+        management_type: &str,
+        start: i64,
+    ) -> Result<EventIter, AdminServiceEventStoreError> {
+        let management_type = management_type.to_string();
+        self.list_events(SystemTime::UNIX_EPOCH, move |record| {
+            record.event_id() > start && record.circuit_management_type() == management_type
+        })
+    }
+
+    fn list_events_by_management_type_since_time(
+        &self,
+        management_type: &str,
+        since: &SystemTime,
+    ) -> Result<EventIter, AdminServiceEventStoreError> {
+        let management_type = management_type.to_string();
+        self.list_events(*since, move |record| {
+            record.circuit_management_type() == management_type
+        })
+    }
+
+    fn clone_boxed(&self) -> Box<dyn AdminServiceEventStore> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::admin::service::messages::{self, CircuitProposal, ProposalType};
+
+    /// Add events to the store and verify that they are listed in the order they were added, with
+    /// increasing IDs.
+    #[test]
+    fn test_list_events() {
+        let store = MemoryAdminServiceEventStore::new();
+
+        assert!(store
+            .list_events_since(0)
+            .expect("Unable to list events")
+            .next()
+            .is_none());
+
+        store
+            .add_event(make_event("circuit_one", "default"))
+            .expect("Unable to add event");
+        store
+            .add_event(make_event("gameroom_one", "gameroom"))
+            .expect("Unable to add event");
+        store
+            .add_event(make_event("circuit_two", "default"))
+            .expect("Unable to add event");
+
+        let events = store
+            .list_events_since(0)
+            .expect("Unable to list events")
+            .map(|record| (record.event_id(), record.event().clone()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                (1, make_event("circuit_one", "default")),
+                (2, make_event("gameroom_one", "gameroom")),
+                (3, make_event("circuit_two", "default")),
+            ],
+            events
+        );
+    }
+
+    /// Add events to the store and verify that only the events after the given ID, and of the
+    /// given management type, are listed.
+    #[test]
+    fn test_list_events_by_management_type_since() {
+        let store = MemoryAdminServiceEventStore::new();
+
+        store
+            .add_event(make_event("circuit_one", "default"))
+            .expect("Unable to add event");
+        let record = store
+            .add_event(make_event("gameroom_one", "gameroom"))
+            .expect("Unable to add event");
+        store
+            .add_event(make_event("circuit_two", "default"))
+            .expect("Unable to add event");
+        store
+            .add_event(make_event("gameroom_two", "gameroom"))
+            .expect("Unable to add event");
+
+        let events = store
+            .list_events_by_management_type_since("gameroom", record.event_id())
+            .expect("Unable to list events")
+            .map(|record| record.event().clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![make_event("gameroom_two", "gameroom")], events);
+    }
+
+    /// Add events to the store and verify that only the events stored at or after the given time,
+    /// and of the given management type, are listed.
+    #[test]
+    fn test_list_events_by_management_type_since_time() {
+        let store = MemoryAdminServiceEventStore::new();
+
+        store
+            .add_event(make_event("gameroom_one", "gameroom"))
+            .expect("Unable to add event");
+        let record = store
+            .add_event(make_event("circuit_one", "default"))
+            .expect("Unable to add event");
+        store
+            .add_event(make_event("gameroom_two", "gameroom"))
+            .expect("Unable to add event");
+
+        let events = store
+            .list_events_by_management_type_since_time("gameroom", record.timestamp())
+            .expect("Unable to list events")
+            .map(|record| record.event().clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![make_event("gameroom_two", "gameroom")], events);
+    }
+
+    /// Add more events than the store's limit and verify that the oldest events are dropped while
+    /// the IDs keep increasing.
+    #[test]
+    fn test_limit() {
+        let store = MemoryAdminServiceEventStore::new_with_limit(NonZeroUsize::new(2).unwrap());
+
+        store
+            .add_event(make_event("circuit_one", "default"))
+            .expect("Unable to add event");
+        store
+            .add_event(make_event("circuit_two", "default"))
+            .expect("Unable to add event");
+        store
+            .add_event(make_event("circuit_three", "default"))
+            .expect("Unable to add event");
+
+        let event_ids = store
+            .list_events_since(0)
+            .expect("Unable to list events")
+            .map(|record| record.event_id())
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec![2, 3], event_ids);
+    }
+
+    fn make_event(circuit_id: &str, management_type: &str) -> AdminServiceEvent {
+        AdminServiceEvent::ProposalSubmitted(CircuitProposal {
+            proposal_type: ProposalType::Create,
+            circuit_id: circuit_id.into(),
+            circuit_hash: "not real hash for tests".into(),
+            circuit: messages::CreateCircuit {
+                circuit_id: circuit_id.into(),
+                roster: vec![],
+                members: vec![],
+                authorization_type: messages::AuthorizationType::Trust,
+                persistence: messages::PersistenceType::Any,
+                durability: messages::DurabilityType::NoDurability,
+                routes: messages::RouteType::Any,
+                circuit_management_type: management_type.into(),
+                application_metadata: vec![],
+                comments: "mock circuit".into(),
+                voting_policy: messages::VotingPolicy::Unanimous,
+            },
+            votes: vec![],
+            requester: vec![],
+            requester_node_id: "another-node".into(),
+        })
+    }
+}
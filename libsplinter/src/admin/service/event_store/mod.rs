@@ -0,0 +1,142 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage for the events emitted by the admin service.
+//!
+//! Each stored event is assigned an ID that is greater than the ID of every event stored before
+//! it, which allows subscribers to resume from the last event they have seen.
+
+#[cfg(all(feature = "admin-service-event-store", feature = "diesel"))]
+pub mod diesel;
+mod error;
+mod memory;
+
+use std::time::SystemTime;
+
+use super::messages::AdminServiceEvent;
+
+pub use self::error::AdminServiceEventStoreError;
+pub use self::memory::MemoryAdminServiceEventStore;
+
+/// An admin service event, along with the ID and time assigned to it when it was stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventRecord {
+    event_id: i64,
+    timestamp: SystemTime,
+    event: AdminServiceEvent,
+}
+
+impl EventRecord {
+    pub fn new(event_id: i64, timestamp: SystemTime, event: AdminServiceEvent) -> Self {
+        Self {
+            event_id,
+            timestamp,
+            event,
+        }
+    }
+
+    /// Returns the ID assigned to the event by the store
+    pub fn event_id(&self) -> i64 {
+        self.event_id
+    }
+
+    /// Returns the time the event was stored
+    pub fn timestamp(&self) -> &SystemTime {
+        &self.timestamp
+    }
+
+    /// Returns the event
+    pub fn event(&self) -> &AdminServiceEvent {
+        &self.event
+    }
+
+    /// Returns the circuit management type of the event's proposal
+    pub fn circuit_management_type(&self) -> &str {
+        &self.event.proposal().circuit.circuit_management_type
+    }
+}
+
+/// An iterator over stored events, in the order of their IDs.
+pub struct EventIter {
+    inner: Box<dyn Iterator<Item = EventRecord> + Send>,
+}
+
+impl EventIter {
+    pub fn new(inner: Box<dyn Iterator<Item = EventRecord> + Send>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Iterator for EventIter {
+    type Item = EventRecord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+pub trait AdminServiceEventStore: Send + Sync {
+    /// Adds an event to the store
+    ///
+    /// # Arguments
+    ///
+    ///  * `event` - The event to be added
+    ///
+    /// Returns the stored event, with the ID and timestamp that were assigned to it
+    fn add_event(
+        &self,
+        event: AdminServiceEvent,
+    ) -> Result<EventRecord, AdminServiceEventStoreError>;
+
+    /// Lists the events that were stored after the event with the given ID
+    ///
+    /// # Arguments
+    ///
+    ///  * `start` - The ID of the last event that should not be returned; `0` lists all events
+    fn list_events_since(&self, start: i64) -> Result<EventIter, AdminServiceEventStoreError>;
+
+    /// Lists the events for the given circuit management type that were stored after the event
+    /// with the given ID
+    ///
+    /// # Arguments
+    ///
+    ///  * `management_type` - The circuit management type of the events
+    ///  * `start` - The ID of the last event that should not be returned; `0` lists all events
+    fn list_events_by_management_type_since(
+        &self,
+        management_type: &str,
+        start: i64,
+    ) -> Result<EventIter, AdminServiceEventStoreError>;
+
+    /// Lists the events for the given circuit management type that were stored at or after the
+    /// given time
+    ///
+    /// # Arguments
+    ///
+    ///  * `management_type` - The circuit management type of the events
+    ///  * `since` - The earliest time of the events that should be returned
+    fn list_events_by_management_type_since_time(
+        &self,
+        management_type: &str,
+        since: &SystemTime,
+    ) -> Result<EventIter, AdminServiceEventStoreError>;
+
+    fn clone_boxed(&self) -> Box<dyn AdminServiceEventStore>;
+}
+
+impl Clone for Box<dyn AdminServiceEventStore> {
+    fn clone(&self) -> Self {
+        self.clone_boxed()
+    }
+}
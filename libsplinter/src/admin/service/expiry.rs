@@ -0,0 +1,73 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{Builder, JoinHandle};
+use std::time::Duration;
+
+use super::shared::AdminServiceShared;
+
+/// Component used by the service to periodically remove the proposals whose time-to-live has
+/// elapsed
+pub struct ProposalExpiryChecker {
+    shutdown_tx: Sender<()>,
+    thread_handle: JoinHandle<()>,
+}
+
+impl ProposalExpiryChecker {
+    /// Start checking for expired proposals, at the given interval, in a separate thread.
+    pub fn new(
+        service_id: &str,
+        shared: Arc<Mutex<AdminServiceShared>>,
+        interval: Duration,
+    ) -> Result<Self, io::Error> {
+        let (shutdown_tx, shutdown_rx) = channel();
+
+        let thread_handle = Builder::new()
+            .name(format!("proposal-expiry-{}", service_id))
+            .spawn(move || {
+                // stop once a shutdown is requested or the checker has been dropped
+                while let Err(RecvTimeoutError::Timeout) = shutdown_rx.recv_timeout(interval) {
+                    match shared.lock() {
+                        Ok(mut shared) => {
+                            if let Err(err) = shared.expire_proposals() {
+                                error!("Unable to expire circuit proposals: {}", err);
+                            }
+                        }
+                        Err(_) => {
+                            error!("the admin shared lock was poisoned");
+                            break;
+                        }
+                    }
+                }
+            })?;
+
+        Ok(ProposalExpiryChecker {
+            shutdown_tx,
+            thread_handle,
+        })
+    }
+
+    /// Consumes self and shuts down the expiry thread.
+    pub fn shutdown(self) {
+        // the thread may have already exited, in which case there is no one to notify
+        let _ = self.shutdown_tx.send(());
+
+        self.thread_handle
+            .join()
+            .unwrap_or_else(|err| error!("proposal expiry thread failed: {:?}", err));
+    }
+}
@@ -17,15 +17,17 @@ use std::collections::VecDeque;
 use std::error::Error;
 use std::fmt;
 use std::ops::Bound;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 
 use crate::storage::sets::DurableOrderedSet;
 
+use super::event_store::EventRecord;
 use super::messages::AdminServiceEvent;
 
-/// A simple entry for AdminServiceEvent values, marked with a timestamp
+/// A simple entry for AdminServiceEvent values, marked with an event ID and a timestamp
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct EventEntry {
+    event_id: i64,
     timestamp: SystemTime,
     event: AdminServiceEvent,
 }
@@ -51,6 +53,10 @@ impl std::borrow::Borrow<SystemTime> for EventEntry {
 /// A Mailbox stores all admin services events that have occurred, ordered by a timestamp generated
 /// upon addition to the mailbox.
 ///
+/// Each event is also assigned an ID, one greater than the ID of the last event in the mailbox.
+/// Timestamps are kept strictly increasing, so that the events are in the same order by either ID
+/// or timestamp.
+///
 /// These events are stored in a durable ordered set, determined by the caller.
 #[derive(Clone)]
 pub struct Mailbox {
@@ -63,29 +69,53 @@ impl Mailbox {
         Self { durable_set }
     }
 
-    /// Add an event to the mailbox.  Returns the recorded event time and a copy of the event.
+    /// Add an event to the mailbox.  Returns the recorded event, with its ID and time.
     ///
     /// # Errors
     ///
     /// Returns a MailboxError if there is an issue with the underlying storage set.
-    pub fn add(
-        &mut self,
-        event: AdminServiceEvent,
-    ) -> Result<(SystemTime, AdminServiceEvent), MailboxError> {
+    pub fn add(&mut self, event: AdminServiceEvent) -> Result<EventRecord, MailboxError> {
+        let now = SystemTime::now();
+        let (event_id, timestamp) = match self.last_entry()? {
+            Some(last) if now <= last.timestamp => {
+                (last.event_id + 1, last.timestamp + Duration::from_nanos(1))
+            }
+            Some(last) => (last.event_id + 1, now),
+            None => (1, now),
+        };
+
         let entry = EventEntry {
-            timestamp: SystemTime::now(),
+            event_id,
+            timestamp,
             event,
         };
         self.durable_set.add(entry.clone()).map_err(|err| {
             MailboxError::with_source("Unable to add event to storage", Box::new(err))
         })?;
 
-        Ok((entry.timestamp, entry.event))
+        Ok(EventRecord::new(
+            entry.event_id,
+            entry.timestamp,
+            entry.event,
+        ))
     }
 
     /// Returns an iterator starting from the given timestamp.
+    ///
+    /// Events added after the iterator is created are not included.
     pub fn iter_since(&self, start_time: SystemTime) -> Result<MailboxIter, MailboxError> {
-        MailboxIter::new(self.durable_set.clone(), start_time, SystemTime::now())
+        match self.last_entry()? {
+            Some(last) if start_time <= last.timestamp => {
+                MailboxIter::new(self.durable_set.clone(), start_time, last.timestamp)
+            }
+            _ => Ok(MailboxIter::empty(self.durable_set.clone())),
+        }
+    }
+
+    fn last_entry(&self) -> Result<Option<EventEntry>, MailboxError> {
+        self.durable_set.last().map_err(|err| {
+            MailboxError::with_source("Unable to read last event from storage", Box::new(err))
+        })
     }
 }
 
@@ -105,7 +135,7 @@ impl MailboxIter {
         end_search: SystemTime,
     ) -> Result<Self, MailboxError> {
         let initial_cache = source
-            .range_iter((&start_search..&end_search).into())
+            .range_iter((&start_search..=&end_search).into())
             .map_err(|err| {
                 MailboxError::with_source(
                     "Unable to iterate over underlying storage",
@@ -129,13 +159,22 @@ impl MailboxIter {
         })
     }
 
+    fn empty(source: Box<dyn DurableOrderedSet<EventEntry, SystemTime>>) -> Self {
+        Self {
+            source,
+            start_search: SystemTime::UNIX_EPOCH,
+            end_search: SystemTime::UNIX_EPOCH,
+            cache: VecDeque::new(),
+        }
+    }
+
     fn reload_cache(&mut self) -> Result<(), MailboxError> {
         self.cache = self
             .source
             .range_iter(
                 (
                     Bound::Excluded(&self.start_search),
-                    Bound::Excluded(&self.end_search),
+                    Bound::Included(&self.end_search),
                 )
                     .into(),
             )
@@ -159,7 +198,7 @@ impl MailboxIter {
 }
 
 impl Iterator for MailboxIter {
-    type Item = (SystemTime, AdminServiceEvent);
+    type Item = EventRecord;
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.cache.is_empty() && self.start_search < self.end_search {
@@ -170,7 +209,7 @@ impl Iterator for MailboxIter {
 
         self.cache
             .pop_front()
-            .map(|event| (event.timestamp, event.event))
+            .map(|entry| EventRecord::new(entry.event_id, entry.timestamp, entry.event))
     }
 }
 
@@ -241,7 +280,7 @@ mod tests {
             mailbox
                 .iter_since(SystemTime::UNIX_EPOCH)
                 .expect("Unable to create an iterator")
-                .map(|(_, evt)| evt)
+                .map(|record| record.event().clone())
                 .collect::<Vec<_>>(),
         );
     }
@@ -253,7 +292,7 @@ mod tests {
         assert!(&mailbox
             .iter_since(SystemTime::UNIX_EPOCH)
             .expect("Unable to create an iterator")
-            .collect::<Vec<(SystemTime, AdminServiceEvent)>>()
+            .collect::<Vec<EventRecord>>()
             .is_empty());
     }
 
@@ -283,7 +322,8 @@ mod tests {
                 make_event("circuit_one", "default"),
                 make_event("gameroom_one", "gameroom"),
             ],
-            iter.map(|(_, evt)| evt).collect::<Vec<_>>(),
+            iter.map(|record| record.event().clone())
+                .collect::<Vec<_>>(),
         );
     }
 
@@ -296,7 +336,7 @@ mod tests {
         mailbox
             .add(make_event("circuit_one", "default"))
             .expect("Unable to add event");
-        let (entry_time, _) = mailbox
+        let record = mailbox
             .add(make_event("gameroom_one", "gameroom"))
             .expect("Unable to add event");
         mailbox
@@ -309,9 +349,38 @@ mod tests {
                 make_event("circuit_two", "default"),
             ],
             mailbox
-                .iter_since(entry_time)
+                .iter_since(*record.timestamp())
+                .expect("Unable to create an iterator")
+                .map(|record| record.event().clone())
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    /// Add several events and ensure that each is assigned an ID one greater than the last, and a
+    /// strictly greater timestamp, even if the events are added within the same instant.
+    #[test]
+    fn test_add_increasing() {
+        let mut mailbox = Mailbox::new(DurableBTreeSet::new_boxed());
+
+        let records = (0..10)
+            .map(|i| {
+                mailbox
+                    .add(make_event(&format!("circuit_{}", i), "default"))
+                    .expect("Unable to add event")
+            })
+            .collect::<Vec<_>>();
+
+        for (i, pair) in records.windows(2).enumerate() {
+            assert_eq!(i as i64 + 1, pair[0].event_id());
+            assert_eq!(pair[0].event_id() + 1, pair[1].event_id());
+            assert!(pair[0].timestamp() < pair[1].timestamp());
+        }
+
+        assert_eq!(
+            records,
+            mailbox
+                .iter_since(SystemTime::UNIX_EPOCH)
                 .expect("Unable to create an iterator")
-                .map(|(_, evt)| evt)
                 .collect::<Vec<_>>(),
         );
     }
@@ -332,6 +401,7 @@ mod tests {
                 circuit_management_type: event_type.into(),
                 application_metadata: vec![],
                 comments: "mock circuit".into(),
+                voting_policy: messages::VotingPolicy::Unanimous,
             },
             votes: vec![],
             requester: vec![],
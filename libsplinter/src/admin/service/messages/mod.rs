@@ -39,6 +39,8 @@ pub struct CreateCircuit {
     pub application_metadata: Vec<u8>,
     #[serde(default)]
     pub comments: String,
+    #[serde(default)]
+    pub voting_policy: VotingPolicy,
 }
 
 impl CreateCircuit {
@@ -63,6 +65,7 @@ impl CreateCircuit {
 
         let durability = match proto.get_durability() {
             admin::Circuit_DurabilityType::NO_DURABILITY => DurabilityType::NoDurability,
+            admin::Circuit_DurabilityType::DURABLE => DurabilityType::Durable,
             admin::Circuit_DurabilityType::UNSET_DURABILITY_TYPE => {
                 return Err(MarshallingError::UnsetField(
                     "Unset durability type".to_string(),
@@ -72,11 +75,22 @@ impl CreateCircuit {
 
         let routes = match proto.get_routes() {
             admin::Circuit_RouteType::ANY_ROUTE => RouteType::Any,
+            admin::Circuit_RouteType::RELAY_ROUTE => RouteType::Relay,
             admin::Circuit_RouteType::UNSET_ROUTE_TYPE => {
                 return Err(MarshallingError::UnsetField("Unset route type".to_string()));
             }
         };
 
+        // circuits proposed without a voting policy keep requiring every member to accept
+        let voting_policy = match proto.get_voting_policy() {
+            admin::Circuit_VotingPolicy::UNANIMOUS
+            | admin::Circuit_VotingPolicy::UNSET_VOTING_POLICY => VotingPolicy::Unanimous,
+            admin::Circuit_VotingPolicy::MAJORITY => VotingPolicy::Majority,
+            admin::Circuit_VotingPolicy::THRESHOLD => {
+                VotingPolicy::Threshold(proto.get_required_votes())
+            }
+        };
+
         Ok(Self {
             circuit_id: proto.take_circuit_id(),
             roster: proto
@@ -96,6 +110,7 @@ impl CreateCircuit {
             circuit_management_type: proto.take_circuit_management_type(),
             application_metadata: proto.take_application_metadata(),
             comments: proto.take_comments(),
+            voting_policy,
         })
     }
 
@@ -136,10 +151,27 @@ impl CreateCircuit {
             DurabilityType::NoDurability => {
                 circuit.set_durability(admin::Circuit_DurabilityType::NO_DURABILITY);
             }
+            DurabilityType::Durable => {
+                circuit.set_durability(admin::Circuit_DurabilityType::DURABLE);
+            }
         };
 
         match self.routes {
             RouteType::Any => circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE),
+            RouteType::Relay => circuit.set_routes(admin::Circuit_RouteType::RELAY_ROUTE),
+        };
+
+        match self.voting_policy {
+            VotingPolicy::Unanimous => {
+                circuit.set_voting_policy(admin::Circuit_VotingPolicy::UNANIMOUS)
+            }
+            VotingPolicy::Majority => {
+                circuit.set_voting_policy(admin::Circuit_VotingPolicy::MAJORITY)
+            }
+            VotingPolicy::Threshold(required_votes) => {
+                circuit.set_voting_policy(admin::Circuit_VotingPolicy::THRESHOLD);
+                circuit.set_required_votes(required_votes);
+            }
         };
 
         let mut create_request = CircuitCreateRequest::new();
@@ -181,11 +213,13 @@ impl Default for PersistenceType {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum DurabilityType {
     NoDurability,
+    Durable,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum RouteType {
     Any,
+    Relay,
 }
 
 impl Default for RouteType {
@@ -194,6 +228,24 @@ impl Default for RouteType {
     }
 }
 
+/// The policy used to decide whether a proposal for a circuit has been accepted by its voting
+/// members
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum VotingPolicy {
+    /// Every voting member must accept the proposal
+    Unanimous,
+    /// More than half of the voting members must accept the proposal
+    Majority,
+    /// At least the given number of voting members must accept the proposal
+    Threshold(u32),
+}
+
+impl Default for VotingPolicy {
+    fn default() -> Self {
+        VotingPolicy::Unanimous
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct SplinterNode {
     pub node_id: String,
@@ -294,6 +346,10 @@ impl CircuitProposal {
             admin::CircuitProposal_ProposalType::ADD_NODE => ProposalType::AddNode,
             admin::CircuitProposal_ProposalType::REMOVE_NODE => ProposalType::RemoveNode,
             admin::CircuitProposal_ProposalType::DESTROY => ProposalType::Destroy,
+            admin::CircuitProposal_ProposalType::ABANDON => ProposalType::Abandon,
+            admin::CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA => {
+                ProposalType::UpdateApplicationMetadata
+            }
             admin::CircuitProposal_ProposalType::UNSET_PROPOSAL_TYPE => {
                 return Err(MarshallingError::UnsetField(
                     "Unset proposal type".to_string(),
@@ -325,6 +381,10 @@ impl CircuitProposal {
             ProposalType::AddNode => admin::CircuitProposal_ProposalType::ADD_NODE,
             ProposalType::RemoveNode => admin::CircuitProposal_ProposalType::REMOVE_NODE,
             ProposalType::Destroy => admin::CircuitProposal_ProposalType::DESTROY,
+            ProposalType::Abandon => admin::CircuitProposal_ProposalType::ABANDON,
+            ProposalType::UpdateApplicationMetadata => {
+                admin::CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA
+            }
         };
 
         let votes = self
@@ -355,6 +415,8 @@ pub enum ProposalType {
     AddNode,
     RemoveNode,
     Destroy,
+    Abandon,
+    UpdateApplicationMetadata,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -400,6 +462,8 @@ pub struct VoteRecord {
     pub public_key: Vec<u8>,
     pub vote: Vote,
     pub voter_node_id: String,
+    #[serde(default)]
+    pub previous_votes: Vec<Vote>,
 }
 
 impl VoteRecord {
@@ -412,23 +476,37 @@ impl VoteRecord {
             }
         };
 
+        let previous_votes = proto
+            .get_previous_votes()
+            .iter()
+            .map(|vote| match vote {
+                admin::CircuitProposalVote_Vote::ACCEPT => Ok(Vote::Accept),
+                admin::CircuitProposalVote_Vote::REJECT => Ok(Vote::Reject),
+                admin::CircuitProposalVote_Vote::UNSET_VOTE => Err(MarshallingError::UnsetField(
+                    "Unset previous vote".to_string(),
+                )),
+            })
+            .collect::<Result<Vec<Vote>, MarshallingError>>()?;
+
         Ok(Self {
             public_key: proto.take_public_key(),
             vote,
             voter_node_id: proto.take_voter_node_id(),
+            previous_votes,
         })
     }
 
     fn into_proto(self) -> admin::CircuitProposal_VoteRecord {
-        let vote = match self.vote {
+        let vote_to_proto = |vote: &Vote| match vote {
             Vote::Accept => admin::CircuitProposalVote_Vote::ACCEPT,
             Vote::Reject => admin::CircuitProposalVote_Vote::REJECT,
         };
 
         let mut vote_record = admin::CircuitProposal_VoteRecord::new();
-        vote_record.set_vote(vote);
+        vote_record.set_vote(vote_to_proto(&self.vote));
         vote_record.set_public_key(self.public_key);
         vote_record.set_voter_node_id(self.voter_node_id);
+        vote_record.set_previous_votes(self.previous_votes.iter().map(vote_to_proto).collect());
 
         vote_record
     }
@@ -447,7 +525,13 @@ pub enum AdminServiceEvent {
     ProposalVote((CircuitProposal, Vec<u8>)),
     ProposalAccepted((CircuitProposal, Vec<u8>)),
     ProposalRejected((CircuitProposal, Vec<u8>)),
+    ProposalExpired(CircuitProposal),
+    ProposalCancelled((CircuitProposal, Vec<u8>)),
     CircuitReady(CircuitProposal),
+    CircuitDestroyed(CircuitProposal),
+    CircuitAbandoned(CircuitProposal),
+    ApplicationMetadataUpdated((CircuitProposal, ApplicationMetadataUpdate)),
+    DurableQueueFull((CircuitProposal, DurableQueueOverflow)),
 }
 
 impl AdminServiceEvent {
@@ -457,7 +541,34 @@ impl AdminServiceEvent {
             AdminServiceEvent::ProposalVote((proposal, _)) => proposal,
             AdminServiceEvent::ProposalAccepted((proposal, _)) => proposal,
             AdminServiceEvent::ProposalRejected((proposal, _)) => proposal,
+            AdminServiceEvent::ProposalExpired(proposal) => proposal,
+            AdminServiceEvent::ProposalCancelled((proposal, _)) => proposal,
             AdminServiceEvent::CircuitReady(proposal) => proposal,
+            AdminServiceEvent::CircuitDestroyed(proposal) => proposal,
+            AdminServiceEvent::CircuitAbandoned(proposal) => proposal,
+            AdminServiceEvent::ApplicationMetadataUpdated((proposal, _)) => proposal,
+            AdminServiceEvent::DurableQueueFull((proposal, _)) => proposal,
         }
     }
 }
+
+/// The member whose messages on a durable circuit are being dropped because the messages stored
+/// for the circuit have reached their size limit.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct DurableQueueOverflow {
+    pub node_id: String,
+    pub size_limit: usize,
+}
+
+/// The application metadata and comments of a circuit before and after an accepted update.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct ApplicationMetadataUpdate {
+    #[serde(serialize_with = "as_hex")]
+    #[serde(deserialize_with = "deserialize_hex")]
+    pub previous_application_metadata: Vec<u8>,
+    pub previous_comments: String,
+    #[serde(serialize_with = "as_hex")]
+    #[serde(deserialize_with = "deserialize_hex")]
+    pub application_metadata: Vec<u8>,
+    pub comments: String,
+}
@@ -14,15 +14,17 @@
 
 mod consensus;
 pub(crate) mod error;
+pub mod event_store;
+mod expiry;
 mod mailbox;
 pub(crate) mod messages;
-pub(super) mod open_proposals;
 pub(super) mod proposal_store;
 mod shared;
 
 use std::any::Any;
 #[cfg(feature = "service-arg-validation")]
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::sync::{mpsc::channel, Arc, Mutex};
 use std::thread;
 use std::time::{Duration, SystemTime};
@@ -30,7 +32,13 @@ use std::time::{Duration, SystemTime};
 use openssl::hash::{hash, MessageDigest};
 use protobuf::{self, Message};
 
-use crate::circuit::SplinterState;
+use crate::admin::store::AdminServiceStore;
+#[cfg(not(feature = "admin-service-store"))]
+use crate::admin::store::{yaml::YamlAdminServiceStore, MemoryAdminServiceStore};
+use crate::circuit::durable::DurableMessageQueue;
+#[cfg(feature = "routing-table")]
+use crate::circuit::routing::RoutingTableWriter;
+use crate::circuit::{service::SplinterNode, Circuit as StateCircuit, SplinterState};
 use crate::consensus::Proposal;
 use crate::hex::to_hex;
 use crate::keys::KeyPermissionManager;
@@ -52,6 +60,8 @@ use crate::signing::SignatureVerifier;
 
 use self::consensus::AdminConsensusManager;
 use self::error::{AdminError, Sha256Error};
+use self::event_store::{AdminServiceEventStore, EventRecord};
+use self::expiry::ProposalExpiryChecker;
 use self::proposal_store::{AdminServiceProposals, ProposalStore};
 use self::shared::AdminServiceShared;
 
@@ -61,13 +71,31 @@ pub use self::error::AdminSubscriberError;
 pub use self::shared::AdminServiceStatus;
 
 const DEFAULT_COORDINATOR_TIMEOUT: u64 = 30; // 30 seconds
+const PROPOSAL_EXPIRY_CHECK_INTERVAL: u64 = 5; // 5 seconds
 
 pub trait AdminServiceEventSubscriber: Send {
+    #[deprecated(
+        since = "0.5.1",
+        note = "please implement `handle_event_record` instead"
+    )]
     fn handle_event(
         &self,
-        admin_service_event: &messages::AdminServiceEvent,
-        timestamp: &SystemTime,
-    ) -> Result<(), AdminSubscriberError>;
+        _admin_service_event: &messages::AdminServiceEvent,
+        _timestamp: &SystemTime,
+    ) -> Result<(), AdminSubscriberError> {
+        Err(AdminSubscriberError::UnableToHandleEvent(
+            "subscriber does not implement handle_event_record".into(),
+        ))
+    }
+
+    /// Handles a stored event, along with the ID and time assigned to it by the event store.
+    ///
+    /// The default implementation passes the event and its time to `handle_event`, so that
+    /// subscribers written before event IDs were added continue to work.
+    fn handle_event_record(&self, event_record: &EventRecord) -> Result<(), AdminSubscriberError> {
+        #[allow(deprecated)]
+        self.handle_event(event_record.event(), event_record.timestamp())
+    }
 }
 
 pub trait AdminCommands: Send + Sync {
@@ -88,6 +116,13 @@ pub trait AdminCommands: Send + Sync {
         event_type: &str,
     ) -> Result<Events, AdminServiceError>;
 
+    /// Returns the events of the given type that were stored after the event with the given ID.
+    fn get_events_since_id(
+        &self,
+        since_event_id: i64,
+        event_type: &str,
+    ) -> Result<Events, AdminServiceError>;
+
     fn admin_service_status(&self) -> Result<AdminServiceStatus, AdminServiceError>;
 
     fn clone_boxed(&self) -> Box<dyn AdminCommands>;
@@ -130,13 +165,13 @@ impl AdminKeyVerifier for Box<dyn RegistryReader> {
     }
 }
 
-/// An iterator over AdminServiceEvents and the time that each occurred.
+/// An iterator over AdminServiceEvents, along with the ID and time assigned to each.
 pub struct Events {
-    inner: Box<dyn Iterator<Item = (SystemTime, messages::AdminServiceEvent)> + Send>,
+    inner: Box<dyn Iterator<Item = EventRecord> + Send>,
 }
 
 impl Iterator for Events {
-    type Item = (SystemTime, messages::AdminServiceEvent);
+    type Item = EventRecord;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.inner.next()
@@ -151,11 +186,18 @@ pub struct AdminService {
     /// The coordinator timeout for the two-phase commit consensus engine
     coordinator_timeout: Duration,
     consensus: Option<AdminConsensusManager>,
+    proposal_expiry: Option<ProposalExpiryChecker>,
     peer_connector: PeerManagerConnector,
 }
 
 impl AdminService {
     #![allow(clippy::too_many_arguments)]
+    /// Constructs a new admin service.
+    ///
+    /// The circuits and proposals are read from and written to the given admin store. Without the
+    /// `admin-service-store` feature, the store is opened for the given storage type, either
+    /// "yaml" files in the state directory or "memory". The circuits already in the store, and
+    /// their members, are loaded into the given splinter state.
     pub fn new(
         node_id: &str,
         orchestrator: ServiceOrchestrator,
@@ -164,18 +206,23 @@ impl AdminService {
             Box<dyn ServiceArgValidator + Send>,
         >,
         peer_connector: PeerManagerConnector,
-        splinter_state: SplinterState,
+        mut splinter_state: SplinterState,
         signature_verifier: Box<dyn SignatureVerifier + Send>,
         key_verifier: Box<dyn AdminKeyVerifier>,
         key_permission_manager: Box<dyn KeyPermissionManager>,
-        storage_type: &str,
-        state_dir: &str,
+        #[cfg(feature = "admin-service-store")] admin_store: Box<dyn AdminServiceStore>,
+        #[cfg(not(feature = "admin-service-store"))] storage_type: &str,
+        #[cfg(not(feature = "admin-service-store"))] state_dir: &str,
         // The coordinator timeout for the two-phase commit consensus engine; if `None`, the
         // default value will be used (30 seconds).
         coordinator_timeout: Option<Duration>,
     ) -> Result<(Self, thread::JoinHandle<()>), ServiceError> {
         let coordinator_timeout =
             coordinator_timeout.unwrap_or_else(|| Duration::from_secs(DEFAULT_COORDINATOR_TIMEOUT));
+        #[cfg(not(feature = "admin-service-store"))]
+        let admin_store = open_admin_store(storage_type, state_dir)?;
+        load_splinter_state(&mut splinter_state, &*admin_store)?;
+
         let orchestrator = Arc::new(Mutex::new(orchestrator));
         let (sender, receiver) = channel();
         peer_connector
@@ -195,12 +242,12 @@ impl AdminService {
                 signature_verifier,
                 key_verifier,
                 key_permission_manager,
-                storage_type,
-                state_dir,
+                admin_store,
             )?)),
             orchestrator,
             coordinator_timeout,
             consensus: None,
+            proposal_expiry: None,
             peer_connector,
         };
 
@@ -243,6 +290,81 @@ impl AdminService {
         AdminServiceProposals::new(&self.admin_service_shared)
     }
 
+    /// Set a routing table that will be kept in sync with the changes the admin service makes to
+    /// circuits.
+    #[cfg(feature = "routing-table")]
+    pub fn set_routing_table_writer(
+        &self,
+        routing_table_writer: Box<dyn RoutingTableWriter>,
+    ) -> Result<(), ServiceError> {
+        self.admin_service_shared
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock("the admin shared lock was poisoned".into()))?
+            .set_routing_table_writer(Some(routing_table_writer));
+
+        Ok(())
+    }
+
+    /// Set the store that admin service events are recorded in. By default, the most recent
+    /// events are kept in memory.
+    pub fn set_event_store(
+        &self,
+        event_store: Box<dyn AdminServiceEventStore>,
+    ) -> Result<(), ServiceError> {
+        self.admin_service_shared
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock("the admin shared lock was poisoned".into()))?
+            .set_event_store(event_store);
+
+        Ok(())
+    }
+
+    /// Set how long circuit proposals wait for votes before they expire, for proposals whose
+    /// payload did not specify a time-to-live. By default, these proposals do not expire.
+    pub fn set_default_proposal_ttl(
+        &self,
+        default_proposal_ttl: Option<Duration>,
+    ) -> Result<(), ServiceError> {
+        self.admin_service_shared
+            .lock()
+            .map_err(|_| ServiceError::PoisonedLock("the admin shared lock was poisoned".into()))?
+            .set_default_proposal_ttl(default_proposal_ttl);
+
+        Ok(())
+    }
+
+    /// Set the queue that holds messages for members of durable circuits that are not connected.
+    /// Subscribers are sent a `DurableQueueFull` event when the messages stored for a circuit
+    /// reach the queue's size limit.
+    pub fn set_durable_message_queue(
+        &self,
+        queue: &DurableMessageQueue,
+    ) -> Result<(), ServiceError> {
+        let size_limit = queue
+            .circuit_size_limit()
+            .map_err(|err| ServiceError::PoisonedLock(err.to_string()))?;
+        let shared = self.admin_service_shared.clone();
+        queue
+            .set_overflow_listener(move |circuit_id, node_id| {
+                let result = match shared.lock() {
+                    Ok(mut shared) => {
+                        shared.handle_durable_queue_full(circuit_id, node_id, size_limit)
+                    }
+                    Err(_) => {
+                        error!("the admin shared lock was poisoned");
+                        return;
+                    }
+                };
+                if let Err(err) = result {
+                    error!(
+                        "Unable to send durable queue event for circuit {}: {}",
+                        circuit_id, err
+                    );
+                }
+            })
+            .map_err(|err| ServiceError::PoisonedLock(err.to_string()))
+    }
+
     /// On restart of a splinter node, all services that this node should run on the existing
     /// circuits should be initialized using the service orchestrator. This may not include all
     /// services if they are not supported locally. It is expected that some services will be
@@ -274,6 +396,11 @@ impl AdminService {
         let mut peer_refs = vec![];
         // start all services of the supported types
         for (circuit_name, circuit) in circuits.iter() {
+            // the local node has left abandoned circuits, so they are not restarted
+            if !circuit.circuit_status().is_active() {
+                continue;
+            }
+
             // restart all peer in the circuit
             for member in circuit.members() {
                 if member != &self.node_id {
@@ -338,7 +465,8 @@ impl AdminService {
             .map_err(|_| {
                 ServiceStartError::PoisonedLock("the admin shared lock was poisoned".into())
             })?
-            .get_proposals();
+            .get_proposals()
+            .map_err(|err| ServiceStartError::Internal(Box::new(err)))?;
 
         for (_, proposal) in proposals.iter() {
             // restart all peer in the circuit
@@ -405,6 +533,15 @@ impl Service for AdminService {
 
         self.consensus = Some(consensus);
 
+        self.proposal_expiry = Some(
+            ProposalExpiryChecker::new(
+                &self.service_id,
+                self.admin_service_shared.clone(),
+                Duration::from_secs(PROPOSAL_EXPIRY_CHECK_INTERVAL),
+            )
+            .map_err(|err| ServiceStartError::Internal(Box::new(err)))?,
+        );
+
         self.admin_service_shared
             .lock()
             .map_err(|_| {
@@ -445,6 +582,10 @@ impl Service for AdminService {
             .shutdown()
             .map_err(|err| ServiceStopError::Internal(Box::new(err)))?;
 
+        if let Some(proposal_expiry) = self.proposal_expiry.take() {
+            proposal_expiry.shutdown();
+        }
+
         self.admin_service_shared
             .lock()
             .map_err(|_| {
@@ -528,9 +669,17 @@ impl Service for AdminService {
                     ServiceError::PoisonedLock("the admin shared lock was poisoned".into())
                 })?;
 
+                let joining_circuit = if proposed_circuit.has_circuit() {
+                    Some(proposed_circuit.get_circuit().clone())
+                } else {
+                    None
+                };
+
                 admin_service_shared.handle_proposed_circuit(
                     proposal,
                     circuit_payload.clone(),
+                    joining_circuit,
+                    proposed_circuit.get_proposal_expires_at(),
                     message_context.sender.to_string(),
                 )
             }
@@ -547,6 +696,43 @@ impl Service for AdminService {
                     .add_ready_member(circuit_id, member_node_id.into())
                     .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
             }
+            AdminMessage_Type::MEMBER_ABANDONED => {
+                let member_abandoned = admin_message.get_member_abandoned();
+                let circuit_id = member_abandoned.get_circuit_id();
+                let member_node_id = member_abandoned.get_member_node_id();
+
+                let mut shared = self.admin_service_shared.lock().map_err(|_| {
+                    ServiceError::PoisonedLock("the admin shared lock was poisoned".into())
+                })?;
+
+                shared
+                    .handle_member_abandoned(circuit_id, member_node_id, &message_context.sender)
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
+            }
+            AdminMessage_Type::CIRCUIT_JOIN_REQUEST => {
+                let circuit = admin_message.get_circuit_join_request().get_circuit();
+
+                let mut shared = self.admin_service_shared.lock().map_err(|_| {
+                    ServiceError::PoisonedLock("the admin shared lock was poisoned".into())
+                })?;
+
+                shared
+                    .handle_circuit_join(circuit.clone(), &message_context.sender)
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
+            }
+            AdminMessage_Type::MEMBER_REMOVED => {
+                let member_removed = admin_message.get_member_removed();
+                let circuit_id = member_removed.get_circuit_id();
+                let member_node_id = member_removed.get_member_node_id();
+
+                let mut shared = self.admin_service_shared.lock().map_err(|_| {
+                    ServiceError::PoisonedLock("the admin shared lock was poisoned".into())
+                })?;
+
+                shared
+                    .handle_member_removed(circuit_id, member_node_id, &message_context.sender)
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
+            }
             AdminMessage_Type::SERVICE_PROTOCOL_VERSION_REQUEST => {
                 let request = admin_message.get_protocol_request();
                 let protocol =
@@ -679,6 +865,20 @@ impl AdminCommands for AdminServiceCommands {
             })
     }
 
+    fn get_events_since_id(
+        &self,
+        since_event_id: i64,
+        event_type: &str,
+    ) -> Result<Events, AdminServiceError> {
+        self.shared
+            .lock()
+            .map_err(|_| AdminServiceError::general_error("Admin shared lock was lock poisoned"))?
+            .get_events_since_id(since_event_id, event_type)
+            .map_err(|err| {
+                AdminServiceError::general_error_with_source("Unable to get events", Box::new(err))
+            })
+    }
+
     fn admin_service_status(&self) -> Result<AdminServiceStatus, AdminServiceError> {
         Ok(self
             .shared
@@ -692,6 +892,72 @@ impl AdminCommands for AdminServiceCommands {
     }
 }
 
+/// Load the circuits held in the admin store, along with their members' node information, into
+/// splinter state.
+fn load_splinter_state(
+    splinter_state: &mut SplinterState,
+    admin_store: &dyn AdminServiceStore,
+) -> Result<(), ServiceError> {
+    let nodes = admin_store
+        .list_nodes()
+        .map_err(|err| ServiceError::UnableToCreate(Box::new(err)))?;
+    for node in nodes {
+        splinter_state
+            .add_node(node.node_id().to_string(), SplinterNode::from(&node))
+            .map_err(|err| ServiceError::UnableToCreate(Box::new(err)))?;
+    }
+
+    let circuits = admin_store
+        .list_circuits(&[])
+        .map_err(|err| ServiceError::UnableToCreate(Box::new(err)))?;
+    for circuit in circuits {
+        let state_circuit = StateCircuit::try_from(&circuit)
+            .map_err(|err| ServiceError::UnableToCreate(Box::new(err)))?;
+        splinter_state
+            .add_circuit(circuit.circuit_id().to_string(), state_circuit)
+            .map_err(|err| ServiceError::UnableToCreate(Box::new(err)))?;
+    }
+
+    Ok(())
+}
+
+/// Open the admin store for the given storage type.
+#[cfg(not(feature = "admin-service-store"))]
+fn open_admin_store(
+    storage_type: &str,
+    state_dir: &str,
+) -> Result<Box<dyn AdminServiceStore>, ServiceError> {
+    match storage_type {
+        "yaml" => {
+            let state_file = |file_name: &str| {
+                std::path::Path::new(state_dir)
+                    .join(file_name)
+                    .to_str()
+                    .map(String::from)
+                    .ok_or_else(|| {
+                        ServiceError::UnableToCreate(Box::new(AdminServiceError::general_error(
+                            "'state_dir' is not a valid UTF-8 string",
+                        )))
+                    })
+            };
+            let store = YamlAdminServiceStore::new(
+                state_file("circuits.yaml")?,
+                state_file("circuit_proposals.yaml")?,
+            )
+            .map_err(|err| ServiceError::UnableToCreate(Box::new(err)))?;
+
+            Ok(Box::new(store))
+        }
+        "memory" => Ok(Box::new(MemoryAdminServiceStore::new())),
+        _ => Err(ServiceError::UnableToCreate(Box::new(
+            AdminServiceError::general_error(&format!(
+                "storage type is not supported: {}",
+                storage_type
+            )),
+        ))),
+    }
+}
+
 pub fn admin_service_id(node_id: &str) -> String {
     format!("admin::{}", node_id)
 }
@@ -738,6 +1004,8 @@ mod tests {
     use std::sync::mpsc::{channel, Sender};
     use std::time::{Duration, Instant};
 
+    #[cfg(feature = "admin-service-store")]
+    use crate::admin::store::MemoryAdminServiceStore;
     use crate::circuit::{directory::CircuitDirectory, SplinterState};
     use crate::keys::insecure::AllowAllKeyPermissionManager;
     use crate::mesh::Mesh;
@@ -759,8 +1027,6 @@ mod tests {
         25, 26, 27, 28, 29, 30, 31, 32,
     ];
 
-    const STATE_DIR: &str = "/var/lib/splinter/";
-
     /// Test that a circuit creation creates the correct connections and sends the appropriate
     /// messages.
     #[test]
@@ -831,8 +1097,12 @@ mod tests {
             Box::new(HashVerifier),
             Box::new(MockAdminKeyVerifier),
             Box::new(AllowAllKeyPermissionManager),
+            #[cfg(feature = "admin-service-store")]
+            Box::new(MemoryAdminServiceStore::new()),
+            #[cfg(not(feature = "admin-service-store"))]
             "memory",
-            STATE_DIR,
+            #[cfg(not(feature = "admin-service-store"))]
+            "/var/lib/splinter/",
             None,
         )
         .expect("Service should have been created correctly");
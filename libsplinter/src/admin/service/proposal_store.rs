@@ -113,7 +113,8 @@ impl ProposalStore for AdminServiceProposals {
             .shared
             .lock()
             .map_err(|_| ProposalStoreError::new("Admin shared lock was lock poisoned"))?
-            .get_proposals();
+            .get_proposals()
+            .map_err(|err| ProposalStoreError::from_source("Unable to get proposals", err))?;
 
         let total = proposals
             .iter()
@@ -14,20 +14,29 @@
 
 use std::cell::RefCell;
 use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
 use std::iter::FromIterator;
-use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use protobuf::{Message, RepeatedField};
 use std::sync::mpsc::Sender;
 
+use crate::admin::store::{
+    AdminServiceStore, Circuit as StoreCircuit, CircuitNode as StoreNode,
+    CircuitProposal as StoreProposal,
+};
+#[cfg(feature = "routing-table")]
+use crate::circuit::routing::{
+    Circuit as RoutingCircuit, CircuitNode as RoutingCircuitNode, RoutingTableWriter,
+    Service as RoutingService, ServiceId as RoutingServiceId,
+};
 use crate::circuit::SplinterState;
 use crate::circuit::{
     service::SplinterNode as StateNode,
     service::{Service, ServiceId},
     AuthorizationType, Circuit as StateCircuit, DurabilityType, PersistenceType, RouteType,
-    ServiceDefinition as StateServiceDefinition,
+    ServiceDefinition as StateServiceDefinition, VotingPolicy,
 };
 use crate::consensus::{Proposal, ProposalId, ProposalUpdate};
 use crate::hex::to_hex;
@@ -35,15 +44,16 @@ use crate::keys::KeyPermissionManager;
 use crate::orchestrator::{ServiceDefinition, ServiceOrchestrator};
 use crate::peer::{PeerManagerConnector, PeerRef};
 use crate::protocol::{ADMIN_PROTOCOL_VERSION, ADMIN_SERVICE_PROTOCOL_MIN};
-#[cfg(feature = "service-arg-validation")]
-use crate::protos::admin::SplinterService;
 use crate::protos::admin::{
-    AdminMessage, AdminMessage_Type, Circuit, CircuitManagementPayload,
+    AdminMessage, AdminMessage_Type, Circuit, CircuitJoinRequest, CircuitManagementPayload,
     CircuitManagementPayload_Action, CircuitManagementPayload_Header, CircuitProposal,
-    CircuitProposalVote, CircuitProposalVote_Vote, CircuitProposal_ProposalType,
-    CircuitProposal_VoteRecord, Circuit_AuthorizationType, Circuit_DurabilityType,
-    Circuit_PersistenceType, Circuit_RouteType, MemberReady, ServiceProtocolVersionRequest,
-    SplinterNode,
+    CircuitProposalCancel, CircuitProposalVote, CircuitProposalVote_Vote,
+    CircuitProposal_ProposalType, CircuitProposal_VoteRecord, CircuitUpdateAddNodeRequest,
+    CircuitUpdateApplicationMetadataRequest, CircuitUpdateRemoveNodeRequest,
+    CircuitUpdateRosterRequest, Circuit_AuthorizationType, Circuit_DurabilityType,
+    Circuit_PersistenceType, Circuit_RouteType, Circuit_VotingPolicy, MemberAbandoned, MemberReady,
+    MemberRemoved, ServiceProtocolVersionRequest, SplinterNode, SplinterService,
+    SplinterService_Argument,
 };
 use crate::service::error::ServiceError;
 #[cfg(feature = "service-arg-validation")]
@@ -51,12 +61,10 @@ use crate::service::validation::ServiceArgValidator;
 
 use crate::service::ServiceNetworkSender;
 use crate::signing::SignatureVerifier;
-use crate::storage::sets::mem::DurableBTreeSet;
 
 use super::error::{AdminSharedError, MarshallingError};
-use super::mailbox::Mailbox;
+use super::event_store::{AdminServiceEventStore, EventRecord, MemoryAdminServiceEventStore};
 use super::messages;
-use super::open_proposals::OpenProposals;
 use super::{
     admin_service_id, sha256, AdminKeyVerifier, AdminServiceEventSubscriber, AdminSubscriberError,
     Events,
@@ -67,6 +75,9 @@ static PROPOSER_ROLE: &str = "proposer";
 
 const DEFAULT_IN_MEMORY_EVENT_LIMIT: usize = 100;
 
+// The circuit that admin messages between nodes are sent on
+const ADMIN_CIRCUIT_ID: &str = "admin";
+
 pub enum PayloadType {
     Circuit(CircuitManagementPayload),
     Consensus(ProposalId, (Proposal, CircuitManagementPayload)),
@@ -116,24 +127,19 @@ impl SubscriberMap {
         }
     }
 
-    fn broadcast_by_type(
-        &self,
-        event_type: &str,
-        admin_service_event: &messages::AdminServiceEvent,
-        timestamp: &SystemTime,
-    ) {
+    fn broadcast_by_type(&self, event_type: &str, event_record: &EventRecord) {
         let mut subscribers_by_type = self.subscribers_by_type.borrow_mut();
         if let Some(subscribers) = subscribers_by_type.get_mut(event_type) {
-            subscribers.retain(|subscriber| {
-                match subscriber.handle_event(admin_service_event, timestamp) {
+            subscribers.retain(
+                |subscriber| match subscriber.handle_event_record(event_record) {
                     Ok(()) => true,
                     Err(AdminSubscriberError::Unsubscribe) => false,
                     Err(AdminSubscriberError::UnableToHandleEvent(msg)) => {
                         error!("Unable to send event: {}", msg);
                         true
                     }
-                }
-            });
+                },
+            );
         }
     }
 
@@ -157,8 +163,9 @@ impl SubscriberMap {
 pub struct AdminServiceShared {
     // the node id of the connected splinter node
     node_id: String,
-    // the list of circuit proposal that are being voted on by members of a circuit
-    open_proposals: OpenProposals,
+    // the store of committed circuits and of the circuit proposals that are being voted on by
+    // members of a circuit
+    admin_store: Box<dyn AdminServiceStore>,
     // the list of circuit that have been committed to splinter state but whose services haven't
     // been initialized
     uninitialized_circuits: HashMap<String, UninitializedCircuit>,
@@ -183,16 +190,25 @@ pub struct AdminServiceShared {
     service_protocols: HashMap<String, u32>,
     // CircuitManagmentPayloads that still need to go through consensus
     pending_circuit_payloads: VecDeque<CircuitManagementPayload>,
+    // circuits that must be sent to nodes added to them, once the service protocol has been
+    // agreed upon with the new member's admin service, by node id
+    pending_circuit_joins: HashMap<String, Vec<Circuit>>,
+    // proposed definitions of the circuits this node is being added to, by circuit id, kept while
+    // the addition is agreed upon through consensus
+    joining_circuits: HashMap<String, Circuit>,
     // The pending consensus proposals
     pending_consensus_proposals: HashMap<ProposalId, (Proposal, CircuitManagementPayload)>,
+    // the expiration times the coordinators of pending consensus proposals set on the circuit
+    // proposals, in seconds since the UNIX epoch
+    proposal_expirations: HashMap<ProposalId, u64>,
     // the pending changes for the current proposal
     pending_changes: Option<CircuitProposalContext>,
     // the verifiers that should be broadcasted for the pending change
     current_consensus_verifiers: Vec<String>,
     // Admin Service Event Subscribers
     event_subscribers: SubscriberMap,
-    // Mailbox of AdminServiceEvent values
-    event_mailbox: Mailbox,
+    // Store of AdminServiceEvent values
+    event_store: Box<dyn AdminServiceEventStore>,
     // copy of splinter state
     splinter_state: SplinterState,
     // signature verifier
@@ -200,6 +216,12 @@ pub struct AdminServiceShared {
     key_verifier: Box<dyn AdminKeyVerifier>,
     key_permission_manager: Box<dyn KeyPermissionManager>,
     proposal_sender: Option<Sender<ProposalUpdate>>,
+    // how long proposals wait for votes before they expire, if the payload that created the
+    // proposal did not specify a time-to-live; if `None`, these proposals do not expire
+    default_proposal_ttl: Option<Duration>,
+    // routing table that is kept in sync with changes to the circuits' rosters
+    #[cfg(feature = "routing-table")]
+    routing_table_writer: Option<Box<dyn RoutingTableWriter>>,
 
     admin_service_status: AdminServiceStatus,
 }
@@ -218,30 +240,16 @@ impl AdminServiceShared {
         signature_verifier: Box<dyn SignatureVerifier + Send>,
         key_verifier: Box<dyn AdminKeyVerifier>,
         key_permission_manager: Box<dyn KeyPermissionManager>,
-        storage_type: &str,
-        state_dir: &str,
+        admin_store: Box<dyn AdminServiceStore>,
     ) -> Result<Self, ServiceError> {
-        let storage_location = match storage_type {
-            "yaml" => Path::new(state_dir)
-                .join("circuit_proposals.yaml")
-                .to_str()
-                .expect("'state_dir' is not a valid UTF-8 string")
-                .to_string(),
-            "memory" => "memory".to_string(),
-            _ => panic!("Storage type is not supported: {}", storage_type),
-        };
-
-        let open_proposals = OpenProposals::new(storage_location)
-            .map_err(|err| ServiceError::UnableToCreate(Box::new(err)))?;
-
-        let event_mailbox = Mailbox::new(DurableBTreeSet::new_boxed_with_bound(
+        let event_store = Box::new(MemoryAdminServiceEventStore::new_with_limit(
             std::num::NonZeroUsize::new(DEFAULT_IN_MEMORY_EVENT_LIMIT).unwrap(),
         ));
 
         Ok(AdminServiceShared {
             node_id,
             network_sender: None,
-            open_proposals,
+            admin_store,
             uninitialized_circuits: Default::default(),
             orchestrator,
             #[cfg(feature = "service-arg-validation")]
@@ -252,16 +260,22 @@ impl AdminServiceShared {
             pending_protocol_payloads: Vec::new(),
             service_protocols: HashMap::new(),
             pending_circuit_payloads: VecDeque::new(),
+            pending_circuit_joins: HashMap::new(),
+            joining_circuits: HashMap::new(),
             pending_consensus_proposals: HashMap::new(),
+            proposal_expirations: HashMap::new(),
             pending_changes: None,
             current_consensus_verifiers: Vec::new(),
             event_subscribers: SubscriberMap::new(),
-            event_mailbox,
+            event_store,
             splinter_state,
             signature_verifier,
             key_verifier,
             key_permission_manager,
             proposal_sender: None,
+            default_proposal_ttl: None,
+            #[cfg(feature = "routing-table")]
+            routing_table_writer: None,
             admin_service_status: AdminServiceStatus::NotRunning,
         })
     }
@@ -282,6 +296,22 @@ impl AdminServiceShared {
         self.proposal_sender = proposal_sender;
     }
 
+    pub fn set_event_store(&mut self, event_store: Box<dyn AdminServiceEventStore>) {
+        self.event_store = event_store;
+    }
+
+    pub fn set_default_proposal_ttl(&mut self, default_proposal_ttl: Option<Duration>) {
+        self.default_proposal_ttl = default_proposal_ttl;
+    }
+
+    #[cfg(feature = "routing-table")]
+    pub fn set_routing_table_writer(
+        &mut self,
+        routing_table_writer: Option<Box<dyn RoutingTableWriter>>,
+    ) {
+        self.routing_table_writer = routing_table_writer;
+    }
+
     pub fn pop_pending_circuit_payload(&mut self) -> Option<CircuitManagementPayload> {
         self.pending_circuit_payloads.pop_front()
     }
@@ -297,9 +327,16 @@ impl AdminServiceShared {
         &mut self,
         id: &ProposalId,
     ) -> Option<(Proposal, CircuitManagementPayload)> {
+        self.proposal_expirations.remove(id);
         self.pending_consensus_proposals.remove(id)
     }
 
+    /// Returns the expiration time the coordinator of the given consensus proposal set on the
+    /// circuit proposal, if the proposal was received from another node.
+    pub fn proposal_expiration(&self, id: &ProposalId) -> Option<u64> {
+        self.proposal_expirations.get(id).copied()
+    }
+
     pub fn add_pending_consensus_proposal(
         &mut self,
         id: ProposalId,
@@ -365,8 +402,69 @@ impl AdminServiceShared {
                     .get_circuit_proposal()
                     .circuit_management_type
                     .clone();
+                self.joining_circuits.remove(circuit_id);
+
+                if action == CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_CANCEL {
+                    return self.cancel_proposal(
+                        circuit_proposal,
+                        circuit_proposal_context.signer_public_key,
+                    );
+                }
 
                 match self.check_approved(&circuit_proposal) {
+                    Ok(CircuitProposalStatus::Accepted)
+                        if circuit_proposal.get_proposal_type()
+                            == CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA =>
+                    {
+                        self.update_circuit_application_metadata(
+                            circuit_proposal,
+                            circuit_proposal_context.signer_public_key,
+                        )
+                    }
+                    Ok(CircuitProposalStatus::Accepted)
+                        if circuit_proposal.get_proposal_type()
+                            == CircuitProposal_ProposalType::DESTROY =>
+                    {
+                        self.destroy_circuit(
+                            circuit_proposal,
+                            circuit_proposal_context.signer_public_key,
+                        )
+                    }
+                    Ok(CircuitProposalStatus::Accepted)
+                        if circuit_proposal.get_proposal_type()
+                            == CircuitProposal_ProposalType::UPDATE_ROSTER =>
+                    {
+                        self.update_circuit_roster(
+                            circuit_proposal,
+                            circuit_proposal_context.signer_public_key,
+                        )
+                    }
+                    Ok(CircuitProposalStatus::Accepted)
+                        if circuit_proposal.get_proposal_type()
+                            == CircuitProposal_ProposalType::ADD_NODE
+                            && !self.splinter_state.has_circuit(circuit_id)? =>
+                    {
+                        // this is the node being added
+                        self.join_circuit(circuit_proposal)
+                    }
+                    Ok(CircuitProposalStatus::Accepted)
+                        if circuit_proposal.get_proposal_type()
+                            == CircuitProposal_ProposalType::ADD_NODE =>
+                    {
+                        self.add_circuit_member(
+                            circuit_proposal,
+                            circuit_proposal_context.signer_public_key,
+                        )
+                    }
+                    Ok(CircuitProposalStatus::Accepted)
+                        if circuit_proposal.get_proposal_type()
+                            == CircuitProposal_ProposalType::REMOVE_NODE =>
+                    {
+                        self.remove_circuit_member(
+                            circuit_proposal,
+                            circuit_proposal_context.signer_public_key,
+                        )
+                    }
                     Ok(CircuitProposalStatus::Accepted) => {
                         // commit new circuit
                         let circuit = circuit_proposal.get_circuit_proposal();
@@ -425,6 +523,68 @@ impl AdminServiceShared {
                                 Ok(())
                             }
 
+                            CircuitManagementPayload_Action::CIRCUIT_DESTROY_REQUEST => {
+                                // notify registered application authorization handlers of the
+                                // committed destroy proposal
+                                let event = messages::AdminServiceEvent::ProposalSubmitted(
+                                    messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                                        .map_err(AdminSharedError::InvalidMessageFormat)?,
+                                );
+                                self.send_event(&mgmt_type, event);
+
+                                info!("committed destroy proposal for circuit {}", circuit_id);
+                                Ok(())
+                            }
+
+                            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ROSTER_REQUEST => {
+                                // notify registered application authorization handlers of the
+                                // committed roster update proposal
+                                let event = messages::AdminServiceEvent::ProposalSubmitted(
+                                    messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                                        .map_err(AdminSharedError::InvalidMessageFormat)?,
+                                );
+                                self.send_event(&mgmt_type, event);
+
+                                info!(
+                                    "committed roster update proposal for circuit {}",
+                                    circuit_id
+                                );
+                                Ok(())
+                            }
+
+                            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ADD_NODE
+                            | CircuitManagementPayload_Action::CIRCUIT_UPDATE_REMOVE_NODE => {
+                                // notify registered application authorization handlers of the
+                                // committed membership change proposal
+                                let event = messages::AdminServiceEvent::ProposalSubmitted(
+                                    messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                                        .map_err(AdminSharedError::InvalidMessageFormat)?,
+                                );
+                                self.send_event(&mgmt_type, event);
+
+                                info!(
+                                    "committed membership change proposal for circuit {}",
+                                    circuit_id
+                                );
+                                Ok(())
+                            }
+
+                            CircuitManagementPayload_Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST => {
+                                // notify registered application authorization handlers of the
+                                // committed application metadata update proposal
+                                let event = messages::AdminServiceEvent::ProposalSubmitted(
+                                    messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                                        .map_err(AdminSharedError::InvalidMessageFormat)?,
+                                );
+                                self.send_event(&mgmt_type, event);
+
+                                info!(
+                                    "committed application metadata update proposal for circuit {}",
+                                    circuit_id
+                                );
+                                Ok(())
+                            }
+
                             CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_VOTE => {
                                 // notify registered application authorization handlers of the
                                 // committed circuit proposal
@@ -450,9 +610,13 @@ impl AdminServiceShared {
                         // remove circuit
                         let proposal = self.remove_proposal(&circuit_id)?;
                         if let Some(proposal) = proposal {
-                            for member in proposal.get_circuit_proposal().members.iter() {
-                                self.remove_peer_ref(member.get_node_id());
-                            }
+                            let members = proposal
+                                .get_circuit_proposal()
+                                .get_members()
+                                .iter()
+                                .map(|member| member.get_node_id().to_string())
+                                .collect::<Vec<_>>();
+                            self.remove_proposed_peer_refs(circuit_id, &members)?;
                         }
                         let circuit_proposal_proto =
                             messages::CircuitProposal::from_proto(circuit_proposal.clone())
@@ -475,19 +639,27 @@ impl AdminServiceShared {
 
     pub fn rollback(&mut self) -> Result<(), AdminSharedError> {
         match self.pending_changes.take() {
-            Some(circuit_proposal_context) => info!(
-                "discarded change for {}",
-                circuit_proposal_context.circuit_proposal.get_circuit_id()
-            ),
+            Some(circuit_proposal_context) => {
+                let circuit_id = circuit_proposal_context.circuit_proposal.get_circuit_id();
+                self.joining_circuits.remove(circuit_id);
+                info!("discarded change for {}", circuit_id)
+            }
             None => debug!("no changes to rollback"),
         }
 
         Ok(())
     }
 
+    /// Validates the given payload and returns the hash of the resulting circuit proposal, along
+    /// with the proposal itself.
+    ///
+    /// If the payload creates a new proposal, the proposal expires at `proposal_expires_at`, as
+    /// set by the coordinator of the change. If `None`, this node coordinates the change and the
+    /// expiration time is computed from the proposal's time-to-live.
     pub fn propose_change(
         &mut self,
         mut circuit_payload: CircuitManagementPayload,
+        proposal_expires_at: Option<u64>,
     ) -> Result<(String, CircuitProposal), AdminSharedError> {
         let header = protobuf::parse_from_bytes::<CircuitManagementPayload_Header>(
             circuit_payload.get_header(),
@@ -530,6 +702,8 @@ impl AdminServiceShared {
                 circuit_proposal.set_circuit_proposal(proposed_circuit);
                 circuit_proposal.set_requester(header.get_requester().to_vec());
                 circuit_proposal.set_requester_node_id(header.get_requester_node_id().to_string());
+                circuit_proposal
+                    .set_expires_at(self.proposal_expires_at(&header, proposal_expires_at));
 
                 let expected_hash = sha256(&circuit_proposal)?;
                 self.pending_changes = Some(CircuitProposalContext {
@@ -562,7 +736,7 @@ impl AdminServiceShared {
                     })?;
 
                 let mut verifiers = vec![];
-                for member in circuit_proposal.get_circuit_proposal().get_members() {
+                for member in self.voting_members(&circuit_proposal)? {
                     verifiers.push(admin_service_id(member.get_node_id()));
                 }
                 let signer_public_key = header.get_requester();
@@ -590,7 +764,19 @@ impl AdminServiceShared {
                 vote_record.set_voter_node_id(header.get_requester_node_id().to_string());
 
                 let mut votes = circuit_proposal.get_votes().to_vec();
-                votes.push(vote_record);
+                // a changed vote replaces the voter's record, keeping the votes it replaces
+                match votes
+                    .iter_mut()
+                    .find(|vote| vote.get_voter_node_id() == header.get_requester_node_id())
+                {
+                    Some(existing_record) => {
+                        let mut previous_votes = existing_record.take_previous_votes();
+                        previous_votes.push(existing_record.get_vote());
+                        vote_record.set_previous_votes(previous_votes);
+                        *existing_record = vote_record;
+                    }
+                    None => votes.push(vote_record),
+                }
                 circuit_proposal.set_votes(RepeatedField::from_vec(votes));
 
                 let expected_hash = sha256(&circuit_proposal)?;
@@ -602,6 +788,171 @@ impl AdminServiceShared {
                 self.current_consensus_verifiers = verifiers;
                 Ok((expected_hash, circuit_proposal))
             }
+            CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_CANCEL => {
+                let cancel = circuit_payload.get_circuit_proposal_cancel();
+
+                let circuit_proposal = self
+                    .get_proposal(cancel.get_circuit_id())
+                    .map_err(|err| {
+                        AdminSharedError::ValidationFailed(format!(
+                            "error occurred when trying to get proposal {}",
+                            err
+                        ))
+                    })?
+                    .ok_or_else(|| {
+                        AdminSharedError::ValidationFailed(format!(
+                            "Received cancellation of a proposal that does not exist: circuit id {}",
+                            cancel.circuit_id
+                        ))
+                    })?;
+
+                let mut verifiers = vec![];
+                for member in self.voting_members(&circuit_proposal)? {
+                    verifiers.push(admin_service_id(member.get_node_id()));
+                }
+
+                self.validate_cancel_proposal(
+                    cancel,
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                    &circuit_proposal,
+                )?;
+                debug!(
+                    "proposing cancellation of proposal for {}",
+                    cancel.circuit_id
+                );
+
+                let expected_hash = sha256(&circuit_proposal)?;
+                self.pending_changes = Some(CircuitProposalContext {
+                    circuit_proposal: circuit_proposal.clone(),
+                    signer_public_key: header.get_requester().to_vec(),
+                    action: CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_CANCEL,
+                });
+                self.current_consensus_verifiers = verifiers;
+                Ok((expected_hash, circuit_proposal))
+            }
+            CircuitManagementPayload_Action::CIRCUIT_DESTROY_REQUEST => {
+                let destroy_request = circuit_payload.get_circuit_destroy_request();
+                let signer_public_key = header.get_requester();
+                let requester_node_id = header.get_requester_node_id();
+
+                self.validate_destroy_circuit(
+                    destroy_request.get_circuit_id(),
+                    signer_public_key,
+                    requester_node_id,
+                )?;
+                debug!("proposing destroy of {}", destroy_request.get_circuit_id());
+
+                let circuit = self.get_circuit_proto(destroy_request.get_circuit_id())?;
+                self.propose_circuit_change(
+                    &header,
+                    CircuitProposal_ProposalType::DESTROY,
+                    circuit,
+                    CircuitManagementPayload_Action::CIRCUIT_DESTROY_REQUEST,
+                    proposal_expires_at,
+                )
+            }
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ROSTER_REQUEST => {
+                let update_roster_request = circuit_payload.get_circuit_update_roster_request();
+                let circuit = self.validate_update_roster(
+                    update_roster_request,
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )?;
+                debug!(
+                    "proposing roster update of {}",
+                    update_roster_request.get_circuit_id()
+                );
+
+                self.propose_circuit_change(
+                    &header,
+                    CircuitProposal_ProposalType::UPDATE_ROSTER,
+                    circuit,
+                    CircuitManagementPayload_Action::CIRCUIT_UPDATE_ROSTER_REQUEST,
+                    proposal_expires_at,
+                )
+            }
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ADD_NODE => {
+                let add_node_request = circuit_payload.get_circuit_update_add_node();
+                let circuit = self
+                    .validate_add_node(
+                        add_node_request,
+                        header.get_requester(),
+                        header.get_requester_node_id(),
+                    )
+                    .map_err(|err| {
+                        // remove the peer refs taken for the proposed members because we will not
+                        // accept this proposal
+                        let circuit_id = add_node_request.get_circuit_id();
+                        let members = match self.joining_circuits.get(circuit_id) {
+                            Some(circuit) => circuit
+                                .get_members()
+                                .iter()
+                                .map(|member| member.get_node_id().to_string())
+                                .collect(),
+                            None => vec![add_node_request.get_node().get_node_id().to_string()],
+                        };
+                        if let Err(err) = self.remove_proposed_peer_refs(circuit_id, &members) {
+                            warn!("Unable to remove peer refs for {}: {}", circuit_id, err);
+                        }
+                        err
+                    })?;
+                debug!(
+                    "proposing addition of {} to {}",
+                    add_node_request.get_node().get_node_id(),
+                    add_node_request.get_circuit_id()
+                );
+
+                self.propose_circuit_change(
+                    &header,
+                    CircuitProposal_ProposalType::ADD_NODE,
+                    circuit,
+                    CircuitManagementPayload_Action::CIRCUIT_UPDATE_ADD_NODE,
+                    proposal_expires_at,
+                )
+            }
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_REMOVE_NODE => {
+                let remove_node_request = circuit_payload.get_circuit_update_remove_node();
+                let circuit = self.validate_remove_node(
+                    remove_node_request,
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )?;
+                debug!(
+                    "proposing removal of {} from {}",
+                    remove_node_request.get_node_id(),
+                    remove_node_request.get_circuit_id()
+                );
+
+                self.propose_circuit_change(
+                    &header,
+                    CircuitProposal_ProposalType::REMOVE_NODE,
+                    circuit,
+                    CircuitManagementPayload_Action::CIRCUIT_UPDATE_REMOVE_NODE,
+                    proposal_expires_at,
+                )
+            }
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST => {
+                let update_request =
+                    circuit_payload.get_circuit_update_application_metadata_request();
+                let circuit = self.validate_update_application_metadata(
+                    update_request,
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )?;
+                debug!(
+                    "proposing application metadata update for {}",
+                    update_request.get_circuit_id()
+                );
+
+                self.propose_circuit_change(
+                    &header,
+                    CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA,
+                    circuit,
+                    CircuitManagementPayload_Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST,
+                    proposal_expires_at,
+                )
+            }
             CircuitManagementPayload_Action::ACTION_UNSET => Err(
                 AdminSharedError::ValidationFailed("Action must be set".to_string()),
             ),
@@ -612,8 +963,81 @@ impl AdminServiceShared {
         }
     }
 
+    /// Set up the pending change for a proposal that modifies an existing circuit. The proposed
+    /// circuit is the circuit as it will be once the proposal is accepted.
+    fn propose_circuit_change(
+        &mut self,
+        header: &CircuitManagementPayload_Header,
+        proposal_type: CircuitProposal_ProposalType,
+        circuit: Circuit,
+        action: CircuitManagementPayload_Action,
+        proposal_expires_at: Option<u64>,
+    ) -> Result<(String, CircuitProposal), AdminSharedError> {
+        // only the current members of the circuit take part in agreeing on the change
+        let current_members = self
+            .splinter_state
+            .circuit(circuit.get_circuit_id())?
+            .map(|circuit| circuit.members().to_vec())
+            .unwrap_or_default();
+        let mut verifiers = vec![];
+        for member in circuit.get_members() {
+            if current_members.contains(&member.get_node_id().to_string()) {
+                verifiers.push(admin_service_id(member.get_node_id()));
+            }
+        }
+
+        let mut circuit_proposal = CircuitProposal::new();
+        circuit_proposal.set_proposal_type(proposal_type);
+        circuit_proposal.set_circuit_id(circuit.get_circuit_id().into());
+        circuit_proposal.set_circuit_hash(sha256(&circuit)?);
+        circuit_proposal.set_circuit_proposal(circuit);
+        circuit_proposal.set_requester(header.get_requester().to_vec());
+        circuit_proposal.set_requester_node_id(header.get_requester_node_id().to_string());
+        circuit_proposal.set_expires_at(self.proposal_expires_at(header, proposal_expires_at));
+
+        let expected_hash = sha256(&circuit_proposal)?;
+        self.pending_changes = Some(CircuitProposalContext {
+            circuit_proposal: circuit_proposal.clone(),
+            signer_public_key: header.get_requester().to_vec(),
+            action,
+        });
+        self.current_consensus_verifiers = verifiers;
+
+        Ok((expected_hash, circuit_proposal))
+    }
+
+    /// Returns the expiration time, in seconds since the UNIX epoch, of a proposal created by the
+    /// payload with the given header; 0 if the proposal does not expire.
+    ///
+    /// The expiration time set by the coordinator of the change is used if there is one.
+    /// Otherwise it is computed from the time-to-live in the header or, if the header does not
+    /// specify one, from the node's default time-to-live.
+    fn proposal_expires_at(
+        &self,
+        header: &CircuitManagementPayload_Header,
+        coordinator_expires_at: Option<u64>,
+    ) -> u64 {
+        if let Some(expires_at) = coordinator_expires_at {
+            return expires_at;
+        }
+
+        let ttl = match header.get_proposal_ttl() {
+            0 => self.default_proposal_ttl,
+            secs => Some(Duration::from_secs(secs)),
+        };
+        ttl.and_then(|ttl| (SystemTime::now() + ttl).duration_since(UNIX_EPOCH).ok())
+            .map(|expires_at| expires_at.as_secs())
+            .unwrap_or(0)
+    }
+
     pub fn has_proposal(&self, circuit_id: &str) -> bool {
-        self.open_proposals.has_proposal(circuit_id)
+        match self.admin_store.get_proposal(circuit_id) {
+            Ok(proposal) => proposal.is_some(),
+            Err(err) => {
+                error!("Unable to check for a proposal for {}: {}", circuit_id, err);
+                false
+            }
+        }
     }
 
     /// Propose a new circuit
@@ -668,64 +1092,198 @@ impl AdminServiceShared {
                 )))
             })?;
 
-        self.check_connected_peers_payload_vote(
-            proposal.get_circuit_proposal().get_members(),
-            payload,
-            message_sender,
-        )
+        let members = self
+            .voting_members(&proposal)
+            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+        self.check_connected_peers_payload_vote(&members, payload, message_sender)
     }
 
-    pub fn send_protocol_request(&mut self, node_id: &str) -> Result<(), ServiceError> {
-        if self
-            .service_protocols
-            .get(&admin_service_id(node_id))
-            .is_none()
-        {
-            // we will always have the network sender at this point
-            if let Some(ref network_sender) = self.network_sender {
-                debug!(
-                    "Sending service protocol request to {}",
-                    admin_service_id(node_id)
-                );
-                let mut request = ServiceProtocolVersionRequest::new();
-                request.set_protocol_min(ADMIN_SERVICE_PROTOCOL_MIN);
-                request.set_protocol_max(ADMIN_PROTOCOL_VERSION);
-                let mut msg = AdminMessage::new();
-                msg.set_message_type(AdminMessage_Type::SERVICE_PROTOCOL_VERSION_REQUEST);
-                msg.set_protocol_request(request);
+    /// Propose cancelling a pending circuit proposal
+    ///
+    /// This operation will propose the withdrawal of the proposal to the nodes voting on it, in
+    /// the same way as a vote.
+    pub fn propose_cancel(
+        &mut self,
+        payload: CircuitManagementPayload,
+        message_sender: String,
+    ) -> Result<(), ServiceError> {
+        let circuit_id = payload.get_circuit_proposal_cancel().get_circuit_id();
+        debug!("received cancellation of proposal for {}", circuit_id);
 
-                let envelope_bytes = msg.write_to_bytes()?;
-                network_sender.send(&admin_service_id(node_id), &envelope_bytes)?;
-            }
-        } else {
-            debug!(
-                "Already agreed on protocol version with {}",
-                admin_service_id(node_id)
-            );
-        }
-        Ok(())
+        let proposal = self
+            .get_proposal(circuit_id)
+            .map_err(|err| {
+                ServiceError::UnableToHandleMessage(Box::new(AdminSharedError::ValidationFailed(
+                    format!("error occurred when trying to get proposal {}", err),
+                )))
+            })?
+            .ok_or_else(|| {
+                ServiceError::UnableToHandleMessage(Box::new(AdminSharedError::ValidationFailed(
+                    format!(
+                        "Received cancellation of a proposal that does not exist: circuit id {}",
+                        circuit_id
+                    ),
+                )))
+            })?;
+
+        let members = self
+            .voting_members(&proposal)
+            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+        self.check_connected_peers_payload_vote(&members, payload, message_sender)
     }
 
-    fn check_connected_peers_payload_vote(
+    /// Propose destroying an existing circuit
+    ///
+    /// This operation will propose the removal of a circuit to all the member nodes of the circuit.
+    /// The members are expected to already be peered, because they share the circuit.
+    pub fn propose_destroy(
         &mut self,
-        members: &[SplinterNode],
         payload: CircuitManagementPayload,
         message_sender: String,
     ) -> Result<(), ServiceError> {
-        let mut missing_protocol_ids = vec![];
-        let mut pending_members = vec![];
-        for node in members {
-            if self.node_id() != node.get_node_id()
-                && self
-                    .service_protocols
-                    .get(&admin_service_id(node.get_node_id()))
-                    .is_none()
-            {
-                self.send_protocol_request(node.get_node_id())?;
-                missing_protocol_ids.push(admin_service_id(node.get_node_id()))
-            }
-            pending_members.push(node.get_node_id().to_string());
-        }
+        let circuit_id = payload
+            .get_circuit_destroy_request()
+            .get_circuit_id()
+            .to_string();
+        debug!("received destroy proposal for {}", circuit_id);
+
+        self.propose_existing_circuit_change(&circuit_id, payload, message_sender)
+    }
+
+    /// Propose a change to the services of an existing circuit
+    ///
+    /// This operation will propose the new roster to all the member nodes of the circuit. The
+    /// members are expected to already be peered, because they share the circuit.
+    pub fn propose_update_roster(
+        &mut self,
+        payload: CircuitManagementPayload,
+        message_sender: String,
+    ) -> Result<(), ServiceError> {
+        let circuit_id = payload
+            .get_circuit_update_roster_request()
+            .get_circuit_id()
+            .to_string();
+        debug!("received roster update proposal for {}", circuit_id);
+
+        self.propose_existing_circuit_change(&circuit_id, payload, message_sender)
+    }
+
+    /// Propose a change to the members of an existing circuit
+    ///
+    /// This operation will propose adding or removing a node to the current member nodes of the
+    /// circuit. A node being added does not vote on the change, but it verifies the proposal like
+    /// the current members, so it is peered with before the proposal is made. It joins the
+    /// circuit once the change has been accepted.
+    pub fn propose_update_members(
+        &mut self,
+        payload: CircuitManagementPayload,
+        message_sender: String,
+    ) -> Result<(), ServiceError> {
+        if payload.has_circuit_update_add_node() {
+            let add_node_request = payload.get_circuit_update_add_node();
+            debug!(
+                "received membership change proposal for {}",
+                add_node_request.get_circuit_id()
+            );
+
+            let node = add_node_request.get_node().clone();
+            return self.check_connected_peers_payload_create(&[node], payload, message_sender);
+        }
+
+        let circuit_id = payload
+            .get_circuit_update_remove_node()
+            .get_circuit_id()
+            .to_string();
+        debug!("received membership change proposal for {}", circuit_id);
+
+        self.propose_existing_circuit_change(&circuit_id, payload, message_sender)
+    }
+
+    /// Propose replacing the application metadata and comments of an existing circuit
+    ///
+    /// This operation will propose the new application metadata to all the member nodes of the
+    /// circuit. The members are expected to already be peered, because they share the circuit.
+    pub fn propose_update_application_metadata(
+        &mut self,
+        payload: CircuitManagementPayload,
+        message_sender: String,
+    ) -> Result<(), ServiceError> {
+        let circuit_id = payload
+            .get_circuit_update_application_metadata_request()
+            .get_circuit_id()
+            .to_string();
+        debug!(
+            "received application metadata update proposal for {}",
+            circuit_id
+        );
+
+        self.propose_existing_circuit_change(&circuit_id, payload, message_sender)
+    }
+
+    fn propose_existing_circuit_change(
+        &mut self,
+        circuit_id: &str,
+        payload: CircuitManagementPayload,
+        message_sender: String,
+    ) -> Result<(), ServiceError> {
+        let circuit = self
+            .get_circuit_proto(circuit_id)
+            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+        self.check_connected_peers_payload_vote(circuit.get_members(), payload, message_sender)
+    }
+
+    pub fn send_protocol_request(&mut self, node_id: &str) -> Result<(), ServiceError> {
+        if self
+            .service_protocols
+            .get(&admin_service_id(node_id))
+            .is_none()
+        {
+            // we will always have the network sender at this point
+            if let Some(ref network_sender) = self.network_sender {
+                debug!(
+                    "Sending service protocol request to {}",
+                    admin_service_id(node_id)
+                );
+                let mut request = ServiceProtocolVersionRequest::new();
+                request.set_protocol_min(ADMIN_SERVICE_PROTOCOL_MIN);
+                request.set_protocol_max(ADMIN_PROTOCOL_VERSION);
+                let mut msg = AdminMessage::new();
+                msg.set_message_type(AdminMessage_Type::SERVICE_PROTOCOL_VERSION_REQUEST);
+                msg.set_protocol_request(request);
+
+                let envelope_bytes = msg.write_to_bytes()?;
+                network_sender.send(&admin_service_id(node_id), &envelope_bytes)?;
+            }
+        } else {
+            debug!(
+                "Already agreed on protocol version with {}",
+                admin_service_id(node_id)
+            );
+        }
+        Ok(())
+    }
+
+    fn check_connected_peers_payload_vote(
+        &mut self,
+        members: &[SplinterNode],
+        payload: CircuitManagementPayload,
+        message_sender: String,
+    ) -> Result<(), ServiceError> {
+        let mut missing_protocol_ids = vec![];
+        let mut pending_members = vec![];
+        for node in members {
+            if self.node_id() != node.get_node_id()
+                && self
+                    .service_protocols
+                    .get(&admin_service_id(node.get_node_id()))
+                    .is_none()
+            {
+                self.send_protocol_request(node.get_node_id())?;
+                missing_protocol_ids.push(admin_service_id(node.get_node_id()))
+            }
+            pending_members.push(node.get_node_id().to_string());
+        }
 
         if missing_protocol_ids.is_empty() {
             self.pending_circuit_payloads.push_back(payload);
@@ -867,6 +1425,100 @@ impl AdminServiceShared {
 
                 self.propose_vote(payload, "local".to_string())
             }
+            CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_CANCEL => {
+                let cancel = payload.get_circuit_proposal_cancel();
+                let circuit_proposal = self
+                    .get_proposal(cancel.get_circuit_id())
+                    .map_err(|err| {
+                        ServiceError::UnableToHandleMessage(Box::new(
+                            AdminSharedError::ValidationFailed(format!(
+                                "error occurred when trying to get proposal {}",
+                                err
+                            )),
+                        ))
+                    })?
+                    .ok_or_else(|| {
+                        ServiceError::UnableToHandleMessage(Box::new(
+                            AdminSharedError::ValidationFailed(format!(
+                                "Received cancellation of a proposal that does not exist: \
+                                 circuit id {}",
+                                cancel.circuit_id
+                            )),
+                        ))
+                    })?;
+
+                self.validate_cancel_proposal(
+                    cancel,
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                    &circuit_proposal,
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.propose_cancel(payload, "local".to_string())
+            }
+            CircuitManagementPayload_Action::CIRCUIT_DESTROY_REQUEST => {
+                let signer_public_key = header.get_requester();
+                let requester_node_id = header.get_requester_node_id();
+                self.validate_destroy_circuit(
+                    payload.get_circuit_destroy_request().get_circuit_id(),
+                    signer_public_key,
+                    requester_node_id,
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.propose_destroy(payload, "local".to_string())
+            }
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ROSTER_REQUEST => {
+                self.validate_update_roster(
+                    payload.get_circuit_update_roster_request(),
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.propose_update_roster(payload, "local".to_string())
+            }
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_ADD_NODE => {
+                self.validate_add_node(
+                    payload.get_circuit_update_add_node(),
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.propose_update_members(payload, "local".to_string())
+            }
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_REMOVE_NODE => {
+                self.validate_remove_node(
+                    payload.get_circuit_update_remove_node(),
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.propose_update_members(payload, "local".to_string())
+            }
+            CircuitManagementPayload_Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST => {
+                self.validate_update_application_metadata(
+                    payload.get_circuit_update_application_metadata_request(),
+                    header.get_requester(),
+                    header.get_requester_node_id(),
+                )
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.propose_update_application_metadata(payload, "local".to_string())
+            }
+            CircuitManagementPayload_Action::CIRCUIT_ABANDON => {
+                let signer_public_key = header.get_requester();
+                let requester_node_id = header.get_requester_node_id();
+                let circuit_id = payload.get_circuit_abandon().get_circuit_id();
+                self.validate_abandon_circuit(circuit_id, signer_public_key, requester_node_id)
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+
+                self.abandon_circuit(circuit_id, signer_public_key.to_vec())
+                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
+            }
             CircuitManagementPayload_Action::ACTION_UNSET => {
                 Err(ServiceError::UnableToHandleMessage(Box::new(
                     AdminSharedError::ValidationFailed(String::from("No action specified")),
@@ -889,17 +1541,54 @@ impl AdminServiceShared {
         &mut self,
         proposal: Proposal,
         payload: CircuitManagementPayload,
+        joining_circuit: Option<Circuit>,
+        proposal_expires_at: u64,
         message_sender: String,
     ) -> Result<(), ServiceError> {
+        self.proposal_expirations
+            .insert(proposal.id.clone(), proposal_expires_at);
+
+        let members = if payload.has_circuit_update_add_node() {
+            let add_node_request = payload.get_circuit_update_add_node();
+            match joining_circuit {
+                // this node is being added, so it must peer with all of the circuit's members
+                Some(circuit)
+                    if add_node_request.get_node().get_node_id() == self.node_id
+                        && circuit.get_circuit_id() == add_node_request.get_circuit_id() =>
+                {
+                    let members = circuit.get_members().to_vec();
+                    self.joining_circuits
+                        .insert(circuit.get_circuit_id().to_string(), circuit);
+                    members
+                }
+                // the current members only need to peer with the node being added
+                _ => match self
+                    .splinter_state
+                    .circuit(add_node_request.get_circuit_id())
+                {
+                    Ok(Some(circuit))
+                        if !circuit
+                            .members()
+                            .contains(add_node_request.get_node().get_node_id()) =>
+                    {
+                        vec![add_node_request.get_node().clone()]
+                    }
+                    _ => vec![],
+                },
+            }
+        } else {
+            payload
+                .get_circuit_create_request()
+                .get_circuit()
+                .get_members()
+                .to_vec()
+        };
+
         let mut missing_protocol_ids = vec![];
         let mut pending_peers = vec![];
         let mut added_peers: Vec<String> = vec![];
         let mut pending_members = vec![];
-        for node in payload
-            .get_circuit_create_request()
-            .get_circuit()
-            .get_members()
-        {
+        for node in members.iter() {
             if self.node_id() != node.get_node_id() {
                 debug!("Referencing node {:?}", node);
                 let peer_ref = self
@@ -966,15 +1655,27 @@ impl AdminServiceShared {
         circuit_management_type: &str,
     ) -> Result<Events, AdminSharedError> {
         let events = self
-            .event_mailbox
-            .iter_since(*since_timestamp)
-            .map_err(|err| AdminSharedError::UnableToAddSubscriber(err.to_string()))?;
+            .event_store
+            .list_events_by_management_type_since_time(circuit_management_type, since_timestamp)
+            .map_err(AdminSharedError::from)?;
+
+        Ok(Events {
+            inner: Box::new(events),
+        })
+    }
+
+    pub fn get_events_since_id(
+        &self,
+        since_event_id: i64,
+        circuit_management_type: &str,
+    ) -> Result<Events, AdminSharedError> {
+        let events = self
+            .event_store
+            .list_events_by_management_type_since(circuit_management_type, since_event_id)
+            .map_err(AdminSharedError::from)?;
 
-        let circuit_management_type = circuit_management_type.to_string();
         Ok(Events {
-            inner: Box::new(events.filter(move |(_, evt)| {
-                evt.proposal().circuit.circuit_management_type == circuit_management_type
-            })),
+            inner: Box::new(events),
         })
     }
 
@@ -994,8 +1695,8 @@ impl AdminServiceShared {
         circuit_management_type: &str,
         event: messages::AdminServiceEvent,
     ) {
-        let (ts, event) = match self.event_mailbox.add(event) {
-            Ok((ts, event)) => (ts, event),
+        let event_record = match self.event_store.add_event(event) {
+            Ok(event_record) => event_record,
             Err(err) => {
                 error!("Unable to store admin event: {}", err);
                 return;
@@ -1003,7 +1704,7 @@ impl AdminServiceShared {
         };
 
         self.event_subscribers
-            .broadcast_by_type(&circuit_management_type, &event, &ts);
+            .broadcast_by_type(&circuit_management_type, &event_record);
     }
 
     pub fn remove_all_event_subscribers(&mut self) {
@@ -1156,9 +1857,26 @@ impl AdminServiceShared {
 
         self.pending_protocol_payloads = waiting;
 
+        let node_id = service_id.trim_start_matches("admin::").to_string();
+        let joined_circuits = self
+            .pending_circuit_joins
+            .remove(&node_id)
+            .unwrap_or_default();
+
         if protocol == 0 {
+            for circuit in joined_circuits {
+                warn!(
+                    "Unable to send circuit {} to new member {}, due to protocol mismatch",
+                    circuit.get_circuit_id(),
+                    node_id
+                );
+            }
+
             // if no agreed protocol, remove all peer refs for proposals
             for pending_payload in ready {
+                if let PayloadType::Consensus(id, _) = &pending_payload.payload_type {
+                    self.proposal_expirations.remove(id);
+                }
                 for peer in pending_payload.members {
                     self.remove_peer_ref(&peer);
                 }
@@ -1167,6 +1885,11 @@ impl AdminServiceShared {
         }
 
         self.service_protocols.insert(service_id.into(), protocol);
+
+        for circuit in joined_circuits {
+            self.send_circuit_join(&node_id, circuit)?;
+        }
+
         for pending_payload in ready {
             match pending_payload.payload_type {
                 PayloadType::Circuit(payload) => self.pending_circuit_payloads.push_back(payload),
@@ -1197,516 +1920,1991 @@ impl AdminServiceShared {
         &self,
         circuit_id: &str,
     ) -> Result<Option<CircuitProposal>, AdminSharedError> {
-        Ok(self.open_proposals.get_proposal(circuit_id)?)
+        self.admin_store
+            .get_proposal(circuit_id)?
+            .map(|proposal| Ok(messages::CircuitProposal::from(&proposal).into_proto()?))
+            .transpose()
     }
 
-    pub fn get_proposals(&self) -> BTreeMap<String, messages::CircuitProposal> {
-        self.open_proposals.get_proposals()
+    pub fn get_proposals(
+        &self,
+    ) -> Result<BTreeMap<String, messages::CircuitProposal>, AdminSharedError> {
+        Ok(self
+            .admin_store
+            .list_proposals(&[])?
+            .map(|proposal| {
+                (
+                    proposal.circuit_id().to_string(),
+                    messages::CircuitProposal::from(&proposal),
+                )
+            })
+            .collect())
     }
 
     pub fn remove_proposal(
         &mut self,
         circuit_id: &str,
     ) -> Result<Option<CircuitProposal>, AdminSharedError> {
-        Ok(self.open_proposals.remove_proposal(circuit_id)?)
+        let proposal = self.get_proposal(circuit_id)?;
+        if proposal.is_some() {
+            self.admin_store.remove_proposal(circuit_id)?;
+        }
+
+        Ok(proposal)
     }
 
+    /// Adds the proposal to the admin store, or updates it if the proposal is already stored.
+    ///
+    /// A new proposal expires at the expiration time agreed upon with the other members, if it
+    /// has one; an updated proposal keeps the expiration time it was stored with.
     pub fn add_proposal(
         &mut self,
         circuit_proposal: CircuitProposal,
     ) -> Result<Option<CircuitProposal>, AdminSharedError> {
-        Ok(self.open_proposals.add_proposal(circuit_proposal)?)
-    }
-
-    /// Add a circuit definition as an uninitialized circuit. If all members are ready, initialize
-    /// services.
-    fn add_uninitialized_circuit(
-        &mut self,
-        circuit: CircuitProposal,
-    ) -> Result<(), AdminSharedError> {
-        let circuit_id = circuit.get_circuit_id().to_string();
-
-        // If uninitialized circuit already exists, add the circuit definition; if not, create the
-        // uninitialized circuit.
-        match self.uninitialized_circuits.get_mut(&circuit_id) {
-            Some(uninit_circuit) => uninit_circuit.circuit = Some(circuit),
+        let previous_proposal = self
+            .admin_store
+            .get_proposal(circuit_proposal.get_circuit_id())?;
+        let expires_at = circuit_proposal.get_expires_at();
+        let builder =
+            StoreProposal::try_from(&messages::CircuitProposal::from_proto(circuit_proposal)?)?
+                .builder();
+
+        match previous_proposal {
+            Some(previous_proposal) => {
+                let builder = match previous_proposal.expires_at() {
+                    Some(expires_at) => builder.with_expires_at(expires_at),
+                    None => builder,
+                };
+                self.admin_store.update_proposal(builder.build()?)?;
+
+                Ok(Some(
+                    messages::CircuitProposal::from(&previous_proposal).into_proto()?,
+                ))
+            }
             None => {
-                self.uninitialized_circuits.insert(
-                    circuit_id.to_string(),
-                    UninitializedCircuit {
-                        circuit: Some(circuit),
-                        ready_members: HashSet::new(),
-                    },
-                );
+                let builder = match expires_at {
+                    0 => builder,
+                    secs => builder.with_expires_at(UNIX_EPOCH + Duration::from_secs(secs)),
+                };
+                self.admin_store.add_proposal(builder.build()?)?;
+
+                Ok(None)
             }
         }
+    }
 
-        // Add self as ready
-        self.uninitialized_circuits
-            .get_mut(&circuit_id)
-            .expect("Uninitialized circuit not set")
-            .ready_members
-            .insert(self.node_id.clone());
+    /// Removes the proposals whose time-to-live has elapsed from the admin store, and notifies
+    /// subscribers that they have expired. A proposal that is currently being agreed upon is
+    /// left until the change has been committed or rolled back.
+    pub fn expire_proposals(&mut self) -> Result<(), AdminSharedError> {
+        let now = SystemTime::now();
+        let pending_circuit_id = self
+            .pending_changes
+            .as_ref()
+            .map(|context| context.circuit_proposal.get_circuit_id().to_string());
+        let expired_proposals = self
+            .admin_store
+            .list_proposals(&[])?
+            .filter(|proposal| {
+                proposal.is_expired(now)
+                    && Some(proposal.circuit_id()) != pending_circuit_id.as_deref()
+            })
+            .collect::<Vec<_>>();
 
-        self.initialize_services_if_members_ready(&circuit_id)
+        for proposal in expired_proposals {
+            self.admin_store.remove_proposal(proposal.circuit_id())?;
+
+            let members = proposal
+                .circuit()
+                .members()
+                .iter()
+                .map(|member| member.node_id().to_string())
+                .collect::<Vec<_>>();
+            self.remove_proposed_peer_refs(proposal.circuit_id(), &members)?;
+
+            info!("circuit proposal for {} has expired", proposal.circuit_id());
+            let event = messages::AdminServiceEvent::ProposalExpired(
+                messages::CircuitProposal::from(&proposal),
+            );
+            self.send_event(proposal.circuit().circuit_management_type(), event);
+        }
+
+        Ok(())
     }
 
-    /// Mark member node as ready to initialize services on the given circuit. If all members are
-    /// now ready, initialize services.
-    pub fn add_ready_member(
+    /// Release the peer references taken for the proposed members of a circuit, once the
+    /// proposal will no longer be accepted. The current members of an existing circuit are still
+    /// needed by the circuit.
+    fn remove_proposed_peer_refs(
         &mut self,
         circuit_id: &str,
-        member_node_id: String,
+        proposed_members: &[String],
     ) -> Result<(), AdminSharedError> {
-        // If uninitialized circuit does not already exist, create it
-        if self.uninitialized_circuits.get(circuit_id).is_none() {
-            self.uninitialized_circuits.insert(
-                circuit_id.to_string(),
-                UninitializedCircuit {
-                    circuit: None,
-                    ready_members: HashSet::new(),
-                },
-            );
+        let current_members = self
+            .splinter_state
+            .circuit(circuit_id)?
+            .map(|circuit| circuit.members().to_vec())
+            .unwrap_or_default();
+
+        for node_id in proposed_members {
+            if node_id != &self.node_id && !current_members.contains(node_id) {
+                self.remove_peer_ref(node_id);
+            }
         }
 
-        self.uninitialized_circuits
-            .get_mut(circuit_id)
-            .expect("Uninitialized circuit not set")
-            .ready_members
-            .insert(member_node_id);
-
-        self.initialize_services_if_members_ready(circuit_id)
+        Ok(())
     }
 
-    /// If all members of an uninitialized circuit are ready, initialize services. Also send
-    /// CircuitReady notification to application authorization handler.
-    fn initialize_services_if_members_ready(
+    /// Remove a proposal that has been cancelled by its requester, and notify subscribers of the
+    /// cancellation.
+    fn cancel_proposal(
         &mut self,
-        circuit_id: &str,
+        circuit_proposal: CircuitProposal,
+        signer_public_key: Vec<u8>,
     ) -> Result<(), AdminSharedError> {
-        let ready = {
-            if let Some(uninitialized_circuit) = self.uninitialized_circuits.get(circuit_id) {
-                if let Some(ref circuit_proposal) = uninitialized_circuit.circuit {
-                    let all_members = HashSet::from_iter(
-                        circuit_proposal
-                            .get_circuit_proposal()
-                            .members
-                            .iter()
-                            .map(|node| node.node_id.clone()),
-                    );
-                    all_members.is_subset(&uninitialized_circuit.ready_members)
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        };
-
-        if ready {
-            let circuit_proposal = self
-                .uninitialized_circuits
-                .remove(circuit_id)
-                .expect("Uninitialized circuit not set")
-                .circuit
-                .expect("Uninitialized circuit's circuit proposal not set");
-            self.initialize_services(circuit_proposal.get_circuit_proposal())?;
+        let circuit_id = circuit_proposal.get_circuit_id().to_string();
+        let mgmt_type = circuit_proposal
+            .get_circuit_proposal()
+            .get_circuit_management_type()
+            .to_string();
 
-            let mgmt_type = circuit_proposal
+        if let Some(proposal) = self.remove_proposal(&circuit_id)? {
+            let members = proposal
                 .get_circuit_proposal()
-                .circuit_management_type
-                .clone();
-            let event = messages::AdminServiceEvent::CircuitReady(
-                messages::CircuitProposal::from_proto(circuit_proposal)?,
-            );
-            self.send_event(&mgmt_type, event);
+                .get_members()
+                .iter()
+                .map(|member| member.get_node_id().to_string())
+                .collect::<Vec<_>>();
+            self.remove_proposed_peer_refs(&circuit_id, &members)?;
         }
 
+        let circuit_proposal_proto = messages::CircuitProposal::from_proto(circuit_proposal)
+            .map_err(AdminSharedError::InvalidMessageFormat)?;
+        let event = messages::AdminServiceEvent::ProposalCancelled((
+            circuit_proposal_proto,
+            signer_public_key,
+        ));
+        self.send_event(&mgmt_type, event);
+
+        info!("circuit proposal for {} has been cancelled", circuit_id);
         Ok(())
     }
 
-    fn validate_create_circuit(
-        &self,
-        circuit: &Circuit,
-        signer_public_key: &[u8],
-        requester_node_id: &str,
+    /// Destroy a circuit whose destroy proposal has been accepted by all members. The services
+    /// this node runs on the circuit are shut down, the circuit is removed from splinter state and
+    /// the peer references held on behalf of the circuit are released.
+    fn destroy_circuit(
+        &mut self,
+        circuit_proposal: CircuitProposal,
+        signer_public_key: Vec<u8>,
     ) -> Result<(), AdminSharedError> {
-        if requester_node_id.is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "requester_node_id is empty".to_string(),
-            ));
-        }
+        let circuit_id = circuit_proposal.get_circuit_id().to_string();
+        let circuit = circuit_proposal.get_circuit_proposal();
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+
+        // remove approved proposal
+        self.remove_proposal(&circuit_id)?;
+
+        let circuit_proposal_proto =
+            messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                .map_err(AdminSharedError::InvalidMessageFormat)?;
+        let event = messages::AdminServiceEvent::ProposalAccepted((
+            circuit_proposal_proto.clone(),
+            signer_public_key,
+        ));
+        self.send_event(&mgmt_type, event);
 
-        self.validate_key(signer_public_key)?;
+        self.shutdown_services(&circuit_id)?;
+        self.remove_circuit_from_splinter_state(circuit)?;
+        #[cfg(feature = "routing-table")]
+        self.remove_routing_table_circuit(&circuit_id)?;
 
-        if !self
-            .key_verifier
-            .is_permitted(requester_node_id, signer_public_key)?
-        {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "{} is not registered for the requester node {}",
-                to_hex(signer_public_key),
-                requester_node_id,
-            )));
+        for member in circuit.get_members() {
+            self.remove_peer_ref(member.get_node_id());
         }
 
-        self.key_permission_manager
-            .is_permitted(signer_public_key, PROPOSER_ROLE)
-            .map_err(|_| {
-                AdminSharedError::ValidationFailed(format!(
-                    "{} is not permitted to vote for node {}",
-                    to_hex(signer_public_key),
-                    requester_node_id
-                ))
-            })?;
+        let event = messages::AdminServiceEvent::CircuitDestroyed(circuit_proposal_proto);
+        self.send_event(&mgmt_type, event);
 
-        if self.has_proposal(circuit.get_circuit_id()) {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "Ignoring duplicate create proposal of circuit {}",
-                circuit.get_circuit_id()
-            )));
-        }
+        info!("circuit {} has been destroyed", circuit_id);
+        Ok(())
+    }
 
-        if self.splinter_state.has_circuit(circuit.get_circuit_id())? {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "Circuit with circuit id {} already exists",
-                circuit.get_circuit_id()
-            )));
-        }
+    /// Apply an application metadata update whose proposal has been accepted by all members. The
+    /// application authorization handlers are sent both the previous and the new values.
+    fn update_circuit_application_metadata(
+        &mut self,
+        circuit_proposal: CircuitProposal,
+        signer_public_key: Vec<u8>,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit_proposal.get_circuit_id().to_string();
+        let circuit = circuit_proposal.get_circuit_proposal();
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
 
-        self.validate_circuit(circuit)?;
+        let previous_circuit = self.get_circuit_proto(&circuit_id)?;
+
+        // remove approved proposal
+        self.remove_proposal(&circuit_id)?;
+
+        let circuit_proposal_proto =
+            messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                .map_err(AdminSharedError::InvalidMessageFormat)?;
+        let event = messages::AdminServiceEvent::ProposalAccepted((
+            circuit_proposal_proto.clone(),
+            signer_public_key,
+        ));
+        self.send_event(&mgmt_type, event);
+
+        self.update_splinter_state(circuit)?;
+
+        let update = messages::ApplicationMetadataUpdate {
+            previous_application_metadata: previous_circuit.get_application_metadata().to_vec(),
+            previous_comments: previous_circuit.get_comments().to_string(),
+            application_metadata: circuit.get_application_metadata().to_vec(),
+            comments: circuit.get_comments().to_string(),
+        };
+        let event = messages::AdminServiceEvent::ApplicationMetadataUpdated((
+            circuit_proposal_proto,
+            update,
+        ));
+        self.send_event(&mgmt_type, event);
+
+        info!(
+            "application metadata of circuit {} has been updated",
+            circuit_id
+        );
         Ok(())
     }
 
-    fn validate_key(&self, public_key: &[u8]) -> Result<(), AdminSharedError> {
-        if public_key.len() != 33 {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "{} is not a valid public key: invalid length",
-                to_hex(public_key)
-            )));
+    /// Apply a roster update whose proposal has been accepted by all members. Services added to
+    /// the roster that this node is allowed to run are started, and removed services are stopped
+    /// and removed from the service directory.
+    fn update_circuit_roster(
+        &mut self,
+        circuit_proposal: CircuitProposal,
+        signer_public_key: Vec<u8>,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit_proposal.get_circuit_id().to_string();
+        let circuit = circuit_proposal.get_circuit_proposal();
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+
+        let current_circuit = self.get_circuit_proto(&circuit_id)?;
+        let removed_services = current_circuit
+            .get_roster()
+            .iter()
+            .filter(|service| {
+                !circuit
+                    .get_roster()
+                    .iter()
+                    .any(|new_service| new_service.get_service_id() == service.get_service_id())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let added_services = circuit
+            .get_roster()
+            .iter()
+            .filter(|service| {
+                !current_circuit
+                    .get_roster()
+                    .iter()
+                    .any(|old_service| old_service.get_service_id() == service.get_service_id())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // remove approved proposal
+        self.remove_proposal(&circuit_id)?;
+
+        let circuit_proposal_proto =
+            messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                .map_err(AdminSharedError::InvalidMessageFormat)?;
+        let event = messages::AdminServiceEvent::ProposalAccepted((
+            circuit_proposal_proto,
+            signer_public_key,
+        ));
+        self.send_event(&mgmt_type, event);
+
+        let removed_ids = removed_services
+            .iter()
+            .map(|service| service.get_service_id().to_string())
+            .collect::<Vec<_>>();
+        self.shutdown_matching_services(&circuit_id, |service_definition| {
+            removed_ids.contains(&service_definition.service_id)
+        })?;
+        for service_id in removed_ids.iter() {
+            let unique_id = ServiceId::new(circuit_id.clone(), service_id.to_string());
+            self.splinter_state.remove_service(&unique_id)?;
         }
 
+        self.update_splinter_state(circuit)?;
+
+        #[cfg(feature = "routing-table")]
+        self.update_routing_table_services(&circuit_id, &added_services, &removed_ids)?;
+
+        // only the services added to the roster need to be started
+        let mut added_circuit = circuit.clone();
+        added_circuit.set_roster(RepeatedField::from_vec(added_services));
+        self.initialize_services(&added_circuit)?;
+
+        info!("roster of circuit {} has been updated", circuit_id);
         Ok(())
     }
 
-    fn validate_circuit(&self, circuit: &Circuit) -> Result<(), AdminSharedError> {
-        if circuit.get_authorization_type() == Circuit_AuthorizationType::UNSET_AUTHORIZATION_TYPE {
-            return Err(AdminSharedError::ValidationFailed(
-                "authorization_type cannot be unset".to_string(),
-            ));
-        }
+    /// Add a node to a circuit after the proposal has been accepted by the current members. The new
+    /// node is sent the circuit definition, which it only accepts for the addition it has
+    /// committed.
+    fn add_circuit_member(
+        &mut self,
+        circuit_proposal: CircuitProposal,
+        signer_public_key: Vec<u8>,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit_proposal.get_circuit_id().to_string();
+        let circuit = circuit_proposal.get_circuit_proposal();
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+
+        let current_members = self
+            .splinter_state
+            .circuit(&circuit_id)?
+            .map(|circuit| circuit.members().to_vec())
+            .unwrap_or_default();
+        let new_members = circuit
+            .get_members()
+            .iter()
+            .filter(|member| !current_members.contains(&member.get_node_id().to_string()))
+            .cloned()
+            .collect::<Vec<_>>();
 
-        if circuit.get_persistence() == Circuit_PersistenceType::UNSET_PERSISTENCE_TYPE {
-            return Err(AdminSharedError::ValidationFailed(
-                "persistence_type cannot be unset".to_string(),
-            ));
-        }
+        // remove approved proposal
+        self.remove_proposal(&circuit_id)?;
 
-        if circuit.get_durability() == Circuit_DurabilityType::UNSET_DURABILITY_TYPE {
-            return Err(AdminSharedError::ValidationFailed(
-                "durability_type cannot be unset".to_string(),
-            ));
-        }
+        let circuit_proposal_proto =
+            messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                .map_err(AdminSharedError::InvalidMessageFormat)?;
+        let event = messages::AdminServiceEvent::ProposalAccepted((
+            circuit_proposal_proto,
+            signer_public_key,
+        ));
+        self.send_event(&mgmt_type, event);
 
-        if circuit.get_routes() == Circuit_RouteType::UNSET_ROUTE_TYPE {
-            return Err(AdminSharedError::ValidationFailed(
-                "route_type cannot be unset".to_string(),
-            ));
-        }
+        self.update_splinter_state(circuit)?;
 
-        if circuit.get_circuit_id().is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "circuit_id must be set".to_string(),
-            ));
+        #[cfg(feature = "routing-table")]
+        self.add_routing_table_circuit(circuit)?;
+
+        // the new members were peered with when the addition was proposed
+        for member in new_members {
+            // the circuit can only be sent once the new member's admin service is reachable
+            if self
+                .service_protocols
+                .contains_key(&admin_service_id(member.get_node_id()))
+            {
+                self.send_circuit_join(member.get_node_id(), circuit.clone())?;
+            } else {
+                self.pending_circuit_joins
+                    .entry(member.get_node_id().to_string())
+                    .or_insert_with(Vec::new)
+                    .push(circuit.clone());
+            }
         }
-        if !messages::is_valid_circuit_id(circuit.get_circuit_id()) {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "'{}' is not a valid circuit ID: must be an 11 character string compose of two, 5 \
-                 character base62 strings joined with a '-' (example: abcDE-F0123)",
-                circuit.get_circuit_id(),
-            )));
+
+        info!("members of circuit {} have been updated", circuit_id);
+        Ok(())
+    }
+
+    /// Remove a node from a circuit after the proposal has been accepted by the remaining members.
+    /// The removed node's services are removed from the circuit, its peer reference is released
+    /// and it is notified of the removal so it can tear down its side of the circuit.
+    fn remove_circuit_member(
+        &mut self,
+        circuit_proposal: CircuitProposal,
+        signer_public_key: Vec<u8>,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit_proposal.get_circuit_id().to_string();
+        let circuit = circuit_proposal.get_circuit_proposal();
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+
+        let current_circuit = self.get_circuit_proto(&circuit_id)?;
+        let removed_members = current_circuit
+            .get_members()
+            .iter()
+            .filter(|member| {
+                !circuit
+                    .get_members()
+                    .iter()
+                    .any(|new_member| new_member.get_node_id() == member.get_node_id())
+            })
+            .map(|member| member.get_node_id().to_string())
+            .collect::<Vec<_>>();
+        let removed_ids = current_circuit
+            .get_roster()
+            .iter()
+            .filter(|service| {
+                !circuit
+                    .get_roster()
+                    .iter()
+                    .any(|new_service| new_service.get_service_id() == service.get_service_id())
+            })
+            .map(|service| service.get_service_id().to_string())
+            .collect::<Vec<_>>();
+
+        // remove approved proposal
+        self.remove_proposal(&circuit_id)?;
+
+        let circuit_proposal_proto =
+            messages::CircuitProposal::from_proto(circuit_proposal.clone())
+                .map_err(AdminSharedError::InvalidMessageFormat)?;
+        let event = messages::AdminServiceEvent::ProposalAccepted((
+            circuit_proposal_proto,
+            signer_public_key,
+        ));
+        self.send_event(&mgmt_type, event);
+
+        for service_id in removed_ids.iter() {
+            let unique_id = ServiceId::new(circuit_id.clone(), service_id.to_string());
+            self.splinter_state.remove_service(&unique_id)?;
         }
 
-        if circuit.get_circuit_management_type().is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "circuit_management_type must be set".to_string(),
-            ));
+        self.update_splinter_state(circuit)?;
+
+        #[cfg(feature = "routing-table")]
+        {
+            self.update_routing_table_services(&circuit_id, &[], &removed_ids)?;
+            self.add_routing_table_circuit(circuit)?;
         }
 
-        let mut members: Vec<String> = Vec::new();
-        let mut all_endpoints: Vec<String> = Vec::new();
-        for member in circuit.get_members() {
-            let node_id = member.get_node_id().to_string();
-            if node_id.is_empty() {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Member node id cannot be empty".to_string(),
-                ));
-            } else if members.contains(&node_id) {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Every member must be unique in the circuit.".to_string(),
-                ));
-            } else {
-                members.push(node_id);
+        // send MEMBER_REMOVED message to the removed members' admin services
+        if let Some(ref network_sender) = self.network_sender {
+            for member in removed_members.iter() {
+                let mut member_removed = MemberRemoved::new();
+                member_removed.set_circuit_id(circuit_id.clone());
+                member_removed.set_member_node_id(member.to_string());
+                let mut msg = AdminMessage::new();
+                msg.set_message_type(AdminMessage_Type::MEMBER_REMOVED);
+                msg.set_member_removed(member_removed);
+
+                let envelope_bytes = msg.write_to_bytes().map_err(MarshallingError::from)?;
+                if let Err(err) = network_sender.send(&admin_service_id(member), &envelope_bytes) {
+                    warn!(
+                        "Unable to notify {} of its removal from circuit {}: {}",
+                        member, circuit_id, err
+                    );
+                }
             }
+        }
 
-            let mut endpoints = member.get_endpoints().to_vec();
-            if endpoints.is_empty() {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Member endpoints cannot be empty".to_string(),
-                ));
-            } else if endpoints.iter().any(|endpoint| endpoint.is_empty()) {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Member cannot have an empty endpoint".to_string(),
-                ));
-            } else if endpoints
-                .iter()
-                .any(|endpoint| all_endpoints.contains(endpoint))
+        let circuits = self.splinter_state.circuits()?;
+        for member in removed_members.iter() {
+            self.remove_peer_ref(member);
+            // keep the node if it is still a member of another circuit
+            if !circuits
+                .values()
+                .any(|circuit| circuit.members().contains(member))
             {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Every member endpoint must be unique in the circuit.".to_string(),
-                ));
-            } else {
-                all_endpoints.append(&mut endpoints);
+                self.splinter_state.remove_node(member)?;
             }
         }
 
-        if members.is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "The circuit must have members".to_string(),
-            ));
-        }
+        info!("members of circuit {} have been updated", circuit_id);
+        Ok(())
+    }
 
-        // check this node is in members
-        if !members.contains(&self.node_id) {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "Circuit does not contain this node: {}",
-                self.node_id
-            )));
+    /// Send the circuit definition to a node that has been added to the circuit.
+    fn send_circuit_join(&self, node_id: &str, circuit: Circuit) -> Result<(), AdminSharedError> {
+        if let Some(ref network_sender) = self.network_sender {
+            let mut join_request = CircuitJoinRequest::new();
+            join_request.set_circuit(circuit);
+            let mut msg = AdminMessage::new();
+            msg.set_message_type(AdminMessage_Type::CIRCUIT_JOIN_REQUEST);
+            msg.set_circuit_join_request(join_request);
+
+            let envelope_bytes = msg.write_to_bytes().map_err(MarshallingError::from)?;
+            network_sender.send(&admin_service_id(node_id), &envelope_bytes)?;
         }
 
-        if circuit.get_roster().is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "The circuit must have services".to_string(),
-            ));
-        }
+        Ok(())
+    }
 
-        let mut services: Vec<String> = Vec::new();
-        // check that all services' allowed nodes are in members
-        for service in circuit.get_roster() {
-            if service.get_allowed_nodes().is_empty() {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Service cannot have an empty allowed nodes list".to_string(),
-                ));
+    #[cfg(feature = "routing-table")]
+    fn update_routing_table_services(
+        &mut self,
+        circuit_id: &str,
+        added_services: &[SplinterService],
+        removed_service_ids: &[String],
+    ) -> Result<(), AdminSharedError> {
+        if let Some(ref mut routing_table_writer) = self.routing_table_writer {
+            for service in added_services {
+                routing_table_writer
+                    .add_service(
+                        RoutingServiceId::new(
+                            circuit_id.to_string(),
+                            service.get_service_id().to_string(),
+                        ),
+                        RoutingService::new(
+                            service.get_service_id().to_string(),
+                            service.get_service_type().to_string(),
+                            service.get_allowed_nodes().to_vec(),
+                            service
+                                .get_arguments()
+                                .iter()
+                                .map(|argument| {
+                                    (
+                                        argument.get_key().to_string(),
+                                        argument.get_value().to_string(),
+                                    )
+                                })
+                                .collect(),
+                        ),
+                    )
+                    .map_err(|err| AdminSharedError::CommitError(err.to_string()))?;
             }
 
-            if service.get_allowed_nodes().len() > 1 {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Only one allowed node for a service is supported".to_string(),
-                ));
+            for service_id in removed_service_ids {
+                routing_table_writer
+                    .remove_service(&RoutingServiceId::new(
+                        circuit_id.to_string(),
+                        service_id.to_string(),
+                    ))
+                    .map_err(|err| AdminSharedError::CommitError(err.to_string()))?;
             }
+        }
 
-            for node in service.get_allowed_nodes() {
-                if !members.contains(node) {
-                    return Err(AdminSharedError::ValidationFailed(format!(
-                        "Service cannot have an allowed node that is not in members: {}",
-                        node
-                    )));
-                }
-            }
+        Ok(())
+    }
 
-            let service_id = service.get_service_id().to_string();
-            if service_id.is_empty() {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Service id cannot be empty".to_string(),
-                ));
-            } else if !messages::is_valid_service_id(&service_id) {
-                return Err(AdminSharedError::ValidationFailed(format!(
-                    "'{}' is not a valid service ID: must be a 4 character base62 string",
-                    service_id,
-                )));
-            } else if services.contains(&service_id) {
-                return Err(AdminSharedError::ValidationFailed(
-                    "Every service must be unique in the circuit.".to_string(),
-                ));
-            } else {
-                services.push(service_id)
-            }
+    /// Add a circuit to the routing table, replacing its previous definition.
+    #[cfg(feature = "routing-table")]
+    fn add_routing_table_circuit(&mut self, circuit: &Circuit) -> Result<(), AdminSharedError> {
+        if let Some(ref mut routing_table_writer) = self.routing_table_writer {
+            let roster = circuit
+                .get_roster()
+                .iter()
+                .map(|service| {
+                    RoutingService::new(
+                        service.get_service_id().to_string(),
+                        service.get_service_type().to_string(),
+                        service.get_allowed_nodes().to_vec(),
+                        service
+                            .get_arguments()
+                            .iter()
+                            .map(|argument| {
+                                (
+                                    argument.get_key().to_string(),
+                                    argument.get_value().to_string(),
+                                )
+                            })
+                            .collect(),
+                    )
+                })
+                .collect();
+            let members = circuit
+                .get_members()
+                .iter()
+                .map(|member| member.get_node_id().to_string())
+                .collect();
+            let nodes = circuit
+                .get_members()
+                .iter()
+                .map(|member| {
+                    RoutingCircuitNode::new(
+                        member.get_node_id().to_string(),
+                        member.get_endpoints().to_vec(),
+                    )
+                })
+                .collect();
 
-            #[cfg(feature = "service-arg-validation")]
-            {
-                self.validate_service_args(&service)?;
-            }
+            routing_table_writer
+                .add_circuit(
+                    circuit.get_circuit_id().to_string(),
+                    RoutingCircuit::new(circuit.get_circuit_id().to_string(), roster, members),
+                    nodes,
+                )
+                .map_err(|err| AdminSharedError::CommitError(err.to_string()))?;
         }
 
-        if circuit.get_circuit_management_type().is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "The circuit must have a mangement type".to_string(),
-            ));
+        Ok(())
+    }
+
+    #[cfg(feature = "routing-table")]
+    fn remove_routing_table_circuit(&mut self, circuit_id: &str) -> Result<(), AdminSharedError> {
+        if let Some(ref mut routing_table_writer) = self.routing_table_writer {
+            routing_table_writer
+                .remove_circuit(circuit_id)
+                .map_err(|err| AdminSharedError::CommitError(err.to_string()))?;
         }
 
         Ok(())
     }
 
-    #[cfg(feature = "service-arg-validation")]
-    fn validate_service_args(&self, service: &SplinterService) -> Result<(), AdminSharedError> {
-        if let Some(validator) = self.service_arg_validators.get(service.get_service_type()) {
-            let args: HashMap<String, String> = service
-                .get_arguments()
-                .iter()
-                .map(|arg| (arg.get_key().into(), arg.get_value().into()))
-                .collect();
+    /// Leave a circuit without the agreement of the other members. The services this node runs on
+    /// the circuit are shut down, the circuit is marked as abandoned and is no longer routed, and
+    /// the other members are notified of the departure.
+    fn abandon_circuit(
+        &mut self,
+        circuit_id: &str,
+        signer_public_key: Vec<u8>,
+    ) -> Result<(), AdminSharedError> {
+        let circuit = self.get_circuit_proto(circuit_id)?;
 
-            validator
-                .validate(&args)
-                .map_err(|err| AdminSharedError::ValidationFailed(err.to_string()))
-        } else {
-            Ok(())
+        // any pending change to the circuit can no longer be voted on by this node
+        if self.has_proposal(circuit_id) {
+            self.remove_proposal(circuit_id)?;
         }
-    }
 
-    fn validate_circuit_vote(
-        &self,
-        proposal_vote: &CircuitProposalVote,
-        signer_public_key: &[u8],
-        circuit_proposal: &CircuitProposal,
-        node_id: &str,
-    ) -> Result<(), AdminSharedError> {
-        let circuit_hash = proposal_vote.get_circuit_hash();
+        self.shutdown_services(circuit_id)?;
 
-        self.validate_key(signer_public_key)?;
+        for service in circuit.get_roster() {
+            let unique_id =
+                ServiceId::new(circuit_id.to_string(), service.get_service_id().to_string());
+            self.splinter_state.remove_service(&unique_id)?;
+        }
 
-        if !self.key_verifier.is_permitted(node_id, signer_public_key)? {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "{} is not registered for voting node {}",
-                to_hex(signer_public_key),
-                node_id,
-            )));
+        if let Some(state_circuit) = self.splinter_state.circuit(circuit_id)? {
+            self.splinter_state
+                .add_circuit(circuit_id.to_string(), state_circuit.into_abandoned())?;
+            self.write_circuit_to_store(circuit_id)?;
         }
+        #[cfg(feature = "routing-table")]
+        self.remove_routing_table_circuit(circuit_id)?;
 
-        if circuit_proposal.get_requester_node_id() == node_id {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "Received vote from requester node: {}",
-                to_hex(circuit_proposal.get_requester())
-            )));
+        // send MEMBER_ABANDONED message to all other members' admin services
+        if let Some(ref network_sender) = self.network_sender {
+            let mut member_abandoned = MemberAbandoned::new();
+            member_abandoned.set_circuit_id(circuit_id.to_string());
+            member_abandoned.set_member_node_id(self.node_id.clone());
+            let mut msg = AdminMessage::new();
+            msg.set_message_type(AdminMessage_Type::MEMBER_ABANDONED);
+            msg.set_member_abandoned(member_abandoned);
+
+            let envelope_bytes = msg.write_to_bytes().map_err(MarshallingError::from)?;
+            for member in circuit.get_members() {
+                if member.get_node_id() != self.node_id {
+                    if let Err(err) = network_sender
+                        .send(&admin_service_id(member.get_node_id()), &envelope_bytes)
+                    {
+                        // the member may be unreachable, which is often why the circuit is being
+                        // abandoned in the first place
+                        warn!(
+                            "Unable to notify {} of abandoning circuit {}: {}",
+                            member.get_node_id(),
+                            circuit_id,
+                            err
+                        );
+                    }
+                }
+            }
         }
 
-        let voted_nodes: Vec<String> = circuit_proposal
-            .get_votes()
-            .iter()
-            .map(|vote| vote.get_voter_node_id().to_string())
-            .collect();
+        for member in circuit.get_members() {
+            if member.get_node_id() != self.node_id {
+                self.remove_peer_ref(member.get_node_id());
+            }
+        }
 
-        if voted_nodes.iter().any(|node| *node == node_id) {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "Received duplicate vote from {} for {}",
-                node_id, proposal_vote.circuit_id
-            )));
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+        let event = messages::AdminServiceEvent::CircuitAbandoned(self.make_event_proposal(
+            CircuitProposal_ProposalType::ABANDON,
+            circuit,
+            signer_public_key,
+            self.node_id.clone(),
+        )?);
+        self.send_event(&mgmt_type, event);
+
+        info!("circuit {} has been abandoned", circuit_id);
+        Ok(())
+    }
+
+    /// Handle another member leaving a circuit. The member's services are removed from the service
+    /// directory and subscribers are notified of the departure.
+    pub fn handle_member_abandoned(
+        &mut self,
+        circuit_id: &str,
+        member_node_id: &str,
+        sender: &str,
+    ) -> Result<(), AdminSharedError> {
+        // only the abandoning member may announce its departure
+        if sender != admin_service_id(member_node_id) {
+            warn!(
+                "Ignoring abandon notification from {} on behalf of {} for circuit {}",
+                sender, member_node_id, circuit_id
+            );
+            return Ok(());
         }
 
-        self.key_permission_manager
-            .is_permitted(signer_public_key, VOTER_ROLE)
-            .map_err(|_| {
-                AdminSharedError::ValidationFailed(format!(
-                    "{} is not permitted to vote for node {}",
-                    to_hex(signer_public_key),
-                    node_id
-                ))
-            })?;
+        let circuit = match self.splinter_state.circuit(circuit_id)? {
+            Some(circuit) if circuit.members().contains(member_node_id) => {
+                self.get_circuit_proto(circuit_id)?
+            }
+            _ => {
+                warn!(
+                    "Received abandon notification from {} for unknown circuit {}",
+                    member_node_id, circuit_id
+                );
+                return Ok(());
+            }
+        };
 
-        // validate hash of circuit
-        if circuit_proposal.get_circuit_hash() != circuit_hash {
-            return Err(AdminSharedError::ValidationFailed(format!(
-                "Hash of circuit does not match circuit proposal: {}",
-                proposal_vote.circuit_id
-            )));
+        for service in circuit.get_roster() {
+            if service
+                .get_allowed_nodes()
+                .iter()
+                .any(|node_id| node_id == member_node_id)
+            {
+                let unique_id =
+                    ServiceId::new(circuit_id.to_string(), service.get_service_id().to_string());
+                self.splinter_state.remove_service(&unique_id)?;
+            }
         }
 
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+        let event = messages::AdminServiceEvent::CircuitAbandoned(self.make_event_proposal(
+            CircuitProposal_ProposalType::ABANDON,
+            circuit,
+            vec![],
+            member_node_id.to_string(),
+        )?);
+        self.send_event(&mgmt_type, event);
+
+        info!(
+            "member {} has abandoned circuit {}",
+            member_node_id, circuit_id
+        );
         Ok(())
     }
 
-    fn validate_circuit_management_payload(
-        &self,
-        payload: &CircuitManagementPayload,
-        header: &CircuitManagementPayload_Header,
+    /// Handle the messages stored for a member of a durable circuit reaching the circuit's size
+    /// limit. Subscribers are notified that messages for the member are being dropped.
+    ///
+    /// Admin messages are stored under the admin circuit, which has no subscribers of its own, so
+    /// for the admin circuit the subscribers of each pending proposal that includes the member are
+    /// notified instead.
+    pub fn handle_durable_queue_full(
+        &mut self,
+        circuit_id: &str,
+        member_node_id: &str,
+        size_limit: usize,
     ) -> Result<(), AdminSharedError> {
-        // Validate payload signature
-        if payload.get_signature().is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "CircuitManagementPayload signature must be set".to_string(),
-            ));
-        };
+        if circuit_id == ADMIN_CIRCUIT_ID {
+            return self.handle_admin_queue_full(member_node_id, size_limit);
+        }
 
-        // Validate the payload header
-        if payload.get_header().is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "CircuitManagementPayload header must be set".to_string(),
-            ));
-        };
+        if self.splinter_state.circuit(circuit_id)?.is_none() {
+            warn!(
+                "Stored messages for {} on {} have reached the size limit",
+                member_node_id, circuit_id
+            );
+            return Ok(());
+        }
 
-        // Validate the header, requester field is set
-        if header.get_requester().is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "CircuitManagementPayload must have a requester".to_string(),
-            ));
-        };
+        let circuit = self.get_circuit_proto(circuit_id)?;
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+        let event = messages::AdminServiceEvent::DurableQueueFull((
+            self.make_event_proposal(
+                CircuitProposal_ProposalType::CREATE,
+                circuit,
+                vec![],
+                self.node_id.clone(),
+            )?,
+            messages::DurableQueueOverflow {
+                node_id: member_node_id.to_string(),
+                size_limit,
+            },
+        ));
+        self.send_event(&mgmt_type, event);
 
-        self.validate_key(header.get_requester())?;
+        warn!(
+            "stored messages for {} on circuit {} have reached the size limit of {} bytes",
+            member_node_id, circuit_id, size_limit
+        );
+        Ok(())
+    }
 
-        // Validate the header, requester_node_id is set
-        if header.get_requester_node_id().is_empty() {
-            return Err(AdminSharedError::ValidationFailed(
-                "CircuitManagementPayload must have a requester node id".to_string(),
+    fn handle_admin_queue_full(
+        &mut self,
+        member_node_id: &str,
+        size_limit: usize,
+    ) -> Result<(), AdminSharedError> {
+        let proposals = self
+            .get_proposals()?
+            .values()
+            .filter(|proposal| {
+                proposal
+                    .circuit
+                    .members
+                    .iter()
+                    .any(|member| member.node_id == member_node_id)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for proposal in proposals {
+            let mgmt_type = proposal.circuit.circuit_management_type.clone();
+            let event = messages::AdminServiceEvent::DurableQueueFull((
+                proposal,
+                messages::DurableQueueOverflow {
+                    node_id: member_node_id.to_string(),
+                    size_limit,
+                },
             ));
-        };
+            self.send_event(&mgmt_type, event);
+        }
 
+        warn!(
+            "stored admin messages for {} have reached the size limit of {} bytes",
+            member_node_id, size_limit
+        );
         Ok(())
     }
 
-    fn check_approved(
-        &self,
-        proposal: &CircuitProposal,
-    ) -> Result<CircuitProposalStatus, AdminSharedError> {
-        let mut received_votes = HashSet::new();
-        for vote in proposal.get_votes() {
-            if vote.get_vote() == CircuitProposalVote_Vote::REJECT {
-                return Ok(CircuitProposalStatus::Rejected);
+    /// Join a circuit that this node has been added to by a vote of the circuit's members. The
+    /// circuit definition is sent by the existing members once the addition has been accepted, and
+    /// must match the addition this node has committed.
+    pub fn handle_circuit_join(
+        &mut self,
+        circuit: Circuit,
+        sender: &str,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit.get_circuit_id().to_string();
+        if self.splinter_state.has_circuit(&circuit_id)? {
+            debug!("Circuit {} has already been joined", circuit_id);
+            return Ok(());
+        }
+
+        let is_existing_member = circuit.get_members().iter().any(|member| {
+            member.get_node_id() != self.node_id && admin_service_id(member.get_node_id()) == sender
+        });
+        match self.get_proposal(&circuit_id)? {
+            Some(proposal)
+                if is_existing_member
+                    && proposal.get_proposal_type() == CircuitProposal_ProposalType::ADD_NODE
+                    && Self::is_proposed_circuit(&proposal, circuit).unwrap_or(false) =>
+            {
+                self.join_circuit(proposal)
+            }
+            _ => {
+                warn!(
+                    "Ignoring request from {} to join circuit {}",
+                    sender, circuit_id
+                );
+                Ok(())
             }
-            received_votes.insert(vote.get_voter_node_id().to_string());
         }
+    }
 
-        let mut required_votes = proposal
-            .get_circuit_proposal()
-            .get_members()
-            .to_vec()
-            .iter()
-            .map(|member| member.get_node_id().to_string())
-            .collect::<HashSet<String>>();
+    /// Returns whether the circuit is the one in the stored proposal, once it is in the form the
+    /// admin store keeps circuits in.
+    fn is_proposed_circuit(
+        proposal: &CircuitProposal,
+        circuit: Circuit,
+    ) -> Result<bool, AdminSharedError> {
+        let mut received_proposal = proposal.clone();
+        received_proposal.set_circuit_proposal(circuit);
+        let received_proposal = messages::CircuitProposal::from(&StoreProposal::try_from(
+            &messages::CircuitProposal::from_proto(received_proposal)?,
+        )?)
+        .into_proto()?;
+
+        Ok(received_proposal.get_circuit_proposal() == proposal.get_circuit_proposal())
+    }
 
-        required_votes.remove(proposal.get_requester_node_id());
+    /// Set up a circuit that this node has been added to, once the addition has been accepted.
+    /// The other members were peered with when the addition was proposed.
+    fn join_circuit(&mut self, circuit_proposal: CircuitProposal) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit_proposal.get_circuit_id().to_string();
+        let circuit = circuit_proposal.get_circuit_proposal();
 
-        if required_votes == received_votes {
-            Ok(CircuitProposalStatus::Accepted)
-        } else {
-            Ok(CircuitProposalStatus::Pending)
-        }
-    }
+        self.remove_proposal(&circuit_id)?;
+        self.update_splinter_state(circuit)?;
 
-    /// Initialize all services that this node should run on the created circuit using the service
-    /// orchestrator. This may not include all services if they are not supported locally. It is
-    /// expected that some services will be started externally.
-    pub fn initialize_services(&mut self, circuit: &Circuit) -> Result<(), AdminSharedError> {
-        let orchestrator = self.orchestrator.lock().map_err(|_| {
-            AdminSharedError::ServiceInitializationFailed {
-                context: "ServiceOrchestrator lock poisoned".into(),
-                source: None,
-            }
-        })?;
+        #[cfg(feature = "routing-table")]
+        self.add_routing_table_circuit(circuit)?;
 
-        // Get all services this node is allowed to run
-        let services = circuit
-            .get_roster()
-            .iter()
-            .filter(|service| {
+        self.initialize_services(circuit)?;
+
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+        let event = messages::AdminServiceEvent::CircuitReady(self.make_event_proposal(
+            CircuitProposal_ProposalType::ADD_NODE,
+            circuit.clone(),
+            vec![],
+            circuit_proposal.get_requester_node_id().to_string(),
+        )?);
+        self.send_event(&mgmt_type, event);
+
+        info!("joined circuit {}", circuit_id);
+        Ok(())
+    }
+
+    /// Leave a circuit that this node has been removed from by a vote of the remaining members.
+    pub fn handle_member_removed(
+        &mut self,
+        circuit_id: &str,
+        member_node_id: &str,
+        sender: &str,
+    ) -> Result<(), AdminSharedError> {
+        let sender_node_id = sender.trim_start_matches("admin::");
+        let is_member = match self.splinter_state.circuit(circuit_id)? {
+            Some(circuit) => circuit.members().contains(sender_node_id),
+            None => false,
+        };
+        if !is_member || member_node_id != self.node_id {
+            warn!(
+                "Ignoring removal notification from {} for circuit {}",
+                sender, circuit_id
+            );
+            return Ok(());
+        }
+
+        let circuit = self.get_circuit_proto(circuit_id)?;
+
+        // any pending change to the circuit can no longer be voted on by this node
+        if self.has_proposal(circuit_id) {
+            self.remove_proposal(circuit_id)?;
+        }
+
+        self.shutdown_services(circuit_id)?;
+        self.remove_circuit_from_splinter_state(&circuit)?;
+
+        #[cfg(feature = "routing-table")]
+        self.remove_routing_table_circuit(circuit_id)?;
+
+        for member in circuit.get_members() {
+            if member.get_node_id() != self.node_id {
+                self.remove_peer_ref(member.get_node_id());
+            }
+        }
+
+        let mgmt_type = circuit.get_circuit_management_type().to_string();
+        let event = messages::AdminServiceEvent::CircuitDestroyed(self.make_event_proposal(
+            CircuitProposal_ProposalType::REMOVE_NODE,
+            circuit,
+            vec![],
+            sender_node_id.to_string(),
+        )?);
+        self.send_event(&mgmt_type, event);
+
+        info!("removed from circuit {} by {}", circuit_id, sender);
+        Ok(())
+    }
+
+    /// Build the proposal reported in an event for a circuit change that was not voted on by this
+    /// node.
+    fn make_event_proposal(
+        &self,
+        proposal_type: CircuitProposal_ProposalType,
+        circuit: Circuit,
+        requester: Vec<u8>,
+        requester_node_id: String,
+    ) -> Result<messages::CircuitProposal, AdminSharedError> {
+        let mut circuit_proposal = CircuitProposal::new();
+        circuit_proposal.set_proposal_type(proposal_type);
+        circuit_proposal.set_circuit_id(circuit.get_circuit_id().into());
+        circuit_proposal.set_circuit_hash(sha256(&circuit)?);
+        circuit_proposal.set_circuit_proposal(circuit);
+        circuit_proposal.set_requester(requester);
+        circuit_proposal.set_requester_node_id(requester_node_id);
+
+        messages::CircuitProposal::from_proto(circuit_proposal)
+            .map_err(AdminSharedError::InvalidMessageFormat)
+    }
+
+    /// Add a circuit definition as an uninitialized circuit. If all members are ready, initialize
+    /// services.
+    fn add_uninitialized_circuit(
+        &mut self,
+        circuit: CircuitProposal,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit.get_circuit_id().to_string();
+
+        // If uninitialized circuit already exists, add the circuit definition; if not, create the
+        // uninitialized circuit.
+        match self.uninitialized_circuits.get_mut(&circuit_id) {
+            Some(uninit_circuit) => uninit_circuit.circuit = Some(circuit),
+            None => {
+                self.uninitialized_circuits.insert(
+                    circuit_id.to_string(),
+                    UninitializedCircuit {
+                        circuit: Some(circuit),
+                        ready_members: HashSet::new(),
+                    },
+                );
+            }
+        }
+
+        // Add self as ready
+        self.uninitialized_circuits
+            .get_mut(&circuit_id)
+            .expect("Uninitialized circuit not set")
+            .ready_members
+            .insert(self.node_id.clone());
+
+        self.initialize_services_if_members_ready(&circuit_id)
+    }
+
+    /// Mark member node as ready to initialize services on the given circuit. If all members are
+    /// now ready, initialize services.
+    pub fn add_ready_member(
+        &mut self,
+        circuit_id: &str,
+        member_node_id: String,
+    ) -> Result<(), AdminSharedError> {
+        // If uninitialized circuit does not already exist, create it
+        if self.uninitialized_circuits.get(circuit_id).is_none() {
+            self.uninitialized_circuits.insert(
+                circuit_id.to_string(),
+                UninitializedCircuit {
+                    circuit: None,
+                    ready_members: HashSet::new(),
+                },
+            );
+        }
+
+        self.uninitialized_circuits
+            .get_mut(circuit_id)
+            .expect("Uninitialized circuit not set")
+            .ready_members
+            .insert(member_node_id);
+
+        self.initialize_services_if_members_ready(circuit_id)
+    }
+
+    /// If all members of an uninitialized circuit are ready, initialize services. Also send
+    /// CircuitReady notification to application authorization handler.
+    fn initialize_services_if_members_ready(
+        &mut self,
+        circuit_id: &str,
+    ) -> Result<(), AdminSharedError> {
+        let ready = {
+            if let Some(uninitialized_circuit) = self.uninitialized_circuits.get(circuit_id) {
+                if let Some(ref circuit_proposal) = uninitialized_circuit.circuit {
+                    let all_members = HashSet::from_iter(
+                        circuit_proposal
+                            .get_circuit_proposal()
+                            .members
+                            .iter()
+                            .map(|node| node.node_id.clone()),
+                    );
+                    all_members.is_subset(&uninitialized_circuit.ready_members)
+                } else {
+                    false
+                }
+            } else {
+                false
+            }
+        };
+
+        if ready {
+            let circuit_proposal = self
+                .uninitialized_circuits
+                .remove(circuit_id)
+                .expect("Uninitialized circuit not set")
+                .circuit
+                .expect("Uninitialized circuit's circuit proposal not set");
+            self.initialize_services(circuit_proposal.get_circuit_proposal())?;
+
+            let mgmt_type = circuit_proposal
+                .get_circuit_proposal()
+                .circuit_management_type
+                .clone();
+            let event = messages::AdminServiceEvent::CircuitReady(
+                messages::CircuitProposal::from_proto(circuit_proposal)?,
+            );
+            self.send_event(&mgmt_type, event);
+        }
+
+        Ok(())
+    }
+
+    fn validate_create_circuit(
+        &self,
+        circuit: &Circuit,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<(), AdminSharedError> {
+        if requester_node_id.is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "requester_node_id is empty".to_string(),
+            ));
+        }
+
+        self.validate_key(signer_public_key)?;
+
+        if !self
+            .key_verifier
+            .is_permitted(requester_node_id, signer_public_key)?
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "{} is not registered for the requester node {}",
+                to_hex(signer_public_key),
+                requester_node_id,
+            )));
+        }
+
+        self.key_permission_manager
+            .is_permitted(signer_public_key, PROPOSER_ROLE)
+            .map_err(|_| {
+                AdminSharedError::ValidationFailed(format!(
+                    "{} is not permitted to vote for node {}",
+                    to_hex(signer_public_key),
+                    requester_node_id
+                ))
+            })?;
+
+        if self.has_proposal(circuit.get_circuit_id()) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Ignoring duplicate create proposal of circuit {}",
+                circuit.get_circuit_id()
+            )));
+        }
+
+        if self.splinter_state.has_circuit(circuit.get_circuit_id())? {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Circuit with circuit id {} already exists",
+                circuit.get_circuit_id()
+            )));
+        }
+
+        self.validate_circuit(circuit)?;
+        self.validate_voting_policy(circuit)?;
+        Ok(())
+    }
+
+    /// Validate that the voting policy of a new circuit can be satisfied by its members.
+    fn validate_voting_policy(&self, circuit: &Circuit) -> Result<(), AdminSharedError> {
+        let required_votes = circuit.get_required_votes() as usize;
+        if circuit.get_voting_policy() != Circuit_VotingPolicy::THRESHOLD {
+            if required_votes != 0 {
+                return Err(AdminSharedError::ValidationFailed(
+                    "required_votes may only be set for a threshold voting policy".to_string(),
+                ));
+            }
+            return Ok(());
+        }
+
+        let member_count = circuit.get_members().len();
+        if required_votes == 0 || required_votes > member_count {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "required_votes must be between 1 and the number of members ({}), was {}",
+                member_count, required_votes
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Validate that the requester may propose a change to an existing circuit, returning the
+    /// circuit as it is currently stored.
+    fn validate_circuit_change(
+        &self,
+        circuit_id: &str,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<StateCircuit, AdminSharedError> {
+        self.validate_change_requester(signer_public_key, requester_node_id)?;
+
+        if self.has_proposal(circuit_id) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Circuit {} already has a pending proposal",
+                circuit_id
+            )));
+        }
+
+        let circuit = self.splinter_state.circuit(circuit_id)?.ok_or_else(|| {
+            AdminSharedError::ValidationFailed(format!(
+                "Circuit with circuit id {} does not exist",
+                circuit_id
+            ))
+        })?;
+
+        if !circuit.circuit_status().is_active() {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Circuit {} has been abandoned",
+                circuit_id
+            )));
+        }
+
+        if !circuit.members().contains(requester_node_id) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Requester node {} is not a member of circuit {}",
+                requester_node_id, circuit_id
+            )));
+        }
+
+        Ok(circuit)
+    }
+
+    /// Validate that the requester of a change to a circuit may propose it.
+    fn validate_change_requester(
+        &self,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<(), AdminSharedError> {
+        if requester_node_id.is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "requester_node_id is empty".to_string(),
+            ));
+        }
+
+        self.validate_key(signer_public_key)?;
+
+        if !self
+            .key_verifier
+            .is_permitted(requester_node_id, signer_public_key)?
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "{} is not registered for the requester node {}",
+                to_hex(signer_public_key),
+                requester_node_id,
+            )));
+        }
+
+        self.key_permission_manager
+            .is_permitted(signer_public_key, PROPOSER_ROLE)
+            .map_err(|_| {
+                AdminSharedError::ValidationFailed(format!(
+                    "{} is not permitted to propose for node {}",
+                    to_hex(signer_public_key),
+                    requester_node_id
+                ))
+            })?;
+
+        Ok(())
+    }
+
+    fn validate_destroy_circuit(
+        &self,
+        circuit_id: &str,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<(), AdminSharedError> {
+        self.validate_circuit_change(circuit_id, signer_public_key, requester_node_id)?;
+        Ok(())
+    }
+
+    /// Validate a roster update request, returning the circuit with the updated roster.
+    fn validate_update_roster(
+        &self,
+        update_roster_request: &CircuitUpdateRosterRequest,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<Circuit, AdminSharedError> {
+        let circuit_id = update_roster_request.get_circuit_id();
+        let state_circuit =
+            self.validate_circuit_change(circuit_id, signer_public_key, requester_node_id)?;
+
+        let add_services = update_roster_request.get_add_services();
+        let remove_services = update_roster_request.get_remove_services();
+        if add_services.is_empty() && remove_services.is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "Roster update must add or remove at least one service".to_string(),
+            ));
+        }
+
+        for service in remove_services {
+            if !state_circuit.roster().contains(service.get_service_id()) {
+                return Err(AdminSharedError::ValidationFailed(format!(
+                    "Service {} is not in the roster of circuit {}",
+                    service.get_service_id(),
+                    circuit_id
+                )));
+            }
+        }
+
+        for service in add_services {
+            if state_circuit.roster().contains(service.get_service_id()) {
+                return Err(AdminSharedError::ValidationFailed(format!(
+                    "Service {} is already in the roster of circuit {}",
+                    service.get_service_id(),
+                    circuit_id
+                )));
+            }
+        }
+
+        let mut circuit = self.get_circuit_proto(circuit_id)?;
+        let roster = circuit
+            .take_roster()
+            .into_iter()
+            .filter(|service| {
+                !remove_services
+                    .iter()
+                    .any(|removed| removed.get_service_id() == service.get_service_id())
+            })
+            .chain(add_services.iter().cloned())
+            .collect::<Vec<_>>();
+        circuit.set_roster(RepeatedField::from_vec(roster));
+
+        // the updated circuit must still be a valid circuit
+        self.validate_circuit(&circuit)?;
+
+        Ok(circuit)
+    }
+
+    /// Validate a request to replace the application metadata and comments of a circuit, returning
+    /// the circuit with the new values.
+    fn validate_update_application_metadata(
+        &self,
+        update_request: &CircuitUpdateApplicationMetadataRequest,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<Circuit, AdminSharedError> {
+        let circuit_id = update_request.get_circuit_id();
+        let state_circuit =
+            self.validate_circuit_change(circuit_id, signer_public_key, requester_node_id)?;
+
+        if state_circuit.application_metadata() == update_request.get_application_metadata()
+            && state_circuit.comments() == update_request.get_comments()
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Application metadata update does not change circuit {}",
+                circuit_id
+            )));
+        }
+
+        let mut circuit = self.get_circuit_proto(circuit_id)?;
+        circuit.set_application_metadata(update_request.get_application_metadata().to_vec());
+        circuit.set_comments(update_request.get_comments().to_string());
+
+        Ok(circuit)
+    }
+
+    /// Validate a request to add a node to a circuit, returning the circuit with the new member.
+    fn validate_add_node(
+        &self,
+        add_node_request: &CircuitUpdateAddNodeRequest,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<Circuit, AdminSharedError> {
+        let circuit_id = add_node_request.get_circuit_id();
+        let node = add_node_request.get_node();
+        if node.get_node_id() == self.node_id && !self.splinter_state.has_circuit(circuit_id)? {
+            return self.validate_joining_circuit(
+                add_node_request,
+                signer_public_key,
+                requester_node_id,
+            );
+        }
+
+        let state_circuit =
+            self.validate_circuit_change(circuit_id, signer_public_key, requester_node_id)?;
+
+        if state_circuit.members().contains(node.get_node_id()) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Node {} is already a member of circuit {}",
+                node.get_node_id(),
+                circuit_id
+            )));
+        }
+
+        let mut circuit = self.get_circuit_proto(circuit_id)?;
+        let mut members = circuit.take_members().into_vec();
+        members.push(node.clone());
+        circuit.set_members(RepeatedField::from_vec(members));
+
+        // the updated circuit must still be a valid circuit
+        self.validate_circuit(&circuit)?;
+
+        Ok(circuit)
+    }
+
+    /// Validate a request to add this node to a circuit it is not yet a member of, returning the
+    /// circuit with this node added. The definition of the circuit is the one proposed by the
+    /// coordinator of the change; the current members each check that the proposal matches their
+    /// own circuit before it is committed.
+    fn validate_joining_circuit(
+        &self,
+        add_node_request: &CircuitUpdateAddNodeRequest,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<Circuit, AdminSharedError> {
+        let circuit_id = add_node_request.get_circuit_id();
+        self.validate_change_requester(signer_public_key, requester_node_id)?;
+
+        if self.has_proposal(circuit_id) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Circuit {} already has a pending proposal",
+                circuit_id
+            )));
+        }
+
+        let circuit = self.joining_circuits.get(circuit_id).ok_or_else(|| {
+            AdminSharedError::ValidationFailed(format!(
+                "The definition of circuit {} has not been received",
+                circuit_id
+            ))
+        })?;
+
+        if !circuit
+            .get_members()
+            .iter()
+            .any(|member| member == add_node_request.get_node())
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Node {} is not a member of the proposed circuit {}",
+                self.node_id, circuit_id
+            )));
+        }
+
+        if !circuit
+            .get_members()
+            .iter()
+            .any(|member| member.get_node_id() == requester_node_id)
+            || requester_node_id == self.node_id
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Requester node {} is not a member of circuit {}",
+                requester_node_id, circuit_id
+            )));
+        }
+
+        self.validate_circuit(circuit)?;
+
+        Ok(circuit.clone())
+    }
+
+    /// Validate a request to remove a node from a circuit, returning the circuit without the
+    /// member and the services that it was allowed to run.
+    fn validate_remove_node(
+        &self,
+        remove_node_request: &CircuitUpdateRemoveNodeRequest,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<Circuit, AdminSharedError> {
+        let circuit_id = remove_node_request.get_circuit_id();
+        let node_id = remove_node_request.get_node_id();
+        let state_circuit =
+            self.validate_circuit_change(circuit_id, signer_public_key, requester_node_id)?;
+
+        if !state_circuit.members().contains(node_id) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Node {} is not a member of circuit {}",
+                node_id, circuit_id
+            )));
+        }
+
+        if node_id == requester_node_id {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Requester node {} cannot propose its own removal from circuit {}",
+                requester_node_id, circuit_id
+            )));
+        }
+
+        let mut circuit = self.get_circuit_proto(circuit_id)?;
+        let members = circuit
+            .take_members()
+            .into_iter()
+            .filter(|member| member.get_node_id() != node_id)
+            .collect::<Vec<_>>();
+        let roster = circuit
+            .take_roster()
+            .into_iter()
+            .filter(|service| {
+                !service
+                    .get_allowed_nodes()
+                    .iter()
+                    .any(|allowed_node| allowed_node == node_id)
+            })
+            .collect::<Vec<_>>();
+        circuit.set_members(RepeatedField::from_vec(members));
+        circuit.set_roster(RepeatedField::from_vec(roster));
+
+        // the updated circuit must still be a valid circuit
+        self.validate_circuit(&circuit)?;
+
+        Ok(circuit)
+    }
+
+    fn validate_abandon_circuit(
+        &self,
+        circuit_id: &str,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+    ) -> Result<(), AdminSharedError> {
+        // a node may only abandon a circuit on its own behalf
+        if requester_node_id != self.node_id {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Requester node {} cannot abandon circuit {} for node {}",
+                requester_node_id, circuit_id, self.node_id
+            )));
+        }
+
+        self.validate_key(signer_public_key)?;
+
+        if !self
+            .key_verifier
+            .is_permitted(requester_node_id, signer_public_key)?
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "{} is not registered for the requester node {}",
+                to_hex(signer_public_key),
+                requester_node_id,
+            )));
+        }
+
+        self.key_permission_manager
+            .is_permitted(signer_public_key, PROPOSER_ROLE)
+            .map_err(|_| {
+                AdminSharedError::ValidationFailed(format!(
+                    "{} is not permitted to abandon circuits for node {}",
+                    to_hex(signer_public_key),
+                    requester_node_id
+                ))
+            })?;
+
+        let circuit = self.splinter_state.circuit(circuit_id)?.ok_or_else(|| {
+            AdminSharedError::ValidationFailed(format!(
+                "Circuit with circuit id {} does not exist",
+                circuit_id
+            ))
+        })?;
+
+        if !circuit.circuit_status().is_active() {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Circuit {} has already been abandoned",
+                circuit_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn validate_key(&self, public_key: &[u8]) -> Result<(), AdminSharedError> {
+        if public_key.len() != 33 {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "{} is not a valid public key: invalid length",
+                to_hex(public_key)
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn validate_circuit(&self, circuit: &Circuit) -> Result<(), AdminSharedError> {
+        if circuit.get_authorization_type() == Circuit_AuthorizationType::UNSET_AUTHORIZATION_TYPE {
+            return Err(AdminSharedError::ValidationFailed(
+                "authorization_type cannot be unset".to_string(),
+            ));
+        }
+
+        if circuit.get_persistence() == Circuit_PersistenceType::UNSET_PERSISTENCE_TYPE {
+            return Err(AdminSharedError::ValidationFailed(
+                "persistence_type cannot be unset".to_string(),
+            ));
+        }
+
+        if circuit.get_durability() == Circuit_DurabilityType::UNSET_DURABILITY_TYPE {
+            return Err(AdminSharedError::ValidationFailed(
+                "durability_type cannot be unset".to_string(),
+            ));
+        }
+
+        if circuit.get_routes() == Circuit_RouteType::UNSET_ROUTE_TYPE {
+            return Err(AdminSharedError::ValidationFailed(
+                "route_type cannot be unset".to_string(),
+            ));
+        }
+
+        if circuit.get_circuit_id().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "circuit_id must be set".to_string(),
+            ));
+        }
+        if !messages::is_valid_circuit_id(circuit.get_circuit_id()) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "'{}' is not a valid circuit ID: must be an 11 character string compose of two, 5 \
+                 character base62 strings joined with a '-' (example: abcDE-F0123)",
+                circuit.get_circuit_id(),
+            )));
+        }
+
+        if circuit.get_circuit_management_type().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "circuit_management_type must be set".to_string(),
+            ));
+        }
+
+        let mut members: Vec<String> = Vec::new();
+        let mut all_endpoints: Vec<String> = Vec::new();
+        for member in circuit.get_members() {
+            let node_id = member.get_node_id().to_string();
+            if node_id.is_empty() {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Member node id cannot be empty".to_string(),
+                ));
+            } else if members.contains(&node_id) {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Every member must be unique in the circuit.".to_string(),
+                ));
+            } else {
+                members.push(node_id);
+            }
+
+            let mut endpoints = member.get_endpoints().to_vec();
+            if endpoints.is_empty() {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Member endpoints cannot be empty".to_string(),
+                ));
+            } else if endpoints.iter().any(|endpoint| endpoint.is_empty()) {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Member cannot have an empty endpoint".to_string(),
+                ));
+            } else if endpoints
+                .iter()
+                .any(|endpoint| all_endpoints.contains(endpoint))
+            {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Every member endpoint must be unique in the circuit.".to_string(),
+                ));
+            } else {
+                all_endpoints.append(&mut endpoints);
+            }
+        }
+
+        if members.is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "The circuit must have members".to_string(),
+            ));
+        }
+
+        // check this node is in members
+        if !members.contains(&self.node_id) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Circuit does not contain this node: {}",
+                self.node_id
+            )));
+        }
+
+        if circuit.get_roster().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "The circuit must have services".to_string(),
+            ));
+        }
+
+        let mut services: Vec<String> = Vec::new();
+        // check that all services' allowed nodes are in members
+        for service in circuit.get_roster() {
+            if service.get_allowed_nodes().is_empty() {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Service cannot have an empty allowed nodes list".to_string(),
+                ));
+            }
+
+            if service.get_allowed_nodes().len() > 1 {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Only one allowed node for a service is supported".to_string(),
+                ));
+            }
+
+            for node in service.get_allowed_nodes() {
+                if !members.contains(node) {
+                    return Err(AdminSharedError::ValidationFailed(format!(
+                        "Service cannot have an allowed node that is not in members: {}",
+                        node
+                    )));
+                }
+            }
+
+            let service_id = service.get_service_id().to_string();
+            if service_id.is_empty() {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Service id cannot be empty".to_string(),
+                ));
+            } else if !messages::is_valid_service_id(&service_id) {
+                return Err(AdminSharedError::ValidationFailed(format!(
+                    "'{}' is not a valid service ID: must be a 4 character base62 string",
+                    service_id,
+                )));
+            } else if services.contains(&service_id) {
+                return Err(AdminSharedError::ValidationFailed(
+                    "Every service must be unique in the circuit.".to_string(),
+                ));
+            } else {
+                services.push(service_id)
+            }
+
+            #[cfg(feature = "service-arg-validation")]
+            {
+                self.validate_service_args(&service)?;
+            }
+        }
+
+        if circuit.get_circuit_management_type().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "The circuit must have a mangement type".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "service-arg-validation")]
+    fn validate_service_args(&self, service: &SplinterService) -> Result<(), AdminSharedError> {
+        if let Some(validator) = self.service_arg_validators.get(service.get_service_type()) {
+            let args: HashMap<String, String> = service
+                .get_arguments()
+                .iter()
+                .map(|arg| (arg.get_key().into(), arg.get_value().into()))
+                .collect();
+
+            validator
+                .validate(&args)
+                .map_err(|err| AdminSharedError::ValidationFailed(err.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn validate_circuit_vote(
+        &self,
+        proposal_vote: &CircuitProposalVote,
+        signer_public_key: &[u8],
+        circuit_proposal: &CircuitProposal,
+        node_id: &str,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_hash = proposal_vote.get_circuit_hash();
+
+        self.validate_key(signer_public_key)?;
+
+        if !self.key_verifier.is_permitted(node_id, signer_public_key)? {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "{} is not registered for voting node {}",
+                to_hex(signer_public_key),
+                node_id,
+            )));
+        }
+
+        if circuit_proposal.get_requester_node_id() == node_id {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Received vote from requester node: {}",
+                to_hex(circuit_proposal.get_requester())
+            )));
+        }
+
+        if !self
+            .voting_members(circuit_proposal)?
+            .iter()
+            .any(|member| member.get_node_id() == node_id)
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Received vote from {} which may not vote on {}",
+                node_id, proposal_vote.circuit_id
+            )));
+        }
+
+        // a voter may change its vote while the proposal is pending, but not repeat it
+        if circuit_proposal.get_votes().iter().any(|vote| {
+            vote.get_voter_node_id() == node_id && vote.get_vote() == proposal_vote.get_vote()
+        }) {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Received duplicate vote from {} for {}",
+                node_id, proposal_vote.circuit_id
+            )));
+        }
+
+        self.key_permission_manager
+            .is_permitted(signer_public_key, VOTER_ROLE)
+            .map_err(|_| {
+                AdminSharedError::ValidationFailed(format!(
+                    "{} is not permitted to vote for node {}",
+                    to_hex(signer_public_key),
+                    node_id
+                ))
+            })?;
+
+        // validate hash of circuit
+        if circuit_proposal.get_circuit_hash() != circuit_hash {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Hash of circuit does not match circuit proposal: {}",
+                proposal_vote.circuit_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Only the key that submitted a proposal, on behalf of the same node, may cancel it.
+    fn validate_cancel_proposal(
+        &self,
+        cancel: &CircuitProposalCancel,
+        signer_public_key: &[u8],
+        requester_node_id: &str,
+        circuit_proposal: &CircuitProposal,
+    ) -> Result<(), AdminSharedError> {
+        self.validate_key(signer_public_key)?;
+
+        if !self
+            .key_verifier
+            .is_permitted(requester_node_id, signer_public_key)?
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "{} is not registered for the requester node {}",
+                to_hex(signer_public_key),
+                requester_node_id,
+            )));
+        }
+
+        if circuit_proposal.get_requester() != signer_public_key
+            || circuit_proposal.get_requester_node_id() != requester_node_id
+        {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Only the requester of the proposal for {} may cancel it",
+                cancel.circuit_id
+            )));
+        }
+
+        self.key_permission_manager
+            .is_permitted(signer_public_key, PROPOSER_ROLE)
+            .map_err(|_| {
+                AdminSharedError::ValidationFailed(format!(
+                    "{} is not permitted to cancel proposals for node {}",
+                    to_hex(signer_public_key),
+                    requester_node_id
+                ))
+            })?;
+
+        if circuit_proposal.get_circuit_hash() != cancel.get_circuit_hash() {
+            return Err(AdminSharedError::ValidationFailed(format!(
+                "Hash of circuit does not match circuit proposal: {}",
+                cancel.circuit_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn validate_circuit_management_payload(
+        &self,
+        payload: &CircuitManagementPayload,
+        header: &CircuitManagementPayload_Header,
+    ) -> Result<(), AdminSharedError> {
+        // Validate payload signature
+        if payload.get_signature().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "CircuitManagementPayload signature must be set".to_string(),
+            ));
+        };
+
+        // Validate the payload header
+        if payload.get_header().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "CircuitManagementPayload header must be set".to_string(),
+            ));
+        };
+
+        // Validate the header, requester field is set
+        if header.get_requester().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "CircuitManagementPayload must have a requester".to_string(),
+            ));
+        };
+
+        self.validate_key(header.get_requester())?;
+
+        // Validate the header, requester_node_id is set
+        if header.get_requester_node_id().is_empty() {
+            return Err(AdminSharedError::ValidationFailed(
+                "CircuitManagementPayload must have a requester node id".to_string(),
+            ));
+        };
+
+        Ok(())
+    }
+
+    /// Applies the circuit's voting policy to the votes on the proposal. The requester is counted
+    /// as accepting its own proposal. The proposal is rejected as soon as enough voting members
+    /// have rejected it that the policy can no longer be satisfied.
+    fn check_approved(
+        &self,
+        proposal: &CircuitProposal,
+    ) -> Result<CircuitProposalStatus, AdminSharedError> {
+        let voting_members = self
+            .voting_members(proposal)?
+            .iter()
+            .map(|member| member.get_node_id().to_string())
+            .collect::<HashSet<String>>();
+
+        let mut accepted = HashSet::new();
+        let mut rejected = HashSet::new();
+        if voting_members.contains(proposal.get_requester_node_id()) {
+            accepted.insert(proposal.get_requester_node_id());
+        }
+        for vote in proposal.get_votes() {
+            if !voting_members.contains(vote.get_voter_node_id()) {
+                continue;
+            }
+            if vote.get_vote() == CircuitProposalVote_Vote::REJECT {
+                rejected.insert(vote.get_voter_node_id());
+            } else {
+                accepted.insert(vote.get_voter_node_id());
+            }
+        }
+
+        let required_votes = self
+            .voting_policy(proposal)?
+            .required_votes(voting_members.len());
+
+        if !rejected.is_empty() && voting_members.len() - rejected.len() < required_votes {
+            Ok(CircuitProposalStatus::Rejected)
+        } else if accepted.len() >= required_votes {
+            Ok(CircuitProposalStatus::Accepted)
+        } else {
+            Ok(CircuitProposalStatus::Pending)
+        }
+    }
+
+    /// Returns the voting policy that applies to the proposal. A new circuit is created according
+    /// to its own policy, while changes to an existing circuit are decided by its current policy.
+    fn voting_policy(&self, proposal: &CircuitProposal) -> Result<VotingPolicy, AdminSharedError> {
+        if proposal.get_proposal_type() != CircuitProposal_ProposalType::CREATE {
+            if let Some(circuit) = self.splinter_state.circuit(proposal.get_circuit_id())? {
+                return Ok(circuit.voting_policy().clone());
+            }
+        }
+
+        Ok(voting_policy_from_proto(proposal.get_circuit_proposal()))
+    }
+
+    /// Returns the members of the proposed circuit that vote on the proposal. All members vote on
+    /// a new circuit, while only the current members of an existing circuit vote on changes to it.
+    fn voting_members(
+        &self,
+        proposal: &CircuitProposal,
+    ) -> Result<Vec<SplinterNode>, AdminSharedError> {
+        let members = proposal.get_circuit_proposal().get_members();
+        if proposal.get_proposal_type() == CircuitProposal_ProposalType::CREATE {
+            return Ok(members.to_vec());
+        }
+
+        // a node being added to a circuit does not have it yet; every other member is current
+        let current_members = match self.splinter_state.circuit(proposal.get_circuit_id())? {
+            Some(circuit) => circuit.members().to_vec(),
+            None => members
+                .iter()
+                .map(|member| member.get_node_id().to_string())
+                .filter(|node_id| node_id != &self.node_id)
+                .collect(),
+        };
+
+        Ok(members
+            .iter()
+            .filter(|member| current_members.contains(&member.get_node_id().to_string()))
+            .cloned()
+            .collect())
+    }
+
+    /// Initialize all services that this node should run on the created circuit using the service
+    /// orchestrator. This may not include all services if they are not supported locally. It is
+    /// expected that some services will be started externally.
+    pub fn initialize_services(&mut self, circuit: &Circuit) -> Result<(), AdminSharedError> {
+        let orchestrator = self.orchestrator.lock().map_err(|_| {
+            AdminSharedError::ServiceInitializationFailed {
+                context: "ServiceOrchestrator lock poisoned".into(),
+                source: None,
+            }
+        })?;
+
+        // Get all services this node is allowed to run
+        let services = circuit
+            .get_roster()
+            .iter()
+            .filter(|service| {
                 service.allowed_nodes.contains(&self.node_id)
                     && orchestrator
                         .supported_service_types()
@@ -1714,1658 +3912,2491 @@ impl AdminServiceShared {
             })
             .collect::<Vec<_>>();
 
-        // Start all services the orchestrator has a factory for
-        for service in services {
-            let service_definition = ServiceDefinition {
-                circuit: circuit.circuit_id.clone(),
-                service_id: service.service_id.clone(),
-                service_type: service.service_type.clone(),
-            };
+        // Start all services the orchestrator has a factory for
+        for service in services {
+            let service_definition = ServiceDefinition {
+                circuit: circuit.circuit_id.clone(),
+                service_id: service.service_id.clone(),
+                service_type: service.service_type.clone(),
+            };
+
+            let service_arguments = service
+                .arguments
+                .iter()
+                .map(|arg| (arg.key.clone(), arg.value.clone()))
+                .collect();
+
+            orchestrator
+                .initialize_service(service_definition.clone(), service_arguments)
+                .map_err(|err| AdminSharedError::ServiceInitializationFailed {
+                    context: format!(
+                        "Unable to start service {} on circuit {}",
+                        service.service_id, circuit.circuit_id
+                    ),
+                    source: Some(err),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Shut down all services that this node is running on the given circuit using the service
+    /// orchestrator.
+    pub fn shutdown_services(&mut self, circuit_id: &str) -> Result<(), AdminSharedError> {
+        self.shutdown_matching_services(circuit_id, |_| true)
+    }
+
+    /// Shut down the services this node is running on the given circuit that match the given
+    /// predicate.
+    fn shutdown_matching_services<F>(
+        &mut self,
+        circuit_id: &str,
+        predicate: F,
+    ) -> Result<(), AdminSharedError>
+    where
+        F: Fn(&ServiceDefinition) -> bool,
+    {
+        let orchestrator =
+            self.orchestrator
+                .lock()
+                .map_err(|_| AdminSharedError::ServiceShutdownFailed {
+                    context: "ServiceOrchestrator lock poisoned".into(),
+                    source: None,
+                })?;
+
+        let services = orchestrator
+            .list_services(vec![circuit_id.to_string()], vec![])
+            .map_err(|err| AdminSharedError::ServiceShutdownFailed {
+                context: format!("Unable to list services on circuit {}: {}", circuit_id, err),
+                source: None,
+            })?;
+
+        for service_definition in services.into_iter().filter(|service| predicate(service)) {
+            orchestrator
+                .shutdown_service(&service_definition)
+                .map_err(|err| AdminSharedError::ServiceShutdownFailed {
+                    context: format!(
+                        "Unable to stop service {} on circuit {}",
+                        service_definition.service_id, circuit_id
+                    ),
+                    source: Some(err),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_circuits(&self) -> Result<BTreeMap<String, StateCircuit>, AdminSharedError> {
+        self.splinter_state
+            .circuits()
+            .map_err(AdminSharedError::from)
+    }
+
+    pub fn get_nodes(&self) -> Result<BTreeMap<String, StateNode>, AdminSharedError> {
+        self.splinter_state.nodes().map_err(AdminSharedError::from)
+    }
+
+    fn update_splinter_state(&mut self, circuit: &Circuit) -> Result<(), AdminSharedError> {
+        let members: Vec<StateNode> = circuit
+            .get_members()
+            .iter()
+            .map(|node| {
+                StateNode::new(
+                    node.get_node_id().to_string(),
+                    node.get_endpoints().to_vec(),
+                )
+            })
+            .collect();
+
+        let roster = circuit.get_roster().iter().map(|service| {
+            StateServiceDefinition::builder(
+                service.get_service_id().to_string(),
+                service.get_service_type().to_string(),
+            )
+            .with_allowed_nodes(service.get_allowed_nodes().to_vec())
+            .with_arguments(
+                service
+                    .get_arguments()
+                    .iter()
+                    .map(|argument| {
+                        (
+                            argument.get_key().to_string(),
+                            argument.get_value().to_string(),
+                        )
+                    })
+                    .collect::<BTreeMap<String, String>>(),
+            )
+            .build()
+        });
+
+        let auth = match circuit.get_authorization_type() {
+            Circuit_AuthorizationType::TRUST_AUTHORIZATION => AuthorizationType::Trust,
+            // This should never happen
+            Circuit_AuthorizationType::UNSET_AUTHORIZATION_TYPE => {
+                return Err(AdminSharedError::CommitError(
+                    "Missing authorization type on circuit commit".to_string(),
+                ))
+            }
+        };
+
+        let persistence = match circuit.get_persistence() {
+            Circuit_PersistenceType::ANY_PERSISTENCE => PersistenceType::Any,
+            // This should never happen
+            Circuit_PersistenceType::UNSET_PERSISTENCE_TYPE => {
+                return Err(AdminSharedError::CommitError(
+                    "Missing persistence type on circuit commit".to_string(),
+                ))
+            }
+        };
+
+        let durability = match circuit.get_durability() {
+            Circuit_DurabilityType::NO_DURABILITY => DurabilityType::NoDurability,
+            Circuit_DurabilityType::DURABLE => DurabilityType::Durable,
+            // This should never happen
+            Circuit_DurabilityType::UNSET_DURABILITY_TYPE => {
+                return Err(AdminSharedError::CommitError(
+                    "Missing durabilty type on circuit commit".to_string(),
+                ))
+            }
+        };
+
+        let routes = match circuit.get_routes() {
+            Circuit_RouteType::ANY_ROUTE => RouteType::Any,
+            Circuit_RouteType::RELAY_ROUTE => RouteType::Relay,
+            // This should never happen
+            Circuit_RouteType::UNSET_ROUTE_TYPE => {
+                return Err(AdminSharedError::CommitError(
+                    "Missing route type on circuit commit".to_string(),
+                ))
+            }
+        };
+
+        let new_circuit = StateCircuit::builder()
+            .with_id(circuit.get_circuit_id().to_string())
+            .with_members(
+                members
+                    .iter()
+                    .map(|node| node.id().to_string())
+                    .collect::<Vec<String>>(),
+            )
+            .with_roster(roster.clone())
+            .with_auth(auth)
+            .with_persistence(persistence)
+            .with_durability(durability)
+            .with_routes(routes)
+            .with_circuit_management_type(circuit.get_circuit_management_type().to_string())
+            .with_application_metadata(circuit.get_application_metadata().to_vec())
+            .with_comments(circuit.get_comments().to_string())
+            .with_voting_policy(voting_policy_from_proto(circuit))
+            .build()
+            .map_err(|err| {
+                AdminSharedError::CommitError(format!("Unable build new circuit: {}", err))
+            })?;
+
+        for member in members {
+            self.splinter_state
+                .add_node(member.id().to_string(), member)?;
+        }
+
+        self.splinter_state
+            .add_circuit(new_circuit.id().to_string(), new_circuit)?;
+
+        for service in roster {
+            if service.allowed_nodes().contains(&self.node_id) {
+                continue;
+            }
+
+            let unique_id = ServiceId::new(
+                circuit.circuit_id.to_string(),
+                service.service_id().to_string(),
+            );
+
+            let allowed_node = &service.allowed_nodes()[0];
+            if let Some(member) = self.splinter_state.node(&allowed_node)? {
+                let service = Service::new(service.service_id().to_string(), None, member.clone());
+                self.splinter_state.add_service(unique_id, service)?;
+            } else {
+                return Err(AdminSharedError::CommitError(format!(
+                    "Unable to find allowed node {} when adding service {} to directory",
+                    allowed_node,
+                    service.service_id()
+                )));
+            }
+        }
+
+        self.write_circuit_to_store(circuit.get_circuit_id())
+    }
+
+    /// Write the circuit, as it is currently held in splinter state, and its members' node
+    /// information to the admin store, replacing any previously stored version of the circuit.
+    fn write_circuit_to_store(&self, circuit_id: &str) -> Result<(), AdminSharedError> {
+        let state_circuit = self.splinter_state.circuit(circuit_id)?.ok_or_else(|| {
+            AdminSharedError::CommitError(format!(
+                "Circuit {} is missing from splinter state",
+                circuit_id
+            ))
+        })?;
+
+        let mut nodes = vec![];
+        for member in state_circuit.members() {
+            let node = self.splinter_state.node(member)?.ok_or_else(|| {
+                AdminSharedError::CommitError(format!(
+                    "Missing node information for member {} of circuit {}",
+                    member, circuit_id
+                ))
+            })?;
+            nodes.push(StoreNode::from(&node));
+        }
+
+        let circuit = StoreCircuit::try_from(&state_circuit)?;
+        if self.admin_store.get_circuit(circuit_id)?.is_some() {
+            self.admin_store.update_circuit(circuit, nodes)?;
+        } else {
+            self.admin_store.add_circuit(circuit, nodes)?;
+        }
+
+        Ok(())
+    }
+
+    /// Build the admin protocol representation of a circuit that has been committed to splinter
+    /// state.
+    fn get_circuit_proto(&self, circuit_id: &str) -> Result<Circuit, AdminSharedError> {
+        let state_circuit = self.splinter_state.circuit(circuit_id)?.ok_or_else(|| {
+            AdminSharedError::ValidationFailed(format!(
+                "Circuit with circuit id {} does not exist",
+                circuit_id
+            ))
+        })?;
+
+        let mut members = vec![];
+        for member in state_circuit.members().to_vec() {
+            let node = self.splinter_state.node(&member)?.ok_or_else(|| {
+                AdminSharedError::CommitError(format!(
+                    "Missing node information for member {} of circuit {}",
+                    member, circuit_id
+                ))
+            })?;
+            let mut splinter_node = SplinterNode::new();
+            splinter_node.set_node_id(member);
+            splinter_node.set_endpoints(RepeatedField::from_vec(node.endpoints().to_vec()));
+            members.push(splinter_node);
+        }
+
+        let roster = state_circuit
+            .roster()
+            .iter()
+            .map(|service| {
+                let mut splinter_service = SplinterService::new();
+                splinter_service.set_service_id(service.service_id().to_string());
+                splinter_service.set_service_type(service.service_type().to_string());
+                splinter_service
+                    .set_allowed_nodes(RepeatedField::from_vec(service.allowed_nodes().to_vec()));
+                splinter_service.set_arguments(RepeatedField::from_vec(
+                    service
+                        .arguments()
+                        .iter()
+                        .map(|(key, value)| {
+                            let mut argument = SplinterService_Argument::new();
+                            argument.set_key(key.to_string());
+                            argument.set_value(value.to_string());
+                            argument
+                        })
+                        .collect(),
+                ));
+                splinter_service
+            })
+            .collect::<Vec<_>>();
+
+        let mut circuit = Circuit::new();
+        circuit.set_circuit_id(state_circuit.id().to_string());
+        circuit.set_members(RepeatedField::from_vec(members));
+        circuit.set_roster(RepeatedField::from_vec(roster));
+        circuit.set_authorization_type(match state_circuit.auth() {
+            AuthorizationType::Trust => Circuit_AuthorizationType::TRUST_AUTHORIZATION,
+        });
+        circuit.set_persistence(match state_circuit.persistence() {
+            PersistenceType::Any => Circuit_PersistenceType::ANY_PERSISTENCE,
+        });
+        circuit.set_durability(match state_circuit.durability() {
+            DurabilityType::NoDurability => Circuit_DurabilityType::NO_DURABILITY,
+            DurabilityType::Durable => Circuit_DurabilityType::DURABLE,
+        });
+        circuit.set_routes(match state_circuit.routes() {
+            RouteType::Any => Circuit_RouteType::ANY_ROUTE,
+            RouteType::Relay => Circuit_RouteType::RELAY_ROUTE,
+        });
+        circuit.set_circuit_management_type(state_circuit.circuit_management_type().to_string());
+        circuit.set_application_metadata(state_circuit.application_metadata().to_vec());
+        circuit.set_comments(state_circuit.comments().to_string());
+        match state_circuit.voting_policy() {
+            VotingPolicy::Unanimous => circuit.set_voting_policy(Circuit_VotingPolicy::UNANIMOUS),
+            VotingPolicy::Majority => circuit.set_voting_policy(Circuit_VotingPolicy::MAJORITY),
+            VotingPolicy::Threshold(required_votes) => {
+                circuit.set_voting_policy(Circuit_VotingPolicy::THRESHOLD);
+                circuit.set_required_votes(*required_votes);
+            }
+        }
+
+        Ok(circuit)
+    }
+
+    fn remove_circuit_from_splinter_state(
+        &mut self,
+        circuit: &Circuit,
+    ) -> Result<(), AdminSharedError> {
+        let circuit_id = circuit.get_circuit_id();
+        self.admin_store.remove_circuit(circuit_id)?;
+
+        for service in circuit.get_roster() {
+            let unique_id =
+                ServiceId::new(circuit_id.to_string(), service.get_service_id().to_string());
+            self.splinter_state.remove_service(&unique_id)?;
+        }
+
+        self.splinter_state.remove_circuit(circuit_id)?;
+
+        // only remove the member nodes that are not part of any remaining circuit
+        let circuits = self.splinter_state.circuits()?;
+        for member in circuit.get_members() {
+            if !circuits
+                .values()
+                .any(|circuit| circuit.members().contains(member.get_node_id()))
+            {
+                self.splinter_state.remove_node(member.get_node_id())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn add_services_to_directory(&mut self) -> Result<(), AdminSharedError> {
+        let circuits = self.splinter_state.circuits()?;
+        for (id, circuit) in circuits {
+            if !circuit.circuit_status().is_active() {
+                continue;
+            }
+
+            for service in circuit.roster() {
+                if service.allowed_nodes().contains(&self.node_id) {
+                    continue;
+                }
+                let unique_id = ServiceId::new(id.to_string(), service.service_id().to_string());
+
+                let allowed_node = &service.allowed_nodes()[0];
+                if let Some(member) = self.splinter_state.node(&allowed_node)? {
+                    // rebuild Node with id
+                    let node =
+                        StateNode::new(allowed_node.to_string(), member.endpoints().to_vec());
+                    let service = Service::new(service.service_id().to_string(), None, node);
+                    self.splinter_state.add_service(unique_id, service)?
+                } else {
+                    return Err(AdminSharedError::CommitError(format!(
+                        "Unable to find allowed node {} when adding service {} to directory",
+                        allowed_node,
+                        service.service_id()
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn verify_signature(&self, payload: &CircuitManagementPayload) -> Result<bool, ServiceError> {
+        let header =
+            protobuf::parse_from_bytes::<CircuitManagementPayload_Header>(payload.get_header())?;
+
+        let signature = payload.get_signature();
+        let public_key = header.get_requester();
+
+        self.signature_verifier
+            .verify(&payload.get_header(), &signature, &public_key)
+            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
+    }
+}
+
+/// Circuits proposed without a voting policy require every voting member to accept.
+fn voting_policy_from_proto(circuit: &Circuit) -> VotingPolicy {
+    match circuit.get_voting_policy() {
+        Circuit_VotingPolicy::UNANIMOUS | Circuit_VotingPolicy::UNSET_VOTING_POLICY => {
+            VotingPolicy::Unanimous
+        }
+        Circuit_VotingPolicy::MAJORITY => VotingPolicy::Majority,
+        Circuit_VotingPolicy::THRESHOLD => VotingPolicy::Threshold(circuit.get_required_votes()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use protobuf::{Message, RepeatedField};
+
+    use crate::admin::service::AdminKeyVerifierError;
+    use crate::admin::store::MemoryAdminServiceStore;
+    use crate::circuit::directory::CircuitDirectory;
+    #[cfg(feature = "routing-table")]
+    use crate::circuit::routing::{memory::RoutingTable, RoutingTableReader};
+    use crate::circuit::CircuitStatus;
+    use crate::keys::insecure::AllowAllKeyPermissionManager;
+    use crate::mesh::{Envelope, Mesh};
+    use crate::network::auth::AuthorizationManager;
+    use crate::network::connection_manager::authorizers::{Authorizers, InprocAuthorizer};
+    use crate::network::connection_manager::ConnectionManager;
+    use crate::peer::{PeerManager, PeerManagerConnector};
+    use crate::protocol::authorization::{
+        AuthorizationMessage, AuthorizationType, Authorized, ConnectRequest, ConnectResponse,
+        TrustRequest,
+    };
+    use crate::protos::admin;
+    use crate::protos::admin::{SplinterNode, SplinterService};
+    use crate::protos::authorization;
+    use crate::protos::network::{NetworkMessage, NetworkMessageType};
+    use crate::protos::prelude::*;
+    use crate::service::{ServiceMessageContext, ServiceSendError};
+    use crate::signing::{
+        hash::{HashSigner, HashVerifier},
+        Signer,
+    };
+    use crate::storage::get_storage;
+    use crate::transport::{
+        inproc::InprocTransport, ConnectError, Connection, DisconnectError, RecvError, SendError,
+        Transport,
+    };
+
+    const PUB_KEY: &[u8] = &[
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
+        25, 26, 27, 28, 29, 30, 31, 32,
+    ];
+
+    /// Test that the CircuitManagementPayload is moved to the pending payloads when the peers are
+    /// fully authorized.
+    #[test]
+    fn test_protocol_agreement() {
+        let mut transport = InprocTransport::default();
+        let mut orchestrator_transport = transport.clone();
+
+        let mut other_listener = transport
+            .listen("inproc://otherplace:8000")
+            .expect("Unable to get listener");
+        let _test_listener = transport
+            .listen("inproc://someplace:8000")
+            .expect("Unable to get listener");
+        let _orchestator_listener = transport
+            .listen("inproc://orchestator")
+            .expect("Unable to get listener");
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(Some(transport));
+        let orchestrator_connection = orchestrator_transport
+            .connect("inproc://orchestator")
+            .expect("failed to create connection");
+        let (orchestrator, _) = ServiceOrchestrator::new(vec![], orchestrator_connection, 1, 1, 1)
+            .expect("failed to create orchestrator");
+        let state = setup_splinter_state();
+        let mut shared = setup_admin_shared("my_peer_id", orchestrator, peer_connector, state);
+
+        let service_sender = MockServiceNetworkSender::new();
+        shared.set_network_sender(Some(Box::new(service_sender.clone())));
+
+        let mut circuit = admin::Circuit::new();
+        circuit.set_circuit_id("01234-ABCDE".into());
+        circuit.set_authorization_type(admin::Circuit_AuthorizationType::TRUST_AUTHORIZATION);
+        circuit.set_persistence(admin::Circuit_PersistenceType::ANY_PERSISTENCE);
+        circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE);
+        circuit.set_durability(admin::Circuit_DurabilityType::NO_DURABILITY);
+        circuit.set_circuit_management_type("test app auth handler".into());
+        circuit.set_comments("test circuit".into());
+
+        circuit.set_members(protobuf::RepeatedField::from_vec(vec![
+            splinter_node("test-node", &["inproc://someplace:8000".to_string()]),
+            splinter_node("other-node", &["inproc://otherplace:8000".to_string()]),
+        ]));
+        circuit.set_roster(protobuf::RepeatedField::from_vec(vec![
+            splinter_service("0123", "sabre"),
+            splinter_service("ABCD", "sabre"),
+        ]));
+
+        let mut request = admin::CircuitCreateRequest::new();
+        request.set_circuit(circuit);
+
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+
+        let mut payload = admin::CircuitManagementPayload::new();
+
+        payload.set_signature(Vec::new());
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        payload.set_circuit_create_request(request);
+
+        // start up thread for other node
+        std::thread::spawn(move || {
+            let mesh = Mesh::new(2, 2);
+            let conn = other_listener.accept().unwrap();
+            mesh.add(conn, "my_peer_id".to_string()).unwrap();
+
+            handle_auth(&mesh, "my_peer_id", "other-node");
+
+            mesh.shutdown_signaler().shutdown();
+        });
+
+        shared
+            .propose_circuit(payload, "test".to_string())
+            .expect("Proposal not accepted");
+
+        // None of the proposed members are peered
+        assert_eq!(1, shared.unpeered_payloads.len());
+        assert_eq!(0, shared.pending_circuit_payloads.len());
+
+        // Set other-node to peered
+        shared
+            .on_peer_connected("other-node")
+            .expect("Unable to set peer to peered");
+
+        // Still waitin on 1 peer
+        assert_eq!(1, shared.unpeered_payloads.len());
+        assert_eq!(0, shared.pending_circuit_payloads.len());
+
+        // Set other-node to peered
+        shared
+            .on_peer_connected("test-node")
+            .expect("Unable to set peer to peered");
+
+        // We're fully peered, but need to wait for protocol to be agreed on
+        assert_eq!(1, shared.pending_protocol_payloads.len());
+        assert_eq!(0, shared.pending_circuit_payloads.len());
+
+        shared
+            .on_protocol_agreement("admin::other-node", 1)
+            .expect("received unexpected error");
+
+        // Waiting on 1 node for protocol agreement
+        assert_eq!(1, shared.pending_protocol_payloads.len());
+        assert_eq!(0, shared.pending_circuit_payloads.len());
+
+        shared
+            .on_protocol_agreement("admin::test-node", 1)
+            .expect("received unexpected error");
+        // We're fully peered and agreed on protocol, so the pending payload is now available
+        assert_eq!(0, shared.pending_protocol_payloads.len());
+        assert_eq!(1, shared.pending_circuit_payloads.len());
+        shutdown(mesh, cm, pm);
+    }
+
+    /// Test that the CircuitManagementPayload message is dropped, if a node fails to match
+    /// protocol versions
+    #[test]
+    fn test_protocol_disagreement() {
+        let mut transport = InprocTransport::default();
+        let mut orchestrator_transport = transport.clone();
+
+        let _listener = transport
+            .listen("inproc://otherplace:8000")
+            .expect("Unable to get listener");
+        let _admin_listener = transport
+            .listen("inproc://admin-service")
+            .expect("Unable to get listener");
+
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(Some(transport));
+        let orchestrator_connection = orchestrator_transport
+            .connect("inproc://admin-service")
+            .expect("failed to create connection");
+        let (orchestrator, _) = ServiceOrchestrator::new(vec![], orchestrator_connection, 1, 1, 1)
+            .expect("failed to create orchestrator");
+        let state = setup_splinter_state();
+        let mut shared = setup_admin_shared("test-node", orchestrator, peer_connector, state);
+
+        let service_sender = MockServiceNetworkSender::new();
+        shared.set_network_sender(Some(Box::new(service_sender.clone())));
+
+        let mut circuit = admin::Circuit::new();
+        circuit.set_circuit_id("01234-ABCDE".into());
+        circuit.set_authorization_type(admin::Circuit_AuthorizationType::TRUST_AUTHORIZATION);
+        circuit.set_persistence(admin::Circuit_PersistenceType::ANY_PERSISTENCE);
+        circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE);
+        circuit.set_circuit_management_type("test app auth handler".into());
+        circuit.set_comments("test circuit".into());
+
+        circuit.set_members(protobuf::RepeatedField::from_vec(vec![
+            splinter_node("test-node", &["inproc://someplace:8000".to_string()]),
+            splinter_node("other-node", &["inproc://otherplace:8000".to_string()]),
+        ]));
+        circuit.set_roster(protobuf::RepeatedField::from_vec(vec![
+            splinter_service("0123", "sabre"),
+            splinter_service("ABCD", "sabre"),
+        ]));
+
+        let mut request = admin::CircuitCreateRequest::new();
+        request.set_circuit(circuit);
+
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+
+        let mut payload = admin::CircuitManagementPayload::new();
+
+        payload.set_signature(Vec::new());
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        payload.set_circuit_create_request(request);
+
+        shared
+            .propose_circuit(payload, "local".to_string())
+            .expect("Proposal not accepted");
+
+        // None of the proposed members are peered
+        assert_eq!(1, shared.unpeered_payloads.len());
+        assert_eq!(0, shared.pending_circuit_payloads.len());
+
+        // Set other-node to peered
+        shared
+            .on_peer_connected("other-node")
+            .expect("Unable to set peer to peered");
 
-            let service_arguments = service
-                .arguments
-                .iter()
-                .map(|arg| (arg.key.clone(), arg.value.clone()))
-                .collect();
+        assert_eq!(0, shared.unpeered_payloads.len());
+        assert_eq!(1, shared.pending_protocol_payloads.len());
+        assert_eq!(0, shared.pending_circuit_payloads.len());
+        shared
+            .on_protocol_agreement("admin::other-node", 0)
+            .expect("received unexpected error");
 
-            orchestrator
-                .initialize_service(service_definition.clone(), service_arguments)
-                .map_err(|err| AdminSharedError::ServiceInitializationFailed {
-                    context: format!(
-                        "Unable to start service {} on circuit {}",
-                        service.service_id, circuit.circuit_id
-                    ),
-                    source: Some(err),
-                })?;
+        // The message should be dropped
+        assert_eq!(0, shared.pending_circuit_payloads.len());
+        assert_eq!(0, shared.pending_protocol_payloads.len());
+
+        // sent the service protocol request but not the payload
+        assert_eq!(
+            1,
+            service_sender
+                .sent
+                .lock()
+                .expect("Network sender lock poisoned")
+                .len()
+        );
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that a valid circuit is validated correctly
+    fn test_validate_circuit_valid() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+
+        if let Err(err) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been valid: {}", err);
+        }
+
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that a circuit proposed with a key that is not permitted for the requesting node is
+    // invalid
+    fn test_validate_circuit_signer_not_permitted() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared_with_key_verifier(
+            "node_a",
+            orchestrator,
+            peer_connector,
+            state,
+            Box::new(MockAdminKeyVerifier::new(false)),
+        );
+        let circuit = setup_test_circuit();
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to requester node not being registered");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit is proposed by a signer key is not a valid public key the proposal is
+    // invalid
+    fn test_validate_circuit_signer_key_invalid() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+
+        let pub_key = (0u8..50).collect::<Vec<_>>();
+        // too short
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, &pub_key[0..10], "node_a") {
+            panic!("Should have been invalid due to key being too short");
+        }
+        // too long
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, &pub_key, "node_a") {
+            panic!("Should have been invalid due to key being too long");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit has a service in its roster with an allowed node that is not in
+    // members an error is returned
+    fn test_validate_circuit_bad_node() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+
+        let mut service_bad = SplinterService::new();
+        service_bad.set_service_id("0123".to_string());
+        service_bad.set_service_type("type_a".to_string());
+        service_bad.set_allowed_nodes(RepeatedField::from_vec(vec!["node_bad".to_string()]));
+
+        circuit.set_roster(RepeatedField::from_vec(vec![service_bad]));
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to service having an allowed node not in members");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit has a service in its roster with too many allowed nodes
+    fn test_validate_circuit_too_many_allowed_nodes() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+
+        let mut service_bad = SplinterService::new();
+        service_bad.set_service_id("0123".to_string());
+        service_bad.set_service_type("type_a".to_string());
+        service_bad.set_allowed_nodes(RepeatedField::from_vec(vec![
+            "node_b".to_string(),
+            "extra".to_string(),
+        ]));
+
+        circuit.set_roster(RepeatedField::from_vec(vec![service_bad]));
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to service having too many allowed nodes");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit has a service with "" for a service id an error is returned
+    fn test_validate_circuit_empty_service_id() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+
+        let mut service_ = SplinterService::new();
+        service_.set_service_id("".to_string());
+        service_.set_service_type("type_a".to_string());
+        service_.set_allowed_nodes(RepeatedField::from_vec(vec!["node_a".to_string()]));
+
+        circuit.set_roster(RepeatedField::from_vec(vec![service_]));
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to service's id being empty");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit has a service with an invalid service id an error is returned
+    fn test_validate_circuit_invalid_service_id() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+
+        let mut service_ = SplinterService::new();
+        service_.set_service_id("invalid_service_id".to_string());
+        service_.set_service_type("type_a".to_string());
+        service_.set_allowed_nodes(RepeatedField::from_vec(vec!["node_a".to_string()]));
+
+        circuit.set_roster(RepeatedField::from_vec(vec![service_]));
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to service's id being empty");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit has a service with duplicate service ids an error is returned
+    fn test_validate_circuit_duplicate_service_id() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+
+        let mut service_a = SplinterService::new();
+        service_a.set_service_id("0123".to_string());
+        service_a.set_service_type("type_a".to_string());
+        service_a.set_allowed_nodes(RepeatedField::from_vec(vec!["node_a".to_string()]));
+
+        let mut service_a2 = SplinterService::new();
+        service_a2.set_service_id("0123".to_string());
+        service_a2.set_service_type("type_a".to_string());
+        service_a2.set_allowed_nodes(RepeatedField::from_vec(vec!["node_b".to_string()]));
+
+        circuit.set_roster(RepeatedField::from_vec(vec![service_a, service_a2]));
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to service's id being a duplicate");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit does not have any services in its roster an error is returned
+    fn test_validate_circuit_empty_roster() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+        circuit.set_roster(RepeatedField::from_vec(vec![]));
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to empty roster");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit does not have any nodes in its members an error is returned
+    fn test_validate_circuit_empty_members() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+
+        circuit.set_members(RepeatedField::from_vec(vec![]));
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid empty members");
         }
-
-        Ok(())
+        shutdown(mesh, cm, pm);
     }
 
-    pub fn get_circuits(&self) -> Result<BTreeMap<String, StateCircuit>, AdminSharedError> {
-        self.splinter_state
-            .circuits()
-            .map_err(AdminSharedError::from)
-    }
+    #[test]
+    // test that a threshold voting policy must require between one and all of the members, and
+    // that required_votes may not be set for any other policy
+    fn test_validate_circuit_voting_policy() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-    pub fn get_nodes(&self) -> Result<BTreeMap<String, StateNode>, AdminSharedError> {
-        self.splinter_state.nodes().map_err(AdminSharedError::from)
-    }
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-    fn update_splinter_state(&mut self, circuit: &Circuit) -> Result<(), AdminSharedError> {
-        let members: Vec<StateNode> = circuit
-            .get_members()
-            .iter()
-            .map(|node| {
-                StateNode::new(
-                    node.get_node_id().to_string(),
-                    node.get_endpoints().to_vec(),
-                )
-            })
-            .collect();
+        circuit.set_voting_policy(Circuit_VotingPolicy::THRESHOLD);
+        circuit.set_required_votes(2);
+        if let Err(err) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been valid: {}", err);
+        }
 
-        let roster = circuit.get_roster().iter().map(|service| {
-            StateServiceDefinition::builder(
-                service.get_service_id().to_string(),
-                service.get_service_type().to_string(),
-            )
-            .with_allowed_nodes(service.get_allowed_nodes().to_vec())
-            .with_arguments(
-                service
-                    .get_arguments()
-                    .iter()
-                    .map(|argument| {
-                        (
-                            argument.get_key().to_string(),
-                            argument.get_value().to_string(),
-                        )
-                    })
-                    .collect::<BTreeMap<String, String>>(),
-            )
-            .build()
-        });
+        circuit.set_required_votes(0);
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because no votes are required");
+        }
 
-        let auth = match circuit.get_authorization_type() {
-            Circuit_AuthorizationType::TRUST_AUTHORIZATION => AuthorizationType::Trust,
-            // This should never happen
-            Circuit_AuthorizationType::UNSET_AUTHORIZATION_TYPE => {
-                return Err(AdminSharedError::CommitError(
-                    "Missing authorization type on circuit commit".to_string(),
-                ))
-            }
-        };
+        circuit.set_required_votes(3);
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because there are only two members");
+        }
 
-        let persistence = match circuit.get_persistence() {
-            Circuit_PersistenceType::ANY_PERSISTENCE => PersistenceType::Any,
-            // This should never happen
-            Circuit_PersistenceType::UNSET_PERSISTENCE_TYPE => {
-                return Err(AdminSharedError::CommitError(
-                    "Missing persistence type on circuit commit".to_string(),
-                ))
-            }
-        };
+        circuit.set_voting_policy(Circuit_VotingPolicy::MAJORITY);
+        circuit.set_required_votes(1);
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because required_votes is set");
+        }
 
-        let durability = match circuit.get_durability() {
-            Circuit_DurabilityType::NO_DURABILITY => DurabilityType::NoDurability,
-            // This should never happen
-            Circuit_DurabilityType::UNSET_DURABILITY_TYPE => {
-                return Err(AdminSharedError::CommitError(
-                    "Missing durabilty type on circuit commit".to_string(),
-                ))
-            }
-        };
+        shutdown(mesh, cm, pm);
+    }
 
-        let routes = match circuit.get_routes() {
-            Circuit_RouteType::ANY_ROUTE => RouteType::Any,
-            // This should never happen
-            Circuit_RouteType::UNSET_ROUTE_TYPE => {
-                return Err(AdminSharedError::CommitError(
-                    "Missing route type on circuit commit".to_string(),
-                ))
-            }
+    #[test]
+    // test that proposals are accepted once the votes satisfy the circuit's voting policy, and
+    // rejected once the policy can no longer be satisfied
+    fn test_check_approved_voting_policy() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+        let mut node_c = SplinterNode::new();
+        node_c.set_node_id("node_c".to_string());
+        node_c.set_endpoints(vec!["test://endpoint_c:0".to_string()].into());
+        circuit.mut_members().push(node_c);
+
+        let vote = |node_id: &str, vote| {
+            let mut vote_record = CircuitProposal_VoteRecord::new();
+            vote_record.set_vote(vote);
+            vote_record.set_public_key(format!("test_signer_{}", node_id).into_bytes());
+            vote_record.set_voter_node_id(node_id.to_string());
+            vote_record
         };
 
-        let new_circuit = StateCircuit::builder()
-            .with_id(circuit.get_circuit_id().to_string())
-            .with_members(
-                members
-                    .iter()
-                    .map(|node| node.id().to_string())
-                    .collect::<Vec<String>>(),
-            )
-            .with_roster(roster.clone())
-            .with_auth(auth)
-            .with_persistence(persistence)
-            .with_durability(durability)
-            .with_routes(routes)
-            .with_circuit_management_type(circuit.get_circuit_management_type().to_string())
-            .build()
-            .map_err(|err| {
-                AdminSharedError::CommitError(format!("Unable build new circuit: {}", err))
-            })?;
+        // the requester, node_b, is counted as accepting its own proposal
+        circuit.set_voting_policy(Circuit_VotingPolicy::MAJORITY);
+        let mut proposal = setup_test_proposal(&circuit);
+        match admin_shared.check_approved(&proposal) {
+            Ok(CircuitProposalStatus::Pending) => (),
+            _ => panic!("Proposal should have been pending"),
+        }
 
-        for member in members {
-            self.splinter_state
-                .add_node(member.id().to_string(), member)?;
+        proposal.set_votes(RepeatedField::from_vec(vec![
+            vote("node_a", CircuitProposalVote_Vote::REJECT),
+            vote("node_c", CircuitProposalVote_Vote::ACCEPT),
+        ]));
+        match admin_shared.check_approved(&proposal) {
+            Ok(CircuitProposalStatus::Accepted) => (),
+            _ => panic!("Proposal should have been accepted"),
         }
 
-        self.splinter_state
-            .add_circuit(new_circuit.id().to_string(), new_circuit)?;
+        circuit.set_voting_policy(Circuit_VotingPolicy::THRESHOLD);
+        circuit.set_required_votes(3);
+        let mut proposal = setup_test_proposal(&circuit);
+        proposal.set_votes(RepeatedField::from_vec(vec![vote(
+            "node_c",
+            CircuitProposalVote_Vote::REJECT,
+        )]));
+        match admin_shared.check_approved(&proposal) {
+            Ok(CircuitProposalStatus::Rejected) => (),
+            _ => panic!("Proposal should have been rejected"),
+        }
 
-        for service in roster {
-            if service.allowed_nodes().contains(&self.node_id) {
-                continue;
-            }
+        shutdown(mesh, cm, pm);
+    }
 
-            let unique_id = ServiceId::new(
-                circuit.circuit_id.to_string(),
-                service.service_id().to_string(),
-            );
+    #[test]
+    // test that if a circuit does not have the local node in the member list an error is
+    // returned
+    fn test_validate_circuit_missing_local_node() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-            let allowed_node = &service.allowed_nodes()[0];
-            if let Some(member) = self.splinter_state.node(&allowed_node)? {
-                let service = Service::new(service.service_id().to_string(), None, member.clone());
-                self.splinter_state.add_service(unique_id, service)?;
-            } else {
-                return Err(AdminSharedError::CommitError(format!(
-                    "Unable to find allowed node {} when adding service {} to directory",
-                    allowed_node,
-                    service.service_id()
-                )));
-            }
-        }
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        Ok(())
-    }
+        let mut node_b = SplinterNode::new();
+        node_b.set_node_id("node_b".to_string());
+        node_b.set_endpoints(vec!["test://endpoint_b:0".to_string()].into());
 
-    pub fn add_services_to_directory(&mut self) -> Result<(), AdminSharedError> {
-        let circuits = self.splinter_state.circuits()?;
-        for (id, circuit) in circuits {
-            for service in circuit.roster() {
-                if service.allowed_nodes().contains(&self.node_id) {
-                    continue;
-                }
-                let unique_id = ServiceId::new(id.to_string(), service.service_id().to_string());
+        circuit.set_members(RepeatedField::from_vec(vec![node_b]));
 
-                let allowed_node = &service.allowed_nodes()[0];
-                if let Some(member) = self.splinter_state.node(&allowed_node)? {
-                    // rebuild Node with id
-                    let node =
-                        StateNode::new(allowed_node.to_string(), member.endpoints().to_vec());
-                    let service = Service::new(service.service_id().to_string(), None, node);
-                    self.splinter_state.add_service(unique_id, service)?
-                } else {
-                    return Err(AdminSharedError::CommitError(format!(
-                        "Unable to find allowed node {} when adding service {} to directory",
-                        allowed_node,
-                        service.service_id()
-                    )));
-                }
-            }
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because node_a is not in members");
         }
-
-        Ok(())
+        shutdown(mesh, cm, pm);
     }
 
-    fn verify_signature(&self, payload: &CircuitManagementPayload) -> Result<bool, ServiceError> {
-        let header =
-            protobuf::parse_from_bytes::<CircuitManagementPayload_Header>(payload.get_header())?;
+    #[test]
+    // test that if a circuit has a member with node id of "" an error is
+    // returned
+    fn test_validate_circuit_empty_node_id() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        let signature = payload.get_signature();
-        let public_key = header.get_requester();
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        self.signature_verifier
-            .verify(&payload.get_header(), &signature, &public_key)
-            .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
-    }
-}
+        let mut node_a = SplinterNode::new();
+        node_a.set_node_id("node_a".to_string());
+        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let mut node_b = SplinterNode::new();
+        node_b.set_node_id("node_b".to_string());
+        node_b.set_endpoints(vec!["test://endpoint_b:0".to_string()].into());
 
-    use std::sync::{Arc, Mutex};
+        let mut node_ = SplinterNode::new();
+        node_.set_node_id("".to_string());
+        node_.set_endpoints(vec!["test://endpoint_:0".to_string()].into());
 
-    use protobuf::{Message, RepeatedField};
+        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b, node_]));
 
-    use crate::admin::service::AdminKeyVerifierError;
-    use crate::circuit::directory::CircuitDirectory;
-    use crate::keys::insecure::AllowAllKeyPermissionManager;
-    use crate::mesh::{Envelope, Mesh};
-    use crate::network::auth::AuthorizationManager;
-    use crate::network::connection_manager::authorizers::{Authorizers, InprocAuthorizer};
-    use crate::network::connection_manager::ConnectionManager;
-    use crate::peer::{PeerManager, PeerManagerConnector};
-    use crate::protocol::authorization::{
-        AuthorizationMessage, AuthorizationType, Authorized, ConnectRequest, ConnectResponse,
-        TrustRequest,
-    };
-    use crate::protos::admin;
-    use crate::protos::admin::{SplinterNode, SplinterService};
-    use crate::protos::authorization;
-    use crate::protos::network::{NetworkMessage, NetworkMessageType};
-    use crate::protos::prelude::*;
-    use crate::service::{ServiceMessageContext, ServiceSendError};
-    use crate::signing::{
-        hash::{HashSigner, HashVerifier},
-        Signer,
-    };
-    use crate::storage::get_storage;
-    use crate::transport::{
-        inproc::InprocTransport, ConnectError, Connection, DisconnectError, RecvError, SendError,
-        Transport,
-    };
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because node_ is has an empty node id");
+        }
+        shutdown(mesh, cm, pm);
+    }
 
-    const PUB_KEY: &[u8] = &[
-        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24,
-        25, 26, 27, 28, 29, 30, 31, 32,
-    ];
+    #[test]
+    // test that if a circuit has duplicate members an error is returned
+    fn test_validate_circuit_duplicate_members() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-    const STATE_DIR: &str = "/var/lib/splinter/";
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-    /// Test that the CircuitManagementPayload is moved to the pending payloads when the peers are
-    /// fully authorized.
-    #[test]
-    fn test_protocol_agreement() {
-        let mut transport = InprocTransport::default();
-        let mut orchestrator_transport = transport.clone();
+        let mut node_a = SplinterNode::new();
+        node_a.set_node_id("node_a".to_string());
+        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
 
-        let mut other_listener = transport
-            .listen("inproc://otherplace:8000")
-            .expect("Unable to get listener");
-        let _test_listener = transport
-            .listen("inproc://someplace:8000")
-            .expect("Unable to get listener");
-        let _orchestator_listener = transport
-            .listen("inproc://orchestator")
-            .expect("Unable to get listener");
+        let mut node_b = SplinterNode::new();
+        node_b.set_node_id("node_b".to_string());
+        node_b.set_endpoints(vec!["test://endpoint_b:0".to_string()].into());
 
-        let (mesh, cm, pm, peer_connector) = setup_peer_connector(Some(transport));
-        let orchestrator_connection = orchestrator_transport
-            .connect("inproc://orchestator")
-            .expect("failed to create connection");
-        let (orchestrator, _) = ServiceOrchestrator::new(vec![], orchestrator_connection, 1, 1, 1)
-            .expect("failed to create orchestrator");
+        let mut node_b2 = SplinterNode::new();
+        node_b2.set_node_id("node_b".to_string());
+        node_b2.set_endpoints(vec!["test://endpoint_b2:0".to_string()].into());
+
+        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b, node_b2]));
+
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because there are duplicate members");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit has an empty circuit id an error is returned
+    fn test_validate_circuit_empty_circuit_id() {
         let state = setup_splinter_state();
-        let mut shared = AdminServiceShared::new(
-            "my_peer_id".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        let service_sender = MockServiceNetworkSender::new();
-        shared.set_network_sender(Some(Box::new(service_sender.clone())));
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        let mut circuit = admin::Circuit::new();
-        circuit.set_circuit_id("01234-ABCDE".into());
-        circuit.set_authorization_type(admin::Circuit_AuthorizationType::TRUST_AUTHORIZATION);
-        circuit.set_persistence(admin::Circuit_PersistenceType::ANY_PERSISTENCE);
-        circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE);
-        circuit.set_durability(admin::Circuit_DurabilityType::NO_DURABILITY);
-        circuit.set_circuit_management_type("test app auth handler".into());
-        circuit.set_comments("test circuit".into());
+        circuit.set_circuit_id("".to_string());
 
-        circuit.set_members(protobuf::RepeatedField::from_vec(vec![
-            splinter_node("test-node", &["inproc://someplace:8000".to_string()]),
-            splinter_node("other-node", &["inproc://otherplace:8000".to_string()]),
-        ]));
-        circuit.set_roster(protobuf::RepeatedField::from_vec(vec![
-            splinter_service("0123", "sabre"),
-            splinter_service("ABCD", "sabre"),
-        ]));
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because the circuit ID is empty");
+        }
+        shutdown(mesh, cm, pm);
+    }
 
-        let mut request = admin::CircuitCreateRequest::new();
-        request.set_circuit(circuit);
+    #[test]
+    // test that if a circuit has an invalid circuit id an error is returned
+    fn test_validate_circuit_invalid_circuit_id() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        let mut header = admin::CircuitManagementPayload_Header::new();
-        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        let mut payload = admin::CircuitManagementPayload::new();
+        circuit.set_circuit_id("invalid_circuit_id".to_string());
 
-        payload.set_signature(Vec::new());
-        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
-        payload.set_circuit_create_request(request);
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because the circuit ID is invalid");
+        }
+        shutdown(mesh, cm, pm);
+    }
 
-        // start up thread for other node
-        std::thread::spawn(move || {
-            let mesh = Mesh::new(2, 2);
-            let conn = other_listener.accept().unwrap();
-            mesh.add(conn, "my_peer_id".to_string()).unwrap();
+    #[test]
+    // test that if a circuit has a member with no endpoints an error is returned
+    fn test_validate_circuit_no_endpoints() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-            handle_auth(&mesh, "my_peer_id", "other-node");
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-            mesh.shutdown_signaler().shutdown();
-        });
+        let mut node_a = SplinterNode::new();
+        node_a.set_node_id("node_a".to_string());
+        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
 
-        shared
-            .propose_circuit(payload, "test".to_string())
-            .expect("Proposal not accepted");
+        let mut node_b = SplinterNode::new();
+        node_b.set_node_id("node_b".to_string());
+        node_b.set_endpoints(vec![].into());
 
-        // None of the proposed members are peered
-        assert_eq!(1, shared.unpeered_payloads.len());
-        assert_eq!(0, shared.pending_circuit_payloads.len());
+        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b]));
 
-        // Set other-node to peered
-        shared
-            .on_peer_connected("other-node")
-            .expect("Unable to set peer to peered");
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because a member has no endpoints");
+        }
+        shutdown(mesh, cm, pm);
+    }
 
-        // Still waitin on 1 peer
-        assert_eq!(1, shared.unpeered_payloads.len());
-        assert_eq!(0, shared.pending_circuit_payloads.len());
+    #[test]
+    // test that if a circuit has a member with an empty endpoint an error is returned
+    fn test_validate_circuit_empty_endpoint() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        // Set other-node to peered
-        shared
-            .on_peer_connected("test-node")
-            .expect("Unable to set peer to peered");
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        // We're fully peered, but need to wait for protocol to be agreed on
-        assert_eq!(1, shared.pending_protocol_payloads.len());
-        assert_eq!(0, shared.pending_circuit_payloads.len());
+        let mut node_a = SplinterNode::new();
+        node_a.set_node_id("node_a".to_string());
+        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
 
-        shared
-            .on_protocol_agreement("admin::other-node", 1)
-            .expect("received unexpected error");
+        let mut node_b = SplinterNode::new();
+        node_b.set_node_id("node_b".to_string());
+        node_b.set_endpoints(vec!["".to_string()].into());
 
-        // Waiting on 1 node for protocol agreement
-        assert_eq!(1, shared.pending_protocol_payloads.len());
-        assert_eq!(0, shared.pending_circuit_payloads.len());
+        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b]));
 
-        shared
-            .on_protocol_agreement("admin::test-node", 1)
-            .expect("received unexpected error");
-        // We're fully peered and agreed on protocol, so the pending payload is now available
-        assert_eq!(0, shared.pending_protocol_payloads.len());
-        assert_eq!(1, shared.pending_circuit_payloads.len());
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because a member has an empty endpoint");
+        }
         shutdown(mesh, cm, pm);
     }
 
-    /// Test that the CircuitManagementPayload message is dropped, if a node fails to match
-    /// protocol versions
     #[test]
-    fn test_protocol_disagreement() {
-        let mut transport = InprocTransport::default();
-        let mut orchestrator_transport = transport.clone();
+    // test that if a circuit has a member with a duplicate endpoint an error is returned
+    fn test_validate_circuit_duplicate_endpoint() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        let _listener = transport
-            .listen("inproc://otherplace:8000")
-            .expect("Unable to get listener");
-        let _admin_listener = transport
-            .listen("inproc://admin-service")
-            .expect("Unable to get listener");
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        let (mesh, cm, pm, peer_connector) = setup_peer_connector(Some(transport));
-        let orchestrator_connection = orchestrator_transport
-            .connect("inproc://admin-service")
-            .expect("failed to create connection");
-        let (orchestrator, _) = ServiceOrchestrator::new(vec![], orchestrator_connection, 1, 1, 1)
-            .expect("failed to create orchestrator");
-        let state = setup_splinter_state();
-        let mut shared = AdminServiceShared::new(
-            "test-node".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
+        let mut node_a = SplinterNode::new();
+        node_a.set_node_id("node_a".to_string());
+        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
 
-        let service_sender = MockServiceNetworkSender::new();
-        shared.set_network_sender(Some(Box::new(service_sender.clone())));
+        let mut node_b = SplinterNode::new();
+        node_b.set_node_id("node_b".to_string());
+        node_b.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
 
-        let mut circuit = admin::Circuit::new();
-        circuit.set_circuit_id("01234-ABCDE".into());
-        circuit.set_authorization_type(admin::Circuit_AuthorizationType::TRUST_AUTHORIZATION);
-        circuit.set_persistence(admin::Circuit_PersistenceType::ANY_PERSISTENCE);
-        circuit.set_routes(admin::Circuit_RouteType::ANY_ROUTE);
-        circuit.set_circuit_management_type("test app auth handler".into());
-        circuit.set_comments("test circuit".into());
+        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b]));
 
-        circuit.set_members(protobuf::RepeatedField::from_vec(vec![
-            splinter_node("test-node", &["inproc://someplace:8000".to_string()]),
-            splinter_node("other-node", &["inproc://otherplace:8000".to_string()]),
-        ]));
-        circuit.set_roster(protobuf::RepeatedField::from_vec(vec![
-            splinter_service("0123", "sabre"),
-            splinter_service("ABCD", "sabre"),
-        ]));
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because a member has a duplicate endpoint");
+        }
+        shutdown(mesh, cm, pm);
+    }
+
+    #[test]
+    // test that if a circuit does not have authorization set an error is returned
+    fn test_validate_circuit_no_authorization() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
+
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        let mut request = admin::CircuitCreateRequest::new();
-        request.set_circuit(circuit);
+        circuit.set_authorization_type(Circuit_AuthorizationType::UNSET_AUTHORIZATION_TYPE);
 
-        let mut header = admin::CircuitManagementPayload_Header::new();
-        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because authorizaiton type is unset");
+        }
+        shutdown(mesh, cm, pm);
+    }
 
-        let mut payload = admin::CircuitManagementPayload::new();
+    #[test]
+    // test that if a circuit does not have persistence set an error is returned
+    fn test_validate_circuit_no_persitance() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        payload.set_signature(Vec::new());
-        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
-        payload.set_circuit_create_request(request);
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        shared
-            .propose_circuit(payload, "local".to_string())
-            .expect("Proposal not accepted");
+        circuit.set_persistence(Circuit_PersistenceType::UNSET_PERSISTENCE_TYPE);
 
-        // None of the proposed members are peered
-        assert_eq!(1, shared.unpeered_payloads.len());
-        assert_eq!(0, shared.pending_circuit_payloads.len());
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because persistence type is unset");
+        }
+        shutdown(mesh, cm, pm);
+    }
 
-        // Set other-node to peered
-        shared
-            .on_peer_connected("other-node")
-            .expect("Unable to set peer to peered");
+    #[test]
+    // test that if a circuit does not have durability set an error is returned
+    fn test_validate_circuit_unset_durability() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        assert_eq!(0, shared.unpeered_payloads.len());
-        assert_eq!(1, shared.pending_protocol_payloads.len());
-        assert_eq!(0, shared.pending_circuit_payloads.len());
-        shared
-            .on_protocol_agreement("admin::other-node", 0)
-            .expect("received unexpected error");
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        // The message should be dropped
-        assert_eq!(0, shared.pending_circuit_payloads.len());
-        assert_eq!(0, shared.pending_protocol_payloads.len());
+        circuit.set_durability(Circuit_DurabilityType::UNSET_DURABILITY_TYPE);
 
-        // sent the service protocol request but not the payload
-        assert_eq!(
-            1,
-            service_sender
-                .sent
-                .lock()
-                .expect("Network sender lock poisoned")
-                .len()
-        );
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because durabilty type is unset");
+        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that a valid circuit is validated correctly
-    fn test_validate_circuit_valid() {
+    // test that if a circuit does not have route type set an error is returned
+    fn test_validate_circuit_no_routes() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let circuit = setup_test_circuit();
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
 
-        if let Err(err) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been valid: {}", err);
-        }
+        circuit.set_routes(Circuit_RouteType::UNSET_ROUTE_TYPE);
 
+        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
+            panic!("Should have been invalid because route type is unset");
+        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that a circuit proposed with a key that is not permitted for the requesting node is
-    // invalid
-    fn test_validate_circuit_signer_not_permitted() {
+    // test that if a circuit does not have circuit_management_type set an error is returned
+    fn test_validate_circuit_no_management_type() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::new(false)),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let circuit = setup_test_circuit();
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let mut circuit = setup_test_circuit();
+
+        circuit.set_circuit_management_type("".to_string());
 
         if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid due to requester node not being registered");
+            panic!("Should have been invalid because route type is unset");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit is proposed by a signer key is not a valid public key the proposal is
-    // invalid
-    fn test_validate_circuit_signer_key_invalid() {
+    // test that a valid circuit proposal vote comes back as valid
+    fn test_validate_proposal_vote_valid() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
         let circuit = setup_test_circuit();
+        let vote = setup_test_vote(&circuit);
+        let proposal = setup_test_proposal(&circuit);
 
-        let pub_key = (0u8..50).collect::<Vec<_>>();
-        // too short
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, &pub_key[0..10], "node_a") {
-            panic!("Should have been invalid due to key being too short");
-        }
-        // too long
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, &pub_key, "node_a") {
-            panic!("Should have been invalid due to key being too long");
+        if let Err(err) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
+            panic!("Should have been valid: {}", err);
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a service in its roster with an allowed node that is not in
-    // members an error is returned
-    fn test_validate_circuit_bad_node() {
+    // test that if the vote is from a key that is not permitted for the voting node the vote is
+    // invalid
+    fn test_validate_proposal_vote_not_permitted() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
+        let admin_shared = setup_admin_shared_with_key_verifier(
+            "node_a",
+            orchestrator,
             peer_connector,
             state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-
-        let mut service_bad = SplinterService::new();
-        service_bad.set_service_id("0123".to_string());
-        service_bad.set_service_type("type_a".to_string());
-        service_bad.set_allowed_nodes(RepeatedField::from_vec(vec!["node_bad".to_string()]));
-
-        circuit.set_roster(RepeatedField::from_vec(vec![service_bad]));
+            Box::new(MockAdminKeyVerifier::new(false)),
+        );
+        let circuit = setup_test_circuit();
+        let vote = setup_test_vote(&circuit);
+        let proposal = setup_test_proposal(&circuit);
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid due to service having an allowed node not in members");
+        if let Ok(_) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
+            panic!("Should have been invalid because voting node is not registered");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a service in its roster with too many allowed nodes
-    fn test_validate_circuit_too_many_allowed_nodes() {
+    // test if the voter is the original requester node the vote is invalid
+    fn test_validate_proposal_vote_requester() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-
-        let mut service_bad = SplinterService::new();
-        service_bad.set_service_id("0123".to_string());
-        service_bad.set_service_type("type_a".to_string());
-        service_bad.set_allowed_nodes(RepeatedField::from_vec(vec![
-            "node_b".to_string(),
-            "extra".to_string(),
-        ]));
-
-        circuit.set_roster(RepeatedField::from_vec(vec![service_bad]));
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        let vote = setup_test_vote(&circuit);
+        let proposal = setup_test_proposal(&circuit);
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid due to service having too many allowed nodes");
+        if let Ok(_) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_b") {
+            panic!("Should have been invalid because voter is the requester");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a service with "" for a service id an error is returned
-    fn test_validate_circuit_empty_service_id() {
+    // test if a voter has already voted on a proposal the new vote is invalid
+    fn test_validate_proposal_vote_duplicate_vote() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        let vote = setup_test_vote(&circuit);
+        let mut proposal = setup_test_proposal(&circuit);
 
-        let mut service_ = SplinterService::new();
-        service_.set_service_id("".to_string());
-        service_.set_service_type("type_a".to_string());
-        service_.set_allowed_nodes(RepeatedField::from_vec(vec!["node_a".to_string()]));
+        let mut vote_record = CircuitProposal_VoteRecord::new();
+        vote_record.set_vote(CircuitProposalVote_Vote::ACCEPT);
+        vote_record.set_public_key(b"test_signer_a".to_vec());
+        vote_record.set_voter_node_id("node_a".to_string());
 
-        circuit.set_roster(RepeatedField::from_vec(vec![service_]));
+        proposal.set_votes(RepeatedField::from_vec(vec![vote_record]));
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid due to service's id being empty");
+        if let Ok(_) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
+            panic!("Should have been invalid because node as already submited a vote");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a service with an invalid service id an error is returned
-    fn test_validate_circuit_invalid_service_id() {
+    // test that a voter may change its vote while the proposal is pending, and that the new vote
+    // replaces its vote record, keeping the previous vote
+    fn test_change_proposal_vote() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        let vote = setup_test_vote(&circuit);
+        let mut proposal = setup_test_proposal(&circuit);
+
+        let mut vote_record = CircuitProposal_VoteRecord::new();
+        vote_record.set_vote(CircuitProposalVote_Vote::REJECT);
+        vote_record.set_public_key(b"test_signer_a".to_vec());
+        vote_record.set_voter_node_id("node_a".to_string());
+        proposal.set_votes(RepeatedField::from_vec(vec![vote_record]));
+
+        if let Err(err) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
+            panic!("Should have been valid: {}", err);
+        }
+
+        admin_shared
+            .add_proposal(proposal)
+            .expect("Unable to add proposal");
+
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_VOTE);
+        header.set_requester(PUB_KEY.into());
+        header.set_requester_node_id("node_a".to_string());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        payload.set_signature(HashSigner.sign(&payload.header).unwrap());
+        payload.set_circuit_proposal_vote(vote);
 
-        let mut service_ = SplinterService::new();
-        service_.set_service_id("invalid_service_id".to_string());
-        service_.set_service_type("type_a".to_string());
-        service_.set_allowed_nodes(RepeatedField::from_vec(vec!["node_a".to_string()]));
+        let (_, changed_proposal) = admin_shared
+            .propose_change(payload, None)
+            .expect("Unable to propose vote change");
 
-        circuit.set_roster(RepeatedField::from_vec(vec![service_]));
+        let votes = changed_proposal.get_votes();
+        assert_eq!(votes.len(), 1);
+        assert_eq!(votes[0].get_vote(), CircuitProposalVote_Vote::ACCEPT);
+        assert_eq!(votes[0].get_public_key(), PUB_KEY);
+        assert_eq!(
+            votes[0].get_previous_votes(),
+            &[CircuitProposalVote_Vote::REJECT]
+        );
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid due to service's id being empty");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a service with duplicate service ids an error is returned
-    fn test_validate_circuit_duplicate_service_id() {
+    // test that if the circuit hash in the circuit proposal does not match the circuit hash on
+    // the vote, the vote is invalid
+    fn test_validate_proposal_vote_circuit_hash_mismatch() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-
-        let mut service_a = SplinterService::new();
-        service_a.set_service_id("0123".to_string());
-        service_a.set_service_type("type_a".to_string());
-        service_a.set_allowed_nodes(RepeatedField::from_vec(vec!["node_a".to_string()]));
-
-        let mut service_a2 = SplinterService::new();
-        service_a2.set_service_id("0123".to_string());
-        service_a2.set_service_type("type_a".to_string());
-        service_a2.set_allowed_nodes(RepeatedField::from_vec(vec!["node_b".to_string()]));
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        let vote = setup_test_vote(&circuit);
+        let mut proposal = setup_test_proposal(&circuit);
 
-        circuit.set_roster(RepeatedField::from_vec(vec![service_a, service_a2]));
+        proposal.set_circuit_hash("bad_hash".to_string());
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid due to service's id being a duplicate");
+        if let Ok(_) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
+            panic!("Should have been invalid because the circuit hash does not match");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit does not have any services in its roster an error is returned
-    fn test_validate_circuit_empty_roster() {
+    // test that only the requester of a proposal may cancel it, and only with the matching
+    // circuit hash
+    fn test_validate_cancel_proposal() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-        circuit.set_roster(RepeatedField::from_vec(vec![]));
+        let admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        let mut proposal = setup_test_proposal(&circuit);
+        proposal.set_requester(PUB_KEY.to_vec());
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid due to empty roster");
+        let mut cancel = CircuitProposalCancel::new();
+        cancel.set_circuit_id(circuit.get_circuit_id().to_string());
+        cancel.set_circuit_hash(proposal.get_circuit_hash().to_string());
+
+        if let Err(err) =
+            admin_shared.validate_cancel_proposal(&cancel, PUB_KEY, "node_b", &proposal)
+        {
+            panic!("Should have been valid: {}", err);
+        }
+
+        if let Ok(_) = admin_shared.validate_cancel_proposal(&cancel, PUB_KEY, "node_a", &proposal)
+        {
+            panic!("Should have been invalid because node is not the requester node");
+        }
+
+        proposal.set_requester(b"test_signer_b".to_vec());
+        if let Ok(_) = admin_shared.validate_cancel_proposal(&cancel, PUB_KEY, "node_b", &proposal)
+        {
+            panic!("Should have been invalid because key is not the requester");
+        }
+
+        proposal.set_requester(PUB_KEY.to_vec());
+        cancel.set_circuit_hash("bad_hash".to_string());
+        if let Ok(_) = admin_shared.validate_cancel_proposal(&cancel, PUB_KEY, "node_b", &proposal)
+        {
+            panic!("Should have been invalid because the circuit hash does not match");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit does not have any nodes in its members an error is returned
-    fn test_validate_circuit_empty_members() {
+    // test that the validate_circuit_management_payload method returns an error in case the
+    // signature is empty.
+    fn test_validate_circuit_management_payload_signature() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
 
-        circuit.set_members(RepeatedField::from_vec(vec![]));
+        let circuit = setup_test_circuit();
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid empty members");
+        let mut request = admin::CircuitCreateRequest::new();
+        request.set_circuit(circuit);
+
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+        header.set_requester(PUB_KEY.into());
+        header.set_requester_node_id("node_b".to_string());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_signature(Vec::new());
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        payload.set_circuit_create_request(request);
+
+        // Asserting the payload will be deemed invalid as the signature is an empty vec.
+        if let Ok(_) = shared.validate_circuit_management_payload(&payload, &header) {
+            panic!("Should have been invalid due to empty signature");
+        }
+
+        payload.set_signature(HashSigner.sign(&payload.header).unwrap());
+        // Asserting the payload passed through validation.
+        if let Err(_) = shared.validate_circuit_management_payload(&payload, &header) {
+            panic!("Should have been valid");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit does not have the local node in the member list an error is
-    // returned
-    fn test_validate_circuit_missing_local_node() {
+    // test that the validate_circuit_management_payload method returns an error in case the
+    // header is empty.
+    fn test_validate_circuit_management_payload_header() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
 
-        let mut node_b = SplinterNode::new();
-        node_b.set_node_id("node_b".to_string());
-        node_b.set_endpoints(vec!["test://endpoint_b:0".to_string()].into());
+        let circuit = setup_test_circuit();
 
-        circuit.set_members(RepeatedField::from_vec(vec![node_b]));
+        let mut request = admin::CircuitCreateRequest::new();
+        request.set_circuit(circuit);
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because node_a is not in members");
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+        header.set_requester(PUB_KEY.into());
+        header.set_requester_node_id("node_b".to_string());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_signature(HashSigner.sign(&payload.header).unwrap());
+        payload.set_circuit_create_request(request);
+
+        // Asserting the payload will be deemed invalid as the header is empty.
+        match shared.validate_circuit_management_payload(&payload, &header) {
+            Err(err) => assert!(err
+                .to_string()
+                .contains("CircuitManagementPayload header must be set")),
+            _ => panic!("Should have been invalid because empty requester field"),
+        }
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        // Asserting the payload passed through validation, and failed at a further step.
+        if let Err(_) = shared.validate_circuit_management_payload(&payload, &header) {
+            panic!("Should have been valid");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a member with node id of "" an error is
-    // returned
-    fn test_validate_circuit_empty_node_id() {
+    // test that the validate_circuit_management_payload method returns an error in case the header
+    // requester field is empty.
+    fn test_validate_circuit_management_header_requester() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
 
-        let mut node_a = SplinterNode::new();
-        node_a.set_node_id("node_a".to_string());
-        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
+        let circuit = setup_test_circuit();
 
-        let mut node_b = SplinterNode::new();
-        node_b.set_node_id("node_b".to_string());
-        node_b.set_endpoints(vec!["test://endpoint_b:0".to_string()].into());
+        let mut request = admin::CircuitCreateRequest::new();
+        request.set_circuit(circuit);
 
-        let mut node_ = SplinterNode::new();
-        node_.set_node_id("".to_string());
-        node_.set_endpoints(vec!["test://endpoint_:0".to_string()].into());
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+        header.set_requester_node_id("node_b".to_string());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_signature(HashSigner.sign(&payload.header).unwrap());
+        payload.set_circuit_create_request(request);
 
-        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b, node_]));
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        // Asserting the payload will be deemed invalid as the header is empty.
+        match shared.validate_circuit_management_payload(&payload, &header) {
+            Err(err) => assert!(err
+                .to_string()
+                .contains("CircuitManagementPayload must have a requester")),
+            _ => panic!("Should have been invalid because empty requester field"),
+        }
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because node_ is has an empty node id");
+        header.set_requester(PUB_KEY.into());
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        // Asserting the payload passed through validation, and failed at a further step.
+        if let Err(_) = shared.validate_circuit_management_payload(&payload, &header) {
+            panic!("Should have been valid");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has duplicate members an error is returned
-    fn test_validate_circuit_duplicate_members() {
+    // test that the CircuitManagementPayload returns an error in case the header requester_node_id
+    // field is empty.
+    fn test_validate_circuit_management_header_requester_node_id() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-
-        let mut node_a = SplinterNode::new();
-        node_a.set_node_id("node_a".to_string());
-        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
+        let shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
 
-        let mut node_b = SplinterNode::new();
-        node_b.set_node_id("node_b".to_string());
-        node_b.set_endpoints(vec!["test://endpoint_b:0".to_string()].into());
+        let circuit = setup_test_circuit();
 
-        let mut node_b2 = SplinterNode::new();
-        node_b2.set_node_id("node_b".to_string());
-        node_b2.set_endpoints(vec!["test://endpoint_b2:0".to_string()].into());
+        let mut request = admin::CircuitCreateRequest::new();
+        request.set_circuit(circuit);
 
-        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b, node_b2]));
+        let mut header = admin::CircuitManagementPayload_Header::new();
+        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+        header.set_requester(PUB_KEY.into());
+        let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_signature(HashSigner.sign(&payload.header).unwrap());
+        payload.set_circuit_create_request(request);
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because there are duplicate members");
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        // Asserting the payload will be deemed invalid as the header is empty.
+        match shared.validate_circuit_management_payload(&payload, &header) {
+            Err(err) => assert!(err
+                .to_string()
+                .contains("CircuitManagementPayload must have a requester node id")),
+            _ => panic!("Should have been invalid because empty requester field"),
+        }
+
+        header.set_requester_node_id("node_b".to_string());
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
+        // Asserting the payload passed through validation, and failed at a further step.
+        if let Err(_) = shared.validate_circuit_management_payload(&payload, &header) {
+            panic!("Should have been valid");
         }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has an empty circuit id an error is returned
-    fn test_validate_circuit_empty_circuit_id() {
+    // test that a destroy request for an existing circuit from one of its members is valid
+    fn test_validate_destroy_circuit_valid() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-
-        circuit.set_circuit_id("".to_string());
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because the circuit ID is empty");
+        if let Err(err) = admin_shared.validate_destroy_circuit("01234-ABCDE", PUB_KEY, "node_a") {
+            panic!("Should have been valid: {}", err);
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has an invalid circuit id an error is returned
-    fn test_validate_circuit_invalid_circuit_id() {
+    // test that a destroy request is invalid if the circuit does not exist or the requester is
+    // not a member of the circuit
+    fn test_validate_destroy_circuit_invalid() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
 
-        circuit.set_circuit_id("invalid_circuit_id".to_string());
+        if let Ok(_) = admin_shared.validate_destroy_circuit("01234-ABCDE", PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to the circuit not existing");
+        }
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because the circuit ID is invalid");
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        if let Ok(_) = admin_shared.validate_destroy_circuit("01234-ABCDE", PUB_KEY, "node_c") {
+            panic!("Should have been invalid due to requester not being a circuit member");
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a member with no endpoints an error is returned
-    fn test_validate_circuit_no_endpoints() {
+    // test that a destroyed circuit, its services and its member nodes are removed from splinter
+    // state
+    fn test_remove_circuit_from_splinter_state() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let mut admin_shared =
+            setup_admin_shared("node_a", orchestrator, peer_connector, state.clone());
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+        assert!(state.has_circuit("01234-ABCDE").unwrap());
+
+        let circuit_proto = admin_shared
+            .get_circuit_proto("01234-ABCDE")
+            .expect("Unable to get circuit");
+        assert_eq!(circuit_proto.get_members().len(), 2);
+        assert_eq!(circuit_proto.get_roster().len(), 2);
+
+        admin_shared
+            .remove_circuit_from_splinter_state(&circuit_proto)
+            .expect("Unable to remove circuit");
+
+        assert!(!state.has_circuit("01234-ABCDE").unwrap());
+        assert!(state.node("node_a").unwrap().is_none());
+        assert!(state.node("node_b").unwrap().is_none());
+        assert!(admin_shared
+            .admin_store
+            .get_circuit("01234-ABCDE")
+            .unwrap()
+            .is_none());
 
-        let mut node_a = SplinterNode::new();
-        node_a.set_node_id("node_a".to_string());
-        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
+        shutdown(mesh, cm, pm);
+    }
 
-        let mut node_b = SplinterNode::new();
-        node_b.set_node_id("node_b".to_string());
-        node_b.set_endpoints(vec![].into());
+    #[test]
+    // test that abandoning a circuit marks it as abandoned, removes its services from the
+    // service directory and that the circuit cannot be abandoned again or on behalf of another
+    // node
+    fn test_abandon_circuit() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b]));
+        let mut admin_shared =
+            setup_admin_shared("node_a", orchestrator, peer_connector, state.clone());
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+        let remote_service = ServiceId::new("01234-ABCDE".into(), "ABCD".into());
+        assert!(state.has_service(&remote_service).unwrap());
+
+        if let Ok(_) = admin_shared.validate_abandon_circuit("01234-ABCDE", PUB_KEY, "node_b") {
+            panic!("Should have been invalid due to requester not being the local node");
+        }
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because a member has no endpoints");
+        admin_shared
+            .validate_abandon_circuit("01234-ABCDE", PUB_KEY, "node_a")
+            .expect("Should have been valid");
+        admin_shared
+            .abandon_circuit("01234-ABCDE", PUB_KEY.to_vec())
+            .expect("Unable to abandon circuit");
+
+        let abandoned = state
+            .circuit("01234-ABCDE")
+            .unwrap()
+            .expect("Circuit should still be in state");
+        assert_eq!(abandoned.circuit_status(), &CircuitStatus::Abandoned);
+        assert!(!state.has_service(&remote_service).unwrap());
+
+        if let Ok(_) = admin_shared.validate_abandon_circuit("01234-ABCDE", PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to the circuit already being abandoned");
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a member with an empty endpoint an error is returned
-    fn test_validate_circuit_empty_endpoint() {
+    // test that an abandon notification is only accepted from the admin service of the member
+    // that abandoned the circuit
+    fn test_handle_member_abandoned_sender() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-
-        let mut node_a = SplinterNode::new();
-        node_a.set_node_id("node_a".to_string());
-        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
+        let mut admin_shared =
+            setup_admin_shared("node_a", orchestrator, peer_connector, state.clone());
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+        let remote_service = ServiceId::new("01234-ABCDE".into(), "ABCD".into());
 
-        let mut node_b = SplinterNode::new();
-        node_b.set_node_id("node_b".to_string());
-        node_b.set_endpoints(vec!["".to_string()].into());
+        admin_shared
+            .handle_member_abandoned("01234-ABCDE", "node_b", &admin_service_id("node_a"))
+            .expect("Unable to handle forged abandon notification");
+        assert!(state.has_service(&remote_service).unwrap());
 
-        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b]));
+        admin_shared
+            .handle_member_abandoned("01234-ABCDE", "node_b", &admin_service_id("node_b"))
+            .expect("Unable to handle abandon notification");
+        assert!(!state.has_service(&remote_service).unwrap());
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because a member has an empty endpoint");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit has a member with a duplicate endpoint an error is returned
-    fn test_validate_circuit_duplicate_endpoint() {
+    // test that the admin circuit's queue reaching its size limit is reported to the subscribers
+    // of the pending proposals that include the member
+    fn test_handle_admin_queue_full() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        admin_shared
+            .add_proposal(setup_test_proposal(&setup_test_circuit()))
+            .expect("Unable to add proposal");
+
+        admin_shared
+            .handle_durable_queue_full("admin", "node_c", 1024)
+            .expect("Unable to handle queue full for a node without proposals");
+        admin_shared
+            .handle_durable_queue_full("admin", "node_b", 1024)
+            .expect("Unable to handle queue full");
+
+        let events = admin_shared
+            .get_events_since_id(0, "test_circuit")
+            .expect("Unable to get events")
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        match events[0].event() {
+            messages::AdminServiceEvent::DurableQueueFull((proposal, overflow)) => {
+                assert_eq!(proposal.circuit_id, "01234-ABCDE");
+                assert_eq!(overflow.node_id, "node_b");
+                assert_eq!(overflow.size_limit, 1024);
+            }
+            event => panic!("Unexpected event: {:?}", event),
+        }
 
-        let mut node_a = SplinterNode::new();
-        node_a.set_node_id("node_a".to_string());
-        node_a.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
+        shutdown(mesh, cm, pm);
+    }
 
-        let mut node_b = SplinterNode::new();
-        node_b.set_node_id("node_b".to_string());
-        node_b.set_endpoints(vec!["test://endpoint_a:0".to_string()].into());
+    #[test]
+    // test that a roster update adding a new service and removing an existing one is valid and
+    // produces the updated circuit
+    fn test_validate_update_roster_valid() {
+        let state = setup_splinter_state();
+        let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
+        let orchestrator = setup_orchestrator();
 
-        circuit.set_members(RepeatedField::from_vec(vec![node_a, node_b]));
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut new_service = splinter_service("EFGH", "type_a");
+        new_service.set_allowed_nodes(RepeatedField::from_vec(vec!["node_b".to_string()]));
+        let mut request = CircuitUpdateRosterRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_add_services(RepeatedField::from_vec(vec![new_service]));
+        request.set_remove_services(RepeatedField::from_vec(vec![splinter_service(
+            "ABCD", "type_a",
+        )]));
+
+        let updated_circuit = admin_shared
+            .validate_update_roster(&request, PUB_KEY, "node_a")
+            .expect("Should have been valid");
+        let service_ids = updated_circuit
+            .get_roster()
+            .iter()
+            .map(|service| service.get_service_id())
+            .collect::<Vec<_>>();
+        assert_eq!(service_ids, vec!["0123", "EFGH"]);
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because a member has a duplicate endpoint");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit does not have authorization set an error is returned
-    fn test_validate_circuit_no_authorization() {
+    // test that a roster update is invalid if it removes a service that is not in the roster, adds
+    // a service that is already in the roster or leaves the roster empty
+    fn test_validate_update_roster_invalid() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut request = CircuitUpdateRosterRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_remove_services(RepeatedField::from_vec(vec![splinter_service(
+            "WXYZ", "type_a",
+        )]));
+        if let Ok(_) = admin_shared.validate_update_roster(&request, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to removing an unknown service");
+        }
 
-        circuit.set_authorization_type(Circuit_AuthorizationType::UNSET_AUTHORIZATION_TYPE);
+        let mut existing_service = splinter_service("ABCD", "type_a");
+        existing_service.set_allowed_nodes(RepeatedField::from_vec(vec!["node_b".to_string()]));
+        let mut request = CircuitUpdateRosterRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_add_services(RepeatedField::from_vec(vec![existing_service]));
+        if let Ok(_) = admin_shared.validate_update_roster(&request, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to adding a duplicate service");
+        }
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because authorizaiton type is unset");
+        let mut request = CircuitUpdateRosterRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_remove_services(RepeatedField::from_vec(vec![
+            splinter_service("0123", "type_a"),
+            splinter_service("ABCD", "type_a"),
+        ]));
+        if let Ok(_) = admin_shared.validate_update_roster(&request, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to an empty roster");
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit does not have persistence set an error is returned
-    fn test_validate_circuit_no_persitance() {
+    // test that an accepted roster update is committed to splinter state, the service directory
+    // and the routing table
+    #[cfg(feature = "routing-table")]
+    fn test_update_circuit_roster() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
+        let routing_table = RoutingTable::default();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-
-        circuit.set_persistence(Circuit_PersistenceType::UNSET_PERSISTENCE_TYPE);
+        let mut admin_shared =
+            setup_admin_shared("node_a", orchestrator, peer_connector, state.clone());
+        admin_shared.set_routing_table_writer(Some(Box::new(routing_table.clone())));
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut new_service = splinter_service("EFGH", "type_a");
+        new_service.set_allowed_nodes(RepeatedField::from_vec(vec!["node_b".to_string()]));
+        let mut request = CircuitUpdateRosterRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_add_services(RepeatedField::from_vec(vec![new_service]));
+        request.set_remove_services(RepeatedField::from_vec(vec![splinter_service(
+            "ABCD", "type_a",
+        )]));
+        let updated_circuit = admin_shared
+            .validate_update_roster(&request, PUB_KEY, "node_a")
+            .expect("Should have been valid");
+
+        let mut proposal = setup_test_proposal(&updated_circuit);
+        proposal.set_proposal_type(CircuitProposal_ProposalType::UPDATE_ROSTER);
+        admin_shared
+            .update_circuit_roster(proposal, PUB_KEY.to_vec())
+            .expect("Unable to update roster");
+
+        let state_circuit = state
+            .circuit("01234-ABCDE")
+            .unwrap()
+            .expect("Circuit should be in state");
+        assert!(state_circuit.roster().contains("EFGH"));
+        assert!(!state_circuit.roster().contains("ABCD"));
+
+        let added = ServiceId::new("01234-ABCDE".into(), "EFGH".into());
+        let removed = ServiceId::new("01234-ABCDE".into(), "ABCD".into());
+        assert!(state.has_service(&added).unwrap());
+        assert!(!state.has_service(&removed).unwrap());
+
+        assert!(routing_table
+            .get_service(&RoutingServiceId::new("01234-ABCDE".into(), "EFGH".into()))
+            .unwrap()
+            .is_some());
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because persistence type is unset");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit does not have durability set an error is returned
-    fn test_validate_circuit_unset_durability() {
+    // test that adding a node that is not yet a member of the circuit is valid and that the new
+    // node does not vote on its own addition
+    fn test_validate_add_node_valid() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut node_c = SplinterNode::new();
+        node_c.set_node_id("node_c".to_string());
+        node_c.set_endpoints(vec!["test://endpoint_c:0".to_string()].into());
+        let mut request = CircuitUpdateAddNodeRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_node(node_c);
+
+        let updated_circuit = admin_shared
+            .validate_add_node(&request, PUB_KEY, "node_a")
+            .expect("Should have been valid");
+        let member_ids = updated_circuit
+            .get_members()
+            .iter()
+            .map(|member| member.get_node_id())
+            .collect::<Vec<_>>();
+        assert_eq!(member_ids, vec!["node_a", "node_b", "node_c"]);
 
-        circuit.set_durability(Circuit_DurabilityType::UNSET_DURABILITY_TYPE);
+        let mut proposal = setup_test_proposal(&updated_circuit);
+        proposal.set_proposal_type(CircuitProposal_ProposalType::ADD_NODE);
+        let mut vote_record = CircuitProposal_VoteRecord::new();
+        vote_record.set_vote(CircuitProposalVote_Vote::ACCEPT);
+        vote_record.set_public_key(b"test_signer_a".to_vec());
+        vote_record.set_voter_node_id("node_a".to_string());
+        proposal.set_votes(RepeatedField::from_vec(vec![vote_record]));
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because durabilty type is unset");
+        match admin_shared.check_approved(&proposal) {
+            Ok(CircuitProposalStatus::Accepted) => (),
+            _ => panic!("Proposal should have been accepted"),
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit does not have route type set an error is returned
-    fn test_validate_circuit_no_routes() {
+    // test that adding a node is invalid if the node is already a member or has no endpoints
+    fn test_validate_add_node_invalid() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
 
-        circuit.set_routes(Circuit_RouteType::UNSET_ROUTE_TYPE);
+        let mut node_b = SplinterNode::new();
+        node_b.set_node_id("node_b".to_string());
+        node_b.set_endpoints(vec!["test://endpoint_b:0".to_string()].into());
+        let mut request = CircuitUpdateAddNodeRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_node(node_b);
+        if let Ok(_) = admin_shared.validate_add_node(&request, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to adding an existing member");
+        }
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because route type is unset");
+        let mut node_c = SplinterNode::new();
+        node_c.set_node_id("node_c".to_string());
+        let mut request = CircuitUpdateAddNodeRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_node(node_c);
+        if let Ok(_) = admin_shared.validate_add_node(&request, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to the new node missing endpoints");
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if a circuit does not have circuit_management_type set an error is returned
-    fn test_validate_circuit_no_management_type() {
+    // test that removing a member drops the member and the services it was allowed to run
+    fn test_validate_remove_node_valid() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let mut circuit = setup_test_circuit();
-
-        circuit.set_circuit_management_type("".to_string());
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut request = CircuitUpdateRemoveNodeRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_node_id("node_b".into());
+
+        let updated_circuit = admin_shared
+            .validate_remove_node(&request, PUB_KEY, "node_a")
+            .expect("Should have been valid");
+        let member_ids = updated_circuit
+            .get_members()
+            .iter()
+            .map(|member| member.get_node_id())
+            .collect::<Vec<_>>();
+        assert_eq!(member_ids, vec!["node_a"]);
+        let service_ids = updated_circuit
+            .get_roster()
+            .iter()
+            .map(|service| service.get_service_id())
+            .collect::<Vec<_>>();
+        assert_eq!(service_ids, vec!["0123"]);
 
-        if let Ok(_) = admin_shared.validate_create_circuit(&circuit, PUB_KEY, "node_a") {
-            panic!("Should have been invalid because route type is unset");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that a valid circuit proposal vote comes back as valid
-    fn test_validate_proposal_vote_valid() {
+    // test that removing a node is invalid if the node is not a member or is the requester
+    fn test_validate_remove_node_invalid() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let circuit = setup_test_circuit();
-        let vote = setup_test_vote(&circuit);
-        let proposal = setup_test_proposal(&circuit);
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        let circuit = setup_test_circuit();
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut request = CircuitUpdateRemoveNodeRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_node_id("node_c".into());
+        if let Ok(_) = admin_shared.validate_remove_node(&request, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to removing an unknown member");
+        }
 
-        if let Err(err) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
-            panic!("Should have been valid: {}", err);
+        let mut request = CircuitUpdateRemoveNodeRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_node_id("node_a".into());
+        if let Ok(_) = admin_shared.validate_remove_node(&request, PUB_KEY, "node_a") {
+            panic!("Should have been invalid due to the requester removing itself");
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if the vote is from a key that is not permitted for the voting node the vote is
-    // invalid
-    fn test_validate_proposal_vote_not_permitted() {
+    // test that an accepted member removal is committed to splinter state, the service directory
+    // and the routing table
+    fn test_remove_circuit_member() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::new(false)),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
+        let mut admin_shared =
+            setup_admin_shared("node_a", orchestrator, peer_connector, state.clone());
+        #[cfg(feature = "routing-table")]
+        let routing_table = RoutingTable::default();
+        #[cfg(feature = "routing-table")]
+        admin_shared.set_routing_table_writer(Some(Box::new(routing_table.clone())));
         let circuit = setup_test_circuit();
-        let vote = setup_test_vote(&circuit);
-        let proposal = setup_test_proposal(&circuit);
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut request = CircuitUpdateRemoveNodeRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_node_id("node_b".into());
+        let updated_circuit = admin_shared
+            .validate_remove_node(&request, PUB_KEY, "node_a")
+            .expect("Should have been valid");
+
+        let mut proposal = setup_test_proposal(&updated_circuit);
+        proposal.set_proposal_type(CircuitProposal_ProposalType::REMOVE_NODE);
+        admin_shared
+            .remove_circuit_member(proposal, PUB_KEY.to_vec())
+            .expect("Unable to remove member");
+
+        let state_circuit = state
+            .circuit("01234-ABCDE")
+            .unwrap()
+            .expect("Circuit should be in state");
+        assert_eq!(state_circuit.members().to_vec(), vec!["node_a".to_string()]);
+        assert!(!state_circuit.roster().contains("ABCD"));
+        assert!(!state
+            .has_service(&ServiceId::new("01234-ABCDE".into(), "ABCD".into()))
+            .unwrap());
+        assert!(state.node("node_b").unwrap().is_none());
+
+        #[cfg(feature = "routing-table")]
+        assert_eq!(
+            routing_table.get_circuit("01234-ABCDE").unwrap(),
+            Some(RoutingCircuit::new(
+                "01234-ABCDE".into(),
+                vec![RoutingService::new(
+                    "0123".into(),
+                    "type_a".into(),
+                    vec!["node_a".into()],
+                    vec![],
+                )],
+                vec!["node_a".into()],
+            ))
+        );
 
-        if let Ok(_) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
-            panic!("Should have been invalid because voting node is not registered");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test if the voter is the original requester node the vote is invalid
-    fn test_validate_proposal_vote_requester() {
+    // test that a node only joins a circuit for an addition it has committed, when the
+    // definition matches the proposal and is sent by an existing member
+    fn test_handle_circuit_join() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-        let circuit = setup_test_circuit();
-        let vote = setup_test_vote(&circuit);
-        let proposal = setup_test_proposal(&circuit);
+        let mut admin_shared =
+            setup_admin_shared("node_c", orchestrator, peer_connector, state.clone());
 
-        if let Ok(_) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_b") {
-            panic!("Should have been invalid because voter is the requester");
+        let mut node_c = SplinterNode::new();
+        node_c.set_node_id("node_c".to_string());
+        node_c.set_endpoints(vec!["test://endpoint_c:0".to_string()].into());
+        let mut circuit = setup_test_circuit();
+        let mut members = circuit.take_members().into_vec();
+        members.push(node_c.clone());
+        circuit.set_members(RepeatedField::from_vec(members));
+
+        // no addition has been committed
+        admin_shared
+            .handle_circuit_join(circuit.clone(), &admin_service_id("node_a"))
+            .expect("Unable to handle join request");
+        assert!(!state.has_circuit("01234-ABCDE").unwrap());
+
+        let mut request = CircuitUpdateAddNodeRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_node(node_c);
+        assert!(
+            admin_shared
+                .validate_add_node(&request, PUB_KEY, "node_a")
+                .is_err(),
+            "Should have been invalid due to the circuit definition not being received"
+        );
+        admin_shared
+            .joining_circuits
+            .insert("01234-ABCDE".into(), circuit.clone());
+        let proposed_circuit = admin_shared
+            .validate_add_node(&request, PUB_KEY, "node_a")
+            .expect("Should have been valid");
+        assert_eq!(proposed_circuit, circuit);
+
+        let mut proposal = setup_test_proposal(&proposed_circuit);
+        proposal.set_proposal_type(CircuitProposal_ProposalType::ADD_NODE);
+        match admin_shared.check_approved(&proposal) {
+            Ok(CircuitProposalStatus::Pending) => (),
+            _ => panic!("Proposal should be pending on the new node"),
         }
+        admin_shared
+            .add_proposal(proposal)
+            .expect("Unable to add proposal");
+
+        // the definition must match the committed proposal
+        let mut altered_circuit = circuit.clone();
+        altered_circuit.set_comments("altered".into());
+        admin_shared
+            .handle_circuit_join(altered_circuit, &admin_service_id("node_a"))
+            .expect("Unable to handle join request");
+        assert!(!state.has_circuit("01234-ABCDE").unwrap());
+
+        // the sender must be an existing member
+        admin_shared
+            .handle_circuit_join(circuit.clone(), &admin_service_id("node_d"))
+            .expect("Unable to handle join request");
+        assert!(!state.has_circuit("01234-ABCDE").unwrap());
+
+        admin_shared
+            .handle_circuit_join(circuit, &admin_service_id("node_b"))
+            .expect("Unable to handle join request");
+        assert!(state.has_circuit("01234-ABCDE").unwrap());
+        assert!(!admin_shared.has_proposal("01234-ABCDE"));
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test if a voter has already voted on a proposal the new vote is invalid
-    fn test_validate_proposal_vote_duplicate_vote() {
+    // test that an application metadata update replaces the metadata and comments of the circuit
+    // and that an update that changes neither is invalid
+    fn test_validate_update_application_metadata() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
         let circuit = setup_test_circuit();
-        let vote = setup_test_vote(&circuit);
-        let mut proposal = setup_test_proposal(&circuit);
-
-        let mut vote_record = CircuitProposal_VoteRecord::new();
-        vote_record.set_vote(CircuitProposalVote_Vote::ACCEPT);
-        vote_record.set_public_key(b"test_signer_a".to_vec());
-        vote_record.set_voter_node_id("node_a".to_string());
-
-        proposal.set_votes(RepeatedField::from_vec(vec![vote_record]));
-
-        if let Ok(_) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
-            panic!("Should have been invalid because node as already submited a vote");
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut request = CircuitUpdateApplicationMetadataRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_application_metadata(b"new metadata".to_vec());
+        request.set_comments("new comments".into());
+
+        let updated_circuit = admin_shared
+            .validate_update_application_metadata(&request, PUB_KEY, "node_a")
+            .expect("Should have been valid");
+        assert_eq!(updated_circuit.get_application_metadata(), b"new metadata");
+        assert_eq!(updated_circuit.get_comments(), "new comments");
+        assert_eq!(updated_circuit.get_roster(), circuit.get_roster());
+
+        let mut request = CircuitUpdateApplicationMetadataRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_application_metadata(circuit.get_application_metadata().to_vec());
+        request.set_comments(circuit.get_comments().into());
+        if let Ok(_) =
+            admin_shared.validate_update_application_metadata(&request, PUB_KEY, "node_a")
+        {
+            panic!("Should have been invalid due to not changing the circuit");
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that if the circuit hash in the circuit proposal does not match the circuit hash on
-    // the vote, the vote is invalid
-    fn test_validate_proposal_vote_circuit_hash_mismatch() {
+    // test that an accepted application metadata update is committed to splinter state
+    fn test_update_circuit_application_metadata() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let admin_shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
+        let mut admin_shared =
+            setup_admin_shared("node_a", orchestrator, peer_connector, state.clone());
         let circuit = setup_test_circuit();
-        let vote = setup_test_vote(&circuit);
-        let mut proposal = setup_test_proposal(&circuit);
-
-        proposal.set_circuit_hash("bad_hash".to_string());
+        admin_shared
+            .update_splinter_state(&circuit)
+            .expect("Unable to add circuit to splinter state");
+
+        let mut request = CircuitUpdateApplicationMetadataRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_application_metadata(b"new metadata".to_vec());
+        request.set_comments("new comments".into());
+        let updated_circuit = admin_shared
+            .validate_update_application_metadata(&request, PUB_KEY, "node_a")
+            .expect("Should have been valid");
+
+        let mut proposal = setup_test_proposal(&updated_circuit);
+        proposal.set_proposal_type(CircuitProposal_ProposalType::UPDATE_APPLICATION_METADATA);
+        admin_shared
+            .update_circuit_application_metadata(proposal, PUB_KEY.to_vec())
+            .expect("Unable to update application metadata");
+
+        let state_circuit = state
+            .circuit("01234-ABCDE")
+            .unwrap()
+            .expect("Circuit should be in state");
+        assert_eq!(state_circuit.application_metadata(), b"new metadata");
+        assert_eq!(state_circuit.comments(), "new comments");
+        assert_eq!(
+            admin_shared
+                .get_circuit_proto("01234-ABCDE")
+                .expect("Unable to get circuit"),
+            updated_circuit
+        );
 
-        if let Ok(_) = admin_shared.validate_circuit_vote(&vote, PUB_KEY, &proposal, "node_a") {
-            panic!("Should have been invalid because the circuit hash does not match");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that the validate_circuit_management_payload method returns an error in case the
-    // signature is empty.
-    fn test_validate_circuit_management_payload_signature() {
+    // test that a proposal whose time-to-live has elapsed is removed and a ProposalExpired event
+    // is sent, while a proposal without a time-to-live is kept
+    fn test_expire_proposals() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
 
-        let circuit = setup_test_circuit();
+        let mut expiring_proposal = setup_test_proposal(&setup_test_circuit());
+        expiring_proposal.set_expires_at(1);
+        admin_shared
+            .add_proposal(expiring_proposal)
+            .expect("Unable to add expiring proposal");
 
-        let mut request = admin::CircuitCreateRequest::new();
-        request.set_circuit(circuit);
+        let mut circuit = setup_test_circuit();
+        circuit.set_circuit_id("56789-FGHIJ".into());
+        admin_shared
+            .add_proposal(setup_test_proposal(&circuit))
+            .expect("Unable to add proposal");
 
-        let mut header = admin::CircuitManagementPayload_Header::new();
-        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
-        header.set_requester(PUB_KEY.into());
-        header.set_requester_node_id("node_b".to_string());
-        let mut payload = admin::CircuitManagementPayload::new();
-        payload.set_signature(Vec::new());
-        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
-        payload.set_circuit_create_request(request);
+        admin_shared
+            .expire_proposals()
+            .expect("Unable to expire proposals");
 
-        // Asserting the payload will be deemed invalid as the signature is an empty vec.
-        if let Ok(_) = shared.validate_circuit_management_payload(&payload, &header) {
-            panic!("Should have been invalid due to empty signature");
-        }
+        assert!(!admin_shared.has_proposal("01234-ABCDE"));
+        assert!(admin_shared.has_proposal("56789-FGHIJ"));
 
-        payload.set_signature(HashSigner.sign(&payload.header).unwrap());
-        // Asserting the payload passed through validation.
-        if let Err(_) = shared.validate_circuit_management_payload(&payload, &header) {
-            panic!("Should have been valid");
+        let events = admin_shared
+            .get_events_since_id(0, "test_circuit")
+            .expect("Unable to get events")
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        match events[0].event() {
+            messages::AdminServiceEvent::ProposalExpired(proposal) => {
+                assert_eq!(proposal.circuit_id, "01234-ABCDE")
+            }
+            event => panic!("Unexpected event: {:?}", event),
         }
+
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that the validate_circuit_management_payload method returns an error in case the
-    // header is empty.
-    fn test_validate_circuit_management_payload_header() {
+    // test that a proposed change uses the expiration time set by the coordinator, if there is
+    // one, rather than computing it from the node's default time-to-live
+    fn test_propose_change_expiration() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-
-        let circuit = setup_test_circuit();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
+        admin_shared.set_default_proposal_ttl(Some(Duration::from_secs(60)));
+        admin_shared
+            .update_splinter_state(&setup_test_circuit())
+            .expect("Unable to add circuit to splinter state");
 
-        let mut request = admin::CircuitCreateRequest::new();
-        request.set_circuit(circuit);
+        let mut request = CircuitUpdateApplicationMetadataRequest::new();
+        request.set_circuit_id("01234-ABCDE".into());
+        request.set_application_metadata(b"new metadata".to_vec());
+        request.set_comments("new comments".into());
 
         let mut header = admin::CircuitManagementPayload_Header::new();
-        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
+        header.set_action(
+            admin::CircuitManagementPayload_Action::CIRCUIT_UPDATE_APPLICATION_METADATA_REQUEST,
+        );
         header.set_requester(PUB_KEY.into());
-        header.set_requester_node_id("node_b".to_string());
+        header.set_requester_node_id("node_a".to_string());
         let mut payload = admin::CircuitManagementPayload::new();
+        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
         payload.set_signature(HashSigner.sign(&payload.header).unwrap());
-        payload.set_circuit_create_request(request);
+        payload.set_circuit_update_application_metadata_request(request);
+
+        let (_, proposal) = admin_shared
+            .propose_change(payload.clone(), Some(12345))
+            .expect("Unable to propose change");
+        assert_eq!(proposal.get_expires_at(), 12345);
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Unable to get time")
+            .as_secs();
+        let (_, proposal) = admin_shared
+            .propose_change(payload, None)
+            .expect("Unable to propose change");
+        assert!(proposal.get_expires_at() >= now + 60);
 
-        // Asserting the payload will be deemed invalid as the header is empty.
-        match shared.validate_circuit_management_payload(&payload, &header) {
-            Err(err) => assert!(err
-                .to_string()
-                .contains("CircuitManagementPayload header must be set")),
-            _ => panic!("Should have been invalid because empty requester field"),
-        }
-        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
-        // Asserting the payload passed through validation, and failed at a further step.
-        if let Err(_) = shared.validate_circuit_management_payload(&payload, &header) {
-            panic!("Should have been valid");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that the validate_circuit_management_payload method returns an error in case the header
-    // requester field is empty.
-    fn test_validate_circuit_management_header_requester() {
+    // test that committing a cancellation removes the proposal, regardless of its votes, and
+    // sends a ProposalCancelled event
+    fn test_commit_cancel_proposal() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
+        let mut admin_shared = setup_admin_shared("node_a", orchestrator, peer_connector, state);
 
-        let circuit = setup_test_circuit();
+        let proposal = setup_test_proposal(&setup_test_circuit());
+        admin_shared
+            .add_proposal(proposal.clone())
+            .expect("Unable to add proposal");
 
-        let mut request = admin::CircuitCreateRequest::new();
-        request.set_circuit(circuit);
+        admin_shared.pending_changes = Some(CircuitProposalContext {
+            circuit_proposal: proposal,
+            signer_public_key: b"test_signer_b".to_vec(),
+            action: CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_CANCEL,
+        });
+        admin_shared
+            .commit()
+            .expect("Unable to commit cancellation");
 
-        let mut header = admin::CircuitManagementPayload_Header::new();
-        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
-        header.set_requester_node_id("node_b".to_string());
-        let mut payload = admin::CircuitManagementPayload::new();
-        payload.set_signature(HashSigner.sign(&payload.header).unwrap());
-        payload.set_circuit_create_request(request);
+        assert!(!admin_shared.has_proposal("01234-ABCDE"));
 
-        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
-        // Asserting the payload will be deemed invalid as the header is empty.
-        match shared.validate_circuit_management_payload(&payload, &header) {
-            Err(err) => assert!(err
-                .to_string()
-                .contains("CircuitManagementPayload must have a requester")),
-            _ => panic!("Should have been invalid because empty requester field"),
+        let events = admin_shared
+            .get_events_since_id(0, "test_circuit")
+            .expect("Unable to get events")
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 1);
+        match events[0].event() {
+            messages::AdminServiceEvent::ProposalCancelled((proposal, signer)) => {
+                assert_eq!(proposal.circuit_id, "01234-ABCDE");
+                assert_eq!(signer, b"test_signer_b");
+            }
+            event => panic!("Unexpected event: {:?}", event),
         }
 
-        header.set_requester(PUB_KEY.into());
-        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
-        // Asserting the payload passed through validation, and failed at a further step.
-        if let Err(_) = shared.validate_circuit_management_payload(&payload, &header) {
-            panic!("Should have been valid");
-        }
         shutdown(mesh, cm, pm);
     }
 
     #[test]
-    // test that the CircuitManagementPayload returns an error in case the header requester_node_id
-    // field is empty.
-    fn test_validate_circuit_management_header_requester_node_id() {
+    // test that cancelling a proposal releases the peer refs held for its other members, and
+    // leaves the refs held for the local node unchanged
+    fn test_cancel_proposal_peer_refs() {
         let state = setup_splinter_state();
         let (mesh, cm, pm, peer_connector) = setup_peer_connector(None);
         let orchestrator = setup_orchestrator();
 
-        let shared = AdminServiceShared::new(
-            "node_a".into(),
-            Arc::new(Mutex::new(orchestrator)),
-            #[cfg(feature = "service-arg-validation")]
-            HashMap::new(),
-            peer_connector,
-            state,
-            Box::new(HashVerifier),
-            Box::new(MockAdminKeyVerifier::default()),
-            Box::new(AllowAllKeyPermissionManager),
-            "memory",
-            STATE_DIR,
-        )
-        .unwrap();
-
-        let circuit = setup_test_circuit();
+        let mut admin_shared =
+            setup_admin_shared("node_a", orchestrator, peer_connector.clone(), state);
+        for node_id in &["node_a", "node_b"] {
+            let peer_ref = peer_connector
+                .add_peer_ref(node_id.to_string(), vec!["inproc://someplace:8000".into()])
+                .expect("Unable to add peer ref");
+            admin_shared.add_peer_ref(peer_ref);
+        }
 
-        let mut request = admin::CircuitCreateRequest::new();
-        request.set_circuit(circuit);
+        let proposal = setup_test_proposal(&setup_test_circuit());
+        admin_shared
+            .add_proposal(proposal.clone())
+            .expect("Unable to add proposal");
 
-        let mut header = admin::CircuitManagementPayload_Header::new();
-        header.set_action(admin::CircuitManagementPayload_Action::CIRCUIT_CREATE_REQUEST);
-        header.set_requester(PUB_KEY.into());
-        let mut payload = admin::CircuitManagementPayload::new();
-        payload.set_signature(HashSigner.sign(&payload.header).unwrap());
-        payload.set_circuit_create_request(request);
+        admin_shared.pending_changes = Some(CircuitProposalContext {
+            circuit_proposal: proposal,
+            signer_public_key: b"test_signer_b".to_vec(),
+            action: CircuitManagementPayload_Action::CIRCUIT_PROPOSAL_CANCEL,
+        });
+        admin_shared
+            .commit()
+            .expect("Unable to commit cancellation");
 
-        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
-        // Asserting the payload will be deemed invalid as the header is empty.
-        match shared.validate_circuit_management_payload(&payload, &header) {
-            Err(err) => assert!(err
-                .to_string()
-                .contains("CircuitManagementPayload must have a requester node id")),
-            _ => panic!("Should have been invalid because empty requester field"),
-        }
+        assert_eq!(admin_shared.peer_refs.get("node_a").map(Vec::len), Some(1));
+        assert!(admin_shared.peer_refs.get("node_b").is_none());
 
-        header.set_requester_node_id("node_b".to_string());
-        payload.set_header(protobuf::Message::write_to_bytes(&header).unwrap());
-        // Asserting the payload passed through validation, and failed at a further step.
-        if let Err(_) = shared.validate_circuit_management_payload(&payload, &header) {
-            panic!("Should have been valid");
-        }
         shutdown(mesh, cm, pm);
     }
 
@@ -3503,6 +6534,44 @@ mod tests {
         orchestrator
     }
 
+    /// Creates an `AdminServiceShared` backed by an in-memory store that accepts all keys.
+    fn setup_admin_shared(
+        node_id: &str,
+        orchestrator: ServiceOrchestrator,
+        peer_connector: PeerManagerConnector,
+        state: SplinterState,
+    ) -> AdminServiceShared {
+        setup_admin_shared_with_key_verifier(
+            node_id,
+            orchestrator,
+            peer_connector,
+            state,
+            Box::new(MockAdminKeyVerifier::default()),
+        )
+    }
+
+    fn setup_admin_shared_with_key_verifier(
+        node_id: &str,
+        orchestrator: ServiceOrchestrator,
+        peer_connector: PeerManagerConnector,
+        state: SplinterState,
+        key_verifier: Box<dyn AdminKeyVerifier>,
+    ) -> AdminServiceShared {
+        AdminServiceShared::new(
+            node_id.into(),
+            Arc::new(Mutex::new(orchestrator)),
+            #[cfg(feature = "service-arg-validation")]
+            HashMap::new(),
+            peer_connector,
+            state,
+            Box::new(HashVerifier),
+            key_verifier,
+            Box::new(AllowAllKeyPermissionManager),
+            Box::new(MemoryAdminServiceStore::new()),
+        )
+        .expect("failed to create admin service shared")
+    }
+
     fn splinter_node(node_id: &str, endpoints: &[String]) -> admin::SplinterNode {
         let mut node = admin::SplinterNode::new();
         node.set_node_id(node_id.into());
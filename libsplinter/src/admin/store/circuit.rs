@@ -14,7 +14,10 @@
 
 //! Structs for building circuits
 
-use crate::admin::messages::is_valid_circuit_id;
+use std::convert::TryFrom;
+
+use crate::admin::messages::{self, is_valid_circuit_id};
+use crate::circuit;
 
 use super::error::BuilderError;
 use super::{ProposedCircuit, Service};
@@ -30,6 +33,10 @@ pub struct Circuit {
     durability: DurabilityType,
     routes: RouteType,
     circuit_management_type: String,
+    circuit_status: CircuitStatus,
+    application_metadata: Vec<u8>,
+    comments: String,
+    voting_policy: VotingPolicy,
 }
 
 impl Circuit {
@@ -72,6 +79,46 @@ impl Circuit {
     pub fn circuit_management_type(&self) -> &str {
         &self.circuit_management_type
     }
+
+    /// Returns the status of the circuit from the point of view of the local node
+    pub fn circuit_status(&self) -> &CircuitStatus {
+        &self.circuit_status
+    }
+
+    /// Returns the application metadata of the circuit
+    pub fn application_metadata(&self) -> &[u8] {
+        &self.application_metadata
+    }
+
+    /// Returns the comments describing the circuit
+    pub fn comments(&self) -> &str {
+        &self.comments
+    }
+
+    /// Returns the policy used to decide whether a proposal for the circuit has been accepted
+    pub fn voting_policy(&self) -> &VotingPolicy {
+        &self.voting_policy
+    }
+}
+
+/// The status of a circuit from the point of view of the local node
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum CircuitStatus {
+    Active,
+    Abandoned,
+}
+
+impl CircuitStatus {
+    /// Returns true if the circuit is active
+    pub fn is_active(&self) -> bool {
+        *self == CircuitStatus::Active
+    }
+}
+
+impl Default for CircuitStatus {
+    fn default() -> Self {
+        CircuitStatus::Active
+    }
 }
 
 /// What type of authorization the circuit requires
@@ -96,12 +143,16 @@ impl Default for PersistenceType {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum DurabilityType {
     NoDurability,
+    /// Messages for a member that is not connected are stored and delivered when it reconnects
+    Durable,
 }
 
 /// How messages are expected to be routed across a circuit
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum RouteType {
     Any,
+    /// Messages may be relayed through other members of the circuit
+    Relay,
 }
 
 impl Default for RouteType {
@@ -110,6 +161,30 @@ impl Default for RouteType {
     }
 }
 
+/// How many of a circuit's voting members must accept a proposal for it to be accepted
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum VotingPolicy {
+    /// Every voting member must accept
+    Unanimous,
+    /// More than half of the voting members must accept
+    Majority,
+    /// At least the given number of voting members must accept
+    Threshold(u32),
+}
+
+impl VotingPolicy {
+    /// Returns true if every voting member must accept
+    pub fn is_unanimous(&self) -> bool {
+        *self == VotingPolicy::Unanimous
+    }
+}
+
+impl Default for VotingPolicy {
+    fn default() -> Self {
+        VotingPolicy::Unanimous
+    }
+}
+
 /// Builder to be used to build a `Circuit`
 #[derive(Default, Clone)]
 pub struct CircuitBuilder {
@@ -121,6 +196,10 @@ pub struct CircuitBuilder {
     durability: Option<DurabilityType>,
     routes: Option<RouteType>,
     circuit_management_type: Option<String>,
+    circuit_status: Option<CircuitStatus>,
+    application_metadata: Option<Vec<u8>>,
+    comments: Option<String>,
+    voting_policy: Option<VotingPolicy>,
 }
 
 impl CircuitBuilder {
@@ -169,6 +248,26 @@ impl CircuitBuilder {
         self.circuit_management_type.clone()
     }
 
+    /// Returns the circuit status in the builder
+    pub fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.circuit_status.clone()
+    }
+
+    /// Returns the application metadata in the builder
+    pub fn application_metadata(&self) -> Option<Vec<u8>> {
+        self.application_metadata.clone()
+    }
+
+    /// Returns the comments in the builder
+    pub fn comments(&self) -> Option<String> {
+        self.comments.clone()
+    }
+
+    /// Returns the voting policy in the builder
+    pub fn voting_policy(&self) -> Option<VotingPolicy> {
+        self.voting_policy.clone()
+    }
+
     /// Sets the circuit ID
     ///
     /// # Arguments
@@ -252,6 +351,47 @@ impl CircuitBuilder {
         self
     }
 
+    /// Sets the circuit status
+    ///
+    /// # Arguments
+    ///
+    ///  * `circuit_status` - The status of the circuit
+    pub fn with_circuit_status(mut self, circuit_status: &CircuitStatus) -> CircuitBuilder {
+        self.circuit_status = Some(circuit_status.clone());
+        self
+    }
+
+    /// Sets the application metadata
+    ///
+    /// # Arguments
+    ///
+    ///  * `application_metadata` - The application metadata for the circuit
+    pub fn with_application_metadata(mut self, application_metadata: &[u8]) -> CircuitBuilder {
+        self.application_metadata = Some(application_metadata.into());
+        self
+    }
+
+    /// Sets the comments
+    ///
+    /// # Arguments
+    ///
+    ///  * `comments` - The comments describing the circuit
+    pub fn with_comments(mut self, comments: &str) -> CircuitBuilder {
+        self.comments = Some(comments.into());
+        self
+    }
+
+    /// Sets the voting policy
+    ///
+    /// # Arguments
+    ///
+    ///  * `voting_policy` - The policy used to decide whether a proposal for the circuit has been
+    ///    accepted
+    pub fn with_voting_policy(mut self, voting_policy: &VotingPolicy) -> CircuitBuilder {
+        self.voting_policy = Some(voting_policy.clone());
+        self
+    }
+
     /// Builds a `Circuit`
     ///
     /// Returns an error if the circuit ID, roster, members or circuit management
@@ -293,6 +433,21 @@ impl CircuitBuilder {
             .circuit_management_type
             .ok_or_else(|| BuilderError::MissingField("circuit_management_type".to_string()))?;
 
+        let circuit_status = self.circuit_status.unwrap_or_default();
+
+        let application_metadata = self.application_metadata.unwrap_or_default();
+
+        let comments = self.comments.unwrap_or_default();
+
+        let voting_policy = match self.voting_policy.unwrap_or_default() {
+            VotingPolicy::Threshold(0) => {
+                return Err(BuilderError::InvalidField(
+                    "voting_policy threshold must require at least one vote".to_string(),
+                ))
+            }
+            voting_policy => voting_policy,
+        };
+
         let create_circuit_message = Circuit {
             id: circuit_id,
             roster,
@@ -302,6 +457,10 @@ impl CircuitBuilder {
             durability,
             routes,
             circuit_management_type,
+            circuit_status,
+            application_metadata,
+            comments,
+            voting_policy,
         };
 
         Ok(create_circuit_message)
@@ -323,6 +482,262 @@ impl From<ProposedCircuit> for Circuit {
             durability: circuit.durability().clone(),
             routes: circuit.routes().clone(),
             circuit_management_type: circuit.circuit_management_type().into(),
+            circuit_status: CircuitStatus::default(),
+            application_metadata: circuit.application_metadata().into(),
+            comments: circuit.comments().into(),
+            voting_policy: circuit.voting_policy().clone(),
+        }
+    }
+}
+
+impl TryFrom<&circuit::Circuit> for Circuit {
+    type Error = BuilderError;
+
+    fn try_from(circuit: &circuit::Circuit) -> Result<Self, Self::Error> {
+        CircuitBuilder::new()
+            .with_circuit_id(circuit.id())
+            .with_roster(
+                &circuit
+                    .roster()
+                    .iter()
+                    .map(Service::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+            .with_members(&circuit.members().to_vec())
+            .with_authorization_type(&AuthorizationType::from(circuit.auth()))
+            .with_persistence(&PersistenceType::from(circuit.persistence()))
+            .with_durability(&DurabilityType::from(circuit.durability()))
+            .with_routes(&RouteType::from(circuit.routes()))
+            .with_circuit_management_type(circuit.circuit_management_type())
+            .with_circuit_status(&CircuitStatus::from(circuit.circuit_status()))
+            .with_application_metadata(circuit.application_metadata())
+            .with_comments(circuit.comments())
+            .with_voting_policy(&VotingPolicy::from(circuit.voting_policy()))
+            .build()
+    }
+}
+
+impl TryFrom<&Circuit> for circuit::Circuit {
+    type Error = circuit::CircuitBuildError;
+
+    fn try_from(circuit: &Circuit) -> Result<Self, Self::Error> {
+        circuit::Circuit::builder()
+            .with_id(circuit.id.clone())
+            .with_roster(circuit.roster.iter().map(circuit::ServiceDefinition::from))
+            .with_members(circuit.members.clone())
+            .with_auth(circuit::AuthorizationType::from(
+                &circuit.authorization_type,
+            ))
+            .with_persistence(circuit::PersistenceType::from(&circuit.persistence))
+            .with_durability(circuit::DurabilityType::from(&circuit.durability))
+            .with_routes(circuit::RouteType::from(&circuit.routes))
+            .with_circuit_management_type(circuit.circuit_management_type.clone())
+            .with_circuit_status(circuit::CircuitStatus::from(&circuit.circuit_status))
+            .with_application_metadata(circuit.application_metadata.clone())
+            .with_comments(circuit.comments.clone())
+            .with_voting_policy(circuit::VotingPolicy::from(&circuit.voting_policy))
+            .build()
+    }
+}
+
+impl From<&circuit::CircuitStatus> for CircuitStatus {
+    fn from(status: &circuit::CircuitStatus) -> Self {
+        match status {
+            circuit::CircuitStatus::Active => CircuitStatus::Active,
+            circuit::CircuitStatus::Abandoned => CircuitStatus::Abandoned,
+        }
+    }
+}
+
+impl From<&CircuitStatus> for circuit::CircuitStatus {
+    fn from(status: &CircuitStatus) -> Self {
+        match status {
+            CircuitStatus::Active => circuit::CircuitStatus::Active,
+            CircuitStatus::Abandoned => circuit::CircuitStatus::Abandoned,
+        }
+    }
+}
+
+impl From<&messages::AuthorizationType> for AuthorizationType {
+    fn from(authorization_type: &messages::AuthorizationType) -> Self {
+        match authorization_type {
+            messages::AuthorizationType::Trust => AuthorizationType::Trust,
+        }
+    }
+}
+
+impl From<&AuthorizationType> for messages::AuthorizationType {
+    fn from(authorization_type: &AuthorizationType) -> Self {
+        match authorization_type {
+            AuthorizationType::Trust => messages::AuthorizationType::Trust,
+        }
+    }
+}
+
+impl From<&circuit::AuthorizationType> for AuthorizationType {
+    fn from(authorization_type: &circuit::AuthorizationType) -> Self {
+        match authorization_type {
+            circuit::AuthorizationType::Trust => AuthorizationType::Trust,
+        }
+    }
+}
+
+impl From<&AuthorizationType> for circuit::AuthorizationType {
+    fn from(authorization_type: &AuthorizationType) -> Self {
+        match authorization_type {
+            AuthorizationType::Trust => circuit::AuthorizationType::Trust,
+        }
+    }
+}
+
+impl From<&messages::PersistenceType> for PersistenceType {
+    fn from(persistence: &messages::PersistenceType) -> Self {
+        match persistence {
+            messages::PersistenceType::Any => PersistenceType::Any,
+        }
+    }
+}
+
+impl From<&PersistenceType> for messages::PersistenceType {
+    fn from(persistence: &PersistenceType) -> Self {
+        match persistence {
+            PersistenceType::Any => messages::PersistenceType::Any,
+        }
+    }
+}
+
+impl From<&circuit::PersistenceType> for PersistenceType {
+    fn from(persistence: &circuit::PersistenceType) -> Self {
+        match persistence {
+            circuit::PersistenceType::Any => PersistenceType::Any,
+        }
+    }
+}
+
+impl From<&PersistenceType> for circuit::PersistenceType {
+    fn from(persistence: &PersistenceType) -> Self {
+        match persistence {
+            PersistenceType::Any => circuit::PersistenceType::Any,
+        }
+    }
+}
+
+impl From<&messages::DurabilityType> for DurabilityType {
+    fn from(durability: &messages::DurabilityType) -> Self {
+        match durability {
+            messages::DurabilityType::NoDurability => DurabilityType::NoDurability,
+            messages::DurabilityType::Durable => DurabilityType::Durable,
+        }
+    }
+}
+
+impl From<&DurabilityType> for messages::DurabilityType {
+    fn from(durability: &DurabilityType) -> Self {
+        match durability {
+            DurabilityType::NoDurability => messages::DurabilityType::NoDurability,
+            DurabilityType::Durable => messages::DurabilityType::Durable,
+        }
+    }
+}
+
+impl From<&circuit::DurabilityType> for DurabilityType {
+    fn from(durability: &circuit::DurabilityType) -> Self {
+        match durability {
+            circuit::DurabilityType::NoDurability => DurabilityType::NoDurability,
+            circuit::DurabilityType::Durable => DurabilityType::Durable,
+        }
+    }
+}
+
+impl From<&DurabilityType> for circuit::DurabilityType {
+    fn from(durability: &DurabilityType) -> Self {
+        match durability {
+            DurabilityType::NoDurability => circuit::DurabilityType::NoDurability,
+            DurabilityType::Durable => circuit::DurabilityType::Durable,
+        }
+    }
+}
+
+impl From<&messages::RouteType> for RouteType {
+    fn from(route_type: &messages::RouteType) -> Self {
+        match route_type {
+            messages::RouteType::Any => RouteType::Any,
+            messages::RouteType::Relay => RouteType::Relay,
+        }
+    }
+}
+
+impl From<&RouteType> for messages::RouteType {
+    fn from(route_type: &RouteType) -> Self {
+        match route_type {
+            RouteType::Any => messages::RouteType::Any,
+            RouteType::Relay => messages::RouteType::Relay,
+        }
+    }
+}
+
+impl From<&circuit::RouteType> for RouteType {
+    fn from(route_type: &circuit::RouteType) -> Self {
+        match route_type {
+            circuit::RouteType::Any => RouteType::Any,
+            circuit::RouteType::Relay => RouteType::Relay,
+        }
+    }
+}
+
+impl From<&RouteType> for circuit::RouteType {
+    fn from(route_type: &RouteType) -> Self {
+        match route_type {
+            RouteType::Any => circuit::RouteType::Any,
+            RouteType::Relay => circuit::RouteType::Relay,
+        }
+    }
+}
+
+impl From<&messages::VotingPolicy> for VotingPolicy {
+    fn from(voting_policy: &messages::VotingPolicy) -> Self {
+        match voting_policy {
+            messages::VotingPolicy::Unanimous => VotingPolicy::Unanimous,
+            messages::VotingPolicy::Majority => VotingPolicy::Majority,
+            messages::VotingPolicy::Threshold(required_votes) => {
+                VotingPolicy::Threshold(*required_votes)
+            }
+        }
+    }
+}
+
+impl From<&VotingPolicy> for messages::VotingPolicy {
+    fn from(voting_policy: &VotingPolicy) -> Self {
+        match voting_policy {
+            VotingPolicy::Unanimous => messages::VotingPolicy::Unanimous,
+            VotingPolicy::Majority => messages::VotingPolicy::Majority,
+            VotingPolicy::Threshold(required_votes) => {
+                messages::VotingPolicy::Threshold(*required_votes)
+            }
+        }
+    }
+}
+
+impl From<&circuit::VotingPolicy> for VotingPolicy {
+    fn from(voting_policy: &circuit::VotingPolicy) -> Self {
+        match voting_policy {
+            circuit::VotingPolicy::Unanimous => VotingPolicy::Unanimous,
+            circuit::VotingPolicy::Majority => VotingPolicy::Majority,
+            circuit::VotingPolicy::Threshold(required_votes) => {
+                VotingPolicy::Threshold(*required_votes)
+            }
+        }
+    }
+}
+
+impl From<&VotingPolicy> for circuit::VotingPolicy {
+    fn from(voting_policy: &VotingPolicy) -> Self {
+        match voting_policy {
+            VotingPolicy::Unanimous => circuit::VotingPolicy::Unanimous,
+            VotingPolicy::Majority => circuit::VotingPolicy::Majority,
+            VotingPolicy::Threshold(required_votes) => {
+                circuit::VotingPolicy::Threshold(*required_votes)
+            }
         }
     }
 }
@@ -14,6 +14,8 @@
 
 //! Structs for building circuits nodes
 
+use crate::circuit::service::SplinterNode;
+
 use super::error::BuilderError;
 use super::ProposedNode;
 
@@ -54,6 +56,21 @@ impl From<ProposedNode> for CircuitNode {
     }
 }
 
+impl From<&SplinterNode> for CircuitNode {
+    fn from(node: &SplinterNode) -> Self {
+        CircuitNode {
+            id: node.id().into(),
+            endpoints: node.endpoints().to_vec(),
+        }
+    }
+}
+
+impl From<&CircuitNode> for SplinterNode {
+    fn from(node: &CircuitNode) -> Self {
+        SplinterNode::new(node.id.clone(), node.endpoints.clone())
+    }
+}
+
 /// Builder for creating a `CircutNode`
 #[derive(Default, Clone)]
 pub struct CircuitNodeBuilder {
@@ -14,7 +14,10 @@
 
 //! Structs for building services
 
-use crate::admin::messages::is_valid_circuit_id;
+use std::convert::TryFrom;
+use std::time::SystemTime;
+
+use crate::admin::messages::{self, is_valid_circuit_id};
 
 use super::error::BuilderError;
 use super::ProposedCircuit;
@@ -29,6 +32,7 @@ pub struct CircuitProposal {
     votes: Vec<VoteRecord>,
     requester: Vec<u8>,
     requester_node_id: String,
+    expires_at: Option<SystemTime>,
 }
 
 impl CircuitProposal {
@@ -44,7 +48,7 @@ impl CircuitProposal {
 
     /// Returns the hash of the circuit in the proposal
     pub fn circuit_hash(&self) -> &str {
-        &self.circuit_id
+        &self.circuit_hash
     }
 
     /// Returns the circuit in the proposal
@@ -67,15 +71,33 @@ impl CircuitProposal {
         &self.requester_node_id
     }
 
+    /// Returns the time after which the proposal expires, if it has a time-to-live
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expires_at
+    }
+
+    /// Returns true if the proposal has a time-to-live that has elapsed by the given time
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        match self.expires_at {
+            Some(expires_at) => expires_at <= now,
+            None => false,
+        }
+    }
+
     pub fn builder(&self) -> CircuitProposalBuilder {
-        CircuitProposalBuilder::new()
+        let builder = CircuitProposalBuilder::new()
             .with_proposal_type(self.proposal_type())
             .with_circuit_id(self.circuit_id())
             .with_circuit_hash(self.circuit_hash())
             .with_circuit(self.circuit())
             .with_votes(self.votes())
             .with_requester(self.requester())
-            .with_requester_node_id(self.requester_node_id())
+            .with_requester_node_id(self.requester_node_id());
+
+        match self.expires_at {
+            Some(expires_at) => builder.with_expires_at(expires_at),
+            None => builder,
+        }
     }
 }
 
@@ -89,6 +111,7 @@ pub struct CircuitProposalBuilder {
     votes: Option<Vec<VoteRecord>>,
     requester: Option<Vec<u8>>,
     requester_node_id: Option<String>,
+    expires_at: Option<SystemTime>,
 }
 
 impl CircuitProposalBuilder {
@@ -132,6 +155,11 @@ impl CircuitProposalBuilder {
         self.requester_node_id.clone()
     }
 
+    /// Returns the time after which the proposal expires
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expires_at
+    }
+
     /// Set the proposal type of the circuit proposal
     ///
     /// # Arguments
@@ -204,6 +232,17 @@ impl CircuitProposalBuilder {
         self
     }
 
+    /// Sets the time after which the proposal expires if it has not been accepted or rejected.
+    /// If not set, the proposal does not expire.
+    ///
+    /// # Arguments
+    ///
+    ///  * `expires_at` - The expiration time of the proposal
+    pub fn with_expires_at(mut self, expires_at: SystemTime) -> CircuitProposalBuilder {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
     /// Builds a `CircuitProposal`
     ///
     /// Returns an error if the circuit ID, circuit, circuit hash, requester, or requester node id
@@ -251,6 +290,7 @@ impl CircuitProposalBuilder {
             votes,
             requester,
             requester_node_id,
+            expires_at: self.expires_at,
         })
     }
 }
@@ -261,6 +301,7 @@ pub struct VoteRecord {
     public_key: Vec<u8>,
     vote: Vote,
     voter_node_id: String,
+    previous_votes: Vec<Vote>,
 }
 
 impl VoteRecord {
@@ -278,6 +319,11 @@ impl VoteRecord {
     pub fn voter_node_id(&self) -> &str {
         &self.voter_node_id
     }
+
+    /// Returns the votes the voter cast before changing its vote, oldest first
+    pub fn previous_votes(&self) -> &[Vote] {
+        &self.previous_votes
+    }
 }
 
 #[derive(Default)]
@@ -285,6 +331,7 @@ pub struct VoteRecordBuilder {
     public_key: Option<Vec<u8>>,
     vote: Option<Vote>,
     voter_node_id: Option<String>,
+    previous_votes: Vec<Vote>,
 }
 
 impl VoteRecordBuilder {
@@ -307,6 +354,11 @@ impl VoteRecordBuilder {
         self.voter_node_id.clone()
     }
 
+    /// Returns the votes the voter cast before changing its vote, oldest first
+    pub fn previous_votes(&self) -> Vec<Vote> {
+        self.previous_votes.clone()
+    }
+
     pub fn with_public_key(mut self, public_key: &[u8]) -> VoteRecordBuilder {
         self.public_key = Some(public_key.to_vec());
         self
@@ -322,6 +374,11 @@ impl VoteRecordBuilder {
         self
     }
 
+    pub fn with_previous_votes(mut self, previous_votes: &[Vote]) -> VoteRecordBuilder {
+        self.previous_votes = previous_votes.to_vec();
+        self
+    }
+
     pub fn build(self) -> Result<VoteRecord, BuilderError> {
         let public_key = self
             .public_key
@@ -339,6 +396,7 @@ impl VoteRecordBuilder {
             public_key,
             vote,
             voter_node_id,
+            previous_votes: self.previous_votes,
         })
     }
 }
@@ -358,4 +416,127 @@ pub enum ProposalType {
     AddNode,
     RemoveNode,
     Destroy,
+    Abandon,
+    UpdateApplicationMetadata,
+}
+
+impl TryFrom<&messages::CircuitProposal> for CircuitProposal {
+    type Error = BuilderError;
+
+    fn try_from(proposal: &messages::CircuitProposal) -> Result<Self, Self::Error> {
+        CircuitProposalBuilder::new()
+            .with_proposal_type(&ProposalType::from(&proposal.proposal_type))
+            .with_circuit_id(&proposal.circuit_id)
+            .with_circuit_hash(&proposal.circuit_hash)
+            .with_circuit(&ProposedCircuit::try_from(&proposal.circuit)?)
+            .with_votes(
+                &proposal
+                    .votes
+                    .iter()
+                    .map(VoteRecord::from)
+                    .collect::<Vec<_>>(),
+            )
+            .with_requester(&proposal.requester)
+            .with_requester_node_id(&proposal.requester_node_id)
+            .build()
+    }
+}
+
+/// The votes are sorted by voter, so that the resulting message does not depend on the order in
+/// which a store returned them. The expiration time is not part of the message.
+impl From<&CircuitProposal> for messages::CircuitProposal {
+    fn from(proposal: &CircuitProposal) -> Self {
+        let mut votes: Vec<messages::VoteRecord> = proposal
+            .votes
+            .iter()
+            .map(messages::VoteRecord::from)
+            .collect();
+        votes.sort_by(|a, b| a.voter_node_id.cmp(&b.voter_node_id));
+
+        messages::CircuitProposal {
+            proposal_type: messages::ProposalType::from(&proposal.proposal_type),
+            circuit_id: proposal.circuit_id.clone(),
+            circuit_hash: proposal.circuit_hash.clone(),
+            circuit: messages::CreateCircuit::from(&proposal.circuit),
+            votes,
+            requester: proposal.requester.clone(),
+            requester_node_id: proposal.requester_node_id.clone(),
+        }
+    }
+}
+
+impl From<&messages::VoteRecord> for VoteRecord {
+    fn from(vote_record: &messages::VoteRecord) -> Self {
+        VoteRecord {
+            public_key: vote_record.public_key.clone(),
+            vote: Vote::from(&vote_record.vote),
+            voter_node_id: vote_record.voter_node_id.clone(),
+            previous_votes: vote_record.previous_votes.iter().map(Vote::from).collect(),
+        }
+    }
+}
+
+impl From<&VoteRecord> for messages::VoteRecord {
+    fn from(vote_record: &VoteRecord) -> Self {
+        messages::VoteRecord {
+            public_key: vote_record.public_key.clone(),
+            vote: messages::Vote::from(&vote_record.vote),
+            voter_node_id: vote_record.voter_node_id.clone(),
+            previous_votes: vote_record
+                .previous_votes
+                .iter()
+                .map(messages::Vote::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<&messages::Vote> for Vote {
+    fn from(vote: &messages::Vote) -> Self {
+        match vote {
+            messages::Vote::Accept => Vote::Accept,
+            messages::Vote::Reject => Vote::Reject,
+        }
+    }
+}
+
+impl From<&Vote> for messages::Vote {
+    fn from(vote: &Vote) -> Self {
+        match vote {
+            Vote::Accept => messages::Vote::Accept,
+            Vote::Reject => messages::Vote::Reject,
+        }
+    }
+}
+
+impl From<&messages::ProposalType> for ProposalType {
+    fn from(proposal_type: &messages::ProposalType) -> Self {
+        match proposal_type {
+            messages::ProposalType::Create => ProposalType::Create,
+            messages::ProposalType::UpdateRoster => ProposalType::UpdateRoster,
+            messages::ProposalType::AddNode => ProposalType::AddNode,
+            messages::ProposalType::RemoveNode => ProposalType::RemoveNode,
+            messages::ProposalType::Destroy => ProposalType::Destroy,
+            messages::ProposalType::Abandon => ProposalType::Abandon,
+            messages::ProposalType::UpdateApplicationMetadata => {
+                ProposalType::UpdateApplicationMetadata
+            }
+        }
+    }
+}
+
+impl From<&ProposalType> for messages::ProposalType {
+    fn from(proposal_type: &ProposalType) -> Self {
+        match proposal_type {
+            ProposalType::Create => messages::ProposalType::Create,
+            ProposalType::UpdateRoster => messages::ProposalType::UpdateRoster,
+            ProposalType::AddNode => messages::ProposalType::AddNode,
+            ProposalType::RemoveNode => messages::ProposalType::RemoveNode,
+            ProposalType::Destroy => messages::ProposalType::Destroy,
+            ProposalType::Abandon => messages::ProposalType::Abandon,
+            ProposalType::UpdateApplicationMetadata => {
+                messages::ProposalType::UpdateApplicationMetadata
+            }
+        }
+    }
 }
@@ -119,8 +119,13 @@ impl AdminServiceStore for DieselAdminServiceStore<diesel::pg::PgConnection> {
         AdminServiceStoreOperations::new(&*self.connection_pool.get()?).add_circuit(circuit, nodes)
     }
 
-    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
-        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).update_circuit(circuit)
+    fn update_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?)
+            .update_circuit(circuit, nodes)
     }
 
     fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
@@ -166,6 +171,10 @@ impl AdminServiceStore for DieselAdminServiceStore<diesel::pg::PgConnection> {
     ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError> {
         AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_services(circuit_id)
     }
+
+    fn clone_boxed(&self) -> Box<dyn AdminServiceStore> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -204,8 +213,13 @@ impl AdminServiceStore for DieselAdminServiceStore<diesel::sqlite::SqliteConnect
         AdminServiceStoreOperations::new(&*self.connection_pool.get()?).add_circuit(circuit, nodes)
     }
 
-    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
-        AdminServiceStoreOperations::new(&*self.connection_pool.get()?).update_circuit(circuit)
+    fn update_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        AdminServiceStoreOperations::new(&*self.connection_pool.get()?)
+            .update_circuit(circuit, nodes)
     }
 
     fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
@@ -251,6 +265,10 @@ impl AdminServiceStore for DieselAdminServiceStore<diesel::sqlite::SqliteConnect
     ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError> {
         AdminServiceStoreOperations::new(&*self.connection_pool.get()?).list_services(circuit_id)
     }
+
+    fn clone_boxed(&self) -> Box<dyn AdminServiceStore> {
+        Box::new(self.clone())
+    }
 }
 
 #[cfg(all(test, feature = "sqlite"))]
@@ -17,6 +17,7 @@
 //! the requirements for storing data with a diesel backend.
 
 use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::admin::store::diesel::schema::{
     circuit, circuit_member, circuit_proposal, node_endpoint, proposed_circuit, proposed_node,
@@ -25,8 +26,8 @@ use crate::admin::store::diesel::schema::{
 };
 use crate::admin::store::error::AdminServiceStoreError;
 use crate::admin::store::{
-    AuthorizationType, DurabilityType, PersistenceType, ProposalType, RouteType, Vote, VoteRecord,
-    VoteRecordBuilder,
+    AuthorizationType, CircuitStatus, DurabilityType, PersistenceType, ProposalType, RouteType,
+    Vote, VoteRecord, VoteRecordBuilder, VotingPolicy,
 };
 use crate::admin::store::{Circuit, CircuitProposal, ProposedCircuit};
 
@@ -40,6 +41,16 @@ pub struct CircuitProposalModel {
     pub circuit_hash: String,
     pub requester: Vec<u8>,
     pub requester_node_id: String,
+    // seconds since the Unix epoch
+    pub expires_at: Option<i64>,
+}
+
+impl CircuitProposalModel {
+    /// Returns the time after which the proposal expires, if it has one
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expires_at
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs as u64))
+    }
 }
 
 impl From<&CircuitProposal> for CircuitProposalModel {
@@ -50,6 +61,12 @@ impl From<&CircuitProposal> for CircuitProposalModel {
             circuit_hash: proposal.circuit_hash().into(),
             requester: proposal.requester().to_vec(),
             requester_node_id: proposal.requester_node_id().into(),
+            expires_at: proposal.expires_at().map(|expires_at| {
+                expires_at
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs() as i64
+            }),
         }
     }
 }
@@ -68,10 +85,14 @@ pub struct ProposedCircuitModel {
     pub circuit_management_type: String,
     pub application_metadata: Vec<u8>,
     pub comments: String,
+    pub voting_policy: String,
+    // only set for a threshold voting policy
+    pub required_votes: Option<i32>,
 }
 
 impl From<&ProposedCircuit> for ProposedCircuitModel {
     fn from(proposed_circuit: &ProposedCircuit) -> Self {
+        let (voting_policy, required_votes) = proposed_circuit.voting_policy().into();
         ProposedCircuitModel {
             circuit_id: proposed_circuit.circuit_id().into(),
             authorization_type: String::from(proposed_circuit.authorization_type()),
@@ -81,6 +102,8 @@ impl From<&ProposedCircuit> for ProposedCircuitModel {
             circuit_management_type: proposed_circuit.circuit_management_type().into(),
             application_metadata: proposed_circuit.application_metadata().into(),
             comments: proposed_circuit.comments().into(),
+            voting_policy,
+            required_votes,
         }
     }
 }
@@ -95,6 +118,8 @@ pub struct VoteRecordModel {
     pub public_key: Vec<u8>,
     pub vote: String,
     pub voter_node_id: String,
+    /// The previous votes of the voter, oldest first, as a comma separated list
+    pub previous_votes: String,
 }
 
 impl From<&CircuitProposal> for Vec<VoteRecordModel> {
@@ -107,6 +132,12 @@ impl From<&CircuitProposal> for Vec<VoteRecordModel> {
                 public_key: vote.public_key().into(),
                 vote: String::from(vote.vote()),
                 voter_node_id: vote.voter_node_id().into(),
+                previous_votes: vote
+                    .previous_votes()
+                    .iter()
+                    .map(String::from)
+                    .collect::<Vec<_>>()
+                    .join(","),
             })
             .collect()
     }
@@ -115,10 +146,18 @@ impl From<&CircuitProposal> for Vec<VoteRecordModel> {
 impl TryFrom<&VoteRecordModel> for VoteRecord {
     type Error = AdminServiceStoreError;
     fn try_from(vote: &VoteRecordModel) -> Result<Self, Self::Error> {
+        let previous_votes = vote
+            .previous_votes
+            .split(',')
+            .filter(|previous_vote| !previous_vote.is_empty())
+            .map(|previous_vote| Vote::try_from(previous_vote.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+
         VoteRecordBuilder::new()
             .with_public_key(&vote.public_key)
             .with_vote(&Vote::try_from(vote.vote.clone())?)
             .with_voter_node_id(&vote.voter_node_id)
+            .with_previous_votes(&previous_votes)
             .build()
             .map_err(|err| AdminServiceStoreError::StorageError {
                 context: String::from("Failed to build VoteRecord"),
@@ -311,10 +350,17 @@ pub struct CircuitModel {
     pub durability: String,
     pub routes: String,
     pub circuit_management_type: String,
+    pub circuit_status: String,
+    pub application_metadata: Vec<u8>,
+    pub comments: String,
+    pub voting_policy: String,
+    // only set for a threshold voting policy
+    pub required_votes: Option<i32>,
 }
 
 impl From<&Circuit> for CircuitModel {
     fn from(circuit: &Circuit) -> Self {
+        let (voting_policy, required_votes) = circuit.voting_policy().into();
         CircuitModel {
             circuit_id: circuit.circuit_id().into(),
             authorization_type: String::from(circuit.authorization_type()),
@@ -322,6 +368,11 @@ impl From<&Circuit> for CircuitModel {
             durability: String::from(circuit.durability()),
             routes: String::from(circuit.routes()),
             circuit_management_type: circuit.circuit_management_type().into(),
+            circuit_status: String::from(circuit.circuit_status()),
+            application_metadata: circuit.application_metadata().into(),
+            comments: circuit.comments().into(),
+            voting_policy,
+            required_votes,
         }
     }
 }
@@ -393,6 +444,8 @@ impl TryFrom<String> for ProposalType {
             "AddNode" => Ok(ProposalType::AddNode),
             "RemoveNode" => Ok(ProposalType::RemoveNode),
             "Destroy" => Ok(ProposalType::Destroy),
+            "Abandon" => Ok(ProposalType::Abandon),
+            "UpdateApplicationMetadata" => Ok(ProposalType::UpdateApplicationMetadata),
             _ => Err(AdminServiceStoreError::StorageError {
                 context: "Unable to convert string to ProposalType".into(),
                 source: None,
@@ -409,6 +462,31 @@ impl From<&ProposalType> for String {
             ProposalType::AddNode => String::from("AddNode"),
             ProposalType::RemoveNode => String::from("RemoveNode"),
             ProposalType::Destroy => String::from("Destroy"),
+            ProposalType::Abandon => String::from("Abandon"),
+            ProposalType::UpdateApplicationMetadata => String::from("UpdateApplicationMetadata"),
+        }
+    }
+}
+
+impl TryFrom<String> for CircuitStatus {
+    type Error = AdminServiceStoreError;
+    fn try_from(variant: String) -> Result<Self, Self::Error> {
+        match variant.as_ref() {
+            "Active" => Ok(CircuitStatus::Active),
+            "Abandoned" => Ok(CircuitStatus::Abandoned),
+            _ => Err(AdminServiceStoreError::StorageError {
+                context: "Unable to convert string to CircuitStatus".into(),
+                source: None,
+            }),
+        }
+    }
+}
+
+impl From<&CircuitStatus> for String {
+    fn from(variant: &CircuitStatus) -> Self {
+        match variant {
+            CircuitStatus::Active => String::from("Active"),
+            CircuitStatus::Abandoned => String::from("Abandoned"),
         }
     }
 }
@@ -460,6 +538,7 @@ impl TryFrom<String> for DurabilityType {
     fn try_from(variant: String) -> Result<Self, Self::Error> {
         match variant.as_ref() {
             "NoDurability" => Ok(DurabilityType::NoDurability),
+            "Durable" => Ok(DurabilityType::Durable),
             _ => Err(AdminServiceStoreError::StorageError {
                 context: "Unable to convert string to DurabilityType".into(),
                 source: None,
@@ -472,6 +551,7 @@ impl From<&DurabilityType> for String {
     fn from(variant: &DurabilityType) -> Self {
         match variant {
             DurabilityType::NoDurability => String::from("NoDurability"),
+            DurabilityType::Durable => String::from("Durable"),
         }
     }
 }
@@ -481,6 +561,7 @@ impl TryFrom<String> for RouteType {
     fn try_from(variant: String) -> Result<Self, Self::Error> {
         match variant.as_ref() {
             "Any" => Ok(RouteType::Any),
+            "Relay" => Ok(RouteType::Relay),
             _ => Err(AdminServiceStoreError::StorageError {
                 context: "Unable to convert string to RouteType".into(),
                 source: None,
@@ -493,6 +574,36 @@ impl From<&RouteType> for String {
     fn from(variant: &RouteType) -> Self {
         match variant {
             RouteType::Any => String::from("Any"),
+            RouteType::Relay => String::from("Relay"),
+        }
+    }
+}
+
+impl TryFrom<(String, Option<i32>)> for VotingPolicy {
+    type Error = AdminServiceStoreError;
+    fn try_from((variant, required_votes): (String, Option<i32>)) -> Result<Self, Self::Error> {
+        match (variant.as_ref(), required_votes) {
+            ("Unanimous", _) => Ok(VotingPolicy::Unanimous),
+            ("Majority", _) => Ok(VotingPolicy::Majority),
+            ("Threshold", Some(required_votes)) if required_votes > 0 => {
+                Ok(VotingPolicy::Threshold(required_votes as u32))
+            }
+            _ => Err(AdminServiceStoreError::StorageError {
+                context: "Unable to convert string to VotingPolicy".into(),
+                source: None,
+            }),
+        }
+    }
+}
+
+impl From<&VotingPolicy> for (String, Option<i32>) {
+    fn from(variant: &VotingPolicy) -> Self {
+        match variant {
+            VotingPolicy::Unanimous => (String::from("Unanimous"), None),
+            VotingPolicy::Majority => (String::from("Majority"), None),
+            VotingPolicy::Threshold(required_votes) => {
+                (String::from("Threshold"), Some(*required_votes as i32))
+            }
         }
     }
 }
@@ -14,7 +14,10 @@
 
 //! Provides the "fetch circuit" operation for the `DieselAdminServiceStore`.
 
-use diesel::prelude::*;
+use diesel::{
+    prelude::*,
+    sql_types::{Binary, Integer, Nullable, Text},
+};
 use std::convert::TryFrom;
 
 use super::{list_services::AdminServiceStoreListServicesOperation, AdminServiceStoreOperations};
@@ -24,8 +27,8 @@ use crate::admin::store::{
         schema::{circuit, circuit_member},
     },
     error::AdminServiceStoreError,
-    AuthorizationType, Circuit, CircuitBuilder, DurabilityType, PersistenceType, RouteType,
-    Service,
+    AuthorizationType, Circuit, CircuitBuilder, CircuitStatus, DurabilityType, PersistenceType,
+    RouteType, Service, VotingPolicy,
 };
 
 pub(in crate::admin::store::diesel) trait AdminServiceStoreFetchCircuitOperation {
@@ -37,6 +40,22 @@ where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
     i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    CircuitModel: diesel::Queryable<
+        (
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Binary,
+            Text,
+            Text,
+            Nullable<Integer>,
+        ),
+        C::Backend,
+    >,
 {
     fn get_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, AdminServiceStoreError> {
         self.conn.transaction::<Option<Circuit>, _, _>(|| {
@@ -83,6 +102,14 @@ where
                     .with_persistence(&PersistenceType::try_from(circuit.persistence)?)
                     .with_durability(&DurabilityType::try_from(circuit.durability)?)
                     .with_routes(&RouteType::try_from(circuit.routes)?)
+                    .with_circuit_management_type(&circuit.circuit_management_type)
+                    .with_circuit_status(&CircuitStatus::try_from(circuit.circuit_status)?)
+                    .with_application_metadata(&circuit.application_metadata)
+                    .with_comments(&circuit.comments)
+                    .with_voting_policy(&VotingPolicy::try_from((
+                        circuit.voting_policy,
+                        circuit.required_votes,
+                    ))?)
                     .build()
                     .map_err(|err| AdminServiceStoreError::StorageError {
                         context: String::from("Failed to build Circuit"),
@@ -16,7 +16,7 @@
 
 use diesel::{
     prelude::*,
-    sql_types::{Binary, Text},
+    sql_types::{BigInt, Binary, Integer, Nullable, Text},
 };
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -36,7 +36,7 @@ use crate::admin::store::{
     error::AdminServiceStoreError,
     AuthorizationType, CircuitProposal, CircuitProposalBuilder, DurabilityType, PersistenceType,
     ProposalType, ProposedCircuitBuilder, ProposedNode, ProposedNodeBuilder, ProposedService,
-    ProposedServiceBuilder, RouteType, VoteRecord,
+    ProposedServiceBuilder, RouteType, VoteRecord, VotingPolicy,
 };
 
 pub(in crate::admin::store::diesel) trait AdminServiceStoreFetchProposalOperation {
@@ -51,10 +51,24 @@ where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
     i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
-    CircuitProposalModel: diesel::Queryable<(Text, Text, Text, Binary, Text), C::Backend>,
-    ProposedCircuitModel:
-        diesel::Queryable<(Text, Text, Text, Text, Text, Text, Binary, Text), C::Backend>,
-    VoteRecordModel: diesel::Queryable<(Text, Binary, Text, Text), C::Backend>,
+    CircuitProposalModel:
+        diesel::Queryable<(Text, Text, Text, Binary, Text, Nullable<BigInt>), C::Backend>,
+    ProposedCircuitModel: diesel::Queryable<
+        (
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Binary,
+            Text,
+            Text,
+            Nullable<Integer>,
+        ),
+        C::Backend,
+    >,
+    VoteRecordModel: diesel::Queryable<(Text, Binary, Text, Text, Text), C::Backend>,
 {
     fn get_proposal(
         &self,
@@ -92,8 +106,9 @@ where
                 // this join will return all matching entries as there are `proposed_node_endpoint`
                 // entries.
                 .inner_join(
-                    proposed_node_endpoint::table
-                        .on(proposed_node::node_id.eq(proposed_node_endpoint::node_id)),
+                    proposed_node_endpoint::table.on(proposed_node::circuit_id
+                        .eq(proposed_node_endpoint::circuit_id)
+                        .and(proposed_node::node_id.eq(proposed_node_endpoint::node_id))),
                 )
                 // Filters the entries based on the provided `proposal_id`.
                 .filter(proposed_node::circuit_id.eq(&proposal.circuit_id))
@@ -139,15 +154,15 @@ where
             let mut proposed_services: HashMap<String, ProposedServiceBuilder> = HashMap::new();
             // Create HashMap of `service_id` to the associated argument values
             let mut arguments_map: HashMap<String, Vec<(String, String)>> = HashMap::new();
-            // Collect all 'proposed_service' entries and associated data using `inner_join`, as
+            // Collect all 'proposed_service' entries and associated data using `left_join`, as
             // `proposed_service` has a one-to-many relationship to `proposed_service_argument`.
             for (proposed_service, opt_arg) in proposed_service::table
                 .filter(proposed_service::circuit_id.eq(&proposal.circuit_id))
                 // The `proposed_service` table has a one-to-many relationship with the
-                // `proposed_service_argument` table. The `inner_join` will retrieve the
+                // `proposed_service_argument` table. The `left_join` will retrieve the
                 // `proposed_service` and all `proposed_service_argument` entries with the matching
                 // `circuit_id` and `service_id`.
-                .inner_join(
+                .left_join(
                     proposed_service_argument::table.on(proposed_service::circuit_id
                         .eq(proposed_service_argument::circuit_id)
                         .and(
@@ -224,26 +239,35 @@ where
                 .with_persistence(&PersistenceType::try_from(proposed_circuit.persistence)?)
                 .with_durability(&DurabilityType::try_from(proposed_circuit.durability)?)
                 .with_routes(&RouteType::try_from(proposed_circuit.routes)?)
+                .with_circuit_management_type(&proposed_circuit.circuit_management_type)
+                .with_application_metadata(&proposed_circuit.application_metadata)
+                .with_comments(&proposed_circuit.comments)
+                .with_voting_policy(&VotingPolicy::try_from((
+                    proposed_circuit.voting_policy,
+                    proposed_circuit.required_votes,
+                ))?)
                 .build()
                 .map_err(|err| AdminServiceStoreError::StorageError {
                     context: String::from("Failed to build ProposedCircuit"),
                     source: Some(Box::new(err)),
                 })?;
-            Ok(Some(
-                CircuitProposalBuilder::new()
-                    .with_proposal_type(&ProposalType::try_from(proposal.proposal_type)?)
-                    .with_circuit_id(&proposal.circuit_id)
-                    .with_circuit_hash(&proposal.circuit_hash)
-                    .with_circuit(&native_proposed_circuit)
-                    .with_votes(&vote_record)
-                    .with_requester(&proposal.requester)
-                    .with_requester_node_id(&proposal.requester_node_id)
-                    .build()
-                    .map_err(|err| AdminServiceStoreError::StorageError {
-                        context: String::from("Failed to build CircuitProposal"),
-                        source: Some(Box::new(err)),
-                    })?,
-            ))
+            let mut proposal_builder = CircuitProposalBuilder::new()
+                .with_proposal_type(&ProposalType::try_from(proposal.proposal_type.clone())?)
+                .with_circuit_id(&proposal.circuit_id)
+                .with_circuit_hash(&proposal.circuit_hash)
+                .with_circuit(&native_proposed_circuit)
+                .with_votes(&vote_record)
+                .with_requester(&proposal.requester)
+                .with_requester_node_id(&proposal.requester_node_id);
+            if let Some(expires_at) = proposal.expires_at() {
+                proposal_builder = proposal_builder.with_expires_at(expires_at);
+            }
+            Ok(Some(proposal_builder.build().map_err(|err| {
+                AdminServiceStoreError::StorageError {
+                    context: String::from("Failed to build CircuitProposal"),
+                    source: Some(Box::new(err)),
+                }
+            })?))
         })
     }
 }
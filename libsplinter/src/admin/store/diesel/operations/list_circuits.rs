@@ -18,8 +18,8 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use diesel::{
-    dsl::{exists, not},
     prelude::*,
+    sql_types::{Binary, Integer, Nullable, Text},
 };
 
 use crate::admin::store::{
@@ -28,8 +28,8 @@ use crate::admin::store::{
         schema::{circuit, circuit_member, service, service_argument},
     },
     error::AdminServiceStoreError,
-    AuthorizationType, Circuit, CircuitBuilder, CircuitPredicate, DurabilityType, PersistenceType,
-    RouteType, Service, ServiceBuilder,
+    AuthorizationType, Circuit, CircuitBuilder, CircuitPredicate, CircuitStatus, DurabilityType,
+    PersistenceType, RouteType, Service, ServiceBuilder, VotingPolicy,
 };
 
 use super::AdminServiceStoreOperations;
@@ -46,47 +46,31 @@ where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
     i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    CircuitModel: diesel::Queryable<
+        (
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Binary,
+            Text,
+            Text,
+            Nullable<Integer>,
+        ),
+        C::Backend,
+    >,
 {
     fn list_circuits(
         &self,
         predicates: &[CircuitPredicate],
     ) -> Result<Box<dyn ExactSizeIterator<Item = Circuit>>, AdminServiceStoreError> {
-        // Collect the management types included in the list of `CircuitPredicates`
-        let management_types: Vec<String> = predicates
-            .iter()
-            .filter_map(|pred| match pred {
-                CircuitPredicate::ManagmentTypeEq(man_type) => Some(man_type.to_string()),
-                _ => None,
-            })
-            .collect::<Vec<String>>();
-        // Collects the members included in the list of `CircuitPredicates`
-        let members: Vec<String> = predicates
-            .iter()
-            .filter_map(|pred| match pred {
-                CircuitPredicate::MembersInclude(members) => Some(members.to_vec()),
-                _ => None,
-            })
-            .flatten()
-            .collect();
-
         self.conn
             .transaction::<Box<dyn ExactSizeIterator<Item = Circuit>>, _, _>(|| {
-                // Collects circuits which match the circuit predicates
+                // Collects all circuits; the predicates are applied once the circuits are built
                 let circuits: HashMap<String, CircuitModel> = circuit::table
-                    // Filters based on the circuit's management type
-                    .filter(circuit::circuit_management_type.eq_any(management_types))
-                    // Circuits are filtered by where there doesn't exist any `circuit_member` entries that
-                    // have a matching circuit_id value and have a node_id field that does not equal
-                    // any of the IDs collected from the `CircuitPredicates`.
-                    .filter(not(exists(
-                        // Selects all `circuit_member` entries where the `node_id` is not equal
-                        // to any of the members in the circuit predicates
-                        circuit_member::table.filter(
-                            circuit_member::circuit_id
-                                .eq(circuit::circuit_id)
-                                .and(circuit_member::node_id.ne_all(members)),
-                        ),
-                    )))
                     .load::<CircuitModel>(self.conn)
                     .map_err(|err| AdminServiceStoreError::QueryError {
                         context: String::from("Unable to load Circuit information"),
@@ -125,7 +109,7 @@ where
                 // Create HashMap of (`circuit_id`, `service_id`) to the associated argument values
                 let mut arguments_map: HashMap<(String, String), Vec<(String, String)>> =
                     HashMap::new();
-                // Collects all `service` and `service_argument` entries using an inner_join on the
+                // Collects all `service` and `service_argument` entries using a left_join on the
                 // `service_id`, since the relationship between `service` and `service_argument` is
                 // one-to-many. Adding the models retrieved from the database backend to HashMaps
                 // removed the duplicate `service` entries collected, and also makes it simpler
@@ -134,10 +118,12 @@ where
                     // Filters the services based on the circuit_ids collected based on the circuits
                     // which matched the predicates.
                     .filter(service::circuit_id.eq_any(&circuit_ids))
-                    // Joins a `service_argument` entry to a `service` entry, based on `service_id`.
-                    .inner_join(
-                        service_argument::table
-                            .on(service::service_id.eq(service_argument::service_id)),
+                    // Joins a `service_argument` entry to a `service` entry, based on `circuit_id`
+                    // and `service_id`.
+                    .left_join(
+                        service_argument::table.on(service::circuit_id
+                            .eq(service_argument::circuit_id)
+                            .and(service::service_id.eq(service_argument::service_id))),
                     )
                     // Collects all data from the `service` entry, and the pertinent data from the
                     // `service_argument` entry.
@@ -214,7 +200,15 @@ where
                         )?)
                         .with_persistence(&PersistenceType::try_from(model.persistence)?)
                         .with_durability(&DurabilityType::try_from(model.durability)?)
-                        .with_routes(&RouteType::try_from(model.routes)?);
+                        .with_routes(&RouteType::try_from(model.routes)?)
+                        .with_circuit_management_type(&model.circuit_management_type)
+                        .with_circuit_status(&CircuitStatus::try_from(model.circuit_status)?)
+                        .with_application_metadata(&model.application_metadata)
+                        .with_comments(&model.comments)
+                        .with_voting_policy(&VotingPolicy::try_from((
+                            model.voting_policy,
+                            model.required_votes,
+                        ))?);
 
                     if let Some(members) = circuit_members.get(&id) {
                         circuit_builder = circuit_builder.with_members(&members);
@@ -223,12 +217,18 @@ where
                         circuit_builder = circuit_builder.with_roster(&services);
                     }
 
-                    ret_circuits.push(circuit_builder.build().map_err(|err| {
+                    let circuit = circuit_builder.build().map_err(|err| {
                         AdminServiceStoreError::OperationError {
                             context: String::from("Unable to build Circuit"),
                             source: Some(Box::new(err)),
                         }
-                    })?);
+                    })?;
+                    if predicates
+                        .iter()
+                        .all(|predicate| predicate.apply_to_circuit(&circuit))
+                    {
+                        ret_circuits.push(circuit);
+                    }
                 }
 
                 Ok(Box::new(ret_circuits.into_iter()))
@@ -18,9 +18,8 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use diesel::{
-    dsl::{exists, not},
     prelude::*,
-    sql_types::{Binary, Text},
+    sql_types::{BigInt, Binary, Integer, Nullable, Text},
 };
 
 use crate::admin::store::{
@@ -37,7 +36,7 @@ use crate::admin::store::{
     error::AdminServiceStoreError,
     AuthorizationType, CircuitPredicate, CircuitProposal, CircuitProposalBuilder, DurabilityType,
     PersistenceType, ProposalType, ProposedCircuitBuilder, ProposedNode, ProposedNodeBuilder,
-    ProposedService, ProposedServiceBuilder, RouteType, VoteRecord,
+    ProposedService, ProposedServiceBuilder, RouteType, VoteRecord, VotingPolicy,
 };
 
 use super::AdminServiceStoreOperations;
@@ -54,58 +53,43 @@ where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
     i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
-    CircuitProposalModel: diesel::Queryable<(Text, Text, Text, Binary, Text), C::Backend>,
-    ProposedCircuitModel:
-        diesel::Queryable<(Text, Text, Text, Text, Text, Text, Binary, Text), C::Backend>,
-    VoteRecordModel: diesel::Queryable<(Text, Binary, Text, Text), C::Backend>,
+    CircuitProposalModel:
+        diesel::Queryable<(Text, Text, Text, Binary, Text, Nullable<BigInt>), C::Backend>,
+    ProposedCircuitModel: diesel::Queryable<
+        (
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Binary,
+            Text,
+            Text,
+            Nullable<Integer>,
+        ),
+        C::Backend,
+    >,
+    VoteRecordModel: diesel::Queryable<(Text, Binary, Text, Text, Text), C::Backend>,
 {
     fn list_proposals(
         &self,
         predicates: &[CircuitPredicate],
     ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitProposal>>, AdminServiceStoreError> {
-        // Collect the management types included in the list of `CircuitPredicates`
-        let management_types: Vec<String> = predicates
-            .iter()
-            .filter_map(|pred| match pred {
-                CircuitPredicate::ManagmentTypeEq(man_type) => Some(man_type.to_string()),
-                _ => None,
-            })
-            .collect::<Vec<String>>();
-        // Collects the members included in the list of `CircuitPredicates`
-        let members: Vec<String> = predicates
-            .iter()
-            .filter_map(|pred| match pred {
-                CircuitPredicate::MembersInclude(members) => Some(members.to_vec()),
-                _ => None,
-            })
-            .flatten()
-            .collect();
         self.conn
             .transaction::<Box<dyn ExactSizeIterator<Item = CircuitProposal>>, _, _>(|| {
-                // Collects proposed circuits which match the circuit predicates
+                // Collects all proposed circuits; the predicates are applied once the proposals
+                // are built
                 let proposed_circuits: HashMap<
                     String,
                     (ProposedCircuitModel, CircuitProposalModel),
                 > = proposed_circuit::table
-                    .filter(proposed_circuit::circuit_management_type.eq_any(management_types))
                     // Join the `circuit_proposal` table as these are one-to-one, and this eliminates
                     // the need for an additional query.
                     .inner_join(
                         circuit_proposal::table
                             .on(circuit_proposal::circuit_id.eq(proposed_circuit::circuit_id)),
                     )
-                    // Proposed circuits are filtered by where there doesn't exist any `proposed_nodes`
-                    // entries that have a matching circuit_id value and have a node_id field that does
-                    // not equal any of the IDs collected from the `CircuitPredicates`.
-                    .filter(not(exists(
-                        // Selects all `proposed_node` entries where the `node_id` is not equal
-                        // to any of the members in the circuit predicates
-                        proposed_node::table.filter(
-                            proposed_node::circuit_id
-                                .eq(proposed_circuit::circuit_id)
-                                .and(proposed_node::node_id.ne_all(members)),
-                        ),
-                    )))
                     .load::<(ProposedCircuitModel, CircuitProposalModel)>(self.conn)
                     .map_err(|err| AdminServiceStoreError::QueryError {
                         context: String::from("Unable to load proposed Circuit information"),
@@ -127,12 +111,17 @@ where
                 > = proposed_circuits
                     .into_iter()
                     .map(|(circuit_id, (proposed_circuit, proposal))| {
-                        let proposal_builder = CircuitProposalBuilder::new()
-                            .with_proposal_type(&ProposalType::try_from(proposal.proposal_type)?)
+                        let mut proposal_builder = CircuitProposalBuilder::new()
+                            .with_proposal_type(&ProposalType::try_from(
+                                proposal.proposal_type.clone(),
+                            )?)
                             .with_circuit_id(&proposal.circuit_id)
                             .with_circuit_hash(&proposal.circuit_hash)
                             .with_requester(&proposal.requester)
                             .with_requester_node_id(&proposal.requester_node_id);
+                        if let Some(expires_at) = proposal.expires_at() {
+                            proposal_builder = proposal_builder.with_expires_at(expires_at);
+                        }
                         let proposed_circuit_builder = ProposedCircuitBuilder::new()
                             .with_circuit_id(&proposed_circuit.circuit_id)
                             .with_authorization_type(&AuthorizationType::try_from(
@@ -147,7 +136,11 @@ where
                             .with_routes(&RouteType::try_from(proposed_circuit.routes)?)
                             .with_circuit_management_type(&proposed_circuit.circuit_management_type)
                             .with_application_metadata(&proposed_circuit.application_metadata)
-                            .with_comments(&proposed_circuit.comments);
+                            .with_comments(&proposed_circuit.comments)
+                            .with_voting_policy(&VotingPolicy::try_from((
+                                proposed_circuit.voting_policy,
+                                proposed_circuit.required_votes,
+                            ))?);
                         Ok((circuit_id, (proposal_builder, proposed_circuit_builder)))
                     })
                     .collect::<Result<HashMap<_, _>, AdminServiceStoreError>>()?;
@@ -160,10 +153,13 @@ where
                 let mut arguments_map: HashMap<(String, String), Vec<(String, String)>> =
                     HashMap::new();
                 for (proposed_service, opt_arg) in proposed_service::table
-                    .inner_join(
-                        proposed_service_argument::table
-                            .on(proposed_service::service_id
-                                .eq(proposed_service_argument::service_id)),
+                    .left_join(
+                        proposed_service_argument::table.on(proposed_service::circuit_id
+                            .eq(proposed_service_argument::circuit_id)
+                            .and(
+                                proposed_service::service_id
+                                    .eq(proposed_service_argument::service_id),
+                            )),
                     )
                     .select((
                         proposed_service::all_columns,
@@ -228,13 +224,15 @@ where
                     }
                 }
                 // Collect `ProposedNodes` and proposed node endpoints
-                let mut proposed_node_endpoints: HashMap<String, Vec<String>> = HashMap::new();
+                let mut proposed_node_endpoints: HashMap<(String, String), Vec<String>> =
+                    HashMap::new();
                 let mut proposed_nodes: HashMap<(String, String), ProposedNodeBuilder> =
                     HashMap::new();
                 for (node, endpoint) in proposed_node::table
                     .inner_join(
-                        proposed_node_endpoint::table
-                            .on(proposed_node::node_id.eq(proposed_node_endpoint::node_id)),
+                        proposed_node_endpoint::table.on(proposed_node::circuit_id
+                            .eq(proposed_node_endpoint::circuit_id)
+                            .and(proposed_node::node_id.eq(proposed_node_endpoint::node_id))),
                     )
                     .select((proposed_node::all_columns, proposed_node_endpoint::endpoint))
                     .load::<(ProposedNodeModel, String)>(self.conn)
@@ -243,19 +241,19 @@ where
                         source: Box::new(err),
                     })?
                 {
-                    if let Some(endpoint_list) = proposed_node_endpoints.get_mut(&node.node_id) {
-                        endpoint_list.push(endpoint.to_string());
-                    } else {
-                        proposed_node_endpoints
-                            .insert(node.node_id.to_string(), vec![endpoint.to_string()]);
-                    }
+                    proposed_node_endpoints
+                        .entry((node.circuit_id.to_string(), node.node_id.to_string()))
+                        .or_insert_with(Vec::new)
+                        .push(endpoint.to_string());
                     proposed_nodes
                         .entry((node.circuit_id.to_string(), node.node_id.to_string()))
                         .or_insert_with(|| ProposedNodeBuilder::new().with_node_id(&node.node_id));
                 }
                 let mut built_proposed_nodes: HashMap<String, Vec<ProposedNode>> = HashMap::new();
                 for ((circuit_id, node_id), mut builder) in proposed_nodes.into_iter() {
-                    if let Some(endpoints) = proposed_node_endpoints.get(&node_id) {
+                    if let Some(endpoints) =
+                        proposed_node_endpoints.get(&(circuit_id.to_string(), node_id))
+                    {
                         builder = builder.with_endpoints(endpoints);
                     }
                     if let Some(nodes) = built_proposed_nodes.get_mut(&circuit_id) {
@@ -311,20 +309,24 @@ where
                     if let Some(votes) = vote_records.get(&circuit_id) {
                         proposal_builder = proposal_builder.with_votes(&votes);
                     }
-                    proposals.push(
-                        proposal_builder
-                            .with_circuit(&proposed_circuit_builder.build().map_err(|err| {
-                                AdminServiceStoreError::StorageError {
-                                    context: String::from("Failed to build ProposedCircuit"),
-                                    source: Some(Box::new(err)),
-                                }
-                            })?)
-                            .build()
-                            .map_err(|err| AdminServiceStoreError::StorageError {
-                                context: String::from("Failed to build CircuitProposal"),
+                    let proposal = proposal_builder
+                        .with_circuit(&proposed_circuit_builder.build().map_err(|err| {
+                            AdminServiceStoreError::StorageError {
+                                context: String::from("Failed to build ProposedCircuit"),
                                 source: Some(Box::new(err)),
-                            })?,
-                    )
+                            }
+                        })?)
+                        .build()
+                        .map_err(|err| AdminServiceStoreError::StorageError {
+                            context: String::from("Failed to build CircuitProposal"),
+                            source: Some(Box::new(err)),
+                        })?;
+                    if predicates
+                        .iter()
+                        .all(|predicate| predicate.apply_to_proposals(&proposal))
+                    {
+                        proposals.push(proposal);
+                    }
                 }
 
                 Ok(Box::new(proposals.into_iter()))
@@ -50,15 +50,15 @@ where
         let mut services: HashMap<String, ServiceBuilder> = HashMap::new();
         // Create HashMap of `service_id` to the associated argument values
         let mut arguments_map: HashMap<String, Vec<(String, String)>> = HashMap::new();
-        // Collect all 'service' entries and associated data using `inner_join`, as each `service`
+        // Collect all 'service' entries and associated data using `left_join`, as each `service`
         // entry has a one-to-many relationship to `service_argument`.
         for (service, opt_arg) in service::table
             // Filter retrieved 'service' entries by the provided `circuit_id`
             .filter(service::circuit_id.eq(&circuit_id))
             // The `service` table has a one-to-many relationship with the `service_argument` table.
-            // The `inner_join` will retrieve the `service` and all `service_argument` entries
+            // The `left_join` will retrieve the `service` and all `service_argument` entries
             // with the matching `circuit_id` and `service_id`.
-            .inner_join(
+            .left_join(
                 service_argument::table.on(service::circuit_id
                     .eq(service_argument::circuit_id)
                     .and(service::service_id.eq(service_argument::service_id))),
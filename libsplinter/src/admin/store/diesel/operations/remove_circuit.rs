@@ -14,10 +14,17 @@
 
 //! Provides the "remove circuit" operation for the `DieselAdminServiceStore`.
 
-use diesel::{dsl::delete, prelude::*};
+use diesel::{
+    dsl::delete,
+    prelude::*,
+    sql_types::{Binary, Integer, Nullable, Text},
+};
 
 use crate::admin::store::{
-    diesel::schema::{circuit, circuit_member, node_endpoint},
+    diesel::{
+        models::CircuitModel,
+        schema::{circuit, circuit_member, node_endpoint, service, service_argument},
+    },
     error::AdminServiceStoreError,
 };
 
@@ -32,15 +39,51 @@ where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
     i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
+    CircuitModel: diesel::Queryable<
+        (
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Binary,
+            Text,
+            Text,
+            Nullable<Integer>,
+        ),
+        C::Backend,
+    >,
 {
     fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
         self.conn.transaction::<(), _, _>(|| {
             // Verify the circuit attempting to be removed exists.
             self.get_circuit(&circuit_id).and_then(|opt_circuit| {
+                // Remove the entries associated with the `circuit_id` explicitly, as SQLite does not
+                // enforce the cascading deletes of the foreign keys unless they are enabled on the
+                // connection.
+                delete(
+                    service_argument::table.filter(service_argument::circuit_id.eq(&circuit_id)),
+                )
+                .execute(self.conn)
+                .map_err(|err| AdminServiceStoreError::QueryError {
+                    context: String::from("Failed to delete Service arguments"),
+                    source: Box::new(err),
+                })?;
+                delete(service::table.filter(service::circuit_id.eq(&circuit_id)))
+                    .execute(self.conn)
+                    .map_err(|err| AdminServiceStoreError::QueryError {
+                        context: String::from("Failed to delete Services"),
+                        source: Box::new(err),
+                    })?;
+                delete(circuit_member::table.filter(circuit_member::circuit_id.eq(&circuit_id)))
+                    .execute(self.conn)
+                    .map_err(|err| AdminServiceStoreError::QueryError {
+                        context: String::from("Failed to delete Circuit members"),
+                        source: Box::new(err),
+                    })?;
                 // Remove the `circuit` entry with the matching `circuit_id`
-                // The `circuit_id` foreign key has cascade delete, meaning all related tables
-                // associated to the `circuit` table via the `circuit_id` will be deleted, if the
-                // corresponding `circuit` entry with the matching `circuit_id` is deleted.
                 delete(circuit::table.find(&circuit_id))
                     .execute(self.conn)
                     .map_err(|err| AdminServiceStoreError::QueryError {
@@ -17,13 +17,16 @@
 use diesel::{
     dsl::delete,
     prelude::*,
-    sql_types::{Binary, Text},
+    sql_types::{BigInt, Binary, Integer, Nullable, Text},
 };
 
 use crate::admin::store::{
     diesel::{
         models::{CircuitProposalModel, ProposedCircuitModel, VoteRecordModel},
-        schema::circuit_proposal,
+        schema::{
+            circuit_proposal, proposed_circuit, proposed_node, proposed_node_endpoint,
+            proposed_service, proposed_service_argument, vote_record,
+        },
     },
     error::AdminServiceStoreError,
 };
@@ -39,20 +42,80 @@ where
     C: diesel::Connection,
     String: diesel::deserialize::FromSql<diesel::sql_types::Text, C::Backend>,
     i64: diesel::deserialize::FromSql<diesel::sql_types::BigInt, C::Backend>,
-    CircuitProposalModel: diesel::Queryable<(Text, Text, Text, Binary, Text), C::Backend>,
-    ProposedCircuitModel:
-        diesel::Queryable<(Text, Text, Text, Text, Text, Text, Binary, Text), C::Backend>,
-    VoteRecordModel: diesel::Queryable<(Text, Binary, Text, Text), C::Backend>,
+    CircuitProposalModel:
+        diesel::Queryable<(Text, Text, Text, Binary, Text, Nullable<BigInt>), C::Backend>,
+    ProposedCircuitModel: diesel::Queryable<
+        (
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Text,
+            Binary,
+            Text,
+            Text,
+            Nullable<Integer>,
+        ),
+        C::Backend,
+    >,
+    VoteRecordModel: diesel::Queryable<(Text, Binary, Text, Text, Text), C::Backend>,
 {
     fn remove_proposal(&self, proposal_id: &str) -> Result<(), AdminServiceStoreError> {
         self.conn.transaction::<(), _, _>(|| {
             // Verify the `proposal` being removed exists
             self.get_proposal(&proposal_id).and_then(|_| {
+                // Remove the entries associated with the `proposal_id` explicitly, as SQLite does
+                // not enforce the cascading deletes of the foreign keys unless they are enabled on
+                // the connection.
+                delete(vote_record::table.filter(vote_record::circuit_id.eq(&proposal_id)))
+                    .execute(self.conn)
+                    .map_err(|err| AdminServiceStoreError::QueryError {
+                        context: String::from("Failed to delete proposal's vote records"),
+                        source: Box::new(err),
+                    })?;
+                delete(
+                    proposed_service_argument::table
+                        .filter(proposed_service_argument::circuit_id.eq(&proposal_id)),
+                )
+                .execute(self.conn)
+                .map_err(|err| AdminServiceStoreError::QueryError {
+                    context: String::from("Failed to delete proposed Services' arguments"),
+                    source: Box::new(err),
+                })?;
+                delete(
+                    proposed_service::table.filter(proposed_service::circuit_id.eq(&proposal_id)),
+                )
+                .execute(self.conn)
+                .map_err(|err| AdminServiceStoreError::QueryError {
+                    context: String::from("Failed to delete proposed Services"),
+                    source: Box::new(err),
+                })?;
+                delete(
+                    proposed_node_endpoint::table
+                        .filter(proposed_node_endpoint::circuit_id.eq(&proposal_id)),
+                )
+                .execute(self.conn)
+                .map_err(|err| AdminServiceStoreError::QueryError {
+                    context: String::from("Failed to delete proposed node endpoints"),
+                    source: Box::new(err),
+                })?;
+                delete(proposed_node::table.filter(proposed_node::circuit_id.eq(&proposal_id)))
+                    .execute(self.conn)
+                    .map_err(|err| AdminServiceStoreError::QueryError {
+                        context: String::from("Failed to delete proposed Nodes"),
+                        source: Box::new(err),
+                    })?;
+                delete(
+                    proposed_circuit::table.filter(proposed_circuit::circuit_id.eq(&proposal_id)),
+                )
+                .execute(self.conn)
+                .map_err(|err| AdminServiceStoreError::QueryError {
+                    context: String::from("Failed to delete ProposedCircuit"),
+                    source: Box::new(err),
+                })?;
                 // Remove the `proposal` entry with the matching `proposal_id`, which is represented
                 // in the `circuit_proposal` by the `circuit_id`.
-                // The `circuit_id` foreign key has cascade delete, meaning all related tables
-                // associated to the `circuit` table via the `circuit_id` will be deleted, if the
-                // corresponding `circuit` entry with the matching `circuit_id` is deleted.
                 delete(circuit_proposal::table.find(&proposal_id))
                     .execute(self.conn)
                     .map_err(|err| AdminServiceStoreError::QueryError {
@@ -14,6 +14,8 @@
 
 //! Provides the "update circuit" operation for the `DieselAdminServiceStore`.
 
+use std::collections::HashMap;
+
 use diesel::{
     dsl::{delete, insert_into, update},
     prelude::*,
@@ -22,22 +24,32 @@ use diesel::{
 use super::AdminServiceStoreOperations;
 use crate::admin::store::{
     diesel::{
-        models::{CircuitMemberModel, CircuitModel, ServiceArgumentModel, ServiceModel},
-        schema::{circuit, circuit_member, service, service_argument},
+        models::{
+            CircuitMemberModel, CircuitModel, NodeEndpointModel, ServiceArgumentModel, ServiceModel,
+        },
+        schema::{circuit, circuit_member, node_endpoint, service, service_argument},
     },
     error::AdminServiceStoreError,
-    Circuit,
+    Circuit, CircuitNode,
 };
 
 pub(in crate::admin::store::diesel) trait AdminServiceStoreUpdateCircuitOperation {
-    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError>;
+    fn update_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError>;
 }
 
 #[cfg(feature = "postgres")]
 impl<'a> AdminServiceStoreUpdateCircuitOperation
     for AdminServiceStoreOperations<'a, diesel::pg::PgConnection>
 {
-    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
+    fn update_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
         self.conn.transaction::<(), _, _>(|| {
             // Verify the `circuit` entry to be updated exists
             circuit::table
@@ -63,12 +75,25 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
                     circuit::durability.eq(circuit_model.durability),
                     circuit::routes.eq(circuit_model.routes),
                     circuit::circuit_management_type.eq(circuit_model.circuit_management_type),
+                    circuit::circuit_status.eq(circuit_model.circuit_status),
+                    circuit::application_metadata.eq(circuit_model.application_metadata),
+                    circuit::comments.eq(circuit_model.comments),
+                    circuit::voting_policy.eq(circuit_model.voting_policy),
+                    circuit::required_votes.eq(circuit_model.required_votes),
                 ))
                 .execute(self.conn)
                 .map_err(|err| AdminServiceStoreError::QueryError {
                     context: String::from("Unable to update Circuit"),
                     source: Box::new(err),
                 })?;
+            let previous_members = circuit_member::table
+                .filter(circuit_member::circuit_id.eq(circuit.circuit_id()))
+                .select(circuit_member::node_id)
+                .load::<String>(self.conn)
+                .map_err(|err| AdminServiceStoreError::QueryError {
+                    context: String::from("Error occurred fetching Circuit members"),
+                    source: Box::new(err),
+                })?;
             // Delete existing data associated with the `Circuit`
             delete(service::table.filter(service::circuit_id.eq(circuit.circuit_id())))
                 .execute(self.conn)
@@ -85,6 +110,14 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
                 context: String::from("Failed to remove old Service arguments"),
                 source: Box::new(err),
             })?;
+            delete(
+                circuit_member::table.filter(circuit_member::circuit_id.eq(circuit.circuit_id())),
+            )
+            .execute(self.conn)
+            .map_err(|err| AdminServiceStoreError::QueryError {
+                context: String::from("Failed to remove old Circuit members"),
+                source: Box::new(err),
+            })?;
             // Insert new data associate with the `Circuit`
             let services: Vec<ServiceModel> = Vec::from(&circuit);
             insert_into(service::table)
@@ -110,6 +143,65 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
                     context: String::from("Unable to insert Circuit members"),
                     source: Box::new(err),
                 })?;
+            // Insert the `node_endpoint` entries of any members that are not already in the
+            // store.
+            for (node_id, endpoints) in nodes
+                .iter()
+                .map(|node| {
+                    (
+                        node.node_id().into(),
+                        node.endpoints()
+                            .iter()
+                            .map(|endpoint| NodeEndpointModel {
+                                node_id: node.node_id().into(),
+                                endpoint: endpoint.into(),
+                            })
+                            .collect::<Vec<NodeEndpointModel>>(),
+                    )
+                })
+                .collect::<HashMap<String, Vec<NodeEndpointModel>>>()
+                .into_iter()
+            {
+                if let Some(0) = node_endpoint::table
+                    .filter(node_endpoint::node_id.eq(&node_id))
+                    .count()
+                    .first(self.conn)
+                    .optional()
+                    .map_err(|err| AdminServiceStoreError::QueryError {
+                        context: String::from("Error occurred counting CircuitNode endpoints"),
+                        source: Box::new(err),
+                    })?
+                {
+                    insert_into(node_endpoint::table)
+                        .values(endpoints)
+                        .execute(self.conn)
+                        .map_err(|err| AdminServiceStoreError::QueryError {
+                            context: String::from("Unable to insert CircuitNode endpoints"),
+                            source: Box::new(err),
+                        })?;
+                }
+            }
+            // Remove the `node_endpoint` entries of any previous members that are no longer a
+            // member of any circuit.
+            for node_id in previous_members {
+                if let Some(0) = circuit_member::table
+                    .filter(circuit_member::node_id.eq(&node_id))
+                    .count()
+                    .first(self.conn)
+                    .optional()
+                    .map_err(|err| AdminServiceStoreError::QueryError {
+                        context: String::from("Error occurred counting Circuit members"),
+                        source: Box::new(err),
+                    })?
+                {
+                    delete(node_endpoint::table.filter(node_endpoint::node_id.eq(node_id)))
+                        .execute(self.conn)
+                        .map_err(|err| AdminServiceStoreError::QueryError {
+                            context: String::from("Failed to delete `node_endpoint` entries"),
+                            source: Box::new(err),
+                        })?;
+                }
+            }
             Ok(())
         })
     }
@@ -119,7 +211,11 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
 impl<'a> AdminServiceStoreUpdateCircuitOperation
     for AdminServiceStoreOperations<'a, diesel::sqlite::SqliteConnection>
 {
-    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
+    fn update_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
         self.conn.transaction::<(), _, _>(|| {
             // Verify the `circuit` entry to be updated exists
             circuit::table
@@ -145,12 +241,25 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
                     circuit::durability.eq(circuit_model.durability),
                     circuit::routes.eq(circuit_model.routes),
                     circuit::circuit_management_type.eq(circuit_model.circuit_management_type),
+                    circuit::circuit_status.eq(circuit_model.circuit_status),
+                    circuit::application_metadata.eq(circuit_model.application_metadata),
+                    circuit::comments.eq(circuit_model.comments),
+                    circuit::voting_policy.eq(circuit_model.voting_policy),
+                    circuit::required_votes.eq(circuit_model.required_votes),
                 ))
                 .execute(self.conn)
                 .map_err(|err| AdminServiceStoreError::QueryError {
                     context: String::from("Unable to update Circuit"),
                     source: Box::new(err),
                 })?;
+            let previous_members = circuit_member::table
+                .filter(circuit_member::circuit_id.eq(circuit.circuit_id()))
+                .select(circuit_member::node_id)
+                .load::<String>(self.conn)
+                .map_err(|err| AdminServiceStoreError::QueryError {
+                    context: String::from("Error occurred fetching Circuit members"),
+                    source: Box::new(err),
+                })?;
             // Delete existing data associated with the `Circuit`
             delete(service::table.filter(service::circuit_id.eq(circuit.circuit_id())))
                 .execute(self.conn)
@@ -167,6 +276,14 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
                 context: String::from("Failed to remove old Service arguments"),
                 source: Box::new(err),
             })?;
+            delete(
+                circuit_member::table.filter(circuit_member::circuit_id.eq(circuit.circuit_id())),
+            )
+            .execute(self.conn)
+            .map_err(|err| AdminServiceStoreError::QueryError {
+                context: String::from("Failed to remove old Circuit members"),
+                source: Box::new(err),
+            })?;
             // Insert new `Circuit` data
             let services: Vec<ServiceModel> = Vec::from(&circuit);
             insert_into(service::table)
@@ -192,6 +309,65 @@ impl<'a> AdminServiceStoreUpdateCircuitOperation
                     context: String::from("Unable to insert Circuit members"),
                     source: Box::new(err),
                 })?;
+            // Insert the `node_endpoint` entries of any members that are not already in the
+            // store.
+            for (node_id, endpoints) in nodes
+                .iter()
+                .map(|node| {
+                    (
+                        node.node_id().into(),
+                        node.endpoints()
+                            .iter()
+                            .map(|endpoint| NodeEndpointModel {
+                                node_id: node.node_id().into(),
+                                endpoint: endpoint.into(),
+                            })
+                            .collect::<Vec<NodeEndpointModel>>(),
+                    )
+                })
+                .collect::<HashMap<String, Vec<NodeEndpointModel>>>()
+                .into_iter()
+            {
+                if let Some(0) = node_endpoint::table
+                    .filter(node_endpoint::node_id.eq(&node_id))
+                    .count()
+                    .first(self.conn)
+                    .optional()
+                    .map_err(|err| AdminServiceStoreError::QueryError {
+                        context: String::from("Error occurred counting CircuitNode endpoints"),
+                        source: Box::new(err),
+                    })?
+                {
+                    insert_into(node_endpoint::table)
+                        .values(endpoints)
+                        .execute(self.conn)
+                        .map_err(|err| AdminServiceStoreError::QueryError {
+                            context: String::from("Unable to insert CircuitNode endpoints"),
+                            source: Box::new(err),
+                        })?;
+                }
+            }
+            // Remove the `node_endpoint` entries of any previous members that are no longer a
+            // member of any circuit.
+            for node_id in previous_members {
+                if let Some(0) = circuit_member::table
+                    .filter(circuit_member::node_id.eq(&node_id))
+                    .count()
+                    .first(self.conn)
+                    .optional()
+                    .map_err(|err| AdminServiceStoreError::QueryError {
+                        context: String::from("Error occurred counting Circuit members"),
+                        source: Box::new(err),
+                    })?
+                {
+                    delete(node_endpoint::table.filter(node_endpoint::node_id.eq(node_id)))
+                        .execute(self.conn)
+                        .map_err(|err| AdminServiceStoreError::QueryError {
+                            context: String::from("Failed to delete `node_endpoint` entries"),
+                            source: Box::new(err),
+                        })?;
+                }
+            }
             Ok(())
         })
     }
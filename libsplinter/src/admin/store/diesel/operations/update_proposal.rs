@@ -68,6 +68,7 @@ impl<'a> AdminServiceStoreUpdateProposalOperation
                     circuit_proposal::circuit_hash.eq(proposal_model.circuit_hash),
                     circuit_proposal::requester.eq(proposal_model.requester),
                     circuit_proposal::requester_node_id.eq(proposal_model.requester_node_id),
+                    circuit_proposal::expires_at.eq(proposal_model.expires_at),
                 ))
                 .execute(self.conn)
                 .map_err(|err| AdminServiceStoreError::QueryError {
@@ -88,6 +89,8 @@ impl<'a> AdminServiceStoreUpdateProposalOperation
                     proposed_circuit::application_metadata
                         .eq(proposed_circuit_model.application_metadata),
                     proposed_circuit::comments.eq(proposed_circuit_model.comments),
+                    proposed_circuit::voting_policy.eq(proposed_circuit_model.voting_policy),
+                    proposed_circuit::required_votes.eq(proposed_circuit_model.required_votes),
                 ))
                 .execute(self.conn)
                 .map_err(|err| AdminServiceStoreError::QueryError {
@@ -96,15 +99,6 @@ impl<'a> AdminServiceStoreUpdateProposalOperation
                 })?;
 
             // Delete existing data associated with the `CircuitProposal` and `ProposedCircuit`
-            let node_ids: Vec<String> = proposed_node::table
-                .filter(proposed_node::circuit_id.eq(proposal.circuit_id()))
-                .select(proposed_node::node_id)
-                .load(self.conn)
-                .map_err(|err| AdminServiceStoreError::QueryError {
-                    context: String::from("Failed to fetch old proposed nodes"),
-                    source: Box::new(err),
-                })?;
-
             delete(
                 proposed_node::table.filter(proposed_node::circuit_id.eq(proposal.circuit_id())),
             )
@@ -115,7 +109,7 @@ impl<'a> AdminServiceStoreUpdateProposalOperation
             })?;
             delete(
                 proposed_node_endpoint::table
-                    .filter(proposed_node_endpoint::node_id.eq_any(&node_ids)),
+                    .filter(proposed_node_endpoint::circuit_id.eq(proposal.circuit_id())),
             )
             .execute(self.conn)
             .map_err(|err| AdminServiceStoreError::QueryError {
@@ -231,6 +225,7 @@ impl<'a> AdminServiceStoreUpdateProposalOperation
                     circuit_proposal::circuit_hash.eq(proposal_model.circuit_hash),
                     circuit_proposal::requester.eq(proposal_model.requester),
                     circuit_proposal::requester_node_id.eq(proposal_model.requester_node_id),
+                    circuit_proposal::expires_at.eq(proposal_model.expires_at),
                 ))
                 .execute(self.conn)
                 .map_err(|err| AdminServiceStoreError::QueryError {
@@ -251,6 +246,8 @@ impl<'a> AdminServiceStoreUpdateProposalOperation
                     proposed_circuit::application_metadata
                         .eq(proposed_circuit_model.application_metadata),
                     proposed_circuit::comments.eq(proposed_circuit_model.comments),
+                    proposed_circuit::voting_policy.eq(proposed_circuit_model.voting_policy),
+                    proposed_circuit::required_votes.eq(proposed_circuit_model.required_votes),
                 ))
                 .execute(self.conn)
                 .map_err(|err| AdminServiceStoreError::QueryError {
@@ -259,15 +256,6 @@ impl<'a> AdminServiceStoreUpdateProposalOperation
                 })?;
 
             // Delete existing data associated with the `CircuitProposal` and `ProposedCircuit`
-            let node_ids: Vec<String> = proposed_node::table
-                .filter(proposed_node::circuit_id.eq(proposal.circuit_id()))
-                .select(proposed_node::node_id)
-                .load(self.conn)
-                .map_err(|err| AdminServiceStoreError::QueryError {
-                    context: String::from("Failed to fetch old proposed nodes"),
-                    source: Box::new(err),
-                })?;
-
             delete(
                 proposed_node::table.filter(proposed_node::circuit_id.eq(proposal.circuit_id())),
             )
@@ -278,7 +266,7 @@ impl<'a> AdminServiceStoreUpdateProposalOperation
             })?;
             delete(
                 proposed_node_endpoint::table
-                    .filter(proposed_node_endpoint::node_id.eq_any(&node_ids)),
+                    .filter(proposed_node_endpoint::circuit_id.eq(proposal.circuit_id())),
             )
             .execute(self.conn)
             .map_err(|err| AdminServiceStoreError::QueryError {
@@ -65,6 +65,9 @@ impl<'a> AdminServiceStoreUpgradeProposalToCircuitOperation
                 .with_durability(proposed_circuit.durability())
                 .with_routes(proposed_circuit.routes())
                 .with_circuit_management_type(proposed_circuit.circuit_management_type())
+                .with_application_metadata(proposed_circuit.application_metadata())
+                .with_comments(proposed_circuit.comments())
+                .with_voting_policy(proposed_circuit.voting_policy())
                 .build()
                 .map_err(|err| AdminServiceStoreError::StorageError {
                     context: String::from("Failed to build Circuit"),
@@ -120,6 +123,9 @@ impl<'a> AdminServiceStoreUpgradeProposalToCircuitOperation
                 .with_durability(proposed_circuit.durability())
                 .with_routes(proposed_circuit.routes())
                 .with_circuit_management_type(proposed_circuit.circuit_management_type())
+                .with_application_metadata(proposed_circuit.application_metadata())
+                .with_comments(proposed_circuit.comments())
+                .with_voting_policy(proposed_circuit.voting_policy())
                 .build()
                 .map_err(|err| AdminServiceStoreError::StorageError {
                     context: String::from("Failed to build Circuit"),
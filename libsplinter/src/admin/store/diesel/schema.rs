@@ -19,6 +19,7 @@ table! {
         circuit_hash -> Text,
         requester -> Binary,
         requester_node_id -> Text,
+        expires_at -> Nullable<BigInt>,
     }
 }
 
@@ -32,6 +33,8 @@ table! {
         circuit_management_type -> Text,
         application_metadata -> Binary,
         comments -> Text,
+        voting_policy -> Text,
+        required_votes -> Nullable<Integer>,
     }
 }
 
@@ -41,6 +44,7 @@ table! {
         public_key -> Binary,
         vote -> Text,
         voter_node_id -> Text,
+        previous_votes -> Text,
     }
 }
 
@@ -103,6 +107,11 @@ table! {
         durability -> Text,
         routes -> Text,
         circuit_management_type -> Text,
+        circuit_status -> Text,
+        application_metadata -> Binary,
+        comments -> Text,
+        voting_policy -> Text,
+        required_votes -> Nullable<Integer>,
     }
 }
 
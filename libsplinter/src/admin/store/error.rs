@@ -24,20 +24,20 @@ pub enum AdminServiceStoreError {
     /// Represents CRUD operations failures
     OperationError {
         context: String,
-        source: Option<Box<dyn Error>>,
+        source: Option<Box<dyn Error + Send>>,
     },
     /// Represents store query failures
     QueryError {
         context: String,
-        source: Box<dyn Error>,
+        source: Box<dyn Error + Send>,
     },
     /// Represents general failures in the store
     StorageError {
         context: String,
-        source: Option<Box<dyn Error>>,
+        source: Option<Box<dyn Error + Send>>,
     },
     /// Represents an issue connecting to the store
-    ConnectionError(Box<dyn Error>),
+    ConnectionError(Box<dyn Error + Send>),
     NotFoundError(String),
 }
 
@@ -0,0 +1,532 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A memory-backed implementation of the [`AdminServiceStore`]. Circuits and proposals are lost
+//! when the store is dropped.
+//!
+//! [`AdminServiceStore`]: ../trait.AdminServiceStore.html
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::{
+    AdminServiceStore, AdminServiceStoreError, Circuit, CircuitNode, CircuitPredicate,
+    CircuitProposal, Service, ServiceId,
+};
+
+#[derive(Default)]
+struct MemoryState {
+    proposals: BTreeMap<String, CircuitProposal>,
+    circuits: BTreeMap<String, Circuit>,
+    nodes: BTreeMap<String, CircuitNode>,
+}
+
+/// A memory-backed implementation of the `AdminServiceStore`
+#[derive(Clone, Default)]
+pub struct MemoryAdminServiceStore {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+impl MemoryAdminServiceStore {
+    /// Creates a new, empty `MemoryAdminServiceStore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<MemoryState>, AdminServiceStoreError> {
+        self.state
+            .lock()
+            .map_err(|_| AdminServiceStoreError::StorageError {
+                context: "Memory admin service store's internal lock was poisoned".to_string(),
+                source: None,
+            })
+    }
+}
+
+impl AdminServiceStore for MemoryAdminServiceStore {
+    fn add_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        if state.proposals.contains_key(proposal.circuit_id()) {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!(
+                    "A proposal with ID {} already exists",
+                    proposal.circuit_id()
+                ),
+                source: None,
+            });
+        }
+
+        state
+            .proposals
+            .insert(proposal.circuit_id().to_string(), proposal);
+
+        Ok(())
+    }
+
+    fn update_proposal(&self, proposal: CircuitProposal) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        if !state.proposals.contains_key(proposal.circuit_id()) {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!(
+                    "A proposal with ID {} does not exist",
+                    proposal.circuit_id()
+                ),
+                source: None,
+            });
+        }
+
+        state
+            .proposals
+            .insert(proposal.circuit_id().to_string(), proposal);
+
+        Ok(())
+    }
+
+    fn remove_proposal(&self, proposal_id: &str) -> Result<(), AdminServiceStoreError> {
+        self.lock_state()?
+            .proposals
+            .remove(proposal_id)
+            .map(|_| ())
+            .ok_or_else(|| AdminServiceStoreError::OperationError {
+                context: format!("A proposal with ID {} does not exist", proposal_id),
+                source: None,
+            })
+    }
+
+    fn get_proposal(
+        &self,
+        proposal_id: &str,
+    ) -> Result<Option<CircuitProposal>, AdminServiceStoreError> {
+        Ok(self.lock_state()?.proposals.get(proposal_id).cloned())
+    }
+
+    fn list_proposals(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitProposal>>, AdminServiceStoreError> {
+        let proposals = self
+            .lock_state()?
+            .proposals
+            .values()
+            .filter(|proposal| {
+                predicates
+                    .iter()
+                    .all(|predicate| predicate.apply_to_proposals(proposal))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(proposals.into_iter()))
+    }
+
+    fn add_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        if state.circuits.contains_key(circuit.circuit_id()) {
+            return Err(AdminServiceStoreError::OperationError {
+                context: format!("A circuit with ID {} already exists", circuit.circuit_id()),
+                source: None,
+            });
+        }
+
+        for node in nodes.into_iter() {
+            if !state.nodes.contains_key(node.node_id()) {
+                state.nodes.insert(node.node_id().to_string(), node);
+            }
+        }
+
+        state
+            .circuits
+            .insert(circuit.circuit_id().to_string(), circuit);
+
+        Ok(())
+    }
+
+    fn update_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        let previous = state.circuits.remove(circuit.circuit_id()).ok_or_else(|| {
+            AdminServiceStoreError::OperationError {
+                context: format!("A circuit with ID {} does not exist", circuit.circuit_id()),
+                source: None,
+            }
+        })?;
+
+        for node in nodes.into_iter() {
+            if !state.nodes.contains_key(node.node_id()) {
+                state.nodes.insert(node.node_id().to_string(), node);
+            }
+        }
+
+        state
+            .circuits
+            .insert(circuit.circuit_id().to_string(), circuit);
+
+        // Only remove the previous members that are no longer a member of any circuit
+        for node_id in previous.members() {
+            if !state
+                .circuits
+                .values()
+                .any(|circuit| circuit.members().contains(node_id))
+            {
+                state.nodes.remove(node_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remove_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        let mut state = self.lock_state()?;
+
+        let circuit = state.circuits.remove(circuit_id).ok_or_else(|| {
+            AdminServiceStoreError::OperationError {
+                context: format!("A circuit with ID {} does not exist", circuit_id),
+                source: None,
+            }
+        })?;
+
+        // Only remove the nodes that are no longer a member of any circuit
+        for node_id in circuit.members() {
+            if !state
+                .circuits
+                .values()
+                .any(|circuit| circuit.members().contains(node_id))
+            {
+                state.nodes.remove(node_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_circuit(&self, circuit_id: &str) -> Result<Option<Circuit>, AdminServiceStoreError> {
+        Ok(self.lock_state()?.circuits.get(circuit_id).cloned())
+    }
+
+    fn list_circuits(
+        &self,
+        predicates: &[CircuitPredicate],
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Circuit>>, AdminServiceStoreError> {
+        let circuits = self
+            .lock_state()?
+            .circuits
+            .values()
+            .filter(|circuit| {
+                predicates
+                    .iter()
+                    .all(|predicate| predicate.apply_to_circuit(circuit))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(circuits.into_iter()))
+    }
+
+    fn upgrade_proposal_to_circuit(&self, circuit_id: &str) -> Result<(), AdminServiceStoreError> {
+        let proposal = {
+            let mut state = self.lock_state()?;
+            state.proposals.remove(circuit_id).ok_or_else(|| {
+                AdminServiceStoreError::NotFoundError(format!(
+                    "Cannot find circuit proposal with id: {}",
+                    circuit_id
+                ))
+            })?
+        };
+
+        let nodes = proposal
+            .circuit()
+            .members()
+            .iter()
+            .map(CircuitNode::from)
+            .collect();
+
+        self.add_circuit(Circuit::from(proposal.circuit().clone()), nodes)
+    }
+
+    fn get_node(&self, node_id: &str) -> Result<Option<CircuitNode>, AdminServiceStoreError> {
+        Ok(self.lock_state()?.nodes.get(node_id).cloned())
+    }
+
+    fn list_nodes(
+        &self,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = CircuitNode>>, AdminServiceStoreError> {
+        let nodes = self
+            .lock_state()?
+            .nodes
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        Ok(Box::new(nodes.into_iter()))
+    }
+
+    fn get_service(
+        &self,
+        service_id: &ServiceId,
+    ) -> Result<Option<Service>, AdminServiceStoreError> {
+        Ok(self
+            .lock_state()?
+            .circuits
+            .get(service_id.circuit())
+            .and_then(|circuit| {
+                circuit
+                    .roster()
+                    .iter()
+                    .find(|service| service.service_id() == service_id.service_id())
+                    .cloned()
+            }))
+    }
+
+    fn list_services(
+        &self,
+        circuit_id: &str,
+    ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError> {
+        let services = self
+            .lock_state()?
+            .circuits
+            .get(circuit_id)
+            .ok_or_else(|| AdminServiceStoreError::OperationError {
+                context: format!("Circuit {} does not exist", circuit_id),
+                source: None,
+            })?
+            .roster()
+            .to_vec();
+
+        Ok(Box::new(services.into_iter()))
+    }
+
+    fn clone_boxed(&self) -> Box<dyn AdminServiceStore> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::admin::store::{
+        CircuitBuilder, CircuitNodeBuilder, CircuitProposalBuilder, ProposalType,
+        ProposedCircuitBuilder, ProposedNodeBuilder, ProposedServiceBuilder, ServiceBuilder,
+    };
+
+    /// Verify that a proposal can be added, updated, listed, and upgraded to a circuit, and that
+    /// the circuit's nodes and services are available afterwards.
+    #[test]
+    fn test_proposal_lifecycle() {
+        let store = MemoryAdminServiceStore::new();
+        let proposal = create_proposal();
+
+        store
+            .add_proposal(proposal.clone())
+            .expect("Unable to add proposal");
+        assert!(store.add_proposal(proposal.clone()).is_err());
+
+        let updated_proposal = proposal
+            .builder()
+            .with_requester_node_id("bubba-node-000")
+            .build()
+            .expect("Unable to build proposal");
+        store
+            .update_proposal(updated_proposal.clone())
+            .expect("Unable to update proposal");
+        assert_eq!(
+            Some(updated_proposal),
+            store
+                .get_proposal("WBKLF-BBBBB")
+                .expect("Unable to get proposal")
+        );
+
+        assert_eq!(
+            1,
+            store
+                .list_proposals(&[CircuitPredicate::ManagmentTypeEq("test".into())])
+                .expect("Unable to list proposals")
+                .len()
+        );
+        assert_eq!(
+            0,
+            store
+                .list_proposals(&[CircuitPredicate::ManagmentTypeEq("other".into())])
+                .expect("Unable to list proposals")
+                .len()
+        );
+
+        store
+            .upgrade_proposal_to_circuit("WBKLF-BBBBB")
+            .expect("Unable to upgrade proposal");
+
+        assert_eq!(
+            None,
+            store
+                .get_proposal("WBKLF-BBBBB")
+                .expect("Unable to get proposal")
+        );
+        let circuit = store
+            .get_circuit("WBKLF-BBBBB")
+            .expect("Unable to get circuit")
+            .expect("Circuit was not added");
+        assert_eq!(b"test metadata", circuit.application_metadata());
+        assert_eq!(2, store.list_nodes().expect("Unable to list nodes").len());
+        assert_eq!(
+            Some("scabbard"),
+            store
+                .get_service(&ServiceId::new("WBKLF-BBBBB".into(), "a000".into()))
+                .expect("Unable to get service")
+                .as_ref()
+                .map(|service| service.service_type())
+        );
+    }
+
+    /// Verify that a circuit can be updated and removed, and that updating or removing the
+    /// circuit adds the new members' nodes and removes the nodes that are no longer a member of
+    /// any circuit.
+    #[test]
+    fn test_update_and_remove_circuit() {
+        let store = MemoryAdminServiceStore::new();
+        let circuit = create_circuit(&["acme-node-000", "bubba-node-000"]);
+
+        store
+            .add_circuit(
+                circuit.clone(),
+                vec![create_node("acme-node-000"), create_node("bubba-node-000")],
+            )
+            .expect("Unable to add circuit");
+        assert!(store.add_circuit(circuit.clone(), vec![]).is_err());
+
+        let updated_circuit = create_circuit(&["acme-node-000"]);
+        store
+            .update_circuit(updated_circuit.clone(), vec![create_node("acme-node-000")])
+            .expect("Unable to update circuit");
+        assert_eq!(
+            Some(updated_circuit),
+            store
+                .get_circuit("WBKLF-AAAAA")
+                .expect("Unable to get circuit")
+        );
+        assert_eq!(
+            None,
+            store
+                .get_node("bubba-node-000")
+                .expect("Unable to get node")
+        );
+
+        let updated_circuit = create_circuit(&["acme-node-000", "bubba-node-000"]);
+        store
+            .update_circuit(
+                updated_circuit,
+                vec![create_node("acme-node-000"), create_node("bubba-node-000")],
+            )
+            .expect("Unable to update circuit");
+        assert_eq!(
+            Some(create_node("bubba-node-000")),
+            store
+                .get_node("bubba-node-000")
+                .expect("Unable to get node")
+        );
+
+        store
+            .remove_circuit("WBKLF-AAAAA")
+            .expect("Unable to remove circuit");
+        assert_eq!(
+            0,
+            store
+                .list_circuits(&[])
+                .expect("Unable to list circuits")
+                .len()
+        );
+        assert_eq!(
+            None,
+            store.get_node("acme-node-000").expect("Unable to get node")
+        );
+        assert!(store.remove_circuit("WBKLF-AAAAA").is_err());
+    }
+
+    fn create_proposal() -> CircuitProposal {
+        CircuitProposalBuilder::new()
+            .with_proposal_type(&ProposalType::Create)
+            .with_circuit_id("WBKLF-BBBBB")
+            .with_circuit_hash("7ddc426972710adc0b2ecd49e89a9dd805fb9206bf516079724c887bedbcdf1d")
+            .with_circuit(
+                &ProposedCircuitBuilder::new()
+                    .with_circuit_id("WBKLF-BBBBB")
+                    .with_roster(&[ProposedServiceBuilder::new()
+                        .with_service_id("a000")
+                        .with_service_type("scabbard")
+                        .with_node_id("acme-node-000")
+                        .with_arguments(&[("peer_services".into(), "[]".into())])
+                        .build()
+                        .expect("Unable to build service")])
+                    .with_members(&[
+                        ProposedNodeBuilder::new()
+                            .with_node_id("acme-node-000")
+                            .with_endpoints(&["tcps://splinterd-node-acme:8044".into()])
+                            .build()
+                            .expect("Unable to build node"),
+                        ProposedNodeBuilder::new()
+                            .with_node_id("bubba-node-000")
+                            .with_endpoints(&["tcps://splinterd-node-bubba:8044".into()])
+                            .build()
+                            .expect("Unable to build node"),
+                    ])
+                    .with_circuit_management_type("test")
+                    .with_application_metadata(b"test metadata")
+                    .with_comments("test circuit")
+                    .build()
+                    .expect("Unable to build circuit"),
+            )
+            .with_requester(&[1, 2, 3])
+            .with_requester_node_id("acme-node-000")
+            .build()
+            .expect("Unable to build proposal")
+    }
+
+    fn create_circuit(members: &[&str]) -> Circuit {
+        CircuitBuilder::new()
+            .with_circuit_id("WBKLF-AAAAA")
+            .with_roster(&[ServiceBuilder::new()
+                .with_service_id("a000")
+                .with_service_type("scabbard")
+                .with_node_id("acme-node-000")
+                .build()
+                .expect("Unable to build service")])
+            .with_members(
+                &members
+                    .iter()
+                    .map(|member| member.to_string())
+                    .collect::<Vec<_>>(),
+            )
+            .with_circuit_management_type("test")
+            .build()
+            .expect("Unable to build circuit")
+    }
+
+    fn create_node(node_id: &str) -> CircuitNode {
+        CircuitNodeBuilder::new()
+            .with_node_id(node_id)
+            .with_endpoints(&[format!("tcps://{}:8044", node_id)])
+            .build()
+            .expect("Unable to build node")
+    }
+}
@@ -18,21 +18,24 @@
 //! proposals. Splinter provides the following implementations of this trait:
 //!
 //! * [`YamlAdminServiceStore`] - A YAML-backed store that is available by default
+//! * [`MemoryAdminServiceStore`] - A memory-backed store, whose contents are lost on restart
 //! * [`DieselAdminServiceStore`] - A database-backed store, powered by [`Diesel`], that currently
 //!   supports SQLite databases (with the `sqlite` feature) and PostgreSQL databases (with the
 //!   `postgres` feature).
 //!
 //! [`AdminServiceStore`]: trait.AdminServiceStore.html
 //! [`YamlAdminServiceStore`]: yaml/struct.YamlAdminServiceStore.html
+//! [`MemoryAdminServiceStore`]: struct.MemoryAdminServiceStore.html
 //! [`DieselAdminServiceStore`]: diesel/struct.DieselAdminServiceStore.html
 //! [`Diesel`]: https://crates.io/crates/diesel
 
 mod circuit;
 mod circuit_node;
 mod circuit_proposal;
-#[cfg(feature = "diesel")]
+#[cfg(all(feature = "admin-service-store", feature = "diesel"))]
 pub mod diesel;
 pub mod error;
+mod memory;
 mod proposed_circuit;
 mod proposed_node;
 mod proposed_service;
@@ -40,16 +43,22 @@ mod service;
 pub mod yaml;
 
 use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt;
 
+use crate::circuit::store::{CircuitFilter, CircuitIter, CircuitStore, CircuitStoreError};
+use crate::circuit::Circuit as StateCircuit;
+
 pub use self::circuit::{
-    AuthorizationType, Circuit, CircuitBuilder, DurabilityType, PersistenceType, RouteType,
+    AuthorizationType, Circuit, CircuitBuilder, CircuitStatus, DurabilityType, PersistenceType,
+    RouteType, VotingPolicy,
 };
 pub use self::circuit_node::{CircuitNode, CircuitNodeBuilder};
 pub use self::circuit_proposal::{
     CircuitProposal, CircuitProposalBuilder, ProposalType, Vote, VoteRecord, VoteRecordBuilder,
 };
 use self::error::AdminServiceStoreError;
+pub use self::memory::MemoryAdminServiceStore;
 pub use self::proposed_circuit::{ProposedCircuit, ProposedCircuitBuilder};
 pub use self::proposed_node::{ProposedNode, ProposedNodeBuilder};
 pub use self::proposed_service::{ProposedService, ProposedServiceBuilder};
@@ -228,14 +237,19 @@ pub trait AdminServiceStore: Send + Sync {
         nodes: Vec<CircuitNode>,
     ) -> Result<(), AdminServiceStoreError>;
 
-    /// Updates a circuit in the store
+    /// Updates a circuit in the store along with the associated services and nodes
     ///
     /// # Arguments
     ///
     ///  * `circuit` - The circuit with the updated information
+    ///  * `nodes` - A list of nodes that represent the circuit's members
     ///
-    ///  Returns an error if a `CircuitProposal` with the same ID does not exist
-    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError>;
+    ///  Returns an error if a `Circuit` with the same ID does not exist
+    fn update_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError>;
 
     /// Removes a circuit from the store
     ///
@@ -301,4 +315,46 @@ pub trait AdminServiceStore: Send + Sync {
         &self,
         circuit_id: &str,
     ) -> Result<Box<dyn ExactSizeIterator<Item = Service>>, AdminServiceStoreError>;
+
+    /// Returns a boxed copy of this store, which operates on the same underlying storage
+    fn clone_boxed(&self) -> Box<dyn AdminServiceStore>;
+}
+
+impl Clone for Box<dyn AdminServiceStore> {
+    fn clone(&self) -> Self {
+        self.clone_boxed()
+    }
+}
+
+impl CircuitStore for Box<dyn AdminServiceStore> {
+    fn circuits(&self, filter: Option<CircuitFilter>) -> Result<CircuitIter, CircuitStoreError> {
+        let predicates = match filter {
+            Some(CircuitFilter::WithMember(member)) => {
+                vec![CircuitPredicate::MembersInclude(vec![member])]
+            }
+            None => vec![],
+        };
+
+        let circuits = self
+            .list_circuits(&predicates)
+            .map_err(|err| CircuitStoreError::from_source("Unable to list circuits".into(), err))?
+            .map(|circuit| StateCircuit::try_from(&circuit))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                CircuitStoreError::from_source("Unable to convert circuit".into(), err)
+            })?;
+
+        Ok(CircuitIter::new(
+            circuits.len() as u64,
+            Box::new(circuits.into_iter()),
+        ))
+    }
+
+    fn circuit(&self, circuit_name: &str) -> Result<Option<StateCircuit>, CircuitStoreError> {
+        self.get_circuit(circuit_name)
+            .map_err(|err| CircuitStoreError::from_source("Unable to fetch circuit".into(), err))?
+            .map(|circuit| StateCircuit::try_from(&circuit))
+            .transpose()
+            .map_err(|err| CircuitStoreError::from_source("Unable to convert circuit".into(), err))
+    }
 }
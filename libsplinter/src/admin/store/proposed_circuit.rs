@@ -14,11 +14,14 @@
 
 //! Structs for building proposed circuits
 
-use crate::admin::messages::is_valid_circuit_id;
+use std::convert::TryFrom;
+
+use crate::admin::messages::{self, is_valid_circuit_id};
 
 use super::error::BuilderError;
 use super::{
     AuthorizationType, DurabilityType, PersistenceType, ProposedNode, ProposedService, RouteType,
+    VotingPolicy,
 };
 
 /// Native representation of a circuit that is being proposed in a proposal
@@ -34,6 +37,7 @@ pub struct ProposedCircuit {
     circuit_management_type: String,
     application_metadata: Vec<u8>,
     comments: String,
+    voting_policy: VotingPolicy,
 }
 
 impl ProposedCircuit {
@@ -85,6 +89,11 @@ impl ProposedCircuit {
     pub fn comments(&self) -> &str {
         &self.comments
     }
+
+    /// Returns the voting policy of the circuit
+    pub fn voting_policy(&self) -> &VotingPolicy {
+        &self.voting_policy
+    }
 }
 
 /// Builder to be used to build a `ProposedCircuit` which will be included in a `CircuitProposal`
@@ -100,6 +109,7 @@ pub struct ProposedCircuitBuilder {
     circuit_management_type: Option<String>,
     application_metadata: Option<Vec<u8>>,
     comments: Option<String>,
+    voting_policy: Option<VotingPolicy>,
 }
 
 impl ProposedCircuitBuilder {
@@ -158,6 +168,11 @@ impl ProposedCircuitBuilder {
         self.comments.clone()
     }
 
+    /// Returns the voting policy of the circuit proposal in the builder
+    pub fn voting_policy(&self) -> Option<VotingPolicy> {
+        self.voting_policy.clone()
+    }
+
     /// Sets the circuit ID
     ///
     /// # Arguments
@@ -264,6 +279,17 @@ impl ProposedCircuitBuilder {
         self
     }
 
+    /// Sets the voting policy
+    ///
+    /// # Arguments
+    ///
+    ///  * `voting_policy` - The policy used to decide whether a proposal for the circuit has been
+    ///    accepted
+    pub fn with_voting_policy(mut self, voting_policy: &VotingPolicy) -> ProposedCircuitBuilder {
+        self.voting_policy = Some(voting_policy.clone());
+        self
+    }
+
     /// Builds a `ProposedCircuit`
     ///
     /// Returns an error if the circuit ID, roster, members or circuit management
@@ -309,6 +335,15 @@ impl ProposedCircuitBuilder {
 
         let comments = self.comments.unwrap_or_default();
 
+        let voting_policy = match self.voting_policy.unwrap_or_default() {
+            VotingPolicy::Threshold(0) => {
+                return Err(BuilderError::InvalidField(
+                    "voting_policy threshold must require at least one vote".to_string(),
+                ))
+            }
+            voting_policy => voting_policy,
+        };
+
         let create_circuit_message = ProposedCircuit {
             circuit_id,
             roster,
@@ -320,8 +355,78 @@ impl ProposedCircuitBuilder {
             circuit_management_type,
             application_metadata,
             comments,
+            voting_policy,
         };
 
         Ok(create_circuit_message)
     }
 }
+
+impl TryFrom<&messages::CreateCircuit> for ProposedCircuit {
+    type Error = BuilderError;
+
+    fn try_from(circuit: &messages::CreateCircuit) -> Result<Self, Self::Error> {
+        ProposedCircuitBuilder::new()
+            .with_circuit_id(&circuit.circuit_id)
+            .with_roster(
+                &circuit
+                    .roster
+                    .iter()
+                    .map(ProposedService::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+            .with_members(
+                &circuit
+                    .members
+                    .iter()
+                    .map(ProposedNode::from)
+                    .collect::<Vec<_>>(),
+            )
+            .with_authorization_type(&AuthorizationType::from(&circuit.authorization_type))
+            .with_persistence(&PersistenceType::from(&circuit.persistence))
+            .with_durability(&DurabilityType::from(&circuit.durability))
+            .with_routes(&RouteType::from(&circuit.routes))
+            .with_circuit_management_type(&circuit.circuit_management_type)
+            .with_application_metadata(&circuit.application_metadata)
+            .with_comments(&circuit.comments)
+            .with_voting_policy(&VotingPolicy::from(&circuit.voting_policy))
+            .build()
+    }
+}
+
+/// The roster, the service arguments and the members are sorted, so that the resulting message
+/// does not depend on the order in which a store returned them.
+impl From<&ProposedCircuit> for messages::CreateCircuit {
+    fn from(circuit: &ProposedCircuit) -> Self {
+        let mut roster: Vec<messages::SplinterService> = circuit
+            .roster
+            .iter()
+            .map(messages::SplinterService::from)
+            .collect();
+        roster.sort_by(|a, b| a.service_id.cmp(&b.service_id));
+        for service in roster.iter_mut() {
+            service.arguments.sort();
+        }
+
+        let mut members: Vec<messages::SplinterNode> = circuit
+            .members
+            .iter()
+            .map(messages::SplinterNode::from)
+            .collect();
+        members.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+
+        messages::CreateCircuit {
+            circuit_id: circuit.circuit_id.clone(),
+            roster,
+            members,
+            authorization_type: messages::AuthorizationType::from(&circuit.authorization_type),
+            persistence: messages::PersistenceType::from(&circuit.persistence),
+            durability: messages::DurabilityType::from(&circuit.durability),
+            routes: messages::RouteType::from(&circuit.routes),
+            circuit_management_type: circuit.circuit_management_type.clone(),
+            application_metadata: circuit.application_metadata.clone(),
+            comments: circuit.comments.clone(),
+            voting_policy: messages::VotingPolicy::from(&circuit.voting_policy),
+        }
+    }
+}
@@ -14,6 +14,8 @@
 
 //! Structs for building proposed nodes
 
+use crate::admin::messages;
+
 use super::error::BuilderError;
 
 /// Native representation of a node in a proposed circuit
@@ -95,3 +97,21 @@ impl ProposedNodeBuilder {
         Ok(node)
     }
 }
+
+impl From<&messages::SplinterNode> for ProposedNode {
+    fn from(node: &messages::SplinterNode) -> Self {
+        ProposedNode {
+            node_id: node.node_id.clone(),
+            endpoints: node.endpoints.clone(),
+        }
+    }
+}
+
+impl From<&ProposedNode> for messages::SplinterNode {
+    fn from(node: &ProposedNode) -> Self {
+        messages::SplinterNode {
+            node_id: node.node_id.clone(),
+            endpoints: node.endpoints.clone(),
+        }
+    }
+}
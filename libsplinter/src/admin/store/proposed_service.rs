@@ -13,7 +13,10 @@
 // limitations under the License.
 
 //! Structs for building proposed services
-use crate::admin::messages::is_valid_service_id;
+
+use std::convert::TryFrom;
+
+use crate::admin::messages::{self, is_valid_service_id};
 
 use super::error::BuilderError;
 
@@ -158,3 +161,37 @@ impl ProposedServiceBuilder {
         Ok(service)
     }
 }
+
+impl TryFrom<&messages::SplinterService> for ProposedService {
+    type Error = BuilderError;
+
+    fn try_from(service: &messages::SplinterService) -> Result<Self, Self::Error> {
+        let node_id = match service.allowed_nodes.as_slice() {
+            [node_id] => node_id,
+            _ => {
+                return Err(BuilderError::InvalidField(format!(
+                    "service {} must have exactly one allowed node",
+                    service.service_id
+                )))
+            }
+        };
+
+        ProposedServiceBuilder::new()
+            .with_service_id(&service.service_id)
+            .with_service_type(&service.service_type)
+            .with_node_id(node_id)
+            .with_arguments(&service.arguments)
+            .build()
+    }
+}
+
+impl From<&ProposedService> for messages::SplinterService {
+    fn from(service: &ProposedService) -> Self {
+        messages::SplinterService {
+            service_id: service.service_id.clone(),
+            service_type: service.service_type.clone(),
+            allowed_nodes: vec![service.node_id.clone()],
+            arguments: service.arguments.clone(),
+        }
+    }
+}
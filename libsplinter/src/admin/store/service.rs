@@ -14,7 +14,10 @@
 
 //! Structs for building services
 
+use std::convert::TryFrom;
+
 use crate::admin::messages::is_valid_service_id;
+use crate::circuit::ServiceDefinition;
 
 use super::error::BuilderError;
 use super::ProposedService;
@@ -182,3 +185,41 @@ impl From<&ProposedService> for Service {
         }
     }
 }
+
+impl TryFrom<&ServiceDefinition> for Service {
+    type Error = BuilderError;
+
+    fn try_from(service: &ServiceDefinition) -> Result<Self, Self::Error> {
+        let node_id = match service.allowed_nodes() {
+            [node_id] => node_id,
+            _ => {
+                return Err(BuilderError::InvalidField(format!(
+                    "service {} must have exactly one allowed node",
+                    service.service_id()
+                )))
+            }
+        };
+
+        ServiceBuilder::new()
+            .with_service_id(service.service_id())
+            .with_service_type(service.service_type())
+            .with_node_id(node_id)
+            .with_arguments(
+                &service
+                    .arguments()
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<Vec<_>>(),
+            )
+            .build()
+    }
+}
+
+impl From<&Service> for ServiceDefinition {
+    fn from(service: &Service) -> Self {
+        ServiceDefinition::builder(service.service_id.clone(), service.service_type.clone())
+            .with_allowed_nodes(vec![service.node_id.clone()])
+            .with_arguments(service.arguments.clone())
+            .build()
+    }
+}
@@ -27,20 +27,22 @@ use std::fs::{rename, File};
 use std::io::Write;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, UNIX_EPOCH};
 
 use self::error::YamlAdminStoreError;
 
 use super::{
     error::BuilderError, AdminServiceStore, AdminServiceStoreError, AuthorizationType, Circuit,
     CircuitBuilder, CircuitNode, CircuitPredicate, CircuitProposal, CircuitProposalBuilder,
-    DurabilityType, PersistenceType, ProposalType, ProposedCircuit, ProposedCircuitBuilder,
-    ProposedNode, ProposedService, ProposedServiceBuilder, RouteType, Service, ServiceBuilder,
-    ServiceId, Vote, VoteRecord, VoteRecordBuilder,
+    CircuitStatus, DurabilityType, PersistenceType, ProposalType, ProposedCircuit,
+    ProposedCircuitBuilder, ProposedNode, ProposedService, ProposedServiceBuilder, RouteType,
+    Service, ServiceBuilder, ServiceId, Vote, VoteRecord, VoteRecordBuilder, VotingPolicy,
 };
 
-use crate::hex::{parse_hex, to_hex};
+use crate::hex::{as_hex, deserialize_hex, parse_hex, to_hex};
 
 /// A YAML backed implementation of the `AdminServiceStore`
+#[derive(Clone)]
 pub struct YamlAdminServiceStore {
     circuit_file_path: String,
     proposal_file_path: String,
@@ -731,9 +733,14 @@ impl AdminServiceStore for YamlAdminServiceStore {
     /// # Arguments
     ///
     ///  * `circuit` - The circuit with the updated information
+    ///  * `nodes` - A list of nodes that represent the circuit's members
     ///
-    ///  Returns an error if a `CircuitProposal` with the same ID does not exist
-    fn update_circuit(&self, circuit: Circuit) -> Result<(), AdminServiceStoreError> {
+    ///  Returns an error if a `Circuit` with the same ID does not exist
+    fn update_circuit(
+        &self,
+        circuit: Circuit,
+        nodes: Vec<CircuitNode>,
+    ) -> Result<(), AdminServiceStoreError> {
         {
             let mut state =
                 self.state
@@ -749,6 +756,35 @@ impl AdminServiceStore for YamlAdminServiceStore {
                 .circuits
                 .contains_key(circuit.circuit_id())
             {
+                let previous_roster = state.circuit_state.circuits[circuit.circuit_id()]
+                    .roster()
+                    .to_vec();
+                for service in previous_roster {
+                    let service_id = ServiceId::new(
+                        service.service_id().to_string(),
+                        circuit.circuit_id().to_string(),
+                    );
+                    state.service_directory.remove(&service_id);
+                }
+
+                for service in circuit.roster() {
+                    let service_id = ServiceId::new(
+                        service.service_id().to_string(),
+                        circuit.circuit_id().to_string(),
+                    );
+
+                    state.service_directory.insert(service_id, service.clone());
+                }
+
+                for node in nodes.into_iter() {
+                    if !state.circuit_state.nodes.contains_key(node.node_id()) {
+                        state
+                            .circuit_state
+                            .nodes
+                            .insert(node.node_id().to_string(), node);
+                    }
+                }
+
                 state
                     .circuit_state
                     .circuits
@@ -1008,6 +1044,10 @@ impl AdminServiceStore for YamlAdminServiceStore {
 
         Ok(Box::new(services.into_iter()))
     }
+
+    fn clone_boxed(&self) -> Box<dyn AdminServiceStore> {
+        Box::new(self.clone())
+    }
 }
 
 /// YAML file specific circuit definition. This circuit definition in the 0.4v YAML stores service
@@ -1024,6 +1064,19 @@ struct YamlCircuit {
     durability: DurabilityType,
     routes: RouteType,
     circuit_management_type: String,
+    #[serde(default, skip_serializing_if = "CircuitStatus::is_active")]
+    circuit_status: CircuitStatus,
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "as_hex",
+        deserialize_with = "deserialize_hex"
+    )]
+    application_metadata: Vec<u8>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    comments: String,
+    #[serde(default, skip_serializing_if = "VotingPolicy::is_unanimous")]
+    voting_policy: VotingPolicy,
 }
 
 impl TryFrom<YamlCircuit> for Circuit {
@@ -1045,6 +1098,10 @@ impl TryFrom<YamlCircuit> for Circuit {
             .with_durability(&circuit.durability)
             .with_routes(&circuit.routes)
             .with_circuit_management_type(&circuit.circuit_management_type)
+            .with_circuit_status(&circuit.circuit_status)
+            .with_application_metadata(&circuit.application_metadata)
+            .with_comments(&circuit.comments)
+            .with_voting_policy(&circuit.voting_policy)
             .build()
     }
 }
@@ -1064,6 +1121,10 @@ impl From<Circuit> for YamlCircuit {
             durability: circuit.durability().clone(),
             routes: circuit.routes().clone(),
             circuit_management_type: circuit.circuit_management_type().into(),
+            circuit_status: circuit.circuit_status().clone(),
+            application_metadata: circuit.application_metadata().to_vec(),
+            comments: circuit.comments().into(),
+            voting_policy: circuit.voting_policy().clone(),
         }
     }
 }
@@ -1176,6 +1237,9 @@ pub struct YamlCircuitProposal {
     votes: Vec<YamlVoteRecord>,
     requester: String,
     requester_node_id: String,
+    // seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
 }
 
 impl From<ProposalState> for YamlProposalState {
@@ -1211,7 +1275,7 @@ impl TryFrom<YamlCircuitProposal> for CircuitProposal {
     type Error = BuilderError;
 
     fn try_from(proposal: YamlCircuitProposal) -> Result<Self, Self::Error> {
-        CircuitProposalBuilder::new()
+        let builder = CircuitProposalBuilder::new()
             .with_circuit_id(&proposal.circuit_id)
             .with_proposal_type(&proposal.proposal_type)
             .with_circuit_hash(&proposal.circuit_hash)
@@ -1226,8 +1290,13 @@ impl TryFrom<YamlCircuitProposal> for CircuitProposal {
             .with_requester(&parse_hex(&proposal.requester).map_err(|_| {
                 BuilderError::InvalidField("Requester public key is not valid hex".to_string())
             })?)
-            .with_requester_node_id(&proposal.requester_node_id)
-            .build()
+            .with_requester_node_id(&proposal.requester_node_id);
+
+        match proposal.expires_at {
+            Some(secs) => builder.with_expires_at(UNIX_EPOCH + Duration::from_secs(secs)),
+            None => builder,
+        }
+        .build()
     }
 }
 
@@ -1245,6 +1314,12 @@ impl From<CircuitProposal> for YamlCircuitProposal {
                 .collect(),
             requester: to_hex(proposal.requester()),
             requester_node_id: proposal.requester_node_id().into(),
+            expires_at: proposal.expires_at().map(|expires_at| {
+                expires_at
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs()
+            }),
         }
     }
 }
@@ -1257,6 +1332,8 @@ pub struct YamlVoteRecord {
     public_key: String,
     vote: Vote,
     voter_node_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    previous_votes: Vec<Vote>,
 }
 
 impl TryFrom<YamlVoteRecord> for VoteRecord {
@@ -1269,6 +1346,7 @@ impl TryFrom<YamlVoteRecord> for VoteRecord {
             })?)
             .with_vote(&vote.vote)
             .with_voter_node_id(&vote.voter_node_id)
+            .with_previous_votes(&vote.previous_votes)
             .build()
     }
 }
@@ -1279,6 +1357,7 @@ impl From<VoteRecord> for YamlVoteRecord {
             public_key: to_hex(vote.public_key()),
             vote: vote.vote().clone(),
             voter_node_id: vote.voter_node_id().into(),
+            previous_votes: vote.previous_votes().to_vec(),
         }
     }
 }
@@ -1298,6 +1377,8 @@ struct YamlProposedCircuit {
     circuit_management_type: String,
     application_metadata: String,
     comments: String,
+    #[serde(default, skip_serializing_if = "VotingPolicy::is_unanimous")]
+    voting_policy: VotingPolicy,
 }
 
 impl TryFrom<YamlProposedCircuit> for ProposedCircuit {
@@ -1323,6 +1404,7 @@ impl TryFrom<YamlProposedCircuit> for ProposedCircuit {
                 BuilderError::InvalidField("Requester public key is not valid hex".to_string())
             })?)
             .with_comments(&circuit.comments)
+            .with_voting_policy(&circuit.voting_policy)
             .build()
     }
 }
@@ -1345,6 +1427,7 @@ impl From<ProposedCircuit> for YamlProposedCircuit {
             circuit_management_type: circuit.circuit_management_type().into(),
             application_metadata: to_hex(circuit.application_metadata()),
             comments: circuit.comments().into(),
+            voting_policy: circuit.voting_policy().clone(),
         }
     }
 }
@@ -1590,6 +1673,38 @@ proposals:
             .is_some());
     }
 
+    // Validate that the YAML admin service store refuses to load a circuit whose threshold voting
+    // policy does not require any votes
+    #[test]
+    fn test_read_zero_threshold_voting_policy() {
+        let temp_dir = TempDir::new("test_read_zero_threshold_voting_policy")
+            .expect("Failed to create temp dir");
+        let circuit_path = temp_dir
+            .path()
+            .join("circuits.yaml")
+            .to_str()
+            .expect("Failed to get path")
+            .to_string();
+
+        let proposals_path = temp_dir
+            .path()
+            .join("circuit_proposals.yaml")
+            .to_str()
+            .expect("Failed to get path")
+            .to_string();
+
+        let mut circuit_state = CIRCUIT_STATE.to_vec();
+        circuit_state.extend_from_slice(
+            b"
+        voting_policy:
+            Threshold: 0",
+        );
+        write_file(&circuit_state, &circuit_path);
+        write_file(PROPOSAL_STATE, &proposals_path);
+
+        assert!(YamlAdminServiceStore::new(circuit_path, proposals_path).is_err());
+    }
+
     // Test the proposal CRUD operations
     //
     // 1. Setup the temp directory with existing state
@@ -1795,13 +1910,15 @@ proposals:
                 .expect("Unable to build circuit");
 
         store
-            .update_circuit(updated_circuit.clone())
+            .update_circuit(updated_circuit.clone(), vec![])
             .expect("Unable to update circuit");
 
         let (new_circuit, new_node) = new_circuit();
 
         assert!(
-            store.update_circuit(new_circuit.clone()).is_err(),
+            store
+                .update_circuit(new_circuit.clone(), vec![new_node.clone()])
+                .is_err(),
             "Updating new cirucit should fail"
         );
 
@@ -0,0 +1,66 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum DurableMessageStoreError {
+    /// Represents failures to read or write the stored messages
+    StorageError {
+        context: String,
+        source: Option<Box<dyn Error + Send>>,
+    },
+    /// Represents failures to start delivering stored messages
+    ForwardingError(String),
+    /// Represents a lock that was poisoned by a panicking thread
+    PoisonedLock(String),
+}
+
+impl Error for DurableMessageStoreError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DurableMessageStoreError::StorageError {
+                source: Some(source),
+                ..
+            } => Some(&**source),
+            DurableMessageStoreError::StorageError { source: None, .. } => None,
+            DurableMessageStoreError::ForwardingError(_) => None,
+            DurableMessageStoreError::PoisonedLock(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for DurableMessageStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DurableMessageStoreError::StorageError {
+                context,
+                source: Some(source),
+            } => write!(
+                f,
+                "the underlying storage returned an error: {}: {}",
+                context, source
+            ),
+            DurableMessageStoreError::StorageError {
+                context,
+                source: None,
+            } => write!(f, "the underlying storage returned an error: {}", context),
+            DurableMessageStoreError::ForwardingError(msg) => {
+                write!(f, "unable to forward stored messages: {}", msg)
+            }
+            DurableMessageStoreError::PoisonedLock(msg) => write!(f, "lock was poisoned: {}", msg),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use atomicwrites::{AllowOverwrite, AtomicFile};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::hex::{parse_hex, to_hex};
+
+use super::{DurableMessage, DurableMessageStore, DurableMessageStoreError};
+
+const MESSAGES_FILE_EXTENSION: &str = "messages";
+
+/// A `DurableMessageStore` that keeps the messages for each node in a file in a directory.
+///
+/// Each file is named after the hex-encoded node ID. Messages are appended to the file as they are
+/// stored, and the file is rewritten when messages are removed. The messages are also kept in
+/// memory, so they are only read from the directory when the store is created.
+pub struct FileDurableMessageStore {
+    directory: PathBuf,
+    messages: HashMap<String, Vec<DurableMessage>>,
+}
+
+impl FileDurableMessageStore {
+    /// Opens the store in the given directory, creating the directory if it does not exist, and
+    /// loads the messages stored by a previous run.
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Result<Self, DurableMessageStoreError> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory).map_err(|err| {
+            storage_error(
+                format!("Unable to create directory {}", directory.display()),
+                err,
+            )
+        })?;
+
+        let entries = fs::read_dir(&directory).map_err(|err| {
+            storage_error(
+                format!("Unable to read directory {}", directory.display()),
+                err,
+            )
+        })?;
+
+        let mut messages = HashMap::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|err| {
+                    storage_error(
+                        format!("Unable to read directory {}", directory.display()),
+                        err,
+                    )
+                })?
+                .path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(MESSAGES_FILE_EXTENSION) {
+                continue;
+            }
+
+            let node_id = match path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| parse_hex(stem).ok())
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+            {
+                Some(node_id) => node_id,
+                None => {
+                    warn!(
+                        "Ignoring unexpected durable message file {}",
+                        path.display()
+                    );
+                    continue;
+                }
+            };
+
+            let node_messages = read_messages(&path, &node_id)?;
+            if !node_messages.is_empty() {
+                messages.insert(node_id, node_messages);
+            }
+        }
+
+        Ok(Self {
+            directory,
+            messages,
+        })
+    }
+
+    fn node_path(&self, node_id: &str) -> PathBuf {
+        self.directory.join(format!(
+            "{}.{}",
+            to_hex(node_id.as_bytes()),
+            MESSAGES_FILE_EXTENSION
+        ))
+    }
+
+    /// Replaces the messages for the given node, removing its file if none are left.
+    fn rewrite_node(
+        &mut self,
+        node_id: &str,
+        remaining: Vec<DurableMessage>,
+    ) -> Result<(), DurableMessageStoreError> {
+        let path = self.node_path(node_id);
+        if remaining.is_empty() {
+            match fs::remove_file(&path) {
+                Ok(()) => (),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => (),
+                Err(err) => {
+                    return Err(storage_error(
+                        format!("Unable to remove {}", path.display()),
+                        err,
+                    ))
+                }
+            }
+            self.messages.remove(node_id);
+        } else {
+            AtomicFile::new(&path, AllowOverwrite)
+                .write(|file| {
+                    remaining
+                        .iter()
+                        .try_for_each(|message| write_message(file, message))
+                })
+                .map_err(|err| {
+                    storage_error(format!("Unable to rewrite {}", path.display()), err)
+                })?;
+            self.messages.insert(node_id.to_string(), remaining);
+        }
+
+        Ok(())
+    }
+}
+
+impl DurableMessageStore for FileDurableMessageStore {
+    fn append(&mut self, message: DurableMessage) -> Result<(), DurableMessageStoreError> {
+        let path = self.node_path(&message.node_id);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|err| storage_error(format!("Unable to open {}", path.display()), err))?;
+
+        let mut record = vec![];
+        write_message(&mut record, &message)
+            .map_err(|err| storage_error("Unable to serialize message".into(), err))?;
+        file.write_all(&record)
+            .and_then(|_| file.sync_data())
+            .map_err(|err| storage_error(format!("Unable to write {}", path.display()), err))?;
+
+        self.messages
+            .entry(message.node_id.clone())
+            .or_default()
+            .push(message);
+        Ok(())
+    }
+
+    fn list_for_node(
+        &self,
+        node_id: &str,
+    ) -> Result<Vec<DurableMessage>, DurableMessageStoreError> {
+        Ok(self.messages.get(node_id).cloned().unwrap_or_default())
+    }
+
+    fn remove_for_node(
+        &mut self,
+        node_id: &str,
+        count: usize,
+    ) -> Result<(), DurableMessageStoreError> {
+        let remaining = match self.messages.get(node_id) {
+            Some(messages) => messages[count.min(messages.len())..].to_vec(),
+            None => return Ok(()),
+        };
+
+        self.rewrite_node(node_id, remaining)
+    }
+
+    fn circuit_size(&self, circuit_id: &str) -> Result<usize, DurableMessageStoreError> {
+        Ok(self
+            .messages
+            .values()
+            .flatten()
+            .filter(|message| message.circuit_id == circuit_id)
+            .map(|message| message.message_bytes.len())
+            .sum())
+    }
+
+    fn remove_expired(
+        &mut self,
+        circuit_id: &str,
+        stored_before: u64,
+    ) -> Result<usize, DurableMessageStoreError> {
+        let expired_nodes = self
+            .messages
+            .iter()
+            .filter(|(_, messages)| {
+                messages.iter().any(|message| {
+                    message.circuit_id == circuit_id && message.stored_at < stored_before
+                })
+            })
+            .map(|(node_id, messages)| (node_id.clone(), messages.clone()))
+            .collect::<Vec<_>>();
+
+        let mut removed = 0;
+        for (node_id, messages) in expired_nodes {
+            let count = messages.len();
+            let remaining = messages
+                .into_iter()
+                .filter(|message| {
+                    message.circuit_id != circuit_id || message.stored_at >= stored_before
+                })
+                .collect::<Vec<_>>();
+            removed += count - remaining.len();
+            self.rewrite_node(&node_id, remaining)?;
+        }
+
+        Ok(removed)
+    }
+}
+
+/// Writes a message as its length-prefixed circuit ID, the time it was stored and its
+/// length-prefixed bytes.
+fn write_message<W: Write>(writer: &mut W, message: &DurableMessage) -> io::Result<()> {
+    writer.write_u32::<BigEndian>(message.circuit_id.len() as u32)?;
+    writer.write_all(message.circuit_id.as_bytes())?;
+    writer.write_u64::<BigEndian>(message.stored_at)?;
+    writer.write_u32::<BigEndian>(message.message_bytes.len() as u32)?;
+    writer.write_all(&message.message_bytes)
+}
+
+/// Reads the messages in the given file. A message that was only partly written, because the node
+/// stopped while writing it, is discarded.
+fn read_messages(
+    path: &Path,
+    node_id: &str,
+) -> Result<Vec<DurableMessage>, DurableMessageStoreError> {
+    let mut bytes = vec![];
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut bytes))
+        .map_err(|err| storage_error(format!("Unable to read {}", path.display()), err))?;
+
+    let mut reader = &bytes[..];
+    let mut messages = vec![];
+    while !reader.is_empty() {
+        match read_message(&mut reader, node_id) {
+            Ok(message) => messages.push(message),
+            Err(_) => {
+                warn!(
+                    "Discarding incomplete message at the end of {}",
+                    path.display()
+                );
+                break;
+            }
+        }
+    }
+
+    Ok(messages)
+}
+
+fn read_message(reader: &mut &[u8], node_id: &str) -> io::Result<DurableMessage> {
+    let circuit_id_len = reader.read_u32::<BigEndian>()? as usize;
+    let mut circuit_id = vec![0; circuit_id_len];
+    reader.read_exact(&mut circuit_id)?;
+    let circuit_id = String::from_utf8(circuit_id)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let stored_at = reader.read_u64::<BigEndian>()?;
+
+    let message_len = reader.read_u32::<BigEndian>()? as usize;
+    let mut message_bytes = vec![0; message_len];
+    reader.read_exact(&mut message_bytes)?;
+
+    Ok(DurableMessage {
+        circuit_id,
+        node_id: node_id.to_string(),
+        stored_at,
+        message_bytes,
+    })
+}
+
+fn storage_error<E: std::error::Error + Send + 'static>(
+    context: String,
+    err: E,
+) -> DurableMessageStoreError {
+    DurableMessageStoreError::StorageError {
+        context,
+        source: Some(Box::new(err)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempdir::TempDir;
+
+    /// Test that stored messages are loaded in order by a new store in the same directory, and
+    /// that removed messages are not.
+    #[test]
+    fn test_messages_survive_restart() {
+        let temp_dir = TempDir::new("test_messages_survive_restart").expect("Failed to create dir");
+
+        let mut store =
+            FileDurableMessageStore::new(temp_dir.path()).expect("Failed to create store");
+        for i in 0..3u8 {
+            store
+                .append(DurableMessage {
+                    circuit_id: "alpha".into(),
+                    node_id: "node::b".into(),
+                    stored_at: 0,
+                    message_bytes: vec![i; 4],
+                })
+                .expect("Failed to append message");
+        }
+        store
+            .remove_for_node("node::b", 1)
+            .expect("Failed to remove message");
+
+        let store = FileDurableMessageStore::new(temp_dir.path()).expect("Failed to reopen store");
+        let messages = store
+            .list_for_node("node::b")
+            .expect("Failed to list messages");
+        assert_eq!(
+            vec![vec![1u8; 4], vec![2u8; 4]],
+            messages
+                .into_iter()
+                .map(|message| message.message_bytes)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(8, store.circuit_size("alpha").expect("Failed to get size"));
+    }
+
+    /// Test that an incomplete message at the end of a file is discarded.
+    #[test]
+    fn test_incomplete_message_discarded() {
+        let temp_dir =
+            TempDir::new("test_incomplete_message_discarded").expect("Failed to create dir");
+
+        let mut store =
+            FileDurableMessageStore::new(temp_dir.path()).expect("Failed to create store");
+        store
+            .append(DurableMessage {
+                circuit_id: "alpha".into(),
+                node_id: "node_b".into(),
+                stored_at: 0,
+                message_bytes: b"complete".to_vec(),
+            })
+            .expect("Failed to append message");
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(store.node_path("node_b"))
+            .expect("Failed to open file");
+        file.write_all(&[0, 0, 0, 5, b'a'])
+            .expect("Failed to write partial message");
+
+        let store = FileDurableMessageStore::new(temp_dir.path()).expect("Failed to reopen store");
+        let messages = store
+            .list_for_node("node_b")
+            .expect("Failed to list messages");
+        assert_eq!(1, messages.len());
+        assert_eq!(b"complete".to_vec(), messages[0].message_bytes);
+    }
+}
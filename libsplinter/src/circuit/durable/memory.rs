@@ -0,0 +1,89 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use super::{DurableMessage, DurableMessageStore, DurableMessageStoreError};
+
+/// A `DurableMessageStore` that keeps messages in memory.
+///
+/// Messages are lost when the node restarts, so this store is only suitable for testing.
+#[derive(Default)]
+pub struct MemoryDurableMessageStore {
+    messages: HashMap<String, Vec<DurableMessage>>,
+}
+
+impl MemoryDurableMessageStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl DurableMessageStore for MemoryDurableMessageStore {
+    fn append(&mut self, message: DurableMessage) -> Result<(), DurableMessageStoreError> {
+        self.messages
+            .entry(message.node_id.clone())
+            .or_default()
+            .push(message);
+        Ok(())
+    }
+
+    fn list_for_node(
+        &self,
+        node_id: &str,
+    ) -> Result<Vec<DurableMessage>, DurableMessageStoreError> {
+        Ok(self.messages.get(node_id).cloned().unwrap_or_default())
+    }
+
+    fn remove_for_node(
+        &mut self,
+        node_id: &str,
+        count: usize,
+    ) -> Result<(), DurableMessageStoreError> {
+        if let Some(messages) = self.messages.get_mut(node_id) {
+            messages.drain(..count.min(messages.len()));
+            if messages.is_empty() {
+                self.messages.remove(node_id);
+            }
+        }
+        Ok(())
+    }
+
+    fn circuit_size(&self, circuit_id: &str) -> Result<usize, DurableMessageStoreError> {
+        Ok(self
+            .messages
+            .values()
+            .flatten()
+            .filter(|message| message.circuit_id == circuit_id)
+            .map(|message| message.message_bytes.len())
+            .sum())
+    }
+
+    fn remove_expired(
+        &mut self,
+        circuit_id: &str,
+        stored_before: u64,
+    ) -> Result<usize, DurableMessageStoreError> {
+        let mut removed = 0;
+        self.messages.retain(|_, messages| {
+            let count = messages.len();
+            messages.retain(|message| {
+                message.circuit_id != circuit_id || message.stored_at >= stored_before
+            });
+            removed += count - messages.len();
+            !messages.is_empty()
+        });
+        Ok(removed)
+    }
+}
@@ -0,0 +1,572 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Store-and-forward delivery for messages on durable circuits.
+//!
+//! Messages for a member node that is not connected are kept in a `DurableMessageStore` and sent
+//! to the node, in the order they were stored, once it connects again. Messages on a circuit
+//! with a message time-to-live are dropped if the node has not connected again before it passes.
+
+mod error;
+mod file;
+mod memory;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::channel;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::network::dispatch::{MessageSender, PeerId};
+use crate::peer::{PeerManagerConnector, PeerManagerNotification};
+
+pub use error::DurableMessageStoreError;
+pub use file::FileDurableMessageStore;
+pub use memory::MemoryDurableMessageStore;
+
+/// A message waiting to be delivered to a member node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DurableMessage {
+    /// The circuit the message was sent on
+    pub circuit_id: String,
+    /// The node the message is for
+    pub node_id: String,
+    /// When the message was stored, in seconds since the Unix epoch
+    pub stored_at: u64,
+    /// The network message bytes to send to the node
+    pub message_bytes: Vec<u8>,
+}
+
+/// Stores messages waiting to be delivered, in the order they were appended for each node.
+pub trait DurableMessageStore: Send {
+    /// Adds a message after the other messages for its node.
+    fn append(&mut self, message: DurableMessage) -> Result<(), DurableMessageStoreError>;
+
+    /// Returns the messages for the given node, oldest first.
+    fn list_for_node(&self, node_id: &str)
+        -> Result<Vec<DurableMessage>, DurableMessageStoreError>;
+
+    /// Removes the given number of the oldest messages for the given node.
+    fn remove_for_node(
+        &mut self,
+        node_id: &str,
+        count: usize,
+    ) -> Result<(), DurableMessageStoreError>;
+
+    /// Returns the total size in bytes of the messages stored for the given circuit.
+    fn circuit_size(&self, circuit_id: &str) -> Result<usize, DurableMessageStoreError>;
+
+    /// Removes the messages for the given circuit that were stored before the given time, in
+    /// seconds since the Unix epoch, and returns how many were removed.
+    fn remove_expired(
+        &mut self,
+        circuit_id: &str,
+        stored_before: u64,
+    ) -> Result<usize, DurableMessageStoreError>;
+}
+
+/// What was done with a message given to `DurableMessageQueue::enqueue`.
+#[derive(Debug, PartialEq)]
+pub enum QueueOutcome {
+    /// The node is connected and has no messages waiting, so the message should be sent now.
+    SendDirectly,
+    /// The message was stored and will be sent when the node connects.
+    Queued,
+    /// The message was dropped because the circuit's stored messages would exceed the size limit.
+    CapacityExceeded,
+}
+
+type OverflowListener = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+struct Inner {
+    store: Box<dyn DurableMessageStore>,
+    circuit_size_limit: usize,
+    overflow_listener: Option<OverflowListener>,
+    // Circuits whose size limit has been exceeded since their messages were last delivered; the
+    // overflow listener is only notified once for each
+    overflowed: HashSet<String>,
+    message_ttls: HashMap<String, Duration>,
+    // The nodes the forwarder has seen connect; a node is only sent messages directly once its
+    // stored messages have been forwarded
+    connected: HashSet<String>,
+}
+
+/// Holds messages for member nodes that are not connected and delivers them when the nodes
+/// connect.
+#[derive(Clone)]
+pub struct DurableMessageQueue {
+    inner: Arc<Mutex<Inner>>,
+    peer_connector: PeerManagerConnector,
+}
+
+impl DurableMessageQueue {
+    /// Creates a queue that keeps at most `circuit_size_limit` bytes of messages for each circuit
+    /// in the given store, using the peer connector to find out when nodes connect.
+    pub fn new(
+        store: Box<dyn DurableMessageStore>,
+        circuit_size_limit: usize,
+        peer_connector: PeerManagerConnector,
+    ) -> Self {
+        DurableMessageQueue {
+            inner: Arc::new(Mutex::new(Inner {
+                store,
+                circuit_size_limit,
+                overflow_listener: None,
+                overflowed: HashSet::new(),
+                message_ttls: HashMap::new(),
+                connected: HashSet::new(),
+            })),
+            peer_connector,
+        }
+    }
+
+    /// Returns the maximum number of bytes of messages kept for each circuit.
+    pub fn circuit_size_limit(&self) -> Result<usize, DurableMessageStoreError> {
+        Ok(self.lock()?.circuit_size_limit)
+    }
+
+    /// Sets a function that is called with the circuit ID and node ID when a message is dropped
+    /// because the circuit's size limit has been reached.
+    ///
+    /// The function is called once each time a circuit reaches its limit, not for every dropped
+    /// message.
+    pub fn set_overflow_listener<F>(&self, listener: F) -> Result<(), DurableMessageStoreError>
+    where
+        F: Fn(&str, &str) + Send + Sync + 'static,
+    {
+        self.lock()?.overflow_listener = Some(Arc::new(listener));
+        Ok(())
+    }
+
+    /// Sets how long messages on the given circuit are kept for a node that does not connect.
+    /// Expired messages are dropped when another message is stored for the circuit, or when
+    /// stored messages are forwarded.
+    pub fn set_message_ttl(
+        &self,
+        circuit_id: &str,
+        ttl: Duration,
+    ) -> Result<(), DurableMessageStoreError> {
+        self.lock()?
+            .message_ttls
+            .insert(circuit_id.to_string(), ttl);
+        Ok(())
+    }
+
+    /// Stores the message for the given node if the node is not connected, or if earlier messages
+    /// for it are still waiting to be delivered. Otherwise, returns `QueueOutcome::SendDirectly`
+    /// and the caller is expected to send the message itself.
+    pub fn enqueue(
+        &self,
+        circuit_id: &str,
+        node_id: &str,
+        message_bytes: Vec<u8>,
+    ) -> Result<QueueOutcome, DurableMessageStoreError> {
+        let (outcome, listener) = {
+            let mut inner = self.lock()?;
+
+            // The forwarder updates the connected nodes while holding the lock, so a node that
+            // connects now is either seen as connected here or has this message delivered by the
+            // forwarder
+            if inner.connected.contains(node_id) && inner.store.list_for_node(node_id)?.is_empty() {
+                return Ok(QueueOutcome::SendDirectly);
+            }
+
+            let now = now()?;
+            if let Some(ttl) = inner.message_ttls.get(circuit_id).copied() {
+                Self::remove_expired(&mut inner, circuit_id, ttl, now)?;
+            }
+
+            let circuit_size = inner.store.circuit_size(circuit_id)?;
+            if circuit_size + message_bytes.len() > inner.circuit_size_limit {
+                let listener = if inner.overflowed.insert(circuit_id.to_string()) {
+                    inner.overflow_listener.clone()
+                } else {
+                    None
+                };
+                (QueueOutcome::CapacityExceeded, listener)
+            } else {
+                inner.store.append(DurableMessage {
+                    circuit_id: circuit_id.to_string(),
+                    node_id: node_id.to_string(),
+                    stored_at: now,
+                    message_bytes,
+                })?;
+                (QueueOutcome::Queued, None)
+            }
+        };
+
+        if let Some(listener) = listener {
+            listener(circuit_id, node_id);
+        }
+
+        Ok(outcome)
+    }
+
+    /// Marks the given node as connected, sends the messages stored for it, in order, and removes
+    /// the ones that were sent. Stops at the first message that cannot be sent.
+    pub fn forward(
+        &self,
+        node_id: &str,
+        sender: &dyn MessageSender<PeerId>,
+    ) -> Result<(), DurableMessageStoreError> {
+        let mut inner = self.lock()?;
+        inner.connected.insert(node_id.to_string());
+
+        let now = now()?;
+        let message_ttls = inner.message_ttls.clone();
+        for (circuit_id, ttl) in message_ttls {
+            Self::remove_expired(&mut inner, &circuit_id, ttl, now)?;
+        }
+
+        let messages = inner.store.list_for_node(node_id)?;
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let mut sent = 0;
+        for message in &messages {
+            if sender
+                .send(node_id.into(), message.message_bytes.clone())
+                .is_err()
+            {
+                warn!(
+                    "Unable to deliver stored message to {}; {} message(s) still waiting",
+                    node_id,
+                    messages.len() - sent
+                );
+                break;
+            }
+            sent += 1;
+        }
+        inner.store.remove_for_node(node_id, sent)?;
+
+        debug!("Delivered {} stored message(s) to {}", sent, node_id);
+
+        Self::refresh_overflowed(&mut inner);
+
+        Ok(())
+    }
+
+    /// Marks the given node as disconnected, so messages for it are stored again.
+    fn disconnected(&self, node_id: &str) -> Result<(), DurableMessageStoreError> {
+        self.lock()?.connected.remove(node_id);
+        Ok(())
+    }
+
+    fn remove_expired(
+        inner: &mut Inner,
+        circuit_id: &str,
+        ttl: Duration,
+        now: u64,
+    ) -> Result<(), DurableMessageStoreError> {
+        let removed = inner
+            .store
+            .remove_expired(circuit_id, now.saturating_sub(ttl.as_secs()))?;
+        if removed > 0 {
+            warn!(
+                "Dropped {} expired stored message(s) on circuit {}",
+                removed, circuit_id
+            );
+            Self::refresh_overflowed(inner);
+        }
+        Ok(())
+    }
+
+    // Forgets the overflowed circuits that are below their size limit again, so the overflow
+    // listener is notified the next time they reach it
+    fn refresh_overflowed(inner: &mut Inner) {
+        let circuit_size_limit = inner.circuit_size_limit;
+        let mut overflowed = std::mem::take(&mut inner.overflowed);
+        overflowed.retain(|circuit_id| {
+            inner
+                .store
+                .circuit_size(circuit_id)
+                .map(|size| size >= circuit_size_limit)
+                .unwrap_or(true)
+        });
+        inner.overflowed = overflowed;
+    }
+
+    /// Starts a thread that delivers the stored messages for each node when it connects, and for
+    /// the nodes that are already connected.
+    ///
+    /// The thread exits when the peer manager stops.
+    pub fn start_forwarding(
+        &self,
+        sender: Box<dyn MessageSender<PeerId>>,
+    ) -> Result<thread::JoinHandle<()>, DurableMessageStoreError> {
+        let (notification_sender, notification_receiver) = channel();
+        self.peer_connector
+            .subscribe_sender::<PeerManagerNotification>(notification_sender)
+            .map_err(|err| DurableMessageStoreError::ForwardingError(err.to_string()))?;
+
+        let connected_peers = self
+            .peer_connector
+            .list_connected_peers()
+            .map_err(|err| DurableMessageStoreError::ForwardingError(err.to_string()))?;
+
+        let queue = self.clone();
+        thread::Builder::new()
+            .name("DurableMessageForwarder".into())
+            .spawn(move || {
+                for node_id in connected_peers {
+                    if let Err(err) = queue.forward(&node_id, &*sender) {
+                        error!("Unable to deliver stored messages to {}: {}", node_id, err);
+                    }
+                }
+
+                while let Ok(notification) = notification_receiver.recv() {
+                    match notification {
+                        PeerManagerNotification::Connected { peer } => {
+                            if let Err(err) = queue.forward(&peer, &*sender) {
+                                error!("Unable to deliver stored messages to {}: {}", peer, err);
+                            }
+                        }
+                        PeerManagerNotification::Disconnected { peer } => {
+                            if let Err(err) = queue.disconnected(&peer) {
+                                error!("Unable to mark {} as disconnected: {}", peer, err);
+                            }
+                        }
+                    }
+                }
+            })
+            .map_err(|err| DurableMessageStoreError::ForwardingError(err.to_string()))
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Inner>, DurableMessageStoreError> {
+        self.inner.lock().map_err(|_| {
+            DurableMessageStoreError::PoisonedLock("Durable message queue lock poisoned".into())
+        })
+    }
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn now() -> Result<u64, DurableMessageStoreError> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .map_err(|err| DurableMessageStoreError::StorageError {
+            context: "System time is before the Unix epoch".into(),
+            source: Some(Box::new(err)),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::VecDeque;
+
+    use crate::peer::{PeerManagerMessage, PeerManagerRequest};
+
+    /// Test that messages for a node that is not connected are stored and then delivered in
+    /// order, and that messages for a connected node are sent directly once nothing is waiting.
+    #[test]
+    fn test_queue_and_forward_in_order() {
+        let queue = DurableMessageQueue::new(
+            Box::new(MemoryDurableMessageStore::new()),
+            1024,
+            mock_peer_connector(),
+        );
+
+        for i in 0..3u8 {
+            assert_eq!(
+                QueueOutcome::Queued,
+                queue
+                    .enqueue("alpha", "node_b", vec![i])
+                    .expect("Failed to enqueue message")
+            );
+        }
+
+        let sender = MockSender::default();
+        queue
+            .forward("node_b", &sender)
+            .expect("Failed to forward messages");
+
+        // Nothing is waiting once the messages are delivered, so this one is sent directly
+        assert_eq!(
+            QueueOutcome::SendDirectly,
+            queue
+                .enqueue("alpha", "node_b", vec![3])
+                .expect("Failed to enqueue message")
+        );
+
+        // Once the node disconnects, messages are stored again
+        queue
+            .disconnected("node_b")
+            .expect("Failed to mark node disconnected");
+        assert_eq!(
+            QueueOutcome::Queued,
+            queue
+                .enqueue("alpha", "node_b", vec![4])
+                .expect("Failed to enqueue message")
+        );
+        queue
+            .forward("node_b", &sender)
+            .expect("Failed to forward messages");
+
+        let sent = sender.outbound.lock().expect("lock was poisoned");
+        assert_eq!(
+            vec![
+                ("node_b".to_string(), vec![0]),
+                ("node_b".to_string(), vec![1]),
+                ("node_b".to_string(), vec![2]),
+                ("node_b".to_string(), vec![4]),
+            ],
+            sent.iter()
+                .map(|(id, bytes)| (id.to_string(), bytes.clone()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Test that messages are dropped once a circuit's size limit is reached, that the overflow
+    /// listener is notified once, and that it is notified again after the messages are delivered
+    /// and the limit is reached again.
+    #[test]
+    fn test_size_limit() {
+        let queue = DurableMessageQueue::new(
+            Box::new(MemoryDurableMessageStore::new()),
+            8,
+            mock_peer_connector(),
+        );
+
+        let overflows = Arc::new(Mutex::new(vec![]));
+        let listener_overflows = overflows.clone();
+        queue
+            .set_overflow_listener(move |circuit_id, node_id| {
+                listener_overflows
+                    .lock()
+                    .expect("lock was poisoned")
+                    .push((circuit_id.to_string(), node_id.to_string()))
+            })
+            .expect("Failed to set listener");
+
+        assert_eq!(
+            QueueOutcome::Queued,
+            queue.enqueue("alpha", "node_b", vec![0; 6]).unwrap()
+        );
+        // Messages on other circuits do not count towards the limit
+        assert_eq!(
+            QueueOutcome::Queued,
+            queue.enqueue("beta", "node_b", vec![0; 6]).unwrap()
+        );
+        assert_eq!(
+            QueueOutcome::CapacityExceeded,
+            queue.enqueue("alpha", "node_b", vec![0; 6]).unwrap()
+        );
+        assert_eq!(
+            QueueOutcome::CapacityExceeded,
+            queue.enqueue("alpha", "node_c", vec![0; 6]).unwrap()
+        );
+        assert_eq!(
+            vec![("alpha".to_string(), "node_b".to_string())],
+            *overflows.lock().unwrap()
+        );
+
+        queue
+            .forward("node_b", &MockSender::default())
+            .expect("Failed to forward messages");
+
+        assert_eq!(
+            QueueOutcome::Queued,
+            queue.enqueue("alpha", "node_c", vec![0; 6]).unwrap()
+        );
+        assert_eq!(
+            QueueOutcome::CapacityExceeded,
+            queue.enqueue("alpha", "node_c", vec![0; 6]).unwrap()
+        );
+        assert_eq!(2, overflows.lock().unwrap().len());
+    }
+
+    /// Test that messages on a circuit with a message time-to-live are dropped once it has passed,
+    /// and that messages on other circuits are kept.
+    #[test]
+    fn test_message_ttl() {
+        let queue = DurableMessageQueue::new(
+            Box::new(MemoryDurableMessageStore::new()),
+            8,
+            mock_peer_connector(),
+        );
+        queue
+            .set_message_ttl("admin", Duration::from_secs(60))
+            .expect("Failed to set ttl");
+
+        for circuit_id in &["admin", "alpha"] {
+            queue
+                .lock()
+                .expect("Failed to lock queue")
+                .store
+                .append(DurableMessage {
+                    circuit_id: circuit_id.to_string(),
+                    node_id: "node_b".into(),
+                    stored_at: 0,
+                    message_bytes: vec![0; 6],
+                })
+                .expect("Failed to append message");
+        }
+
+        // The expired message no longer counts towards the limit
+        assert_eq!(
+            QueueOutcome::Queued,
+            queue.enqueue("admin", "node_b", vec![1; 6]).unwrap()
+        );
+
+        let sender = MockSender::default();
+        queue
+            .forward("node_b", &sender)
+            .expect("Failed to forward messages");
+        assert_eq!(
+            vec![vec![0; 6], vec![1; 6]],
+            sender
+                .outbound
+                .lock()
+                .expect("lock was poisoned")
+                .iter()
+                .map(|(_, bytes)| bytes.clone())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    /// Returns a peer connector that reports no peers as connected.
+    fn mock_peer_connector() -> PeerManagerConnector {
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            while let Ok(message) = receiver.recv() {
+                if let PeerManagerMessage::Request(PeerManagerRequest::ListConnectedPeers {
+                    sender,
+                }) = message
+                {
+                    let _ = sender.send(Ok(vec![]));
+                }
+            }
+        });
+        PeerManagerConnector::new(sender)
+    }
+
+    #[derive(Clone, Default)]
+    struct MockSender {
+        outbound: Arc<Mutex<VecDeque<(PeerId, Vec<u8>)>>>,
+    }
+
+    impl MessageSender<PeerId> for MockSender {
+        fn send(&self, id: PeerId, message: Vec<u8>) -> Result<(), (PeerId, Vec<u8>)> {
+            self.outbound
+                .lock()
+                .expect("lock was poisoned")
+                .push_back((id, message));
+
+            Ok(())
+        }
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::circuit::durable::{DurableMessageQueue, QueueOutcome};
 use crate::circuit::handlers::create_message;
 use crate::circuit::SplinterState;
 use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
@@ -26,6 +27,7 @@ const ADMIN_SERVICE_ID_PREFIX: &str = "admin::";
 pub struct AdminDirectMessageHandler {
     node_id: String,
     state: SplinterState,
+    durable_message_queue: Option<DurableMessageQueue>,
 }
 
 impl Handler for AdminDirectMessageHandler {
@@ -64,7 +66,10 @@ impl Handler for AdminDirectMessageHandler {
         // msg bytes will either be message bytes of a direct message or an error message
         // the msg_recipient is either the service/node id to send the message to or is the
         // peer_id to send back the error message
-        let (msg_bytes, msg_recipient) = self.create_response(msg, context)?;
+        let (msg_bytes, msg_recipient) = match self.create_response(msg, context)? {
+            Some(response) => response,
+            None => return Ok(()),
+        };
         // either forward the direct message or send back an error message.
         sender
             .send(msg_recipient.into(), msg_bytes)
@@ -77,14 +82,27 @@ impl Handler for AdminDirectMessageHandler {
 
 impl AdminDirectMessageHandler {
     pub fn new(node_id: String, state: SplinterState) -> Self {
-        Self { node_id, state }
+        Self {
+            node_id,
+            state,
+            durable_message_queue: None,
+        }
+    }
+
+    /// Stores admin messages for nodes that are not connected in the given queue, so they are
+    /// delivered when the nodes reconnect instead of being lost.
+    pub fn with_durable_message_queue(mut self, queue: DurableMessageQueue) -> Self {
+        self.durable_message_queue = Some(queue);
+        self
     }
 
+    /// Returns the message bytes and the recipient to send them to, or `None` if the message was
+    /// stored to be delivered later.
     fn create_response(
         &self,
         msg: AdminDirectMessage,
         context: &MessageContext<PeerId, CircuitMessageType>,
-    ) -> Result<(Vec<u8>, String), DispatchError> {
+    ) -> Result<Option<(Vec<u8>, String)>, DispatchError> {
         let circuit_name = msg.get_circuit();
         let msg_sender = msg.get_sender();
         let recipient = msg.get_recipient();
@@ -98,10 +116,10 @@ impl AdminDirectMessageHandler {
                     msg_sender
                 ),
             )?;
-            return Ok((
+            return Ok(Some((
                 create_message(err_msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?,
                 context.source_peer_id().into(),
-            ));
+            )));
         }
 
         if !is_admin_service_id(recipient) {
@@ -113,10 +131,10 @@ impl AdminDirectMessageHandler {
                     recipient
                 ),
             )?;
-            return Ok((
+            return Ok(Some((
                 create_message(err_msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?,
                 context.source_peer_id().into(),
-            ));
+            )));
         }
 
         // msg bytes will either be message bytes of a direct message or an error message
@@ -141,6 +159,11 @@ impl AdminDirectMessageHandler {
             let msg_bytes = context.message_bytes().to_vec();
             let network_msg_bytes =
                 create_message(msg_bytes, CircuitMessageType::ADMIN_DIRECT_MESSAGE)?;
+            if node_id != self.node_id
+                && !self.should_send(circuit_name, node_id, &network_msg_bytes)?
+            {
+                return Ok(None);
+            }
             (network_msg_bytes, target_node.to_string())
         } else {
             // if the circuit does not exist, send circuit error
@@ -154,7 +177,39 @@ impl AdminDirectMessageHandler {
                 create_message(msg_bytes, CircuitMessageType::CIRCUIT_ERROR_MESSAGE)?;
             (network_msg_bytes, context.source_peer_id().to_string())
         };
-        Ok(response)
+        Ok(Some(response))
+    }
+
+    /// Returns whether an admin message for the given node should be sent now, storing it
+    /// instead if a durable message queue is set and the node is not connected.
+    fn should_send(
+        &self,
+        circuit_name: &str,
+        node_id: &str,
+        network_msg_bytes: &[u8],
+    ) -> Result<bool, DispatchError> {
+        let queue = match &self.durable_message_queue {
+            Some(queue) => queue,
+            None => return Ok(true),
+        };
+
+        match queue
+            .enqueue(circuit_name, node_id, network_msg_bytes.to_vec())
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?
+        {
+            QueueOutcome::SendDirectly => Ok(true),
+            QueueOutcome::Queued => {
+                debug!("Stored admin message for {} until it reconnects", node_id);
+                Ok(false)
+            }
+            QueueOutcome::CapacityExceeded => {
+                warn!(
+                    "Dropping admin message for {}: stored messages have reached the size limit",
+                    node_id
+                );
+                Ok(false)
+            }
+        }
     }
 }
 
@@ -12,9 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::circuit::durable::{DurableMessageQueue, QueueOutcome};
 use crate::circuit::handlers::create_message;
-use crate::circuit::{ServiceId, SplinterState};
+use crate::circuit::handlers::relay_message::{create_relay_message, next_hop};
+use crate::circuit::{Circuit, DurabilityType, RouteType, ServiceId, SplinterState};
 use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
+use crate::peer::ConnectedPeers;
 use crate::protos::circuit::{
     CircuitDirectMessage, CircuitError, CircuitError_Error, CircuitMessageType,
 };
@@ -25,6 +28,8 @@ use protobuf::Message;
 pub struct CircuitDirectMessageHandler {
     node_id: String,
     state: SplinterState,
+    connected_peers: Option<ConnectedPeers>,
+    durable_message_queue: Option<DurableMessageQueue>,
 }
 
 impl Handler for CircuitDirectMessageHandler {
@@ -70,10 +75,12 @@ impl Handler for CircuitDirectMessageHandler {
         // the msg_recipient is either the service/node id to send the message to or is the
         // peer_id to send back the error message
         let (msg_bytes, msg_recipient) = {
+            // messages are not routed on circuits that have been abandoned by this node
             if let Some(circuit) = self
                 .state
                 .circuit(circuit_name)
                 .map_err(|err| DispatchError::HandleError(err.context()))?
+                .filter(|circuit| circuit.circuit_status().is_active())
             {
                 // Check if the message sender is allowed on the circuit
                 // if the sender is not allowed on the circuit
@@ -123,14 +130,29 @@ impl Handler for CircuitDirectMessageHandler {
                     {
                         let node_id = service.node().id().to_string();
                         // If the service is on this node send message to the service, otherwise
-                        // send the message to the node the service is connected to
+                        // send the message to the node the service is connected to, relaying it
+                        // through another member if the circuit allows it and the node is not
+                        // connected
                         if node_id != self.node_id {
                             let msg_bytes = context.message_bytes().to_vec();
-                            let network_msg_bytes = create_message(
-                                msg_bytes,
-                                CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
-                            )?;
-                            (network_msg_bytes, node_id)
+                            if let Some(relay_hop) = self.relay_hop(&circuit, &node_id)? {
+                                let network_msg_bytes = create_relay_message(
+                                    circuit_name,
+                                    &self.node_id,
+                                    &node_id,
+                                    msg_bytes,
+                                )?;
+                                (network_msg_bytes, relay_hop)
+                            } else {
+                                let network_msg_bytes = create_message(
+                                    msg_bytes,
+                                    CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                                )?;
+                                if !self.should_send(&circuit, &node_id, &network_msg_bytes)? {
+                                    return Ok(());
+                                }
+                                (network_msg_bytes, node_id)
+                            }
                         } else {
                             let msg_bytes = context.message_bytes().to_vec();
                             let network_msg_bytes = create_message(
@@ -214,7 +236,83 @@ impl Handler for CircuitDirectMessageHandler {
 
 impl CircuitDirectMessageHandler {
     pub fn new(node_id: String, state: SplinterState) -> Self {
-        CircuitDirectMessageHandler { node_id, state }
+        CircuitDirectMessageHandler {
+            node_id,
+            state,
+            connected_peers: None,
+            durable_message_queue: None,
+        }
+    }
+
+    /// Enables store-and-forward delivery on circuits with the `Durable` durability type, using
+    /// the given queue to hold messages for nodes that are not connected.
+    pub fn with_durable_message_queue(mut self, queue: DurableMessageQueue) -> Self {
+        self.durable_message_queue = Some(queue);
+        self
+    }
+
+    /// Returns whether a message for the given node should be sent now. On durable circuits, the
+    /// message is stored instead if the node is not connected or earlier messages for it are still
+    /// waiting to be delivered.
+    fn should_send(
+        &self,
+        circuit: &Circuit,
+        node_id: &str,
+        network_msg_bytes: &[u8],
+    ) -> Result<bool, DispatchError> {
+        let queue = match (&self.durable_message_queue, circuit.durability()) {
+            (Some(queue), DurabilityType::Durable) => queue,
+            _ => return Ok(true),
+        };
+
+        match queue
+            .enqueue(circuit.id(), node_id, network_msg_bytes.to_vec())
+            .map_err(|err| DispatchError::HandleError(err.to_string()))?
+        {
+            QueueOutcome::SendDirectly => Ok(true),
+            QueueOutcome::Queued => {
+                debug!(
+                    "Stored message on {} for {} until it reconnects",
+                    circuit.id(),
+                    node_id
+                );
+                Ok(false)
+            }
+            QueueOutcome::CapacityExceeded => {
+                warn!(
+                    "Dropping message on {} for {}: stored messages have reached the size limit",
+                    circuit.id(),
+                    node_id
+                );
+                Ok(false)
+            }
+        }
+    }
+
+    /// Enables relaying messages on circuits with the `Relay` route type, using the connected
+    /// peers to find out which nodes are connected.
+    pub fn with_connected_peers(mut self, connected_peers: ConnectedPeers) -> Self {
+        self.connected_peers = Some(connected_peers);
+        self
+    }
+
+    /// Returns the peer to relay a message for the given node through, or `None` if the message
+    /// should be sent to the node directly.
+    fn relay_hop(
+        &self,
+        circuit: &Circuit,
+        destination: &str,
+    ) -> Result<Option<String>, DispatchError> {
+        let connected_peers = match (&self.connected_peers, circuit.routes()) {
+            (Some(connected_peers), RouteType::Relay) => connected_peers,
+            _ => return Ok(None),
+        };
+
+        let path = [self.node_id.clone()];
+        match next_hop(connected_peers, circuit, destination, &path)? {
+            Some(hop) if hop != destination => Ok(Some(hop)),
+            _ => Ok(None),
+        }
     }
 }
 
@@ -223,13 +321,17 @@ mod tests {
     use super::*;
 
     use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
     use std::sync::{Arc, Mutex};
+    use std::thread;
 
     use crate::circuit::directory::CircuitDirectory;
+    use crate::circuit::durable::MemoryDurableMessageStore;
     use crate::circuit::service::{Service, SplinterNode};
     use crate::circuit::{AuthorizationType, Circuit, DurabilityType, PersistenceType, RouteType};
     use crate::network::dispatch::Dispatcher;
-    use crate::protos::circuit::CircuitMessage;
+    use crate::peer::{PeerManagerConnector, PeerManagerMessage, PeerManagerRequest};
+    use crate::protos::circuit::{CircuitMessage, CircuitRelayMessage};
     use crate::protos::network::NetworkMessage;
 
     // Test that a direct message will be properly sent to the service if the message is meant for
@@ -308,6 +410,201 @@ mod tests {
         )
     }
 
+    // Test that a direct message on a relay circuit is relayed through a connected member when
+    // the node the recipient service is connected to is not connected
+    #[test]
+    fn test_circuit_direct_message_handler_relay() {
+        // Set up dispatcher and mock sender
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        // Add circuit and service to splinter state
+        let circuit = Circuit::builder()
+            .with_id("alpha".into())
+            .with_auth(AuthorizationType::Trust)
+            .with_members(vec!["123".into(), "345".into(), "hub".into()])
+            .with_roster(vec!["abc".into(), "def".into()])
+            .with_persistence(PersistenceType::Any)
+            .with_durability(DurabilityType::NoDurability)
+            .with_routes(RouteType::Relay)
+            .with_circuit_management_type("circuit_direct_test_app".into())
+            .build()
+            .expect("Should have built a correct circuit");
+
+        let mut circuit_directory = CircuitDirectory::new();
+        circuit_directory.add_circuit("alpha".to_string(), circuit);
+
+        let state = SplinterState::new("memory".to_string(), circuit_directory);
+
+        let node_123 = SplinterNode::new("123".to_string(), vec!["123.0.0.1:0".to_string()]);
+        let node_345 = SplinterNode::new("345".to_string(), vec!["123.0.0.1:0".to_string()]);
+
+        let service_abc = Service::new("abc".to_string(), None, node_123);
+        let service_def =
+            Service::new("def".to_string(), Some("def_network".to_string()), node_345);
+        let abc_id = ServiceId::new("alpha".into(), "abc".into());
+        let def_id = ServiceId::new("alpha".into(), "def".into());
+        state.add_service(abc_id, service_abc).unwrap();
+        state.add_service(def_id, service_def).unwrap();
+
+        // Add direct message handler to dispatcher; only the hub node is connected
+        let (peer_sender, peer_receiver) = channel();
+        thread::spawn(move || {
+            while let Ok(message) = peer_receiver.recv() {
+                match message {
+                    PeerManagerMessage::Request(PeerManagerRequest::Subscribe {
+                        sender, ..
+                    }) => {
+                        let _ = sender.send(Ok(0));
+                    }
+                    PeerManagerMessage::Request(PeerManagerRequest::ListConnectedPeers {
+                        sender,
+                    }) => {
+                        let _ = sender.send(Ok(vec!["hub".to_string()]));
+                    }
+                    _ => (),
+                }
+            }
+        });
+        let connected_peers = ConnectedPeers::track(&PeerManagerConnector::new(peer_sender))
+            .expect("Unable to track connected peers");
+        let handler = CircuitDirectMessageHandler::new("345".to_string(), state)
+            .with_connected_peers(connected_peers);
+
+        dispatcher.set_handler(Box::new(handler));
+
+        // create dispatch message
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("def".into());
+        direct_message.set_recipient("abc".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("1234".into());
+        let direct_bytes = direct_message.write_to_bytes().unwrap();
+
+        // dispatch the message
+        dispatcher
+            .dispatch(
+                "def".into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_bytes.clone(),
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "hub",
+            CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+            |msg: CircuitRelayMessage| {
+                assert_eq!(msg.get_circuit(), "alpha");
+                assert_eq!(msg.get_origin(), "345");
+                assert_eq!(msg.get_destination(), "123");
+                assert_eq!(msg.get_path().to_vec(), vec!["345".to_string()]);
+                assert_eq!(msg.get_payload().to_vec(), direct_bytes);
+            },
+        )
+    }
+
+    // Test that a direct message on a durable circuit for a node that is not connected is stored
+    // instead of sent, and is sent to the node when the stored messages are forwarded
+    #[test]
+    fn test_circuit_direct_message_handler_durable() {
+        // Set up dispatcher and mock sender
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        // Add circuit and service to splinter state
+        let circuit = Circuit::builder()
+            .with_id("alpha".into())
+            .with_auth(AuthorizationType::Trust)
+            .with_members(vec!["123".into(), "345".into()])
+            .with_roster(vec!["abc".into(), "def".into()])
+            .with_persistence(PersistenceType::Any)
+            .with_durability(DurabilityType::Durable)
+            .with_routes(RouteType::Any)
+            .with_circuit_management_type("circuit_direct_test_app".into())
+            .build()
+            .expect("Should have built a correct circuit");
+
+        let mut circuit_directory = CircuitDirectory::new();
+        circuit_directory.add_circuit("alpha".to_string(), circuit);
+
+        let state = SplinterState::new("memory".to_string(), circuit_directory);
+
+        let node_123 = SplinterNode::new("123".to_string(), vec!["123.0.0.1:0".to_string()]);
+        let node_345 = SplinterNode::new("345".to_string(), vec!["123.0.0.1:0".to_string()]);
+
+        let service_abc = Service::new("abc".to_string(), None, node_123);
+        let service_def =
+            Service::new("def".to_string(), Some("def_network".to_string()), node_345);
+        let abc_id = ServiceId::new("alpha".into(), "abc".into());
+        let def_id = ServiceId::new("alpha".into(), "def".into());
+        state.add_service(abc_id, service_abc).unwrap();
+        state.add_service(def_id, service_def).unwrap();
+
+        // Add direct message handler to dispatcher; no nodes are connected
+        let (peer_sender, peer_receiver) = channel();
+        thread::spawn(move || {
+            while let Ok(message) = peer_receiver.recv() {
+                if let PeerManagerMessage::Request(PeerManagerRequest::ListConnectedPeers {
+                    sender,
+                }) = message
+                {
+                    let _ = sender.send(Ok(vec![]));
+                }
+            }
+        });
+        let queue = DurableMessageQueue::new(
+            Box::new(MemoryDurableMessageStore::new()),
+            1024,
+            PeerManagerConnector::new(peer_sender),
+        );
+        let handler = CircuitDirectMessageHandler::new("345".to_string(), state)
+            .with_durable_message_queue(queue.clone());
+
+        dispatcher.set_handler(Box::new(handler));
+
+        // create dispatch message
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("def".into());
+        direct_message.set_recipient("abc".into());
+        direct_message.set_payload(b"test".to_vec());
+        direct_message.set_correlation_id("1234".into());
+        let direct_bytes = direct_message.write_to_bytes().unwrap();
+
+        // dispatch the message
+        dispatcher
+            .dispatch(
+                "def".into(),
+                &CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+                direct_bytes,
+            )
+            .unwrap();
+
+        assert!(mock_sender.next_outbound().is_none());
+
+        queue
+            .forward("123", &mock_sender)
+            .expect("Unable to forward stored messages");
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_network_message(
+            message,
+            id.into(),
+            "123",
+            CircuitMessageType::CIRCUIT_DIRECT_MESSAGE,
+            |msg: CircuitDirectMessage| {
+                assert_eq!(msg.get_sender(), "def");
+                assert_eq!(msg.get_recipient(), "abc");
+                assert_eq!(msg.get_payload(), b"test");
+                assert_eq!(msg.get_correlation_id(), "1234");
+            },
+        )
+    }
+
     // Test that a direct message will be properly sent to the node the recipient service is
     // connected to
     #[test]
@@ -16,6 +16,7 @@ mod admin_message;
 mod circuit_error;
 mod circuit_message;
 mod direct_message;
+mod relay_message;
 mod service_handlers;
 
 use protobuf::Message;
@@ -27,6 +28,7 @@ pub use self::admin_message::AdminDirectMessageHandler;
 pub use self::circuit_error::CircuitErrorHandler;
 pub use self::circuit_message::CircuitMessageHandler;
 pub use self::direct_message::CircuitDirectMessageHandler;
+pub use self::relay_message::CircuitRelayMessageHandler;
 pub use self::service_handlers::ServiceConnectRequestHandler;
 pub use self::service_handlers::ServiceDisconnectRequestHandler;
 
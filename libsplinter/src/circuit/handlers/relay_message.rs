@@ -0,0 +1,662 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::circuit::handlers::create_message;
+use crate::circuit::{Circuit, RouteType, ServiceId, SplinterState};
+use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
+use crate::peer::ConnectedPeers;
+use crate::protos::circuit::{CircuitDirectMessage, CircuitMessageType, CircuitRelayMessage};
+
+use protobuf::Message;
+
+/// The number of hops a relayed message may take after leaving its origin node.
+const MAX_RELAY_HOPS: u32 = 8;
+
+// Implements a handler that handles CircuitRelayMessage, either delivering the relayed direct
+// message to a service on this node or relaying it on towards its destination node
+pub struct CircuitRelayMessageHandler {
+    node_id: String,
+    state: SplinterState,
+    connected_peers: ConnectedPeers,
+}
+
+impl Handler for CircuitRelayMessageHandler {
+    type Source = PeerId;
+    type MessageType = CircuitMessageType;
+    type Message = CircuitRelayMessage;
+
+    fn match_type(&self) -> Self::MessageType {
+        CircuitMessageType::CIRCUIT_RELAY_MESSAGE
+    }
+
+    fn handle(
+        &self,
+        mut msg: Self::Message,
+        context: &MessageContext<Self::Source, Self::MessageType>,
+        sender: &dyn MessageSender<Self::Source>,
+    ) -> Result<(), DispatchError> {
+        debug!(
+            "Handle Circuit Relay Message on {} ({} => {}) via {} [{} hop{} left]",
+            msg.get_circuit(),
+            msg.get_origin(),
+            msg.get_destination(),
+            context.source_peer_id(),
+            msg.get_ttl(),
+            if msg.get_ttl() == 1 { "" } else { "s" }
+        );
+
+        let previous_hop = context.source_peer_id();
+        let origin = msg.get_origin().to_string();
+        let destination = msg.get_destination().to_string();
+
+        // messages are only relayed on active circuits that allow relaying
+        let circuit = match self
+            .state
+            .circuit(msg.get_circuit())
+            .map_err(|err| DispatchError::HandleError(err.context()))?
+            .filter(|circuit| circuit.circuit_status().is_active())
+        {
+            Some(circuit) if circuit.routes() == &RouteType::Relay => circuit,
+            _ => {
+                warn!(
+                    "Dropping relayed message on {}: circuit does not allow relaying",
+                    msg.get_circuit()
+                );
+                return Ok(());
+            }
+        };
+
+        let members = circuit.members();
+        if !members.contains(&origin)
+            || !members.contains(&destination)
+            || !members.contains(previous_hop)
+        {
+            warn!(
+                "Dropping relayed message on {}: {}, {} and {} must be circuit members",
+                msg.get_circuit(),
+                origin,
+                destination,
+                previous_hop
+            );
+            return Ok(());
+        }
+
+        // the path must start at the origin and end at the previous hop, which is the only node
+        // whose identity has been verified
+        let path = msg.get_path();
+        let path_matches = match (path.first(), path.last()) {
+            (Some(first), Some(last)) => first == &origin && last == previous_hop,
+            _ => &origin == previous_hop,
+        };
+        if !path_matches {
+            warn!(
+                "Dropping relayed message on {} from {}: path {:?} does not lead from {} to {}",
+                msg.get_circuit(),
+                previous_hop,
+                path,
+                origin,
+                previous_hop
+            );
+            return Ok(());
+        }
+
+        if msg
+            .get_path()
+            .iter()
+            .any(|node_id| node_id == &self.node_id)
+        {
+            warn!(
+                "Dropping relayed message on {} from {}: message has looped back to this node",
+                msg.get_circuit(),
+                origin
+            );
+            return Ok(());
+        }
+
+        if destination == self.node_id {
+            return self.deliver(&circuit, &origin, msg.get_payload(), sender);
+        }
+
+        if msg.get_ttl() == 0 {
+            warn!(
+                "Dropping relayed message on {} from {} to {}: time to live expired",
+                msg.get_circuit(),
+                origin,
+                destination
+            );
+            return Ok(());
+        }
+
+        msg.mut_path().push(self.node_id.clone());
+        let ttl = msg.get_ttl() - 1;
+        msg.set_ttl(ttl);
+
+        let next_hop = match next_hop(
+            &self.connected_peers,
+            &circuit,
+            &destination,
+            msg.get_path(),
+        )? {
+            Some(next_hop) => next_hop,
+            None => {
+                warn!(
+                    "Dropping relayed message on {} from {} to {}: no route to destination",
+                    msg.get_circuit(),
+                    origin,
+                    destination
+                );
+                return Ok(());
+            }
+        };
+
+        let network_msg_bytes = create_message(
+            msg.write_to_bytes()?,
+            CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+        )?;
+        sender
+            .send(next_hop.into(), network_msg_bytes)
+            .map_err(|(recipient, payload)| {
+                DispatchError::NetworkSendError((recipient.into(), payload))
+            })?;
+        Ok(())
+    }
+}
+
+impl CircuitRelayMessageHandler {
+    pub fn new(node_id: String, state: SplinterState, connected_peers: ConnectedPeers) -> Self {
+        CircuitRelayMessageHandler {
+            node_id,
+            state,
+            connected_peers,
+        }
+    }
+
+    /// Delivers a relayed direct message to the recipient service connected to this node.
+    ///
+    /// The message is only delivered if its sender is a service of the origin node. Only the
+    /// previous hop is authenticated, and it must be the last node on the message's path, which
+    /// must start at the origin. A member that sends a message directly therefore cannot send it
+    /// on behalf of the services of other members, but a member that relays a message can still
+    /// change its origin and path.
+    fn deliver(
+        &self,
+        circuit: &Circuit,
+        origin: &str,
+        payload: &[u8],
+        sender: &dyn MessageSender<PeerId>,
+    ) -> Result<(), DispatchError> {
+        let direct_msg: CircuitDirectMessage = protobuf::parse_from_bytes(payload)?;
+
+        if direct_msg.get_circuit() != circuit.id()
+            || !circuit.roster().contains(direct_msg.get_sender())
+            || !circuit.roster().contains(direct_msg.get_recipient())
+        {
+            warn!(
+                "Dropping relayed message on {}: {} and {} must be in the circuit roster",
+                circuit.id(),
+                direct_msg.get_sender(),
+                direct_msg.get_recipient()
+            );
+            return Ok(());
+        }
+
+        let sender_id = ServiceId::new(
+            circuit.id().to_string(),
+            direct_msg.get_sender().to_string(),
+        );
+        match self
+            .state
+            .get_service(&sender_id)
+            .map_err(|err| DispatchError::HandleError(err.context()))?
+        {
+            Some(service) if service.node().id() == origin => (),
+            _ => {
+                warn!(
+                    "Dropping relayed message on {}: {} is not a service of origin node {}",
+                    circuit.id(),
+                    direct_msg.get_sender(),
+                    origin
+                );
+                return Ok(());
+            }
+        }
+
+        let recipient_id = ServiceId::new(
+            circuit.id().to_string(),
+            direct_msg.get_recipient().to_string(),
+        );
+        let peer_id = match self
+            .state
+            .get_service(&recipient_id)
+            .map_err(|err| DispatchError::HandleError(err.context()))?
+        {
+            Some(service) if service.node().id() == self.node_id => match service.peer_id() {
+                Some(peer_id) => peer_id.clone(),
+                None => {
+                    warn!("No peer id for service:{} ", service.service_id());
+                    return Ok(());
+                }
+            },
+            _ => {
+                warn!(
+                    "Dropping relayed message on {}: {} is not connected to this node",
+                    circuit.id(),
+                    direct_msg.get_recipient()
+                );
+                return Ok(());
+            }
+        };
+
+        let network_msg_bytes =
+            create_message(payload.to_vec(), CircuitMessageType::CIRCUIT_DIRECT_MESSAGE)?;
+        sender
+            .send(peer_id.into(), network_msg_bytes)
+            .map_err(|(recipient, payload)| {
+                DispatchError::NetworkSendError((recipient.into(), payload))
+            })?;
+        Ok(())
+    }
+}
+
+/// Returns the peer that a message for the destination node should be sent to next.
+///
+/// The destination itself is returned if it is connected. Otherwise the message is relayed
+/// through the first connected member of the circuit, in the order the members are listed. Nodes
+/// in `path` have already handled the message and are never chosen. Returns `None` if no member
+/// of the circuit can take the message.
+pub(super) fn next_hop(
+    connected_peers: &ConnectedPeers,
+    circuit: &Circuit,
+    destination: &str,
+    path: &[String],
+) -> Result<Option<String>, DispatchError> {
+    let is_connected = |node_id: &str| {
+        connected_peers
+            .is_connected(node_id)
+            .map_err(|err| DispatchError::HandleError(err.to_string()))
+    };
+
+    if is_connected(destination)? {
+        return Ok(Some(destination.to_string()));
+    }
+
+    for member in circuit.members() {
+        if member != destination
+            && !path.iter().any(|visited| visited == member)
+            && is_connected(member)?
+        {
+            return Ok(Some(member.to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Wraps a serialized direct message in a relay message from this node to the destination node.
+pub(super) fn create_relay_message(
+    circuit: &str,
+    origin: &str,
+    destination: &str,
+    direct_msg_bytes: Vec<u8>,
+) -> Result<Vec<u8>, DispatchError> {
+    let mut relay_msg = CircuitRelayMessage::new();
+    relay_msg.set_circuit(circuit.into());
+    relay_msg.set_origin(origin.into());
+    relay_msg.set_destination(destination.into());
+    relay_msg.set_path(vec![origin.to_string()].into());
+    relay_msg.set_ttl(MAX_RELAY_HOPS);
+    relay_msg.set_payload(direct_msg_bytes);
+
+    Ok(create_message(
+        relay_msg.write_to_bytes()?,
+        CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::VecDeque;
+    use std::sync::mpsc::channel;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    use crate::circuit::directory::CircuitDirectory;
+    use crate::circuit::service::{Service, SplinterNode};
+    use crate::circuit::{AuthorizationType, DurabilityType, PersistenceType};
+    use crate::network::dispatch::Dispatcher;
+    use crate::peer::{PeerManagerConnector, PeerManagerMessage, PeerManagerRequest};
+    use crate::protos::circuit::CircuitMessage;
+    use crate::protos::network::NetworkMessage;
+
+    // Test that a relayed message for another node is relayed on to the destination when it is
+    // connected
+    #[test]
+    fn test_relay_message_forwarded() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let handler = CircuitRelayMessageHandler::new(
+            "hub".into(),
+            relay_state("hub"),
+            mock_connected_peers(vec!["node_a".into(), "node_b".into()]),
+        );
+        dispatcher.set_handler(Box::new(handler));
+
+        let relay_bytes = relay_message("node_a", "node_b", vec!["node_a".into()], 8);
+        dispatcher
+            .dispatch(
+                "node_a".into(),
+                &CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+                relay_bytes,
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_eq!("node_b", &String::from(id));
+        let relay_msg: CircuitRelayMessage =
+            parse_circuit_message(message, CircuitMessageType::CIRCUIT_RELAY_MESSAGE);
+        assert_eq!(
+            vec!["node_a".to_string(), "hub".to_string()],
+            relay_msg.get_path().to_vec()
+        );
+        assert_eq!(7, relay_msg.get_ttl());
+    }
+
+    // Test that a relayed message for this node is delivered to the recipient service
+    #[test]
+    fn test_relay_message_delivered() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let handler = CircuitRelayMessageHandler::new(
+            "node_b".into(),
+            relay_state("node_b"),
+            mock_connected_peers(vec!["hub".into()]),
+        );
+        dispatcher.set_handler(Box::new(handler));
+
+        let relay_bytes = relay_message("node_a", "node_b", vec!["node_a".into(), "hub".into()], 7);
+        dispatcher
+            .dispatch(
+                "hub".into(),
+                &CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+                relay_bytes,
+            )
+            .unwrap();
+
+        let (id, message) = mock_sender.next_outbound().expect("No message was sent");
+        assert_eq!("service_b_peer", &String::from(id));
+        let direct_msg: CircuitDirectMessage =
+            parse_circuit_message(message, CircuitMessageType::CIRCUIT_DIRECT_MESSAGE);
+        assert_eq!("service_a", direct_msg.get_sender());
+        assert_eq!("service_b", direct_msg.get_recipient());
+        assert_eq!(b"test".to_vec(), direct_msg.get_payload().to_vec());
+    }
+
+    // Test that a relayed message is not delivered when its sender is not a service of the node
+    // the message claims to originate from
+    #[test]
+    fn test_relay_message_sender_not_on_origin() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let handler = CircuitRelayMessageHandler::new(
+            "node_b".into(),
+            relay_state("node_b"),
+            mock_connected_peers(vec!["hub".into()]),
+        );
+        dispatcher.set_handler(Box::new(handler));
+
+        // service_a is a service of node_a, not of the hub
+        let relay_bytes = relay_message("hub", "node_b", vec!["hub".into()], 8);
+        dispatcher
+            .dispatch(
+                "hub".into(),
+                &CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+                relay_bytes,
+            )
+            .unwrap();
+
+        assert!(mock_sender.next_outbound().is_none());
+    }
+
+    // Test that a relayed message is dropped unless its path starts at its origin and ends at the
+    // node it was received from, or, if its path is empty, unless it was received from its origin
+    #[test]
+    fn test_relay_message_path_mismatch() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let handler = CircuitRelayMessageHandler::new(
+            "node_b".into(),
+            relay_state("node_b"),
+            mock_connected_peers(vec!["hub".into()]),
+        );
+        dispatcher.set_handler(Box::new(handler));
+
+        for path in vec![
+            vec![],
+            vec!["node_a".to_string()],
+            vec!["hub".to_string()],
+            vec!["hub".to_string(), "node_a".to_string()],
+        ] {
+            let relay_bytes = relay_message("node_a", "node_b", path, 7);
+            dispatcher
+                .dispatch(
+                    "hub".into(),
+                    &CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+                    relay_bytes,
+                )
+                .unwrap();
+            assert!(mock_sender.next_outbound().is_none());
+        }
+
+        // a message received directly from its origin may have an empty path
+        let relay_bytes = relay_message("node_a", "node_b", vec![], 8);
+        dispatcher
+            .dispatch(
+                "node_a".into(),
+                &CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+                relay_bytes,
+            )
+            .unwrap();
+        assert!(mock_sender.next_outbound().is_some());
+    }
+
+    // Test that relayed messages are dropped when they loop back to a node they passed through,
+    // or when their time to live has expired
+    #[test]
+    fn test_relay_message_loop_and_ttl() {
+        let mock_sender = MockSender::new();
+        let mut dispatcher = Dispatcher::new(Box::new(mock_sender.clone()));
+
+        let handler = CircuitRelayMessageHandler::new(
+            "hub".into(),
+            relay_state("hub"),
+            mock_connected_peers(vec!["node_a".into(), "node_b".into()]),
+        );
+        dispatcher.set_handler(Box::new(handler));
+
+        let looped_bytes = relay_message(
+            "node_a",
+            "node_b",
+            vec!["node_a".into(), "hub".into(), "node_b".into()],
+            6,
+        );
+        dispatcher
+            .dispatch(
+                "node_b".into(),
+                &CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+                looped_bytes,
+            )
+            .unwrap();
+        assert!(mock_sender.next_outbound().is_none());
+
+        let expired_bytes = relay_message("node_a", "node_b", vec!["node_a".into()], 0);
+        dispatcher
+            .dispatch(
+                "node_a".into(),
+                &CircuitMessageType::CIRCUIT_RELAY_MESSAGE,
+                expired_bytes,
+            )
+            .unwrap();
+        assert!(mock_sender.next_outbound().is_none());
+    }
+
+    // Test that the next hop is the destination when it is connected, a connected member of the
+    // circuit otherwise, and never a node the message has already passed through
+    #[test]
+    fn test_next_hop() {
+        let state = relay_state("node_a");
+        let circuit = state.circuit("alpha").unwrap().unwrap();
+
+        let connected_peers = mock_connected_peers(vec!["hub".into(), "node_b".into()]);
+        assert_eq!(
+            Some("node_b".to_string()),
+            next_hop(&connected_peers, &circuit, "node_b", &[]).unwrap()
+        );
+
+        let connected_peers = mock_connected_peers(vec!["hub".into()]);
+        assert_eq!(
+            Some("hub".to_string()),
+            next_hop(&connected_peers, &circuit, "node_b", &[]).unwrap()
+        );
+        assert_eq!(
+            None,
+            next_hop(&connected_peers, &circuit, "node_b", &["hub".into()]).unwrap()
+        );
+    }
+
+    fn relay_state(node_id: &str) -> SplinterState {
+        let circuit = Circuit::builder()
+            .with_id("alpha".into())
+            .with_auth(AuthorizationType::Trust)
+            .with_members(vec!["node_a".into(), "hub".into(), "node_b".into()])
+            .with_roster(vec!["service_a".into(), "service_b".into()])
+            .with_persistence(PersistenceType::Any)
+            .with_durability(DurabilityType::NoDurability)
+            .with_routes(RouteType::Relay)
+            .with_circuit_management_type("circuit_relay_test_app".into())
+            .build()
+            .expect("Should have built a correct circuit");
+
+        let mut circuit_directory = CircuitDirectory::new();
+        circuit_directory.add_circuit("alpha".to_string(), circuit);
+        let state = SplinterState::new("memory".to_string(), circuit_directory);
+
+        let node_a = SplinterNode::new("node_a".to_string(), vec![]);
+        let node_b = SplinterNode::new("node_b".to_string(), vec![]);
+        let service_b_peer = if node_id == "node_b" {
+            Some("service_b_peer".to_string())
+        } else {
+            None
+        };
+        state
+            .add_service(
+                ServiceId::new("alpha".into(), "service_a".into()),
+                Service::new("service_a".to_string(), None, node_a),
+            )
+            .unwrap();
+        state
+            .add_service(
+                ServiceId::new("alpha".into(), "service_b".into()),
+                Service::new("service_b".to_string(), service_b_peer, node_b),
+            )
+            .unwrap();
+
+        state
+    }
+
+    fn relay_message(origin: &str, destination: &str, path: Vec<String>, ttl: u32) -> Vec<u8> {
+        let mut direct_message = CircuitDirectMessage::new();
+        direct_message.set_circuit("alpha".into());
+        direct_message.set_sender("service_a".into());
+        direct_message.set_recipient("service_b".into());
+        direct_message.set_payload(b"test".to_vec());
+
+        let mut relay_message = CircuitRelayMessage::new();
+        relay_message.set_circuit("alpha".into());
+        relay_message.set_origin(origin.into());
+        relay_message.set_destination(destination.into());
+        relay_message.set_path(path.into());
+        relay_message.set_ttl(ttl);
+        relay_message.set_payload(direct_message.write_to_bytes().unwrap());
+        relay_message.write_to_bytes().unwrap()
+    }
+
+    fn parse_circuit_message<M: protobuf::Message>(
+        message: Vec<u8>,
+        expected_circuit_msg_type: CircuitMessageType,
+    ) -> M {
+        let network_msg: NetworkMessage = protobuf::parse_from_bytes(&message).unwrap();
+        let circuit_msg: CircuitMessage =
+            protobuf::parse_from_bytes(network_msg.get_payload()).unwrap();
+        assert_eq!(expected_circuit_msg_type, circuit_msg.get_message_type());
+        protobuf::parse_from_bytes(circuit_msg.get_payload()).unwrap()
+    }
+
+    /// Returns connected peers that track a peer manager with the given peers connected.
+    fn mock_connected_peers(connected_peers: Vec<String>) -> ConnectedPeers {
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            while let Ok(message) = receiver.recv() {
+                match message {
+                    PeerManagerMessage::Request(PeerManagerRequest::Subscribe {
+                        sender, ..
+                    }) => {
+                        let _ = sender.send(Ok(0));
+                    }
+                    PeerManagerMessage::Request(PeerManagerRequest::ListConnectedPeers {
+                        sender,
+                    }) => {
+                        let _ = sender.send(Ok(connected_peers.clone()));
+                    }
+                    _ => (),
+                }
+            }
+        });
+        ConnectedPeers::track(&PeerManagerConnector::new(sender))
+            .expect("Unable to track connected peers")
+    }
+
+    #[derive(Clone)]
+    struct MockSender {
+        outbound: Arc<Mutex<VecDeque<(PeerId, Vec<u8>)>>>,
+    }
+
+    impl MockSender {
+        fn new() -> Self {
+            Self {
+                outbound: Arc::new(Mutex::new(VecDeque::new())),
+            }
+        }
+
+        fn next_outbound(&self) -> Option<(PeerId, Vec<u8>)> {
+            self.outbound.lock().expect("lock was poisoned").pop_front()
+        }
+    }
+
+    impl MessageSender<PeerId> for MockSender {
+        fn send(&self, id: PeerId, message: Vec<u8>) -> Result<(), (PeerId, Vec<u8>)> {
+            self.outbound
+                .lock()
+                .expect("lock was poisoned")
+                .push_back((id, message));
+
+            Ok(())
+        }
+    }
+}
@@ -57,10 +57,12 @@ impl Handler for ServiceConnectRequestHandler {
         response.set_service_id(service_id.into());
 
         // hold on to the write lock for the entirety of the function
+        // services cannot connect to a circuit that has been abandoned by this node
         let circuit_result = self
             .state
             .circuit(circuit_name)
-            .map_err(|err| DispatchError::HandleError(err.context()))?;
+            .map_err(|err| DispatchError::HandleError(err.context()))?
+            .filter(|circuit| circuit.circuit_status().is_active());
 
         if let Some(circuit) = circuit_result {
             // If the circuit has the service in its roster and the service is not yet connected
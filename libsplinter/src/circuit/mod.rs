@@ -15,6 +15,7 @@
 #[cfg(feature = "service-network")]
 pub mod component;
 pub mod directory;
+pub mod durable;
 pub mod handlers;
 #[cfg(feature = "routing-table")]
 pub mod routing;
@@ -34,6 +35,7 @@ use std::sync::{Arc, RwLock};
 use crate::circuit::directory::CircuitDirectory;
 use crate::circuit::service::{Service, ServiceId, SplinterNode};
 use crate::circuit::store::{CircuitFilter, CircuitIter, CircuitStore, CircuitStoreError};
+use crate::hex::{as_hex, deserialize_hex};
 use crate::storage::get_storage;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -49,6 +51,21 @@ pub struct Circuit {
 
     #[serde(default = "Circuit::default_management_type")]
     circuit_management_type: String,
+
+    #[serde(default, skip_serializing_if = "CircuitStatus::is_active")]
+    circuit_status: CircuitStatus,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "as_hex",
+        deserialize_with = "deserialize_hex"
+    )]
+    application_metadata: Vec<u8>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    comments: String,
+    #[serde(default, skip_serializing_if = "VotingPolicy::is_unanimous")]
+    voting_policy: VotingPolicy,
 }
 
 impl Circuit {
@@ -70,6 +87,10 @@ impl Circuit {
             durability: DurabilityType::NoDurability,
             routes: RouteType::Any,
             circuit_management_type: "".into(),
+            circuit_status: CircuitStatus::Active,
+            application_metadata: vec![],
+            comments: "".into(),
+            voting_policy: VotingPolicy::Unanimous,
         }
     }
 
@@ -104,6 +125,30 @@ impl Circuit {
     pub fn circuit_management_type(&self) -> &str {
         &self.circuit_management_type
     }
+
+    pub fn circuit_status(&self) -> &CircuitStatus {
+        &self.circuit_status
+    }
+
+    pub fn application_metadata(&self) -> &[u8] {
+        &self.application_metadata
+    }
+
+    pub fn comments(&self) -> &str {
+        &self.comments
+    }
+
+    pub fn voting_policy(&self) -> &VotingPolicy {
+        &self.voting_policy
+    }
+
+    /// Returns a copy of this circuit marked as abandoned by the local node.
+    pub fn into_abandoned(self) -> Self {
+        Circuit {
+            circuit_status: CircuitStatus::Abandoned,
+            ..self
+        }
+    }
 }
 
 #[derive(Default)]
@@ -117,6 +162,10 @@ pub struct CircuitBuilder {
     routes: Option<RouteType>,
 
     circuit_management_type: Option<String>,
+    circuit_status: Option<CircuitStatus>,
+    application_metadata: Vec<u8>,
+    comments: Option<String>,
+    voting_policy: Option<VotingPolicy>,
 }
 
 impl CircuitBuilder {
@@ -167,6 +216,30 @@ impl CircuitBuilder {
         self
     }
 
+    pub fn with_circuit_status(mut self, circuit_status: CircuitStatus) -> Self {
+        self.circuit_status = Some(circuit_status);
+
+        self
+    }
+
+    pub fn with_application_metadata(mut self, application_metadata: Vec<u8>) -> Self {
+        self.application_metadata = application_metadata;
+
+        self
+    }
+
+    pub fn with_comments(mut self, comments: String) -> Self {
+        self.comments = Some(comments);
+
+        self
+    }
+
+    pub fn with_voting_policy(mut self, voting_policy: VotingPolicy) -> Self {
+        self.voting_policy = Some(voting_policy);
+
+        self
+    }
+
     pub fn build(self) -> Result<Circuit, CircuitBuildError> {
         if self.members.is_empty() {
             return Err(CircuitBuildError(
@@ -196,6 +269,10 @@ impl CircuitBuilder {
             circuit_management_type: self
                 .circuit_management_type
                 .unwrap_or_else(Circuit::default_management_type),
+            circuit_status: self.circuit_status.unwrap_or_default(),
+            application_metadata: self.application_metadata,
+            comments: self.comments.unwrap_or_default(),
+            voting_policy: self.voting_policy.unwrap_or_default(),
         })
     }
 }
@@ -355,11 +432,73 @@ pub enum PersistenceType {
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum DurabilityType {
     NoDurability,
+    /// Messages for a member that is not connected are stored by the sending node and delivered in
+    /// order when the member reconnects
+    Durable,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub enum RouteType {
     Any,
+    /// Messages may be relayed through other members of the circuit when the sending node is not
+    /// connected to the recipient's node
+    Relay,
+}
+
+/// The status of a circuit from the point of view of the local node.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum CircuitStatus {
+    Active,
+    /// The local node has unilaterally left the circuit; its services are stopped and no traffic
+    /// is routed for the circuit.
+    Abandoned,
+}
+
+impl CircuitStatus {
+    pub fn is_active(&self) -> bool {
+        *self == CircuitStatus::Active
+    }
+}
+
+impl Default for CircuitStatus {
+    fn default() -> Self {
+        CircuitStatus::Active
+    }
+}
+
+/// The policy used to decide whether a proposal for a circuit has been accepted by its voting
+/// members.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum VotingPolicy {
+    Unanimous,
+    Majority,
+    /// At least the given number of voting members, and at least one, must accept; if fewer
+    /// members remain on the circuit, all of them must accept.
+    Threshold(u32),
+}
+
+impl VotingPolicy {
+    pub fn is_unanimous(&self) -> bool {
+        *self == VotingPolicy::Unanimous
+    }
+
+    /// Returns the number of accepting votes needed for a proposal to be accepted, given the
+    /// number of members that vote on it.
+    pub fn required_votes(&self, voting_members: usize) -> usize {
+        match self {
+            VotingPolicy::Unanimous => voting_members,
+            VotingPolicy::Majority => voting_members / 2 + 1,
+            VotingPolicy::Threshold(required_votes) => {
+                std::cmp::min(std::cmp::max(*required_votes as usize, 1), voting_members)
+            }
+        }
+    }
+}
+
+impl Default for VotingPolicy {
+    fn default() -> Self {
+        VotingPolicy::Unanimous
+    }
 }
 
 pub enum RosterIter<'r> {
@@ -796,4 +935,17 @@ mod tests {
         // Check that state does not have any nodes
         assert!(storage.read().nodes().len() == 0);
     }
+
+    // test the number of accepting votes each voting policy requires
+    #[test]
+    fn test_voting_policy_required_votes() {
+        assert_eq!(VotingPolicy::Unanimous.required_votes(4), 4);
+        assert_eq!(VotingPolicy::Majority.required_votes(4), 3);
+        assert_eq!(VotingPolicy::Majority.required_votes(5), 3);
+        assert_eq!(VotingPolicy::Threshold(2).required_votes(5), 2);
+        // a threshold larger than the remaining members requires all of them
+        assert_eq!(VotingPolicy::Threshold(4).required_votes(3), 3);
+        // a threshold of zero still requires an accepting vote
+        assert_eq!(VotingPolicy::Threshold(0).required_votes(3), 1);
+    }
 }
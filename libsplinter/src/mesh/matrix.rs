@@ -18,7 +18,7 @@ use crate::transport::matrix::{
     ConnectionMatrixAddError, ConnectionMatrixEnvelope, ConnectionMatrixLifeCycle,
     ConnectionMatrixReceiver, ConnectionMatrixRecvError, ConnectionMatrixRecvTimeoutError,
     ConnectionMatrixRemoveError, ConnectionMatrixSendError, ConnectionMatrixSender,
-    ConnectionMatrixShutdown,
+    ConnectionMatrixShutdown, ConnectionMatrixStats,
 };
 use crate::transport::Connection;
 
@@ -58,6 +58,10 @@ impl ConnectionMatrixLifeCycle for MeshLifeCycle {
             )
         })
     }
+
+    fn connection_stats(&self, id: &str) -> Option<ConnectionMatrixStats> {
+        self.mesh.connection_stats(id)
+    }
 }
 
 #[derive(Clone)]
@@ -55,6 +55,7 @@ mod matrix;
 mod outgoing;
 mod pool;
 mod reactor;
+mod stats;
 
 use std::collections::HashMap;
 use std::error::Error;
@@ -70,6 +71,7 @@ pub use crate::mesh::matrix::{
 };
 use crate::mesh::outgoing::Outgoing;
 pub use crate::transport::matrix::ConnectionMatrixEnvelope as Envelope;
+pub use crate::transport::matrix::ConnectionMatrixStats as ConnectionStats;
 
 use crate::collections::BiHashMap;
 use crate::mesh::reactor::Reactor;
@@ -186,6 +188,14 @@ impl Mesh {
         }
     }
 
+    /// Returns the traffic statistics of a connection in the mesh, or `None` if the mesh has no
+    /// connection with the given unique id.
+    pub fn connection_stats(&self, unique_id: &str) -> Option<ConnectionStats> {
+        let state = self.state.read().ok()?;
+        let mesh_id = state.unique_ids.get_by_key(unique_id)?;
+        state.outgoings.get(mesh_id).map(Outgoing::stats)
+    }
+
     /// Receive a new envelope from the mesh.
     pub fn recv(&self) -> Result<Envelope, RecvError> {
         let internal_envelope = self.incoming.recv().map_err(|_| RecvError::Disconnected)?;
@@ -368,7 +378,8 @@ mod tests {
     use std::thread;
 
     use crate::transport::{
-        socket::tests::create_test_tls_transport, socket::TcpTransport, Transport,
+        inproc::InprocTransport, socket::tests::create_test_tls_transport, socket::TcpTransport,
+        Transport,
     };
 
     fn assert_ok<T, E: Debug>(result: Result<T, E>) -> T {
@@ -546,6 +557,49 @@ mod tests {
         test_add_remove_connections(tls, "127.0.0.1:0");
     }
 
+    // Test that the mesh counts the messages and bytes sent and received over each connection
+    #[test]
+    fn test_connection_stats() {
+        let mut transport = InprocTransport::default();
+        let mut listener = assert_ok(transport.listen("inproc://stats"));
+
+        let mesh = Mesh::new(4, 4);
+        assert_ok(mesh.add(
+            assert_ok(transport.connect("inproc://stats")),
+            "client".to_string(),
+        ));
+        assert_ok(mesh.add(assert_ok(listener.accept()), "server".to_string()));
+
+        assert_ok(mesh.send(Envelope::new("client".to_string(), b"hello".to_vec())));
+        assert_ok(mesh.send(Envelope::new("client".to_string(), b"world!".to_vec())));
+        assert_ok(mesh.recv());
+        assert_ok(mesh.recv());
+
+        assert_eq!(
+            Some(ConnectionStats {
+                messages_sent: 2,
+                bytes_sent: 11,
+                messages_received: 0,
+                bytes_received: 0,
+                queue_depth: 0,
+            }),
+            mesh.connection_stats("client")
+        );
+        assert_eq!(
+            Some(ConnectionStats {
+                messages_sent: 0,
+                bytes_sent: 0,
+                messages_received: 2,
+                bytes_received: 11,
+                queue_depth: 0,
+            }),
+            mesh.connection_stats("server")
+        );
+        assert_eq!(None, mesh.connection_stats("unknown"));
+
+        mesh.shutdown_signaler().shutdown();
+    }
+
     #[test]
     // Test that mesh can be shutdown after sending and receiving a message.
     //
@@ -15,32 +15,49 @@
 use mio_extras::channel::{SyncSender, TrySendError};
 
 use std::io;
+use std::sync::Arc;
 
-use super::InternalEnvelope;
+use super::stats::Counters;
+use super::{ConnectionStats, InternalEnvelope};
 
 /// Handle for sending to a specific connection in the mesh
 #[derive(Clone)]
 pub struct Outgoing {
     id: usize,
     tx: SyncSender<InternalEnvelope>,
+    counters: Arc<Counters>,
 }
 
 impl Outgoing {
-    pub(super) fn new(id: usize, tx: SyncSender<InternalEnvelope>) -> Self {
-        Outgoing { id, tx }
+    pub(super) fn new(
+        id: usize,
+        tx: SyncSender<InternalEnvelope>,
+        counters: Arc<Counters>,
+    ) -> Self {
+        Outgoing { id, tx, counters }
     }
 
     pub fn send(&self, payload: Vec<u8>) -> Result<(), SendError> {
-        self.tx.try_send(InternalEnvelope::Message {
+        // Count the message before sending it, so the reactor never unqueues an uncounted message
+        self.counters.message_queued();
+        if let Err(err) = self.tx.try_send(InternalEnvelope::Message {
             id: self.id,
             payload,
-        })?;
+        }) {
+            self.counters.message_unqueued();
+            return Err(err.into());
+        }
         Ok(())
     }
 
     pub fn id(&self) -> usize {
         self.id
     }
+
+    /// Returns the traffic statistics of the connection
+    pub fn stats(&self) -> ConnectionStats {
+        self.counters.stats()
+    }
 }
 
 #[derive(Debug)]
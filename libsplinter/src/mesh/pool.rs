@@ -21,9 +21,11 @@ use std::collections::HashMap;
 use std::fmt;
 use std::io;
 use std::sync::mpsc::TryRecvError;
+use std::sync::Arc;
 
 use crate::transport::{Connection, RecvError, SendError};
 
+use super::stats::Counters;
 use super::InternalEnvelope;
 
 /// A structure for holding onto many connections and receivers and assigning new connections
@@ -64,6 +66,7 @@ impl Pool {
         &mut self,
         connection: Box<dyn Connection>,
         outgoing: mio_channel::Receiver<InternalEnvelope>,
+        counters: Arc<Counters>,
     ) -> Result<usize, io::Error> {
         let connection_token = self.next_token();
         let outgoing_token = self.next_token();
@@ -87,7 +90,14 @@ impl Pool {
         self.tokens.insert(outgoing_token, id);
         self.entries.insert(
             id,
-            Entry::new(id, connection, connection_token, outgoing, outgoing_token),
+            Entry::new(
+                id,
+                connection,
+                connection_token,
+                outgoing,
+                outgoing_token,
+                counters,
+            ),
         );
 
         Ok(id)
@@ -190,6 +200,7 @@ struct Entry {
     outgoing_token: Token,
     cached: RefCell<Option<Vec<u8>>>,
     write_evented_guard: RefCell<bool>,
+    counters: Arc<Counters>,
 }
 
 impl fmt::Debug for Entry {
@@ -209,6 +220,7 @@ impl Entry {
         connection_token: Token,
         outgoing: mio_channel::Receiver<InternalEnvelope>,
         outgoing_token: Token,
+        counters: Arc<Counters>,
     ) -> Self {
         Entry {
             id,
@@ -218,6 +230,7 @@ impl Entry {
             outgoing_token,
             cached: RefCell::new(None),
             write_evented_guard: RefCell::new(false),
+            counters,
         }
     }
 
@@ -313,6 +326,7 @@ impl Entry {
 
         match connection.send(&payload) {
             Ok(()) => {
+                self.counters.message_sent(payload.len());
                 // Return to readable only.
                 if self.write_evented_guard.replace(false) {
                     poll.reregister(
@@ -361,6 +375,7 @@ impl Entry {
             };
             match connection.recv() {
                 Ok(payload) => {
+                    self.counters.message_received(payload.len());
                     match incoming_tx.try_send(InternalEnvelope::Message {
                         id: self.id,
                         payload,
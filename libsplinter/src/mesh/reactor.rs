@@ -16,6 +16,7 @@ use mio::{Event, Events, Token};
 use mio_extras::channel as mio_channel;
 
 use std::sync::mpsc::TryRecvError;
+use std::sync::Arc;
 use std::thread;
 
 use crate::mesh::{
@@ -26,6 +27,7 @@ use crate::mesh::{
     incoming::Incoming,
     outgoing::Outgoing,
     pool::Pool,
+    stats::Counters,
     InternalEnvelope,
 };
 use crate::transport::Connection;
@@ -149,9 +151,10 @@ impl Reactor {
 
     fn add_connection(&mut self, connection: Box<dyn Connection>) -> AddResponse {
         let (tx, rx) = mio_channel::sync_channel(self.outgoing_capacity);
+        let counters = Arc::new(Counters::default());
 
-        match self.pool.add(connection, rx) {
-            Ok(id) => Ok(Outgoing::new(id, tx)),
+        match self.pool.add(connection, rx, Arc::clone(&counters)) {
+            Ok(id) => Ok(Outgoing::new(id, tx, counters)),
             Err(err) => Err(AddError::Io(err)),
         }
     }
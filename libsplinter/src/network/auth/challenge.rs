@@ -0,0 +1,273 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Challenge authorization of connections.
+//!
+//! A node accepting a connection sends a random nonce and its own identity to the connecting node.
+//! The connecting node signs the challenger's identity, its own identity and the nonce with its
+//! node key. The signature is accepted if it was made by one of the keys that the registry lists
+//! for the identity the connecting node claims.
+//!
+//! Because the signed message names both nodes, a signature made for a challenge from one node
+//! can't be presented to another. A node only answers challenges on connections that it is
+//! challenging itself, and only accepts the identity of the node it answered a challenge from.
+
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use rand::Rng;
+
+use crate::hex::{parse_hex, to_hex};
+use crate::registry::RegistryReader;
+use crate::signing::{SignatureVerifier, SignatureVerifierFactory, Signer};
+
+use super::AuthorizationManagerError;
+
+const NONCE_SIZE: usize = 64;
+
+/// The keys and registry used to authorize connections via challenges.
+///
+/// The signer is used to answer the challenges sent by remote nodes. The challenges sent to remote
+/// nodes must be signed by one of the keys listed in the registry for the identity they claim; the
+/// signatures are checked with verifiers created by the verifier factory.
+#[derive(Clone)]
+pub struct ChallengeAuthorization {
+    signer: Arc<dyn Signer + Send + Sync>,
+    verifier_factory: Arc<Mutex<Box<dyn SignatureVerifierFactory>>>,
+    registry: Arc<dyn RegistryReader>,
+}
+
+impl ChallengeAuthorization {
+    /// Constructs a new `ChallengeAuthorization`.
+    pub fn new(
+        signer: Box<dyn Signer + Send + Sync>,
+        verifier_factory: Box<dyn SignatureVerifierFactory>,
+        registry: Box<dyn RegistryReader>,
+    ) -> Self {
+        Self {
+            signer: signer.into(),
+            verifier_factory: Arc::new(Mutex::new(verifier_factory)),
+            registry: registry.into(),
+        }
+    }
+
+    /// Creates the authorizer for a single connection.
+    pub(super) fn connection_authorizer(
+        &self,
+        identity: &str,
+    ) -> Result<ChallengeAuthorizer, AuthorizationManagerError> {
+        let verifier = self
+            .verifier_factory
+            .lock()
+            .map_err(|_| AuthorizationManagerError("Verifier factory lock was poisoned".into()))?
+            .create_verifier();
+
+        Ok(ChallengeAuthorizer {
+            identity: identity.to_string(),
+            signer: Arc::clone(&self.signer),
+            verifier: Mutex::new(verifier),
+            registry: Arc::clone(&self.registry),
+            state: Mutex::new(ChallengeState::default()),
+        })
+    }
+}
+
+/// The errors that may occur while authorizing a connection via a challenge.
+#[derive(Debug)]
+pub(crate) enum ChallengeError {
+    /// The remote node did not prove the identity it claims.
+    Rejected(String),
+    /// The challenge could not be created or checked.
+    Internal(String),
+}
+
+impl fmt::Display for ChallengeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChallengeError::Rejected(msg) => f.write_str(msg),
+            ChallengeError::Internal(msg) => f.write_str(msg),
+        }
+    }
+}
+
+/// Performs the challenge authorization of a single connection.
+pub(crate) struct ChallengeAuthorizer {
+    identity: String,
+    signer: Arc<dyn Signer + Send + Sync>,
+    verifier: Mutex<Box<dyn SignatureVerifier>>,
+    registry: Arc<dyn RegistryReader>,
+    state: Mutex<ChallengeState>,
+}
+
+/// The progress of the challenges on a single connection.
+#[derive(Default)]
+struct ChallengeState {
+    /// The nonce sent to the remote node, until its signature is checked.
+    nonce: Option<Vec<u8>>,
+    /// Whether a challenge has been sent to the remote node.
+    challenge_sent: bool,
+    /// The identity of the remote node, once it has sent a challenge or answered one.
+    remote_identity: Option<String>,
+}
+
+impl ChallengeState {
+    /// Sets the identity of the remote node, failing if it differs from the one already set.
+    fn set_remote_identity(&mut self, identity: &str) -> Result<(), ChallengeError> {
+        match &self.remote_identity {
+            Some(remote_identity) if remote_identity != identity => {
+                Err(ChallengeError::Rejected(format!(
+                    "Remote node claimed to be both {} and {}",
+                    remote_identity, identity
+                )))
+            }
+            Some(_) => Ok(()),
+            None => {
+                self.remote_identity = Some(identity.to_string());
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ChallengeAuthorizer {
+    /// Returns the local node's identity, which is sent to the remote node with each challenge.
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    /// Returns a new nonce for the remote node to sign, replacing any previous one.
+    pub fn new_nonce(&self) -> Result<Vec<u8>, ChallengeError> {
+        let mut nonce = vec![0; NONCE_SIZE];
+        rand::thread_rng().fill(&mut nonce[..]);
+
+        let mut state = self.lock_state()?;
+        state.nonce = Some(nonce.clone());
+        state.challenge_sent = true;
+
+        Ok(nonce)
+    }
+
+    /// Signs a challenge sent by the remote node, returning the local public key and the
+    /// signature.
+    ///
+    /// A challenge is only signed if this node has sent a challenge of its own on the connection,
+    /// and the remote node must then prove the identity it sent with its challenge.
+    pub fn sign(
+        &self,
+        challenger: &str,
+        nonce: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), ChallengeError> {
+        let mut state = self.lock_state()?;
+        if !state.challenge_sent {
+            return Err(ChallengeError::Rejected(
+                "No challenge was sent on this connection".into(),
+            ));
+        }
+
+        if challenger == self.identity {
+            return Err(ChallengeError::Rejected(format!(
+                "Challenge was sent with the local identity {}",
+                challenger
+            )));
+        }
+
+        state.set_remote_identity(challenger)?;
+
+        let signature = self
+            .signer
+            .sign(&challenge_message(challenger, &self.identity, nonce))
+            .map_err(|err| ChallengeError::Internal(err.to_string()))?;
+
+        Ok((self.signer.public_key().to_vec(), signature))
+    }
+
+    /// Verifies that the challenge sent to the remote node was signed by one of the keys the
+    /// registry lists for the given identity.
+    ///
+    /// If the remote node has sent a challenge, the given identity must be the one it sent with
+    /// the challenge.
+    pub fn verify(
+        &self,
+        identity: &str,
+        public_key: &[u8],
+        signature: &[u8],
+    ) -> Result<(), ChallengeError> {
+        let mut state = self.lock_state()?;
+        let nonce = state
+            .nonce
+            .take()
+            .ok_or_else(|| ChallengeError::Rejected("No challenge was sent".into()))?;
+
+        state.set_remote_identity(identity)?;
+
+        let node = self
+            .registry
+            .fetch_node(identity)
+            .map_err(|err| ChallengeError::Internal(err.to_string()))?
+            .ok_or_else(|| {
+                ChallengeError::Rejected(format!("{} is not in the registry", identity))
+            })?;
+
+        if !node
+            .keys
+            .iter()
+            .filter_map(|key| parse_hex(key).ok())
+            .any(|key| key == public_key)
+        {
+            return Err(ChallengeError::Rejected(format!(
+                "{} is not a key of {}",
+                to_hex(public_key),
+                identity
+            )));
+        }
+
+        let verified = self
+            .verifier
+            .lock()
+            .map_err(|_| ChallengeError::Internal("Verifier lock was poisoned".into()))?
+            .verify(
+                &challenge_message(&self.identity, identity, &nonce),
+                signature,
+                public_key,
+            )
+            .map_err(|err| ChallengeError::Rejected(err.to_string()))?;
+
+        if verified {
+            Ok(())
+        } else {
+            Err(ChallengeError::Rejected(format!(
+                "Invalid challenge signature for {}",
+                identity
+            )))
+        }
+    }
+
+    fn lock_state(&self) -> Result<MutexGuard<ChallengeState>, ChallengeError> {
+        self.state
+            .lock()
+            .map_err(|_| ChallengeError::Internal("Challenge state lock was poisoned".into()))
+    }
+}
+
+/// Returns the message signed in answer to a challenge: the challenger's identity, the identity of
+/// the node answering the challenge and the nonce. Each identity is prefixed with its length.
+pub(super) fn challenge_message(challenger: &str, identity: &str, nonce: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(8 + challenger.len() + identity.len() + nonce.len());
+    message.extend_from_slice(&(challenger.len() as u32).to_be_bytes());
+    message.extend_from_slice(challenger.as_bytes());
+    message.extend_from_slice(&(identity.len() as u32).to_be_bytes());
+    message.extend_from_slice(identity.as_bytes());
+    message.extend_from_slice(nonce);
+    message
+}
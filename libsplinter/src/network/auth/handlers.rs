@@ -12,11 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "challenge-authorization")]
+use std::sync::Arc;
+
 use protobuf::Message;
 
 use crate::network::dispatch::{
     ConnectionId, DispatchError, Dispatcher, Handler, MessageContext, MessageSender,
 };
+#[cfg(feature = "challenge-authorization")]
+use crate::protocol::authorization::ChallengeSubmitRequest;
 use crate::protocol::authorization::{
     AuthorizationError, AuthorizationMessage, AuthorizationType, Authorized, ConnectRequest,
     ConnectResponse, TrustRequest,
@@ -25,6 +30,8 @@ use crate::protos::authorization;
 use crate::protos::network::{NetworkMessage, NetworkMessageType};
 use crate::protos::prelude::*;
 
+#[cfg(feature = "challenge-authorization")]
+use super::challenge::{ChallengeAuthorizer, ChallengeError};
 use super::{
     AuthorizationAction, AuthorizationActionError, AuthorizationManagerStateMachine,
     AuthorizationMessageSender, AuthorizationState,
@@ -37,18 +44,46 @@ use super::{
 /// itself to handle updating identities (or removing connections with authorization failures).
 ///
 /// The identity provided is sent to connections for Trust authorizations.
+///
+/// If a challenge authorizer is provided, connections must be authorized via a challenge, and the
+/// authorizer is used to answer the challenges sent by the remote node.
 pub fn create_authorization_dispatcher(
     identity: String,
     auth_manager: AuthorizationManagerStateMachine,
     auth_msg_sender: impl MessageSender<ConnectionId> + Clone + 'static,
+    #[cfg(feature = "challenge-authorization")] challenge_authorizer: Option<
+        Arc<ChallengeAuthorizer>,
+    >,
 ) -> Dispatcher<NetworkMessageType, ConnectionId> {
     let mut auth_dispatcher = Dispatcher::new(Box::new(auth_msg_sender.clone()));
 
-    auth_dispatcher.set_handler(Box::new(ConnectRequestHandler::new(auth_manager.clone())));
-
-    auth_dispatcher.set_handler(Box::new(ConnectResponseHandler::new(identity)));
-
-    auth_dispatcher.set_handler(Box::new(TrustRequestHandler::new(auth_manager.clone())));
+    auth_dispatcher.set_handler(Box::new(ConnectRequestHandler::new(
+        auth_manager.clone(),
+        #[cfg(feature = "challenge-authorization")]
+        challenge_authorizer.clone(),
+    )));
+
+    auth_dispatcher.set_handler(Box::new(ConnectResponseHandler::new(
+        identity,
+        #[cfg(feature = "challenge-authorization")]
+        challenge_authorizer.clone(),
+    )));
+
+    auth_dispatcher.set_handler(Box::new(TrustRequestHandler::new(
+        auth_manager.clone(),
+        #[cfg(feature = "challenge-authorization")]
+        challenge_authorizer.is_some(),
+    )));
+
+    #[cfg(feature = "challenge-authorization")]
+    {
+        if let Some(challenge_authorizer) = challenge_authorizer {
+            auth_dispatcher.set_handler(Box::new(ChallengeSubmitRequestHandler::new(
+                auth_manager.clone(),
+                challenge_authorizer,
+            )));
+        }
+    }
 
     auth_dispatcher.set_handler(Box::new(AuthorizedHandler::new(auth_manager.clone())));
 
@@ -157,11 +192,41 @@ impl Handler for AuthorizedHandler {
 /// Handler for the Connect Request Authorization Message Type
 struct ConnectRequestHandler {
     auth_manager: AuthorizationManagerStateMachine,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorizer: Option<Arc<ChallengeAuthorizer>>,
 }
 
 impl ConnectRequestHandler {
-    fn new(auth_manager: AuthorizationManagerStateMachine) -> Self {
-        ConnectRequestHandler { auth_manager }
+    fn new(
+        auth_manager: AuthorizationManagerStateMachine,
+        #[cfg(feature = "challenge-authorization")] challenge_authorizer: Option<
+            Arc<ChallengeAuthorizer>,
+        >,
+    ) -> Self {
+        ConnectRequestHandler {
+            auth_manager,
+            #[cfg(feature = "challenge-authorization")]
+            challenge_authorizer,
+        }
+    }
+
+    /// Returns the authorization types accepted from the remote connection. If challenge
+    /// authorization is required, a new nonce is created for the remote node to sign.
+    fn accepted_authorization_types(&self) -> Result<Vec<AuthorizationType>, DispatchError> {
+        #[cfg(feature = "challenge-authorization")]
+        {
+            if let Some(challenge_authorizer) = &self.challenge_authorizer {
+                let nonce = challenge_authorizer
+                    .new_nonce()
+                    .map_err(|err| DispatchError::HandleError(err.to_string()))?;
+                return Ok(vec![AuthorizationType::Challenge {
+                    nonce,
+                    challenger: challenge_authorizer.identity().to_string(),
+                }]);
+            }
+        }
+
+        Ok(vec![AuthorizationType::Trust])
     }
 }
 
@@ -228,7 +293,7 @@ impl Handler for ConnectRequestHandler {
                 }
 
                 let response = AuthorizationMessage::ConnectResponse(ConnectResponse {
-                    accepted_authorization_types: vec![AuthorizationType::Trust],
+                    accepted_authorization_types: self.accepted_authorization_types()?,
                 });
 
                 let mut msg = NetworkMessage::new();
@@ -253,11 +318,22 @@ impl Handler for ConnectRequestHandler {
 /// Handler for the ConnectResponse Authorization Message Type
 struct ConnectResponseHandler {
     identity: String,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorizer: Option<Arc<ChallengeAuthorizer>>,
 }
 
 impl ConnectResponseHandler {
-    fn new(identity: String) -> Self {
-        ConnectResponseHandler { identity }
+    fn new(
+        identity: String,
+        #[cfg(feature = "challenge-authorization")] challenge_authorizer: Option<
+            Arc<ChallengeAuthorizer>,
+        >,
+    ) -> Self {
+        ConnectResponseHandler {
+            identity,
+            #[cfg(feature = "challenge-authorization")]
+            challenge_authorizer,
+        }
     }
 }
 
@@ -283,6 +359,52 @@ impl Handler for ConnectResponseHandler {
             connect_response,
         );
 
+        #[cfg(feature = "challenge-authorization")]
+        {
+            let challenge = connect_response
+                .accepted_authorization_types
+                .iter()
+                .find_map(|t| match t {
+                    AuthorizationType::Challenge { nonce, challenger } => Some((challenger, nonce)),
+                    _ => None,
+                });
+
+            if let (Some(challenge_authorizer), Some((challenger, nonce))) =
+                (&self.challenge_authorizer, challenge)
+            {
+                let (public_key, signature) = match challenge_authorizer.sign(challenger, nonce) {
+                    Ok(signed) => signed,
+                    Err(ChallengeError::Rejected(err_msg)) => {
+                        warn!(
+                            "Ignoring challenge from connection {}: {}",
+                            context.source_connection_id(),
+                            err_msg
+                        );
+                        return Ok(());
+                    }
+                    Err(ChallengeError::Internal(err_msg)) => {
+                        return Err(DispatchError::HandleError(err_msg))
+                    }
+                };
+                let submit_request =
+                    AuthorizationMessage::ChallengeSubmitRequest(ChallengeSubmitRequest {
+                        identity: self.identity.clone(),
+                        public_key,
+                        signature,
+                    });
+                let mut msg = NetworkMessage::new();
+                msg.set_message_type(NetworkMessageType::AUTHORIZATION);
+                msg.set_payload(
+                    IntoBytes::<authorization::AuthorizationMessage>::into_bytes(submit_request)?,
+                );
+                return sender
+                    .send(context.source_id().clone(), msg.write_to_bytes()?)
+                    .map_err(|(recipient, payload)| {
+                        DispatchError::NetworkSendError((recipient.into(), payload))
+                    });
+            }
+        }
+
         if connect_response
             .accepted_authorization_types
             .iter()
@@ -309,11 +431,20 @@ impl Handler for ConnectResponseHandler {
 /// Handler for the TrustRequest Authorization Message Type
 struct TrustRequestHandler {
     auth_manager: AuthorizationManagerStateMachine,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_required: bool,
 }
 
 impl TrustRequestHandler {
-    fn new(auth_manager: AuthorizationManagerStateMachine) -> Self {
-        TrustRequestHandler { auth_manager }
+    fn new(
+        auth_manager: AuthorizationManagerStateMachine,
+        #[cfg(feature = "challenge-authorization")] challenge_required: bool,
+    ) -> Self {
+        TrustRequestHandler {
+            auth_manager,
+            #[cfg(feature = "challenge-authorization")]
+            challenge_required,
+        }
     }
 }
 
@@ -333,6 +464,22 @@ impl Handler for TrustRequestHandler {
         sender: &dyn MessageSender<Self::Source>,
     ) -> Result<(), DispatchError> {
         let trust_request = TrustRequest::from_proto(msg)?;
+
+        #[cfg(feature = "challenge-authorization")]
+        {
+            if self.challenge_required {
+                return reject_connection(
+                    &self.auth_manager,
+                    context,
+                    sender,
+                    format!(
+                        "Trust authorization of {} is not accepted",
+                        trust_request.identity
+                    ),
+                );
+            }
+        }
+
         match self.auth_manager.next_state(
             context.source_connection_id(),
             AuthorizationAction::TrustIdentifying(trust_request.identity),
@@ -369,6 +516,135 @@ impl Handler for TrustRequestHandler {
     }
 }
 
+/// Handler for the ChallengeSubmitRequest Authorization Message Type
+#[cfg(feature = "challenge-authorization")]
+struct ChallengeSubmitRequestHandler {
+    auth_manager: AuthorizationManagerStateMachine,
+    challenge_authorizer: Arc<ChallengeAuthorizer>,
+}
+
+#[cfg(feature = "challenge-authorization")]
+impl ChallengeSubmitRequestHandler {
+    fn new(
+        auth_manager: AuthorizationManagerStateMachine,
+        challenge_authorizer: Arc<ChallengeAuthorizer>,
+    ) -> Self {
+        ChallengeSubmitRequestHandler {
+            auth_manager,
+            challenge_authorizer,
+        }
+    }
+}
+
+#[cfg(feature = "challenge-authorization")]
+impl Handler for ChallengeSubmitRequestHandler {
+    type Source = ConnectionId;
+    type MessageType = authorization::AuthorizationMessageType;
+    type Message = authorization::ChallengeSubmitRequest;
+
+    fn match_type(&self) -> Self::MessageType {
+        authorization::AuthorizationMessageType::CHALLENGE_SUBMIT_REQUEST
+    }
+
+    fn handle(
+        &self,
+        msg: Self::Message,
+        context: &MessageContext<Self::Source, Self::MessageType>,
+        sender: &dyn MessageSender<Self::Source>,
+    ) -> Result<(), DispatchError> {
+        let submit_request = ChallengeSubmitRequest::from_proto(msg)?;
+
+        match self.challenge_authorizer.verify(
+            &submit_request.identity,
+            &submit_request.public_key,
+            &submit_request.signature,
+        ) {
+            Ok(()) => (),
+            Err(ChallengeError::Rejected(err_msg)) => {
+                return reject_connection(&self.auth_manager, context, sender, err_msg)
+            }
+            Err(ChallengeError::Internal(err_msg)) => {
+                return Err(DispatchError::HandleError(err_msg))
+            }
+        }
+
+        match self.auth_manager.next_state(
+            context.source_connection_id(),
+            AuthorizationAction::ChallengeIdentifying(submit_request.identity),
+        ) {
+            Err(err) => {
+                warn!(
+                    "Ignoring challenge submit request message from connection {}: {}",
+                    context.source_connection_id(),
+                    err
+                );
+            }
+            Ok(AuthorizationState::RemoteIdentified(identity))
+            | Ok(AuthorizationState::Authorized(identity)) => {
+                debug!(
+                    "Sending Authorized message to connection {} after verifying identity {}",
+                    context.source_connection_id(),
+                    identity,
+                );
+                let auth_msg = AuthorizationMessage::Authorized(Authorized);
+                let mut msg = NetworkMessage::new();
+                msg.set_message_type(NetworkMessageType::AUTHORIZATION);
+                msg.set_payload(
+                    IntoBytes::<authorization::AuthorizationMessage>::into_bytes(auth_msg)?,
+                );
+                sender
+                    .send(context.source_id().clone(), msg.write_to_bytes()?)
+                    .map_err(|(recipient, payload)| {
+                        DispatchError::NetworkSendError((recipient.into(), payload))
+                    })?;
+            }
+            Ok(next_state) => panic!("Should not have been able to transition to {}", next_state),
+        }
+        Ok(())
+    }
+}
+
+/// Sends an authorization error to the remote connection and marks the connection as
+/// unauthorized.
+#[cfg(feature = "challenge-authorization")]
+fn reject_connection(
+    auth_manager: &AuthorizationManagerStateMachine,
+    context: &MessageContext<ConnectionId, authorization::AuthorizationMessageType>,
+    sender: &dyn MessageSender<ConnectionId>,
+    err_msg: String,
+) -> Result<(), DispatchError> {
+    info!(
+        "Rejecting authorization of connection {}: {}",
+        context.source_connection_id(),
+        err_msg
+    );
+
+    let auth_msg = AuthorizationMessage::AuthorizationError(
+        AuthorizationError::AuthorizationRejected(err_msg),
+    );
+    let mut msg = NetworkMessage::new();
+    msg.set_message_type(NetworkMessageType::AUTHORIZATION);
+    msg.set_payload(IntoBytes::<authorization::AuthorizationMessage>::into_bytes(auth_msg)?);
+    sender
+        .send(context.source_id().clone(), msg.write_to_bytes()?)
+        .map_err(|(recipient, payload)| {
+            DispatchError::NetworkSendError((recipient.into(), payload))
+        })?;
+
+    if let Err(err) = auth_manager.next_state(
+        context.source_connection_id(),
+        AuthorizationAction::Unauthorizing,
+    ) {
+        warn!(
+            "Unable to unauthorize connection {}: {}",
+            context.source_connection_id(),
+            err
+        );
+    }
+
+    Ok(())
+}
+
 /// Handler for the Authorization Error Message Type
 struct AuthorizationErrorHandler {
     auth_manager: AuthorizationManagerStateMachine,
@@ -443,6 +719,16 @@ mod tests {
 
     use crate::protos::authorization;
     use crate::protos::network::{NetworkMessage, NetworkMessageType};
+    #[cfg(feature = "challenge-authorization")]
+    use crate::{
+        hex::to_hex,
+        network::auth::{challenge::challenge_message, ChallengeAuthorization},
+        registry::{MetadataPredicate, Node, NodeIter, RegistryError, RegistryReader},
+        signing::{
+            hash::{HashSigner, HashVerifier},
+            Signer,
+        },
+    };
 
     /// Test that an connect request is properly handled via the dispatcher.
     ///
@@ -456,8 +742,13 @@ mod tests {
         let auth_mgr = AuthorizationManagerStateMachine::default();
         let mock_sender = MockSender::new();
         let dispatch_sender = mock_sender.clone();
-        let dispatcher =
-            create_authorization_dispatcher("mock_identity".into(), auth_mgr, dispatch_sender);
+        let dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            auth_mgr,
+            dispatch_sender,
+            #[cfg(feature = "challenge-authorization")]
+            None,
+        );
 
         let connection_id = "test_connection".to_string();
         let mut msg = authorization::ConnectRequest::new();
@@ -515,8 +806,13 @@ mod tests {
         let auth_mgr = AuthorizationManagerStateMachine::default();
         let mock_sender = MockSender::new();
         let dispatch_sender = mock_sender.clone();
-        let dispatcher =
-            create_authorization_dispatcher("mock_identity".into(), auth_mgr, dispatch_sender);
+        let dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            auth_mgr,
+            dispatch_sender,
+            #[cfg(feature = "challenge-authorization")]
+            None,
+        );
         let connection_id = "test_connection".to_string();
         let mut msg = authorization::ConnectResponse::new();
         msg.set_accepted_authorization_types(
@@ -559,8 +855,13 @@ mod tests {
         let auth_mgr = AuthorizationManagerStateMachine::default();
         let mock_sender = MockSender::new();
         let dispatch_sender = mock_sender.clone();
-        let dispatcher =
-            create_authorization_dispatcher("mock_identity".into(), auth_mgr, dispatch_sender);
+        let dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            auth_mgr,
+            dispatch_sender,
+            #[cfg(feature = "challenge-authorization")]
+            None,
+        );
         let connection_id = "test_connection".to_string();
         // Begin the connection process, otherwise, the response will fail
         let mut msg = authorization::ConnectRequest::new();
@@ -613,6 +914,418 @@ mod tests {
         );
     }
 
+    /// Test that a connect request is answered with a challenge when challenge authorization is
+    /// required.
+    ///
+    /// This is verified by:
+    ///
+    /// 1) the connect response only accepts challenge authorization
+    /// 2) the connect response includes a nonce and the local identity
+    #[cfg(feature = "challenge-authorization")]
+    #[test]
+    fn challenge_connect_request_dispatch() {
+        let auth_mgr = AuthorizationManagerStateMachine::default();
+        let mock_sender = MockSender::new();
+        let dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            auth_mgr,
+            mock_sender.clone(),
+            Some(challenge_authorizer("mock_identity")),
+        );
+
+        let connect_res_msg = send_connect_request(&dispatcher, &mock_sender, "test_connection");
+        assert_eq!(
+            vec![authorization::ConnectResponse_AuthorizationType::CHALLENGE],
+            connect_res_msg.get_accepted_authorization_types().to_vec()
+        );
+        assert!(!connect_res_msg.get_challenge_nonce().is_empty());
+        assert_eq!("mock_identity", connect_res_msg.get_challenger_identity());
+    }
+
+    /// Test that a connect response with a challenge is answered with the signed challenge.
+    ///
+    /// This is verified by:
+    ///
+    /// 1) a challenge submit request is sent to the remote connection
+    /// 2) the request includes the local identity, public key and the signature of the
+    ///    challenger's identity, the local identity and the nonce
+    #[cfg(feature = "challenge-authorization")]
+    #[test]
+    fn challenge_connect_response_dispatch() {
+        let auth_mgr = AuthorizationManagerStateMachine::default();
+        let mock_sender = MockSender::new();
+        let dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            auth_mgr,
+            mock_sender.clone(),
+            Some(challenge_authorizer("mock_identity")),
+        );
+
+        send_connect_request(&dispatcher, &mock_sender, "test_connection");
+        let submit_req = send_challenge(
+            &dispatcher,
+            &mock_sender,
+            "test_connection",
+            "remote_identity",
+            b"test_nonce",
+        );
+        assert_eq!("mock_identity", submit_req.get_identity());
+        assert_eq!(HashSigner.public_key(), submit_req.get_public_key());
+        assert_eq!(
+            HashSigner
+                .sign(&challenge_message(
+                    "remote_identity",
+                    "mock_identity",
+                    b"test_nonce"
+                ))
+                .unwrap(),
+            submit_req.get_signature()
+        );
+    }
+
+    /// Test that a challenge is not answered on a connection that the local node has not
+    /// challenged.
+    #[cfg(feature = "challenge-authorization")]
+    #[test]
+    fn challenge_connect_response_unchallenged() {
+        let auth_mgr = AuthorizationManagerStateMachine::default();
+        let mock_sender = MockSender::new();
+        let dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            auth_mgr,
+            mock_sender.clone(),
+            Some(challenge_authorizer("mock_identity")),
+        );
+
+        dispatch_challenge(
+            &dispatcher,
+            "test_connection",
+            "remote_identity",
+            b"test_nonce",
+        );
+        assert!(mock_sender.next_outbound().is_none());
+    }
+
+    /// Test that a signature made for a challenge sent by a third node is rejected.
+    ///
+    /// The local node challenges a connection, and the nonce is relayed to the remote node as a
+    /// challenge from a third node. The remote node's answer is relayed back to the local node,
+    /// which must reject it.
+    #[cfg(feature = "challenge-authorization")]
+    #[test]
+    fn challenge_relayed_signature_rejected() {
+        let local_sender = MockSender::new();
+        let local_dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            AuthorizationManagerStateMachine::default(),
+            local_sender.clone(),
+            Some(challenge_authorizer("mock_identity")),
+        );
+        let remote_sender = MockSender::new();
+        let remote_dispatcher = create_authorization_dispatcher(
+            "remote_identity".into(),
+            AuthorizationManagerStateMachine::default(),
+            remote_sender.clone(),
+            Some(challenge_authorizer("remote_identity")),
+        );
+
+        let connect_res_msg = send_connect_request(&local_dispatcher, &local_sender, "conn_local");
+        send_connect_request(&remote_dispatcher, &remote_sender, "conn_remote");
+        let submit_req = send_challenge(
+            &remote_dispatcher,
+            &remote_sender,
+            "conn_remote",
+            "third_identity",
+            connect_res_msg.get_challenge_nonce(),
+        );
+
+        send_challenge_submit_request(
+            &local_dispatcher,
+            "conn_local",
+            submit_req.get_identity(),
+            submit_req.get_public_key(),
+            submit_req.get_signature(),
+        );
+        let (_, msg_bytes) = local_sender
+            .next_outbound()
+            .expect("Unable to receive message over the network");
+        let _auth_err: authorization::AuthorizationError = expect_auth_message(
+            authorization::AuthorizationMessageType::AUTHORIZATION_ERROR,
+            &msg_bytes,
+        );
+    }
+
+    /// Test that a challenge signed by a key the registry lists for the identity is authorized,
+    /// and that a challenge signed by any other key is rejected.
+    #[cfg(feature = "challenge-authorization")]
+    #[test]
+    fn challenge_submit_request_dispatch() {
+        let auth_mgr = AuthorizationManagerStateMachine::default();
+        let mock_sender = MockSender::new();
+        let dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            auth_mgr,
+            mock_sender.clone(),
+            Some(challenge_authorizer("mock_identity")),
+        );
+
+        // a valid signature by a key of the identity is authorized
+        let connect_res_msg = send_connect_request(&dispatcher, &mock_sender, "conn_a");
+        send_challenge_submit_request(
+            &dispatcher,
+            "conn_a",
+            "remote_identity",
+            HashSigner.public_key(),
+            &HashSigner
+                .sign(&challenge_message(
+                    "mock_identity",
+                    "remote_identity",
+                    connect_res_msg.get_challenge_nonce(),
+                ))
+                .unwrap(),
+        );
+        let (_, msg_bytes) = mock_sender
+            .next_outbound()
+            .expect("Unable to receive message over the network");
+        let _auth_msg: authorization::AuthorizedMessage = expect_auth_message(
+            authorization::AuthorizationMessageType::AUTHORIZE,
+            &msg_bytes,
+        );
+
+        // a key that is not listed for the identity is rejected
+        let connect_res_msg = send_connect_request(&dispatcher, &mock_sender, "conn_b");
+        send_challenge_submit_request(
+            &dispatcher,
+            "conn_b",
+            "remote_identity",
+            b"other_key",
+            &HashSigner
+                .sign(&challenge_message(
+                    "mock_identity",
+                    "remote_identity",
+                    connect_res_msg.get_challenge_nonce(),
+                ))
+                .unwrap(),
+        );
+        let (_, msg_bytes) = mock_sender
+            .next_outbound()
+            .expect("Unable to receive message over the network");
+        let _auth_err: authorization::AuthorizationError = expect_auth_message(
+            authorization::AuthorizationMessageType::AUTHORIZATION_ERROR,
+            &msg_bytes,
+        );
+
+        // a signature of something other than the nonce is rejected
+        send_connect_request(&dispatcher, &mock_sender, "conn_c");
+        send_challenge_submit_request(
+            &dispatcher,
+            "conn_c",
+            "remote_identity",
+            HashSigner.public_key(),
+            &HashSigner.sign(b"not the nonce").unwrap(),
+        );
+        let (_, msg_bytes) = mock_sender
+            .next_outbound()
+            .expect("Unable to receive message over the network");
+        let _auth_err: authorization::AuthorizationError = expect_auth_message(
+            authorization::AuthorizationMessageType::AUTHORIZATION_ERROR,
+            &msg_bytes,
+        );
+    }
+
+    /// Test that a trust request is rejected when challenge authorization is required.
+    #[cfg(feature = "challenge-authorization")]
+    #[test]
+    fn challenge_trust_request_rejected() {
+        let auth_mgr = AuthorizationManagerStateMachine::default();
+        let mock_sender = MockSender::new();
+        let dispatcher = create_authorization_dispatcher(
+            "mock_identity".into(),
+            auth_mgr,
+            mock_sender.clone(),
+            Some(challenge_authorizer("mock_identity")),
+        );
+
+        send_connect_request(&dispatcher, &mock_sender, "test_connection");
+
+        let mut trust_req = authorization::TrustRequest::new();
+        trust_req.set_identity("remote_identity".into());
+        let mut auth_msg = authorization::AuthorizationMessage::new();
+        auth_msg.set_message_type(authorization::AuthorizationMessageType::TRUST_REQUEST);
+        auth_msg.set_payload(trust_req.write_to_bytes().unwrap());
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                "test_connection".to_string().into(),
+                &NetworkMessageType::AUTHORIZATION,
+                auth_msg.write_to_bytes().unwrap()
+            )
+        );
+
+        let (_, msg_bytes) = mock_sender
+            .next_outbound()
+            .expect("Unable to receive message over the network");
+        let _auth_err: authorization::AuthorizationError = expect_auth_message(
+            authorization::AuthorizationMessageType::AUTHORIZATION_ERROR,
+            &msg_bytes,
+        );
+    }
+
+    /// Sends a unidirectional connect request and returns the connect response.
+    #[cfg(feature = "challenge-authorization")]
+    fn send_connect_request(
+        dispatcher: &Dispatcher<NetworkMessageType, ConnectionId>,
+        mock_sender: &MockSender,
+        connection_id: &str,
+    ) -> authorization::ConnectResponse {
+        let mut msg = authorization::ConnectRequest::new();
+        msg.set_handshake_mode(authorization::ConnectRequest_HandshakeMode::UNIDIRECTIONAL);
+        let mut auth_msg = authorization::AuthorizationMessage::new();
+        auth_msg.set_message_type(authorization::AuthorizationMessageType::CONNECT_REQUEST);
+        auth_msg.set_payload(msg.write_to_bytes().unwrap());
+
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                connection_id.to_string().into(),
+                &NetworkMessageType::AUTHORIZATION,
+                auth_msg.write_to_bytes().unwrap()
+            )
+        );
+
+        let (_, msg_bytes) = mock_sender
+            .next_outbound()
+            .expect("Unable to receive message over the network");
+        expect_auth_message(
+            authorization::AuthorizationMessageType::CONNECT_RESPONSE,
+            &msg_bytes,
+        )
+    }
+
+    /// Sends a connect response with a challenge and returns the challenge submit request sent in
+    /// answer.
+    #[cfg(feature = "challenge-authorization")]
+    fn send_challenge(
+        dispatcher: &Dispatcher<NetworkMessageType, ConnectionId>,
+        mock_sender: &MockSender,
+        connection_id: &str,
+        challenger: &str,
+        nonce: &[u8],
+    ) -> authorization::ChallengeSubmitRequest {
+        dispatch_challenge(dispatcher, connection_id, challenger, nonce);
+
+        let (_, msg_bytes) = mock_sender
+            .next_outbound()
+            .expect("Unable to receive message over the network");
+        expect_auth_message(
+            authorization::AuthorizationMessageType::CHALLENGE_SUBMIT_REQUEST,
+            &msg_bytes,
+        )
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    fn dispatch_challenge(
+        dispatcher: &Dispatcher<NetworkMessageType, ConnectionId>,
+        connection_id: &str,
+        challenger: &str,
+        nonce: &[u8],
+    ) {
+        let mut msg = authorization::ConnectResponse::new();
+        msg.set_accepted_authorization_types(vec![
+            authorization::ConnectResponse_AuthorizationType::CHALLENGE,
+        ]);
+        msg.set_challenge_nonce(nonce.to_vec());
+        msg.set_challenger_identity(challenger.into());
+        let mut auth_msg = authorization::AuthorizationMessage::new();
+        auth_msg.set_message_type(authorization::AuthorizationMessageType::CONNECT_RESPONSE);
+        auth_msg.set_payload(msg.write_to_bytes().unwrap());
+
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                connection_id.to_string().into(),
+                &NetworkMessageType::AUTHORIZATION,
+                auth_msg.write_to_bytes().unwrap()
+            )
+        );
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    fn send_challenge_submit_request(
+        dispatcher: &Dispatcher<NetworkMessageType, ConnectionId>,
+        connection_id: &str,
+        identity: &str,
+        public_key: &[u8],
+        signature: &[u8],
+    ) {
+        let mut submit_req = authorization::ChallengeSubmitRequest::new();
+        submit_req.set_identity(identity.into());
+        submit_req.set_public_key(public_key.to_vec());
+        submit_req.set_signature(signature.to_vec());
+        let mut auth_msg = authorization::AuthorizationMessage::new();
+        auth_msg
+            .set_message_type(authorization::AuthorizationMessageType::CHALLENGE_SUBMIT_REQUEST);
+        auth_msg.set_payload(submit_req.write_to_bytes().unwrap());
+
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                connection_id.to_string().into(),
+                &NetworkMessageType::AUTHORIZATION,
+                auth_msg.write_to_bytes().unwrap()
+            )
+        );
+    }
+
+    /// Creates a challenge authorizer for the given identity that signs with a `HashSigner`, and a
+    /// registry in which "remote_identity" has the `HashSigner` key.
+    #[cfg(feature = "challenge-authorization")]
+    fn challenge_authorizer(identity: &str) -> Arc<ChallengeAuthorizer> {
+        let node = Node::builder("remote_identity")
+            .with_endpoint("tcp://remote:8044")
+            .with_display_name("Remote")
+            .with_key(to_hex(HashSigner.public_key()))
+            .build()
+            .expect("Unable to build node");
+
+        Arc::new(
+            ChallengeAuthorization::new(
+                Box::new(HashSigner),
+                Box::new(HashVerifier),
+                Box::new(MockRegistry { node }),
+            )
+            .connection_authorizer(identity)
+            .expect("Unable to create challenge authorizer"),
+        )
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    struct MockRegistry {
+        node: Node,
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    impl RegistryReader for MockRegistry {
+        fn list_nodes<'a, 'b: 'a>(
+            &'b self,
+            _predicates: &'a [MetadataPredicate],
+        ) -> Result<NodeIter<'a>, RegistryError> {
+            Ok(Box::new(vec![self.node.clone()].into_iter()))
+        }
+
+        fn count_nodes(&self, _predicates: &[MetadataPredicate]) -> Result<u32, RegistryError> {
+            Ok(1)
+        }
+
+        fn fetch_node(&self, identity: &str) -> Result<Option<Node>, RegistryError> {
+            if identity == self.node.identity {
+                Ok(Some(self.node.clone()))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
     fn expect_auth_message<M: protobuf::Message>(
         message_type: authorization::AuthorizationMessageType,
         msg_bytes: &[u8],
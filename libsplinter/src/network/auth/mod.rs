@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "challenge-authorization")]
+mod challenge;
 mod connection_manager;
 mod handlers;
 mod pool;
@@ -31,6 +33,9 @@ use crate::transport::{Connection, RecvError};
 use self::handlers::create_authorization_dispatcher;
 use self::pool::{ThreadPool, ThreadPoolBuilder};
 
+#[cfg(feature = "challenge-authorization")]
+pub use self::challenge::ChallengeAuthorization;
+
 const AUTHORIZATION_THREAD_POOL_SIZE: usize = 8;
 
 /// The states of a connection during authorization.
@@ -64,6 +69,8 @@ type Identity = String;
 pub(crate) enum AuthorizationAction {
     Connecting,
     TrustIdentifying(Identity),
+    #[cfg(feature = "challenge-authorization")]
+    ChallengeIdentifying(Identity),
     Unauthorizing,
     RemoteAuthorizing,
 }
@@ -73,6 +80,8 @@ impl fmt::Display for AuthorizationAction {
         match self {
             AuthorizationAction::Connecting => f.write_str("Connecting"),
             AuthorizationAction::TrustIdentifying(_) => f.write_str("TrustIdentifying"),
+            #[cfg(feature = "challenge-authorization")]
+            AuthorizationAction::ChallengeIdentifying(_) => f.write_str("ChallengeIdentifying"),
             AuthorizationAction::Unauthorizing => f.write_str("Unauthorizing"),
             AuthorizationAction::RemoteAuthorizing => f.write_str("RemoteAuthorizing"),
         }
@@ -117,6 +126,8 @@ pub struct AuthorizationManager {
     local_identity: String,
     thread_pool: ThreadPool,
     shared: Arc<Mutex<ManagedAuthorizations>>,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorization: Option<ChallengeAuthorization>,
 }
 
 impl AuthorizationManager {
@@ -134,9 +145,22 @@ impl AuthorizationManager {
             thread_pool,
             shared,
             local_identity,
+            #[cfg(feature = "challenge-authorization")]
+            challenge_authorization: None,
         })
     }
 
+    /// Requires connections to be authorized via challenges, rather than by trusting the identity
+    /// the remote node claims.
+    #[cfg(feature = "challenge-authorization")]
+    pub fn with_challenge_authorization(
+        mut self,
+        challenge_authorization: ChallengeAuthorization,
+    ) -> Self {
+        self.challenge_authorization = Some(challenge_authorization);
+        self
+    }
+
     pub fn shutdown_signaler(&self) -> ShutdownSignaler {
         ShutdownSignaler {
             thread_pool_signaler: self.thread_pool.shutdown_signaler(),
@@ -152,6 +176,8 @@ impl AuthorizationManager {
             local_identity: self.local_identity.clone(),
             shared: Arc::clone(&self.shared),
             executor: self.thread_pool.executor(),
+            #[cfg(feature = "challenge-authorization")]
+            challenge_authorization: self.challenge_authorization.clone(),
         }
     }
 }
@@ -173,6 +199,8 @@ pub struct AuthorizationConnector {
     local_identity: String,
     shared: Arc<Mutex<ManagedAuthorizations>>,
     executor: pool::JobExecutor,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorization: Option<ChallengeAuthorization>,
 }
 
 impl AuthorizationConnector {
@@ -190,8 +218,20 @@ impl AuthorizationConnector {
             shared: Arc::clone(&self.shared),
         };
         let msg_sender = AuthorizationMessageSender { sender: tx };
-        let dispatcher =
-            create_authorization_dispatcher(self.local_identity.clone(), state_machine, msg_sender);
+        #[cfg(feature = "challenge-authorization")]
+        let challenge_authorizer = match &self.challenge_authorization {
+            Some(challenge_authorization) => Some(Arc::new(
+                challenge_authorization.connection_authorizer(&self.local_identity)?,
+            )),
+            None => None,
+        };
+        let dispatcher = create_authorization_dispatcher(
+            self.local_identity.clone(),
+            state_machine,
+            msg_sender,
+            #[cfg(feature = "challenge-authorization")]
+            challenge_authorizer,
+        );
         self.executor.execute(move || {
             let connect_request_bytes = match connect_msg_bytes() {
                 Ok(bytes) => bytes,
@@ -373,6 +413,12 @@ impl AuthorizationManagerStateMachine {
                     // Verify pub key allowed
                     Ok(new_state)
                 }
+                #[cfg(feature = "challenge-authorization")]
+                AuthorizationAction::ChallengeIdentifying(identity) => {
+                    let new_state = AuthorizationState::RemoteIdentified(identity);
+                    *cur_state = new_state.clone();
+                    Ok(new_state)
+                }
                 AuthorizationAction::RemoteAuthorizing => {
                     *cur_state = AuthorizationState::RemoteAccepted;
                     Ok(AuthorizationState::RemoteAccepted)
@@ -399,6 +445,12 @@ impl AuthorizationManagerStateMachine {
                     *cur_state = new_state.clone();
                     Ok(new_state)
                 }
+                #[cfg(feature = "challenge-authorization")]
+                AuthorizationAction::ChallengeIdentifying(identity) => {
+                    let new_state = AuthorizationState::Authorized(identity);
+                    *cur_state = new_state.clone();
+                    Ok(new_state)
+                }
                 _ => Err(AuthorizationActionError::InvalidMessageOrder(
                     AuthorizationState::RemoteAccepted,
                     action,
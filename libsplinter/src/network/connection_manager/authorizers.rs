@@ -21,6 +21,9 @@
 
 use std::collections::HashMap;
 
+#[cfg(feature = "tls-peer-identity")]
+use openssl::{nid::Nid, x509::X509};
+
 use crate::transport::Connection;
 
 use super::{AuthorizationResult, Authorizer, AuthorizerCallback, AuthorizerError};
@@ -128,6 +131,138 @@ impl Authorizer for Authorizers {
     }
 }
 
+/// The fields of a peer certificate that hold the identity of the peer.
+#[cfg(feature = "tls-peer-identity")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CertificateIdentityRule {
+    /// The common name (CN) of the certificate's subject.
+    CommonName,
+    /// The DNS names and URIs of the certificate's subject alternative name (SAN) extension.
+    SubjectAltName,
+}
+
+#[cfg(feature = "tls-peer-identity")]
+impl CertificateIdentityRule {
+    /// Return the identities that the given DER-encoded certificate holds under this rule.
+    fn identities(&self, der: &[u8]) -> Result<Vec<String>, AuthorizerError> {
+        let cert = X509::from_der(der)
+            .map_err(|err| AuthorizerError(format!("Invalid peer certificate: {}", err)))?;
+
+        match self {
+            CertificateIdentityRule::CommonName => Ok(cert
+                .subject_name()
+                .entries_by_nid(Nid::COMMONNAME)
+                .filter_map(|entry| std::str::from_utf8(entry.data().as_slice()).ok())
+                .map(String::from)
+                .collect()),
+            CertificateIdentityRule::SubjectAltName => Ok(cert
+                .subject_alt_names()
+                .map(|names| {
+                    names
+                        .iter()
+                        .filter_map(|name| name.dnsname().or_else(|| name.uri()))
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default()),
+        }
+    }
+}
+
+/// Authorize connections whose identities are vouched for by their peer certificates.
+///
+/// The TlsIdentityAuthorizer delegates the authorization of a connection to another authorizer,
+/// which provides the identity that the remote peer claims.  The connection is only authorized if
+/// the certificate the peer presented when the connection was established holds that identity,
+/// according to the configured rule.  Connections without a verified peer certificate are not
+/// authorized.
+#[cfg(feature = "tls-peer-identity")]
+pub struct TlsIdentityAuthorizer {
+    rule: CertificateIdentityRule,
+    authorizer: Box<dyn Authorizer + Send>,
+}
+
+#[cfg(feature = "tls-peer-identity")]
+impl TlsIdentityAuthorizer {
+    /// Construct a new TlsIdentityAuthorizer that checks the identities provided by the given
+    /// authorizer against the peer certificates, using the given rule.
+    pub fn new(
+        rule: CertificateIdentityRule,
+        authorizer: impl Authorizer + 'static + Send,
+    ) -> Self {
+        Self {
+            rule,
+            authorizer: Box::new(authorizer),
+        }
+    }
+}
+
+#[cfg(feature = "tls-peer-identity")]
+impl Authorizer for TlsIdentityAuthorizer {
+    fn authorize_connection(
+        &self,
+        connection_id: String,
+        connection: Box<dyn Connection>,
+        on_complete: AuthorizerCallback,
+    ) -> Result<(), AuthorizerError> {
+        let cert_identities = match connection
+            .peer_certificate()
+            .map(|der| self.rule.identities(&der))
+        {
+            Some(Ok(identities)) if !identities.is_empty() => identities,
+            Some(Err(err)) => {
+                warn!(
+                    "Unable to read the peer certificate of {} ({}): {}",
+                    connection_id,
+                    connection.remote_endpoint(),
+                    err
+                );
+                return (*on_complete)(AuthorizationResult::Unauthorized {
+                    connection_id,
+                    connection,
+                })
+                .map_err(|err| AuthorizerError(err.to_string()));
+            }
+            _ => {
+                warn!(
+                    "No peer certificate identity for {} ({})",
+                    connection_id,
+                    connection.remote_endpoint()
+                );
+                return (*on_complete)(AuthorizationResult::Unauthorized {
+                    connection_id,
+                    connection,
+                })
+                .map_err(|err| AuthorizerError(err.to_string()));
+            }
+        };
+
+        self.authorizer.authorize_connection(
+            connection_id,
+            connection,
+            Box::new(move |result| match result {
+                AuthorizationResult::Authorized {
+                    connection_id,
+                    identity,
+                    connection,
+                } if !cert_identities.contains(&identity) => {
+                    warn!(
+                        "Identity {} claimed by {} ({}) does not match its peer certificate",
+                        identity,
+                        connection_id,
+                        connection.remote_endpoint()
+                    );
+                    (*on_complete)(AuthorizationResult::Unauthorized {
+                        connection_id,
+                        connection,
+                    })
+                }
+                result => (*on_complete)(result),
+            }),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -288,14 +423,147 @@ mod tests {
         }
     }
 
+    // Test that the TLS identity authorizer authorizes identities that match the common name of
+    // the peer certificate, and rejects others.
+    #[cfg(feature = "tls-peer-identity")]
+    #[test]
+    fn tls_identity_common_name_authorization() {
+        let cert = make_peer_cert("test-ident1", &["test-ident2"]);
+
+        let authorizer = TlsIdentityAuthorizer::new(
+            CertificateIdentityRule::CommonName,
+            NoopAuthorizer::new("test-ident1"),
+        );
+        match authorize(&authorizer, Some(cert.clone())) {
+            AuthorizationResult::Authorized { identity, .. } => {
+                assert_eq!("test-ident1", &identity)
+            }
+            AuthorizationResult::Unauthorized { .. } => panic!("should have been authorized"),
+        }
+
+        let authorizer = TlsIdentityAuthorizer::new(
+            CertificateIdentityRule::CommonName,
+            NoopAuthorizer::new("test-ident2"),
+        );
+        match authorize(&authorizer, Some(cert)) {
+            AuthorizationResult::Authorized { .. } => panic!("should not have been authorized"),
+            AuthorizationResult::Unauthorized { .. } => (),
+        }
+    }
+
+    // Test that the TLS identity authorizer authorizes identities that match one of the subject
+    // alternative names of the peer certificate, and rejects others.
+    #[cfg(feature = "tls-peer-identity")]
+    #[test]
+    fn tls_identity_subject_alt_name_authorization() {
+        let cert = make_peer_cert("test-ident1", &["test-ident2", "test-ident3"]);
+
+        let authorizer = TlsIdentityAuthorizer::new(
+            CertificateIdentityRule::SubjectAltName,
+            NoopAuthorizer::new("test-ident3"),
+        );
+        match authorize(&authorizer, Some(cert.clone())) {
+            AuthorizationResult::Authorized { identity, .. } => {
+                assert_eq!("test-ident3", &identity)
+            }
+            AuthorizationResult::Unauthorized { .. } => panic!("should have been authorized"),
+        }
+
+        let authorizer = TlsIdentityAuthorizer::new(
+            CertificateIdentityRule::SubjectAltName,
+            NoopAuthorizer::new("test-ident1"),
+        );
+        match authorize(&authorizer, Some(cert)) {
+            AuthorizationResult::Authorized { .. } => panic!("should not have been authorized"),
+            AuthorizationResult::Unauthorized { .. } => (),
+        }
+    }
+
+    // Test that the TLS identity authorizer rejects connections without a peer certificate.
+    #[cfg(feature = "tls-peer-identity")]
+    #[test]
+    fn tls_identity_no_certificate_authorization() {
+        let authorizer = TlsIdentityAuthorizer::new(
+            CertificateIdentityRule::CommonName,
+            NoopAuthorizer::new("test-ident1"),
+        );
+        match authorize(&authorizer, None) {
+            AuthorizationResult::Authorized { .. } => panic!("should not have been authorized"),
+            AuthorizationResult::Unauthorized { .. } => (),
+        }
+    }
+
+    #[cfg(feature = "tls-peer-identity")]
+    fn authorize(
+        authorizer: &dyn Authorizer,
+        peer_certificate: Option<Vec<u8>>,
+    ) -> AuthorizationResult {
+        let (tx, rx) = mpsc::channel();
+
+        let mut connection = MockConnection::new("tcps://127.0.0.1:8044");
+        connection.peer_certificate = peer_certificate;
+        authorizer
+            .authorize_connection(
+                "abcd-1234".into(),
+                Box::new(connection),
+                Box::new(move |result| tx.send(result).map_err(Box::from)),
+            )
+            .unwrap();
+
+        rx.recv().unwrap()
+    }
+
+    // Make a self-signed, DER-encoded certificate with the given common name and DNS subject
+    // alternative names.
+    #[cfg(feature = "tls-peer-identity")]
+    fn make_peer_cert(common_name: &str, alt_names: &[&str]) -> Vec<u8> {
+        use openssl::asn1::Asn1Time;
+        use openssl::hash::MessageDigest;
+        use openssl::pkey::PKey;
+        use openssl::rsa::Rsa;
+        use openssl::x509::{extension::SubjectAlternativeName, X509NameBuilder};
+
+        let privkey = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+
+        let mut x509_name = X509NameBuilder::new().unwrap();
+        x509_name.append_entry_by_text("CN", common_name).unwrap();
+        let x509_name = x509_name.build();
+
+        let mut cert_builder = X509::builder().unwrap();
+        cert_builder.set_version(2).unwrap();
+        cert_builder.set_subject_name(&x509_name).unwrap();
+        cert_builder.set_issuer_name(&x509_name).unwrap();
+        cert_builder.set_pubkey(&privkey).unwrap();
+        let not_before = Asn1Time::days_from_now(0).unwrap();
+        cert_builder.set_not_before(&not_before).unwrap();
+        let not_after = Asn1Time::days_from_now(365).unwrap();
+        cert_builder.set_not_after(&not_after).unwrap();
+
+        let mut san = SubjectAlternativeName::new();
+        for alt_name in alt_names {
+            san.dns(alt_name);
+        }
+        let san = san.build(&cert_builder.x509v3_context(None, None)).unwrap();
+        cert_builder.append_extension(san).unwrap();
+
+        cert_builder
+            .sign(&privkey, MessageDigest::sha256())
+            .unwrap();
+        cert_builder.build().to_der().unwrap()
+    }
+
     struct MockConnection {
         remote_endpoint: String,
+        #[cfg(feature = "tls-peer-identity")]
+        peer_certificate: Option<Vec<u8>>,
     }
 
     impl MockConnection {
         fn new(remote_endpoint: &str) -> Self {
             Self {
                 remote_endpoint: remote_endpoint.to_string(),
+                #[cfg(feature = "tls-peer-identity")]
+                peer_certificate: None,
             }
         }
     }
@@ -324,6 +592,11 @@ mod tests {
         fn evented(&self) -> &dyn mio::Evented {
             unimplemented!()
         }
+
+        #[cfg(feature = "tls-peer-identity")]
+        fn peer_certificate(&self) -> Option<Vec<u8>> {
+            self.peer_certificate.clone()
+        }
     }
 
     struct NoopAuthorizer {
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use std::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 use protobuf::Message;
 
@@ -23,6 +25,7 @@ use crate::transport::matrix::{ConnectionMatrixLifeCycle, ConnectionMatrixSender
 use crate::transport::Transport;
 
 use super::error::ConnectionManagerError;
+use super::reconnection::{ExponentialBackoff, ReconnectionPolicy};
 use super::{
     AuthResult, Authorizer, CmMessage, CmRequest, ConnectionManager, ConnectionManagerNotification,
     ConnectionManagerState, ConnectionMetadataExt, SubscriberMap,
@@ -30,6 +33,8 @@ use super::{
 
 const DEFAULT_HEARTBEAT_INTERVAL: u64 = 10;
 const DEFAULT_MAXIMUM_RETRY_FREQUENCY: u64 = 300;
+/// The delay after the first failed reconnection attempt under the default reconnection policy.
+const INITIAL_RETRY_DELAY: u64 = 20;
 
 pub struct ConnectionManagerBuilder<T, U> {
     authorizer: Option<Box<dyn Authorizer + Send>>,
@@ -38,6 +43,7 @@ pub struct ConnectionManagerBuilder<T, U> {
     transport: Option<Box<dyn Transport + Send>>,
     heartbeat_interval: u64,
     maximum_retry_frequency: u64,
+    reconnection_policy: Option<Arc<dyn ReconnectionPolicy>>,
 }
 
 impl<T, U> Default for ConnectionManagerBuilder<T, U> {
@@ -49,6 +55,7 @@ impl<T, U> Default for ConnectionManagerBuilder<T, U> {
             transport: None,
             heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
             maximum_retry_frequency: DEFAULT_MAXIMUM_RETRY_FREQUENCY,
+            reconnection_policy: None,
         }
     }
 }
@@ -114,11 +121,23 @@ where
     ///
     /// All outbound connections that are lost while managed by the resulting instance will be
     /// retried up to this maximum.
+    ///
+    /// This is ignored if a default reconnection policy is set.
     pub fn with_maximum_retry_frequency(mut self, frequency: u64) -> Self {
         self.maximum_retry_frequency = frequency;
         self
     }
 
+    /// Set the default reconnection policy for the resulting connection manager.
+    ///
+    /// Lost outbound connections that were not requested with a policy of their own are
+    /// reconnected according to this policy. If not set, they are retried with an exponential
+    /// backoff, starting at 20 seconds, up to the maximum retry frequency, and never abandoned.
+    pub fn with_reconnection_policy(mut self, policy: Box<dyn ReconnectionPolicy>) -> Self {
+        self.reconnection_policy = Some(Arc::from(policy));
+        self
+    }
+
     /// Create a started connection manager instance.
     ///
     /// This function creates and starts a `ConnectionManager` instance, which includes a
@@ -131,7 +150,13 @@ where
     pub fn start(mut self) -> Result<ConnectionManager, ConnectionManagerError> {
         let (sender, recv) = channel();
         let heartbeat = self.heartbeat_interval;
-        let retry_frequency = self.maximum_retry_frequency;
+        let maximum_retry_frequency = self.maximum_retry_frequency;
+        let reconnection_policy = self.reconnection_policy.take().unwrap_or_else(|| {
+            Arc::new(ExponentialBackoff::new(
+                Duration::from_secs(INITIAL_RETRY_DELAY),
+                Duration::from_secs(maximum_retry_frequency),
+            ))
+        });
 
         let authorizer = self
             .authorizer
@@ -158,7 +183,7 @@ where
                     life_cycle,
                     matrix_sender,
                     transport,
-                    retry_frequency,
+                    reconnection_policy,
                 );
                 let mut subscribers = SubscriberMap::new();
                 loop {
@@ -222,9 +247,11 @@ fn handle_request<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
             endpoint,
             sender,
             connection_id,
+            reconnection_policy,
         } => state.add_outbound_connection(
             &endpoint,
             connection_id,
+            reconnection_policy,
             sender,
             internal_sender,
             authorizer,
@@ -251,6 +278,15 @@ fn handle_request<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
                 warn!("connector dropped before receiving result of list connections");
             }
         }
+        CmRequest::ConnectionStats { sender } => {
+            if sender.send(Ok(state.connection_stats())).is_err() {
+                warn!("connector dropped before receiving result of connection stats");
+            }
+        }
+        CmRequest::HeartbeatReply {
+            identity,
+            heartbeat_id,
+        } => state.on_heartbeat_reply(&identity, heartbeat_id),
         CmRequest::AddInboundConnection { sender, connection } => {
             state.add_inbound_connection(connection, sender, internal_sender, authorizer)
         }
@@ -302,7 +338,8 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
     authorizer: &dyn Authorizer,
     internal_sender: Sender<CmMessage>,
 ) {
-    let heartbeat_message = match create_heartbeat() {
+    let heartbeat_id = state.next_heartbeat_id();
+    let heartbeat_message = match create_heartbeat(heartbeat_id) {
         Ok(h) => h,
         Err(err) => {
             error!("Failed to create heartbeat message: {:?}", err);
@@ -316,13 +353,13 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
         match metadata.extended_metadata {
             ConnectionMetadataExt::Outbound {
                 reconnecting,
-                retry_frequency,
+                retry_delay,
                 last_connection_attempt,
                 ..
             } => {
                 // if connection is already attempting reconnection, call reconnect
                 if reconnecting {
-                    if last_connection_attempt.elapsed().as_secs() > retry_frequency {
+                    if last_connection_attempt.elapsed() >= retry_delay {
                         reconnections.push(endpoint.to_string());
                     }
                 } else {
@@ -340,6 +377,8 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
                             identity: metadata.identity.to_string(),
                         });
                         reconnections.push(endpoint.to_string());
+                    } else {
+                        metadata.health.heartbeat_sent(heartbeat_id);
                     }
                 }
             }
@@ -364,6 +403,7 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
                     }
                 } else {
                     *disconnected = false;
+                    metadata.health.heartbeat_sent(heartbeat_id);
                 }
             }
         }
@@ -382,8 +422,10 @@ fn send_heartbeats<T: ConnectionMatrixLifeCycle, U: ConnectionMatrixSender>(
 }
 
 /// Creates NetworkHeartbeat message and serializes it into a byte array.
-fn create_heartbeat() -> Result<Vec<u8>, ConnectionManagerError> {
-    let heartbeat = NetworkHeartbeat::new().write_to_bytes().map_err(|_| {
+fn create_heartbeat(heartbeat_id: u64) -> Result<Vec<u8>, ConnectionManagerError> {
+    let mut heartbeat = NetworkHeartbeat::new();
+    heartbeat.set_heartbeat_id(heartbeat_id);
+    let heartbeat = heartbeat.write_to_bytes().map_err(|_| {
         ConnectionManagerError::HeartbeatError("cannot create NetworkHeartbeat message".to_string())
     })?;
     let mut heartbeat_message = NetworkMessage::new();
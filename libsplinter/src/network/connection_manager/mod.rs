@@ -16,25 +16,27 @@ pub mod authorizers;
 mod builder;
 mod error;
 mod notification;
+pub mod reconnection;
+mod stats;
 
-use std::cmp::min;
 use std::collections::HashMap;
 use std::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
 
 use uuid::Uuid;
 
 pub use builder::ConnectionManagerBuilder;
 pub use error::{AuthorizerError, ConnectionManagerError};
 pub use notification::ConnectionManagerNotification;
+pub use reconnection::ReconnectionPolicy;
+pub use stats::{ConnectionDirection, ConnectionState, ConnectionStats};
 
 use crate::threading::pacemaker;
 use crate::transport::matrix::{ConnectionMatrixLifeCycle, ConnectionMatrixSender};
 use crate::transport::{ConnectError, Connection, Transport};
 
-const INITIAL_RETRY_FREQUENCY: u64 = 10;
-
 pub type AuthorizerCallback =
     Box<dyn Fn(AuthorizationResult) -> Result<(), Box<dyn std::error::Error>> + Send>;
 
@@ -117,6 +119,7 @@ enum CmRequest {
     RequestOutboundConnection {
         endpoint: String,
         connection_id: String,
+        reconnection_policy: Option<Arc<dyn ReconnectionPolicy>>,
         sender: Sender<Result<(), ConnectionManagerError>>,
     },
     RemoveConnection {
@@ -126,6 +129,13 @@ enum CmRequest {
     ListConnections {
         sender: Sender<Result<Vec<String>, ConnectionManagerError>>,
     },
+    ConnectionStats {
+        sender: Sender<Result<Vec<ConnectionStats>, ConnectionManagerError>>,
+    },
+    HeartbeatReply {
+        identity: String,
+        heartbeat_id: u64,
+    },
     AddInboundConnection {
         connection: Box<dyn Connection>,
         sender: Sender<Result<(), ConnectionManagerError>>,
@@ -225,6 +235,40 @@ impl Connector {
         &self,
         endpoint: &str,
         connection_id: &str,
+    ) -> Result<(), ConnectionManagerError> {
+        self.send_connection_request(endpoint, connection_id, None)
+    }
+
+    /// Request a connection to the given endpoint, which is reconnected according to the given
+    /// policy if it is lost.
+    ///
+    /// This behaves like `request_connection`, except that the given policy replaces the
+    /// connection manager's default reconnection policy for this connection. If a connection to
+    /// the endpoint already exists, its policy is replaced. When the policy abandons the
+    /// connection, a `ConnectionManagerNotification::ConnectionAbandoned` will be sent to
+    /// subscribers.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the connection cannot be created.
+    pub fn request_connection_with_policy(
+        &self,
+        endpoint: &str,
+        connection_id: &str,
+        reconnection_policy: Box<dyn ReconnectionPolicy>,
+    ) -> Result<(), ConnectionManagerError> {
+        self.send_connection_request(
+            endpoint,
+            connection_id,
+            Some(Arc::from(reconnection_policy)),
+        )
+    }
+
+    fn send_connection_request(
+        &self,
+        endpoint: &str,
+        connection_id: &str,
+        reconnection_policy: Option<Arc<dyn ReconnectionPolicy>>,
     ) -> Result<(), ConnectionManagerError> {
         let (sender, recv) = channel();
         self.sender
@@ -232,6 +276,7 @@ impl Connector {
                 sender,
                 endpoint: endpoint.to_string(),
                 connection_id: connection_id.into(),
+                reconnection_policy,
             }))
             .map_err(|_| {
                 ConnectionManagerError::SendMessageError(
@@ -371,6 +416,53 @@ impl Connector {
         })?
     }
 
+    /// List the statistics of the connections available to this Connector instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns a ConnectionManagerError if the connections cannot be queried.
+    pub fn connection_stats(&self) -> Result<Vec<ConnectionStats>, ConnectionManagerError> {
+        let (sender, recv) = channel();
+        self.sender
+            .send(CmMessage::Request(CmRequest::ConnectionStats { sender }))
+            .map_err(|_| {
+                ConnectionManagerError::SendMessageError(
+                    "The connection manager is no longer running".into(),
+                )
+            })?;
+
+        recv.recv().map_err(|_| {
+            ConnectionManagerError::SendMessageError(
+                "The connection manager is no longer running".into(),
+            )
+        })?
+    }
+
+    /// Report that the node with the given identity replied to a heartbeat.
+    ///
+    /// The round trip time of the heartbeat is recorded in the statistics of the node's
+    /// connections. Replies to heartbeats other than the last one sent are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns a ConnectionManagerError if the connection manager is no longer running.
+    pub fn heartbeat_reply_received(
+        &self,
+        identity: &str,
+        heartbeat_id: u64,
+    ) -> Result<(), ConnectionManagerError> {
+        self.sender
+            .send(CmMessage::Request(CmRequest::HeartbeatReply {
+                identity: identity.to_string(),
+                heartbeat_id,
+            }))
+            .map_err(|_| {
+                ConnectionManagerError::SendMessageError(
+                    "The connection manager is no longer running".into(),
+                )
+            })
+    }
+
     /// Add a new inbound connection.
     ///
     /// # Error
@@ -426,6 +518,7 @@ struct ConnectionMetadata {
     endpoint: String,
     identity: String,
     extended_metadata: ConnectionMetadataExt,
+    health: ConnectionHealth,
 }
 
 impl ConnectionMetadata {
@@ -446,15 +539,34 @@ impl ConnectionMetadata {
     }
 }
 
+/// Heartbeat and reconnection history of a connection, reported in its statistics.
+#[derive(Clone, Debug, Default)]
+struct ConnectionHealth {
+    last_heartbeat: Option<SystemTime>,
+    // the id of the last heartbeat sent, and when it was sent
+    pending_heartbeat: Option<(u64, Instant)>,
+    round_trip_time: Option<Duration>,
+    reconnections: u64,
+}
+
+impl ConnectionHealth {
+    fn heartbeat_sent(&mut self, heartbeat_id: u64) {
+        self.last_heartbeat = Some(SystemTime::now());
+        self.pending_heartbeat = Some((heartbeat_id, Instant::now()));
+    }
+}
+
 /// Enum describing metadata that is specific to the two different connection
 /// types, outbound and inbound.
 #[derive(Clone, Debug)]
 enum ConnectionMetadataExt {
     Outbound {
         reconnecting: bool,
-        retry_frequency: u64,
+        retry_delay: Duration,
         last_connection_attempt: Instant,
         reconnection_attempts: u64,
+        reconnecting_since: Instant,
+        reconnection_policy: Arc<dyn ReconnectionPolicy>,
     },
     Inbound {
         disconnected: bool,
@@ -473,7 +585,10 @@ where
     life_cycle: T,
     matrix_sender: U,
     transport: Box<dyn Transport>,
-    maximum_retry_frequency: u64,
+    default_reconnection_policy: Arc<dyn ReconnectionPolicy>,
+    // policies of requested outbound connections that are not yet authorized
+    pending_reconnection_policies: HashMap<String, Arc<dyn ReconnectionPolicy>>,
+    next_heartbeat_id: u64,
 }
 
 impl<T, U> ConnectionManagerState<T, U>
@@ -485,14 +600,16 @@ where
         life_cycle: T,
         matrix_sender: U,
         transport: Box<dyn Transport + Send>,
-        maximum_retry_frequency: u64,
+        default_reconnection_policy: Arc<dyn ReconnectionPolicy>,
     ) -> Self {
         Self {
             life_cycle,
             matrix_sender,
             transport,
             connections: HashMap::new(),
-            maximum_retry_frequency,
+            default_reconnection_policy,
+            pending_reconnection_policies: HashMap::new(),
+            next_heartbeat_id: 1,
         }
     }
 
@@ -535,23 +652,33 @@ where
     }
 
     /// Adds a new outbound connection.
+    ///
+    /// If a reconnection policy is given, it replaces the default policy for the connection.
+    #[allow(clippy::too_many_arguments)]
     fn add_outbound_connection(
         &mut self,
         endpoint: &str,
         connection_id: String,
+        reconnection_policy: Option<Arc<dyn ReconnectionPolicy>>,
         reply_sender: Sender<Result<(), ConnectionManagerError>>,
         internal_sender: Sender<CmMessage>,
         authorizer: &dyn Authorizer,
         subscribers: &mut SubscriberMap,
     ) {
-        if let Some(connection) = self.connections.get(endpoint) {
+        if let Some(connection) = self.connections.get_mut(endpoint) {
             let identity = connection.identity().to_string();
             // if this connection not reconnecting or disconnected, send Connected
             // notification.
             match connection.extended_metadata {
                 ConnectionMetadataExt::Outbound {
-                    ref reconnecting, ..
+                    ref reconnecting,
+                    reconnection_policy: ref mut current_policy,
+                    ..
                 } => {
+                    if let Some(policy) = reconnection_policy {
+                        *current_policy = policy;
+                    }
+
                     if !reconnecting {
                         subscribers.broadcast(ConnectionManagerNotification::Connected {
                             endpoint: endpoint.to_string(),
@@ -577,6 +704,11 @@ where
         } else {
             match self.transport.connect(endpoint) {
                 Ok(connection) => {
+                    if let Some(policy) = reconnection_policy {
+                        self.pending_reconnection_policies
+                            .insert(endpoint.to_string(), policy);
+                    }
+
                     // add the connection to the authorization pool.
                     let auth_endpoint = endpoint.to_string();
                     if let Err(err) = authorizer.authorize_connection(
@@ -591,6 +723,7 @@ where
                                 .map_err(Box::from)
                         }),
                     ) {
+                        self.pending_reconnection_policies.remove(endpoint);
                         if reply_sender
                             .send(Err(ConnectionManagerError::connection_creation_error(
                                 &err.to_string(),
@@ -639,6 +772,24 @@ where
         auth_result: AuthorizationResult,
         subscribers: &mut SubscriberMap,
     ) {
+        // a newly requested connection uses the policy it was requested with, while a
+        // reconnected connection keeps its current policy
+        let reconnection_policy = self
+            .pending_reconnection_policies
+            .remove(&endpoint)
+            .or_else(|| {
+                self.connections
+                    .get(&endpoint)
+                    .and_then(|meta| match meta.extended_metadata {
+                        ConnectionMetadataExt::Outbound {
+                            ref reconnection_policy,
+                            ..
+                        } => Some(Arc::clone(reconnection_policy)),
+                        ConnectionMetadataExt::Inbound { .. } => None,
+                    })
+            })
+            .unwrap_or_else(|| Arc::clone(&self.default_reconnection_policy));
+
         match auth_result {
             AuthorizationResult::Authorized {
                 connection_id,
@@ -660,17 +811,30 @@ where
                     return;
                 }
 
+                // a connection replacing an existing one is a reconnection
+                let reconnections = self
+                    .connections
+                    .get(&endpoint)
+                    .map(|meta| meta.health.reconnections + 1)
+                    .unwrap_or(0);
+
                 self.connections.insert(
                     endpoint.clone(),
                     ConnectionMetadata {
                         connection_id: connection_id.to_string(),
                         identity: identity.clone(),
                         endpoint: endpoint.clone(),
+                        health: ConnectionHealth {
+                            reconnections,
+                            ..Default::default()
+                        },
                         extended_metadata: ConnectionMetadataExt::Outbound {
                             reconnecting: false,
-                            retry_frequency: INITIAL_RETRY_FREQUENCY,
+                            retry_delay: Duration::from_secs(0),
                             last_connection_attempt: Instant::now(),
                             reconnection_attempts: 0,
+                            reconnecting_since: Instant::now(),
+                            reconnection_policy,
                         },
                     },
                 );
@@ -736,6 +900,7 @@ where
                         extended_metadata: ConnectionMetadataExt::Inbound {
                             disconnected: false,
                         },
+                        health: ConnectionHealth::default(),
                     },
                 );
 
@@ -843,24 +1008,49 @@ where
                 error!("Error authorizing {}: {}", endpoint, err);
             }
         } else {
-            let reconnection_attempts = match meta.extended_metadata {
+            let (reconnection_attempts, retry_delay) = match meta.extended_metadata {
                 ConnectionMetadataExt::Outbound {
                     ref mut reconnecting,
-                    ref mut retry_frequency,
+                    ref mut retry_delay,
                     ref mut last_connection_attempt,
                     ref mut reconnection_attempts,
+                    ref mut reconnecting_since,
+                    ref reconnection_policy,
                 } => {
-                    *reconnecting = true;
-                    *retry_frequency = min(*retry_frequency * 2, self.maximum_retry_frequency);
+                    if !*reconnecting {
+                        *reconnecting = true;
+                        *reconnecting_since = Instant::now();
+                    }
                     *last_connection_attempt = Instant::now();
                     *reconnection_attempts += 1;
 
-                    *reconnection_attempts
+                    let next_delay = reconnection_policy
+                        .next_delay(*reconnection_attempts, reconnecting_since.elapsed());
+                    if let Some(delay) = next_delay {
+                        *retry_delay = delay;
+                    }
+
+                    (*reconnection_attempts, next_delay)
                 }
                 // We checked earlier that this was an outbound connection
                 _ => unreachable!(),
             };
             let identity = meta.identity.to_string();
+
+            if retry_delay.is_none() {
+                warn!(
+                    "Abandoning connection to {} after {} reconnection attempts",
+                    endpoint, reconnection_attempts
+                );
+                self.remove_connection(endpoint)?;
+                subscribers.broadcast(ConnectionManagerNotification::ConnectionAbandoned {
+                    endpoint: endpoint.to_string(),
+                    attempts: reconnection_attempts,
+                    identity,
+                });
+                return Ok(());
+            }
+
             self.connections.insert(endpoint.to_string(), meta);
 
             // Notify subscribers of reconnection failure
@@ -873,6 +1063,78 @@ where
         Ok(())
     }
 
+    /// Returns the statistics of all connections.
+    fn connection_stats(&self) -> Vec<ConnectionStats> {
+        self.connections
+            .values()
+            .map(|meta| {
+                let (direction, state) = match meta.extended_metadata {
+                    ConnectionMetadataExt::Outbound { reconnecting, .. } => (
+                        ConnectionDirection::Outbound,
+                        if reconnecting {
+                            ConnectionState::Reconnecting
+                        } else {
+                            ConnectionState::Connected
+                        },
+                    ),
+                    ConnectionMetadataExt::Inbound { disconnected } => (
+                        ConnectionDirection::Inbound,
+                        if disconnected {
+                            ConnectionState::Disconnected
+                        } else {
+                            ConnectionState::Connected
+                        },
+                    ),
+                };
+                let traffic = self
+                    .life_cycle
+                    .connection_stats(meta.connection_id())
+                    .unwrap_or_default();
+
+                ConnectionStats {
+                    endpoint: meta.endpoint.clone(),
+                    connection_id: meta.connection_id.clone(),
+                    identity: meta.identity.clone(),
+                    direction,
+                    state,
+                    messages_sent: traffic.messages_sent,
+                    bytes_sent: traffic.bytes_sent,
+                    messages_received: traffic.messages_received,
+                    bytes_received: traffic.bytes_received,
+                    queue_depth: traffic.queue_depth,
+                    last_heartbeat: meta.health.last_heartbeat,
+                    reconnections: meta.health.reconnections,
+                    round_trip_time: meta.health.round_trip_time,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the id for the next round of heartbeats.
+    fn next_heartbeat_id(&mut self) -> u64 {
+        let heartbeat_id = self.next_heartbeat_id;
+        self.next_heartbeat_id += 1;
+        heartbeat_id
+    }
+
+    /// Records the round trip time of a heartbeat that the node with the given identity replied
+    /// to, if it was the last heartbeat sent to it.
+    fn on_heartbeat_reply(&mut self, identity: &str, heartbeat_id: u64) {
+        for meta in self
+            .connections
+            .values_mut()
+            .filter(|meta| meta.identity == identity)
+        {
+            match meta.health.pending_heartbeat {
+                Some((pending_id, sent_at)) if pending_id == heartbeat_id => {
+                    meta.health.round_trip_time = Some(sent_at.elapsed());
+                    meta.health.pending_heartbeat = None;
+                }
+                _ => (),
+            }
+        }
+    }
+
     fn connection_metadata(&self) -> &HashMap<String, ConnectionMetadata> {
         &self.connections
     }
@@ -895,7 +1157,7 @@ mod tests {
     use crate::mesh::Mesh;
     use crate::network::auth::tests::negotiation_connection_auth;
     use crate::network::auth::AuthorizationManager;
-    use crate::protos::network::{NetworkMessage, NetworkMessageType};
+    use crate::protos::network::{NetworkHeartbeat, NetworkMessage, NetworkMessageType};
     use crate::transport::inproc::InprocTransport;
     use crate::transport::socket::TcpTransport;
 
@@ -1018,6 +1280,67 @@ mod tests {
         cm.await_shutdown();
     }
 
+    /// Test that the statistics of a connection:
+    ///
+    /// 1. count the heartbeats sent over the connection
+    /// 2. record the round trip time once the heartbeat is replied to
+    #[test]
+    fn test_connection_stats() {
+        let mut transport = Box::new(InprocTransport::default());
+        let mut listener = transport.listen("inproc://test").unwrap();
+        let mesh = Mesh::new(512, 128);
+        let mesh_clone = mesh.clone();
+
+        thread::spawn(move || {
+            let conn = listener.accept().unwrap();
+            mesh_clone.add(conn, "remote_id".to_string()).unwrap();
+        });
+
+        let cm = ConnectionManager::builder()
+            .with_authorizer(Box::new(NoopAuthorizer::new("test_identity")))
+            .with_matrix_life_cycle(mesh.get_life_cycle())
+            .with_matrix_sender(mesh.get_sender())
+            .with_transport(transport)
+            .start()
+            .expect("Unable to start Connection Manager");
+
+        let connector = cm.connector();
+
+        connector
+            .request_connection("inproc://test", "test_id")
+            .expect("A connection could not be created");
+
+        let envelope = mesh.recv().unwrap();
+        let message: NetworkMessage = protobuf::parse_from_bytes(envelope.payload()).unwrap();
+        let heartbeat: NetworkHeartbeat =
+            protobuf::parse_from_bytes(message.get_payload()).unwrap();
+        assert!(!heartbeat.get_reply());
+
+        connector
+            .heartbeat_reply_received("test_identity", heartbeat.get_heartbeat_id())
+            .expect("Unable to report heartbeat reply");
+
+        let stats = connector
+            .connection_stats()
+            .expect("Unable to get connection stats");
+        assert_eq!(1, stats.len());
+
+        let stats = &stats[0];
+        assert_eq!("inproc://test", stats.endpoint);
+        assert_eq!("test_id", stats.connection_id);
+        assert_eq!("test_identity", stats.identity);
+        assert_eq!(ConnectionDirection::Outbound, stats.direction);
+        assert_eq!(ConnectionState::Connected, stats.state);
+        assert!(stats.messages_sent >= 1);
+        assert!(stats.bytes_sent >= envelope.payload().len() as u64);
+        assert!(stats.last_heartbeat.is_some());
+        assert!(stats.round_trip_time.is_some());
+        assert_eq!(0, stats.reconnections);
+
+        cm.shutdown_signaler().shutdown();
+        cm.await_shutdown();
+    }
+
     /// Test that heartbeats are correctly sent to tcp connections
     #[test]
     fn test_heartbeat_raw_tcp() {
@@ -1309,6 +1632,11 @@ mod tests {
             }
         );
 
+        let stats = connector
+            .connection_stats()
+            .expect("Unable to get connection stats");
+        assert_eq!(1, stats[0].reconnections);
+
         tx.send(()).expect("Could not send completion signal");
 
         cm.shutdown_signaler().shutdown();
@@ -1316,6 +1644,116 @@ mod tests {
         auth_mgr.shutdown_and_await();
     }
 
+    /// Test that a connection requested with a reconnection policy is abandoned when the policy
+    /// gives up:
+    ///
+    /// 1. Connect to a TCP listener with a policy allowing a single reconnection attempt
+    /// 2. Disconnect the connection and close the listener, so the attempt fails
+    /// 3. Verify that subscribers are notified of the disconnection and of the abandoned
+    ///    connection, and that the connection is removed
+    #[test]
+    fn test_reconnection_policy_abandons_connection() {
+        let mut transport = Box::new(TcpTransport::default());
+        let mut listener = transport
+            .listen("tcp://localhost:0")
+            .expect("Cannot listen for connections");
+        let endpoint = listener.endpoint();
+        let mesh1 = Mesh::new(512, 128);
+
+        let (tx, rx) = mpsc::channel();
+        let join_handle = thread::spawn(move || {
+            let mesh2 = Mesh::new(512, 128);
+            let conn = listener.accept().expect("Cannot accept connection");
+            mesh2
+                .add(conn, "test_id".to_string())
+                .expect("Cannot add connection to mesh");
+
+            negotiation_connection_auth(&mesh2, "test_id", "some-peer");
+
+            // wait until the connection has been authorized
+            rx.recv().expect("Did not receive connected signal");
+
+            // close the connection and the listener, so that reconnecting fails
+            drop(listener);
+            let mut connection = mesh2
+                .remove(&"test_id".to_string())
+                .expect("Cannot remove connection from mesh");
+            connection
+                .disconnect()
+                .expect("Connection failed to disconnect");
+
+            mesh2.shutdown_signaler().shutdown();
+        });
+
+        let auth_mgr = AuthorizationManager::new("test_identity".into())
+            .expect("Unable to create authorization pool");
+        let cm = ConnectionManager::builder()
+            .with_authorizer(Box::new(auth_mgr.authorization_connector()))
+            .with_matrix_life_cycle(mesh1.get_life_cycle())
+            .with_matrix_sender(mesh1.get_sender())
+            .with_transport(transport)
+            .with_heartbeat_interval(1)
+            .start()
+            .expect("Unable to start Connection Manager");
+        let connector = cm.connector();
+
+        let (sub_tx, sub_rx) = mpsc::channel();
+        connector.subscribe(sub_tx).expect("Cannot subscribe");
+
+        connector
+            .request_connection_with_policy(
+                &endpoint,
+                "test_id",
+                Box::new(reconnection::MaxAttempts::new(
+                    1,
+                    reconnection::FixedInterval::new(Duration::from_secs(1)),
+                )),
+            )
+            .expect("A connection could not be created");
+
+        let notification: ConnectionManagerNotification =
+            sub_rx.recv().expect("Cannot receive notification");
+        assert_eq!(
+            notification,
+            ConnectionManagerNotification::Connected {
+                endpoint: endpoint.clone(),
+                connection_id: "test_id".to_string(),
+                identity: "some-peer".to_string()
+            }
+        );
+
+        tx.send(()).expect("Could not send connected signal");
+        join_handle.join().expect("Listener thread panicked");
+
+        let notification = sub_rx.recv().expect("Cannot receive notification");
+        assert_eq!(
+            notification,
+            ConnectionManagerNotification::Disconnected {
+                endpoint: endpoint.clone(),
+                identity: "some-peer".to_string()
+            }
+        );
+
+        let notification = sub_rx.recv().expect("Cannot receive notification");
+        assert_eq!(
+            notification,
+            ConnectionManagerNotification::ConnectionAbandoned {
+                endpoint: endpoint.clone(),
+                attempts: 1,
+                identity: "some-peer".to_string()
+            }
+        );
+
+        assert!(connector
+            .list_connections()
+            .expect("Unable to list connections")
+            .is_empty());
+
+        cm.shutdown_signaler().shutdown();
+        cm.await_shutdown();
+        auth_mgr.shutdown_and_await();
+    }
+
     /// Test that an inbound connection may be added to the connection manager
     /// This test does the following:
     /// 1. Add an inbound connection to a connection manager
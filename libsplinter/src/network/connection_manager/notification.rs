@@ -40,4 +40,10 @@ pub enum ConnectionManagerNotification {
         attempts: u64,
         identity: String,
     },
+    /// The connection's reconnection policy gave up reconnecting it, and it has been removed.
+    ConnectionAbandoned {
+        endpoint: String,
+        attempts: u64,
+        identity: String,
+    },
 }
@@ -0,0 +1,236 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Policies deciding how the connection manager reconnects lost outbound connections.
+
+use std::fmt;
+use std::time::Duration;
+
+use rand::Rng;
+
+/// Decides when a lost outbound connection is retried, and when it is abandoned.
+///
+/// The first reconnection attempt is made as soon as the connection is found to be lost.  After
+/// each failed attempt, the policy is asked for the delay before the next one.  The connection
+/// manager checks its connections on every heartbeat, so delays are rounded up to the heartbeat
+/// interval.
+pub trait ReconnectionPolicy: fmt::Debug + Send + Sync {
+    /// Returns the delay before the next reconnection attempt, or `None` if the connection should
+    /// be abandoned.
+    ///
+    /// `attempts` is the number of failed reconnection attempts so far, and `elapsed` is the time
+    /// since the first of them.
+    fn next_delay(&self, attempts: u64, elapsed: Duration) -> Option<Duration>;
+}
+
+/// Waits an exponentially growing delay between attempts, never abandoning the connection.
+///
+/// The delay after the first failed attempt is the initial delay, and is doubled after each
+/// further failed attempt, up to the maximum delay.
+#[derive(Clone, Debug)]
+pub struct ExponentialBackoff {
+    initial: Duration,
+    maximum: Duration,
+    jitter: f64,
+}
+
+impl ExponentialBackoff {
+    /// Constructs a new `ExponentialBackoff` without jitter.
+    pub fn new(initial: Duration, maximum: Duration) -> Self {
+        Self {
+            initial,
+            maximum,
+            jitter: 0.0,
+        }
+    }
+
+    /// Shorten each delay by a random fraction of itself, up to the given fraction between 0.0
+    /// and 1.0.
+    ///
+    /// Jitter spreads out the attempts of nodes that lost their connections at the same time.
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl ReconnectionPolicy for ExponentialBackoff {
+    fn next_delay(&self, attempts: u64, _elapsed: Duration) -> Option<Duration> {
+        let exponent = attempts.saturating_sub(1).min(u64::from(u32::MAX)) as u32;
+        let delay = 2u32
+            .checked_pow(exponent)
+            .and_then(|factor| self.initial.checked_mul(factor))
+            .map(|delay| delay.min(self.maximum))
+            .unwrap_or(self.maximum);
+
+        if self.jitter > 0.0 {
+            let fraction = rand::thread_rng().gen_range(0.0, self.jitter);
+            Some(delay.mul_f64(1.0 - fraction))
+        } else {
+            Some(delay)
+        }
+    }
+}
+
+/// Waits the same delay between attempts, never abandoning the connection.
+#[derive(Clone, Debug)]
+pub struct FixedInterval {
+    interval: Duration,
+}
+
+impl FixedInterval {
+    /// Constructs a new `FixedInterval` that waits the given interval between attempts.
+    pub fn new(interval: Duration) -> Self {
+        Self { interval }
+    }
+}
+
+impl ReconnectionPolicy for FixedInterval {
+    fn next_delay(&self, _attempts: u64, _elapsed: Duration) -> Option<Duration> {
+        Some(self.interval)
+    }
+}
+
+/// Abandons the connection after a number of failed attempts, waiting the delays of another
+/// policy until then.
+#[derive(Clone, Debug)]
+pub struct MaxAttempts<P> {
+    max_attempts: u64,
+    policy: P,
+}
+
+impl<P: ReconnectionPolicy> MaxAttempts<P> {
+    /// Constructs a new `MaxAttempts` that abandons the connection after `max_attempts` failed
+    /// attempts.
+    pub fn new(max_attempts: u64, policy: P) -> Self {
+        Self {
+            max_attempts,
+            policy,
+        }
+    }
+}
+
+impl<P: ReconnectionPolicy> ReconnectionPolicy for MaxAttempts<P> {
+    fn next_delay(&self, attempts: u64, elapsed: Duration) -> Option<Duration> {
+        if attempts >= self.max_attempts {
+            None
+        } else {
+            self.policy.next_delay(attempts, elapsed)
+        }
+    }
+}
+
+/// Abandons the connection once it has been reconnecting for longer than a deadline, waiting the
+/// delays of another policy until then.
+#[derive(Clone, Debug)]
+pub struct Deadline<P> {
+    deadline: Duration,
+    policy: P,
+}
+
+impl<P: ReconnectionPolicy> Deadline<P> {
+    /// Constructs a new `Deadline` that abandons the connection after reconnecting for the given
+    /// duration.
+    pub fn new(deadline: Duration, policy: P) -> Self {
+        Self { deadline, policy }
+    }
+}
+
+impl<P: ReconnectionPolicy> ReconnectionPolicy for Deadline<P> {
+    fn next_delay(&self, attempts: u64, elapsed: Duration) -> Option<Duration> {
+        if elapsed >= self.deadline {
+            None
+        } else {
+            self.policy.next_delay(attempts, elapsed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the exponential backoff doubles its delay up to the maximum, and that jitter
+    /// only shortens the delay.
+    #[test]
+    fn test_exponential_backoff() {
+        let policy = ExponentialBackoff::new(Duration::from_secs(10), Duration::from_secs(60));
+        let delays: Vec<Option<Duration>> = (1..=5)
+            .map(|attempts| policy.next_delay(attempts, Duration::from_secs(0)))
+            .collect();
+        assert_eq!(
+            vec![
+                Some(Duration::from_secs(10)),
+                Some(Duration::from_secs(20)),
+                Some(Duration::from_secs(40)),
+                Some(Duration::from_secs(60)),
+                Some(Duration::from_secs(60)),
+            ],
+            delays
+        );
+
+        assert_eq!(
+            Some(Duration::from_secs(60)),
+            policy.next_delay(u64::MAX, Duration::from_secs(0))
+        );
+
+        let policy = policy.with_jitter(0.5);
+        for attempts in 1..=5 {
+            let delay = policy
+                .next_delay(attempts, Duration::from_secs(0))
+                .expect("Jittered policy abandoned the connection");
+            assert!(delay <= Duration::from_secs(60));
+            assert!(delay >= Duration::from_secs(5));
+        }
+    }
+
+    /// Test that a fixed interval always waits the same delay.
+    #[test]
+    fn test_fixed_interval() {
+        let policy = FixedInterval::new(Duration::from_secs(5));
+        assert_eq!(
+            Some(Duration::from_secs(5)),
+            policy.next_delay(1, Duration::from_secs(0))
+        );
+        assert_eq!(
+            Some(Duration::from_secs(5)),
+            policy.next_delay(100, Duration::from_secs(3600))
+        );
+    }
+
+    /// Test that the connection is abandoned after the maximum number of attempts.
+    #[test]
+    fn test_max_attempts() {
+        let policy = MaxAttempts::new(3, FixedInterval::new(Duration::from_secs(5)));
+        assert_eq!(
+            Some(Duration::from_secs(5)),
+            policy.next_delay(2, Duration::from_secs(0))
+        );
+        assert_eq!(None, policy.next_delay(3, Duration::from_secs(0)));
+    }
+
+    /// Test that the connection is abandoned once the deadline has passed.
+    #[test]
+    fn test_deadline() {
+        let policy = Deadline::new(
+            Duration::from_secs(60),
+            FixedInterval::new(Duration::from_secs(5)),
+        );
+        assert_eq!(
+            Some(Duration::from_secs(5)),
+            policy.next_delay(10, Duration::from_secs(59))
+        );
+        assert_eq!(None, policy.next_delay(1, Duration::from_secs(60)));
+    }
+}
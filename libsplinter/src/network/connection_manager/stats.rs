@@ -0,0 +1,58 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::{Duration, SystemTime};
+
+/// Whether a connection was requested by the local node or accepted from a remote node
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConnectionDirection {
+    Inbound,
+    Outbound,
+}
+
+/// The current state of a connection
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConnectionState {
+    Connected,
+    /// An outbound connection was lost and is being reconnected
+    Reconnecting,
+    /// An inbound connection was lost; it will be removed or replaced by the remote node
+    Disconnected,
+}
+
+/// Traffic and health statistics of a connection managed by the connection manager
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConnectionStats {
+    pub endpoint: String,
+    pub connection_id: String,
+    pub identity: String,
+    pub direction: ConnectionDirection,
+    pub state: ConnectionState,
+    /// The number of messages sent over the connection
+    pub messages_sent: u64,
+    /// The number of payload bytes sent over the connection
+    pub bytes_sent: u64,
+    /// The number of messages received from the connection
+    pub messages_received: u64,
+    /// The number of payload bytes received from the connection
+    pub bytes_received: u64,
+    /// The number of messages waiting to be sent over the connection
+    pub queue_depth: usize,
+    /// When the last heartbeat was sent over the connection
+    pub last_heartbeat: Option<SystemTime>,
+    /// The number of times the connection has been reconnected
+    pub reconnections: u64,
+    /// The round trip time of the last heartbeat that the remote node replied to
+    pub round_trip_time: Option<Duration>,
+}
@@ -12,14 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::network::connection_manager::Connector;
 use crate::network::dispatch::{DispatchError, Handler, MessageContext, MessageSender, PeerId};
+use crate::network::ping::NetworkPinger;
 use crate::protos::network::{NetworkEcho, NetworkHeartbeat, NetworkMessage, NetworkMessageType};
 
 use protobuf::Message;
 
 // Implements a handler that handles NetworkEcho Messages
+//
+// Echoes for the local node are sent back to their source as replies. Replies are passed to the
+// pinger, if one is set, and are not forwarded.
 pub struct NetworkEchoHandler {
     node_id: String,
+    pinger: Option<NetworkPinger>,
 }
 
 impl Handler for NetworkEchoHandler {
@@ -39,10 +45,18 @@ impl Handler for NetworkEchoHandler {
     ) -> Result<(), DispatchError> {
         debug!("ECHO: {:?}", msg);
 
+        if msg.get_reply() {
+            if let Some(pinger) = &self.pinger {
+                pinger.reply_received(context.source_peer_id(), msg.get_echo_id());
+            }
+            return Ok(());
+        }
+
         let recipient = {
             // if the recipient is us forward back to sender else forward on to the intended
             // recipient
             if msg.get_recipient() == self.node_id {
+                msg.set_reply(true);
                 context.source_peer_id().to_string()
             } else {
                 msg.get_recipient().to_string()
@@ -72,13 +86,27 @@ impl Handler for NetworkEchoHandler {
 
 impl NetworkEchoHandler {
     pub fn new(node_id: String) -> Self {
-        NetworkEchoHandler { node_id }
+        NetworkEchoHandler {
+            node_id,
+            pinger: None,
+        }
+    }
+
+    /// Pass replies to echoes to the given pinger.
+    pub fn with_pinger(mut self, pinger: NetworkPinger) -> Self {
+        self.pinger = Some(pinger);
+        self
     }
 }
 
 // Implements a handler that handles NetworkHeartbeat Messages
+//
+// Heartbeats that expect a reply are answered. Replies to the local node's heartbeats are
+// reported to the connection manager, if one is set, to measure the round trip time.
 #[derive(Default)]
-pub struct NetworkHeartbeatHandler {}
+pub struct NetworkHeartbeatHandler {
+    connector: Option<Connector>,
+}
 
 impl Handler for NetworkHeartbeatHandler {
     type Source = PeerId;
@@ -91,18 +119,60 @@ impl Handler for NetworkHeartbeatHandler {
 
     fn handle(
         &self,
-        _msg: Self::Message,
+        msg: Self::Message,
         context: &MessageContext<Self::Source, Self::MessageType>,
-        _sender: &dyn MessageSender<Self::Source>,
+        sender: &dyn MessageSender<Self::Source>,
     ) -> Result<(), DispatchError> {
         trace!("Received Heartbeat from {}", context.source_peer_id());
-        Ok(())
+
+        if msg.get_reply() {
+            if let Some(connector) = &self.connector {
+                if let Err(err) = connector
+                    .heartbeat_reply_received(context.source_peer_id(), msg.get_heartbeat_id())
+                {
+                    warn!("Unable to record heartbeat reply: {}", err);
+                }
+            }
+            return Ok(());
+        }
+
+        if msg.get_heartbeat_id() == 0 {
+            return Ok(());
+        }
+
+        let mut reply = NetworkHeartbeat::new();
+        reply.set_heartbeat_id(msg.get_heartbeat_id());
+        reply.set_reply(true);
+
+        let mut network_msg = NetworkMessage::new();
+        network_msg.set_message_type(NetworkMessageType::NETWORK_HEARTBEAT);
+        network_msg.set_payload(
+            reply
+                .write_to_bytes()
+                .map_err(|err| DispatchError::SerializationError(err.to_string()))?,
+        );
+        let network_msg_bytes = network_msg
+            .write_to_bytes()
+            .map_err(|err| DispatchError::SerializationError(err.to_string()))?;
+
+        sender
+            .send(context.source_peer_id().into(), network_msg_bytes)
+            .map_err(|(recipient, payload)| {
+                DispatchError::NetworkSendError((recipient.into(), payload))
+            })
     }
 }
 
 impl NetworkHeartbeatHandler {
     pub fn new() -> Self {
-        NetworkHeartbeatHandler {}
+        NetworkHeartbeatHandler { connector: None }
+    }
+
+    /// Report replies to heartbeats to the connection manager of the given connector.
+    pub fn with_connector(connector: Connector) -> Self {
+        NetworkHeartbeatHandler {
+            connector: Some(connector),
+        }
     }
 }
 
@@ -112,9 +182,11 @@ mod tests {
 
     use std::collections::VecDeque;
     use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
 
     use crate::network::dispatch::Dispatcher;
-    use crate::protos::network::{NetworkEcho, NetworkMessageType};
+    use crate::protos::network::{NetworkEcho, NetworkHeartbeat, NetworkMessageType};
 
     #[test]
     fn dispatch_to_handler() {
@@ -155,6 +227,93 @@ mod tests {
         assert_eq!(echo.get_recipient(), "TestPeer");
         assert_eq!(echo.get_time_to_live(), 2);
         assert_eq!(echo.get_payload().to_vec(), b"HelloWorld".to_vec());
+        assert!(echo.get_reply());
+    }
+
+    // Test that a reply to an echo is passed to the pinger and is not sent on.
+    #[test]
+    fn echo_reply_to_pinger() {
+        let network_sender = MockSender::new();
+        let pinger = NetworkPinger::new(Box::new(network_sender.clone()));
+        let mut dispatcher: Dispatcher<NetworkMessageType> =
+            Dispatcher::new(Box::new(network_sender.clone()));
+        dispatcher.set_handler(Box::new(
+            NetworkEchoHandler::new("TestPeer".to_string()).with_pinger(pinger.clone()),
+        ));
+
+        let ping_handle = thread::spawn(move || pinger.ping("OTHER_PEER", Duration::from_secs(10)));
+
+        // wait for the pinger's echo to be sent
+        let (recipient, network_message) = loop {
+            if let Some(outbound) = network_sender.next_outbound() {
+                break outbound;
+            }
+            thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(recipient, "OTHER_PEER".into());
+        let network_msg: NetworkMessage = protobuf::parse_from_bytes(&network_message).unwrap();
+        let mut echo: NetworkEcho = protobuf::parse_from_bytes(network_msg.get_payload()).unwrap();
+
+        echo.set_reply(true);
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                "OTHER_PEER".into(),
+                &NetworkMessageType::NETWORK_ECHO,
+                echo.write_to_bytes().unwrap()
+            )
+        );
+
+        assert!(ping_handle.join().unwrap().is_ok());
+        assert!(network_sender.next_outbound().is_none());
+    }
+
+    // Test that a heartbeat expecting a reply is answered with a reply carrying the same id, and
+    // that heartbeats without an id and replies are not answered.
+    #[test]
+    fn heartbeat_reply() {
+        let network_sender = MockSender::new();
+        let mut dispatcher: Dispatcher<NetworkMessageType> =
+            Dispatcher::new(Box::new(network_sender.clone()));
+        dispatcher.set_handler(Box::new(NetworkHeartbeatHandler::new()));
+
+        let mut heartbeat = NetworkHeartbeat::new();
+        heartbeat.set_heartbeat_id(7);
+        assert_eq!(
+            Ok(()),
+            dispatcher.dispatch(
+                "OTHER_PEER".into(),
+                &NetworkMessageType::NETWORK_HEARTBEAT,
+                heartbeat.write_to_bytes().unwrap()
+            )
+        );
+
+        let (recipient, network_message) = network_sender
+            .next_outbound()
+            .expect("Unable to get expected message");
+        assert_eq!(recipient, "OTHER_PEER".into());
+
+        let network_msg: NetworkMessage = protobuf::parse_from_bytes(&network_message).unwrap();
+        assert_eq!(
+            network_msg.get_message_type(),
+            NetworkMessageType::NETWORK_HEARTBEAT
+        );
+        let reply: NetworkHeartbeat =
+            protobuf::parse_from_bytes(network_msg.get_payload()).unwrap();
+        assert_eq!(reply.get_heartbeat_id(), 7);
+        assert!(reply.get_reply());
+
+        for heartbeat in vec![NetworkHeartbeat::new(), reply] {
+            assert_eq!(
+                Ok(()),
+                dispatcher.dispatch(
+                    "OTHER_PEER".into(),
+                    &NetworkMessageType::NETWORK_HEARTBEAT,
+                    heartbeat.write_to_bytes().unwrap()
+                )
+            );
+        }
+        assert!(network_sender.next_outbound().is_none());
     }
 
     #[derive(Clone)]
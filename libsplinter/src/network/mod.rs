@@ -16,4 +16,7 @@ pub mod auth;
 pub mod connection_manager;
 pub mod dispatch;
 pub mod handlers;
+pub mod ping;
 pub(crate) mod reply;
+#[cfg(all(feature = "network-rest-api", feature = "rest-api"))]
+pub mod rest_api;
@@ -0,0 +1,213 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Measures the round trip time to peers using `NetworkEcho` messages.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use protobuf::Message;
+
+use crate::network::dispatch::{MessageSender, PeerId};
+use crate::protos::network::{NetworkEcho, NetworkMessage, NetworkMessageType};
+
+// The echo only needs to reach the peer and come back
+const ECHO_TIME_TO_LIVE: i32 = 2;
+
+type PendingEchoes = HashMap<(String, u64), Sender<Instant>>;
+
+/// Sends `NetworkEcho` messages to peers and waits for their replies.
+///
+/// Replies are passed to the pinger by the `NetworkEchoHandler` it is given to with
+/// `NetworkEchoHandler::with_pinger`.
+#[derive(Clone)]
+pub struct NetworkPinger {
+    sender: Arc<Mutex<Box<dyn MessageSender<PeerId>>>>,
+    pending: Arc<Mutex<PendingEchoes>>,
+    next_echo_id: Arc<AtomicU64>,
+}
+
+impl NetworkPinger {
+    /// Creates a pinger that sends echoes with the given network message sender.
+    pub fn new(sender: Box<dyn MessageSender<PeerId>>) -> Self {
+        NetworkPinger {
+            sender: Arc::new(Mutex::new(sender)),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            next_echo_id: Arc::new(AtomicU64::new(1)),
+        }
+    }
+
+    /// Sends an echo to the given peer and returns the time until its reply was received.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PingError::SendError` if the echo could not be sent, which usually means the peer
+    /// is not connected, and `PingError::Timeout` if no reply was received within the timeout.
+    pub fn ping(&self, peer_id: &str, timeout: Duration) -> Result<Duration, PingError> {
+        let echo_id = self.next_echo_id.fetch_add(1, Ordering::SeqCst);
+        let key = (peer_id.to_string(), echo_id);
+
+        let mut echo = NetworkEcho::new();
+        echo.set_recipient(peer_id.to_string());
+        echo.set_time_to_live(ECHO_TIME_TO_LIVE);
+        echo.set_echo_id(echo_id);
+
+        let mut network_msg = NetworkMessage::new();
+        network_msg.set_message_type(NetworkMessageType::NETWORK_ECHO);
+        network_msg.set_payload(
+            echo.write_to_bytes()
+                .map_err(|err| PingError::InternalError(err.to_string()))?,
+        );
+        let network_msg_bytes = network_msg
+            .write_to_bytes()
+            .map_err(|err| PingError::InternalError(err.to_string()))?;
+
+        let (reply_sender, reply_receiver) = channel();
+        self.pending
+            .lock()
+            .map_err(|_| PingError::InternalError("pending echoes lock poisoned".into()))?
+            .insert(key.clone(), reply_sender);
+
+        let sent_at = Instant::now();
+        let send_result = self
+            .sender
+            .lock()
+            .map_err(|_| PingError::InternalError("network sender lock poisoned".into()))?
+            .send(peer_id.into(), network_msg_bytes);
+
+        let result = match send_result {
+            Ok(()) => match reply_receiver.recv_timeout(timeout) {
+                Ok(received_at) => Ok(received_at.saturating_duration_since(sent_at)),
+                Err(RecvTimeoutError::Timeout) => Err(PingError::Timeout),
+                Err(RecvTimeoutError::Disconnected) => {
+                    Err(PingError::InternalError("reply sender was dropped".into()))
+                }
+            },
+            Err(_) => Err(PingError::SendError(format!(
+                "unable to send echo to {}",
+                peer_id
+            ))),
+        };
+
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&key);
+        }
+
+        result
+    }
+
+    /// Records that the reply to the given echo was received from the given peer. Replies to
+    /// echoes that were not sent by this pinger, or that have timed out, are ignored.
+    pub(crate) fn reply_received(&self, peer_id: &str, echo_id: u64) {
+        let received_at = Instant::now();
+        match self.pending.lock() {
+            Ok(mut pending) => {
+                if let Some(sender) = pending.remove(&(peer_id.to_string(), echo_id)) {
+                    let _ = sender.send(received_at);
+                }
+            }
+            Err(_) => error!("Unable to record echo reply: pending echoes lock poisoned"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum PingError {
+    /// The echo could not be sent to the peer
+    SendError(String),
+    /// No reply was received before the timeout
+    Timeout,
+    InternalError(String),
+}
+
+impl Error for PingError {}
+
+impl fmt::Display for PingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PingError::SendError(msg) => write!(f, "{}", msg),
+            PingError::Timeout => write!(f, "timed out waiting for echo reply"),
+            PingError::InternalError(msg) => write!(f, "internal error: {}", msg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::thread;
+
+    /// Test that a ping returns once the reply to its echo is received, and that replies from
+    /// another peer or to another echo are ignored.
+    #[test]
+    fn test_ping_reply() {
+        let (echo_sender, echo_receiver) = channel();
+        let pinger = NetworkPinger::new(Box::new(ChannelSender(Mutex::new(echo_sender))));
+
+        let replying_pinger = pinger.clone();
+        let join_handle = thread::spawn(move || {
+            let (peer_id, bytes): (PeerId, Vec<u8>) =
+                echo_receiver.recv().expect("Unable to receive echo");
+            let network_msg: NetworkMessage =
+                protobuf::parse_from_bytes(&bytes).expect("Unable to parse network message");
+            assert_eq!(
+                network_msg.get_message_type(),
+                NetworkMessageType::NETWORK_ECHO
+            );
+            let echo: NetworkEcho = protobuf::parse_from_bytes(network_msg.get_payload())
+                .expect("Unable to parse echo");
+            assert_eq!(echo.get_recipient(), "node_b");
+            assert!(!echo.get_reply());
+
+            replying_pinger.reply_received("node_c", echo.get_echo_id());
+            replying_pinger.reply_received(&peer_id, echo.get_echo_id() + 1);
+            replying_pinger.reply_received(&peer_id, echo.get_echo_id());
+        });
+
+        pinger
+            .ping("node_b", Duration::from_secs(10))
+            .expect("Ping failed");
+        join_handle.join().expect("Reply thread panicked");
+    }
+
+    /// Test that a ping that is not answered times out.
+    #[test]
+    fn test_ping_timeout() {
+        let (echo_sender, _echo_receiver) = channel();
+        let pinger = NetworkPinger::new(Box::new(ChannelSender(Mutex::new(echo_sender))));
+
+        assert_eq!(
+            Err(PingError::Timeout),
+            pinger.ping("node_b", Duration::from_millis(10))
+        );
+    }
+
+    struct ChannelSender(Mutex<Sender<(PeerId, Vec<u8>)>>);
+
+    impl MessageSender<PeerId> for ChannelSender {
+        fn send(&self, id: PeerId, message: Vec<u8>) -> Result<(), (PeerId, Vec<u8>)> {
+            self.0
+                .lock()
+                .expect("lock was poisoned")
+                .send((id, message))
+                .map_err(|err| err.0)
+        }
+    }
+}
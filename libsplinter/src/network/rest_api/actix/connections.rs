@@ -0,0 +1,62 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoints:
+//!
+//! * `GET /network/connections` for listing the node's connections and their statistics
+
+use std::sync::{Arc, Mutex};
+
+use crate::actix_web::{web, Error, HttpResponse};
+use crate::futures::Future;
+use crate::network::connection_manager::{ConnectionManagerError, Connector};
+use crate::network::rest_api::resources::connections::{
+    ConnectionResponse, ListConnectionsResponse,
+};
+use crate::protocol;
+use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
+
+pub fn make_connections_resource(connector: Arc<Mutex<Connector>>) -> Resource {
+    Resource::build("/network/connections")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::NETWORK_LIST_CONNECTIONS_MIN,
+            protocol::NETWORK_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |_, _| list_connections(connector.clone()))
+}
+
+fn list_connections(
+    connector: Arc<Mutex<Connector>>,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    Box::new(
+        web::block(move || {
+            let connector = connector
+                .lock()
+                .map_err(|_| ConnectionManagerError::StatePoisoned)?
+                .clone();
+            connector.connection_stats()
+        })
+        .then(|res| {
+            Ok(match res {
+                Ok(stats) => HttpResponse::Ok().json(ListConnectionsResponse {
+                    data: stats.iter().map(ConnectionResponse::from).collect(),
+                }),
+                Err(err) => {
+                    error!("Unable to list connections: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                }
+            })
+        }),
+    )
+}
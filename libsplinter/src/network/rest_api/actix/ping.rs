@@ -0,0 +1,74 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module provides the following endpoints:
+//!
+//! * `GET /network/ping/{node_id}` for measuring the round trip time to a connected node
+
+use std::time::Duration;
+
+use crate::actix_web::{error::BlockingError, web, Error, HttpRequest, HttpResponse};
+use crate::futures::Future;
+use crate::network::ping::{NetworkPinger, PingError};
+use crate::network::rest_api::resources::ping::PingResponse;
+use crate::protocol;
+use crate::rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard, Resource};
+
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn make_ping_resource(pinger: NetworkPinger) -> Resource {
+    Resource::build("/network/ping/{node_id}")
+        .add_request_guard(ProtocolVersionRangeGuard::new(
+            protocol::NETWORK_PING_MIN,
+            protocol::NETWORK_PROTOCOL_VERSION,
+        ))
+        .add_method(Method::Get, move |r, _| ping(r, pinger.clone()))
+}
+
+fn ping(
+    request: HttpRequest,
+    pinger: NetworkPinger,
+) -> Box<dyn Future<Item = HttpResponse, Error = Error>> {
+    let node_id = request
+        .match_info()
+        .get("node_id")
+        .unwrap_or("")
+        .to_string();
+    Box::new(
+        web::block(move || {
+            pinger
+                .ping(&node_id, PING_TIMEOUT)
+                .map(|round_trip_time| (node_id, round_trip_time))
+        })
+        .then(|res| {
+            Ok(match res {
+                Ok((node_id, round_trip_time)) => HttpResponse::Ok().json(PingResponse {
+                    node_id,
+                    round_trip_time_ms: round_trip_time.as_secs_f64() * 1000.0,
+                }),
+                Err(BlockingError::Error(PingError::SendError(msg))) => {
+                    HttpResponse::NotFound().json(ErrorResponse::not_found(&msg))
+                }
+                Err(BlockingError::Error(PingError::Timeout)) => HttpResponse::GatewayTimeout()
+                    .json(ErrorResponse::request_timeout(
+                        "Timed out waiting for the node to reply",
+                    )),
+                Err(err) => {
+                    error!("Unable to ping node: {}", err);
+                    HttpResponse::InternalServerError().json(ErrorResponse::internal_error())
+                }
+            })
+        }),
+    )
+}
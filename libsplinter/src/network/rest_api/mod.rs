@@ -0,0 +1,82 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module defines the REST API endpoints for inspecting the node's network connections.
+
+#[cfg(feature = "rest-api-actix")]
+mod actix;
+mod resources;
+
+use std::sync::{Arc, Mutex};
+
+use crate::rest_api::{Resource, RestResourceProvider};
+
+use super::connection_manager::Connector;
+use super::ping::NetworkPinger;
+
+/// Provides the REST API [Resource](splinter::rest_api::Resource) definitions for inspecting the
+/// connections of the splinter node.
+///
+/// The following endpoints are provided:
+///
+/// * `GET /network/connections` - List the connections of the node with their traffic and health
+///   statistics
+/// * `GET /network/ping/{node_id}` - Measure the round trip time to a connected node; only
+///   provided if a pinger was set with `with_pinger`
+///
+/// These endpoints are only available if the following REST API backend feature is enabled:
+///
+/// * `rest-api-actix`
+#[derive(Clone)]
+pub struct NetworkResourceProvider {
+    connector: Arc<Mutex<Connector>>,
+    pinger: Option<NetworkPinger>,
+}
+
+impl NetworkResourceProvider {
+    pub fn new(connector: Connector) -> Self {
+        Self {
+            connector: Arc::new(Mutex::new(connector)),
+            pinger: None,
+        }
+    }
+
+    /// Provide the ping endpoint, which sends echoes with the given pinger.
+    pub fn with_pinger(mut self, pinger: NetworkPinger) -> Self {
+        self.pinger = Some(pinger);
+        self
+    }
+}
+
+impl RestResourceProvider for NetworkResourceProvider {
+    fn resources(&self) -> Vec<Resource> {
+        // Allowing unused_mut because resources must be mutable if feature rest-api-actix is
+        // enabled
+        #[allow(unused_mut)]
+        let mut resources = Vec::new();
+
+        #[cfg(feature = "rest-api-actix")]
+        {
+            resources.append(&mut vec![actix::connections::make_connections_resource(
+                self.connector.clone(),
+            )]);
+
+            if let Some(pinger) = &self.pinger {
+                resources.push(actix::ping::make_ping_resource(pinger.clone()));
+            }
+        }
+
+        resources
+    }
+}
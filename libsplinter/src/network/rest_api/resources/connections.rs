@@ -0,0 +1,72 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::UNIX_EPOCH;
+
+use crate::network::connection_manager::{ConnectionDirection, ConnectionState, ConnectionStats};
+
+#[derive(Debug, Serialize)]
+pub struct ListConnectionsResponse<'a> {
+    pub data: Vec<ConnectionResponse<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConnectionResponse<'a> {
+    pub endpoint: &'a str,
+    pub connection_id: &'a str,
+    pub identity: &'a str,
+    pub direction: &'static str,
+    pub state: &'static str,
+    pub messages_sent: u64,
+    pub bytes_sent: u64,
+    pub messages_received: u64,
+    pub bytes_received: u64,
+    pub queue_depth: usize,
+    /// Seconds since the Unix epoch
+    pub last_heartbeat: Option<u64>,
+    pub reconnections: u64,
+    pub round_trip_time_ms: Option<f64>,
+}
+
+impl<'a> From<&'a ConnectionStats> for ConnectionResponse<'a> {
+    fn from(stats: &'a ConnectionStats) -> Self {
+        Self {
+            endpoint: &stats.endpoint,
+            connection_id: &stats.connection_id,
+            identity: &stats.identity,
+            direction: match stats.direction {
+                ConnectionDirection::Inbound => "inbound",
+                ConnectionDirection::Outbound => "outbound",
+            },
+            state: match stats.state {
+                ConnectionState::Connected => "connected",
+                ConnectionState::Reconnecting => "reconnecting",
+                ConnectionState::Disconnected => "disconnected",
+            },
+            messages_sent: stats.messages_sent,
+            bytes_sent: stats.bytes_sent,
+            messages_received: stats.messages_received,
+            bytes_received: stats.bytes_received,
+            queue_depth: stats.queue_depth,
+            last_heartbeat: stats
+                .last_heartbeat
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_secs()),
+            reconnections: stats.reconnections,
+            round_trip_time_ms: stats
+                .round_trip_time
+                .map(|duration| duration.as_secs_f64() * 1000.0),
+        }
+    }
+}
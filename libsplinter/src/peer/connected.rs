@@ -0,0 +1,153 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A view of the connected peers that is kept up to date by `PeerManager` notifications.
+
+use std::collections::HashSet;
+use std::sync::mpsc::channel;
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use super::connector::PeerManagerConnector;
+use super::error::{PeerListError, PeerManagerError};
+use super::notification::PeerManagerNotification;
+
+/// Tracks which peers are connected, from the notifications of a `PeerManager`.
+///
+/// Unlike `PeerManagerConnector::list_connected_peers`, checking whether a peer is connected does
+/// not wait on the `PeerManager`, so it may be done for every message that is handled.
+#[derive(Clone)]
+pub struct ConnectedPeers {
+    peers: Arc<RwLock<HashSet<String>>>,
+}
+
+impl ConnectedPeers {
+    /// Starts tracking the peers that are connected to the `PeerManager` of the given connector.
+    ///
+    /// The peers are tracked by a background thread, which exits when the `PeerManager` stops.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `PeerManagerError` if the `PeerManager` cannot be subscribed to, or the current
+    /// connected peers cannot be listed.
+    pub fn track(peer_connector: &PeerManagerConnector) -> Result<Self, PeerManagerError> {
+        // Subscribe before listing the connected peers, so no change is missed in between
+        let (notification_sender, notification_receiver) = channel();
+        peer_connector.subscribe_sender::<PeerManagerNotification>(notification_sender)?;
+
+        let connected_peers = peer_connector
+            .list_connected_peers()
+            .map_err(|err| PeerManagerError::StartUpError(err.to_string()))?;
+
+        let peers: Arc<RwLock<HashSet<String>>> =
+            Arc::new(RwLock::new(connected_peers.into_iter().collect()));
+        let thread_peers = Arc::clone(&peers);
+        thread::Builder::new()
+            .name("ConnectedPeers".into())
+            .spawn(move || {
+                while let Ok(notification) = notification_receiver.recv() {
+                    let mut peers = match thread_peers.write() {
+                        Ok(peers) => peers,
+                        Err(_) => {
+                            error!("Connected peers lock poisoned; no longer tracking peers");
+                            break;
+                        }
+                    };
+
+                    match notification {
+                        PeerManagerNotification::Connected { peer } => {
+                            peers.insert(peer);
+                        }
+                        PeerManagerNotification::Disconnected { peer } => {
+                            peers.remove(&peer);
+                        }
+                    }
+                }
+            })
+            .map_err(|err| PeerManagerError::StartUpError(err.to_string()))?;
+
+        Ok(ConnectedPeers { peers })
+    }
+
+    /// Returns whether the given peer is connected.
+    pub fn is_connected(&self, peer_id: &str) -> Result<bool, PeerListError> {
+        Ok(self
+            .peers
+            .read()
+            .map_err(|_| PeerListError::InternalError("Connected peers lock poisoned".into()))?
+            .contains(peer_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc::{Receiver, Sender};
+    use std::time::{Duration, Instant};
+
+    use crate::peer::notification::Subscriber;
+    use crate::peer::{PeerManagerMessage, PeerManagerRequest};
+
+    /// Test that the connected peers start as the peers listed by the peer manager, and follow
+    /// its notifications afterwards.
+    #[test]
+    fn test_track_connected_peers() {
+        let (sender, receiver) = channel();
+        let peer_connector = PeerManagerConnector::new(sender);
+        let (subscriber_sender, subscriber_receiver) = channel();
+        thread::spawn(move || mock_peer_manager(receiver, subscriber_sender));
+
+        let connected_peers = ConnectedPeers::track(&peer_connector).expect("Unable to track");
+        assert!(connected_peers.is_connected("node_a").unwrap());
+        assert!(!connected_peers.is_connected("node_b").unwrap());
+
+        let subscriber = subscriber_receiver.recv().expect("No subscriber was added");
+        subscriber(PeerManagerNotification::Connected {
+            peer: "node_b".into(),
+        })
+        .unwrap();
+        subscriber(PeerManagerNotification::Disconnected {
+            peer: "node_a".into(),
+        })
+        .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while connected_peers.is_connected("node_a").unwrap() {
+            assert!(Instant::now() < deadline, "Notifications were not applied");
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(connected_peers.is_connected("node_b").unwrap());
+    }
+
+    /// Answers subscribe requests, handing the subscriber to the test, and reports "node_a" as
+    /// the only connected peer.
+    fn mock_peer_manager(
+        receiver: Receiver<PeerManagerMessage>,
+        subscriber_sender: Sender<Subscriber>,
+    ) {
+        while let Ok(message) = receiver.recv() {
+            match message {
+                PeerManagerMessage::Request(PeerManagerRequest::Subscribe { sender, callback }) => {
+                    subscriber_sender.send(callback).unwrap();
+                    sender.send(Ok(0)).unwrap();
+                }
+                PeerManagerMessage::Request(PeerManagerRequest::ListConnectedPeers { sender }) => {
+                    sender.send(Ok(vec!["node_a".into()])).unwrap();
+                }
+                _ => (),
+            }
+        }
+    }
+}
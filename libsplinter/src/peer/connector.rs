@@ -176,6 +176,28 @@ impl PeerManagerConnector {
             .map_err(|err| PeerListError::ReceiveError(format!("{:?}", err)))?
     }
 
+    /// Requests the list of peers that currently have a connection.
+    ///
+    /// Unlike `list_peers`, peers that are still being connected to or are reconnecting are not
+    /// included.
+    pub fn list_connected_peers(&self) -> Result<Vec<String>, PeerListError> {
+        let (sender, recv) = channel();
+        let message =
+            PeerManagerMessage::Request(PeerManagerRequest::ListConnectedPeers { sender });
+
+        match self.sender.send(message) {
+            Ok(()) => (),
+            Err(_) => {
+                return Err(PeerListError::InternalError(
+                    "Unable to send message to PeerManager, receiver dropped".to_string(),
+                ))
+            }
+        };
+
+        recv.recv()
+            .map_err(|err| PeerListError::ReceiveError(format!("{:?}", err)))?
+    }
+
     /// Requests the map of currently connected peers to connection IDs
     ///
     /// Returns a map of peer IDs to connection IDs
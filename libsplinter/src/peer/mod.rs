@@ -15,14 +15,16 @@
 //! Data structures and implementations for managing Splinter peers.
 //!
 //! The public interface includes the structs [`PeerManager`], [`PeerManagerConnector`],
-//! [`PeerInterconnect`] and the enum [`PeerManagerNotification`].
+//! [`ConnectedPeers`], [`PeerInterconnect`] and the enum [`PeerManagerNotification`].
 //!
 //! [`PeerManager`]: struct.PeerManager.html
 //! [`PeerManagerConnector`]: connector/struct.PeerManagerConnector.html
+//! [`ConnectedPeers`]: connected/struct.ConnectedPeers.html
 //! [`PeerInterconnect`]: interconnect/struct.PeerInterconnect.html
 //! [`PeerManagerNotification`]: notification/enum.PeerManagerNotification.html
 
 mod builder;
+mod connected;
 mod connector;
 mod error;
 pub mod interconnect;
@@ -45,6 +47,7 @@ use crate::network::connection_manager::{ConnectionManagerError, Connector};
 use crate::threading::pacemaker;
 
 pub use self::builder::PeerManagerBuilder;
+pub use self::connected::ConnectedPeers;
 pub use self::connector::PeerManagerConnector;
 use self::connector::PeerRemover;
 use self::error::{
@@ -102,6 +105,9 @@ pub(crate) enum PeerManagerRequest {
     ListUnreferencedPeers {
         sender: Sender<Result<Vec<String>, PeerListError>>,
     },
+    ListConnectedPeers {
+        sender: Sender<Result<Vec<String>, PeerListError>>,
+    },
     ConnectionIds {
         sender: Sender<Result<BiHashMap<String, String>, PeerConnectionIdError>>,
     },
@@ -506,6 +512,11 @@ fn handle_request(
                 warn!("Connector dropped before receiving result of list unreferenced peers");
             }
         }
+        PeerManagerRequest::ListConnectedPeers { sender } => {
+            if sender.send(Ok(peers.connected_peer_ids())).is_err() {
+                warn!("Connector dropped before receiving result of list connected peers");
+            }
+        }
         PeerManagerRequest::ConnectionIds { sender } => {
             if sender.send(Ok(peers.connection_ids())).is_err() {
                 warn!("Connector dropped before receiving result of connection IDs");
@@ -1006,6 +1017,15 @@ fn handle_notifications(
                 max_retry_attempts,
             )
         }
+        ConnectionManagerNotification::ConnectionAbandoned {
+            endpoint, attempts, ..
+        } => handle_fatal_connection(
+            endpoint,
+            format!("reconnection abandoned after {} attempts", attempts),
+            peers,
+            subscribers,
+            max_retry_attempts,
+        ),
     }
 }
 
@@ -83,6 +83,15 @@ impl PeerMap {
             .collect()
     }
 
+    /// Returns the IDs of the peers that are currently connected
+    pub fn connected_peer_ids(&self) -> Vec<String> {
+        self.peers
+            .iter()
+            .filter(|(_, metadata)| metadata.status == PeerStatus::Connected)
+            .map(|(_, metadata)| metadata.id.to_string())
+            .collect()
+    }
+
     /// Returns the current map of peer IDs to connection IDs
     pub fn connection_ids(&self) -> BiHashMap<String, String> {
         let mut peer_to_connection_id = BiHashMap::new();
@@ -244,6 +253,32 @@ pub mod tests {
         );
     }
 
+    // Test that connected_peer_ids() only returns the connected peers
+    //  1. Add a connected and a pending peer
+    //  2. Test that only the connected peer's id is returned
+    #[test]
+    fn test_get_connected_peer_ids() {
+        let mut peer_map = PeerMap::new(10);
+
+        peer_map.insert(
+            "test_peer".to_string(),
+            "connection_id_1".to_string(),
+            vec!["test_endpoint1".to_string()],
+            "test_endpoint1".to_string(),
+            PeerStatus::Connected,
+        );
+
+        peer_map.insert(
+            "next_peer".to_string(),
+            "connection_id_2".to_string(),
+            vec!["endpoint1".to_string()],
+            "endpoint1".to_string(),
+            PeerStatus::Pending,
+        );
+
+        assert_eq!(peer_map.connected_peer_ids(), vec!["test_peer".to_string()]);
+    }
+
     // Test that connection_ids() returns correctly
     //  1. Test that an empty peer_map returns an empty BiHashMap
     //  2. Add two peers and test that their ids are returned from connection_ids()
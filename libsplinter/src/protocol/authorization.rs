@@ -29,12 +29,20 @@ pub enum AuthorizationMessage {
     AuthorizationError(AuthorizationError),
 
     TrustRequest(TrustRequest),
+
+    ChallengeSubmitRequest(ChallengeSubmitRequest),
 }
 
 /// The possible types of authorization that may be computed during the handshake.
 #[derive(Debug)]
 pub enum AuthorizationType {
     Trust,
+    /// Challenge authorization, where the connecting node must sign the given nonce, along with
+    /// the challenger's identity and its own, with its node key.
+    Challenge {
+        nonce: Vec<u8>,
+        challenger: String,
+    },
 }
 
 /// A connection request message.
@@ -64,10 +72,22 @@ pub struct TrustRequest {
     pub identity: String,
 }
 
+/// A challenge submit request.
+///
+/// A challenge submit request is sent in response to a Connect Response that accepts challenge
+/// authorization. It carries the signature of the challenger's identity, the connecting node's
+/// identity and the nonce by the connecting node's key.
+#[derive(Debug)]
+pub struct ChallengeSubmitRequest {
+    pub identity: String,
+    pub public_key: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
 /// A successful authorization message.
 ///
-/// This message is returned after either a TrustResponse has been returned by the remote
-/// connection.
+/// This message is returned after either a TrustRequest or a ChallengeSubmitRequest has been
+/// returned by the remote connection.
 #[derive(Debug)]
 pub struct Authorized;
 
@@ -114,6 +134,10 @@ impl FromProto<authorization::ConnectResponse> for ConnectResponse {
                 .iter()
                 .map(|t| match t {
                     TRUST => Ok(AuthorizationType::Trust),
+                    CHALLENGE => Ok(AuthorizationType::Challenge {
+                        nonce: source.get_challenge_nonce().to_vec(),
+                        challenger: source.get_challenger_identity().to_string(),
+                    }),
                     UNSET_AUTHORIZATION_TYPE => Err(ProtoConversionError::InvalidTypeError(
                         "no authorization type was set".into(),
                     )),
@@ -127,17 +151,24 @@ impl FromNative<ConnectResponse> for authorization::ConnectResponse {
     fn from_native(source: ConnectResponse) -> Result<Self, ProtoConversionError> {
         let mut response = authorization::ConnectResponse::new();
 
-        response.set_accepted_authorization_types(
-            source
-                .accepted_authorization_types
-                .into_iter()
-                .map(|auth_type| match auth_type {
-                    AuthorizationType::Trust => {
-                        authorization::ConnectResponse_AuthorizationType::TRUST
-                    }
-                })
-                .collect(),
-        );
+        let mut challenge_nonce = vec![];
+        let mut challenger_identity = String::new();
+        let accepted_authorization_types = source
+            .accepted_authorization_types
+            .into_iter()
+            .map(|auth_type| match auth_type {
+                AuthorizationType::Trust => authorization::ConnectResponse_AuthorizationType::TRUST,
+                AuthorizationType::Challenge { nonce, challenger } => {
+                    challenge_nonce = nonce;
+                    challenger_identity = challenger;
+                    authorization::ConnectResponse_AuthorizationType::CHALLENGE
+                }
+            })
+            .collect();
+
+        response.set_accepted_authorization_types(accepted_authorization_types);
+        response.set_challenge_nonce(challenge_nonce);
+        response.set_challenger_identity(challenger_identity);
 
         Ok(response)
     }
@@ -160,6 +191,29 @@ impl FromNative<TrustRequest> for authorization::TrustRequest {
     }
 }
 
+impl FromProto<authorization::ChallengeSubmitRequest> for ChallengeSubmitRequest {
+    fn from_proto(
+        mut source: authorization::ChallengeSubmitRequest,
+    ) -> Result<Self, ProtoConversionError> {
+        Ok(Self {
+            identity: source.take_identity(),
+            public_key: source.take_public_key(),
+            signature: source.take_signature(),
+        })
+    }
+}
+
+impl FromNative<ChallengeSubmitRequest> for authorization::ChallengeSubmitRequest {
+    fn from_native(source: ChallengeSubmitRequest) -> Result<Self, ProtoConversionError> {
+        let mut request = authorization::ChallengeSubmitRequest::new();
+        request.set_identity(source.identity);
+        request.set_public_key(source.public_key);
+        request.set_signature(source.signature);
+
+        Ok(request)
+    }
+}
+
 impl FromProto<authorization::AuthorizedMessage> for Authorized {
     fn from_proto(_: authorization::AuthorizedMessage) -> Result<Self, ProtoConversionError> {
         Ok(Authorized)
@@ -233,6 +287,13 @@ impl FromProto<authorization::AuthorizationMessage> for AuthorizationMessage {
             >::from_bytes(
                 source.get_payload()
             )?)),
+            CHALLENGE_SUBMIT_REQUEST => {
+                Ok(AuthorizationMessage::ChallengeSubmitRequest(FromBytes::<
+                    authorization::ChallengeSubmitRequest,
+                >::from_bytes(
+                    source.get_payload(),
+                )?))
+            }
             UNSET_AUTHORIZATION_MESSAGE_TYPE => Err(ProtoConversionError::InvalidTypeError(
                 "no message type was set".into(),
             )),
@@ -277,6 +338,12 @@ impl FromNative<AuthorizationMessage> for authorization::AuthorizationMessage {
                     payload,
                 )?);
             }
+            AuthorizationMessage::ChallengeSubmitRequest(payload) => {
+                message.set_message_type(CHALLENGE_SUBMIT_REQUEST);
+                message.set_payload(
+                    IntoBytes::<authorization::ChallengeSubmitRequest>::into_bytes(payload)?,
+                );
+            }
         }
         Ok(message)
     }
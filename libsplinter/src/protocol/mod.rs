@@ -36,6 +36,14 @@ pub(crate) const ADMIN_LIST_CIRCUITS_MIN: u32 = 1;
 #[cfg(feature = "rest-api-actix")]
 pub(crate) const ADMIN_FETCH_CIRCUIT_MIN: u32 = 1;
 
+#[cfg(feature = "network-rest-api")]
+pub const NETWORK_PROTOCOL_VERSION: u32 = 2;
+
+#[cfg(all(feature = "network-rest-api", feature = "rest-api-actix"))]
+pub(crate) const NETWORK_LIST_CONNECTIONS_MIN: u32 = 1;
+#[cfg(all(feature = "network-rest-api", feature = "rest-api-actix"))]
+pub(crate) const NETWORK_PING_MIN: u32 = 2;
+
 #[cfg(feature = "oauth")]
 pub const OAUTH_PROTOCOL_VERSION: u32 = 1;
 
@@ -561,6 +561,17 @@ impl ServiceConnectionAgent {
                     );
                 }
             }
+            ConnectionManagerNotification::ConnectionAbandoned {
+                endpoint, attempts, ..
+            } => {
+                if let Some(info) = self.services.remove_connection_by_endoint(&endpoint) {
+                    error!(
+                        "Abandoned reconnecting to service processor {} after {} attempts; \
+                        removing",
+                        info.identity, attempts
+                    );
+                }
+            }
             ConnectionManagerNotification::FatalConnectionError { endpoint, error } => {
                 if let Some(info) = self.services.remove_connection_by_endoint(&endpoint) {
                     error!(
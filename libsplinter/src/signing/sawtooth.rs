@@ -69,6 +69,59 @@ impl<'c> Signer for SawtoothSecp256k1RefSigner<'c> {
     }
 }
 
+/// A Sawtooth Secp256k Signer that owns a context.
+///
+/// The SawtoothSecp256k1Signer provides an implementation of the Signer trait, that uses its own
+/// Secp256k1Context.
+pub struct SawtoothSecp256k1Signer {
+    context: secp256k1::Secp256k1Context,
+    private_key: secp256k1::Secp256k1PrivateKey,
+    public_key: Vec<u8>,
+}
+
+impl SawtoothSecp256k1Signer {
+    pub fn new(private_key: secp256k1::Secp256k1PrivateKey) -> Result<Self, Error> {
+        let context = secp256k1::Secp256k1Context::new();
+        let public_key = context
+            .get_public_key(&private_key)
+            .map_err(|err| Error::SigningError(format!("Unable to extract public key: {}", err)))?
+            .as_slice()
+            .to_vec();
+        Ok(Self {
+            context,
+            private_key,
+            public_key,
+        })
+    }
+
+    /// Creates a signer from a hex-encoded private key.
+    pub fn from_hex(private_key: &str) -> Result<Self, Error> {
+        let private_key = secp256k1::Secp256k1PrivateKey::from_hex(private_key)
+            .map_err(|err| Error::SigningError(format!("Unable to read private key: {}", err)))?;
+        Self::new(private_key)
+    }
+}
+
+impl Signer for SawtoothSecp256k1Signer {
+    fn sign(&self, message: &[u8]) -> Result<Vec<u8>, Error> {
+        self.context
+            .sign(message, &self.private_key)
+            .map_err(|err| Error::SigningError(format!("Failed to sign message: {}", err)))
+            .and_then(|signature| {
+                hex::parse_hex(&signature).map_err(|err| {
+                    Error::SigningError(format!(
+                        "Unable to parse sawtooth signature {} into bytes: {}",
+                        signature, err
+                    ))
+                })
+            })
+    }
+
+    fn public_key(&self) -> &[u8] {
+        &self.public_key
+    }
+}
+
 /// A Sawtooth Secp256k SignatureVerifier that references a context.
 ///
 /// The SawtoothSecp256k1RefSignatureVerifier provides an implementation of the SignatureVerifier
@@ -153,4 +206,13 @@ mod tests {
 
         test_signer_implementation(&sawtooth_signer, &sawtooth_verifier);
     }
+
+    #[test]
+    fn test_sawtooth_secp256k1_owned_context() {
+        let sawtooth_signer =
+            SawtoothSecp256k1Signer::from_hex(KEY1_PRIV_HEX).expect("Unable to create signer");
+        let sawtooth_verifier = SawtoothSecp256k1SignatureVerifier::new();
+
+        test_signer_implementation(&sawtooth_signer, &sawtooth_verifier);
+    }
 }
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[cfg(feature = "admin-service-event-store")]
+use crate::admin::service::event_store::{AdminServiceEventStore, MemoryAdminServiceEventStore};
+#[cfg(feature = "admin-service-store")]
+use crate::admin::store::{AdminServiceStore, MemoryAdminServiceStore};
 #[cfg(feature = "biome-credentials")]
 use crate::biome::{
     CredentialsStore, MemoryCredentialsStore, MemoryRefreshTokenStore, RefreshTokenStore,
@@ -25,6 +29,10 @@ use super::StoreFactory;
 /// A `StoryFactory` backed by memory.
 #[derive(Default)]
 pub struct MemoryStoreFactory {
+    #[cfg(feature = "admin-service-event-store")]
+    admin_service_event_store: MemoryAdminServiceEventStore,
+    #[cfg(feature = "admin-service-store")]
+    admin_service_store: MemoryAdminServiceStore,
     #[cfg(feature = "biome-credentials")]
     biome_credentials_store: MemoryCredentialsStore,
     #[cfg(feature = "biome-key-management")]
@@ -50,6 +58,10 @@ impl MemoryStoreFactory {
         let biome_user_store = MemoryUserStore::new();
 
         Self {
+            #[cfg(feature = "admin-service-event-store")]
+            admin_service_event_store: MemoryAdminServiceEventStore::new(),
+            #[cfg(feature = "admin-service-store")]
+            admin_service_store: MemoryAdminServiceStore::new(),
             #[cfg(feature = "biome-credentials")]
             biome_credentials_store,
             #[cfg(feature = "biome-key-management")]
@@ -62,6 +74,16 @@ impl MemoryStoreFactory {
 }
 
 impl StoreFactory for MemoryStoreFactory {
+    #[cfg(feature = "admin-service-event-store")]
+    fn get_admin_service_event_store(&self) -> Box<dyn AdminServiceEventStore> {
+        Box::new(self.admin_service_event_store.clone())
+    }
+
+    #[cfg(feature = "admin-service-store")]
+    fn get_admin_service_store(&self) -> Box<dyn AdminServiceStore> {
+        Box::new(self.admin_service_store.clone())
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_credentials_store(&self) -> Box<dyn CredentialsStore> {
         Box::new(self.biome_credentials_store.clone())
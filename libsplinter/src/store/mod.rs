@@ -25,6 +25,16 @@ use diesel::r2d2::{ConnectionManager, Pool};
 
 /// An abstract factory for creating Splinter stores backed by the same storage
 pub trait StoreFactory {
+    /// Get a new `AdminServiceEventStore`
+    #[cfg(feature = "admin-service-event-store")]
+    fn get_admin_service_event_store(
+        &self,
+    ) -> Box<dyn crate::admin::service::event_store::AdminServiceEventStore>;
+
+    /// Get a new `AdminServiceStore`
+    #[cfg(feature = "admin-service-store")]
+    fn get_admin_service_store(&self) -> Box<dyn crate::admin::store::AdminServiceStore>;
+
     /// Get a new `CredentialsStore`
     #[cfg(feature = "biome-credentials")]
     fn get_biome_credentials_store(&self) -> Box<dyn crate::biome::CredentialsStore>;
@@ -31,6 +31,24 @@ impl PgStoreFactory {
 }
 
 impl StoreFactory for PgStoreFactory {
+    #[cfg(feature = "admin-service-event-store")]
+    fn get_admin_service_event_store(
+        &self,
+    ) -> Box<dyn crate::admin::service::event_store::AdminServiceEventStore> {
+        Box::new(
+            crate::admin::service::event_store::diesel::DieselAdminServiceEventStore::new(
+                self.pool.clone(),
+            ),
+        )
+    }
+
+    #[cfg(feature = "admin-service-store")]
+    fn get_admin_service_store(&self) -> Box<dyn crate::admin::store::AdminServiceStore> {
+        Box::new(crate::admin::store::diesel::DieselAdminServiceStore::new(
+            self.pool.clone(),
+        ))
+    }
+
     #[cfg(feature = "biome-credentials")]
     fn get_biome_credentials_store(&self) -> Box<dyn crate::biome::CredentialsStore> {
         Box::new(crate::biome::DieselCredentialsStore::new(self.pool.clone()))
@@ -0,0 +1,625 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A transport that injects faults into the connections of another transport.
+//!
+//! The [`FaultInjectingTransport`] wraps an inner transport, and its connections can be made to
+//! delay, drop, duplicate or reorder the messages they send, or be partitioned from other
+//! transports.  The faults are controlled at any time through a [`FaultController`], which may be
+//! shared by several transports, such as the transports of the nodes in a test network.
+//!
+//! Each transport is given a name, such as the id of its node, which it exchanges with the remote
+//! transport when a connection is established.  Both ends of a connection must therefore be
+//! fault injecting transports.
+//!
+//! Random faults are decided by a seeded random number generator, so a test that sends the same
+//! messages in the same order sees the same faults on every run.
+//!
+//! This module is intended for testing only.
+//!
+//! [`FaultInjectingTransport`]: struct.FaultInjectingTransport.html
+//! [`FaultController`]: struct.FaultController.html
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use mio::Evented;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{
+    AcceptError, ConnectError, Connection, DisconnectError, ListenError, Listener, RecvError,
+    SendError, Transport,
+};
+
+const DEFAULT_SEED: u64 = 0;
+
+/// How long to wait for the name of the remote transport when a connection is established.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const HANDSHAKE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug)]
+pub struct FaultControllerError(pub String);
+
+impl Error for FaultControllerError {}
+
+impl fmt::Display for FaultControllerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Controls the faults injected by the transports created with it.
+///
+/// A partition is between two named transports: their connections to each other neither send
+/// nor receive messages, and new connections between them are refused.  Because transports are
+/// identified by name rather than by endpoint, a partition applies to the inbound connections of
+/// a transport as well as to its outbound ones.
+#[derive(Clone)]
+pub struct FaultController {
+    state: Arc<Mutex<FaultState>>,
+}
+
+struct FaultState {
+    latency: Option<Duration>,
+    drop_probability: f64,
+    duplicate_probability: f64,
+    reorder_probability: f64,
+    partitions: HashSet<(String, String)>,
+    rng: StdRng,
+}
+
+impl FaultController {
+    /// Construct a new FaultController that decides random faults using the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(FaultState {
+                latency: None,
+                drop_probability: 0.0,
+                duplicate_probability: 0.0,
+                reorder_probability: 0.0,
+                partitions: HashSet::new(),
+                rng: StdRng::seed_from_u64(seed),
+            })),
+        }
+    }
+
+    /// Wrap the given transport, under the given name, with faults controlled by this instance.
+    pub fn wrap<T>(&self, name: &str, inner: T) -> FaultInjectingTransport
+    where
+        T: Transport + Send + 'static,
+    {
+        FaultInjectingTransport {
+            name: name.to_string(),
+            inner: Box::new(inner),
+            controller: self.clone(),
+        }
+    }
+
+    /// Delay every message sent by the given duration, or stop delaying messages.
+    ///
+    /// The delay blocks the thread that sends the message.
+    pub fn set_latency(&self, latency: Option<Duration>) -> Result<(), FaultControllerError> {
+        self.state()?.latency = latency;
+        Ok(())
+    }
+
+    /// Set the probability, between 0.0 and 1.0, of a sent message being dropped.
+    pub fn set_drop_probability(&self, probability: f64) -> Result<(), FaultControllerError> {
+        self.state()?.drop_probability = probability;
+        Ok(())
+    }
+
+    /// Set the probability, between 0.0 and 1.0, of a sent message being delivered twice.
+    pub fn set_duplicate_probability(&self, probability: f64) -> Result<(), FaultControllerError> {
+        self.state()?.duplicate_probability = probability;
+        Ok(())
+    }
+
+    /// Set the probability, between 0.0 and 1.0, of a sent message being held back and delivered
+    /// after the next message sent over the same connection.
+    pub fn set_reorder_probability(&self, probability: f64) -> Result<(), FaultControllerError> {
+        self.state()?.reorder_probability = probability;
+        Ok(())
+    }
+
+    /// Partition the transports with the given names from each other.
+    pub fn partition(&self, name: &str, other: &str) -> Result<(), FaultControllerError> {
+        self.state()?.partitions.insert(partition_key(name, other));
+        Ok(())
+    }
+
+    /// Heal the partition between the transports with the given names.
+    pub fn heal(&self, name: &str, other: &str) -> Result<(), FaultControllerError> {
+        self.state()?.partitions.remove(&partition_key(name, other));
+        Ok(())
+    }
+
+    /// Remove all faults, healing all partitions.
+    pub fn clear(&self) -> Result<(), FaultControllerError> {
+        let mut state = self.state()?;
+        state.latency = None;
+        state.drop_probability = 0.0;
+        state.duplicate_probability = 0.0;
+        state.reorder_probability = 0.0;
+        state.partitions.clear();
+        Ok(())
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, FaultState>, FaultControllerError> {
+        self.state
+            .lock()
+            .map_err(|_| FaultControllerError("FaultController state lock was poisoned".into()))
+    }
+
+    fn is_partitioned(&self, name: &str, other: &str) -> Result<bool, FaultControllerError> {
+        Ok(self
+            .state()?
+            .partitions
+            .contains(&partition_key(name, other)))
+    }
+
+    /// Decide the faults for the next message sent between the transports with the given names.
+    fn next_faults(&self, name: &str, other: &str) -> Result<MessageFaults, FaultControllerError> {
+        let mut state = self.state()?;
+        if state.partitions.contains(&partition_key(name, other)) {
+            return Ok(MessageFaults {
+                latency: None,
+                drop: true,
+                duplicate: false,
+                reorder: false,
+            });
+        }
+
+        let drop = state.drop_probability;
+        let duplicate = state.duplicate_probability;
+        let reorder = state.reorder_probability;
+        Ok(MessageFaults {
+            latency: state.latency,
+            drop: state.rng.gen::<f64>() < drop,
+            duplicate: state.rng.gen::<f64>() < duplicate,
+            reorder: state.rng.gen::<f64>() < reorder,
+        })
+    }
+}
+
+impl Default for FaultController {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
+
+/// A partition is between a pair of transports, regardless of which end of it is named first.
+fn partition_key(name: &str, other: &str) -> (String, String) {
+    if name <= other {
+        (name.to_string(), other.to_string())
+    } else {
+        (other.to_string(), name.to_string())
+    }
+}
+
+/// Receive the name of the remote transport, which is the first message sent by either end of a
+/// connection.
+fn recv_name(connection: &mut Box<dyn Connection>) -> Result<String, RecvError> {
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    loop {
+        match connection.recv() {
+            Ok(name) => {
+                return String::from_utf8(name).map_err(|_| {
+                    RecvError::ProtocolError("Remote transport name is not valid UTF-8".into())
+                })
+            }
+            Err(RecvError::WouldBlock) if Instant::now() < deadline => {
+                thread::sleep(HANDSHAKE_POLL_INTERVAL)
+            }
+            Err(RecvError::WouldBlock) => {
+                return Err(RecvError::ProtocolError(
+                    "Timed out waiting for the remote transport name".into(),
+                ))
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn partitioned_error(name: &str, remote_name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::ConnectionRefused,
+        format!("{} is partitioned from {}", name, remote_name),
+    )
+}
+
+struct MessageFaults {
+    latency: Option<Duration>,
+    drop: bool,
+    duplicate: bool,
+    reorder: bool,
+}
+
+/// A Transport that injects the faults set by its FaultController into its connections.
+///
+/// Instances are created by [`FaultController::wrap`].
+///
+/// [`FaultController::wrap`]: struct.FaultController.html#method.wrap
+pub struct FaultInjectingTransport {
+    name: String,
+    inner: Box<dyn Transport + Send>,
+    controller: FaultController,
+}
+
+impl Transport for FaultInjectingTransport {
+    fn accepts(&self, address: &str) -> bool {
+        self.inner.accepts(address)
+    }
+
+    fn connect(&mut self, endpoint: &str) -> Result<Box<dyn Connection>, ConnectError> {
+        let mut connection = self.inner.connect(endpoint)?;
+
+        connection.send(self.name.as_bytes()).map_err(|err| {
+            ConnectError::ProtocolError(format!("Unable to send transport name: {}", err))
+        })?;
+        let remote_name = recv_name(&mut connection).map_err(|err| {
+            ConnectError::ProtocolError(format!("Unable to receive remote transport name: {}", err))
+        })?;
+
+        if self
+            .controller
+            .is_partitioned(&self.name, &remote_name)
+            .map_err(|err| ConnectError::ProtocolError(err.to_string()))?
+        {
+            let _ = connection.disconnect();
+            return Err(ConnectError::IoError(partitioned_error(
+                &self.name,
+                &remote_name,
+            )));
+        }
+
+        Ok(Box::new(FaultInjectingConnection {
+            name: self.name.clone(),
+            remote_name,
+            inner: connection,
+            controller: self.controller.clone(),
+            held: None,
+        }))
+    }
+
+    fn listen(&mut self, bind: &str) -> Result<Box<dyn Listener>, ListenError> {
+        Ok(Box::new(FaultInjectingListener {
+            name: self.name.clone(),
+            inner: self.inner.listen(bind)?,
+            controller: self.controller.clone(),
+        }))
+    }
+}
+
+struct FaultInjectingListener {
+    name: String,
+    inner: Box<dyn Listener>,
+    controller: FaultController,
+}
+
+impl Listener for FaultInjectingListener {
+    fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
+        let mut connection = self.inner.accept()?;
+
+        let remote_name = recv_name(&mut connection).map_err(|err| {
+            AcceptError::ProtocolError(format!("Unable to receive remote transport name: {}", err))
+        })?;
+        connection.send(self.name.as_bytes()).map_err(|err| {
+            AcceptError::ProtocolError(format!("Unable to send transport name: {}", err))
+        })?;
+
+        if self
+            .controller
+            .is_partitioned(&self.name, &remote_name)
+            .map_err(|err| AcceptError::ProtocolError(err.to_string()))?
+        {
+            let _ = connection.disconnect();
+            return Err(AcceptError::IoError(partitioned_error(
+                &self.name,
+                &remote_name,
+            )));
+        }
+
+        Ok(Box::new(FaultInjectingConnection {
+            name: self.name.clone(),
+            remote_name,
+            inner: connection,
+            controller: self.controller.clone(),
+            held: None,
+        }))
+    }
+
+    fn endpoint(&self) -> String {
+        self.inner.endpoint()
+    }
+}
+
+struct FaultInjectingConnection {
+    name: String,
+    remote_name: String,
+    inner: Box<dyn Connection>,
+    controller: FaultController,
+    held: Option<Vec<u8>>,
+}
+
+impl Connection for FaultInjectingConnection {
+    fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
+        let faults = self
+            .controller
+            .next_faults(&self.name, &self.remote_name)
+            .map_err(|err| SendError::ProtocolError(err.to_string()))?;
+
+        if let Some(latency) = faults.latency {
+            thread::sleep(latency);
+        }
+
+        if faults.drop {
+            return Ok(());
+        }
+
+        if faults.reorder && self.held.is_none() {
+            self.held = Some(message.to_vec());
+            return Ok(());
+        }
+
+        self.inner.send(message)?;
+        if faults.duplicate {
+            self.inner.send(message)?;
+        }
+
+        if let Some(held) = self.held.take() {
+            self.inner.send(&held)?;
+        }
+
+        Ok(())
+    }
+
+    fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
+        let message = self.inner.recv()?;
+        if self
+            .controller
+            .is_partitioned(&self.name, &self.remote_name)
+            .map_err(|err| RecvError::ProtocolError(err.to_string()))?
+        {
+            return Err(RecvError::WouldBlock);
+        }
+
+        Ok(message)
+    }
+
+    fn remote_endpoint(&self) -> String {
+        self.inner.remote_endpoint()
+    }
+
+    fn local_endpoint(&self) -> String {
+        self.inner.local_endpoint()
+    }
+
+    fn disconnect(&mut self) -> Result<(), DisconnectError> {
+        self.inner.disconnect()
+    }
+
+    fn evented(&self) -> &dyn Evented {
+        self.inner.evented()
+    }
+
+    #[cfg(feature = "tls-peer-identity")]
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        self.inner.peer_certificate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Instant;
+
+    use crate::transport::inproc::InprocTransport;
+
+    /// Create a connected pair of connections through fault injecting inproc transports named
+    /// "alpha" (the listener) and "beta" (the connector).
+    fn connection_pair(controller: &FaultController) -> (Box<dyn Connection>, Box<dyn Connection>) {
+        let inproc = InprocTransport::default();
+        let mut alpha = controller.wrap("alpha", inproc.clone());
+        let mut beta = controller.wrap("beta", inproc);
+
+        let listener = alpha.listen("inproc://alpha").unwrap();
+        let (outbound, inbound, _) = connect(&mut beta, listener, "inproc://alpha");
+
+        (outbound.unwrap(), inbound.unwrap())
+    }
+
+    /// The outbound and inbound ends of a connection attempt, and the listener that accepted it.
+    type ConnectResult = (
+        Result<Box<dyn Connection>, ConnectError>,
+        Result<Box<dyn Connection>, AcceptError>,
+        Box<dyn Listener>,
+    );
+
+    /// Connect the transport to the listener, accepting the connection on another thread while
+    /// the transport names are exchanged.  The listener is returned for further connections.
+    fn connect(
+        transport: &mut FaultInjectingTransport,
+        mut listener: Box<dyn Listener>,
+        endpoint: &str,
+    ) -> ConnectResult {
+        let join_handle = thread::spawn(move || {
+            let inbound = listener.accept();
+            (inbound, listener)
+        });
+        let outbound = transport.connect(endpoint);
+        let (inbound, listener) = join_handle.join().expect("Unable to join accept thread");
+
+        (outbound, inbound, listener)
+    }
+
+    /// Receive all messages that are currently available on the connection.
+    fn recv_all(connection: &mut Box<dyn Connection>) -> Vec<Vec<u8>> {
+        let mut messages = vec![];
+        loop {
+            match connection.recv() {
+                Ok(message) => messages.push(message),
+                Err(RecvError::WouldBlock) => break,
+                Err(err) => panic!("Unable to receive message: {}", err),
+            }
+        }
+        messages
+    }
+
+    /// Test that a connection without faults delivers all messages, in order.
+    #[test]
+    fn test_no_faults() {
+        let controller = FaultController::default();
+        let (mut outbound, mut inbound) = connection_pair(&controller);
+
+        for i in 0..10u8 {
+            outbound.send(&[i]).unwrap();
+        }
+
+        let expected: Vec<Vec<u8>> = (0..10u8).map(|i| vec![i]).collect();
+        assert_eq!(expected, recv_all(&mut inbound));
+    }
+
+    /// Test that messages are always dropped with a drop probability of 1.0, and are delivered
+    /// again once the fault is cleared.
+    #[test]
+    fn test_drop() {
+        let controller = FaultController::default();
+        let (mut outbound, mut inbound) = connection_pair(&controller);
+
+        controller.set_drop_probability(1.0).unwrap();
+        outbound.send(b"dropped").unwrap();
+        assert!(recv_all(&mut inbound).is_empty());
+
+        controller.clear().unwrap();
+        outbound.send(b"delivered").unwrap();
+        assert_eq!(vec![b"delivered".to_vec()], recv_all(&mut inbound));
+    }
+
+    /// Test that messages are delivered twice with a duplicate probability of 1.0.
+    #[test]
+    fn test_duplicate() {
+        let controller = FaultController::default();
+        let (mut outbound, mut inbound) = connection_pair(&controller);
+
+        controller.set_duplicate_probability(1.0).unwrap();
+        outbound.send(b"message").unwrap();
+        assert_eq!(
+            vec![b"message".to_vec(), b"message".to_vec()],
+            recv_all(&mut inbound)
+        );
+    }
+
+    /// Test that with a reorder probability of 1.0, each held message is delivered after the
+    /// message that follows it.
+    #[test]
+    fn test_reorder() {
+        let controller = FaultController::default();
+        let (mut outbound, mut inbound) = connection_pair(&controller);
+
+        controller.set_reorder_probability(1.0).unwrap();
+        for i in 0..4u8 {
+            outbound.send(&[i]).unwrap();
+        }
+
+        assert_eq!(
+            vec![vec![1], vec![0], vec![3], vec![2]],
+            recv_all(&mut inbound)
+        );
+    }
+
+    /// Test that the same seed produces the same faults.
+    #[test]
+    fn test_deterministic_faults() {
+        let run = || {
+            let controller = FaultController::new(7);
+            let (mut outbound, mut inbound) = connection_pair(&controller);
+            controller.set_drop_probability(0.5).unwrap();
+            for i in 0..32u8 {
+                outbound.send(&[i]).unwrap();
+            }
+            recv_all(&mut inbound)
+        };
+
+        let first = run();
+        assert!(!first.is_empty() && first.len() < 32);
+        assert_eq!(first, run());
+    }
+
+    /// Test that sent messages are delayed by the latency.
+    #[test]
+    fn test_latency() {
+        let controller = FaultController::default();
+        let (mut outbound, mut inbound) = connection_pair(&controller);
+
+        controller
+            .set_latency(Some(Duration::from_millis(100)))
+            .unwrap();
+        let start = Instant::now();
+        outbound.send(b"message").unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(vec![b"message".to_vec()], recv_all(&mut inbound));
+    }
+
+    /// Test that a partition:
+    ///
+    /// 1. drops messages sent in either direction, on both the outbound and the inbound
+    ///    connections of a partitioned transport
+    /// 2. refuses new connections between the partitioned transports
+    /// 3. delivers messages again once healed
+    #[test]
+    fn test_partition() {
+        let controller = FaultController::default();
+        let inproc = InprocTransport::default();
+        let mut alpha = controller.wrap("alpha", inproc.clone());
+        let mut beta = controller.wrap("beta", inproc);
+
+        let listener = alpha.listen("inproc://alpha").unwrap();
+        let (outbound, inbound, listener) = connect(&mut beta, listener, "inproc://alpha");
+        let mut outbound = outbound.unwrap();
+        let mut inbound = inbound.unwrap();
+
+        // partition the listening transport, which only knows the connection as inbound
+        controller.partition("alpha", "beta").unwrap();
+
+        outbound.send(b"to alpha").unwrap();
+        inbound.send(b"to beta").unwrap();
+        assert!(recv_all(&mut inbound).is_empty());
+        assert!(recv_all(&mut outbound).is_empty());
+
+        match connect(&mut beta, listener, "inproc://alpha") {
+            (Err(ConnectError::IoError(err)), Err(AcceptError::IoError(_)), _) => {
+                assert_eq!(io::ErrorKind::ConnectionRefused, err.kind())
+            }
+            (outbound, inbound, _) => panic!(
+                "Connection should have been refused: {:?}, {:?}",
+                outbound.map(|_| ()),
+                inbound.map(|_| ())
+            ),
+        }
+
+        controller.heal("beta", "alpha").unwrap();
+
+        outbound.send(b"to alpha").unwrap();
+        inbound.send(b"to beta").unwrap();
+        assert_eq!(vec![b"to alpha".to_vec()], recv_all(&mut inbound));
+        assert_eq!(vec![b"to beta".to_vec()], recv_all(&mut outbound));
+    }
+}
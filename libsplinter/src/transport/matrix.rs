@@ -89,6 +89,21 @@ impl From<ConnectionMatrixEnvelope> for Vec<u8> {
     }
 }
 
+/// Traffic statistics of a connection in a connection matrix
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionMatrixStats {
+    /// The number of messages sent over the connection
+    pub messages_sent: u64,
+    /// The number of payload bytes sent over the connection
+    pub bytes_sent: u64,
+    /// The number of messages received from the connection
+    pub messages_received: u64,
+    /// The number of payload bytes received from the connection
+    pub bytes_received: u64,
+    /// The number of messages waiting to be sent over the connection
+    pub queue_depth: usize,
+}
+
 /// Defines connection lifecycle operations (addition and removal of a `Connection`)
 ///
 /// This trait is distinct from the sender/receiver traits because the lifecycle operations
@@ -117,6 +132,18 @@ pub trait ConnectionMatrixLifeCycle: Clone + Send {
     ///
     /// If the remove failed, a `ConnectionMatrixRemoveError` will be returned.
     fn remove(&self, id: &str) -> Result<Box<dyn Connection>, ConnectionMatrixRemoveError>;
+
+    /// Returns the traffic statistics of a connection in the connection matrix
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the connection identifier for the connection
+    ///
+    /// Returns `None` if the connection is not in the matrix, or if the matrix does not keep
+    /// statistics.
+    fn connection_stats(&self, _id: &str) -> Option<ConnectionMatrixStats> {
+        None
+    }
 }
 
 /// Defines a function to send a message using a connection identifier
@@ -36,6 +36,8 @@
 //! [`Transport`]: trait.Transport.html
 
 mod error;
+#[cfg(feature = "fault-injection")]
+pub mod fault;
 pub mod inproc;
 pub(crate) mod matrix;
 pub mod multi;
@@ -80,6 +82,15 @@ pub trait Connection: Send {
 
     /// Returns a `mio::event::Evented` for this connection which can be used for polling.
     fn evented(&self) -> &dyn Evented;
+
+    /// Return the DER-encoded certificate presented by the remote peer.
+    ///
+    /// This is only provided by connections that verified the certificate against a certificate
+    /// authority when the connection was established, such as TLS connections with a CA file.
+    #[cfg(feature = "tls-peer-identity")]
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        None
+    }
 }
 
 pub trait Listener: Send {
@@ -112,6 +112,22 @@ mod tests {
         tests::test_transport(transport, "127.0.0.1:0");
     }
 
+    /// Test MultiTransport using a Unix socket transport for the listening endpoint, with the
+    /// standard transport tests.
+    #[cfg(feature = "unix-transport")]
+    #[test]
+    fn test_transport_unix_listener() {
+        let temp_dir = tempdir::TempDir::new("multi-transport-test").unwrap();
+        let raw_transport = Box::new(socket::TcpTransport::default());
+        let unix_transport = Box::new(socket::UnixTransport::default());
+
+        let transport = MultiTransport::new(vec![raw_transport, unix_transport]);
+        tests::test_transport(
+            transport,
+            &format!("unix://{}", temp_dir.path().join("test.sock").display()),
+        );
+    }
+
     /// Create a transport with tcp and tls transports and attempt to create an unknown protocol.
     /// Expect that a protocol error should be returned.
     #[test]
@@ -12,21 +12,49 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::borrow::Cow;
 use std::io::{self, Cursor, Read, Write};
 use std::thread;
 use std::time::Duration;
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "frame-v2")]
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
 
 const HEADER_LENGTH: usize = 6;
+#[cfg(feature = "frame-v2")]
+const HEADER_V2_LENGTH: usize = 12;
+
+/// Payloads smaller than this are not worth compressing.
+const COMPRESSION_THRESHOLD: usize = 1024;
+
+/// The largest payload, in bytes, that will be read from or written to a version 2 frame.
+///
+/// The length in a frame header is sent by the remote peer, so it is checked against this limit
+/// before any buffer is allocated for the payload. Decompressed payloads are held to the same
+/// limit. Version 1 frames are not held to this limit, so that they are unchanged for existing
+/// peers; their length is bounded by their `u32` header field.
+#[cfg(feature = "frame-v2")]
+const MAX_FRAME_LENGTH: usize = 64 * 1024 * 1024;
 
 /// An error that may be returned during frame-related operations
 #[derive(Debug)]
 pub enum FrameError {
     IoError(io::Error),
     InvalidChecksum,
-    InvalidHeaderLength(usize),
+    InvalidHeaderLength {
+        expected: usize,
+        actual: usize,
+    },
+    FrameTooLarge {
+        max: usize,
+        actual: u64,
+    },
     UnsupportedVersion,
+    #[cfg(feature = "frame-v2")]
+    UnsupportedCompression(u16),
+    #[cfg(feature = "frame-v2")]
+    CompressionError(String),
     HandshakeFailure(String),
 }
 
@@ -35,12 +63,21 @@ impl std::fmt::Display for FrameError {
         match self {
             FrameError::IoError(err) => f.write_str(&err.to_string()),
             FrameError::InvalidChecksum => f.write_str("Invalid checksum in frame header"),
-            FrameError::InvalidHeaderLength(n) => write!(
+            FrameError::InvalidHeaderLength { expected, actual } => write!(
                 f,
                 "Invalid header length expected {} but was {}",
-                HEADER_LENGTH, n
+                expected, actual
             ),
+            FrameError::FrameTooLarge { max, actual } => {
+                write!(f, "Frame length {} exceeds the maximum of {}", actual, max)
+            }
             FrameError::UnsupportedVersion => f.write_str("Unsupported frame version"),
+            #[cfg(feature = "frame-v2")]
+            FrameError::UnsupportedCompression(id) => {
+                write!(f, "Unsupported frame compression {}", id)
+            }
+            #[cfg(feature = "frame-v2")]
+            FrameError::CompressionError(msg) => f.write_str(msg),
             FrameError::HandshakeFailure(msg) => f.write_str(&msg),
         }
     }
@@ -51,8 +88,13 @@ impl std::error::Error for FrameError {
         match self {
             FrameError::IoError(err) => Some(&*err),
             FrameError::InvalidChecksum => None,
-            FrameError::InvalidHeaderLength(_) => None,
+            FrameError::InvalidHeaderLength { .. } => None,
+            FrameError::FrameTooLarge { .. } => None,
             FrameError::UnsupportedVersion => None,
+            #[cfg(feature = "frame-v2")]
+            FrameError::UnsupportedCompression(_) => None,
+            #[cfg(feature = "frame-v2")]
+            FrameError::CompressionError(_) => None,
             FrameError::HandshakeFailure(_) => None,
         }
     }
@@ -71,6 +113,32 @@ impl From<io::Error> for FrameError {
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum FrameVersion {
     V1 = 1,
+    /// Version 2 frames have a 64-bit length and flags that specify the payload compression.
+    #[cfg(feature = "frame-v2")]
+    V2 = 2,
+}
+
+impl FrameVersion {
+    /// The latest frame version supported.
+    pub fn latest() -> Self {
+        #[cfg(feature = "frame-v2")]
+        {
+            FrameVersion::V2
+        }
+        #[cfg(not(feature = "frame-v2"))]
+        {
+            FrameVersion::V1
+        }
+    }
+
+    fn from_u16(version: u16) -> Option<Self> {
+        match version {
+            1 => Some(FrameVersion::V1),
+            #[cfg(feature = "frame-v2")]
+            2 => Some(FrameVersion::V2),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for FrameVersion {
@@ -79,6 +147,92 @@ impl std::fmt::Display for FrameVersion {
     }
 }
 
+/// The Frame compression
+///
+/// This specifies the algorithm that may be used to compress frame payloads.  It is negotiated
+/// along with the frame version, and is only used by frame versions that support compression.
+/// Each frame indicates whether or not its payload was compressed.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum FrameCompression {
+    None = 0,
+    #[cfg(feature = "frame-v2")]
+    Deflate = 1,
+}
+
+impl FrameCompression {
+    /// The supported compression algorithms, in order of preference.
+    #[cfg(feature = "frame-v2")]
+    const SUPPORTED: &'static [FrameCompression] = &[FrameCompression::Deflate];
+
+    #[cfg(feature = "frame-v2")]
+    fn from_u16(id: u16) -> Result<Self, FrameError> {
+        match id {
+            0 => Ok(FrameCompression::None),
+            1 => Ok(FrameCompression::Deflate),
+            _ => Err(FrameError::UnsupportedCompression(id)),
+        }
+    }
+
+    /// A bit set of the supported compression algorithms, as sent during negotiation.
+    #[cfg(feature = "frame-v2")]
+    fn supported_bits() -> u16 {
+        Self::SUPPORTED
+            .iter()
+            .fold(0, |bits, compression| bits | 1 << *compression as u16)
+    }
+
+    /// The preferred compression algorithm that is in the given bit set.
+    #[cfg(feature = "frame-v2")]
+    fn preferred(bits: u16) -> Self {
+        Self::SUPPORTED
+            .iter()
+            .find(|compression| bits & 1 << **compression as u16 != 0)
+            .copied()
+            .unwrap_or(FrameCompression::None)
+    }
+
+    fn compress(self, data: &[u8]) -> Result<Vec<u8>, FrameError> {
+        match self {
+            FrameCompression::None => Ok(data.to_vec()),
+            #[cfg(feature = "frame-v2")]
+            FrameCompression::Deflate => {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(data)
+                    .and_then(|_| encoder.finish())
+                    .map_err(|err| {
+                        FrameError::CompressionError(format!("Unable to compress frame: {}", err))
+                    })
+            }
+        }
+    }
+
+    /// Decompress the data, failing if the result would be longer than `max_length` bytes.
+    #[cfg(feature = "frame-v2")]
+    fn decompress(self, data: Vec<u8>, max_length: usize) -> Result<Vec<u8>, FrameError> {
+        match self {
+            FrameCompression::None => Ok(data),
+            FrameCompression::Deflate => {
+                let mut decompressed = vec![];
+                // Read one byte past the limit so that an oversized payload can be detected
+                DeflateDecoder::new(&data[..])
+                    .take(max_length as u64 + 1)
+                    .read_to_end(&mut decompressed)
+                    .map_err(|err| {
+                        FrameError::CompressionError(format!("Unable to decompress frame: {}", err))
+                    })?;
+                if decompressed.len() > max_length {
+                    return Err(FrameError::CompressionError(format!(
+                        "Decompressed frame exceeds the maximum length of {}",
+                        max_length
+                    )));
+                }
+                Ok(decompressed)
+            }
+        }
+    }
+}
+
 /// A complete Frame of transmitted data.
 ///
 /// This struct owns the data that has been transmitted.  It is essentially a receiving frame.
@@ -99,6 +253,8 @@ impl Frame {
     /// This function returns an error if:
     ///
     /// - the header is malformed
+    /// - the header length, or the decompressed data length, of a version 2 frame exceeds the
+    ///   maximum frame length
     /// - the data length doesn't match the header length
     /// - an IO error occurs
     pub fn read<R: Read>(reader: &mut R) -> Result<Self, FrameError> {
@@ -114,34 +270,53 @@ impl Frame {
         };
 
         match frame_header {
-            FrameHeader::V1 { length } => {
-                let mut buffer = vec![0; length as usize];
-                let mut remaining = &mut buffer[..];
-
-                while !remaining.is_empty() {
-                    match reader.read(remaining) {
-                        Ok(0) => break,
-                        Ok(n) => {
-                            let tmp = remaining;
-                            remaining = &mut tmp[n..];
-                        }
-                        Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
-                        Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                            thread::sleep(Duration::from_millis(100));
-                        }
-                        Err(e) => return Err(FrameError::IoError(e)),
-                    }
+            FrameHeader::V1 { length } => Ok(Self {
+                data: Self::read_payload(reader, length as usize)?,
+            }),
+            #[cfg(feature = "frame-v2")]
+            FrameHeader::V2 { flags, length } => {
+                if length > MAX_FRAME_LENGTH as u64 {
+                    return Err(FrameError::FrameTooLarge {
+                        max: MAX_FRAME_LENGTH,
+                        actual: length,
+                    });
                 }
-                if !remaining.is_empty() {
-                    Err(FrameError::IoError(io::Error::new(
-                        io::ErrorKind::UnexpectedEof,
-                        "Could not receive complete frame",
-                    )))
-                } else {
-                    Ok(Self { data: buffer })
+
+                let compression = FrameCompression::from_u16(flags & COMPRESSION_FLAGS_MASK)?;
+                let payload = Self::read_payload(reader, length as usize)?;
+                Ok(Self {
+                    data: compression.decompress(payload, MAX_FRAME_LENGTH)?,
+                })
+            }
+        }
+    }
+
+    fn read_payload<R: Read>(reader: &mut R, length: usize) -> Result<Vec<u8>, FrameError> {
+        let mut buffer = vec![0; length];
+        let mut remaining = &mut buffer[..];
+
+        while !remaining.is_empty() {
+            match reader.read(remaining) {
+                Ok(0) => break,
+                Ok(n) => {
+                    let tmp = remaining;
+                    remaining = &mut tmp[n..];
+                }
+                Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(100));
                 }
+                Err(e) => return Err(FrameError::IoError(e)),
             }
         }
+        if !remaining.is_empty() {
+            Err(FrameError::IoError(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Could not receive complete frame",
+            )))
+        } else {
+            Ok(buffer)
+        }
     }
 }
 
@@ -150,6 +325,7 @@ impl Frame {
 /// This struct references the data that has been transmitted.  It is essentially a sending frame.
 pub struct FrameRef<'a> {
     version: FrameVersion,
+    compression: FrameCompression,
     data: &'a [u8],
 }
 
@@ -157,17 +333,54 @@ impl<'a> FrameRef<'a> {
     /// Construct a FrameRef for the given byte slice, which will be transmitted using the given
     /// frame version.
     pub fn new<'b: 'a>(version: FrameVersion, data: &'b [u8]) -> FrameRef<'a> {
-        Self { version, data }
+        Self {
+            version,
+            compression: FrameCompression::None,
+            data,
+        }
+    }
+
+    /// Set the compression that may be used for the data.
+    ///
+    /// The data is only compressed if the frame version supports compression, and if it is large
+    /// enough for compression to be worthwhile.
+    pub fn with_compression(mut self, compression: FrameCompression) -> Self {
+        self.compression = compression;
+        self
     }
 
     /// Write the frame to the given writer.
     ///
     /// # Errors
     ///
-    /// Returns a FrameError if an IO error occurs.
+    /// Returns a FrameError if:
+    ///
+    /// - the data of a version 2 frame is longer than the maximum frame length
+    /// - an IO error occurs
     pub fn write<W: Write>(self, writer: &mut W) -> Result<(), FrameError> {
+        #[cfg(feature = "frame-v2")]
+        {
+            if self.version == FrameVersion::V2 && self.data.len() > MAX_FRAME_LENGTH {
+                return Err(FrameError::FrameTooLarge {
+                    max: MAX_FRAME_LENGTH,
+                    actual: self.data.len() as u64,
+                });
+            }
+        }
+
+        let data = self.payload()?;
+
         let frame_header = match self.version {
-            FrameVersion::V1 => FrameHeader::v1(self.data.len() as u32),
+            FrameVersion::V1 => FrameHeader::v1(data.len() as u32),
+            #[cfg(feature = "frame-v2")]
+            FrameVersion::V2 => {
+                let compression = if data.len() < self.data.len() {
+                    self.compression
+                } else {
+                    FrameCompression::None
+                };
+                FrameHeader::v2(compression as u16, data.len() as u64)
+            }
         };
         loop {
             match frame_header.write(writer) {
@@ -180,7 +393,7 @@ impl<'a> FrameRef<'a> {
             }
         }
 
-        let mut buffer = &self.data[..];
+        let mut buffer = &data[..];
         while !buffer.is_empty() {
             match writer.write(buffer) {
                 Ok(0) => {
@@ -200,6 +413,23 @@ impl<'a> FrameRef<'a> {
         writer.flush()?;
         Ok(())
     }
+
+    /// Return the data to transmit, which is compressed if that makes it smaller.
+    fn payload(&self) -> Result<Cow<'a, [u8]>, FrameError> {
+        if self.version == FrameVersion::V1
+            || self.compression == FrameCompression::None
+            || self.data.len() < COMPRESSION_THRESHOLD
+        {
+            return Ok(Cow::Borrowed(self.data));
+        }
+
+        let compressed = self.compression.compress(self.data)?;
+        if compressed.len() < self.data.len() {
+            Ok(Cow::Owned(compressed))
+        } else {
+            Ok(Cow::Borrowed(self.data))
+        }
+    }
 }
 
 /// A FrameHeader.
@@ -207,15 +437,32 @@ impl<'a> FrameRef<'a> {
 /// Each variant corresponds to the implementation for a given version.
 #[derive(Debug, PartialEq)]
 enum FrameHeader {
-    V1 { length: u32 },
+    V1 {
+        length: u32,
+    },
+    #[cfg(feature = "frame-v2")]
+    V2 {
+        flags: u16,
+        length: u64,
+    },
 }
 
+/// The bits of the version 2 header flags that specify the compression of the payload.
+#[cfg(feature = "frame-v2")]
+const COMPRESSION_FLAGS_MASK: u16 = 0x00ff;
+
 impl FrameHeader {
     /// Construct a version 1 frame header.
     fn v1(length: u32) -> Self {
         FrameHeader::V1 { length }
     }
 
+    /// Construct a version 2 frame header.
+    #[cfg(feature = "frame-v2")]
+    fn v2(flags: u16, length: u64) -> Self {
+        FrameHeader::V2 { flags, length }
+    }
+
     /// Read a FrameHeader from the given reader.
     ///
     /// This function uses the first 2 bytes of the stream to read the version, and constructs the
@@ -240,7 +487,10 @@ impl FrameHeader {
 
                 let n = reader.read(&mut cursor.get_mut()[std::mem::size_of::<u16>()..])?;
                 if n != HEADER_LENGTH + 1 - std::mem::size_of::<u16>() {
-                    return Err(FrameError::InvalidHeaderLength(n));
+                    return Err(FrameError::InvalidHeaderLength {
+                        expected: HEADER_LENGTH + 1 - std::mem::size_of::<u16>(),
+                        actual: n,
+                    });
                 }
 
                 let checksum = compute_checksum(&cursor.get_ref()[..HEADER_LENGTH]);
@@ -252,6 +502,31 @@ impl FrameHeader {
                     length: cursor.read_u32::<BigEndian>()?,
                 })
             }
+            #[cfg(feature = "frame-v2")]
+            2 => {
+                // Header length + checksum byte
+                let mut buffer = [0u8; HEADER_V2_LENGTH + 1];
+                let mut cursor = Cursor::new(&mut buffer[..]);
+                cursor.write_u16::<BigEndian>(2u16)?;
+
+                let n = reader.read(&mut cursor.get_mut()[std::mem::size_of::<u16>()..])?;
+                if n != HEADER_V2_LENGTH + 1 - std::mem::size_of::<u16>() {
+                    return Err(FrameError::InvalidHeaderLength {
+                        expected: HEADER_V2_LENGTH + 1 - std::mem::size_of::<u16>(),
+                        actual: n,
+                    });
+                }
+
+                let checksum = compute_checksum(&cursor.get_ref()[..HEADER_V2_LENGTH]);
+                if checksum != cursor.get_ref()[HEADER_V2_LENGTH] {
+                    return Err(FrameError::InvalidChecksum);
+                }
+
+                Ok(FrameHeader::V2 {
+                    flags: cursor.read_u16::<BigEndian>()?,
+                    length: cursor.read_u64::<BigEndian>()?,
+                })
+            }
             _ => Err(FrameError::UnsupportedVersion),
         }
     }
@@ -279,6 +554,20 @@ impl FrameHeader {
 
                 writer.write_all(&cursor.into_inner()[..])?;
             }
+            #[cfg(feature = "frame-v2")]
+            FrameHeader::V2 { flags, length } => {
+                let mut header_bytes = [0u8; HEADER_V2_LENGTH + 1];
+                let mut cursor = Cursor::new(&mut header_bytes[..]);
+
+                cursor.write_u16::<BigEndian>(2)?;
+                cursor.write_u16::<BigEndian>(flags)?;
+                cursor.write_u64::<BigEndian>(length)?;
+
+                cursor.get_mut()[HEADER_V2_LENGTH] =
+                    compute_checksum(&cursor.get_ref()[..HEADER_V2_LENGTH]);
+
+                writer.write_all(&cursor.into_inner()[..])?;
+            }
         }
 
         Ok(())
@@ -305,6 +594,11 @@ fn compute_checksum(buffer: &[u8]) -> u8 {
 }
 
 /// Negotiate the frame version for a given socket connection.
+///
+/// If the negotiated version supports compression, the compression used for frame payloads is
+/// negotiated as well: the outbound side transmits the set of compression algorithms it
+/// supports, and the inbound side replies with its preferred algorithm from that set, or `0` for
+/// no compression.
 pub enum FrameNegotiation {
     /// The Outbound variant transmits the min and max supported version, and expects to receive
     /// either a version in that range, or `0` if the other end cannot support the a version in
@@ -313,9 +607,12 @@ pub enum FrameNegotiation {
         min: FrameVersion,
         max: FrameVersion,
     },
-    /// The Inbound variant transmits receives the min and max and decides if it should send its
-    /// version or `0`, depending on whether or not it falls in the range.
-    Inbound { version: FrameVersion },
+    /// The Inbound variant receives the min and max and decides if it should send the highest
+    /// version it supports in that range, or `0`, if it supports none of them.
+    Inbound {
+        min: FrameVersion,
+        max: FrameVersion,
+    },
 }
 
 impl FrameNegotiation {
@@ -324,20 +621,25 @@ impl FrameNegotiation {
         FrameNegotiation::Outbound { min, max }
     }
 
-    /// Construct the inbound side of a negotiation with the given version.
-    pub fn inbound(version: FrameVersion) -> Self {
-        FrameNegotiation::Inbound { version }
+    /// Construct the inbound side of a negotiation with the given min,max.
+    pub fn inbound(min: FrameVersion, max: FrameVersion) -> Self {
+        FrameNegotiation::Inbound { min, max }
     }
 
-    /// Negotiate frame version to use for future communications over the given stream.
+    /// Negotiate frame version and compression to use for future communications over the given
+    /// stream.
     ///
     /// # Errors
     ///
     /// Returns a FrameError if:
     ///
     /// - either end cannot agree on a version
+    /// - the other end selects an unknown compression algorithm
     /// - an IO error, if one occurs
-    pub fn negotiate<S: Read + Write>(self, stream: &mut S) -> Result<FrameVersion, FrameError> {
+    pub fn negotiate<S: Read + Write>(
+        self,
+        stream: &mut S,
+    ) -> Result<(FrameVersion, FrameCompression), FrameError> {
         match self {
             FrameNegotiation::Outbound { min, max } => {
                 stream
@@ -349,28 +651,76 @@ impl FrameNegotiation {
 
                 let frame_version = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
 
-                match frame_version {
-                    0 => Err(FrameError::UnsupportedVersion),
-                    1 => Ok(FrameVersion::V1),
+                match FrameVersion::from_u16(frame_version) {
+                    Some(version) if min as u16 <= frame_version && frame_version <= max as u16 => {
+                        Self::outbound_compression(version, stream)
+                            .map(|compression| (version, compression))
+                    }
                     _ => Err(FrameError::UnsupportedVersion),
                 }
             }
-            FrameNegotiation::Inbound { version } => {
-                let min = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
-                let max = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
-                if min > version as u16 || max < version as u16 {
-                    stream.write_u16::<BigEndian>(0).map_err(Self::map_io_err)?;
-                    Err(FrameError::UnsupportedVersion)
-                } else {
-                    stream
-                        .write_u16::<BigEndian>(version as u16)
-                        .map_err(Self::map_io_err)?;
-                    Ok(version)
+            FrameNegotiation::Inbound { min, max } => {
+                let remote_min = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
+                let remote_max = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
+
+                let lowest = remote_min.max(min as u16);
+                let highest = remote_max.min(max as u16);
+                match (lowest..=highest).rev().find_map(FrameVersion::from_u16) {
+                    Some(version) => {
+                        stream
+                            .write_u16::<BigEndian>(version as u16)
+                            .map_err(Self::map_io_err)?;
+                        Self::inbound_compression(version, stream)
+                            .map(|compression| (version, compression))
+                    }
+                    None => {
+                        stream.write_u16::<BigEndian>(0).map_err(Self::map_io_err)?;
+                        Err(FrameError::UnsupportedVersion)
+                    }
                 }
             }
         }
     }
 
+    #[cfg_attr(not(feature = "frame-v2"), allow(unused_variables))]
+    fn outbound_compression<S: Read + Write>(
+        version: FrameVersion,
+        stream: &mut S,
+    ) -> Result<FrameCompression, FrameError> {
+        match version {
+            FrameVersion::V1 => Ok(FrameCompression::None),
+            #[cfg(feature = "frame-v2")]
+            FrameVersion::V2 => {
+                stream
+                    .write_u16::<BigEndian>(FrameCompression::supported_bits())
+                    .map_err(Self::map_io_err)?;
+
+                let compression = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
+                FrameCompression::from_u16(compression)
+            }
+        }
+    }
+
+    #[cfg_attr(not(feature = "frame-v2"), allow(unused_variables))]
+    fn inbound_compression<S: Read + Write>(
+        version: FrameVersion,
+        stream: &mut S,
+    ) -> Result<FrameCompression, FrameError> {
+        match version {
+            FrameVersion::V1 => Ok(FrameCompression::None),
+            #[cfg(feature = "frame-v2")]
+            FrameVersion::V2 => {
+                let remote_bits = stream.read_u16::<BigEndian>().map_err(Self::map_io_err)?;
+
+                let compression = FrameCompression::preferred(remote_bits);
+                stream
+                    .write_u16::<BigEndian>(compression as u16)
+                    .map_err(Self::map_io_err)?;
+                Ok(compression)
+            }
+        }
+    }
+
     fn map_io_err(err: io::Error) -> FrameError {
         use io::ErrorKind::*;
         match err.kind() {
@@ -498,10 +848,10 @@ mod tests {
             .expect("Unable to write frame header");
 
         header_cursor.set_position(0);
-        let FrameHeader::V1 { length } =
-            FrameHeader::read(&mut header_cursor).expect("Unable to read header");
-
-        assert_eq!(100, length);
+        assert_eq!(
+            FrameHeader::v1(100),
+            FrameHeader::read(&mut header_cursor).expect("Unable to read header")
+        );
     }
 
     /// Test that outbound frame version negotiation works:
@@ -515,7 +865,7 @@ mod tests {
 
         let (done_tx, done_rx) = std::sync::mpsc::channel();
         let join_handle = thread::spawn(move || {
-            let res = FrameNegotiation::inbound(FrameVersion::V1)
+            let res = FrameNegotiation::inbound(FrameVersion::V1, FrameVersion::V1)
                 .negotiate(&mut rx)
                 .expect("Should have successfully negotiated");
 
@@ -528,13 +878,93 @@ mod tests {
             .negotiate(&mut tx)
             .expect("Unable to negotiate a valid version");
 
-        assert_eq!(FrameVersion::V1, version);
+        assert_eq!((FrameVersion::V1, FrameCompression::None), version);
+
+        done_tx.send(1u8).expect("unable to send stop signal");
+
+        let remote_res = join_handle.join().expect("Unable to join thread");
+
+        assert_eq!((FrameVersion::V1, FrameCompression::None), remote_res);
+    }
+
+    /// Test that version 2 and compression are negotiated when both ends support them:
+    /// 1. Create a stream pair
+    /// 2. Send one end to a thread, to act as the inbound receiver, supporting V1 through V2
+    /// 3. Create an outbound negotiation for V1 through V2 and execute it on the stream
+    /// 4. Verify that both ends agree on V2 with deflate compression.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn v2_outbound_negotiation() {
+        let (mut tx, mut rx) = stream::byte_stream_pair();
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let join_handle = thread::spawn(move || {
+            let res = FrameNegotiation::inbound(FrameVersion::V1, FrameVersion::V2)
+                .negotiate(&mut rx)
+                .expect("Should have successfully negotiated");
+
+            done_rx.recv().unwrap();
+
+            res
+        });
+
+        let version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::V2)
+            .negotiate(&mut tx)
+            .expect("Unable to negotiate a valid version");
+
+        assert_eq!((FrameVersion::V2, FrameCompression::Deflate), version);
 
         done_tx.send(1u8).expect("unable to send stop signal");
 
         let remote_res = join_handle.join().expect("Unable to join thread");
 
-        assert_eq!(FrameVersion::V1, remote_res);
+        assert_eq!((FrameVersion::V2, FrameCompression::Deflate), remote_res);
+    }
+
+    /// Test that a version 2 end still interoperates with a version 1 end, in either direction:
+    /// 1. Negotiate between a V1 through V2 outbound end and a V1 inbound end
+    /// 2. Negotiate between a V1 outbound end and a V1 through V2 inbound end
+    /// 3. Verify that both negotiations agree on V1 without compression.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn v2_v1_negotiation() {
+        let ranges = vec![
+            (
+                (FrameVersion::V1, FrameVersion::V2),
+                (FrameVersion::V1, FrameVersion::V1),
+            ),
+            (
+                (FrameVersion::V1, FrameVersion::V1),
+                (FrameVersion::V1, FrameVersion::V2),
+            ),
+        ];
+
+        for ((out_min, out_max), (in_min, in_max)) in ranges {
+            let (mut tx, mut rx) = stream::byte_stream_pair();
+
+            let (done_tx, done_rx) = std::sync::mpsc::channel();
+            let join_handle = thread::spawn(move || {
+                let res = FrameNegotiation::inbound(in_min, in_max)
+                    .negotiate(&mut rx)
+                    .expect("Should have successfully negotiated");
+
+                done_rx.recv().unwrap();
+
+                res
+            });
+
+            let version = FrameNegotiation::outbound(out_min, out_max)
+                .negotiate(&mut tx)
+                .expect("Unable to negotiate a valid version");
+
+            assert_eq!((FrameVersion::V1, FrameCompression::None), version);
+
+            done_tx.send(1u8).expect("unable to send stop signal");
+
+            let remote_res = join_handle.join().expect("Unable to join thread");
+
+            assert_eq!((FrameVersion::V1, FrameCompression::None), remote_res);
+        }
     }
 
     /// Test that outbound frame version negotiation works:
@@ -586,7 +1016,7 @@ mod tests {
             done_rx.recv().unwrap();
         });
 
-        let res = FrameNegotiation::inbound(FrameVersion::V1).negotiate(&mut tx);
+        let res = FrameNegotiation::inbound(FrameVersion::V1, FrameVersion::V1).negotiate(&mut tx);
 
         done_tx.send(1u8).expect("Unable to send stop signal");
 
@@ -626,7 +1056,7 @@ mod tests {
             res
         });
 
-        let res = FrameNegotiation::inbound(FrameVersion::V1).negotiate(&mut tx);
+        let res = FrameNegotiation::inbound(FrameVersion::V1, FrameVersion::V1).negotiate(&mut tx);
 
         done_tx.send(1u8).expect("Unable to send stop signal");
 
@@ -676,6 +1106,119 @@ mod tests {
         assert_eq!(input.to_vec(), frame.data);
     }
 
+    /// Test that a version 2 frame whose header length exceeds the maximum frame length is
+    /// rejected before its payload is read.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn read_frame_too_large() {
+        let mut cursor = Cursor::new(vec![]);
+        FrameHeader::v2(0, MAX_FRAME_LENGTH as u64 + 1)
+            .write(&mut cursor)
+            .expect("Unable to write header");
+
+        cursor.set_position(0);
+
+        match Frame::read(&mut cursor) {
+            Err(FrameError::FrameTooLarge { max, actual }) => {
+                assert_eq!(MAX_FRAME_LENGTH, max);
+                assert_eq!(MAX_FRAME_LENGTH as u64 + 1, actual);
+            }
+            res => panic!("Unexpected result: {:?}", res.map(|frame| frame.data)),
+        }
+    }
+
+    /// Test that a version 1 frame is not held to the maximum frame length: a header length
+    /// beyond it is read as usual, failing only because the payload is missing.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn read_v1_frame_over_max_length() {
+        let mut cursor = Cursor::new(vec![]);
+        FrameHeader::v1(MAX_FRAME_LENGTH as u32 + 1)
+            .write(&mut cursor)
+            .expect("Unable to write header");
+
+        cursor.set_position(0);
+
+        match Frame::read(&mut cursor) {
+            Err(FrameError::IoError(err)) => {
+                assert_eq!(io::ErrorKind::UnexpectedEof, err.kind())
+            }
+            res => panic!("Unexpected result: {:?}", res.map(|frame| frame.data)),
+        }
+    }
+
+    /// Test that a compressed payload that decompresses to more than the maximum length is
+    /// rejected.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn decompress_too_large() {
+        let input = vec![0u8; 4096];
+        let compressed = FrameCompression::Deflate
+            .compress(&input)
+            .expect("Unable to compress data");
+
+        assert_eq!(
+            input,
+            FrameCompression::Deflate
+                .decompress(compressed.clone(), input.len())
+                .expect("Unable to decompress data")
+        );
+
+        match FrameCompression::Deflate.decompress(compressed, input.len() - 1) {
+            Err(FrameError::CompressionError(_)) => (),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    /// Test a round-trip write and read of a version 2 FrameHeader.
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn round_trip_v2() {
+        let header_bytes = vec![0u8; HEADER_V2_LENGTH + 1];
+        let mut header_cursor = Cursor::new(header_bytes);
+
+        let frame_header = FrameHeader::v2(1, u64::from(u32::MAX) + 1);
+
+        frame_header
+            .write(&mut header_cursor)
+            .expect("Unable to write frame header");
+
+        header_cursor.set_position(0);
+        assert_eq!(
+            FrameHeader::v2(1, u64::from(u32::MAX) + 1),
+            FrameHeader::read(&mut header_cursor).expect("Unable to read header")
+        );
+    }
+
+    /// Write version 2 frames to a stream and verify that equivalent frames are read back from
+    /// the stream:
+    /// 1. A small frame, which is not compressed
+    /// 2. A large, compressible frame, which is written compressed
+    #[cfg(feature = "frame-v2")]
+    #[test]
+    fn frame_round_trip_v2() {
+        let small_input = b"hello world".to_vec();
+        let large_input = b"hello world".repeat(1000);
+
+        for input in vec![small_input, large_input] {
+            let frame_ref =
+                FrameRef::new(FrameVersion::V2, &input).with_compression(FrameCompression::Deflate);
+
+            let mut cursor = Cursor::new(vec![]);
+
+            frame_ref.write(&mut cursor).expect("Unable to write data");
+
+            let compressed = cursor.get_ref().len() < input.len();
+            assert_eq!(input.len() >= COMPRESSION_THRESHOLD, compressed);
+
+            cursor.set_position(0);
+
+            let frame = Frame::read(&mut cursor).expect("Unable to read frame");
+
+            assert_eq!(input, frame.data);
+        }
+    }
+
     #[cfg(not(target_os = "unix"))]
     mod stream {
         use std::io::{Error as IoError, Read, Write};
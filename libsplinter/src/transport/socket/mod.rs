@@ -15,9 +15,13 @@
 mod frame;
 mod tcp;
 mod tls;
+#[cfg(feature = "unix-transport")]
+mod unix;
 
 pub use tcp::TcpTransport;
 pub use tls::{TlsConnection, TlsInitError, TlsTransport};
+#[cfg(feature = "unix-transport")]
+pub use unix::UnixTransport;
 
 #[cfg(test)]
 pub mod tests {
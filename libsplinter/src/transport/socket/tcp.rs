@@ -21,7 +21,7 @@ use crate::transport::{
     SendError, Transport,
 };
 
-use super::frame::{Frame, FrameError, FrameNegotiation, FrameRef, FrameVersion};
+use super::frame::{Frame, FrameCompression, FrameError, FrameNegotiation, FrameRef, FrameVersion};
 
 const PROTOCOL_PREFIX: &str = "tcp://";
 
@@ -49,19 +49,21 @@ impl Transport for TcpTransport {
         // Connect a std::net::TcpStream to make sure connect() block
         let mut stream = TcpStream::connect(address)?;
 
-        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::V1)
-            .negotiate(&mut stream)
-            .map_err(|err| match err {
-                FrameError::UnsupportedVersion => ConnectError::ProtocolError(
-                    "Unable to connect; remote version is not with in range".into(),
-                ),
-                FrameError::IoError(err) => ConnectError::from(err),
-                e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
-            })?;
+        let (frame_version, frame_compression) =
+            FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::latest())
+                .negotiate(&mut stream)
+                .map_err(|err| match err {
+                    FrameError::UnsupportedVersion => ConnectError::ProtocolError(
+                        "Unable to connect; remote version is not with in range".into(),
+                    ),
+                    FrameError::IoError(err) => ConnectError::from(err),
+                    e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
+                })?;
 
         let mio_stream = MioTcpStream::from_stream(stream)?;
         Ok(Box::new(TcpConnection {
             frame_version,
+            frame_compression,
             stream: mio_stream,
         }))
     }
@@ -96,20 +98,24 @@ impl Listener for TcpListener {
     fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
         let (mut stream, _) = self.listener.accept()?;
 
-        let frame_version = FrameNegotiation::inbound(FrameVersion::V1)
-            .negotiate(&mut stream)
-            .map_err(|err| match err {
-                FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
-                    "Local {} protocol version {} not supported by remote",
-                    PROTOCOL_PREFIX,
-                    FrameVersion::V1
-                )),
-                FrameError::IoError(err) => AcceptError::from(err),
-                err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
-            })?;
+        let (frame_version, frame_compression) =
+            FrameNegotiation::inbound(FrameVersion::V1, FrameVersion::latest())
+                .negotiate(&mut stream)
+                .map_err(|err| match err {
+                    FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
+                        "Local {} protocol version {} not supported by remote",
+                        PROTOCOL_PREFIX,
+                        FrameVersion::latest()
+                    )),
+                    FrameError::IoError(err) => AcceptError::from(err),
+                    err => {
+                        AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err))
+                    }
+                })?;
 
         let connection = TcpConnection {
             frame_version,
+            frame_compression,
             stream: MioTcpStream::from_stream(stream)?,
         };
         Ok(Box::new(connection))
@@ -122,12 +128,16 @@ impl Listener for TcpListener {
 
 struct TcpConnection {
     frame_version: FrameVersion,
+    frame_compression: FrameCompression,
     stream: MioTcpStream,
 }
 
 impl Connection for TcpConnection {
     fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
-        match FrameRef::new(self.frame_version, message).write(&mut self.stream) {
+        match FrameRef::new(self.frame_version, message)
+            .with_compression(self.frame_compression)
+            .write(&mut self.stream)
+        {
             Err(FrameError::IoError(e)) => Err(SendError::from(e)),
             Err(err) => Err(SendError::ProtocolError(err.to_string())),
             Ok(_) => Ok(()),
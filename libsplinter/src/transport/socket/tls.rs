@@ -18,6 +18,8 @@ use openssl::ssl::{
     Error as OpensslError, HandshakeError, SslAcceptor, SslConnector, SslFiletype, SslMethod,
     SslStream, SslVerifyMode,
 };
+#[cfg(feature = "tls-peer-identity")]
+use openssl::x509::X509VerifyResult;
 use url::{ParseError, Url};
 
 use std::error::Error;
@@ -32,7 +34,7 @@ use crate::transport::{
     SendError, Transport,
 };
 
-use super::frame::{Frame, FrameError, FrameNegotiation, FrameRef, FrameVersion};
+use super::frame::{Frame, FrameCompression, FrameError, FrameNegotiation, FrameRef, FrameVersion};
 
 /// tls:// is deprecated, tcps:// should be used instead
 const DEPRECATED_PROTOCOL_PREFIX: &str = "tls://";
@@ -136,19 +138,21 @@ impl Transport for TlsTransport {
         let stream = TcpStream::connect(address)?;
         let mut tls_stream = self.connector.connect(&dns_name, stream)?;
 
-        let frame_version = FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::V1)
-            .negotiate(&mut tls_stream)
-            .map_err(|err| match err {
-                FrameError::UnsupportedVersion => ConnectError::ProtocolError(
-                    "Unable to connect; remote version is not with in range".into(),
-                ),
-                FrameError::IoError(err) => ConnectError::from(err),
-                e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
-            })?;
+        let (frame_version, frame_compression) =
+            FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::latest())
+                .negotiate(&mut tls_stream)
+                .map_err(|err| match err {
+                    FrameError::UnsupportedVersion => ConnectError::ProtocolError(
+                        "Unable to connect; remote version is not with in range".into(),
+                    ),
+                    FrameError::IoError(err) => ConnectError::from(err),
+                    e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
+                })?;
 
         tls_stream.get_ref().set_nonblocking(true)?;
         let connection = TlsConnection {
             frame_version,
+            frame_compression,
             stream: tls_stream,
         };
         Ok(Box::new(connection))
@@ -189,21 +193,25 @@ impl Listener for TlsListener {
         let (stream, _) = self.listener.accept()?;
         let mut tls_stream = self.acceptor.accept(stream)?;
 
-        let frame_version = FrameNegotiation::inbound(FrameVersion::V1)
-            .negotiate(&mut tls_stream)
-            .map_err(|err| match err {
-                FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
-                    "Local {} protocol version {} not supported by remote",
-                    PROTOCOL_PREFIX,
-                    FrameVersion::V1
-                )),
-                FrameError::IoError(err) => AcceptError::from(err),
-                err => AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err)),
-            })?;
+        let (frame_version, frame_compression) =
+            FrameNegotiation::inbound(FrameVersion::V1, FrameVersion::latest())
+                .negotiate(&mut tls_stream)
+                .map_err(|err| match err {
+                    FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
+                        "Local {} protocol version {} not supported by remote",
+                        PROTOCOL_PREFIX,
+                        FrameVersion::latest()
+                    )),
+                    FrameError::IoError(err) => AcceptError::from(err),
+                    err => {
+                        AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err))
+                    }
+                })?;
 
         tls_stream.get_ref().set_nonblocking(true)?;
         let connection = TlsConnection {
             frame_version,
+            frame_compression,
             stream: tls_stream,
         };
         Ok(Box::new(connection))
@@ -216,12 +224,16 @@ impl Listener for TlsListener {
 
 pub struct TlsConnection {
     frame_version: FrameVersion,
+    frame_compression: FrameCompression,
     stream: SslStream<TcpStream>,
 }
 
 impl Connection for TlsConnection {
     fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
-        match FrameRef::new(self.frame_version, message).write(&mut self.stream) {
+        match FrameRef::new(self.frame_version, message)
+            .with_compression(self.frame_compression)
+            .write(&mut self.stream)
+        {
             Err(FrameError::IoError(e)) => Err(SendError::from(e)),
             Err(err) => Err(SendError::ProtocolError(err.to_string())),
             Ok(_) => Ok(()),
@@ -253,6 +265,18 @@ impl Connection for TlsConnection {
     fn evented(&self) -> &dyn Evented {
         self
     }
+
+    #[cfg(feature = "tls-peer-identity")]
+    fn peer_certificate(&self) -> Option<Vec<u8>> {
+        let ssl = self.stream.ssl();
+        if !ssl.verify_mode().contains(SslVerifyMode::PEER)
+            || ssl.verify_result() != X509VerifyResult::OK
+        {
+            return None;
+        }
+
+        ssl.peer_certificate().and_then(|cert| cert.to_der().ok())
+    }
 }
 
 impl TlsConnection {
@@ -264,6 +288,7 @@ impl TlsConnection {
     pub fn new(stream: SslStream<TcpStream>) -> Self {
         TlsConnection {
             frame_version: FrameVersion::V1,
+            frame_compression: FrameCompression::None,
             stream,
         }
     }
@@ -457,6 +482,24 @@ pub(crate) mod tests {
         tests::test_transport(transport, "127.0.0.1:0");
     }
 
+    // Test that connections only provide the peer certificate if it was verified against the CA.
+    #[cfg(feature = "tls-peer-identity")]
+    #[test]
+    fn test_peer_certificate() {
+        for &(insecure, verified) in &[(false, true), (true, false)] {
+            let mut transport = create_test_tls_transport(insecure);
+            let mut listener = transport.listen("127.0.0.1:0").unwrap();
+            let endpoint = listener.endpoint();
+
+            let handle = std::thread::spawn(move || transport.connect(&endpoint).unwrap());
+            let inbound = listener.accept().unwrap();
+            let outbound = handle.join().unwrap();
+
+            assert_eq!(verified, inbound.peer_certificate().is_some());
+            assert_eq!(verified, outbound.peer_certificate().is_some());
+        }
+    }
+
     #[cfg(not(unix))]
     #[test]
     fn test_poll_no_verify() {
@@ -0,0 +1,377 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use mio::{unix::EventedFd, Evented, Poll, PollOpt, Ready, Token};
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::net::Shutdown;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener as StdUnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+use crate::transport::{
+    AcceptError, ConnectError, Connection, DisconnectError, ListenError, Listener, RecvError,
+    SendError, Transport,
+};
+
+use super::frame::{Frame, FrameCompression, FrameError, FrameNegotiation, FrameRef, FrameVersion};
+
+const PROTOCOL_PREFIX: &str = "unix://";
+
+/// A Transport for Unix domain sockets.
+///
+/// Endpoints are specified as `unix://` followed by the path of the socket file, such as
+/// `unix:///var/run/splinter/service.sock`.  Access to the socket is controlled by the
+/// permissions of the socket file and its directory.
+#[derive(Default)]
+pub struct UnixTransport {
+    permissions: Option<u32>,
+}
+
+impl UnixTransport {
+    /// Set the permissions of the socket files created by listeners, such as `0o660`.
+    ///
+    /// If not set, the socket files are created with the permissions allowed by the process's
+    /// umask.
+    pub fn with_permissions(mut self, mode: u32) -> Self {
+        self.permissions = Some(mode);
+        self
+    }
+}
+
+impl Transport for UnixTransport {
+    fn accepts(&self, address: &str) -> bool {
+        address.starts_with(PROTOCOL_PREFIX)
+    }
+
+    fn connect(&mut self, endpoint: &str) -> Result<Box<dyn Connection>, ConnectError> {
+        if !self.accepts(endpoint) {
+            return Err(ConnectError::ProtocolError(format!(
+                "Invalid protocol \"{}\"",
+                endpoint
+            )));
+        }
+
+        let path = &endpoint[PROTOCOL_PREFIX.len()..];
+        let mut stream = UnixStream::connect(path)?;
+
+        let (frame_version, frame_compression) =
+            FrameNegotiation::outbound(FrameVersion::V1, FrameVersion::latest())
+                .negotiate(&mut stream)
+                .map_err(|err| match err {
+                    FrameError::UnsupportedVersion => ConnectError::ProtocolError(
+                        "Unable to connect; remote version is not with in range".into(),
+                    ),
+                    FrameError::IoError(err) => ConnectError::from(err),
+                    e => ConnectError::ProtocolError(format!("Unexpected protocol error: {}", e)),
+                })?;
+
+        stream.set_nonblocking(true)?;
+        Ok(Box::new(UnixConnection {
+            frame_version,
+            frame_compression,
+            remote_endpoint: endpoint.to_string(),
+            local_endpoint: PROTOCOL_PREFIX.to_string(),
+            stream,
+        }))
+    }
+
+    fn listen(&mut self, bind: &str) -> Result<Box<dyn Listener>, ListenError> {
+        if !self.accepts(bind) {
+            return Err(ListenError::ProtocolError(format!(
+                "Invalid protocol \"{}\"",
+                bind
+            )));
+        }
+
+        let path = PathBuf::from(&bind[PROTOCOL_PREFIX.len()..]);
+
+        // A socket file left behind by a previous listener would prevent binding, but one that
+        // still accepts connections belongs to a running listener and must be left alone
+        if let Ok(metadata) = fs::symlink_metadata(&path) {
+            if metadata.file_type().is_socket() {
+                if UnixStream::connect(&path).is_ok() {
+                    return Err(ListenError::IoError(
+                        format!("Failed to bind to {}", bind),
+                        io::Error::new(
+                            io::ErrorKind::AddrInUse,
+                            "another listener is accepting connections on the socket",
+                        ),
+                    ));
+                }
+                fs::remove_file(&path).map_err(|err| {
+                    ListenError::IoError(format!("Failed to remove stale socket {}", bind), err)
+                })?;
+            }
+        }
+
+        let listener = match self.permissions {
+            Some(mode) => bind_with_permissions(&path, mode),
+            None => StdUnixListener::bind(&path),
+        }
+        .map_err(|err| ListenError::IoError(format!("Failed to bind to {}", bind), err))?;
+
+        Ok(Box::new(UnixListener { listener, path }))
+    }
+}
+
+/// Bind a listener to the given path, with the socket file given the specified permissions.
+///
+/// The socket is bound inside a directory that only the current user can access, and is moved to
+/// the given path once its permissions have been set, so that it can never be connected to with
+/// broader permissions than requested.
+fn bind_with_permissions(path: &Path, mode: u32) -> io::Result<StdUnixListener> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "socket path has no file name")
+    })?;
+
+    let mut private_dir_name = OsString::from(".");
+    private_dir_name.push(file_name);
+    private_dir_name.push(format!(".{}", std::process::id()));
+    let private_dir = path.with_file_name(private_dir_name);
+    fs::DirBuilder::new().mode(0o700).create(&private_dir)?;
+
+    let private_path = private_dir.join(file_name);
+    let result = StdUnixListener::bind(&private_path).and_then(|listener| {
+        fs::set_permissions(&private_path, fs::Permissions::from_mode(mode))?;
+        fs::rename(&private_path, path)?;
+        Ok(listener)
+    });
+
+    if result.is_err() {
+        let _ = fs::remove_file(&private_path);
+    }
+    let _ = fs::remove_dir(&private_dir);
+
+    result
+}
+
+struct UnixListener {
+    listener: StdUnixListener,
+    path: PathBuf,
+}
+
+impl Listener for UnixListener {
+    fn accept(&mut self) -> Result<Box<dyn Connection>, AcceptError> {
+        let (mut stream, _) = self.listener.accept()?;
+
+        let (frame_version, frame_compression) =
+            FrameNegotiation::inbound(FrameVersion::V1, FrameVersion::latest())
+                .negotiate(&mut stream)
+                .map_err(|err| match err {
+                    FrameError::UnsupportedVersion => AcceptError::ProtocolError(format!(
+                        "Local {} protocol version {} not supported by remote",
+                        PROTOCOL_PREFIX,
+                        FrameVersion::latest()
+                    )),
+                    FrameError::IoError(err) => AcceptError::from(err),
+                    err => {
+                        AcceptError::ProtocolError(format!("Unexpected protocol error: {}", err))
+                    }
+                })?;
+
+        stream.set_nonblocking(true)?;
+
+        // The connecting end of a Unix socket is unnamed, so inbound connections are identified by
+        // the listener's endpoint and the connection's file descriptor, which is unique among the
+        // open connections.
+        Ok(Box::new(UnixConnection {
+            frame_version,
+            frame_compression,
+            remote_endpoint: format!("{}#{}", self.endpoint(), stream.as_raw_fd()),
+            local_endpoint: self.endpoint(),
+            stream,
+        }))
+    }
+
+    fn endpoint(&self) -> String {
+        format!("{}{}", PROTOCOL_PREFIX, self.path.display())
+    }
+}
+
+impl Drop for UnixListener {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            debug!("Unable to remove socket {}: {}", self.path.display(), err);
+        }
+    }
+}
+
+struct UnixConnection {
+    frame_version: FrameVersion,
+    frame_compression: FrameCompression,
+    remote_endpoint: String,
+    local_endpoint: String,
+    stream: UnixStream,
+}
+
+impl Connection for UnixConnection {
+    fn send(&mut self, message: &[u8]) -> Result<(), SendError> {
+        match FrameRef::new(self.frame_version, message)
+            .with_compression(self.frame_compression)
+            .write(&mut self.stream)
+        {
+            Err(FrameError::IoError(e)) => Err(SendError::from(e)),
+            Err(err) => Err(SendError::ProtocolError(err.to_string())),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    fn recv(&mut self) -> Result<Vec<u8>, RecvError> {
+        match Frame::read(&mut self.stream) {
+            Err(FrameError::IoError(e)) => Err(RecvError::from(e)),
+            Err(err) => Err(RecvError::ProtocolError(err.to_string())),
+            Ok(frame) => Ok(frame.into_inner()),
+        }
+    }
+
+    fn remote_endpoint(&self) -> String {
+        self.remote_endpoint.clone()
+    }
+
+    fn local_endpoint(&self) -> String {
+        self.local_endpoint.clone()
+    }
+
+    fn disconnect(&mut self) -> Result<(), DisconnectError> {
+        self.stream
+            .shutdown(Shutdown::Both)
+            .map_err(DisconnectError::from)
+    }
+
+    fn evented(&self) -> &dyn Evented {
+        self
+    }
+}
+
+impl AsRawFd for UnixConnection {
+    fn as_raw_fd(&self) -> RawFd {
+        self.stream.as_raw_fd()
+    }
+}
+
+impl Evented for UnixConnection {
+    fn register(
+        &self,
+        poll: &Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(poll, token, interest, opts)
+    }
+
+    fn reregister(
+        &self,
+        poll: &Poll,
+        token: Token,
+        interest: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(poll, token, interest, opts)
+    }
+
+    fn deregister(&self, poll: &Poll) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(poll)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::tests;
+
+    use tempdir::TempDir;
+
+    fn socket_endpoint(temp_dir: &TempDir) -> String {
+        format!(
+            "{}{}",
+            PROTOCOL_PREFIX,
+            temp_dir.path().join("test.sock").display()
+        )
+    }
+
+    #[test]
+    fn test_accepts() {
+        let transport = UnixTransport::default();
+        assert!(transport.accepts("unix:///var/run/splinter/service.sock"));
+
+        assert!(!transport.accepts("/var/run/splinter/service.sock"));
+        assert!(!transport.accepts("tcp://127.0.0.1:0"));
+        assert!(!transport.accepts("127.0.0.1:0"));
+    }
+
+    #[test]
+    fn test_transport() {
+        let temp_dir = TempDir::new("unix-transport-test").unwrap();
+        let transport = UnixTransport::default();
+
+        tests::test_transport(transport, &socket_endpoint(&temp_dir));
+    }
+
+    #[test]
+    fn test_poll() {
+        let temp_dir = TempDir::new("unix-transport-test").unwrap();
+        let transport = UnixTransport::default();
+
+        tests::test_poll(transport, &socket_endpoint(&temp_dir));
+    }
+
+    /// Test that the socket file of a listener:
+    ///
+    /// 1. is created with the configured permissions
+    /// 2. replaces a stale socket file at the same path
+    /// 3. accepts connections
+    /// 4. is not replaced by a second listener on the same path
+    /// 5. is removed when the listener is dropped
+    #[test]
+    fn test_socket_file() {
+        let temp_dir = TempDir::new("unix-transport-test").unwrap();
+        let endpoint = socket_endpoint(&temp_dir);
+        let path = temp_dir.path().join("test.sock");
+
+        // leave a stale socket file behind
+        let stale = StdUnixListener::bind(&path).unwrap();
+        drop(stale);
+        assert!(path.exists());
+
+        let mut transport = UnixTransport::default().with_permissions(0o600);
+        let listener = transport.listen(&endpoint).unwrap();
+
+        let metadata = fs::metadata(&path).unwrap();
+        assert!(metadata.file_type().is_socket());
+        assert_eq!(0o600, metadata.permissions().mode() & 0o777);
+        assert_eq!(
+            vec![path.file_name().unwrap().to_os_string()],
+            fs::read_dir(temp_dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect::<Vec<_>>()
+        );
+
+        assert!(UnixStream::connect(&path).is_ok());
+
+        match transport.listen(&endpoint) {
+            Err(ListenError::IoError(_, err)) => assert_eq!(io::ErrorKind::AddrInUse, err.kind()),
+            res => panic!("Unexpected result: {:?}", res.map(|_| ())),
+        }
+        assert!(path.exists());
+
+        drop(listener);
+        assert!(!path.exists());
+    }
+}
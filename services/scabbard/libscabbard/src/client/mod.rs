@@ -86,24 +86,27 @@ impl ScabbardClient {
     }
 
     /// Get the value at the given `address` in state for the scabbard instance with the given
-    /// `service_id`. Returns `None` if there is no entry at the given address.
+    /// `service_id`. Returns `None` if there is no entry at the given address. If a `state_root`
+    /// is given, the value is read from state at that root instead of the current state root.
     ///
     /// # Errors
     ///
     /// Returns an error in any of the following cases:
     /// * The client's URL was invalid
     /// * The given address is not a valid hex address
+    /// * The given `state_root` is not a valid hex state root or is unknown to the service
     /// * The REST API request failed
     /// * An internal server error occurred in the scabbard service
     pub fn get_state_at_address(
         &self,
         service_id: &ServiceId,
         address: &str,
+        state_root: Option<&str>,
     ) -> Result<Option<Vec<u8>>, ScabbardClientError> {
         parse_hex(address)
             .map_err(|err| ScabbardClientError::new_with_source("invalid address", err.into()))?;
 
-        let url = Url::parse(&format!(
+        let mut url = Url::parse(&format!(
             "{}/scabbard/{}/{}/state/{}",
             &self.url,
             service_id.circuit(),
@@ -111,6 +114,12 @@ impl ScabbardClient {
             address
         ))
         .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+        if let Some(state_root) = state_root {
+            parse_hex(state_root).map_err(|err| {
+                ScabbardClientError::new_with_source("invalid state root", err.into())
+            })?;
+            url.set_query(Some(&format!("state_root={}", state_root)))
+        }
 
         let request = Client::new().get(url);
         let response = request
@@ -143,19 +152,22 @@ impl ScabbardClient {
     }
 
     /// Get all entries under the given address `prefix` in state for the scabbard instance with
-    /// the given `service_id`.
+    /// the given `service_id`. If a `state_root` is given, the entries are read from state at that
+    /// root instead of the current state root.
     ///
     /// # Errors
     ///
     /// Returns an error in any of the following cases:
     /// * The client's URL was invalid
     /// * The given `prefix` is not a valid hex address prefix
+    /// * The given `state_root` is not a valid hex state root or is unknown to the service
     /// * The REST API request failed
     /// * An internal server error occurred in the scabbard service
     pub fn get_state_with_prefix(
         &self,
         service_id: &ServiceId,
         prefix: Option<&str>,
+        state_root: Option<&str>,
     ) -> Result<Vec<StateEntry>, ScabbardClientError> {
         let mut url = Url::parse(&format!(
             "{}/scabbard/{}/{}/state",
@@ -164,6 +176,7 @@ impl ScabbardClient {
             service_id.service_id()
         ))
         .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+        let mut query = vec![];
         if let Some(prefix) = prefix {
             parse_hex(prefix).map_err(|err| {
                 ScabbardClientError::new_with_source("invalid prefix", err.into())
@@ -173,7 +186,16 @@ impl ScabbardClient {
                     "prefix must be less than 70 characters",
                 ));
             }
-            url.set_query(Some(&format!("prefix={}", prefix)))
+            query.push(format!("prefix={}", prefix));
+        }
+        if let Some(state_root) = state_root {
+            parse_hex(state_root).map_err(|err| {
+                ScabbardClientError::new_with_source("invalid state root", err.into())
+            })?;
+            query.push(format!("state_root={}", state_root));
+        }
+        if !query.is_empty() {
+            url.set_query(Some(&query.join("&")));
         }
 
         let request = Client::new().get(url);
@@ -244,6 +266,186 @@ impl ScabbardClient {
             )))
         }
     }
+
+    /// List the most recently committed state roots of the scabbard instance with the given
+    /// `service_id`, newest first, with the IDs of the batches that produced them. Up to `limit`
+    /// roots are returned, starting with the root at `offset`; if not given, the REST API's
+    /// defaults are used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The client's URL was invalid
+    /// * The REST API request failed
+    /// * An internal server error occurred in the scabbard service
+    pub fn list_state_roots(
+        &self,
+        service_id: &ServiceId,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<Vec<CommittedRoot>, ScabbardClientError> {
+        let mut url = Url::parse(&format!(
+            "{}/scabbard/{}/{}/state_roots",
+            &self.url,
+            service_id.circuit(),
+            service_id.service_id()
+        ))
+        .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+        let query = offset
+            .map(|offset| format!("offset={}", offset))
+            .into_iter()
+            .chain(limit.map(|limit| format!("limit={}", limit)))
+            .collect::<Vec<_>>();
+        if !query.is_empty() {
+            url.set_query(Some(&query.join("&")));
+        }
+
+        let request = Client::new().get(url);
+        let response = request
+            .header("SplinterProtocolVersion", SCABBARD_PROTOCOL_VERSION)
+            .send()
+            .map_err(|err| ScabbardClientError::new_with_source("request failed", err.into()))?;
+
+        if response.status().is_success() {
+            let list: CommittedRootList = response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize response body",
+                    err.into(),
+                )
+            })?;
+            Ok(list.data)
+        } else {
+            let status = response.status();
+            let msg: ErrorResponse = response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize error response body",
+                    err.into(),
+                )
+            })?;
+            Err(ScabbardClientError::new(&format!(
+                "failed to list state roots: {}: {}",
+                status, msg
+            )))
+        }
+    }
+
+    /// Get the receipts of the transactions with the given `ids` from the scabbard instance with
+    /// the given `service_id`. Receipts are only returned for transactions that have been
+    /// committed, in the order of the given `ids`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * No transaction IDs were given
+    /// * The client's URL was invalid
+    /// * The REST API request failed
+    /// * An internal server error occurred in the scabbard service
+    pub fn get_receipts(
+        &self,
+        service_id: &ServiceId,
+        ids: &[&str],
+    ) -> Result<Vec<TransactionReceipt>, ScabbardClientError> {
+        if ids.is_empty() {
+            return Err(ScabbardClientError::new("no transaction IDs given"));
+        }
+
+        let mut url = Url::parse(&format!(
+            "{}/scabbard/{}/{}/receipts",
+            &self.url,
+            service_id.circuit(),
+            service_id.service_id()
+        ))
+        .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+        url.set_query(Some(&format!("ids={}", ids.join(","))));
+
+        let request = Client::new().get(url);
+        let response = request
+            .header("SplinterProtocolVersion", SCABBARD_PROTOCOL_VERSION)
+            .send()
+            .map_err(|err| ScabbardClientError::new_with_source("request failed", err.into()))?;
+
+        if response.status().is_success() {
+            response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize response body",
+                    err.into(),
+                )
+            })
+        } else {
+            let status = response.status();
+            let msg: ErrorResponse = response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize error response body",
+                    err.into(),
+                )
+            })?;
+            Err(ScabbardClientError::new(&format!(
+                "failed to get transaction receipts: {}: {}",
+                status, msg
+            )))
+        }
+    }
+
+    /// List the receipts of the transactions committed by the scabbard instance with the given
+    /// `service_id`, in the order they were committed. Up to `limit` receipts are returned,
+    /// starting with the receipt at `offset`; if not given, the REST API's defaults are used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in any of the following cases:
+    /// * The client's URL was invalid
+    /// * The REST API request failed
+    /// * An internal server error occurred in the scabbard service
+    pub fn list_receipts(
+        &self,
+        service_id: &ServiceId,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Result<Vec<TransactionReceipt>, ScabbardClientError> {
+        let mut url = Url::parse(&format!(
+            "{}/scabbard/{}/{}/receipts/list",
+            &self.url,
+            service_id.circuit(),
+            service_id.service_id()
+        ))
+        .map_err(|err| ScabbardClientError::new_with_source("invalid URL", err.into()))?;
+        let query = offset
+            .map(|offset| format!("offset={}", offset))
+            .into_iter()
+            .chain(limit.map(|limit| format!("limit={}", limit)))
+            .collect::<Vec<_>>();
+        if !query.is_empty() {
+            url.set_query(Some(&query.join("&")));
+        }
+
+        let request = Client::new().get(url);
+        let response = request
+            .header("SplinterProtocolVersion", SCABBARD_PROTOCOL_VERSION)
+            .send()
+            .map_err(|err| ScabbardClientError::new_with_source("request failed", err.into()))?;
+
+        if response.status().is_success() {
+            let list: TransactionReceiptList = response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize response body",
+                    err.into(),
+                )
+            })?;
+            Ok(list.data)
+        } else {
+            let status = response.status();
+            let msg: ErrorResponse = response.json().map_err(|err| {
+                ScabbardClientError::new_with_source(
+                    "failed to deserialize error response body",
+                    err.into(),
+                )
+            })?;
+            Err(ScabbardClientError::new(&format!(
+                "failed to list transaction receipts: {}: {}",
+                status, msg
+            )))
+        }
+    }
 }
 
 /// Using the given `base_url` and `batch_link` to check batch statuses, `wait` the given duration
@@ -428,6 +630,110 @@ impl StateEntry {
     }
 }
 
+/// A state root committed by a Scabbard service, with the IDs of the batches that produced it.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct CommittedRoot {
+    height: u64,
+    state_root: String,
+    batch_ids: Vec<String>,
+}
+
+impl CommittedRoot {
+    /// Get the height at which the root was committed.
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Get the state root hash.
+    pub fn state_root(&self) -> &str {
+        &self.state_root
+    }
+
+    /// Get the IDs of the batches that were committed to produce the root. This is empty if the
+    /// service caught up to the root using a snapshot from another service.
+    pub fn batch_ids(&self) -> &[String] {
+        &self.batch_ids
+    }
+}
+
+/// The receipt of a transaction that was committed by a Scabbard service.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct TransactionReceipt {
+    transaction_id: String,
+    result: TransactionResult,
+}
+
+impl TransactionReceipt {
+    /// Get the ID of the transaction.
+    pub fn transaction_id(&self) -> &str {
+        &self.transaction_id
+    }
+
+    /// Get the result of executing the transaction.
+    pub fn result(&self) -> &TransactionResult {
+        &self.result
+    }
+}
+
+/// The result of executing a transaction.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "resultType", content = "message")]
+pub enum TransactionResult {
+    Valid {
+        state_changes: Vec<StateChange>,
+        events: Vec<Event>,
+        data: Vec<Vec<u8>>,
+    },
+    Invalid {
+        error_message: String,
+        error_data: Vec<u8>,
+    },
+}
+
+/// A change made to a Scabbard service's state by a transaction.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub enum StateChange {
+    Set { key: String, value: Vec<u8> },
+    Delete { key: String },
+}
+
+/// An event emitted by a transaction.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Event {
+    event_type: String,
+    attributes: Vec<(String, String)>,
+    data: Vec<u8>,
+}
+
+impl Event {
+    /// Get the type of the event.
+    pub fn event_type(&self) -> &str {
+        &self.event_type
+    }
+
+    /// Get the attributes of the event.
+    pub fn attributes(&self) -> &[(String, String)] {
+        &self.attributes
+    }
+
+    /// Get the data of the event.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+/// Used for deserializing `GET /state_roots` responses.
+#[derive(Debug, Serialize, Deserialize)]
+struct CommittedRootList {
+    data: Vec<CommittedRoot>,
+}
+
+/// Used for deserializing `GET /receipts/list` responses.
+#[derive(Debug, Serialize, Deserialize)]
+struct TransactionReceiptList {
+    data: Vec<TransactionReceipt>,
+}
+
 /// Used for deserializing the batch link provided by the Scabbard REST API.
 #[derive(Debug, Serialize, Deserialize)]
 struct Link {
@@ -503,14 +809,16 @@ mod tests {
 
     use crate::protocol::{
         SCABBARD_ADD_BATCHES_PROTOCOL_MIN, SCABBARD_BATCH_STATUSES_PROTOCOL_MIN,
-        SCABBARD_GET_STATE_PROTOCOL_MIN, SCABBARD_LIST_STATE_PROTOCOL_MIN,
-        SCABBARD_STATE_ROOT_PROTOCOL_MIN,
+        SCABBARD_GET_RECEIPTS_PROTOCOL_MIN, SCABBARD_GET_STATE_PROTOCOL_MIN,
+        SCABBARD_LIST_RECEIPTS_PROTOCOL_MIN, SCABBARD_LIST_STATE_PROTOCOL_MIN,
+        SCABBARD_LIST_STATE_ROOTS_PROTOCOL_MIN, SCABBARD_STATE_ROOT_PROTOCOL_MIN,
     };
 
     const MOCK_CIRCUIT_ID: &str = "01234-abcde";
     const MOCK_SERVICE_ID: &str = "ABCD";
     const MOCK_BATCH_ID: &str = "batch_id";
     const MOCK_STATE_ROOT_HASH: &str = "abcd";
+    const MOCK_TRANSACTION_ID: &str = "transaction_id";
 
     /// Verify that a `ServiceId` can be correctly parsed from a fully-qualified service ID string.
     #[test]
@@ -589,30 +897,54 @@ mod tests {
 
         // Verify that a request for an existing entry is successful and returns the right value
         let value = client
-            .get_state_at_address(&service_id, &mock_state_entry().address)
+            .get_state_at_address(&service_id, &mock_state_entry().address, None)
             .expect("Failed to get state for existing entry");
         assert_eq!(value, Some(mock_state_entry().value));
 
         // Verify that a request for a non-existent entry is successful and returns `None`
         let value = client
-            .get_state_at_address(&service_id, "012345")
+            .get_state_at_address(&service_id, "012345", None)
             .expect("Failed to get state for non-existent entry");
         assert_eq!(value, None);
 
+        // Verify that a request at a known state root is successful and returns the right value
+        let value = client
+            .get_state_at_address(
+                &service_id,
+                &mock_state_entry().address,
+                Some(MOCK_STATE_ROOT_HASH),
+            )
+            .expect("Failed to get state at state root");
+        assert_eq!(value, Some(mock_state_entry().value));
+
+        // Verify that a request at an unknown state root results in an error being returned
+        assert!(client
+            .get_state_at_address(&service_id, &mock_state_entry().address, Some("0123"))
+            .is_err());
+
+        // Verify that an invalid state root results in an error being returned
+        assert!(client
+            .get_state_at_address(
+                &service_id,
+                &mock_state_entry().address,
+                Some("not a valid state root")
+            )
+            .is_err());
+
         // Verify that an invalid URL results in an error being returned
         assert!(ScabbardClient::new("not a valid URL")
-            .get_state_at_address(&service_id, &mock_state_entry().address)
+            .get_state_at_address(&service_id, &mock_state_entry().address, None)
             .is_err());
 
         // Verify that an invalid address results in an error being returned
         assert!(client
-            .get_state_at_address(&service_id, "not a valid address")
+            .get_state_at_address(&service_id, "not a valid address", None)
             .is_err());
 
         // Verify that an error response code results in an error being returned
         resource_manager.internal_server_error(true);
         assert!(client
-            .get_state_at_address(&service_id, &mock_state_entry().address)
+            .get_state_at_address(&service_id, &mock_state_entry().address, None)
             .is_err());
         resource_manager.internal_server_error(false);
 
@@ -634,37 +966,54 @@ mod tests {
 
         // Verify that a request with no prefix is successful and returns the right value
         let entries = client
-            .get_state_with_prefix(&service_id, None)
+            .get_state_with_prefix(&service_id, None, None)
             .expect("Failed to get all entries");
         assert_eq!(entries, vec![mock_state_entry()]);
 
+        // Verify that a request at a known state root is successful and returns the right value
+        let entries = client
+            .get_state_with_prefix(
+                &service_id,
+                Some(&mock_state_entry().address[..2]),
+                Some(MOCK_STATE_ROOT_HASH),
+            )
+            .expect("Failed to get entries at state root");
+        assert_eq!(entries, vec![mock_state_entry()]);
+
+        // Verify that a request at an unknown state root results in an error being returned
+        assert!(client
+            .get_state_with_prefix(&service_id, None, Some("0123"))
+            .is_err());
+
         // Verify that a request with a prefix that contains an existing entry is successful and
         // returns the right value
         let entries = client
-            .get_state_with_prefix(&service_id, Some(&mock_state_entry().address[..2]))
+            .get_state_with_prefix(&service_id, Some(&mock_state_entry().address[..2]), None)
             .expect("Failed to get entries under prefix with existing entry");
         assert_eq!(entries, vec![mock_state_entry()]);
 
         // Verify that a request with a prefix that does not contain any existing entries is
         // successful and returns the right value
         let entries = client
-            .get_state_with_prefix(&service_id, Some("01"))
+            .get_state_with_prefix(&service_id, Some("01"), None)
             .expect("Failed to get entries under prefix with existing entry");
         assert_eq!(entries, vec![]);
 
         // Verify that an invalid URL results in an error being returned
         assert!(ScabbardClient::new("not a valid URL")
-            .get_state_with_prefix(&service_id, None)
+            .get_state_with_prefix(&service_id, None, None)
             .is_err());
 
         // Verify that an invalid address prefix results in an error being returned
         assert!(client
-            .get_state_with_prefix(&service_id, Some("not a valid address"))
+            .get_state_with_prefix(&service_id, Some("not a valid address"), None)
             .is_err());
 
         // Verify that an error response code results in an error being returned
         resource_manager.internal_server_error(true);
-        assert!(client.get_state_with_prefix(&service_id, None).is_err());
+        assert!(client
+            .get_state_with_prefix(&service_id, None, None)
+            .is_err());
         resource_manager.internal_server_error(false);
 
         shutdown_handle
@@ -700,6 +1049,116 @@ mod tests {
         join_handle.join().expect("Unable to join rest api thread");
     }
 
+    /// Verify that the `ScabbardClient::get_receipts` method works properly.
+    #[test]
+    fn get_receipts() {
+        let mut resource_manager = ResourceManager::new();
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(resource_manager.resources());
+
+        let client = ScabbardClient::new(&format!("http://{}", bind_url));
+        let service_id = ServiceId::new(MOCK_CIRCUIT_ID, MOCK_SERVICE_ID);
+
+        // Verify that a request for an existing receipt is successful and returns the receipt
+        let receipts = client
+            .get_receipts(&service_id, &[MOCK_TRANSACTION_ID])
+            .expect("Failed to get existing receipt");
+        assert_eq!(receipts, vec![mock_receipt()]);
+
+        // Verify that a request for a non-existent receipt is successful and returns no receipts
+        let receipts = client
+            .get_receipts(&service_id, &["unknown"])
+            .expect("Failed to get non-existent receipt");
+        assert_eq!(receipts, vec![]);
+
+        // Verify that a request without any IDs results in an error being returned
+        assert!(client.get_receipts(&service_id, &[]).is_err());
+
+        // Verify that an invalid URL results in an error being returned
+        assert!(ScabbardClient::new("not a valid URL")
+            .get_receipts(&service_id, &[MOCK_TRANSACTION_ID])
+            .is_err());
+
+        // Verify that an error response code results in an error being returned
+        resource_manager.internal_server_error(true);
+        assert!(client
+            .get_receipts(&service_id, &[MOCK_TRANSACTION_ID])
+            .is_err());
+        resource_manager.internal_server_error(false);
+
+        shutdown_handle
+            .shutdown()
+            .expect("unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Verify that the `ScabbardClient::list_state_roots` method works properly.
+    #[test]
+    fn list_state_roots() {
+        let mut resource_manager = ResourceManager::new();
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(resource_manager.resources());
+
+        let client = ScabbardClient::new(&format!("http://{}", bind_url));
+        let service_id = ServiceId::new(MOCK_CIRCUIT_ID, MOCK_SERVICE_ID);
+
+        // Verify that a request with the default offset is successful and returns the root
+        let roots = client
+            .list_state_roots(&service_id, None, Some(10))
+            .expect("Failed to list state roots");
+        assert_eq!(roots, vec![mock_committed_root()]);
+        assert_eq!(roots[0].batch_ids(), &[MOCK_BATCH_ID.to_string()]);
+
+        // Verify that a request with an offset past the root returns no roots
+        let roots = client
+            .list_state_roots(&service_id, Some(1), None)
+            .expect("Failed to list state roots with offset");
+        assert_eq!(roots, vec![]);
+
+        // Verify that an error response code results in an error being returned
+        resource_manager.internal_server_error(true);
+        assert!(client.list_state_roots(&service_id, None, None).is_err());
+        resource_manager.internal_server_error(false);
+
+        shutdown_handle
+            .shutdown()
+            .expect("unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    /// Verify that the `ScabbardClient::list_receipts` method works properly.
+    #[test]
+    fn list_receipts() {
+        let mut resource_manager = ResourceManager::new();
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(resource_manager.resources());
+
+        let client = ScabbardClient::new(&format!("http://{}", bind_url));
+        let service_id = ServiceId::new(MOCK_CIRCUIT_ID, MOCK_SERVICE_ID);
+
+        // Verify that a request with the default offset is successful and returns the receipt
+        let receipts = client
+            .list_receipts(&service_id, None, Some(10))
+            .expect("Failed to list receipts");
+        assert_eq!(receipts, vec![mock_receipt()]);
+
+        // Verify that a request with an offset past the receipt returns no receipts
+        let receipts = client
+            .list_receipts(&service_id, Some(1), None)
+            .expect("Failed to list receipts with offset");
+        assert_eq!(receipts, vec![]);
+
+        // Verify that an error response code results in an error being returned
+        resource_manager.internal_server_error(true);
+        assert!(client.list_receipts(&service_id, None, None).is_err());
+        resource_manager.internal_server_error(false);
+
+        shutdown_handle
+            .shutdown()
+            .expect("unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
     struct ResourceManager {
         resources: Vec<Resource>,
         internal_server_error: Arc<AtomicBool>,
@@ -809,6 +1268,10 @@ mod tests {
                         .match_info()
                         .get("address")
                         .expect("address should not be none");
+                    let query: web::Query<HashMap<String, String>> =
+                        web::Query::from_query(request.query_string())
+                            .expect("Failed to get query string");
+                    let state_root = query.get("state_root").map(String::as_str);
 
                     if internal_server_error_clone.load(Ordering::SeqCst) {
                         let response = ErrorResponse {
@@ -819,6 +1282,11 @@ mod tests {
                                 .json(response)
                                 .into_future(),
                         )
+                    } else if state_root.map_or(false, |root| root != MOCK_STATE_ROOT_HASH) {
+                        let response = ErrorResponse {
+                            message: "Unknown state root".into(),
+                        };
+                        Box::new(HttpResponse::BadRequest().json(response).into_future())
                     } else if address == mock_state_entry().address {
                         Box::new(
                             HttpResponse::Ok()
@@ -845,6 +1313,7 @@ mod tests {
                         web::Query::from_query(request.query_string())
                             .expect("Failed to get query string");
                     let prefix = query.get("prefix").map(String::as_str);
+                    let state_root = query.get("state_root").map(String::as_str);
 
                     if internal_server_error_clone.load(Ordering::SeqCst) {
                         let response = ErrorResponse {
@@ -855,6 +1324,11 @@ mod tests {
                                 .json(response)
                                 .into_future(),
                         )
+                    } else if state_root.map_or(false, |root| root != MOCK_STATE_ROOT_HASH) {
+                        let response = ErrorResponse {
+                            message: "Unknown state root".into(),
+                        };
+                        Box::new(HttpResponse::BadRequest().json(response).into_future())
                     } else {
                         let return_entry = match prefix {
                             Some(prefix) => mock_state_entry().address.starts_with(prefix),
@@ -892,6 +1366,117 @@ mod tests {
                 });
             resources.push(state_root);
 
+            let internal_server_error_clone = internal_server_error.clone();
+            let state_roots = Resource::build(&format!("{}/state_roots", scabbard_base))
+                .add_request_guard(ProtocolVersionRangeGuard::new(
+                    SCABBARD_LIST_STATE_ROOTS_PROTOCOL_MIN,
+                    SCABBARD_PROTOCOL_VERSION,
+                ))
+                .add_method(Method::Get, move |request, _| {
+                    let query: web::Query<HashMap<String, String>> =
+                        web::Query::from_query(request.query_string())
+                            .expect("Failed to get query string");
+                    let offset = query
+                        .get("offset")
+                        .map(|offset| offset.parse::<usize>().expect("Invalid offset"))
+                        .unwrap_or(0);
+
+                    if internal_server_error_clone.load(Ordering::SeqCst) {
+                        let response = ErrorResponse {
+                            message: "Request failed".into(),
+                        };
+                        Box::new(
+                            HttpResponse::InternalServerError()
+                                .json(response)
+                                .into_future(),
+                        )
+                    } else {
+                        let data = if offset == 0 {
+                            vec![mock_committed_root()]
+                        } else {
+                            vec![]
+                        };
+                        Box::new(
+                            HttpResponse::Ok()
+                                .json(CommittedRootList { data })
+                                .into_future(),
+                        )
+                    }
+                });
+            resources.push(state_roots);
+
+            let internal_server_error_clone = internal_server_error.clone();
+            let receipts = Resource::build(&format!("{}/receipts", scabbard_base))
+                .add_request_guard(ProtocolVersionRangeGuard::new(
+                    SCABBARD_GET_RECEIPTS_PROTOCOL_MIN,
+                    SCABBARD_PROTOCOL_VERSION,
+                ))
+                .add_method(Method::Get, move |request, _| {
+                    let query: web::Query<HashMap<String, String>> =
+                        web::Query::from_query(request.query_string())
+                            .expect("Failed to get query string");
+
+                    if internal_server_error_clone.load(Ordering::SeqCst) {
+                        let response = ErrorResponse {
+                            message: "Request failed".into(),
+                        };
+                        Box::new(
+                            HttpResponse::InternalServerError()
+                                .json(response)
+                                .into_future(),
+                        )
+                    } else {
+                        let receipts = query
+                            .get("ids")
+                            .expect("No IDs in query")
+                            .split(',')
+                            .filter(|id| *id == MOCK_TRANSACTION_ID)
+                            .map(|_| mock_receipt())
+                            .collect::<Vec<_>>();
+                        Box::new(HttpResponse::Ok().json(receipts).into_future())
+                    }
+                });
+            resources.push(receipts);
+
+            let internal_server_error_clone = internal_server_error.clone();
+            let receipts_list = Resource::build(&format!("{}/receipts/list", scabbard_base))
+                .add_request_guard(ProtocolVersionRangeGuard::new(
+                    SCABBARD_LIST_RECEIPTS_PROTOCOL_MIN,
+                    SCABBARD_PROTOCOL_VERSION,
+                ))
+                .add_method(Method::Get, move |request, _| {
+                    let query: web::Query<HashMap<String, String>> =
+                        web::Query::from_query(request.query_string())
+                            .expect("Failed to get query string");
+                    let offset = query
+                        .get("offset")
+                        .map(|offset| offset.parse::<usize>().expect("Invalid offset"))
+                        .unwrap_or(0);
+
+                    if internal_server_error_clone.load(Ordering::SeqCst) {
+                        let response = ErrorResponse {
+                            message: "Request failed".into(),
+                        };
+                        Box::new(
+                            HttpResponse::InternalServerError()
+                                .json(response)
+                                .into_future(),
+                        )
+                    } else {
+                        let data = if offset == 0 {
+                            vec![mock_receipt()]
+                        } else {
+                            vec![]
+                        };
+                        Box::new(
+                            HttpResponse::Ok()
+                                .json(TransactionReceiptList { data })
+                                .into_future(),
+                        )
+                    }
+                });
+            resources.push(receipts_list);
+
             Self {
                 resources,
                 internal_server_error,
@@ -924,6 +1509,32 @@ mod tests {
         }
     }
 
+    fn mock_committed_root() -> CommittedRoot {
+        CommittedRoot {
+            height: 1,
+            state_root: MOCK_STATE_ROOT_HASH.into(),
+            batch_ids: vec![MOCK_BATCH_ID.into()],
+        }
+    }
+
+    fn mock_receipt() -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_id: MOCK_TRANSACTION_ID.into(),
+            result: TransactionResult::Valid {
+                state_changes: vec![StateChange::Set {
+                    key: "abcdef".into(),
+                    value: b"value".to_vec(),
+                }],
+                events: vec![Event {
+                    event_type: "event".into(),
+                    attributes: vec![("key".into(), "value".into())],
+                    data: vec![],
+                }],
+                data: vec![],
+            },
+        }
+    }
+
     fn run_rest_api_on_open_port(
         resources: Vec<Resource>,
     ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-pub const SCABBARD_PROTOCOL_VERSION: u32 = 1;
+pub const SCABBARD_PROTOCOL_VERSION: u32 = 3;
 
 #[cfg(all(feature = "rest-api", feature = "rest-api-actix"))]
 pub(crate) const SCABBARD_SUBSCRIBE_PROTOCOL_MIN: u32 = 1;
@@ -26,3 +26,9 @@ pub(crate) const SCABBARD_GET_STATE_PROTOCOL_MIN: u32 = 1;
 pub(crate) const SCABBARD_LIST_STATE_PROTOCOL_MIN: u32 = 1;
 #[cfg(all(feature = "rest-api", feature = "rest-api-actix"))]
 pub(crate) const SCABBARD_STATE_ROOT_PROTOCOL_MIN: u32 = 1;
+#[cfg(all(feature = "rest-api", feature = "rest-api-actix"))]
+pub(crate) const SCABBARD_GET_RECEIPTS_PROTOCOL_MIN: u32 = 2;
+#[cfg(all(feature = "rest-api", feature = "rest-api-actix"))]
+pub(crate) const SCABBARD_LIST_RECEIPTS_PROTOCOL_MIN: u32 = 2;
+#[cfg(all(feature = "rest-api", feature = "rest-api-actix"))]
+pub(crate) const SCABBARD_LIST_STATE_ROOTS_PROTOCOL_MIN: u32 = 3;
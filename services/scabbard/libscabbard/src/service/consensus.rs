@@ -30,8 +30,12 @@ use transact::protos::IntoBytes;
 use crate::protos::scabbard::{ProposedBatch, ScabbardMessage, ScabbardMessage_Type};
 
 use super::error::{ScabbardConsensusManagerError, ScabbardError};
-use super::shared::ScabbardShared;
+use super::shared::{ProposedBatches, ScabbardShared};
 use super::state::ScabbardState;
+use super::sync::StateSynchronizer;
+
+/// How long to wait for a snapshot from a proposer whose state is ahead of this service's state
+const STATE_SYNC_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// Component used by the service to manage and interact with consenus
 pub struct ScabbardConsensusManager {
@@ -47,8 +51,13 @@ impl ScabbardConsensusManager {
         service_id: String,
         shared: Arc<Mutex<ScabbardShared>>,
         state: Arc<Mutex<ScabbardState>>,
+        state_sync: Arc<StateSynchronizer>,
         // The coordinator timeout for the two-phase commit consensus engine
         coordinator_timeout: Duration,
+        // The maximum number of batches in a proposal
+        proposal_batch_limit: usize,
+        // The maximum total size, in bytes, of the batches in a proposal
+        proposal_size_limit: usize,
     ) -> Result<Self, ScabbardConsensusManagerError> {
         let peer_ids = shared
             .lock()
@@ -66,6 +75,9 @@ impl ScabbardConsensusManager {
             proposal_update_tx.clone(),
             shared.clone(),
             state,
+            state_sync,
+            proposal_batch_limit,
+            proposal_size_limit,
         );
         let consensus_network_sender =
             ScabbardConsensusNetworkSender::new(service_id.clone(), shared);
@@ -135,6 +147,9 @@ pub struct ScabbardProposalManager {
     proposal_update_sender: Sender<ProposalUpdate>,
     shared: Arc<Mutex<ScabbardShared>>,
     state: Arc<Mutex<ScabbardState>>,
+    state_sync: Arc<StateSynchronizer>,
+    proposal_batch_limit: usize,
+    proposal_size_limit: usize,
 }
 
 impl ScabbardProposalManager {
@@ -143,14 +158,93 @@ impl ScabbardProposalManager {
         proposal_update_sender: Sender<ProposalUpdate>,
         shared: Arc<Mutex<ScabbardShared>>,
         state: Arc<Mutex<ScabbardState>>,
+        state_sync: Arc<StateSynchronizer>,
+        proposal_batch_limit: usize,
+        proposal_size_limit: usize,
     ) -> Self {
         ScabbardProposalManager {
             service_id,
             proposal_update_sender,
             shared,
             state,
+            state_sync,
+            proposal_batch_limit,
+            proposal_size_limit,
         }
     }
+
+    /// Compares this service's state with the state the given proposal was made on. If this
+    /// service is behind the proposer, its state is first brought up to date with a snapshot of
+    /// the proposer's state at the proposal's previous state root. If the proposer is behind, the
+    /// proposal is rejected; the proposer catches up when it checks a proposal made on a later
+    /// state.
+    ///
+    /// Returns whether the proposal can be checked against this service's state.
+    fn sync_with_proposer(&self, proposed: &ProposedBatches) -> Result<bool, ProposalManagerError> {
+        // Services that don't track their height don't set the proposal height; their proposals
+        // are checked as-is.
+        if proposed.proposal.proposal_height == 0 {
+            return Ok(true);
+        }
+        let previous_height = proposed.proposal.proposal_height - 1;
+        let previous_root = String::from_utf8(proposed.proposal.previous_id.clone().into())
+            .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+
+        let height = self
+            .state
+            .lock()
+            .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
+            .current_height();
+
+        if previous_height > height {
+            info!(
+                "State is behind proposer {} (height {} < {}); requesting snapshot",
+                proposed.proposer, height, previous_height
+            );
+            self.state_sync
+                .request_snapshot(&proposed.proposer, previous_height, &previous_root)
+                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+            if !self
+                .state_sync
+                .wait_for_snapshot(&proposed.proposer, previous_height, STATE_SYNC_TIMEOUT)
+                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?
+            {
+                warn!(
+                    "Did not receive snapshot from proposer {}",
+                    proposed.proposer
+                );
+                self.state_sync
+                    .cancel_snapshot(&proposed.proposer)
+                    .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+                return Ok(false);
+            }
+        } else if previous_height < height {
+            info!(
+                "Proposer {} is behind (height {} < {}); rejecting proposal",
+                proposed.proposer, previous_height, height
+            );
+            return Ok(false);
+        }
+
+        let state = self
+            .state
+            .lock()
+            .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
+        if state.current_height() != previous_height || state.current_state_root() != previous_root
+        {
+            error!(
+                "State has diverged from proposer {} at height {}: expected state root {} but was \
+                 {}",
+                proposed.proposer,
+                previous_height,
+                previous_root,
+                state.current_state_root()
+            );
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
 }
 
 impl ProposalManager for ScabbardProposalManager {
@@ -166,22 +260,48 @@ impl ProposalManager for ScabbardProposalManager {
             .lock()
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
 
-        if let Some(batch) = shared.pop_batch_from_queue() {
-            let expected_hash = self
-                .state
-                .lock()
-                .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
-                .prepare_change(batch.clone())
+        let batches =
+            shared.pop_batches_from_queue(self.proposal_batch_limit, self.proposal_size_limit);
+
+        if !batches.is_empty() {
+            let mut state = self.state.lock().map_err(|_| {
+                ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned))
+            })?;
+            let (expected_hash, batches) = state
+                .prepare_change(batches)
                 .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
 
-            // Intentionally leaving out the previous_id and proposal_height fields, since this
-            // service and two phase consensus don't use them. This means the proposal ID can just
-            // be the summary.
+            // The invalid batches have been marked as such in the batch history; if none of the
+            // batches are valid, there is nothing to propose.
+            if batches.is_empty() {
+                state
+                    .rollback()
+                    .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+                self.proposal_update_sender
+                    .send(ProposalUpdate::ProposalCreated(None))?;
+                return Ok(());
+            }
+
+            // Two phase consensus doesn't use the previous_id and proposal_height fields, so they
+            // are used to tell the other services which state the proposal was made on: the
+            // previous_id is the current state root and the proposal_height is the height the
+            // state will have once the proposal is committed. The proposal ID can just be the
+            // summary.
             let mut proposal = Proposal::default();
             proposal.id = expected_hash.as_bytes().into();
+            proposal.previous_id = state.current_state_root().as_bytes().into();
+            proposal.proposal_height = state.current_height() + 1;
             proposal.summary = expected_hash.as_bytes().into();
-
-            shared.add_proposed_batch(proposal.id.clone(), batch.clone());
+            drop(state);
+
+            shared.add_proposed_batches(
+                proposal.id.clone(),
+                ProposedBatches {
+                    proposal: proposal.clone(),
+                    proposer: self.service_id.clone(),
+                    batches: batches.clone(),
+                },
+            );
 
             // Send the proposal to the other services
             let mut proposed_batch = ProposedBatch::new();
@@ -191,11 +311,15 @@ impl ProposalManager for ScabbardProposalManager {
                     .try_into()
                     .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?,
             );
-            proposed_batch.set_batch(
-                batch
-                    .into_bytes()
-                    .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?,
-            );
+            let batches_bytes = batches
+                .into_iter()
+                .map(|batch| batch.into_bytes())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
+            if batches_bytes.len() == 1 {
+                proposed_batch.set_batch(batches_bytes[0].clone());
+            }
+            proposed_batch.set_batches(batches_bytes.into());
             proposed_batch.set_service_id(self.service_id.clone());
 
             let mut msg = ScabbardMessage::new();
@@ -226,22 +350,42 @@ impl ProposalManager for ScabbardProposalManager {
     }
 
     fn check_proposal(&self, id: &ProposalId) -> Result<(), ProposalManagerError> {
-        let batch = self
+        let proposed = self
             .shared
             .lock()
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
-            .get_proposed_batch(id)
+            .get_proposed_batches(id)
             .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?
             .clone();
 
-        let hash = self
+        if !self.sync_with_proposer(&proposed)? {
+            self.proposal_update_sender
+                .send(ProposalUpdate::ProposalInvalid(id.clone()))?;
+            return Ok(());
+        }
+
+        let batches = proposed.batches;
+        let batch_count = batches.len();
+
+        let (hash, valid_batches) = self
             .state
             .lock()
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?
-            .prepare_change(batch)
+            .prepare_change(batches)
             .map_err(|err| ProposalManagerError::Internal(Box::new(err)))?;
 
-        if hash.as_bytes() != id.as_ref() {
+        // The proposer only proposes valid batches, so an invalid batch means this service does
+        // not agree with the proposal
+        if valid_batches.len() != batch_count {
+            warn!(
+                "Proposal {} has {} invalid batch(es)",
+                id,
+                batch_count - valid_batches.len()
+            );
+
+            self.proposal_update_sender
+                .send(ProposalUpdate::ProposalInvalid(id.clone()))?;
+        } else if hash.as_bytes() != id.as_ref() {
             warn!("Hash mismatch: expected {} but was {}", id, hash);
 
             self.proposal_update_sender
@@ -267,7 +411,7 @@ impl ProposalManager for ScabbardProposalManager {
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
 
         shared
-            .remove_proposed_batch(id)
+            .remove_proposed_batches(id)
             .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?;
 
         self.state
@@ -291,7 +435,7 @@ impl ProposalManager for ScabbardProposalManager {
             .map_err(|_| ProposalManagerError::Internal(Box::new(ScabbardError::LockPoisoned)))?;
 
         shared
-            .remove_proposed_batch(id)
+            .remove_proposed_batches(id)
             .ok_or_else(|| ProposalManagerError::UnknownProposal(id.clone()))?;
 
         self.state
@@ -31,6 +31,7 @@ pub enum ScabbardError {
     MessageTypeUnset,
     NotConnected,
     StateInteractionFailed(ScabbardStateError),
+    StateSyncFailed(Box<dyn Error + Send>),
 }
 
 impl Error for ScabbardError {
@@ -43,6 +44,7 @@ impl Error for ScabbardError {
             ScabbardError::MessageTypeUnset => None,
             ScabbardError::NotConnected => None,
             ScabbardError::StateInteractionFailed(err) => Some(err),
+            ScabbardError::StateSyncFailed(err) => Some(&**err),
         }
     }
 }
@@ -65,6 +67,9 @@ impl std::fmt::Display for ScabbardError {
             ScabbardError::StateInteractionFailed(err) => {
                 write!(f, "interaction with scabbard state failed: {}", err)
             }
+            ScabbardError::StateSyncFailed(err) => {
+                write!(f, "failed to synchronize state: {}", err)
+            }
         }
     }
 }
@@ -118,6 +118,15 @@ impl ServiceFactory for ScabbardFactory {
     /// - `coordinator_timeout`: the length of time (in milliseconds) that the network has to
     ///   commit a proposal before the coordinator rejects it (if not provided, default is 30
     ///   seconds)
+    /// - `proposal_batch_limit`: the maximum number of queued batches that are proposed together
+    ///   (if not provided, default is 100)
+    /// - `proposal_size_limit`: the maximum total size, in bytes, of the queued batches that are
+    ///   proposed together (if not provided, default is 1 MiB)
+    /// - `batch_history_limit`: the maximum number of batches whose statuses are kept in the
+    ///   batch history (if not provided, default is 10,000)
+    /// - `batch_history_max_age`: the length of time (in seconds) that a batch's status is kept in
+    ///   the batch history after the batch was submitted (if not provided, batches are only
+    ///   removed when the history exceeds its limit)
     fn create(
         &self,
         service_id: String,
@@ -160,6 +169,19 @@ impl ServiceFactory for ScabbardFactory {
                 ))),
             })
             .transpose()?;
+        let proposal_batch_limit = parse_limit_arg(&args, "proposal_batch_limit")?;
+        let proposal_size_limit = parse_limit_arg(&args, "proposal_size_limit")?;
+        let batch_history_limit = parse_limit_arg(&args, "batch_history_limit")?;
+        let batch_history_max_age = args
+            .get("batch_history_max_age")
+            .map(|max_age| match max_age.parse::<u64>() {
+                Ok(max_age) => Ok(Duration::from_secs(max_age)),
+                Err(err) => Err(FactoryCreateError::InvalidArguments(format!(
+                    "invalid batch_history_max_age: {}",
+                    err
+                ))),
+            })
+            .transpose()?;
 
         let service = Scabbard::new(
             service_id,
@@ -172,6 +194,10 @@ impl ServiceFactory for ScabbardFactory {
             self.signature_verifier_factory.create_verifier(),
             admin_keys,
             coordinator_timeout,
+            proposal_batch_limit,
+            proposal_size_limit,
+            batch_history_limit,
+            batch_history_max_age,
         )
         .map_err(|err| FactoryCreateError::CreationFailed(Box::new(err)))?;
 
@@ -183,11 +209,17 @@ impl ServiceFactory for ScabbardFactory {
     /// endpoints as [`ServiceEndpoint`]s:
     ///
     /// * `POST /batches` - Add one or more batches to scabbard's queue
-    /// * `GET /batch_statuses` - Get the status of one or more batches
+    /// * `GET /batch_statuses` - Get the status of one or more batches, or of the batches
+    ///   submitted in a time range
     /// * `GET /ws/subscribe` - Subscribe to scabbard state-delta events
-    /// * `GET /state/{address}` - Get a value from scabbard's state
-    /// * `GET /state` - Get multiple scabbard state entries
+    /// * `GET /state/{address}` - Get a value from scabbard's state, optionally at a previous
+    ///   state root
+    /// * `GET /state` - Get multiple scabbard state entries, optionally at a previous state root
     /// * `GET /state_root` - Get the current state root hash of scabbard's state
+    /// * `GET /state_roots` - List the recently committed state roots of scabbard's state, with
+    ///   the IDs of the batches that produced them
+    /// * `GET /receipts` - Get the receipts of one or more committed transactions
+    /// * `GET /receipts/list` - List the receipts of committed transactions in commit order
     ///
     /// These endpoints are only available if the following REST API backend feature is enabled:
     ///
@@ -210,6 +242,9 @@ impl ServiceFactory for ScabbardFactory {
                 actix::state_address::make_get_state_at_address_endpoint(),
                 actix::state::make_get_state_with_prefix_endpoint(),
                 actix::state_root::make_get_state_root_endpoint(),
+                actix::state_root::make_list_state_roots_endpoint(),
+                actix::receipts::make_get_receipts_endpoint(),
+                actix::receipts::make_list_receipts_endpoint(),
             ])
         }
 
@@ -217,6 +252,26 @@ impl ServiceFactory for ScabbardFactory {
     }
 }
 
+/// Parses the optional proposal limit with the given name, which must be a positive integer.
+fn parse_limit_arg(
+    args: &HashMap<String, String>,
+    name: &str,
+) -> Result<Option<usize>, FactoryCreateError> {
+    args.get(name)
+        .map(|limit| match limit.parse::<usize>() {
+            Ok(0) => Err(FactoryCreateError::InvalidArguments(format!(
+                "invalid {}: must be greater than 0",
+                name
+            ))),
+            Ok(limit) => Ok(limit),
+            Err(err) => Err(FactoryCreateError::InvalidArguments(format!(
+                "invalid {}: {}",
+                name, err
+            ))),
+        })
+        .transpose()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -260,6 +315,62 @@ mod tests {
         assert_eq!(scabbard.coordinator_timeout, Duration::from_millis(123));
     }
 
+    /// Verify that the `proposal_batch_limit` and `proposal_size_limit` service arguments are
+    /// properly set for a new `Scabbard` instance, and that a limit of 0 is rejected.
+    #[test]
+    fn create_with_proposal_limits() {
+        let factory = get_factory();
+        let mut args = get_mock_args();
+        args.insert("proposal_batch_limit".into(), "10".into());
+        args.insert("proposal_size_limit".into(), "2048".into());
+
+        let service = factory
+            .create("".into(), "", "", args.clone())
+            .expect("failed to create service");
+        let scabbard = (&*service)
+            .as_any()
+            .downcast_ref::<Scabbard>()
+            .expect("failed to downcast Service to Scabbard");
+
+        assert_eq!(scabbard.proposal_batch_limit, 10);
+        assert_eq!(scabbard.proposal_size_limit, 2048);
+
+        args.insert("proposal_batch_limit".into(), "0".into());
+        assert!(
+            factory.create("".into(), "", "", args).is_err(),
+            "Creating factory with a proposal_batch_limit of 0 did not fail"
+        );
+    }
+
+    /// Verify that the `batch_history_limit` and `batch_history_max_age` service arguments are
+    /// accepted when valid and rejected when invalid.
+    #[test]
+    fn create_with_batch_history_retention() {
+        let factory = get_factory();
+        let mut args = get_mock_args();
+        args.insert("batch_history_limit".into(), "500".into());
+        args.insert("batch_history_max_age".into(), "3600".into());
+
+        factory
+            .create("".into(), "", "", args.clone())
+            .expect("failed to create service");
+
+        let mut invalid_limit_args = args.clone();
+        invalid_limit_args.insert("batch_history_limit".into(), "0".into());
+        assert!(
+            factory
+                .create("".into(), "", "", invalid_limit_args)
+                .is_err(),
+            "Creating factory with a batch_history_limit of 0 did not fail"
+        );
+
+        args.insert("batch_history_max_age".into(), "one hour".into());
+        assert!(
+            factory.create("".into(), "", "", args).is_err(),
+            "Creating factory with an invalid batch_history_max_age did not fail"
+        );
+    }
+
     /// Verify that `Scabbard` creation fails when the `peer_services` argument isn't specified.
     #[test]
     fn create_without_peer_services() {
@@ -23,13 +23,14 @@ mod factory;
 mod rest_api;
 mod shared;
 mod state;
+mod sync;
 
 use std::any::Any;
 use std::collections::{HashSet, VecDeque};
 use std::convert::TryFrom;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use openssl::hash::{hash, MessageDigest};
 use splinter::{
@@ -40,7 +41,10 @@ use splinter::{
     },
     signing::SignatureVerifier,
 };
-use transact::{protocol::batch::BatchPair, protos::FromBytes};
+use transact::{
+    protocol::{batch::BatchPair, receipt::TransactionReceipt},
+    protos::FromBytes,
+};
 
 use super::hex::to_hex;
 use super::protos::scabbard::{ScabbardMessage, ScabbardMessage_Type};
@@ -50,15 +54,23 @@ use error::ScabbardError;
 #[cfg(feature = "service-arg-validation")]
 pub use factory::ScabbardArgValidator;
 pub use factory::ScabbardFactory;
-use shared::ScabbardShared;
+use shared::{ProposedBatches, ScabbardShared};
 pub use state::{
-    BatchInfo, BatchInfoIter, BatchStatus, Events, StateChange, StateChangeEvent, StateIter,
+    BatchInfo, BatchInfoIter, BatchStatus, CommittedRoot, Events, StateChange, StateChangeEvent,
+    StateIter,
 };
-use state::{ScabbardState, StateSubscriber};
+use state::{ScabbardState, StateSubscriber, DEFAULT_BATCH_HISTORY_SIZE};
+use sync::StateSynchronizer;
 
 const SERVICE_TYPE: &str = "scabbard";
 
 const DEFAULT_COORDINATOR_TIMEOUT: u64 = 30; // 30 seconds
+const DEFAULT_PROPOSAL_BATCH_LIMIT: usize = 100;
+const DEFAULT_PROPOSAL_SIZE_LIMIT: usize = 1 << 20; // 1024 ** 2
+/// The maximum number of transaction receipts returned by a single `list_receipts` call
+const MAX_RECEIPT_LIST_LIMIT: usize = 1000;
+/// The maximum number of batch statuses returned by a single `get_batch_info_in_range` call
+const MAX_BATCH_INFO_LIST_LIMIT: usize = 1000;
 
 /// A service for running Sawtooth Sabre smart contracts with two-phase commit consensus.
 #[derive(Clone)]
@@ -67,8 +79,14 @@ pub struct Scabbard {
     service_id: String,
     shared: Arc<Mutex<ScabbardShared>>,
     state: Arc<Mutex<ScabbardState>>,
+    /// Brings this service's state up to date when it falls behind its peers
+    state_sync: Arc<StateSynchronizer>,
     /// The coordinator timeout for the two-phase commit consensus engine
     coordinator_timeout: Duration,
+    /// The maximum number of batches in a proposal
+    proposal_batch_limit: usize,
+    /// The maximum total size, in bytes, of the batches in a proposal
+    proposal_size_limit: usize,
     consensus: Arc<Mutex<Option<ScabbardConsensusManager>>>,
 }
 
@@ -94,12 +112,24 @@ impl Scabbard {
         // The coordinator timeout for the two-phase commit consensus engine; if `None`, the
         // default value will be used (30 seconds).
         coordinator_timeout: Option<Duration>,
+        // The maximum number of queued batches that are proposed together; if `None`, the default
+        // value will be used (100).
+        proposal_batch_limit: Option<usize>,
+        // The maximum total size, in bytes, of the batches that are proposed together; if `None`,
+        // the default value will be used (1 MiB).
+        proposal_size_limit: Option<usize>,
+        // The maximum number of batches to keep in the batch history; if `None`, the default value
+        // will be used (10,000).
+        batch_history_limit: Option<usize>,
+        // The maximum time to keep a batch in the batch history; if `None`, batches are only
+        // removed when the history exceeds its limit.
+        batch_history_max_age: Option<Duration>,
     ) -> Result<Self, ScabbardError> {
         let shared = ScabbardShared::new(VecDeque::new(), None, peer_services, signature_verifier);
 
         let (state_db_path, receipt_db_path) =
             compute_db_paths(&service_id, circuit_id, state_db_dir, receipt_db_dir)?;
-        let state = ScabbardState::new(
+        let mut state = ScabbardState::new(
             state_db_path.as_path(),
             state_db_size,
             receipt_db_path.as_path(),
@@ -107,39 +137,84 @@ impl Scabbard {
             admin_keys,
         )
         .map_err(|err| ScabbardError::InitializationFailed(Box::new(err)))?;
+        state.batch_history().set_retention(
+            batch_history_limit.unwrap_or(DEFAULT_BATCH_HISTORY_SIZE),
+            batch_history_max_age,
+        );
 
         let coordinator_timeout =
             coordinator_timeout.unwrap_or_else(|| Duration::from_secs(DEFAULT_COORDINATOR_TIMEOUT));
 
+        let shared = Arc::new(Mutex::new(shared));
+        let state = Arc::new(Mutex::new(state));
+        let state_sync = Arc::new(StateSynchronizer::new(shared.clone(), state.clone()));
+
         Ok(Scabbard {
             circuit_id: circuit_id.to_string(),
             service_id,
-            shared: Arc::new(Mutex::new(shared)),
-            state: Arc::new(Mutex::new(state)),
+            shared,
+            state,
+            state_sync,
             coordinator_timeout,
+            proposal_batch_limit: proposal_batch_limit.unwrap_or(DEFAULT_PROPOSAL_BATCH_LIMIT),
+            proposal_size_limit: proposal_size_limit.unwrap_or(DEFAULT_PROPOSAL_SIZE_LIMIT),
             consensus: Arc::new(Mutex::new(None)),
         })
     }
 
     /// Fetch the value at the given `address` in the scabbard service's state. Returns `None` if
-    /// the `address` is not set.
-    pub fn get_state_at_address(&self, address: &str) -> Result<Option<Vec<u8>>, ScabbardError> {
+    /// the `address` is not set. If a `state_root` is provided, the value is read at that state
+    /// root instead of the current state root.
+    pub fn get_state_at_address(
+        &self,
+        address: &str,
+        state_root: Option<&str>,
+    ) -> Result<Option<Vec<u8>>, ScabbardError> {
         Ok(self
             .state
             .lock()
             .map_err(|_| ScabbardError::LockPoisoned)?
-            .get_state_at_address(address)?)
+            .get_state_at_address(address, state_root)?)
     }
 
     /// Fetch a list of entries in the scabbard service's state. If a `prefix` is provided, only
     /// return entries whose addresses are under the given address prefix. If no `prefix` is
-    /// provided, return all state entries.
-    pub fn get_state_with_prefix(&self, prefix: Option<&str>) -> Result<StateIter, ScabbardError> {
+    /// provided, return all state entries. If a `state_root` is provided, the entries are read at
+    /// that state root instead of the current state root.
+    pub fn get_state_with_prefix(
+        &self,
+        prefix: Option<&str>,
+        state_root: Option<&str>,
+    ) -> Result<StateIter, ScabbardError> {
         Ok(self
             .state
             .lock()
             .map_err(|_| ScabbardError::LockPoisoned)?
-            .get_state_with_prefix(prefix)?)
+            .get_state_with_prefix(prefix, state_root)?)
+    }
+
+    /// Check whether the scabbard service's state can be read at the given state root.
+    pub fn contains_state_root(&self, state_root: &str) -> Result<bool, ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .contains_state_root(state_root)?)
+    }
+
+    /// List the most recently committed state roots of the scabbard service's state, newest
+    /// first, with the IDs of the batches that produced them. Returns the requested roots and the
+    /// total number of roots that can be listed.
+    pub fn list_committed_roots(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<CommittedRoot>, usize), ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .list_committed_roots(offset, limit)?)
     }
 
     /// Get the current state root hash of the scabbard service's state.
@@ -169,7 +244,7 @@ impl Scabbard {
                     .lock()
                     .map_err(|_| ScabbardError::LockPoisoned)?
                     .batch_history()
-                    .add_batch(&batch.batch().header_signature());
+                    .add_batch(&batch.batch().header_signature())?;
 
                 link.push_str(&format!("{},", batch.batch().header_signature()));
                 shared.add_batch_to_queue(batch);
@@ -204,6 +279,51 @@ impl Scabbard {
         Ok(state.batch_history().get_batch_info(ids, wait)?)
     }
 
+    /// Get the `BatchInfo` of up to `limit` batches in the batch history that were submitted at or
+    /// after `since` and, if given, before `until`, in the order the batches were submitted,
+    /// skipping the first `offset` of them. At most `MAX_BATCH_INFO_LIST_LIMIT` batches are
+    /// returned, whatever the `limit`.
+    pub fn get_batch_info_in_range(
+        &self,
+        since: SystemTime,
+        until: Option<SystemTime>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<BatchInfoIter, ScabbardError> {
+        let mut state = self.state.lock().map_err(|_| ScabbardError::LockPoisoned)?;
+        Ok(state.batch_history().get_batch_info_in_range(
+            since,
+            until,
+            offset,
+            limit.min(MAX_BATCH_INFO_LIST_LIMIT),
+        )?)
+    }
+
+    /// Get the receipts of the committed transactions with the given IDs, in the order of the
+    /// given IDs. IDs of transactions that have not been committed are skipped.
+    pub fn get_receipts(&self, ids: &[String]) -> Result<Vec<TransactionReceipt>, ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .get_receipts(ids)?)
+    }
+
+    /// Get up to `limit` transaction receipts in the order the transactions were committed,
+    /// starting with the receipt at `offset`, along with the total number of receipts. At most
+    /// `MAX_RECEIPT_LIST_LIMIT` receipts are returned, whatever the `limit`.
+    pub fn list_receipts(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<TransactionReceipt>, usize), ScabbardError> {
+        Ok(self
+            .state
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .list_receipts(offset, limit.min(MAX_RECEIPT_LIST_LIMIT))?)
+    }
+
     pub fn get_events_since(&self, event_id: Option<String>) -> Result<Events, ScabbardError> {
         Ok(self
             .state
@@ -258,7 +378,10 @@ impl Service for Scabbard {
                 self.service_id().into(),
                 self.shared.clone(),
                 self.state.clone(),
+                self.state_sync.clone(),
                 self.coordinator_timeout,
+                self.proposal_batch_limit,
+                self.proposal_size_limit,
             )
             .map_err(|err| ServiceStartError::Internal(Box::new(ScabbardError::from(err))))?,
         );
@@ -313,9 +436,9 @@ impl Service for Scabbard {
     fn handle_message(
         &self,
         message_bytes: &[u8],
-        _message_context: &ServiceMessageContext,
+        message_context: &ServiceMessageContext,
     ) -> Result<(), ServiceError> {
-        let message: ScabbardMessage = protobuf::parse_from_bytes(message_bytes)?;
+        let mut message: ScabbardMessage = protobuf::parse_from_bytes(message_bytes)?;
 
         match message.get_message_type() {
             ScabbardMessage_Type::CONSENSUS_MESSAGE => self
@@ -330,13 +453,29 @@ impl Service for Scabbard {
                 let proposed_batch = message.get_proposed_batch();
 
                 let proposal = Proposal::try_from(proposed_batch.get_proposal())?;
-                let batch = BatchPair::from_bytes(proposed_batch.get_batch())
-                    .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?;
+                let batches = if proposed_batch.get_batches().is_empty() {
+                    vec![BatchPair::from_bytes(proposed_batch.get_batch())
+                        .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?]
+                } else {
+                    proposed_batch
+                        .get_batches()
+                        .iter()
+                        .map(|bytes| BatchPair::from_bytes(bytes))
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))?
+                };
 
                 self.shared
                     .lock()
                     .map_err(|_| ServiceError::PoisonedLock("shared lock poisoned".into()))?
-                    .add_proposed_batch(proposal.id.clone(), batch);
+                    .add_proposed_batches(
+                        proposal.id.clone(),
+                        ProposedBatches {
+                            proposal: proposal.clone(),
+                            proposer: proposed_batch.get_service_id().to_string(),
+                            batches,
+                        },
+                    );
 
                 self.consensus
                     .lock()
@@ -349,6 +488,14 @@ impl Service for Scabbard {
                     ))
                     .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err)))
             }
+            ScabbardMessage_Type::STATE_SYNC_REQUEST => self
+                .state_sync
+                .handle_request(&message_context.sender, message.get_state_sync_request())
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err))),
+            ScabbardMessage_Type::STATE_SYNC_RESPONSE => self
+                .state_sync
+                .handle_response(&message_context.sender, message.take_state_sync_response())
+                .map_err(|err| ServiceError::UnableToHandleMessage(Box::new(err))),
             ScabbardMessage_Type::UNSET => Err(ServiceError::InvalidMessageFormat(Box::new(
                 ScabbardError::MessageTypeUnset,
             ))),
@@ -405,6 +552,10 @@ pub mod tests {
             Box::new(HashVerifier),
             vec![],
             None,
+            None,
+            None,
+            None,
+            None,
         )
         .expect("failed to create service");
         assert_eq!(service.service_id(), "new_scabbard");
@@ -426,6 +577,10 @@ pub mod tests {
             Box::new(HashVerifier),
             vec![],
             None,
+            None,
+            None,
+            None,
+            None,
         )
         .expect("failed to create service");
         let registry = MockServiceNetworkRegistry::new();
@@ -447,6 +602,10 @@ pub mod tests {
             Box::new(HashVerifier),
             vec![],
             None,
+            None,
+            None,
+            None,
+            None,
         )
         .expect("failed to create service");
         test_connect_and_disconnect(&mut service);
@@ -14,18 +14,22 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use actix_web::{web, HttpResponse};
 use futures::IntoFuture;
 use splinter::{
-    rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard},
+    rest_api::{
+        paging::{DEFAULT_LIMIT, DEFAULT_OFFSET},
+        ErrorResponse, Method, ProtocolVersionRangeGuard,
+    },
     service::rest_api::ServiceEndpoint,
 };
 
 use crate::protocol;
 use crate::service::{
-    rest_api::resources::batch_statuses::BatchInfoResponse, Scabbard, SERVICE_TYPE,
+    rest_api::resources::batch_statuses::BatchInfoResponse, Scabbard, MAX_BATCH_INFO_LIST_LIMIT,
+    SERVICE_TYPE,
 };
 
 const DEFAULT_BATCH_STATUS_WAIT_SECS: u64 = 300;
@@ -59,31 +63,103 @@ pub fn make_get_batch_status_endpoint() -> ServiceEndpoint {
                     );
                 };
 
-            let ids = if let Some(ids) = query.get("ids") {
-                ids.split(',').map(String::from).collect()
+            let batch_info_iter = if let Some(ids) = query.get("ids") {
+                let ids = ids.split(',').map(String::from).collect();
+
+                let wait = query
+                    .get("wait")
+                    .and_then(|wait_str| {
+                        if wait_str.as_str() == "false" {
+                            None
+                        } else {
+                            wait_str
+                                .parse()
+                                .ok()
+                                .or(Some(DEFAULT_BATCH_STATUS_WAIT_SECS))
+                        }
+                    })
+                    .map(Duration::from_secs);
+
+                scabbard.get_batch_info(ids, wait)
+            } else if let Some(since) = query.get("since") {
+                let since = match parse_time(since) {
+                    Some(since) => since,
+                    None => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request("Invalid since time"))
+                                .into_future(),
+                        );
+                    }
+                };
+                let until = match query.get("until").map(|until| parse_time(until)) {
+                    Some(Some(until)) => Some(until),
+                    Some(None) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request("Invalid until time"))
+                                .into_future(),
+                        );
+                    }
+                    None => None,
+                };
+
+                let offset = match query.get("offset") {
+                    Some(value) => match value.parse::<usize>() {
+                        Ok(val) => val,
+                        Err(err) => {
+                            return Box::new(
+                                HttpResponse::BadRequest()
+                                    .json(ErrorResponse::bad_request(&format!(
+                                        "Invalid offset value passed: {}. Error: {}",
+                                        value, err
+                                    )))
+                                    .into_future(),
+                            )
+                        }
+                    },
+                    None => DEFAULT_OFFSET,
+                };
+
+                let limit = match query.get("limit") {
+                    Some(value) => match value.parse::<usize>() {
+                        Ok(val) if val > 0 && val <= MAX_BATCH_INFO_LIST_LIMIT => val,
+                        Ok(_) => {
+                            return Box::new(
+                                HttpResponse::BadRequest()
+                                    .json(ErrorResponse::bad_request(&format!(
+                                        "Invalid limit value passed: must be between 1 and {}",
+                                        MAX_BATCH_INFO_LIST_LIMIT
+                                    )))
+                                    .into_future(),
+                            )
+                        }
+                        Err(err) => {
+                            return Box::new(
+                                HttpResponse::BadRequest()
+                                    .json(ErrorResponse::bad_request(&format!(
+                                        "Invalid limit value passed: {}. Error: {}",
+                                        value, err
+                                    )))
+                                    .into_future(),
+                            )
+                        }
+                    },
+                    None => DEFAULT_LIMIT,
+                };
+
+                scabbard.get_batch_info_in_range(since, until, offset, limit)
             } else {
                 return Box::new(
                     HttpResponse::BadRequest()
-                        .json(ErrorResponse::bad_request("No batch IDs specified"))
+                        .json(ErrorResponse::bad_request(
+                            "No batch IDs or since time specified",
+                        ))
                         .into_future(),
                 );
             };
 
-            let wait = query
-                .get("wait")
-                .and_then(|wait_str| {
-                    if wait_str.as_str() == "false" {
-                        None
-                    } else {
-                        wait_str
-                            .parse()
-                            .ok()
-                            .or(Some(DEFAULT_BATCH_STATUS_WAIT_SECS))
-                    }
-                })
-                .map(Duration::from_secs);
-
-            let batch_info_iter = match scabbard.get_batch_info(ids, wait) {
+            let batch_info_iter = match batch_info_iter {
                 Ok(iter) => iter,
                 Err(err) => {
                     error!("Failed to get batch statuses iterator: {}", err);
@@ -122,3 +198,172 @@ pub fn make_get_batch_status_endpoint() -> ServiceEndpoint {
         ))],
     }
 }
+
+/// Parses a time given as a number of seconds since the Unix epoch.
+fn parse_time(secs: &str) -> Option<SystemTime> {
+    secs.parse()
+        .ok()
+        .and_then(|secs| UNIX_EPOCH.checked_add(Duration::from_secs(secs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+    use std::thread;
+
+    use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::Value as JsonValue;
+    use tempdir::TempDir;
+
+    use splinter::{
+        rest_api::{Resource, RestApiBuilder, RestApiServerError, RestApiShutdownHandle},
+        service::Service,
+        signing::hash::HashVerifier,
+    };
+
+    const MOCK_CIRCUIT_ID: &str = "abcde-01234";
+    const MOCK_SERVICE_ID: &str = "ABCD";
+    const TEMP_DB_SIZE: usize = 1 << 30; // 1024 ** 3
+
+    /// Verify that the `GET /batch_statuses` endpoint pages through the batches submitted in a
+    /// time range.
+    ///
+    /// 1. Initialize an instance of the `Scabbard` service and add three batches to its batch
+    ///    history.
+    /// 2. Setup the REST API with the endpoint exposed.
+    /// 3. Request the batches since the Unix epoch with a limit of 1 and an offset of 1, verify
+    ///    that the response code is 200, and check that only the second batch is returned.
+    /// 4. Request the batches with a limit of 0 and with a limit above the maximum, and verify
+    ///    that the response code is 400.
+    #[test]
+    fn batch_statuses_in_range() {
+        let temp_dir = TempDir::new("batch_statuses_in_range").expect("Failed to create temp dir");
+
+        // Initialize scabbard and add three batches to its history
+        let scabbard = Scabbard::new(
+            MOCK_SERVICE_ID.into(),
+            MOCK_CIRCUIT_ID,
+            Default::default(),
+            temp_dir.path(),
+            TEMP_DB_SIZE,
+            temp_dir.path(),
+            TEMP_DB_SIZE,
+            Box::new(HashVerifier),
+            vec![],
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to create scabbard");
+        for id in &["batch1", "batch2", "batch3"] {
+            scabbard
+                .state
+                .lock()
+                .expect("State lock poisoned")
+                .batch_history()
+                .add_batch(id)
+                .expect("Failed to add batch");
+            // Make sure each batch is added at a different time
+            thread::sleep(Duration::from_millis(2));
+        }
+
+        // Setup the REST API
+        let scabbard: Arc<Mutex<dyn Service>> = Arc::new(Mutex::new(scabbard));
+        let (shutdown_handle, join_handle, bind_url) =
+            run_rest_api_on_open_port(vec![resource_from_service_endpoint(
+                make_get_batch_status_endpoint(),
+                scabbard,
+            )]);
+
+        // Verify that the offset and limit select a page of the batches
+        let url = Url::parse(&format!(
+            "http://{}/batch_statuses?since=0&limit=1&offset=1",
+            bind_url
+        ))
+        .expect("Failed to parse URL");
+        let resp = get(url);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let batches: JsonValue = resp.json().expect("Failed to deserialize body");
+        let batches = batches.as_array().expect("Response is not a JSON array");
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0]["id"], "batch2");
+
+        // Verify that a limit of 0 is rejected
+        let url = Url::parse(&format!(
+            "http://{}/batch_statuses?since=0&limit=0",
+            bind_url
+        ))
+        .expect("Failed to parse URL");
+        assert_eq!(get(url).status(), StatusCode::BAD_REQUEST);
+
+        // Verify that a limit above the maximum is rejected
+        let url = Url::parse(&format!(
+            "http://{}/batch_statuses?since=0&limit={}",
+            bind_url,
+            MAX_BATCH_INFO_LIST_LIMIT + 1
+        ))
+        .expect("Failed to parse URL");
+        assert_eq!(get(url).status(), StatusCode::BAD_REQUEST);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn get(url: Url) -> reqwest::blocking::Response {
+        Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::SCABBARD_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request")
+    }
+
+    fn resource_from_service_endpoint(
+        service_endpoint: ServiceEndpoint,
+        service: Arc<Mutex<dyn Service>>,
+    ) -> Resource {
+        let mut resource = Resource::build(&service_endpoint.route);
+        for request_guard in service_endpoint.request_guards.into_iter() {
+            resource = resource.add_request_guard(request_guard);
+        }
+        let handler = service_endpoint.handler;
+        resource.add_method(service_endpoint.method, move |request, payload| {
+            (handler)(
+                request,
+                payload,
+                &*service.lock().expect("Service lock poisoned"),
+            )
+        })
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        (10000..20000)
+            .find_map(|port| {
+                let bind_url = format!("127.0.0.1:{}", port);
+                let result = RestApiBuilder::new()
+                    .with_bind(&bind_url)
+                    .add_resources(resources.clone())
+                    .build()
+                    .expect("Failed to build REST API")
+                    .run();
+                match result {
+                    Ok((shutdown_handle, join_handle)) => {
+                        Some((shutdown_handle, join_handle, bind_url))
+                    }
+                    Err(RestApiServerError::BindError(_)) => None,
+                    Err(err) => panic!("Failed to run REST API: {}", err),
+                }
+            })
+            .expect("No port available")
+    }
+}
@@ -14,6 +14,7 @@
 
 pub mod batch_statuses;
 pub mod batches;
+pub mod receipts;
 pub mod state;
 pub mod state_address;
 pub mod state_root;
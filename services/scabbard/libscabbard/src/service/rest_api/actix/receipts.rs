@@ -0,0 +1,441 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use actix_web::{web, HttpResponse};
+use futures::IntoFuture;
+use splinter::{
+    rest_api::{
+        paging::{get_response_paging_info, DEFAULT_LIMIT, DEFAULT_OFFSET},
+        ErrorResponse, Method, ProtocolVersionRangeGuard,
+    },
+    service::rest_api::ServiceEndpoint,
+};
+
+use crate::protocol;
+use crate::service::{
+    rest_api::resources::receipts::{ListTransactionReceiptsResponse, TransactionReceiptResponse},
+    Scabbard, MAX_RECEIPT_LIST_LIMIT, SERVICE_TYPE,
+};
+
+pub fn make_get_receipts_endpoint() -> ServiceEndpoint {
+    ServiceEndpoint {
+        service_type: SERVICE_TYPE.into(),
+        route: "/receipts".into(),
+        method: Method::Get,
+        handler: Arc::new(move |request, _, service| {
+            let scabbard = match service.as_any().downcast_ref::<Scabbard>() {
+                Some(s) => s,
+                None => {
+                    error!("Failed to downcast to scabbard service");
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            let query: web::Query<HashMap<String, String>> =
+                if let Ok(q) = web::Query::from_query(request.query_string()) {
+                    q
+                } else {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid query"))
+                            .into_future(),
+                    );
+                };
+
+            let ids = if let Some(ids) = query.get("ids") {
+                ids.split(',').map(String::from).collect::<Vec<_>>()
+            } else {
+                return Box::new(
+                    HttpResponse::BadRequest()
+                        .json(ErrorResponse::bad_request("No transaction IDs specified"))
+                        .into_future(),
+                );
+            };
+
+            Box::new(match scabbard.get_receipts(&ids) {
+                Ok(receipts) => HttpResponse::Ok()
+                    .json(
+                        receipts
+                            .iter()
+                            .map(TransactionReceiptResponse::from)
+                            .collect::<Vec<_>>(),
+                    )
+                    .into_future(),
+                Err(err) => {
+                    error!("Failed to get transaction receipts: {}", err);
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future()
+                }
+            })
+        }),
+        request_guards: vec![Box::new(ProtocolVersionRangeGuard::new(
+            protocol::SCABBARD_GET_RECEIPTS_PROTOCOL_MIN,
+            protocol::SCABBARD_PROTOCOL_VERSION,
+        ))],
+    }
+}
+
+pub fn make_list_receipts_endpoint() -> ServiceEndpoint {
+    ServiceEndpoint {
+        service_type: SERVICE_TYPE.into(),
+        route: "/receipts/list".into(),
+        method: Method::Get,
+        handler: Arc::new(move |request, _, service| {
+            let scabbard = match service.as_any().downcast_ref::<Scabbard>() {
+                Some(s) => s,
+                None => {
+                    error!("Failed to downcast to scabbard service");
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            let query: web::Query<HashMap<String, String>> =
+                if let Ok(q) = web::Query::from_query(request.query_string()) {
+                    q
+                } else {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid query"))
+                            .into_future(),
+                    );
+                };
+
+            let offset = match query.get("offset") {
+                Some(value) => match value.parse::<usize>() {
+                    Ok(val) => val,
+                    Err(err) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Invalid offset value passed: {}. Error: {}",
+                                    value, err
+                                )))
+                                .into_future(),
+                        )
+                    }
+                },
+                None => DEFAULT_OFFSET,
+            };
+
+            let limit = match query.get("limit") {
+                Some(value) => match value.parse::<usize>() {
+                    Ok(val) if val > 0 && val <= MAX_RECEIPT_LIST_LIMIT => val,
+                    Ok(_) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Invalid limit value passed: must be between 1 and {}",
+                                    MAX_RECEIPT_LIST_LIMIT
+                                )))
+                                .into_future(),
+                        )
+                    }
+                    Err(err) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Invalid limit value passed: {}. Error: {}",
+                                    value, err
+                                )))
+                                .into_future(),
+                        )
+                    }
+                },
+                None => DEFAULT_LIMIT,
+            };
+
+            Box::new(match scabbard.list_receipts(offset, limit) {
+                Ok((receipts, total)) => HttpResponse::Ok()
+                    .json(ListTransactionReceiptsResponse {
+                        data: receipts
+                            .iter()
+                            .map(TransactionReceiptResponse::from)
+                            .collect(),
+                        paging: get_response_paging_info(
+                            Some(limit),
+                            Some(offset),
+                            request.uri().path(),
+                            total,
+                        ),
+                    })
+                    .into_future(),
+                Err(err) => {
+                    error!("Failed to list transaction receipts: {}", err);
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future()
+                }
+            })
+        }),
+        request_guards: vec![Box::new(ProtocolVersionRangeGuard::new(
+            protocol::SCABBARD_LIST_RECEIPTS_PROTOCOL_MIN,
+            protocol::SCABBARD_PROTOCOL_VERSION,
+        ))],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::Value as JsonValue;
+    use tempdir::TempDir;
+    use transact::{
+        families::command::make_command_transaction,
+        protocol::{
+            batch::BatchBuilder,
+            command::{BytesEntry, Command, SetState},
+        },
+        signing::hash::HashSigner,
+    };
+
+    use splinter::{
+        rest_api::{Resource, RestApiBuilder, RestApiServerError, RestApiShutdownHandle},
+        service::Service,
+        signing::hash::HashVerifier,
+    };
+
+    use crate::service::{compute_db_paths, state::ScabbardState, Scabbard};
+
+    const MOCK_CIRCUIT_ID: &str = "abcde-01234";
+    const MOCK_SERVICE_ID: &str = "ABCD";
+    const TEMP_DB_SIZE: usize = 1 << 30; // 1024 ** 3
+
+    /// Verify that the `GET /receipts` and `GET /receipts/list` endpoints work properly.
+    ///
+    /// 1. Initialize a temporary instance of `ScabbardState` and commit two transactions, each in
+    ///    its own batch.
+    /// 2. Initialize an instance of the `Scabbard` service that's backed by the same underlying
+    ///    state that was set in the previous step.
+    /// 3. Setup the REST API with both endpoints exposed.
+    /// 4. Request the receipt of the second transaction and an unknown transaction, verify that the
+    ///    response code is 200, and check that only the second transaction's receipt is returned
+    ///    with its state change.
+    /// 5. Request the receipts without any IDs and verify that the response code is 400.
+    /// 6. List the receipts with a limit of 1 and an offset of 1, verify that the response code is
+    ///    200, and check that the second transaction's receipt is returned with the total count.
+    /// 7. List the receipts with a limit of 0 and verify that the response code is 400.
+    #[test]
+    fn receipts() {
+        let paths = StatePaths::new("receipts");
+
+        // Initialize a temporary scabbard state and commit two transactions
+        let txn_ids = {
+            let mut state = ScabbardState::new(
+                &paths.state_db_path,
+                TEMP_DB_SIZE,
+                &paths.receipt_db_path,
+                TEMP_DB_SIZE,
+                vec![],
+            )
+            .expect("Failed to initialize state");
+
+            let signer = HashSigner::default();
+            let mut txn_ids = vec![];
+            for address in &["abcdef01", "abcdef02"] {
+                let txn = make_command_transaction(&[Command::SetState(SetState::new(vec![
+                    BytesEntry::new(address.to_string(), b"value".to_vec()),
+                ]))])
+                .take()
+                .0;
+                txn_ids.push(txn.header_signature().to_string());
+                let batch = BatchBuilder::new()
+                    .with_transactions(vec![txn])
+                    .build_pair(&signer)
+                    .expect("Failed to build batch");
+                state
+                    .prepare_change(vec![batch])
+                    .expect("Failed to prepare change");
+                state.commit().expect("Failed to commit change");
+            }
+            txn_ids
+        };
+
+        // Initialize scabbard
+        let scabbard = Scabbard::new(
+            MOCK_SERVICE_ID.into(),
+            MOCK_CIRCUIT_ID,
+            Default::default(),
+            paths.temp_dir.path(),
+            TEMP_DB_SIZE,
+            paths.temp_dir.path(),
+            TEMP_DB_SIZE,
+            Box::new(HashVerifier),
+            vec![],
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("Failed to create scabbard");
+
+        // Setup the REST API
+        let scabbard: Arc<Mutex<dyn Service>> = Arc::new(Mutex::new(scabbard));
+        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
+            resource_from_service_endpoint(make_get_receipts_endpoint(), scabbard.clone()),
+            resource_from_service_endpoint(make_list_receipts_endpoint(), scabbard),
+        ]);
+
+        // Verify that only the receipt of the committed transaction is returned
+        let url = Url::parse(&format!(
+            "http://{}/receipts?ids={},unknown",
+            bind_url, txn_ids[1]
+        ))
+        .expect("Failed to parse URL");
+        let resp = get(url);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let receipts: JsonValue = resp.json().expect("Failed to deserialize body");
+        let receipts = receipts.as_array().expect("Response is not a JSON array");
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0]["transaction_id"], txn_ids[1].as_str());
+        assert_eq!(receipts[0]["result"]["resultType"], "Valid");
+        assert_eq!(
+            receipts[0]["result"]["message"]["state_changes"][0]["Set"]["key"],
+            "abcdef02"
+        );
+
+        // Verify that a request without IDs is rejected
+        let url =
+            Url::parse(&format!("http://{}/receipts", bind_url)).expect("Failed to parse URL");
+        assert_eq!(get(url).status(), StatusCode::BAD_REQUEST);
+
+        // Verify that the receipts are listed in commit order with the total count
+        let url = Url::parse(&format!(
+            "http://{}/receipts/list?limit=1&offset=1",
+            bind_url
+        ))
+        .expect("Failed to parse URL");
+        let resp = get(url);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let list: JsonValue = resp.json().expect("Failed to deserialize body");
+        let receipts = list["data"].as_array().expect("data is not a JSON array");
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0]["transaction_id"], txn_ids[1].as_str());
+        assert_eq!(list["paging"]["total"], 2);
+
+        // Verify that a limit of 0 is rejected
+        let url = Url::parse(&format!("http://{}/receipts/list?limit=0", bind_url))
+            .expect("Failed to parse URL");
+        assert_eq!(get(url).status(), StatusCode::BAD_REQUEST);
+
+        // Verify that a limit above the maximum is rejected
+        let url = Url::parse(&format!(
+            "http://{}/receipts/list?limit={}",
+            bind_url,
+            MAX_RECEIPT_LIST_LIMIT + 1
+        ))
+        .expect("Failed to parse URL");
+        assert_eq!(get(url).status(), StatusCode::BAD_REQUEST);
+
+        shutdown_handle
+            .shutdown()
+            .expect("Unable to shutdown rest api");
+        join_handle.join().expect("Unable to join rest api thread");
+    }
+
+    fn get(url: Url) -> reqwest::blocking::Response {
+        Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::SCABBARD_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request")
+    }
+
+    struct StatePaths {
+        pub temp_dir: TempDir,
+        pub state_db_path: PathBuf,
+        pub receipt_db_path: PathBuf,
+    }
+
+    impl StatePaths {
+        fn new(prefix: &str) -> Self {
+            let temp_dir = TempDir::new(prefix).expect("Failed to create temp dir");
+            // This computes the paths such that they're the same ones that will be used by
+            // scabbard when it's initialized
+            let (state_db_path, receipt_db_path) = compute_db_paths(
+                MOCK_SERVICE_ID,
+                MOCK_CIRCUIT_ID,
+                temp_dir.path(),
+                temp_dir.path(),
+            )
+            .expect("Failed to compute DB paths");
+            Self {
+                temp_dir,
+                state_db_path,
+                receipt_db_path,
+            }
+        }
+    }
+
+    fn resource_from_service_endpoint(
+        service_endpoint: ServiceEndpoint,
+        service: Arc<Mutex<dyn Service>>,
+    ) -> Resource {
+        let mut resource = Resource::build(&service_endpoint.route);
+        for request_guard in service_endpoint.request_guards.into_iter() {
+            resource = resource.add_request_guard(request_guard);
+        }
+        let handler = service_endpoint.handler;
+        resource.add_method(service_endpoint.method, move |request, payload| {
+            (handler)(
+                request,
+                payload,
+                &*service.lock().expect("Service lock poisoned"),
+            )
+        })
+    }
+
+    fn run_rest_api_on_open_port(
+        resources: Vec<Resource>,
+    ) -> (RestApiShutdownHandle, std::thread::JoinHandle<()>, String) {
+        (10000..20000)
+            .find_map(|port| {
+                let bind_url = format!("127.0.0.1:{}", port);
+                let result = RestApiBuilder::new()
+                    .with_bind(&bind_url)
+                    .add_resources(resources.clone())
+                    .build()
+                    .expect("Failed to build REST API")
+                    .run();
+                match result {
+                    Ok((shutdown_handle, join_handle)) => {
+                        Some((shutdown_handle, join_handle, bind_url))
+                    }
+                    Err(RestApiServerError::BindError(_)) => None,
+                    Err(err) => panic!("Failed to run REST API: {}", err),
+                }
+            })
+            .expect("No port available")
+    }
+}
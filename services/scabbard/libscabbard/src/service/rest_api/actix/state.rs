@@ -56,7 +56,29 @@ pub fn make_get_state_with_prefix_endpoint() -> ServiceEndpoint {
 
             let prefix = query.get("prefix").map(String::as_str);
 
-            Box::new(match scabbard.get_state_with_prefix(prefix) {
+            let state_root = query.get("state_root").map(String::as_str);
+            if let Some(state_root) = state_root {
+                match scabbard.contains_state_root(state_root) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request("Unknown state root"))
+                                .into_future(),
+                        )
+                    }
+                    Err(err) => {
+                        error!("Failed to check state root: {}", err);
+                        return Box::new(
+                            HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future(),
+                        );
+                    }
+                }
+            }
+
+            Box::new(match scabbard.get_state_with_prefix(prefix, state_root) {
                 Ok(state_iter) => {
                     let res = state_iter.collect::<Result<Vec<_>, _>>();
                     match res {
@@ -172,7 +194,7 @@ mod tests {
                 .build_pair(&signer)
                 .expect("Failed to build batch");
             state
-                .prepare_change(batch)
+                .prepare_change(vec![batch])
                 .expect("Failed to prepare change");
             state.commit().expect("Failed to commit change");
         }
@@ -189,6 +211,10 @@ mod tests {
             Box::new(HashVerifier),
             vec![],
             None,
+            None,
+            None,
+            None,
+            None,
         )
         .expect("Failed to create scabbard");
 
@@ -12,9 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use actix_web::HttpResponse;
+use actix_web::{web, HttpResponse};
 use futures::IntoFuture;
 use splinter::{
     rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard},
@@ -47,7 +48,40 @@ pub fn make_get_state_at_address_endpoint() -> ServiceEndpoint {
                 .get("address")
                 .expect("address should not be none");
 
-            Box::new(match scabbard.get_state_at_address(address) {
+            let query: web::Query<HashMap<String, String>> =
+                if let Ok(q) = web::Query::from_query(request.query_string()) {
+                    q
+                } else {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid query"))
+                            .into_future(),
+                    );
+                };
+
+            let state_root = query.get("state_root").map(String::as_str);
+            if let Some(state_root) = state_root {
+                match scabbard.contains_state_root(state_root) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request("Unknown state root"))
+                                .into_future(),
+                        )
+                    }
+                    Err(err) => {
+                        error!("Failed to check state root: {}", err);
+                        return Box::new(
+                            HttpResponse::InternalServerError()
+                                .json(ErrorResponse::internal_error())
+                                .into_future(),
+                        );
+                    }
+                }
+            }
+
+            Box::new(match scabbard.get_state_at_address(address, state_root) {
                 Ok(Some(value)) => HttpResponse::Ok().json(value).into_future(),
                 Ok(None) => HttpResponse::NotFound()
                     .json(ErrorResponse::not_found("Address not set"))
@@ -99,14 +133,20 @@ mod tests {
 
     /// Verify that the `GET /state/{address}` endpoint works properly.
     ///
-    /// 1. Initialize a temporary instance of `ScabbardState` and set a single address in state.
+    /// 1. Initialize a temporary instance of `ScabbardState` and set a single address in state,
+    ///    then set the address again to a new value.
     /// 2. Initialize an instance of the `Scabbard` service that's backed by the same underlying
     ///    state that was set in the previous step.
     /// 3. Setup the REST API with the `GET /state/{address}` endpoint exposed.
     /// 4. Make a request to the endpoint with an unset address and verify that the response code is
     ///    400 to indicate that the address was not found (unset).
     /// 5. Make a request to the endpoint with the previously set address, verify that the response
-    ///    code is 200, and check that the body of the response is the value that was set in state.
+    ///    code is 200, and check that the body of the response is the latest value that was set in
+    ///    state.
+    /// 6. Make a request to the endpoint with the state root of the first change, verify that the
+    ///    response code is 200, and check that the body of the response is the first value.
+    /// 7. Make a request to the endpoint with an unknown state root and verify that the response
+    ///    code is 400.
     #[test]
     fn state_at_address() {
         let paths = StatePaths::new("state_at_address");
@@ -114,7 +154,8 @@ mod tests {
         // Initialize a temporary scabbard state and set a value; this will pre-populate the DBs
         let address = "abcdef".to_string();
         let value = b"value".to_vec();
-        {
+        let new_value = b"new_value".to_vec();
+        let first_state_root = {
             let mut state = ScabbardState::new(
                 &paths.state_db_path,
                 TEMP_DB_SIZE,
@@ -125,21 +166,26 @@ mod tests {
             .expect("Failed to initialize state");
 
             let signer = HashSigner::default();
-            let batch = BatchBuilder::new()
-                .with_transactions(vec![
-                    make_command_transaction(&[Command::SetState(SetState::new(vec![
-                        BytesEntry::new(address.clone(), value.clone()),
-                    ]))])
-                    .take()
-                    .0,
-                ])
-                .build_pair(&signer)
-                .expect("Failed to build batch");
-            state
-                .prepare_change(batch)
-                .expect("Failed to prepare change");
-            state.commit().expect("Failed to commit change");
-        }
+            let mut first_state_root = None;
+            for value in vec![value.clone(), new_value.clone()] {
+                let batch = BatchBuilder::new()
+                    .with_transactions(vec![
+                        make_command_transaction(&[Command::SetState(SetState::new(vec![
+                            BytesEntry::new(address.clone(), value),
+                        ]))])
+                        .take()
+                        .0,
+                    ])
+                    .build_pair(&signer)
+                    .expect("Failed to build batch");
+                state
+                    .prepare_change(vec![batch])
+                    .expect("Failed to prepare change");
+                state.commit().expect("Failed to commit change");
+                first_state_root.get_or_insert_with(|| state.current_state_root().to_string());
+            }
+            first_state_root.expect("No state root committed")
+        };
 
         // Initialize scabbard
         let scabbard = Scabbard::new(
@@ -153,6 +199,10 @@ mod tests {
             Box::new(HashVerifier),
             vec![],
             None,
+            None,
+            None,
+            None,
+            None,
         )
         .expect("Failed to create scabbard");
 
@@ -189,8 +239,44 @@ mod tests {
             .expect("Failed to perform request");
         assert_eq!(resp.status(), StatusCode::OK);
         let response_value: Vec<u8> = resp.json().expect("Failed to deserialize body");
+        assert_eq!(response_value, new_value);
+
+        // Verify that a request at the first state root returns the value set by the first change
+        let url = Url::parse(&format!(
+            "{}/{}?state_root={}",
+            base_url, address, first_state_root
+        ))
+        .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::SCABBARD_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let response_value: Vec<u8> = resp.json().expect("Failed to deserialize body");
         assert_eq!(response_value, value);
 
+        // Verify that a request at an unknown state root results in a BAD_REQUEST response
+        let url = Url::parse(&format!(
+            "{}/{}?state_root={}",
+            base_url,
+            address,
+            "0".repeat(64)
+        ))
+        .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::SCABBARD_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
         shutdown_handle
             .shutdown()
             .expect("Unable to shutdown rest api");
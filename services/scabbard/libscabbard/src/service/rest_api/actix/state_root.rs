@@ -12,17 +12,24 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use actix_web::HttpResponse;
+use actix_web::{web, HttpResponse};
 use futures::IntoFuture;
 use splinter::{
-    rest_api::{ErrorResponse, Method, ProtocolVersionRangeGuard},
+    rest_api::{
+        paging::{get_response_paging_info, DEFAULT_LIMIT, DEFAULT_OFFSET},
+        ErrorResponse, Method, ProtocolVersionRangeGuard,
+    },
     service::rest_api::ServiceEndpoint,
 };
 
 use crate::protocol;
-use crate::service::{Scabbard, SERVICE_TYPE};
+use crate::service::{
+    rest_api::resources::state::{CommittedRootResponse, ListCommittedRootsResponse},
+    Scabbard, SERVICE_TYPE,
+};
 
 pub fn make_get_state_root_endpoint() -> ServiceEndpoint {
     ServiceEndpoint {
@@ -59,6 +66,105 @@ pub fn make_get_state_root_endpoint() -> ServiceEndpoint {
     }
 }
 
+pub fn make_list_state_roots_endpoint() -> ServiceEndpoint {
+    ServiceEndpoint {
+        service_type: SERVICE_TYPE.into(),
+        route: "/state_roots".into(),
+        method: Method::Get,
+        handler: Arc::new(move |request, _, service| {
+            let scabbard = match service.as_any().downcast_ref::<Scabbard>() {
+                Some(s) => s,
+                None => {
+                    error!("Failed to downcast to scabbard service");
+                    return Box::new(
+                        HttpResponse::InternalServerError()
+                            .json(ErrorResponse::internal_error())
+                            .into_future(),
+                    );
+                }
+            };
+
+            let query: web::Query<HashMap<String, String>> =
+                if let Ok(q) = web::Query::from_query(request.query_string()) {
+                    q
+                } else {
+                    return Box::new(
+                        HttpResponse::BadRequest()
+                            .json(ErrorResponse::bad_request("Invalid query"))
+                            .into_future(),
+                    );
+                };
+
+            let offset = match query.get("offset") {
+                Some(value) => match value.parse::<usize>() {
+                    Ok(val) => val,
+                    Err(err) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Invalid offset value passed: {}. Error: {}",
+                                    value, err
+                                )))
+                                .into_future(),
+                        )
+                    }
+                },
+                None => DEFAULT_OFFSET,
+            };
+
+            let limit = match query.get("limit") {
+                Some(value) => match value.parse::<usize>() {
+                    Ok(val) if val > 0 => val,
+                    Ok(_) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(
+                                    "Invalid limit value passed: must be greater than 0",
+                                ))
+                                .into_future(),
+                        )
+                    }
+                    Err(err) => {
+                        return Box::new(
+                            HttpResponse::BadRequest()
+                                .json(ErrorResponse::bad_request(&format!(
+                                    "Invalid limit value passed: {}. Error: {}",
+                                    value, err
+                                )))
+                                .into_future(),
+                        )
+                    }
+                },
+                None => DEFAULT_LIMIT,
+            };
+
+            Box::new(match scabbard.list_committed_roots(offset, limit) {
+                Ok((roots, total)) => HttpResponse::Ok()
+                    .json(ListCommittedRootsResponse {
+                        data: roots.iter().map(CommittedRootResponse::from).collect(),
+                        paging: get_response_paging_info(
+                            Some(limit),
+                            Some(offset),
+                            request.uri().path(),
+                            total,
+                        ),
+                    })
+                    .into_future(),
+                Err(err) => {
+                    error!("Failed to list committed state roots: {}", err);
+                    HttpResponse::InternalServerError()
+                        .json(ErrorResponse::internal_error())
+                        .into_future()
+                }
+            })
+        }),
+        request_guards: vec![Box::new(ProtocolVersionRangeGuard::new(
+            protocol::SCABBARD_LIST_STATE_ROOTS_PROTOCOL_MIN,
+            protocol::SCABBARD_PROTOCOL_VERSION,
+        ))],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,6 +173,7 @@ mod tests {
     use std::sync::Mutex;
 
     use reqwest::{blocking::Client, StatusCode, Url};
+    use serde_json::Value as JsonValue;
     use tempdir::TempDir;
     use transact::{
         families::command::make_command_transaction,
@@ -89,22 +196,27 @@ mod tests {
     const MOCK_SERVICE_ID: &str = "ABCD";
     const TEMP_DB_SIZE: usize = 1 << 30; // 1024 ** 3
 
-    /// Verify that the `GET /state_root` endpoint works properly.
+    /// Verify that the `GET /state_root` and `GET /state_roots` endpoints work properly.
     ///
     /// 1. Initialize a temporary instance of `ScabbardState`, set some values in state, and get
     ///    the resulting state root hash.
     /// 2. Initialize an instance of the `Scabbard` service that's backed by the same underlying
     ///    state that was set in the previous step.
-    /// 3. Setup the REST API with the `GET /state_root` endpoint exposed.
-    /// 3. Make a request to the endpoint, verify that the response code is 200, and check that the
-    ///    body of the response contains the same state root hash that was reported in step (1).
+    /// 3. Setup the REST API with the `GET /state_root` and `GET /state_roots` endpoints exposed.
+    /// 4. Make a request to the `GET /state_root` endpoint, verify that the response code is 200,
+    ///    and check that the body of the response contains the same state root hash that was
+    ///    reported in step (1).
+    /// 5. Make a request to the `GET /state_roots` endpoint, verify that the response code is 200,
+    ///    and check that the state root is listed with the ID of the batch that produced it.
+    /// 6. Make a request to the `GET /state_roots` endpoint with a limit of 0 and verify that the
+    ///    response code is 400.
     #[test]
     fn state_root() {
         let paths = StatePaths::new("state_root");
 
         // Initialize a temporary scabbard state and set some values to pre-populate the DBs, then
         // get the resulting state root hash.
-        let expected_state_root = {
+        let (expected_state_root, batch_id) = {
             let mut state = ScabbardState::new(
                 &paths.state_db_path,
                 TEMP_DB_SIZE,
@@ -126,11 +238,12 @@ mod tests {
                 ])
                 .build_pair(&signer)
                 .expect("Failed to build batch");
+            let batch_id = batch.batch().header_signature().to_string();
             state
-                .prepare_change(batch)
+                .prepare_change(vec![batch])
                 .expect("Failed to prepare change");
             state.commit().expect("Failed to commit change");
-            state.current_state_root().to_string()
+            (state.current_state_root().to_string(), batch_id)
         };
 
         // Initialize scabbard
@@ -145,15 +258,19 @@ mod tests {
             Box::new(HashVerifier),
             vec![],
             None,
+            None,
+            None,
+            None,
+            None,
         )
         .expect("Failed to create scabbard");
 
         // Setup the REST API
-        let (shutdown_handle, join_handle, bind_url) =
-            run_rest_api_on_open_port(vec![resource_from_service_endpoint(
-                make_get_state_root_endpoint(),
-                Arc::new(Mutex::new(scabbard)),
-            )]);
+        let scabbard: Arc<Mutex<dyn Service>> = Arc::new(Mutex::new(scabbard));
+        let (shutdown_handle, join_handle, bind_url) = run_rest_api_on_open_port(vec![
+            resource_from_service_endpoint(make_get_state_root_endpoint(), scabbard.clone()),
+            resource_from_service_endpoint(make_list_state_roots_endpoint(), scabbard),
+        ]);
 
         // Verify that a request is successful and the correct state root hash is returned
         let url =
@@ -170,6 +287,39 @@ mod tests {
         let response_state_root: String = resp.json().expect("Failed to deserialize body");
         assert_eq!(response_state_root, expected_state_root);
 
+        // Verify that the state root is listed with the ID of the batch that produced it
+        let url =
+            Url::parse(&format!("http://{}/state_roots", bind_url)).expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::SCABBARD_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::OK);
+        let list: JsonValue = resp.json().expect("Failed to deserialize body");
+        let roots = list["data"].as_array().expect("data is not a JSON array");
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0]["height"], 1);
+        assert_eq!(roots[0]["state_root"], expected_state_root.as_str());
+        assert_eq!(roots[0]["batch_ids"][0], batch_id.as_str());
+        assert_eq!(list["paging"]["total"], 1);
+
+        // Verify that a limit of 0 is rejected
+        let url = Url::parse(&format!("http://{}/state_roots?limit=0", bind_url))
+            .expect("Failed to parse URL");
+        let resp = Client::new()
+            .get(url)
+            .header(
+                "SplinterProtocolVersion",
+                protocol::SCABBARD_PROTOCOL_VERSION,
+            )
+            .send()
+            .expect("Failed to perform request");
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
         shutdown_handle
             .shutdown()
             .expect("Unable to shutdown rest api");
@@ -14,4 +14,5 @@
 
 pub mod batch_statuses;
 pub mod batches;
+pub mod receipts;
 pub mod state;
@@ -0,0 +1,110 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use splinter::rest_api::paging::Paging;
+use transact::protocol::receipt::{Event, StateChange, TransactionReceipt, TransactionResult};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransactionReceiptResponse<'a> {
+    pub transaction_id: &'a str,
+    pub result: TransactionResultResponse<'a>,
+}
+
+impl<'a> From<&'a TransactionReceipt> for TransactionReceiptResponse<'a> {
+    fn from(receipt: &'a TransactionReceipt) -> Self {
+        Self {
+            transaction_id: &receipt.transaction_id,
+            result: TransactionResultResponse::from(&receipt.transaction_result),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "resultType", content = "message")]
+pub enum TransactionResultResponse<'a> {
+    Valid {
+        state_changes: Vec<StateChangeResponse<'a>>,
+        events: Vec<EventResponse<'a>>,
+        data: &'a [Vec<u8>],
+    },
+    Invalid {
+        error_message: &'a str,
+        error_data: &'a [u8],
+    },
+}
+
+impl<'a> From<&'a TransactionResult> for TransactionResultResponse<'a> {
+    fn from(result: &'a TransactionResult) -> Self {
+        match result {
+            TransactionResult::Valid {
+                state_changes,
+                events,
+                data,
+            } => TransactionResultResponse::Valid {
+                state_changes: state_changes
+                    .iter()
+                    .map(StateChangeResponse::from)
+                    .collect(),
+                events: events.iter().map(EventResponse::from).collect(),
+                data,
+            },
+            TransactionResult::Invalid {
+                error_message,
+                error_data,
+            } => TransactionResultResponse::Invalid {
+                error_message,
+                error_data,
+            },
+        }
+    }
+}
+
+/// Serialized the same way as the state changes of `StateChangeEvent`s
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum StateChangeResponse<'a> {
+    Set { key: &'a str, value: &'a [u8] },
+    Delete { key: &'a str },
+}
+
+impl<'a> From<&'a StateChange> for StateChangeResponse<'a> {
+    fn from(change: &'a StateChange) -> Self {
+        match change {
+            StateChange::Set { key, value } => StateChangeResponse::Set { key, value },
+            StateChange::Delete { key } => StateChangeResponse::Delete { key },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventResponse<'a> {
+    pub event_type: &'a str,
+    pub attributes: &'a [(String, String)],
+    pub data: &'a [u8],
+}
+
+impl<'a> From<&'a Event> for EventResponse<'a> {
+    fn from(event: &'a Event) -> Self {
+        Self {
+            event_type: &event.event_type,
+            attributes: &event.attributes,
+            data: &event.data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListTransactionReceiptsResponse<'a> {
+    pub data: Vec<TransactionReceiptResponse<'a>>,
+    pub paging: Paging,
+}
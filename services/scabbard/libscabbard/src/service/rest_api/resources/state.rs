@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use splinter::rest_api::paging::Paging;
+
+use crate::service::state::CommittedRoot;
+
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct StateEntryResponse<'a> {
     pub address: &'a str,
@@ -26,3 +30,26 @@ impl<'a> From<&'a (String, Vec<u8>)> for StateEntryResponse<'a> {
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CommittedRootResponse<'a> {
+    pub height: u64,
+    pub state_root: &'a str,
+    pub batch_ids: &'a [String],
+}
+
+impl<'a> From<&'a CommittedRoot> for CommittedRootResponse<'a> {
+    fn from(root: &'a CommittedRoot) -> Self {
+        Self {
+            height: root.height,
+            state_root: &root.state_root,
+            batch_ids: &root.batch_ids,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListCommittedRootsResponse<'a> {
+    pub data: Vec<CommittedRootResponse<'a>>,
+    pub paging: Paging,
+}
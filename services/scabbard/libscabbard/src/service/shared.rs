@@ -19,7 +19,7 @@ use transact::protocol::transaction::{HashMethod, TransactionHeader};
 use transact::protos::FromBytes;
 
 use splinter::{
-    consensus::ProposalId,
+    consensus::{Proposal, ProposalId},
     service::ServiceNetworkSender,
     signing::{hash::HashVerifier, SignatureVerifier},
 };
@@ -28,6 +28,14 @@ use crate::hex::parse_hex;
 
 use super::error::ScabbardError;
 
+/// The batches of a proposal, along with the proposal itself and the service that proposed it
+#[derive(Clone)]
+pub struct ProposedBatches {
+    pub proposal: Proposal,
+    pub proposer: String,
+    pub batches: Vec<BatchPair>,
+}
+
 /// Data structure used to store information that's shared between components in this service
 pub struct ScabbardShared {
     /// Queue of batches that have been submitted locally via the REST API, but have not yet been
@@ -39,7 +47,7 @@ pub struct ScabbardShared {
     /// List of service IDs that this service is configured to communicate and share state with.
     peer_services: HashSet<String>,
     /// Tracks which batches are currently being evaluated, indexed by corresponding proposal IDs.
-    proposed_batches: HashMap<ProposalId, BatchPair>,
+    proposed_batches: HashMap<ProposalId, ProposedBatches>,
     signature_verifier: Box<dyn SignatureVerifier>,
 }
 
@@ -63,8 +71,27 @@ impl ScabbardShared {
         self.batch_queue.push_back(batch)
     }
 
-    pub fn pop_batch_from_queue(&mut self) -> Option<BatchPair> {
-        self.batch_queue.pop_front()
+    /// Removes up to `max_count` batches from the front of the queue, stopping before the total
+    /// size of the removed batches would exceed `max_bytes`. The first batch in the queue is always
+    /// removed, even if it is larger than `max_bytes`, so that large batches are still proposed.
+    pub fn pop_batches_from_queue(&mut self, max_count: usize, max_bytes: usize) -> Vec<BatchPair> {
+        let mut batches = vec![];
+        let mut total_bytes = 0;
+        while batches.len() < max_count {
+            let size = match self.batch_queue.front() {
+                Some(batch) => batch_size(batch),
+                None => break,
+            };
+            if !batches.is_empty() && total_bytes + size > max_bytes {
+                break;
+            }
+            total_bytes += size;
+            // The front of the queue was checked above
+            if let Some(batch) = self.batch_queue.pop_front() {
+                batches.push(batch);
+            }
+        }
+        batches
     }
 
     pub fn network_sender(&self) -> Option<&dyn ServiceNetworkSender> {
@@ -83,19 +110,19 @@ impl ScabbardShared {
         &self.peer_services
     }
 
-    pub fn add_proposed_batch(
+    pub fn add_proposed_batches(
         &mut self,
         proposal_id: ProposalId,
-        batch: BatchPair,
-    ) -> Option<BatchPair> {
-        self.proposed_batches.insert(proposal_id, batch)
+        proposed_batches: ProposedBatches,
+    ) -> Option<ProposedBatches> {
+        self.proposed_batches.insert(proposal_id, proposed_batches)
     }
 
-    pub fn get_proposed_batch(&self, proposal_id: &ProposalId) -> Option<&BatchPair> {
+    pub fn get_proposed_batches(&self, proposal_id: &ProposalId) -> Option<&ProposedBatches> {
         self.proposed_batches.get(proposal_id)
     }
 
-    pub fn remove_proposed_batch(&mut self, proposal_id: &ProposalId) -> Option<BatchPair> {
+    pub fn remove_proposed_batches(&mut self, proposal_id: &ProposalId) -> Option<ProposedBatches> {
         self.proposed_batches.remove(&proposal_id)
     }
 
@@ -198,3 +225,16 @@ impl ScabbardShared {
         Ok(true)
     }
 }
+
+/// Approximates the serialized size of a batch by the size of its headers, signatures, and
+/// transaction payloads.
+fn batch_size(batch: &BatchPair) -> usize {
+    let batch = batch.batch();
+    batch.header().len()
+        + batch.header_signature().len()
+        + batch
+            .transactions()
+            .iter()
+            .map(|txn| txn.header().len() + txn.header_signature().len() + txn.payload().len())
+            .sum::<usize>()
+}
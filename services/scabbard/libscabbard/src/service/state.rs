@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashSet, VecDeque};
 use std::convert::TryFrom;
 use std::fmt;
 use std::path::Path;
@@ -20,7 +20,7 @@ use std::sync::{
     mpsc::{channel, Receiver, RecvTimeoutError, Sender},
     Arc, RwLock,
 };
-use std::time::{Duration, Instant, SystemTime};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use protobuf::Message;
 use sawtooth::store::{lmdb::LmdbOrderedStore, receipt_store::TransactionReceiptStore};
@@ -59,7 +59,11 @@ const EXECUTION_TIMEOUT: u64 = 300; // five minutes
 const CURRENT_STATE_ROOT_INDEX: &str = "current_state_root";
 const ITER_CACHE_SIZE: usize = 64;
 const COMPLETED_BATCH_INFO_ITER_RETRY_MILLIS: u64 = 100;
-const DEFAULT_BATCH_HISTORY_SIZE: usize = 100;
+const BATCH_HISTORY_INDEX: &str = "batch_history";
+const BATCH_HISTORY_TIME_INDEX: &str = "batch_history_time";
+pub(super) const DEFAULT_BATCH_HISTORY_SIZE: usize = 10_000;
+const STATE_ROOT_HISTORY_INDEX: &str = "state_root_history";
+const STATE_ROOT_HISTORY_SIZE: usize = 1000;
 
 /// Iterator over entries in a Scabbard service's state
 pub type StateIter = Box<dyn Iterator<Item = Result<(String, Vec<u8>), ScabbardStateError>>>;
@@ -69,8 +73,11 @@ pub struct ScabbardState {
     context_manager: ContextManager,
     executor: Executor,
     current_state_root: String,
+    /// The number of changes that have been committed, including changes applied from snapshots
+    height: u64,
     transaction_receipt_store: Arc<RwLock<TransactionReceiptStore>>,
-    pending_changes: Option<(String, Vec<TransactionReceipt>)>,
+    /// The signatures of the valid batches of the prepared change, and their receipts
+    pending_changes: Option<(Vec<String>, Vec<TransactionReceipt>)>,
     event_subscribers: Vec<Box<dyn StateSubscriber>>,
     batch_history: BatchHistory,
 }
@@ -86,6 +93,9 @@ impl ScabbardState {
         // Initialize the database
         let mut indexes = INDEXES.to_vec();
         indexes.push(CURRENT_STATE_ROOT_INDEX);
+        indexes.push(BATCH_HISTORY_INDEX);
+        indexes.push(BATCH_HISTORY_TIME_INDEX);
+        indexes.push(STATE_ROOT_HISTORY_INDEX);
         let db = Box::new(LmdbDatabase::new(
             LmdbContext::new(state_db_path, indexes.len(), Some(state_db_size))?,
             &indexes,
@@ -121,6 +131,9 @@ impl ScabbardState {
             )?
         };
 
+        let height = Self::read_height(&*db)?;
+        let batch_history = BatchHistory::new(db.clone_box())?;
+
         // Initialize transact
         let context_manager = ContextManager::new(Box::new(MerkleState::new(db.clone())));
         let mut executor = Executor::new(vec![Box::new(StaticExecutionAdapter::new_adapter(
@@ -142,6 +155,7 @@ impl ScabbardState {
             context_manager,
             executor,
             current_state_root,
+            height,
             transaction_receipt_store: Arc::new(RwLock::new(TransactionReceiptStore::new(
                 Box::new(
                     LmdbOrderedStore::new(receipt_db_path, Some(receipt_db_size))
@@ -150,7 +164,7 @@ impl ScabbardState {
             ))),
             pending_changes: None,
             event_subscribers: vec![],
-            batch_history: BatchHistory::new(),
+            batch_history,
         })
     }
 
@@ -161,7 +175,29 @@ impl ScabbardState {
             .map_err(|e| ScabbardStateError(format!("Unable to read HEAD entry: {}", e)))
     }
 
-    fn write_current_state_root(&self) -> Result<(), ScabbardStateError> {
+    fn read_height(db: &dyn Database) -> Result<u64, ScabbardStateError> {
+        let height = db
+            .get_reader()
+            .and_then(|reader| reader.index_get(CURRENT_STATE_ROOT_INDEX, b"HEIGHT"))
+            .map_err(|e| ScabbardStateError(format!("Unable to read HEIGHT entry: {}", e)))?;
+
+        // State created before heights were tracked starts at 0
+        match height {
+            Some(bytes) => {
+                let mut height = [0; 8];
+                if bytes.len() != height.len() {
+                    return Err(ScabbardStateError("HEIGHT entry is invalid".into()));
+                }
+                height.copy_from_slice(&bytes);
+                Ok(u64::from_be_bytes(height))
+            }
+            None => Ok(0),
+        }
+    }
+
+    /// Writes the current state root and height, and records the root in the state root history
+    /// along with the IDs of the batches that were committed to produce it.
+    fn write_current_state_root(&self, batch_ids: &[String]) -> Result<(), ScabbardStateError> {
         let current_root_bytes = hex::parse_hex(&self.current_state_root).map_err(|e| {
             ScabbardStateError(format!(
                 "The in-memory current state root is invalid: {}",
@@ -169,6 +205,25 @@ impl ScabbardState {
             ))
         })?;
 
+        // Only the most recent roots are kept in the history
+        let expired_keys = {
+            let reader = self.db.get_reader().map_err(|e| {
+                ScabbardStateError(format!(
+                    "Unable to start read transaction for state root history: {}",
+                    e
+                ))
+            })?;
+            let count = reader.index_count(STATE_ROOT_HISTORY_INDEX).map_err(|e| {
+                ScabbardStateError(format!("Unable to count committed roots: {}", e))
+            })?;
+            reader
+                .index_cursor(STATE_ROOT_HISTORY_INDEX)
+                .map_err(|e| ScabbardStateError(format!("Unable to read committed roots: {}", e)))?
+                .take((count + 1).saturating_sub(STATE_ROOT_HISTORY_SIZE))
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>()
+        };
+
         let mut writer = self.db.get_writer().map_err(|e| {
             ScabbardStateError(format!(
                 "Unable to start write transaction for HEAD entry: {}",
@@ -180,6 +235,35 @@ impl ScabbardState {
             .index_put(CURRENT_STATE_ROOT_INDEX, b"HEAD", &current_root_bytes)
             .map_err(|e| ScabbardStateError(format!("Unable to write HEAD entry: {}", e)))?;
 
+        writer
+            .index_put(
+                CURRENT_STATE_ROOT_INDEX,
+                b"HEIGHT",
+                &self.height.to_be_bytes(),
+            )
+            .map_err(|e| ScabbardStateError(format!("Unable to write HEIGHT entry: {}", e)))?;
+
+        let committed_root = serde_json::to_vec(&CommittedRoot {
+            height: self.height,
+            state_root: self.current_state_root.clone(),
+            batch_ids: batch_ids.to_vec(),
+        })
+        .map_err(|e| ScabbardStateError(format!("Unable to serialize committed root: {}", e)))?;
+        writer
+            .index_put(
+                STATE_ROOT_HISTORY_INDEX,
+                &self.height.to_be_bytes(),
+                &committed_root,
+            )
+            .map_err(|e| ScabbardStateError(format!("Unable to write committed root: {}", e)))?;
+        for key in expired_keys {
+            writer
+                .index_delete(STATE_ROOT_HISTORY_INDEX, &key)
+                .map_err(|e| {
+                    ScabbardStateError(format!("Unable to remove committed root: {}", e))
+                })?;
+        }
+
         writer
             .commit()
             .map_err(|e| ScabbardStateError(format!("Unable to commit HEAD entry: {}", e)))?;
@@ -188,40 +272,160 @@ impl ScabbardState {
     }
 
     /// Fetch the value at the given `address` in state. Returns `None` if the `address` is not set.
+    /// If a `state_root` is provided, the value is read from state at that root instead of the
+    /// current state root.
     pub fn get_state_at_address(
         &self,
         address: &str,
+        state_root: Option<&str>,
     ) -> Result<Option<Vec<u8>>, ScabbardStateError> {
-        Ok(
-            MerkleRadixTree::new(self.db.clone(), Some(&self.current_state_root))?
-                .get_value(address)?,
-        )
+        Ok(MerkleRadixTree::new(
+            self.db.clone(),
+            Some(state_root.unwrap_or(&self.current_state_root)),
+        )?
+        .get_value(address)?)
     }
 
     /// Fetch a list of entries in state. If a `prefix` is provided, only return entries whose
     /// addresses are under the given address prefix. If no `prefix` is provided, return all state
-    /// entries.
+    /// entries. If a `state_root` is provided, the entries are read from state at that root
+    /// instead of the current state root.
     pub fn get_state_with_prefix(
         &self,
         prefix: Option<&str>,
+        state_root: Option<&str>,
     ) -> Result<StateIter, ScabbardStateError> {
         Ok(Box::new(
-            MerkleRadixTree::new(self.db.clone(), Some(&self.current_state_root))?
-                .leaves(prefix)
-                .or_else(|err| match err {
-                    StateDatabaseError::NotFound(_) => Ok(Box::new(std::iter::empty())),
-                    err => Err(err),
-                })?
-                .map(|res| res.map_err(ScabbardStateError::from)),
+            MerkleRadixTree::new(
+                self.db.clone(),
+                Some(state_root.unwrap_or(&self.current_state_root)),
+            )?
+            .leaves(prefix)
+            .or_else(|err| match err {
+                StateDatabaseError::NotFound(_) => Ok(Box::new(std::iter::empty())),
+                err => Err(err),
+            })?
+            .map(|res| res.map_err(ScabbardStateError::from)),
         ))
     }
 
+    /// Check whether state can be read at the given state root.
+    pub fn contains_state_root(&self, state_root: &str) -> Result<bool, ScabbardStateError> {
+        match MerkleRadixTree::new(self.db.clone(), Some(state_root)) {
+            Ok(_) => Ok(true),
+            Err(StateDatabaseError::NotFound(_)) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// List the most recently committed state roots, newest first, with the IDs of the batches
+    /// that were committed to produce them. Returns the requested roots and the total number of
+    /// roots in the history.
+    ///
+    /// Roots produced by applying a snapshot from a peer are listed without batch IDs.
+    pub fn list_committed_roots(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<CommittedRoot>, usize), ScabbardStateError> {
+        let reader = self.db.get_reader()?;
+        let total = reader.index_count(STATE_ROOT_HISTORY_INDEX)?;
+        let mut roots = reader
+            .index_cursor(STATE_ROOT_HISTORY_INDEX)?
+            .skip(total.saturating_sub(offset.saturating_add(limit)))
+            .take(limit.min(total.saturating_sub(offset)))
+            .map(|(_, value)| {
+                serde_json::from_slice(&value).map_err(|err| {
+                    ScabbardStateError(format!("failed to deserialize committed root: {}", err))
+                })
+            })
+            .collect::<Result<Vec<CommittedRoot>, _>>()?;
+        roots.reverse();
+
+        Ok((roots, total))
+    }
+
     /// Get the current state root hash.
     pub fn current_state_root(&self) -> &str {
         &self.current_state_root
     }
 
-    pub fn prepare_change(&mut self, batch: BatchPair) -> Result<String, ScabbardStateError> {
+    /// Get the number of changes that have been committed.
+    pub fn current_height(&self) -> u64 {
+        self.height
+    }
+
+    /// Replaces the current state with a snapshot of a peer's state at the given height.
+    ///
+    /// The snapshot is verified by checking that it produces the expected state root; if it does
+    /// not, the snapshot is discarded. Batches committed by the peer since this service's height
+    /// are not added to the batch history or the transaction receipt store, and no state change
+    /// events are sent for them.
+    pub fn apply_snapshot(
+        &mut self,
+        height: u64,
+        state_root: &str,
+        entries: Vec<(String, Vec<u8>)>,
+    ) -> Result<(), ScabbardStateError> {
+        if self.pending_changes.is_some() {
+            return Err(ScabbardStateError(
+                "cannot apply snapshot while a change is pending".into(),
+            ));
+        }
+
+        let snapshot_addresses = entries
+            .iter()
+            .map(|(address, _)| address.clone())
+            .collect::<HashSet<_>>();
+        let mut state_changes = self
+            .get_state_with_prefix(None, None)?
+            .map(|res| res.map(|(address, _)| address))
+            .filter(|res| match res {
+                Ok(address) => !snapshot_addresses.contains(address),
+                Err(_) => true,
+            })
+            .map(|res| res.map(|key| TransactStateChange::Delete { key }))
+            .collect::<Result<Vec<_>, _>>()?;
+        state_changes.extend(
+            entries
+                .into_iter()
+                .map(|(key, value)| TransactStateChange::Set { key, value }),
+        );
+
+        let merkle_state = MerkleState::new(self.db.clone());
+        let computed_root =
+            merkle_state.compute_state_id(&self.current_state_root, &state_changes)?;
+        if computed_root != state_root {
+            return Err(ScabbardStateError(format!(
+                "snapshot produces state root {} instead of {}",
+                computed_root, state_root
+            )));
+        }
+
+        self.current_state_root = merkle_state.commit(&self.current_state_root, &state_changes)?;
+        self.height = height;
+        self.write_current_state_root(&[])?;
+
+        info!(
+            "applied snapshot with {} change(s) for state root {} at height {}",
+            state_changes.len(),
+            self.current_state_root,
+            self.height,
+        );
+
+        Ok(())
+    }
+
+    /// Executes the given batches in order on top of the current state and prepares their changes
+    /// to be committed. Each batch sees the changes of the valid batches before it.
+    ///
+    /// Returns the state root that committing the changes will produce, and the batches that were
+    /// valid. Invalid batches are marked as such in the batch history and their changes are not
+    /// included, so they do not prevent the other batches from being committed.
+    pub fn prepare_change(
+        &mut self,
+        batches: Vec<BatchPair>,
+    ) -> Result<(String, Vec<BatchPair>), ScabbardStateError> {
         // Setup the transact scheduler
         let (result_tx, result_rx) = std::sync::mpsc::channel();
         let mut scheduler = SerialScheduler::new(
@@ -234,54 +438,78 @@ impl ScabbardState {
             }
         }))?;
 
-        // Add the batch to, finalize, and execute the scheduler
-        scheduler.add_batch(batch.clone())?;
+        // Add the batches to, finalize, and execute the scheduler
+        for batch in &batches {
+            scheduler.add_batch(batch.clone())?;
+        }
         scheduler.finalize()?;
         self.executor
             .execute(scheduler.take_task_iterator()?, scheduler.new_notifier()?)?;
 
-        // Get the results and shutdown the scheduler
-        let recv_result = result_rx.recv_timeout(Duration::from_secs(EXECUTION_TIMEOUT));
+        // Get the result of each batch, in order, and shutdown the scheduler
+        let deadline = Instant::now() + Duration::from_secs(EXECUTION_TIMEOUT);
+        let mut batch_results = Vec::with_capacity(batches.len());
+        let recv_result = loop {
+            if batch_results.len() == batches.len() {
+                break Ok(());
+            }
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            match result_rx.recv_timeout(timeout) {
+                Ok(Some(batch_result)) => batch_results.push(batch_result),
+                Ok(None) => {
+                    break Err(ScabbardStateError(
+                        "no result returned from executor".into(),
+                    ))
+                }
+                Err(_) => {
+                    break Err(ScabbardStateError(
+                        "failed to receive result in reasonable time".into(),
+                    ))
+                }
+            }
+        };
 
         scheduler.shutdown();
 
-        let batch_result = recv_result
-            .map_err(|_| ScabbardStateError("failed to receive result in reasonable time".into()))?
-            .ok_or_else(|| ScabbardStateError("no result returned from executor".into()))?;
-
-        let batch_status = batch_result.clone().into();
-        let signature = batch.batch().header_signature();
-        self.batch_history
-            .update_batch_status(&signature, batch_status);
-
-        let txn_receipts = batch_result
-            .receipts
-            .into_iter()
-            .map(|receipt| match receipt.transaction_result {
-                TransactionResult::Valid { .. } => Ok(receipt),
-                TransactionResult::Invalid { error_message, .. } => Err(ScabbardStateError(
-                    format!("transaction failed: {:?}", error_message),
-                )),
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+        recv_result?;
+
+        let mut valid_batches = vec![];
+        let mut signatures = vec![];
+        let mut txn_receipts = vec![];
+        for (batch, batch_result) in batches.into_iter().zip(batch_results) {
+            let batch_status = BatchStatus::from(batch_result.clone());
+            let signature = batch.batch().header_signature().to_string();
+            self.batch_history
+                .update_batch_status(&signature, batch_status.clone())?;
+
+            match batch_status {
+                BatchStatus::Valid(_) => {
+                    txn_receipts.extend(batch_result.receipts);
+                    signatures.push(signature);
+                    valid_batches.push(batch);
+                }
+                _ => warn!("Batch {} is invalid and will not be committed", signature),
+            }
+        }
 
         // Save the results and compute the resulting state root
         let state_root = MerkleState::new(self.db.clone()).compute_state_id(
             &self.current_state_root,
             &receipts_into_transact_state_changes(&txn_receipts)?,
         )?;
-        self.pending_changes = Some((signature.to_string(), txn_receipts));
-        Ok(state_root)
+        self.pending_changes = Some((signatures, txn_receipts));
+        Ok((state_root, valid_batches))
     }
 
     pub fn commit(&mut self) -> Result<(), ScabbardStateError> {
         match self.pending_changes.take() {
-            Some((signature, txn_receipts)) => {
+            Some((signatures, txn_receipts)) => {
                 let state_changes = receipts_into_transact_state_changes(&txn_receipts)?;
                 self.current_state_root = MerkleState::new(self.db.clone())
                     .commit(&self.current_state_root, &state_changes)?;
+                self.height += 1;
 
-                self.write_current_state_root()?;
+                self.write_current_state_root(&signatures)?;
 
                 info!(
                     "committed {} change(s) for new state root {}",
@@ -324,7 +552,9 @@ impl ScabbardState {
                     });
                 }
 
-                self.batch_history.commit(&signature);
+                for signature in signatures {
+                    self.batch_history.commit(&signature)?;
+                }
 
                 Ok(())
             }
@@ -352,6 +582,67 @@ impl ScabbardState {
         Events::new(self.transaction_receipt_store.clone(), event_id)
     }
 
+    /// Get the transaction receipts with the given transaction IDs, in the order of the given
+    /// IDs. IDs that have no receipt are skipped.
+    pub fn get_receipts(
+        &self,
+        ids: &[String],
+    ) -> Result<Vec<TransactionReceipt>, ScabbardStateError> {
+        let transaction_receipt_store = self.transaction_receipt_store.read().map_err(|err| {
+            ScabbardStateError(format!("transaction receipt store lock poisoned: {}", err))
+        })?;
+
+        ids.iter()
+            .filter_map(|id| {
+                transaction_receipt_store
+                    .get_by_id(id.clone())
+                    .map_err(|err| {
+                        ScabbardStateError(format!(
+                            "failed to get transaction receipt from store: {}",
+                            err
+                        ))
+                    })
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// Get up to `limit` transaction receipts in the order they were committed, starting with the
+    /// receipt at `offset`. The total number of receipts is returned with them.
+    ///
+    /// The receipts are read by their index in the store, so only the requested receipts are
+    /// read.
+    pub fn list_receipts(
+        &self,
+        offset: usize,
+        limit: usize,
+    ) -> Result<(Vec<TransactionReceipt>, usize), ScabbardStateError> {
+        let transaction_receipt_store = self.transaction_receipt_store.read().map_err(|err| {
+            ScabbardStateError(format!("transaction receipt store lock poisoned: {}", err))
+        })?;
+
+        let total = transaction_receipt_store.count().map_err(|err| {
+            ScabbardStateError(format!("failed to count transaction receipts: {}", err))
+        })?;
+        let start = (offset as u64).min(total);
+        let end = start.saturating_add(limit as u64).min(total);
+        let receipts = (start..end)
+            .filter_map(|index| {
+                transaction_receipt_store
+                    .get_by_index(index)
+                    .map_err(|err| {
+                        ScabbardStateError(format!(
+                            "failed to get transaction receipt from store: {}",
+                            err
+                        ))
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((receipts, total as usize))
+    }
+
     pub fn add_subscriber(&mut self, subscriber: Box<dyn StateSubscriber>) {
         self.event_subscribers.push(subscriber);
     }
@@ -600,6 +891,15 @@ impl InvalidTransaction {
     }
 }
 
+/// A state root that was committed, with the IDs of the batches whose changes produced it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommittedRoot {
+    /// The height at which the root was committed
+    pub height: u64,
+    pub state_root: String,
+    pub batch_ids: Vec<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BatchInfo {
     pub id: String,
@@ -614,24 +914,58 @@ impl BatchInfo {
     }
 }
 
-/// BatchHistory keeps track of batches submitted to scabbard
+/// The stored form of a `BatchInfo`; the batch ID is the key it is stored under
+#[derive(Serialize, Deserialize)]
+struct BatchHistoryEntry {
+    status: BatchStatus,
+    timestamp: SystemTime,
+}
+
+/// BatchHistory keeps track of batches submitted to scabbard.
+///
+/// The history is stored in scabbard's state database, so batch statuses are kept across restarts.
+/// Each batch is indexed both by its ID and by the time it was added, which is used to evict the
+/// oldest batches once the history exceeds its limit or they are older than its maximum age.
 pub struct BatchHistory {
-    history: HashMap<String, BatchInfo>,
+    db: Box<dyn Database>,
     limit: usize,
+    max_age: Option<Duration>,
     batch_subscribers: Vec<(HashSet<String>, Sender<BatchInfo>)>,
 }
 
 impl BatchHistory {
-    pub fn new() -> Self {
-        Self::default()
+    /// Opens the batch history stored in the given database. Batches that were still pending when
+    /// the history was last used are removed, because the queued and proposed batches they
+    /// belonged to were not persisted.
+    fn new(db: Box<dyn Database>) -> Result<Self, ScabbardStateError> {
+        let history = Self {
+            db,
+            limit: DEFAULT_BATCH_HISTORY_SIZE,
+            max_age: None,
+            batch_subscribers: vec![],
+        };
+        history.remove_incomplete_batches()?;
+        Ok(history)
     }
 
-    pub fn add_batch(&mut self, signature: &str) {
-        self.upsert_batch(signature.into(), BatchStatus::Pending);
+    /// Sets the maximum number of batches to keep in the history and, optionally, the maximum
+    /// time to keep a batch in the history after it was added.
+    pub fn set_retention(&mut self, limit: usize, max_age: Option<Duration>) {
+        self.limit = limit;
+        self.max_age = max_age;
+    }
+
+    pub fn add_batch(&mut self, signature: &str) -> Result<(), ScabbardStateError> {
+        self.upsert_batch(signature.into(), BatchStatus::Pending)?;
+        Ok(())
     }
 
-    fn update_batch_status(&mut self, signature: &str, status: BatchStatus) {
-        let batch_info = self.upsert_batch(signature.into(), status);
+    fn update_batch_status(
+        &mut self,
+        signature: &str,
+        status: BatchStatus,
+    ) -> Result<(), ScabbardStateError> {
+        let batch_info = self.upsert_batch(signature.into(), status)?;
 
         match batch_info.status {
             BatchStatus::Invalid(_) | BatchStatus::Valid(_) => {
@@ -639,13 +973,16 @@ impl BatchHistory {
             }
             _ => {}
         }
+
+        Ok(())
     }
 
-    fn commit(&mut self, signature: &str) {
-        match self.history.get_mut(signature) {
-            Some(info) => match info.status.clone() {
+    fn commit(&mut self, signature: &str) -> Result<(), ScabbardStateError> {
+        match self.get(signature)? {
+            Some(mut info) => match info.status.clone() {
                 BatchStatus::Valid(txns) => {
                     info.set_status(BatchStatus::Committed(txns));
+                    self.put(&info, false)?;
                 }
                 _ => {
                     error!(
@@ -661,34 +998,162 @@ impl BatchHistory {
                 );
             }
         }
+
+        Ok(())
     }
 
-    fn upsert_batch(&mut self, signature: String, status: BatchStatus) -> BatchInfo {
-        match self.history.get_mut(&signature) {
-            Some(info) => {
+    fn upsert_batch(
+        &mut self,
+        signature: String,
+        status: BatchStatus,
+    ) -> Result<BatchInfo, ScabbardStateError> {
+        match self.get(&signature)? {
+            Some(mut info) => {
                 info.set_status(status);
-                info.clone()
+                self.put(&info, false)?;
+                Ok(info)
             }
             None => {
                 let batch_info = BatchInfo {
-                    id: signature.clone(),
+                    id: signature,
                     status,
                     timestamp: SystemTime::now(),
                 };
 
-                self.history.insert(signature, batch_info.clone());
+                self.put(&batch_info, true)?;
+                self.evict_expired_batches()?;
 
-                if self.history.len() > self.limit {
-                    self.history
-                        .clone()
-                        .into_iter()
-                        .min_by_key(|(_, v)| v.timestamp)
-                        .and_then(|(k, _)| self.history.remove(&k));
-                }
+                Ok(batch_info)
+            }
+        }
+    }
+
+    fn get(&self, signature: &str) -> Result<Option<BatchInfo>, ScabbardStateError> {
+        let entry = self
+            .db
+            .get_reader()
+            .and_then(|reader| reader.index_get(BATCH_HISTORY_INDEX, signature.as_bytes()))?;
 
-                batch_info
+        entry
+            .map(|bytes| {
+                let entry: BatchHistoryEntry = serde_json::from_slice(&bytes).map_err(|err| {
+                    ScabbardStateError(format!(
+                        "failed to deserialize batch history entry for {}: {}",
+                        signature, err
+                    ))
+                })?;
+                Ok(BatchInfo {
+                    id: signature.into(),
+                    status: entry.status,
+                    timestamp: entry.timestamp,
+                })
+            })
+            .transpose()
+    }
+
+    /// Writes the given batch info; if the batch is new, it is also added to the time index.
+    fn put(&self, info: &BatchInfo, new: bool) -> Result<(), ScabbardStateError> {
+        let entry = serde_json::to_vec(&BatchHistoryEntry {
+            status: info.status.clone(),
+            timestamp: info.timestamp,
+        })
+        .map_err(|err| {
+            ScabbardStateError(format!(
+                "failed to serialize batch history entry for {}: {}",
+                info.id, err
+            ))
+        })?;
+
+        let mut writer = self.db.get_writer()?;
+        writer.index_put(BATCH_HISTORY_INDEX, info.id.as_bytes(), &entry)?;
+        if new {
+            writer.index_put(
+                BATCH_HISTORY_TIME_INDEX,
+                &time_index_key(info.timestamp, &info.id),
+                info.id.as_bytes(),
+            )?;
+        }
+        writer.commit()?;
+
+        Ok(())
+    }
+
+    /// Removes the given batches, which are identified by their time index keys and IDs.
+    fn remove(&self, batches: Vec<(Vec<u8>, Vec<u8>)>) -> Result<(), ScabbardStateError> {
+        if batches.is_empty() {
+            return Ok(());
+        }
+
+        let mut writer = self.db.get_writer()?;
+        for (time_key, signature) in batches {
+            writer.index_delete(BATCH_HISTORY_TIME_INDEX, &time_key)?;
+            writer.index_delete(BATCH_HISTORY_INDEX, &signature)?;
+        }
+        writer.commit()?;
+
+        Ok(())
+    }
+
+    /// Removes the oldest batches while the history exceeds its limit or they are older than its
+    /// maximum age.
+    fn evict_expired_batches(&self) -> Result<(), ScabbardStateError> {
+        let cutoff = self
+            .max_age
+            .and_then(|max_age| SystemTime::now().checked_sub(max_age))
+            .map(millis_since_epoch);
+
+        let expired = {
+            let reader = self.db.get_reader()?;
+            let mut remaining = reader.index_count(BATCH_HISTORY_TIME_INDEX)?;
+            reader
+                .index_cursor(BATCH_HISTORY_TIME_INDEX)?
+                .take_while(|(time_key, _)| {
+                    let too_many = remaining > self.limit;
+                    let too_old = cutoff
+                        .map(|cutoff| time_index_key_millis(time_key) < cutoff)
+                        .unwrap_or(false);
+                    if too_many || too_old {
+                        remaining -= 1;
+                        true
+                    } else {
+                        false
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+
+        self.remove(expired)
+    }
+
+    fn remove_incomplete_batches(&self) -> Result<(), ScabbardStateError> {
+        let incomplete = {
+            let reader = self.db.get_reader()?;
+            let batches = reader
+                .index_cursor(BATCH_HISTORY_TIME_INDEX)?
+                .collect::<Vec<_>>();
+            drop(reader);
+
+            let mut incomplete = vec![];
+            for (time_key, signature) in batches {
+                let id = String::from_utf8(signature.clone()).map_err(|err| {
+                    ScabbardStateError(format!("invalid batch ID in batch history: {}", err))
+                })?;
+                match self.get(&id)?.map(|info| info.status) {
+                    Some(BatchStatus::Invalid(_)) | Some(BatchStatus::Committed(_)) => {}
+                    _ => incomplete.push((time_key, signature)),
+                }
             }
+            incomplete
+        };
+
+        if !incomplete.is_empty() {
+            debug!(
+                "Removing {} incomplete batch(es) from the batch history",
+                incomplete.len()
+            );
         }
+
+        self.remove(incomplete)
     }
 
     pub fn get_batch_info(
@@ -696,29 +1161,75 @@ impl BatchHistory {
         ids: HashSet<String>,
         wait: Option<Duration>,
     ) -> Result<BatchInfoIter, ScabbardStateError> {
+        // Batches are only evicted when others are added, so evict any that have expired since
+        self.evict_expired_batches()?;
+
         match wait {
             Some(timeout) => self.completed_batch_info_iter(ids, timeout),
-            None => Ok(self.no_wait_batch_info_iter(&ids)),
+            None => self.no_wait_batch_info_iter(&ids),
         }
     }
 
-    fn no_wait_batch_info_iter(&self, ids: &HashSet<String>) -> BatchInfoIter {
-        Box::new(
-            ids.iter()
-                .map(|id| {
-                    Ok(if let Some(info) = self.history.get(id) {
-                        info.clone()
-                    } else {
-                        BatchInfo {
-                            id: id.to_string(),
-                            status: BatchStatus::Unknown,
-                            timestamp: SystemTime::now(),
-                        }
-                    })
+    /// Get the `BatchInfo` of up to `limit` batches that were added to the history at or after
+    /// `since` and, if given, before `until`, in the order they were added, skipping the first
+    /// `offset` of them.
+    pub fn get_batch_info_in_range(
+        &self,
+        since: SystemTime,
+        until: Option<SystemTime>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<BatchInfoIter, ScabbardStateError> {
+        self.evict_expired_batches()?;
+
+        let since = millis_since_epoch(since);
+        let until = until.map(millis_since_epoch);
+
+        let signatures = {
+            let reader = self.db.get_reader()?;
+            reader
+                .index_cursor(BATCH_HISTORY_TIME_INDEX)?
+                .filter(|(time_key, _)| time_index_key_millis(time_key) >= since)
+                .take_while(|(time_key, _)| {
+                    until
+                        .map(|until| time_index_key_millis(time_key) < until)
+                        .unwrap_or(true)
                 })
+                .skip(offset)
+                .take(limit)
+                .map(|(_, signature)| signature)
                 .collect::<Vec<_>>()
-                .into_iter(),
-        )
+        };
+
+        let mut infos = vec![];
+        for signature in signatures {
+            let id = String::from_utf8(signature).map_err(|err| {
+                ScabbardStateError(format!("invalid batch ID in batch history: {}", err))
+            })?;
+            if let Some(info) = self.get(&id)? {
+                infos.push(Ok(info));
+            }
+        }
+
+        Ok(Box::new(infos.into_iter()))
+    }
+
+    fn no_wait_batch_info_iter(
+        &self,
+        ids: &HashSet<String>,
+    ) -> Result<BatchInfoIter, ScabbardStateError> {
+        let infos = ids
+            .iter()
+            .map(|id| {
+                Ok(Ok(self.get(id)?.unwrap_or_else(|| BatchInfo {
+                    id: id.to_string(),
+                    status: BatchStatus::Unknown,
+                    timestamp: SystemTime::now(),
+                })))
+            })
+            .collect::<Result<Vec<_>, ScabbardStateError>>()?;
+
+        Ok(Box::new(infos.into_iter()))
     }
 
     fn completed_batch_info_iter(
@@ -728,7 +1239,7 @@ impl BatchHistory {
     ) -> Result<BatchInfoIter, ScabbardStateError> {
         // Get batches that are already completed
         let iter = self
-            .no_wait_batch_info_iter(&ids)
+            .no_wait_batch_info_iter(&ids)?
             .filter_map(|res| {
                 let info = res.ok()?;
                 match info.status {
@@ -771,14 +1282,26 @@ impl BatchHistory {
     }
 }
 
-impl Default for BatchHistory {
-    fn default() -> Self {
-        Self {
-            history: HashMap::new(),
-            limit: DEFAULT_BATCH_HISTORY_SIZE,
-            batch_subscribers: vec![],
-        }
+fn millis_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// The key of a batch in the time index: the time it was added, in milliseconds since the epoch
+/// (big-endian, so keys are ordered by time), followed by the batch ID
+fn time_index_key(timestamp: SystemTime, signature: &str) -> Vec<u8> {
+    let mut key = millis_since_epoch(timestamp).to_be_bytes().to_vec();
+    key.extend_from_slice(signature.as_bytes());
+    key
+}
+
+fn time_index_key_millis(key: &[u8]) -> u64 {
+    let mut millis = [0; 8];
+    if key.len() >= millis.len() {
+        millis.copy_from_slice(&key[..8]);
     }
+    u64::from_be_bytes(millis)
 }
 
 pub type BatchInfoIter = Box<dyn Iterator<Item = Result<BatchInfo, String>>>;
@@ -842,6 +1365,7 @@ impl Iterator for ChannelBatchInfoIter {
 mod tests {
     use super::*;
 
+    use std::collections::HashMap;
     use std::path::PathBuf;
 
     use tempdir::TempDir;
@@ -849,7 +1373,7 @@ mod tests {
         families::command::make_command_transaction,
         protocol::{
             batch::BatchBuilder,
-            command::{BytesEntry, Command, SetState},
+            command::{BytesEntry, Command, ReturnInvalid, SetState},
         },
         signing::hash::HashSigner,
     };
@@ -958,14 +1482,14 @@ mod tests {
             .build_pair(&signer)
             .expect("Failed to build batch");
         state
-            .prepare_change(batch)
+            .prepare_change(vec![batch])
             .expect("Failed to prepare change");
         state.commit().expect("Failed to commit change");
 
         // Get the value and verify it
         assert_eq!(
             state
-                .get_state_at_address(&address)
+                .get_state_at_address(&address, None)
                 .expect("Failed to get state for set address"),
             Some(value),
         );
@@ -973,7 +1497,7 @@ mod tests {
         // Get state at an unset address and verify it
         assert_eq!(
             state
-                .get_state_at_address("0123456789")
+                .get_state_at_address("0123456789", None)
                 .expect("Failed to get state for unset address"),
             None,
         );
@@ -1027,13 +1551,13 @@ mod tests {
             .build_pair(&signer)
             .expect("Failed to build batch");
         state
-            .prepare_change(batch)
+            .prepare_change(vec![batch])
             .expect("Failed to prepare change");
         state.commit().expect("Failed to commit change");
 
         // Get all state entries and verify that they're correctly returned
         let all_entries = state
-            .get_state_with_prefix(None)
+            .get_state_with_prefix(None, None)
             .expect("Failed to get all entries")
             .collect::<Result<Vec<_>, _>>()
             .expect("Failed to collect all entries");
@@ -1043,7 +1567,7 @@ mod tests {
 
         // Get state entries under the shared prefix and verify the correct entries are returned
         let prefix_entries = state
-            .get_state_with_prefix(Some(&prefix))
+            .get_state_with_prefix(Some(&prefix), None)
             .expect("Failed to get entries under prefix")
             .collect::<Result<Vec<_>, _>>()
             .expect("Failed to collect entries under prefix");
@@ -1054,13 +1578,457 @@ mod tests {
         // Get state entries under a prefix with no set addresses and verify that no entries are
         // returned
         let no_entries = state
-            .get_state_with_prefix(Some("abcdef0123456789"))
+            .get_state_with_prefix(Some("abcdef0123456789"), None)
             .expect("Failed to get entries under unset prefix")
             .collect::<Result<Vec<_>, _>>()
             .expect("Failed to collect entries under unset prefix");
         assert!(no_entries.is_empty());
     }
 
+    /// Verify that the batches of a multi-batch change are executed in order and that an invalid
+    /// batch does not prevent the other batches from being committed.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState`.
+    /// 2. Prepare a change with three batches, where the second batch is invalid.
+    /// 3. Verify that only the first and third batches are returned as valid.
+    /// 4. Commit the change and verify that the values set by the valid batches are in state.
+    /// 5. Verify that the batch history has the valid batches as committed and the invalid batch
+    ///    as invalid.
+    #[test]
+    fn prepare_multi_batch_change() {
+        let paths = StatePaths::new("prepare_multi_batch_change");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let signer = HashSigner::default();
+        let make_batch = |command: Command| {
+            BatchBuilder::new()
+                .with_transactions(vec![make_command_transaction(&[command]).take().0])
+                .build_pair(&signer)
+                .expect("Failed to build batch")
+        };
+        let batches = vec![
+            make_batch(Command::SetState(SetState::new(vec![BytesEntry::new(
+                "abcdef01".into(),
+                b"value1".to_vec(),
+            )]))),
+            make_batch(Command::ReturnInvalid(ReturnInvalid::new(
+                "invalid batch".into(),
+            ))),
+            make_batch(Command::SetState(SetState::new(vec![BytesEntry::new(
+                "abcdef02".into(),
+                b"value2".to_vec(),
+            )]))),
+        ];
+        let ids = batches
+            .iter()
+            .map(|batch| batch.batch().header_signature().to_string())
+            .collect::<Vec<_>>();
+        for id in &ids {
+            state
+                .batch_history()
+                .add_batch(id)
+                .expect("Failed to add batch");
+        }
+
+        let (_, valid_batches) = state
+            .prepare_change(batches)
+            .expect("Failed to prepare change");
+        assert_eq!(
+            valid_batches
+                .iter()
+                .map(|batch| batch.batch().header_signature())
+                .collect::<Vec<_>>(),
+            vec![ids[0].as_str(), ids[2].as_str()],
+        );
+        state.commit().expect("Failed to commit change");
+
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef01", None)
+                .expect("Failed to get state"),
+            Some(b"value1".to_vec()),
+        );
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef02", None)
+                .expect("Failed to get state"),
+            Some(b"value2".to_vec()),
+        );
+
+        let statuses = state
+            .batch_history()
+            .get_batch_info(ids.iter().cloned().collect(), None)
+            .expect("Failed to get batch info")
+            .map(|info| {
+                let info = info.expect("Failed to get batch info");
+                (info.id, info.status)
+            })
+            .collect::<HashMap<_, _>>();
+        assert!(matches!(statuses[&ids[0]], BatchStatus::Committed(_)));
+        assert!(matches!(statuses[&ids[1]], BatchStatus::Invalid(_)));
+        assert!(matches!(statuses[&ids[2]], BatchStatus::Committed(_)));
+    }
+
+    /// Verify that state can be read at previously committed state roots, and that committed roots
+    /// are listed with the IDs of the batches that produced them.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState`.
+    /// 2. Commit two batches, one at a time, that set the same address to different values.
+    /// 3. List the committed roots and verify that both are listed, newest first, with their
+    ///    batch IDs, and that the offset and limit are applied.
+    /// 4. Verify that reading state at the first root returns the first value, while reading at
+    ///    the current root returns the second value.
+    /// 5. Verify that an unknown state root is reported as not found.
+    #[test]
+    fn historical_state_queries() {
+        let paths = StatePaths::new("historical_state_queries");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let signer = HashSigner::default();
+        let mut batch_ids = vec![];
+        for value in &[b"value1", b"value2"] {
+            let batch = BatchBuilder::new()
+                .with_transactions(vec![
+                    make_command_transaction(&[Command::SetState(SetState::new(vec![
+                        BytesEntry::new("abcdef01".into(), value.to_vec()),
+                    ]))])
+                    .take()
+                    .0,
+                ])
+                .build_pair(&signer)
+                .expect("Failed to build batch");
+            batch_ids.push(batch.batch().header_signature().to_string());
+            state
+                .prepare_change(vec![batch])
+                .expect("Failed to prepare change");
+            state.commit().expect("Failed to commit change");
+        }
+
+        let (roots, total) = state
+            .list_committed_roots(0, 10)
+            .expect("Failed to list committed roots");
+        assert_eq!(total, 2);
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].height, 2);
+        assert_eq!(roots[0].state_root, state.current_state_root());
+        assert_eq!(roots[0].batch_ids, vec![batch_ids[1].clone()]);
+        assert_eq!(roots[1].height, 1);
+        assert_eq!(roots[1].batch_ids, vec![batch_ids[0].clone()]);
+
+        let (paged_roots, _) = state
+            .list_committed_roots(1, 1)
+            .expect("Failed to list committed roots");
+        assert_eq!(paged_roots, vec![roots[1].clone()]);
+
+        let first_root = roots[1].state_root.as_str();
+        assert!(state
+            .contains_state_root(first_root)
+            .expect("Failed to check state root"));
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef01", Some(first_root))
+                .expect("Failed to get state"),
+            Some(b"value1".to_vec()),
+        );
+        assert_eq!(
+            state
+                .get_state_with_prefix(Some("abcdef"), Some(first_root))
+                .expect("Failed to get state iterator")
+                .collect::<Result<Vec<_>, _>>()
+                .expect("Failed to get state entries"),
+            vec![("abcdef01".to_string(), b"value1".to_vec())],
+        );
+        assert_eq!(
+            state
+                .get_state_at_address("abcdef01", None)
+                .expect("Failed to get state"),
+            Some(b"value2".to_vec()),
+        );
+
+        assert!(!state
+            .contains_state_root(&"0".repeat(64))
+            .expect("Failed to check state root"));
+    }
+
+    /// Verify that the batch history is persisted, that incomplete batches are removed when it is
+    /// reopened, and that it can be queried by time range and limited in size.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState`, add a valid batch, an invalid batch, and a
+    ///    batch that is never executed to its batch history, and execute and commit the first two.
+    /// 2. Open the batch history again, as is done on restart, and verify that the committed and
+    ///    invalid batches are still in the history, while the pending batch was removed.
+    /// 3. Verify that a range query from the Unix epoch returns the remaining batches in the order
+    ///    they were added, that its offset and limit select a part of them, and that a range query
+    ///    in the future returns nothing.
+    /// 4. Limit the history to a single batch, add another batch, and verify that the older
+    ///    batches were evicted.
+    /// 5. Limit the age of the batches in the history, wait until the remaining batch is older
+    ///    than that, and verify that it is no longer returned, although no batch was added.
+    #[test]
+    fn persistent_batch_history() {
+        let paths = StatePaths::new("persistent_batch_history");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+        let get_statuses = |history: &mut BatchHistory, ids: &[String]| {
+            history
+                .get_batch_info(ids.iter().cloned().collect(), None)
+                .expect("Failed to get batch info")
+                .map(|info| {
+                    let info = info.expect("Failed to get batch info");
+                    (info.id, info.status)
+                })
+                .collect::<HashMap<_, _>>()
+        };
+
+        let signer = HashSigner::default();
+        let make_batch = |command: Command| {
+            BatchBuilder::new()
+                .with_transactions(vec![make_command_transaction(&[command]).take().0])
+                .build_pair(&signer)
+                .expect("Failed to build batch")
+        };
+        let batches = vec![
+            make_batch(Command::SetState(SetState::new(vec![BytesEntry::new(
+                "abcdef01".into(),
+                b"value1".to_vec(),
+            )]))),
+            make_batch(Command::ReturnInvalid(ReturnInvalid::new(
+                "invalid batch".into(),
+            ))),
+        ];
+        let mut ids = batches
+            .iter()
+            .map(|batch| batch.batch().header_signature().to_string())
+            .collect::<Vec<_>>();
+        ids.push("pending_batch".into());
+
+        for id in &ids {
+            state
+                .batch_history()
+                .add_batch(id)
+                .expect("Failed to add batch");
+            // Make sure each batch is added at a different time
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        state
+            .prepare_change(batches)
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+
+        let mut history =
+            BatchHistory::new(state.db.clone_box()).expect("Failed to open batch history");
+        let statuses = get_statuses(&mut history, &ids);
+        assert!(matches!(statuses[&ids[0]], BatchStatus::Committed(_)));
+        assert!(matches!(statuses[&ids[1]], BatchStatus::Invalid(_)));
+        assert!(matches!(statuses[&ids[2]], BatchStatus::Unknown));
+
+        let get_range_ids = |history: &BatchHistory, since, offset, limit| {
+            history
+                .get_batch_info_in_range(since, None, offset, limit)
+                .expect("Failed to get batch info in range")
+                .map(|info| info.expect("Failed to get batch info").id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            get_range_ids(&history, UNIX_EPOCH, 0, 10),
+            ids[..2].to_vec()
+        );
+        assert_eq!(
+            get_range_ids(&history, UNIX_EPOCH, 1, 1),
+            ids[1..2].to_vec()
+        );
+        assert!(get_range_ids(
+            &history,
+            SystemTime::now() + Duration::from_secs(3600),
+            0,
+            10
+        )
+        .is_empty());
+
+        history.set_retention(1, None);
+        history.add_batch("new_batch").expect("Failed to add batch");
+        let statuses = get_statuses(&mut history, &ids);
+        assert!(matches!(statuses[&ids[0]], BatchStatus::Unknown));
+        assert!(matches!(statuses[&ids[1]], BatchStatus::Unknown));
+        let statuses = get_statuses(&mut history, &["new_batch".to_string()]);
+        assert!(matches!(statuses["new_batch"], BatchStatus::Pending));
+
+        history.set_retention(1, Some(Duration::from_millis(1)));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(get_range_ids(&history, UNIX_EPOCH, 0, 10).is_empty());
+        let statuses = get_statuses(&mut history, &["new_batch".to_string()]);
+        assert!(matches!(statuses["new_batch"], BatchStatus::Unknown));
+    }
+
+    /// Verify that a snapshot of one state can be applied to another state, and that a snapshot
+    /// that does not produce the expected state root is rejected.
+    ///
+    /// 1. Initialize two new, empty `ScabbardState`s.
+    /// 2. Commit a change to the first state and take a snapshot of it.
+    /// 3. Apply the snapshot to the second state with the wrong state root and verify that it is
+    ///    rejected and the second state is unchanged.
+    /// 4. Apply the snapshot with the first state's root and verify that the second state now has
+    ///    the same state root, height, and values as the first.
+    #[test]
+    fn apply_snapshot() {
+        let source_paths = StatePaths::new("apply_snapshot_source");
+        let mut source_state = ScabbardState::new(
+            &source_paths.state_db_path,
+            TEMP_DB_SIZE,
+            &source_paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize source state");
+        let target_paths = StatePaths::new("apply_snapshot_target");
+        let mut target_state = ScabbardState::new(
+            &target_paths.state_db_path,
+            TEMP_DB_SIZE,
+            &target_paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize target state");
+
+        let signer = HashSigner::default();
+        let batch = BatchBuilder::new()
+            .with_transactions(vec![
+                make_command_transaction(&[Command::SetState(SetState::new(vec![
+                    BytesEntry::new("abcdef01".into(), b"value1".to_vec()),
+                ]))])
+                .take()
+                .0,
+            ])
+            .build_pair(&signer)
+            .expect("Failed to build batch");
+        source_state
+            .prepare_change(vec![batch])
+            .expect("Failed to prepare change");
+        source_state.commit().expect("Failed to commit change");
+        assert_eq!(source_state.current_height(), 1);
+
+        let snapshot = source_state
+            .get_state_with_prefix(None, None)
+            .expect("Failed to read state")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("Failed to take snapshot");
+
+        let original_root = target_state.current_state_root().to_string();
+        assert!(target_state
+            .apply_snapshot(1, &original_root, snapshot.clone())
+            .is_err());
+        assert_eq!(target_state.current_state_root(), original_root);
+        assert_eq!(target_state.current_height(), 0);
+
+        target_state
+            .apply_snapshot(1, source_state.current_state_root(), snapshot)
+            .expect("Failed to apply snapshot");
+        assert_eq!(
+            target_state.current_state_root(),
+            source_state.current_state_root()
+        );
+        assert_eq!(target_state.current_height(), 1);
+        assert_eq!(
+            target_state
+                .get_state_at_address("abcdef01", None)
+                .expect("Failed to get state"),
+            Some(b"value1".to_vec()),
+        );
+    }
+
+    /// Verify that the receipts of committed transactions can be fetched by ID and listed in
+    /// commit order.
+    ///
+    /// 1. Initialize a new, empty `ScabbardState`.
+    /// 2. Commit two changes, each with a single transaction.
+    /// 3. Get the receipts for both transaction IDs and an unknown ID, and verify that only the
+    ///    two receipts are returned, in the requested order.
+    /// 4. List the receipts with different offsets and limits and verify the results.
+    #[test]
+    fn get_and_list_receipts() {
+        let paths = StatePaths::new("get_and_list_receipts");
+        let mut state = ScabbardState::new(
+            &paths.state_db_path,
+            TEMP_DB_SIZE,
+            &paths.receipt_db_path,
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        let signer = HashSigner::default();
+        let mut txn_ids = vec![];
+        for (address, value) in &[("abcdef01", b"value1"), ("abcdef02", b"value2")] {
+            let txn = make_command_transaction(&[Command::SetState(SetState::new(vec![
+                BytesEntry::new(address.to_string(), value.to_vec()),
+            ]))])
+            .take()
+            .0;
+            txn_ids.push(txn.header_signature().to_string());
+            let batch = BatchBuilder::new()
+                .with_transactions(vec![txn])
+                .build_pair(&signer)
+                .expect("Failed to build batch");
+            state
+                .prepare_change(vec![batch])
+                .expect("Failed to prepare change");
+            state.commit().expect("Failed to commit change");
+        }
+
+        let receipts = state
+            .get_receipts(&[txn_ids[1].clone(), "unknown".into(), txn_ids[0].clone()])
+            .expect("Failed to get receipts");
+        assert_eq!(
+            receipts
+                .iter()
+                .map(|receipt| receipt.transaction_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![txn_ids[1].as_str(), txn_ids[0].as_str()],
+        );
+        assert!(matches!(
+            receipts[0].transaction_result,
+            TransactionResult::Valid { .. }
+        ));
+
+        let (receipts, total) = state.list_receipts(0, 10).expect("Failed to list receipts");
+        assert_eq!(total, 2);
+        assert_eq!(
+            receipts
+                .iter()
+                .map(|receipt| receipt.transaction_id.as_str())
+                .collect::<Vec<_>>(),
+            vec![txn_ids[0].as_str(), txn_ids[1].as_str()],
+        );
+
+        let (receipts, total) = state.list_receipts(1, 1).expect("Failed to list receipts");
+        assert_eq!(total, 2);
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].transaction_id, txn_ids[1]);
+
+        let (receipts, _) = state.list_receipts(2, 10).expect("Failed to list receipts");
+        assert!(receipts.is_empty());
+    }
+
     struct StatePaths {
         _temp_dir_handle: TempDir,
         pub state_db_path: PathBuf,
@@ -0,0 +1,669 @@
+// Copyright 2018-2020 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Brings the state of a scabbard service that has fallen behind its peers up to date by
+//! transferring a snapshot of a peer's state.
+//!
+//! A snapshot is only requested for the state a proposal was made on, and only a snapshot that
+//! was requested is accepted: the response must come from the service the request was sent to,
+//! and must be for the requested height and state root.
+//!
+//! Snapshots are limited to `MAX_SNAPSHOT_SIZE` bytes. A service whose peers' state is larger than
+//! that can't be brought up to date this way: the peer refuses to send the snapshot, and the
+//! requester stops waiting for it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use protobuf::Message;
+
+use crate::protos::scabbard::{
+    ScabbardMessage, ScabbardMessage_Type, StateEntry, StateSyncRequest, StateSyncResponse,
+};
+
+use super::error::ScabbardError;
+use super::shared::ScabbardShared;
+use super::state::{ScabbardState, StateIter};
+
+/// The maximum number of state entries sent in a single `StateSyncResponse`
+const SNAPSHOT_CHUNK_SIZE: usize = 1000;
+/// The maximum total size, in bytes of addresses and values, of a snapshot that is sent or
+/// received. Larger snapshots are refused by the sender and discarded by the requester.
+const MAX_SNAPSHOT_SIZE: usize = 256 * 1024 * 1024;
+
+/// A snapshot that has been requested from a peer service
+struct IncomingSnapshot {
+    height: u64,
+    state_root: String,
+    next_chunk_index: u32,
+    size: usize,
+    entries: Vec<(String, Vec<u8>)>,
+}
+
+/// Sends snapshots of this service's state to peers that request them, and applies the snapshots
+/// this service requested when it is behind.
+pub struct StateSynchronizer {
+    shared: Arc<Mutex<ScabbardShared>>,
+    state: Arc<Mutex<ScabbardState>>,
+    /// Snapshots that have been requested, indexed by the ID of the service they were requested
+    /// from
+    incoming: Mutex<HashMap<String, IncomingSnapshot>>,
+    /// Notified whenever a requested snapshot has been applied, refused or discarded
+    snapshot_done: Condvar,
+    max_snapshot_size: usize,
+}
+
+impl StateSynchronizer {
+    pub fn new(shared: Arc<Mutex<ScabbardShared>>, state: Arc<Mutex<ScabbardState>>) -> Self {
+        StateSynchronizer {
+            shared,
+            state,
+            incoming: Mutex::new(HashMap::new()),
+            snapshot_done: Condvar::new(),
+            max_snapshot_size: MAX_SNAPSHOT_SIZE,
+        }
+    }
+
+    /// Requests a snapshot of the given peer service's state at the given height and state root.
+    /// Any snapshot previously requested from the peer is discarded.
+    pub fn request_snapshot(
+        &self,
+        peer_service: &str,
+        height: u64,
+        state_root: &str,
+    ) -> Result<(), ScabbardError> {
+        self.incoming
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .insert(
+                peer_service.to_string(),
+                IncomingSnapshot {
+                    height,
+                    state_root: state_root.to_string(),
+                    next_chunk_index: 0,
+                    size: 0,
+                    entries: vec![],
+                },
+            );
+
+        let mut request = StateSyncRequest::new();
+        request.set_height(height);
+        request.set_state_root(state_root.into());
+
+        let mut msg = ScabbardMessage::new();
+        msg.set_message_type(ScabbardMessage_Type::STATE_SYNC_REQUEST);
+        msg.set_state_sync_request(request);
+
+        self.send(peer_service, msg)
+    }
+
+    /// Stops waiting for the snapshot requested from the given peer service; any part of it that
+    /// is received afterwards is ignored.
+    pub fn cancel_snapshot(&self, peer_service: &str) -> Result<(), ScabbardError> {
+        self.incoming
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .remove(peer_service);
+        Ok(())
+    }
+
+    /// Sends a snapshot of this service's state at the requested state root to the requesting
+    /// service, if it is a peer and the state root is known.
+    pub fn handle_request(
+        &self,
+        requester: &str,
+        request: &StateSyncRequest,
+    ) -> Result<(), ScabbardError> {
+        if !self.is_peer_service(requester)? {
+            warn!(
+                "Ignoring state sync request from unknown service {}",
+                requester
+            );
+            return Ok(());
+        }
+
+        let entries = {
+            let state = self.state.lock().map_err(|_| ScabbardError::LockPoisoned)?;
+            if !state.contains_state_root(request.get_state_root())? {
+                debug!(
+                    "Ignoring state sync request from {}; state root {} is unknown",
+                    requester,
+                    request.get_state_root()
+                );
+                return Ok(());
+            }
+            // The entries are read from the state root as they are sent, without holding the
+            // state lock
+            state.get_state_with_prefix(None, Some(request.get_state_root()))?
+        };
+
+        self.send_snapshot(
+            requester,
+            request.get_height(),
+            request.get_state_root(),
+            entries,
+        )
+    }
+
+    /// Sends the given entries as a snapshot to the given peer service, in chunks of up to
+    /// `SNAPSHOT_CHUNK_SIZE` entries. If the snapshot is larger than the maximum snapshot size,
+    /// the chunk that exceeds it is replaced by a refusal, so that the peer stops waiting.
+    fn send_snapshot(
+        &self,
+        recipient: &str,
+        height: u64,
+        state_root: &str,
+        mut entries: StateIter,
+    ) -> Result<(), ScabbardError> {
+        info!(
+            "Sending snapshot for state root {} at height {} to {}",
+            state_root, height, recipient
+        );
+
+        let mut size = 0;
+        let mut chunk_index = 0;
+        loop {
+            let chunk = entries
+                .by_ref()
+                .take(SNAPSHOT_CHUNK_SIZE)
+                .collect::<Result<Vec<_>, _>>()?;
+            size += chunk
+                .iter()
+                .map(|(address, value)| address.len() + value.len())
+                .sum::<usize>();
+
+            let refused = size > self.max_snapshot_size;
+            // An empty state is still sent as a single, empty chunk
+            let last_chunk = refused || chunk.len() < SNAPSHOT_CHUNK_SIZE;
+
+            let mut response = StateSyncResponse::new();
+            response.set_height(height);
+            response.set_state_root(state_root.into());
+            response.set_chunk_index(chunk_index);
+            if refused {
+                warn!(
+                    "Refusing to send snapshot to {}; it is larger than {} bytes",
+                    recipient, self.max_snapshot_size
+                );
+                response.set_refused(true);
+            } else {
+                response.set_entries(
+                    chunk
+                        .into_iter()
+                        .map(|(address, value)| {
+                            let mut entry = StateEntry::new();
+                            entry.set_address(address);
+                            entry.set_value(value);
+                            entry
+                        })
+                        .collect::<Vec<_>>()
+                        .into(),
+                );
+            }
+            response.set_last_chunk(last_chunk);
+
+            let mut msg = ScabbardMessage::new();
+            msg.set_message_type(ScabbardMessage_Type::STATE_SYNC_RESPONSE);
+            msg.set_state_sync_response(response);
+
+            self.send(recipient, msg)?;
+
+            if last_chunk {
+                return Ok(());
+            }
+            chunk_index += 1;
+        }
+    }
+
+    /// Adds a part of a snapshot that was requested from the sending service. Once the last part
+    /// is received, the snapshot is applied if it is ahead of this service's state. If the sender
+    /// refused to send the snapshot, the request is dropped.
+    pub fn handle_response(
+        &self,
+        sender: &str,
+        mut response: StateSyncResponse,
+    ) -> Result<(), ScabbardError> {
+        let mut incoming = self
+            .incoming
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?;
+
+        let snapshot = match incoming.get_mut(sender) {
+            Some(snapshot)
+                if snapshot.next_chunk_index == response.get_chunk_index()
+                    && snapshot.height == response.get_height()
+                    && snapshot.state_root == response.get_state_root() =>
+            {
+                snapshot
+            }
+            Some(_) => {
+                warn!(
+                    "Discarding snapshot from {}; received unexpected chunk {}",
+                    sender,
+                    response.get_chunk_index()
+                );
+                incoming.remove(sender);
+                self.snapshot_done.notify_all();
+                return Ok(());
+            }
+            None => {
+                warn!(
+                    "Ignoring state sync response from {}; no snapshot was requested from it",
+                    sender
+                );
+                return Ok(());
+            }
+        };
+
+        if response.get_refused() {
+            warn!(
+                "{} refused to send the requested snapshot; it is too large",
+                sender
+            );
+            incoming.remove(sender);
+            self.snapshot_done.notify_all();
+            return Ok(());
+        }
+
+        snapshot.next_chunk_index += 1;
+        for mut entry in response.take_entries().into_iter() {
+            snapshot.size += entry.get_address().len() + entry.get_value().len();
+            snapshot
+                .entries
+                .push((entry.take_address(), entry.take_value()));
+        }
+
+        if snapshot.size > self.max_snapshot_size {
+            warn!(
+                "Discarding snapshot from {}; it is larger than {} bytes",
+                sender, self.max_snapshot_size
+            );
+            incoming.remove(sender);
+            self.snapshot_done.notify_all();
+            return Ok(());
+        }
+
+        if !response.get_last_chunk() {
+            return Ok(());
+        }
+
+        // The snapshot is complete; this was checked to be present above
+        let snapshot = match incoming.remove(sender) {
+            Some(snapshot) => snapshot,
+            None => return Ok(()),
+        };
+
+        // Waiters are woken once the incoming lock is released, after the snapshot is applied
+        self.snapshot_done.notify_all();
+
+        let mut state = self.state.lock().map_err(|_| ScabbardError::LockPoisoned)?;
+        if snapshot.height <= state.current_height() {
+            debug!(
+                "Ignoring snapshot from {}; its height ({}) is not ahead of this service's \
+                 height ({})",
+                sender,
+                snapshot.height,
+                state.current_height()
+            );
+            return Ok(());
+        }
+
+        state.apply_snapshot(snapshot.height, &snapshot.state_root, snapshot.entries)?;
+
+        Ok(())
+    }
+
+    /// Waits until this service's state has reached the given height, until the snapshot
+    /// requested from the given peer service is refused or discarded, or until the timeout
+    /// expires. Returns whether the height was reached.
+    pub fn wait_for_snapshot(
+        &self,
+        peer_service: &str,
+        height: u64,
+        timeout: Duration,
+    ) -> Result<bool, ScabbardError> {
+        let deadline = Instant::now() + timeout;
+        let mut incoming = self
+            .incoming
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?;
+
+        loop {
+            if self
+                .state
+                .lock()
+                .map_err(|_| ScabbardError::LockPoisoned)?
+                .current_height()
+                >= height
+            {
+                return Ok(true);
+            }
+
+            let now = Instant::now();
+            if now >= deadline || !incoming.contains_key(peer_service) {
+                return Ok(false);
+            }
+
+            incoming = self
+                .snapshot_done
+                .wait_timeout(incoming, deadline - now)
+                .map_err(|_| ScabbardError::LockPoisoned)?
+                .0;
+        }
+    }
+
+    fn is_peer_service(&self, service_id: &str) -> Result<bool, ScabbardError> {
+        Ok(self
+            .shared
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .peer_services()
+            .contains(service_id))
+    }
+
+    fn send(&self, recipient: &str, msg: ScabbardMessage) -> Result<(), ScabbardError> {
+        let msg_bytes = msg
+            .write_to_bytes()
+            .map_err(|err| ScabbardError::StateSyncFailed(Box::new(err)))?;
+
+        self.shared
+            .lock()
+            .map_err(|_| ScabbardError::LockPoisoned)?
+            .network_sender()
+            .ok_or(ScabbardError::NotConnected)?
+            .send(recipient, &msg_bytes)
+            .map_err(|err| ScabbardError::StateSyncFailed(Box::new(err)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::{HashSet, VecDeque};
+
+    use splinter::signing::hash::HashVerifier;
+    use tempdir::TempDir;
+    use transact::{
+        families::command::make_command_transaction,
+        protocol::{
+            batch::BatchBuilder,
+            command::{BytesEntry, Command, SetState},
+        },
+        signing::hash::HashSigner,
+    };
+
+    use crate::service::tests::MockServiceNetworkSender;
+
+    const TEMP_DB_SIZE: usize = 1 << 30; // 1024 ** 3
+
+    /// Verify that a snapshot requested by one service's synchronizer brings its state up to date,
+    /// and that a snapshot that was not requested is ignored.
+    ///
+    /// 1. Initialize two services' states and synchronizers.
+    /// 2. Commit a change to the source service's state.
+    /// 3. Handle a state sync request for the source state root from an unknown service and
+    ///    verify that nothing is sent.
+    /// 4. Request a snapshot of the source state from the target synchronizer, handle the request
+    ///    with the source synchronizer and verify that a complete snapshot is sent.
+    /// 5. Pass the snapshot to the target synchronizer and verify that the target state has the
+    ///    source state's root and height.
+    /// 6. Pass the snapshot to the target synchronizer again and verify that it is ignored, since
+    ///    it is no longer requested.
+    #[test]
+    fn sync_from_snapshot() {
+        let source_dir = TempDir::new("sync_from_snapshot_source").expect("Failed to create dir");
+        let source_sender = MockServiceNetworkSender::new();
+        let source = synchronizer(&source_dir, "target", source_sender.clone());
+        let target_dir = TempDir::new("sync_from_snapshot_target").expect("Failed to create dir");
+        let target_sender = MockServiceNetworkSender::new();
+        let target = synchronizer(&target_dir, "source", target_sender.clone());
+
+        let source_root = commit_entry(&source, "abcdef01", b"value1");
+
+        let mut request = StateSyncRequest::new();
+        request.set_height(1);
+        request.set_state_root(source_root.clone());
+        source
+            .handle_request("unknown", &request)
+            .expect("Failed to handle request");
+        assert!(source_sender
+            .sent
+            .lock()
+            .expect("sent lock poisoned")
+            .is_empty());
+
+        target
+            .request_snapshot("source", 1, &source_root)
+            .expect("Failed to request snapshot");
+        let (recipient, bytes) = target_sender
+            .sent
+            .lock()
+            .expect("sent lock poisoned")
+            .pop()
+            .expect("No request was sent");
+        assert_eq!(recipient, "source");
+        let msg: ScabbardMessage =
+            protobuf::parse_from_bytes(&bytes).expect("Failed to parse message");
+        assert_eq!(
+            msg.get_message_type(),
+            ScabbardMessage_Type::STATE_SYNC_REQUEST
+        );
+        source
+            .handle_request("target", msg.get_state_sync_request())
+            .expect("Failed to handle request");
+
+        let sent = source_sender
+            .sent
+            .lock()
+            .expect("sent lock poisoned")
+            .clone();
+        assert!(!sent.is_empty());
+        let mut responses = vec![];
+        for (index, (recipient, bytes)) in sent.into_iter().enumerate() {
+            assert_eq!(recipient, "target");
+            let mut msg: ScabbardMessage =
+                protobuf::parse_from_bytes(&bytes).expect("Failed to parse message");
+            assert_eq!(
+                msg.get_message_type(),
+                ScabbardMessage_Type::STATE_SYNC_RESPONSE
+            );
+            assert_eq!(
+                msg.get_state_sync_response().get_chunk_index(),
+                index as u32
+            );
+            responses.push(msg.take_state_sync_response());
+        }
+        assert!(responses.last().expect("No response").get_last_chunk());
+
+        for response in responses.clone() {
+            target
+                .handle_response("source", response)
+                .expect("Failed to handle response");
+        }
+
+        assert!(target
+            .wait_for_snapshot("source", 1, Duration::from_millis(10))
+            .expect("Failed to wait for snapshot"));
+        let target_state = target.state.lock().expect("state lock poisoned");
+        assert_eq!(target_state.current_state_root(), source_root);
+        assert_eq!(
+            target_state
+                .get_state_at_address("abcdef01", None)
+                .expect("Failed to get state"),
+            Some(b"value1".to_vec()),
+        );
+        drop(target_state);
+
+        for response in responses {
+            target
+                .handle_response("source", response)
+                .expect("Failed to handle response");
+        }
+        assert!(target
+            .incoming
+            .lock()
+            .expect("incoming lock poisoned")
+            .is_empty());
+    }
+
+    /// Verify that a snapshot that was not requested is not applied.
+    ///
+    /// 1. Initialize a service's state and synchronizer.
+    /// 2. Pass it a complete snapshot from a peer service that it did not request, and verify that
+    ///    its state is unchanged.
+    #[test]
+    fn unrequested_snapshot_ignored() {
+        let dir = TempDir::new("unrequested_snapshot_ignored").expect("Failed to create dir");
+        let sync = synchronizer(&dir, "source", MockServiceNetworkSender::new());
+        let original_root = sync
+            .state
+            .lock()
+            .expect("state lock poisoned")
+            .current_state_root()
+            .to_string();
+
+        let mut response = StateSyncResponse::new();
+        response.set_height(1);
+        response.set_state_root(original_root.clone());
+        response.set_last_chunk(true);
+        sync.handle_response("source", response)
+            .expect("Failed to handle response");
+
+        let state = sync.state.lock().expect("state lock poisoned");
+        assert_eq!(state.current_height(), 0);
+        assert_eq!(state.current_state_root(), original_root);
+    }
+
+    /// Verify that a snapshot larger than the maximum snapshot size is refused, and that the
+    /// requester stops waiting for it as soon as the refusal is received.
+    ///
+    /// 1. Initialize two services' states and synchronizers, with a one byte maximum snapshot size
+    ///    on the source.
+    /// 2. Commit a change to the source service's state.
+    /// 3. Request a snapshot of the source state from the target synchronizer, handle the request
+    ///    with the source synchronizer and verify that a single refusal is sent.
+    /// 4. Pass the refusal to the target synchronizer and verify that the snapshot is no longer
+    ///    requested, and that waiting for it returns without reaching the height or waiting for
+    ///    the timeout.
+    #[test]
+    fn over_cap_snapshot_refused() {
+        let source_dir =
+            TempDir::new("over_cap_snapshot_refused_source").expect("Failed to create dir");
+        let source_sender = MockServiceNetworkSender::new();
+        let mut source = synchronizer(&source_dir, "target", source_sender.clone());
+        source.max_snapshot_size = 1;
+        let target_dir =
+            TempDir::new("over_cap_snapshot_refused_target").expect("Failed to create dir");
+        let target = synchronizer(&target_dir, "source", MockServiceNetworkSender::new());
+
+        let source_root = commit_entry(&source, "abcdef01", b"value1");
+
+        target
+            .request_snapshot("source", 1, &source_root)
+            .expect("Failed to request snapshot");
+        let mut request = StateSyncRequest::new();
+        request.set_height(1);
+        request.set_state_root(source_root);
+        source
+            .handle_request("target", &request)
+            .expect("Failed to handle request");
+
+        let mut sent = source_sender
+            .sent
+            .lock()
+            .expect("sent lock poisoned")
+            .clone();
+        assert_eq!(sent.len(), 1);
+        let (recipient, bytes) = sent.pop().expect("No response was sent");
+        assert_eq!(recipient, "target");
+        let mut msg: ScabbardMessage =
+            protobuf::parse_from_bytes(&bytes).expect("Failed to parse message");
+        let response = msg.take_state_sync_response();
+        assert!(response.get_refused());
+        assert!(response.get_last_chunk());
+        assert!(response.get_entries().is_empty());
+
+        target
+            .handle_response("source", response)
+            .expect("Failed to handle response");
+        assert!(target
+            .incoming
+            .lock()
+            .expect("incoming lock poisoned")
+            .is_empty());
+
+        let start = Instant::now();
+        assert!(!target
+            .wait_for_snapshot("source", 1, Duration::from_secs(10))
+            .expect("Failed to wait for snapshot"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            target
+                .state
+                .lock()
+                .expect("state lock poisoned")
+                .current_height(),
+            0
+        );
+    }
+
+    /// Commits a change that sets the given address to the given value in the synchronizer's
+    /// state, and returns the new state root.
+    fn commit_entry(sync: &StateSynchronizer, address: &str, value: &[u8]) -> String {
+        let signer = HashSigner::default();
+        let batch = BatchBuilder::new()
+            .with_transactions(vec![
+                make_command_transaction(&[Command::SetState(SetState::new(vec![
+                    BytesEntry::new(address.into(), value.to_vec()),
+                ]))])
+                .take()
+                .0,
+            ])
+            .build_pair(&signer)
+            .expect("Failed to build batch");
+        let mut state = sync.state.lock().expect("state lock poisoned");
+        state
+            .prepare_change(vec![batch])
+            .expect("Failed to prepare change");
+        state.commit().expect("Failed to commit change");
+        state.current_state_root().to_string()
+    }
+
+    fn synchronizer(
+        dir: &TempDir,
+        peer_service: &str,
+        sender: MockServiceNetworkSender,
+    ) -> StateSynchronizer {
+        let mut peer_services = HashSet::new();
+        peer_services.insert(peer_service.to_string());
+        let shared = ScabbardShared::new(
+            VecDeque::new(),
+            Some(Box::new(sender)),
+            peer_services,
+            Box::new(HashVerifier),
+        );
+        let state = ScabbardState::new(
+            &dir.path().join("state.lmdb"),
+            TEMP_DB_SIZE,
+            &dir.path().join("receipts.lmdb"),
+            TEMP_DB_SIZE,
+            vec![],
+        )
+        .expect("Failed to initialize state");
+
+        StateSynchronizer::new(Arc::new(Mutex::new(shared)), Arc::new(Mutex::new(state)))
+    }
+}
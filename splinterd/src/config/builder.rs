@@ -257,6 +257,21 @@ impl ConfigBuilder {
                 .ok_or_else(|| {
                     ConfigError::MissingValue("admin service coordinator timeout".to_string())
                 })?,
+            proposal_ttl: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.proposal_ttl() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }),
+            durable_message_limit: self
+                .partial_configs
+                .iter()
+                .find_map(|p| match p.durable_message_limit() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                })
+                .ok_or_else(|| ConfigError::MissingValue("durable message limit".to_string()))?,
 
             state_dir: self
                 .partial_configs
@@ -299,6 +314,20 @@ impl ConfigBuilder {
                     Some(v) => Some((v, p.source())),
                     None => None,
                 }),
+            #[cfg(feature = "tls-peer-identity")]
+            tls_peer_identity: self.partial_configs.iter().find_map(|p| {
+                match p.tls_peer_identity() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
+            #[cfg(feature = "challenge-authorization")]
+            challenge_authorization_key: self.partial_configs.iter().find_map(|p| {
+                match p.challenge_authorization_key() {
+                    Some(v) => Some((v, p.source())),
+                    None => None,
+                }
+            }),
             strict_ref_counts: self
                 .partial_configs
                 .iter()
@@ -80,6 +80,8 @@ impl<'a> PartialConfigBuilder for ClapPartialConfigBuilder<'_> {
             .with_registry_auto_refresh(parse_value(&self.matches, "registry_auto_refresh")?)
             .with_registry_forced_refresh(parse_value(&self.matches, "registry_forced_refresh")?)
             .with_heartbeat(parse_value(&self.matches, "heartbeat")?)
+            .with_proposal_ttl(parse_value(&self.matches, "proposal_ttl")?)
+            .with_durable_message_limit(parse_value(&self.matches, "durable_message_limit")?)
             .with_tls_insecure(if self.matches.is_present("tls_insecure") {
                 Some(true)
             } else {
@@ -123,6 +125,22 @@ impl<'a> PartialConfigBuilder for ClapPartialConfigBuilder<'_> {
             )
         }
 
+        #[cfg(feature = "tls-peer-identity")]
+        {
+            partial_config = partial_config.with_tls_peer_identity(
+                self.matches.value_of("tls_peer_identity").map(String::from),
+            )
+        }
+
+        #[cfg(feature = "challenge-authorization")]
+        {
+            partial_config = partial_config.with_challenge_authorization_key(
+                self.matches
+                    .value_of("challenge_authorization_key")
+                    .map(String::from),
+            )
+        }
+
         Ok(partial_config)
     }
 }
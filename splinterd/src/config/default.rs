@@ -39,6 +39,7 @@ const REGISTRY_AUTO_REFRESH: u64 = 600; // 600 seconds = 10 minutes
 const REGISTRY_FORCED_REFRESH: u64 = 10; // 10 seconds
 const HEARTBEAT: u64 = 30; // 30 seconds
 const ADMIN_TIMEOUT: u64 = 30; // 30 seconds
+const DURABLE_MESSAGE_LIMIT: u64 = 16 * 1024 * 1024; // 16 MiB
 
 pub struct DefaultPartialConfigBuilder;
 
@@ -70,6 +71,7 @@ impl PartialConfigBuilder for DefaultPartialConfigBuilder {
             .with_registry_forced_refresh(Some(REGISTRY_FORCED_REFRESH))
             .with_heartbeat(Some(HEARTBEAT))
             .with_admin_timeout(Some(ADMIN_TIMEOUT))
+            .with_durable_message_limit(Some(DURABLE_MESSAGE_LIMIT))
             .with_state_dir(Some(String::from(STATE_DIR)))
             .with_tls_insecure(Some(false))
             .with_no_tls(Some(false))
@@ -144,6 +146,7 @@ mod tests {
             config.admin_timeout(),
             Some(Duration::from_secs(ADMIN_TIMEOUT))
         );
+        assert_eq!(config.durable_message_limit(), Some(DURABLE_MESSAGE_LIMIT));
         assert_eq!(config.state_dir(), Some(String::from(STATE_DIR)));
         assert_eq!(config.tls_insecure(), Some(false));
         assert_eq!(config.no_tls(), Some(false));
@@ -63,6 +63,8 @@ pub struct Config {
     registry_forced_refresh: (u64, ConfigSource),
     heartbeat: (u64, ConfigSource),
     admin_timeout: (Duration, ConfigSource),
+    proposal_ttl: Option<(Duration, ConfigSource)>,
+    durable_message_limit: (u64, ConfigSource),
     state_dir: (String, ConfigSource),
     tls_insecure: (bool, ConfigSource),
     no_tls: (bool, ConfigSource),
@@ -70,6 +72,10 @@ pub struct Config {
     enable_biome: (bool, ConfigSource),
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<(Vec<String>, ConfigSource)>,
+    #[cfg(feature = "tls-peer-identity")]
+    tls_peer_identity: Option<(String, ConfigSource)>,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorization_key: Option<(String, ConfigSource)>,
     strict_ref_counts: (bool, ConfigSource),
 }
 
@@ -168,6 +174,18 @@ impl Config {
         self.admin_timeout.0
     }
 
+    pub fn proposal_ttl(&self) -> Option<Duration> {
+        if let Some((ttl, _)) = &self.proposal_ttl {
+            Some(*ttl)
+        } else {
+            None
+        }
+    }
+
+    pub fn durable_message_limit(&self) -> u64 {
+        self.durable_message_limit.0
+    }
+
     pub fn state_dir(&self) -> &str {
         &self.state_dir.0
     }
@@ -194,6 +212,24 @@ impl Config {
         }
     }
 
+    #[cfg(feature = "tls-peer-identity")]
+    pub fn tls_peer_identity(&self) -> Option<&str> {
+        if let Some((rule, _)) = &self.tls_peer_identity {
+            Some(rule)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    pub fn challenge_authorization_key(&self) -> Option<&str> {
+        if let Some((key_file, _)) = &self.challenge_authorization_key {
+            Some(key_file)
+        } else {
+            None
+        }
+    }
+
     pub fn strict_ref_counts(&self) -> bool {
         self.strict_ref_counts.0
     }
@@ -292,6 +328,18 @@ impl Config {
         &self.admin_timeout.1
     }
 
+    fn durable_message_limit_source(&self) -> &ConfigSource {
+        &self.durable_message_limit.1
+    }
+
+    fn proposal_ttl_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.proposal_ttl {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
     fn state_dir_source(&self) -> &ConfigSource {
         &self.state_dir.1
     }
@@ -318,6 +366,24 @@ impl Config {
         }
     }
 
+    #[cfg(feature = "tls-peer-identity")]
+    fn tls_peer_identity_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.tls_peer_identity {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    fn challenge_authorization_key_source(&self) -> Option<&ConfigSource> {
+        if let Some((_, source)) = &self.challenge_authorization_key {
+            Some(source)
+        } else {
+            None
+        }
+    }
+
     fn strict_ref_counts_source(&self) -> &ConfigSource {
         &self.strict_ref_counts.1
     }
@@ -427,6 +493,14 @@ impl Config {
             self.admin_timeout(),
             self.admin_timeout_source()
         );
+        if let (Some(ttl), Some(source)) = (self.proposal_ttl(), self.proposal_ttl_source()) {
+            debug!("Config: proposal_ttl: {:?} (source: {:?})", ttl, source);
+        }
+        debug!(
+            "Config: durable_message_limit: {} (source: {:?})",
+            self.durable_message_limit(),
+            self.durable_message_limit_source()
+        );
         #[cfg(feature = "database")]
         debug!(
             "database: {} (source: {:?})",
@@ -451,6 +525,26 @@ impl Config {
         );
         #[cfg(feature = "rest-api-cors")]
         self.log_whitelist();
+        #[cfg(feature = "tls-peer-identity")]
+        {
+            if let (Some(rule), Some(source)) =
+                (self.tls_peer_identity(), self.tls_peer_identity_source())
+            {
+                debug!("Config: tls_peer_identity: {} (source: {:?})", rule, source);
+            }
+        }
+        #[cfg(feature = "challenge-authorization")]
+        {
+            if let (Some(key_file), Some(source)) = (
+                self.challenge_authorization_key(),
+                self.challenge_authorization_key_source(),
+            ) {
+                debug!(
+                    "Config: challenge_authorization_key: {} (source: {:?})",
+                    key_file, source
+                );
+            }
+        }
         debug!(
             "Config: strict_ref_counts: {:?} (source: {:?})",
             self.strict_ref_counts(),
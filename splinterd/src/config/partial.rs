@@ -56,6 +56,8 @@ pub struct PartialConfig {
     registry_forced_refresh: Option<u64>,
     heartbeat: Option<u64>,
     admin_timeout: Option<Duration>,
+    proposal_ttl: Option<Duration>,
+    durable_message_limit: Option<u64>,
     state_dir: Option<String>,
     tls_insecure: Option<bool>,
     no_tls: Option<bool>,
@@ -63,6 +65,10 @@ pub struct PartialConfig {
     enable_biome: Option<bool>,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "tls-peer-identity")]
+    tls_peer_identity: Option<String>,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorization_key: Option<String>,
     strict_ref_counts: Option<bool>,
 }
 
@@ -94,6 +100,8 @@ impl PartialConfig {
             registry_forced_refresh: None,
             heartbeat: None,
             admin_timeout: None,
+            proposal_ttl: None,
+            durable_message_limit: None,
             state_dir: None,
             tls_insecure: None,
             no_tls: None,
@@ -101,6 +109,10 @@ impl PartialConfig {
             enable_biome: None,
             #[cfg(feature = "rest-api-cors")]
             whitelist: None,
+            #[cfg(feature = "tls-peer-identity")]
+            tls_peer_identity: None,
+            #[cfg(feature = "challenge-authorization")]
+            challenge_authorization_key: None,
             strict_ref_counts: None,
         }
     }
@@ -195,6 +207,14 @@ impl PartialConfig {
         self.admin_timeout
     }
 
+    pub fn proposal_ttl(&self) -> Option<Duration> {
+        self.proposal_ttl
+    }
+
+    pub fn durable_message_limit(&self) -> Option<u64> {
+        self.durable_message_limit
+    }
+
     pub fn state_dir(&self) -> Option<String> {
         self.state_dir.clone()
     }
@@ -217,6 +237,16 @@ impl PartialConfig {
         self.whitelist.clone()
     }
 
+    #[cfg(feature = "tls-peer-identity")]
+    pub fn tls_peer_identity(&self) -> Option<String> {
+        self.tls_peer_identity.clone()
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    pub fn challenge_authorization_key(&self) -> Option<String> {
+        self.challenge_authorization_key.clone()
+    }
+
     pub fn strict_ref_counts(&self) -> Option<bool> {
         self.strict_ref_counts
     }
@@ -463,6 +493,29 @@ impl PartialConfig {
         self
     }
 
+    /// Adds a `proposal_ttl` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposal_ttl` - The default time-to-live for circuit proposals (in seconds).
+    ///
+    pub fn with_proposal_ttl(mut self, proposal_ttl: Option<u64>) -> Self {
+        self.proposal_ttl = proposal_ttl.map(Duration::from_secs);
+        self
+    }
+
+    /// Adds a `durable_message_limit` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `durable_message_limit` - The maximum size of the messages stored for each durable
+    ///   circuit (in bytes).
+    ///
+    pub fn with_durable_message_limit(mut self, durable_message_limit: Option<u64>) -> Self {
+        self.durable_message_limit = durable_message_limit;
+        self
+    }
+
     /// Adds a `state_dir` value to the `PartialConfig` object.
     ///
     /// # Arguments
@@ -520,6 +573,35 @@ impl PartialConfig {
         self
     }
 
+    #[cfg(feature = "tls-peer-identity")]
+    /// Adds a `tls_peer_identity` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `tls_peer_identity` - The peer certificate field that holds the node ID of the peer,
+    ///   either "cn" or "san"
+    ///
+    pub fn with_tls_peer_identity(mut self, tls_peer_identity: Option<String>) -> Self {
+        self.tls_peer_identity = tls_peer_identity;
+        self
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    /// Adds a `challenge_authorization_key` value to the `PartialConfig` object.
+    ///
+    /// # Arguments
+    ///
+    /// * `challenge_authorization_key` - The file holding the node's private key, used to
+    ///   require challenge authorization of peers
+    ///
+    pub fn with_challenge_authorization_key(
+        mut self,
+        challenge_authorization_key: Option<String>,
+    ) -> Self {
+        self.challenge_authorization_key = challenge_authorization_key;
+        self
+    }
+
     /// Adds a `strict_ref_counts` value to the `PartialConfig` object.
     ///
     /// # Arguments
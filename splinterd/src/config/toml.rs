@@ -50,9 +50,15 @@ struct TomlConfig {
     registry_forced_refresh: Option<u64>,
     heartbeat: Option<u64>,
     admin_timeout: Option<u64>,
+    proposal_ttl: Option<u64>,
+    durable_message_limit: Option<u64>,
     version: Option<String>,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "tls-peer-identity")]
+    tls_peer_identity: Option<String>,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorization_key: Option<String>,
 
     // Deprecated values
     cert_dir: Option<String>,
@@ -132,7 +138,9 @@ impl PartialConfigBuilder for TomlPartialConfigBuilder {
             .with_registry_auto_refresh(self.toml_config.registry_auto_refresh)
             .with_registry_forced_refresh(self.toml_config.registry_forced_refresh)
             .with_heartbeat(self.toml_config.heartbeat)
-            .with_admin_timeout(self.toml_config.admin_timeout);
+            .with_admin_timeout(self.toml_config.admin_timeout)
+            .with_proposal_ttl(self.toml_config.proposal_ttl)
+            .with_durable_message_limit(self.toml_config.durable_message_limit);
 
         #[cfg(feature = "service-endpoint")]
         {
@@ -149,6 +157,18 @@ impl PartialConfigBuilder for TomlPartialConfigBuilder {
             partial_config = partial_config.with_whitelist(self.toml_config.whitelist);
         }
 
+        #[cfg(feature = "tls-peer-identity")]
+        {
+            partial_config =
+                partial_config.with_tls_peer_identity(self.toml_config.tls_peer_identity);
+        }
+
+        #[cfg(feature = "challenge-authorization")]
+        {
+            partial_config = partial_config
+                .with_challenge_authorization_key(self.toml_config.challenge_authorization_key);
+        }
+
         // deprecated values, only set if the current value was not set
         if partial_config.tls_cert_dir().is_none() {
             partial_config = partial_config.with_tls_cert_dir(self.toml_config.cert_dir)
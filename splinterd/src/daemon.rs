@@ -29,17 +29,36 @@ use scabbard::service::ScabbardArgValidator;
 use scabbard::service::ScabbardFactory;
 use splinter::admin::rest_api::CircuitResourceProvider;
 use splinter::admin::service::{admin_service_id, AdminService};
+#[cfg(feature = "admin-service-store")]
+use splinter::admin::store::yaml::YamlAdminServiceStore;
+#[cfg(feature = "admin-service-store")]
+use splinter::admin::store::{AdminServiceStore, MemoryAdminServiceStore};
 #[cfg(feature = "biome")]
 use splinter::biome::rest_api::{BiomeRestResourceManager, BiomeRestResourceManagerBuilder};
 use splinter::circuit::directory::CircuitDirectory;
+use splinter::circuit::durable::{
+    DurableMessageQueue, DurableMessageStore, FileDurableMessageStore, MemoryDurableMessageStore,
+};
 use splinter::circuit::handlers::{
     AdminDirectMessageHandler, CircuitDirectMessageHandler, CircuitErrorHandler,
-    CircuitMessageHandler, ServiceConnectRequestHandler, ServiceDisconnectRequestHandler,
+    CircuitMessageHandler, CircuitRelayMessageHandler, ServiceConnectRequestHandler,
+    ServiceDisconnectRequestHandler,
+};
+#[cfg(feature = "routing-table")]
+use splinter::circuit::routing::{
+    memory::RoutingTable, Circuit as RoutingCircuit, CircuitNode as RoutingCircuitNode,
+    RoutingTableWriter, Service as RoutingService,
 };
 use splinter::circuit::{SplinterState, SplinterStateError};
 use splinter::keys::insecure::AllowAllKeyPermissionManager;
 use splinter::mesh::Mesh;
 use splinter::network::auth::AuthorizationManager;
+#[cfg(feature = "challenge-authorization")]
+use splinter::network::auth::ChallengeAuthorization;
+#[cfg(feature = "tls-peer-identity")]
+use splinter::network::connection_manager::authorizers::{
+    CertificateIdentityRule, TlsIdentityAuthorizer,
+};
 use splinter::network::connection_manager::{
     authorizers::Authorizers, authorizers::InprocAuthorizer, ConnectionManager, Connector,
 };
@@ -47,10 +66,13 @@ use splinter::network::dispatch::{
     dispatch_channel, DispatchLoopBuilder, DispatchMessageSender, Dispatcher,
 };
 use splinter::network::handlers::{NetworkEchoHandler, NetworkHeartbeatHandler};
+use splinter::network::ping::NetworkPinger;
+#[cfg(feature = "network-rest-api")]
+use splinter::network::rest_api::NetworkResourceProvider;
 use splinter::orchestrator::{NewOrchestratorError, ServiceOrchestrator};
 use splinter::peer::interconnect::NetworkMessageSender;
 use splinter::peer::interconnect::PeerInterconnectBuilder;
-use splinter::peer::PeerManager;
+use splinter::peer::{ConnectedPeers, PeerManager};
 use splinter::protos::circuit::CircuitMessageType;
 use splinter::protos::network::NetworkMessageType;
 use splinter::registry::{
@@ -64,7 +86,8 @@ use splinter::rest_api::{
 use splinter::service::validation::ServiceArgValidator;
 use splinter::service::{self, ServiceProcessor, ShutdownHandle};
 use splinter::signing::sawtooth::SawtoothSecp256k1SignatureVerifier;
-use splinter::storage::get_storage;
+#[cfg(feature = "challenge-authorization")]
+use splinter::signing::Signer;
 use splinter::transport::{
     inproc::InprocTransport, multi::MultiTransport, AcceptError, ConnectError, Connection,
     Incoming, ListenError, Listener, Transport,
@@ -80,6 +103,10 @@ const ADMIN_SERVICE_PROCESSOR_INCOMING_CAPACITY: usize = 8;
 const ADMIN_SERVICE_PROCESSOR_OUTGOING_CAPACITY: usize = 8;
 const ADMIN_SERVICE_PROCESSOR_CHANNEL_CAPACITY: usize = 8;
 
+// How long admin messages are held for a node that does not reconnect, when proposals do not
+// expire
+const DEFAULT_ADMIN_MESSAGE_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 #[cfg(feature = "health")]
 const HEALTH_SERVICE_PROCESSOR_INCOMING_CAPACITY: usize = 8;
 #[cfg(feature = "health")]
@@ -109,8 +136,14 @@ pub struct SplinterDaemon {
     registry_forced_refresh: u64,
     storage_type: String,
     admin_timeout: Duration,
+    proposal_ttl: Option<Duration>,
+    durable_message_limit: u64,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "tls-peer-identity")]
+    tls_peer_identity: Option<CertificateIdentityRule>,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorization_signer: Option<Box<dyn Signer + Send + Sync>>,
     heartbeat: u64,
     strict_ref_counts: bool,
 }
@@ -123,28 +156,16 @@ impl SplinterDaemon {
         let mut service_transport = InprocTransport::default();
         transport.add_transport(Box::new(service_transport.clone()));
 
-        // Load initial state from the configured storage type and state directory, then create the
-        // new SplinterState from the retrieved circuit directory
-        let storage_location = match &self.storage_type as &str {
-            "yaml" => Path::new(&self.state_dir)
-                .join("circuits.yaml")
-                .to_str()
-                .ok_or_else(|| {
-                    StartError::StorageError("'state_dir' is not a valid UTF-8 string".into())
-                })?
-                .to_string(),
-            "memory" => "memory".to_string(),
-            _ => {
-                return Err(StartError::StorageError(format!(
-                    "storage type is not supported: {}",
-                    self.storage_type
-                )))
-            }
+        // Circuits and proposals are kept in the admin store, which is backed by the configured
+        // database if there is one, or by the configured storage type otherwise
+        #[cfg(feature = "admin-service-store")]
+        let admin_store = match &self.db_url {
+            Some(db_url) => build_admin_service_store(db_url)?,
+            None => self.build_storage_admin_service_store()?,
         };
-        let storage = get_storage(&storage_location, CircuitDirectory::new)
-            .map_err(StartError::StorageError)?;
-        let circuit_directory = storage.read().clone();
-        let state = SplinterState::new(storage_location, circuit_directory);
+
+        // The admin service loads the circuits in the admin store into splinter state
+        let state = SplinterState::new("memory".to_string(), CircuitDirectory::new());
 
         // set up the listeners on the transport. This will set up listeners for different
         // transports based on the protocol prefix of the endpoint.
@@ -178,11 +199,21 @@ impl SplinterDaemon {
         #[cfg(feature = "health")]
         internal_service_listeners.push(transport.listen("inproc://health_service")?);
 
+        let (registry, registry_shutdown) = create_registry(
+            &self.state_dir,
+            &self.registries,
+            self.registry_auto_refresh,
+            self.registry_forced_refresh,
+        )?;
+
         info!("Starting SpinterNode with ID {}", self.node_id);
-        let authorization_manager =
-            AuthorizationManager::new(self.node_id.clone()).map_err(|err| {
-                StartError::NetworkError(format!("Unable to create authorization manager: {}", err))
-            })?;
+        let authorization_manager = build_authorization_manager(
+            &self.node_id,
+            #[cfg(feature = "challenge-authorization")]
+            self.challenge_authorization_signer.take(),
+            #[cfg(feature = "challenge-authorization")]
+            &*registry,
+        )?;
 
         // Allowing unused_mut because inproc_ids must be mutable if feature health is enabled
         #[allow(unused_mut)]
@@ -207,6 +238,17 @@ impl SplinterDaemon {
 
         let mut authorizers = Authorizers::new();
         authorizers.add_authorizer("inproc", inproc_authorizer);
+        // With a TLS peer identity rule, every other connection must present a peer certificate
+        // that holds the identity it claims, so connections over tcp:// or unix:// are rejected
+        #[cfg(feature = "tls-peer-identity")]
+        match self.tls_peer_identity {
+            Some(rule) => authorizers.add_authorizer(
+                "",
+                TlsIdentityAuthorizer::new(rule, authorization_manager.authorization_connector()),
+            ),
+            None => authorizers.add_authorizer("", authorization_manager.authorization_connector()),
+        }
+        #[cfg(not(feature = "tls-peer-identity"))]
         authorizers.add_authorizer("", authorization_manager.authorization_connector());
 
         let connection_manager = ConnectionManager::builder()
@@ -284,6 +326,39 @@ impl SplinterDaemon {
             })?;
 
         let network_sender = interconnect.new_network_sender();
+        let network_pinger = NetworkPinger::new(Box::new(network_sender.clone()));
+
+        // Messages on durable circuits for members that are not connected are held here until
+        // the members reconnect
+        let durable_message_queue = DurableMessageQueue::new(
+            self.build_durable_message_store()?,
+            self.durable_message_limit as usize,
+            peer_connector.clone(),
+        );
+        // Admin messages are only useful while the proposals they concern are pending
+        durable_message_queue
+            .set_message_ttl(
+                "admin",
+                self.proposal_ttl.unwrap_or(DEFAULT_ADMIN_MESSAGE_TTL),
+            )
+            .map_err(|err| {
+                StartError::NetworkError(format!(
+                    "Unable to set admin message time-to-live: {}",
+                    err
+                ))
+            })?;
+        let durable_message_forwarder_join = durable_message_queue
+            .start_forwarding(Box::new(network_sender.clone()))
+            .map_err(|err| {
+                StartError::NetworkError(format!(
+                    "Unable to start durable message forwarder: {}",
+                    err
+                ))
+            })?;
+
+        let connected_peers = ConnectedPeers::track(&peer_connector).map_err(|err| {
+            StartError::NetworkError(format!("Unable to track connected peers: {}", err))
+        })?;
 
         // Set up the Circuit dispatcher
         let circuit_dispatcher = set_up_circuit_dispatcher(
@@ -291,6 +366,8 @@ impl SplinterDaemon {
             &self.node_id,
             &self.network_endpoints,
             state.clone(),
+            connected_peers,
+            durable_message_queue.clone(),
         );
         let circuit_dispatch_loop = DispatchLoopBuilder::new()
             .with_dispatcher(circuit_dispatcher)
@@ -304,8 +381,13 @@ impl SplinterDaemon {
         let circuit_dispatcher_shutdown = circuit_dispatch_loop.shutdown_signaler();
 
         // Set up the Network dispatcher
-        let network_dispatcher =
-            set_up_network_dispatcher(network_sender, &self.node_id, circuit_dispatch_sender);
+        let network_dispatcher = set_up_network_dispatcher(
+            network_sender,
+            &self.node_id,
+            circuit_dispatch_sender,
+            connection_connector.clone(),
+            network_pinger.clone(),
+        );
 
         let network_dispatch_loop = DispatchLoopBuilder::new()
             .with_dispatcher(network_dispatcher)
@@ -381,13 +463,6 @@ impl SplinterDaemon {
 
         let signature_verifier = SawtoothSecp256k1SignatureVerifier::new();
 
-        let (registry, registry_shutdown) = create_registry(
-            &self.state_dir,
-            &self.registries,
-            self.registry_auto_refresh,
-            self.registry_forced_refresh,
-        )?;
-
         let (admin_service, admin_notification_join) = AdminService::new(
             &self.node_id,
             orchestrator,
@@ -403,7 +478,11 @@ impl SplinterDaemon {
             Box::new(signature_verifier),
             Box::new(registry.clone_box_as_reader()),
             Box::new(AllowAllKeyPermissionManager),
+            #[cfg(feature = "admin-service-store")]
+            admin_store.clone(),
+            #[cfg(not(feature = "admin-service-store"))]
             &self.storage_type,
+            #[cfg(not(feature = "admin-service-store"))]
             &self.state_dir,
             Some(self.admin_timeout),
         )
@@ -411,6 +490,50 @@ impl SplinterDaemon {
             StartError::AdminServiceError(format!("unable to create admin service: {}", err))
         })?;
 
+        admin_service
+            .set_default_proposal_ttl(self.proposal_ttl)
+            .map_err(|err| {
+                StartError::AdminServiceError(format!(
+                    "unable to set admin service proposal time-to-live: {}",
+                    err
+                ))
+            })?;
+
+        admin_service
+            .set_durable_message_queue(&durable_message_queue)
+            .map_err(|err| {
+                StartError::AdminServiceError(format!(
+                    "unable to set admin service durable message queue: {}",
+                    err
+                ))
+            })?;
+
+        // The admin service keeps the routing table in sync with the circuits it manages, once it
+        // has been populated with the circuits the admin service loaded into splinter state
+        #[cfg(feature = "routing-table")]
+        admin_service
+            .set_routing_table_writer(Box::new(build_routing_table(&state)?))
+            .map_err(|err| {
+                StartError::AdminServiceError(format!(
+                    "unable to set admin service routing table: {}",
+                    err
+                ))
+            })?;
+
+        #[cfg(feature = "admin-service-event-store")]
+        {
+            if let Some(db_url) = &self.db_url {
+                admin_service
+                    .set_event_store(build_admin_service_event_store(db_url)?)
+                    .map_err(|err| {
+                        StartError::AdminServiceError(format!(
+                            "unable to set admin service event store: {}",
+                            err
+                        ))
+                    })?;
+            }
+        }
+
         let node_id = self.node_id.clone();
         let display_name = self.display_name.clone();
         #[cfg(feature = "service-endpoint")]
@@ -418,6 +541,10 @@ impl SplinterDaemon {
         let network_endpoints = self.network_endpoints.clone();
         let advertised_endpoints = self.advertised_endpoints.clone();
 
+        #[cfg(feature = "admin-service-store")]
+        let circuit_resource_provider =
+            CircuitResourceProvider::new(self.node_id.to_string(), admin_store);
+        #[cfg(not(feature = "admin-service-store"))]
         let circuit_resource_provider =
             CircuitResourceProvider::new(self.node_id.to_string(), state);
 
@@ -466,6 +593,13 @@ impl SplinterDaemon {
             }
         }
 
+        #[cfg(feature = "network-rest-api")]
+        {
+            let network_resources = NetworkResourceProvider::new(connection_connector.clone())
+                .with_pinger(network_pinger);
+            rest_api_builder = rest_api_builder.add_resources(network_resources.resources());
+        }
+
         let mut health_service_processor_join_handle: Option<_> = None;
         #[cfg(feature = "health")]
         {
@@ -534,6 +668,7 @@ impl SplinterDaemon {
         debug!("Shutting down admin service's peer manager notification receiver...");
         let _ = admin_notification_join.join();
         debug!("Shutting down admin service's peer manager notification receiver (complete)");
+        let _ = durable_message_forwarder_join.join();
         connection_manager_shutdown.shutdown();
         connection_manager.await_shutdown();
         self.mesh.shutdown_signaler().shutdown();
@@ -635,6 +770,66 @@ impl SplinterDaemon {
             )
         })?
     }
+
+    /// Build the store for messages on durable circuits. With YAML storage, the messages are kept
+    /// in the state directory so they survive a restart.
+    fn build_durable_message_store(&self) -> Result<Box<dyn DurableMessageStore>, StartError> {
+        match &self.storage_type as &str {
+            "yaml" => {
+                let store = FileDurableMessageStore::new(
+                    Path::new(&self.state_dir).join("durable_messages"),
+                )
+                .map_err(|err| {
+                    StartError::StorageError(format!(
+                        "unable to load durable message store: {}",
+                        err
+                    ))
+                })?;
+
+                Ok(Box::new(store))
+            }
+            "memory" => Ok(Box::new(MemoryDurableMessageStore::new())),
+            _ => Err(StartError::StorageError(format!(
+                "storage type is not supported: {}",
+                self.storage_type
+            ))),
+        }
+    }
+
+    /// Build the admin store for the configured storage type. YAML storage keeps circuits and
+    /// proposals in the state directory.
+    #[cfg(feature = "admin-service-store")]
+    fn build_storage_admin_service_store(&self) -> Result<Box<dyn AdminServiceStore>, StartError> {
+        match &self.storage_type as &str {
+            "yaml" => {
+                let state_file = |file_name: &str| {
+                    Path::new(&self.state_dir)
+                        .join(file_name)
+                        .to_str()
+                        .map(String::from)
+                        .ok_or_else(|| {
+                            StartError::StorageError(
+                                "'state_dir' is not a valid UTF-8 string".into(),
+                            )
+                        })
+                };
+                let store = YamlAdminServiceStore::new(
+                    state_file("circuits.yaml")?,
+                    state_file("circuit_proposals.yaml")?,
+                )
+                .map_err(|err| {
+                    StartError::StorageError(format!("unable to load admin store: {}", err))
+                })?;
+
+                Ok(Box::new(store))
+            }
+            "memory" => Ok(Box::new(MemoryAdminServiceStore::new())),
+            _ => Err(StartError::StorageError(format!(
+                "storage type is not supported: {}",
+                self.storage_type
+            ))),
+        }
+    }
 }
 
 #[cfg(feature = "health")]
@@ -688,6 +883,79 @@ fn start_health_service(
     })?
 }
 
+/// Build a routing table holding the known nodes and the active circuits in splinter state.
+#[cfg(feature = "routing-table")]
+fn build_routing_table(state: &SplinterState) -> Result<RoutingTable, StartError> {
+    let mut routing_table = RoutingTable::default();
+
+    let nodes = state
+        .nodes()
+        .map_err(|err| StartError::AdminServiceError(format!("unable to list nodes: {}", err)))?
+        .into_iter()
+        .map(|(node_id, node)| RoutingCircuitNode::new(node_id, node.endpoints().to_vec()))
+        .collect();
+    routing_table.add_nodes(nodes).map_err(|err| {
+        StartError::AdminServiceError(format!("unable to add nodes to routing table: {}", err))
+    })?;
+
+    let circuits = state
+        .circuits()
+        .map_err(|err| StartError::AdminServiceError(format!("unable to list circuits: {}", err)))?
+        .into_iter()
+        .filter(|(_, circuit)| circuit.circuit_status().is_active())
+        .map(|(circuit_id, circuit)| {
+            let roster = circuit
+                .roster()
+                .iter()
+                .map(|service| {
+                    RoutingService::new(
+                        service.service_id().to_string(),
+                        service.service_type().to_string(),
+                        service.allowed_nodes().to_vec(),
+                        service
+                            .arguments()
+                            .iter()
+                            .map(|(key, value)| (key.to_string(), value.to_string()))
+                            .collect(),
+                    )
+                })
+                .collect();
+            RoutingCircuit::new(circuit_id, roster, circuit.members().to_vec())
+        })
+        .collect();
+    routing_table.add_circuits(circuits).map_err(|err| {
+        StartError::AdminServiceError(format!("unable to add circuits to routing table: {}", err))
+    })?;
+
+    Ok(routing_table)
+}
+
+#[cfg(feature = "admin-service-store")]
+fn build_admin_service_store(db_url: &str) -> Result<Box<dyn AdminServiceStore>, StartError> {
+    let connection_uri = db_url.parse().map_err(|err| {
+        StartError::StorageError(format!("Invalid database URL provided: {}", err))
+    })?;
+    let store_factory = splinter::store::create_store_factory(connection_uri).map_err(|err| {
+        StartError::StorageError(format!("Failed to initialize store factory: {}", err))
+    })?;
+
+    Ok(store_factory.get_admin_service_store())
+}
+
+#[cfg(feature = "admin-service-event-store")]
+fn build_admin_service_event_store(
+    db_url: &str,
+) -> Result<Box<dyn splinter::admin::service::event_store::AdminServiceEventStore>, StartError> {
+    let connection_uri = db_url.parse().map_err(|err| {
+        StartError::StorageError(format!("Invalid database URL provided: {}", err))
+    })?;
+    let store_factory = splinter::store::create_store_factory(connection_uri).map_err(|err| {
+        StartError::StorageError(format!("Failed to initialize store factory: {}", err))
+    })?;
+
+    Ok(store_factory.get_admin_service_event_store())
+}
+
 #[cfg(feature = "biome")]
 fn build_biome_routes(db_url: String) -> Result<BiomeRestResourceManager, StartError> {
     info!("Adding biome routes");
@@ -740,8 +1008,14 @@ pub struct SplinterDaemonBuilder {
     storage_type: Option<String>,
     heartbeat: Option<u64>,
     admin_timeout: Duration,
+    proposal_ttl: Option<Duration>,
+    durable_message_limit: Option<u64>,
     #[cfg(feature = "rest-api-cors")]
     whitelist: Option<Vec<String>>,
+    #[cfg(feature = "tls-peer-identity")]
+    tls_peer_identity: Option<CertificateIdentityRule>,
+    #[cfg(feature = "challenge-authorization")]
+    challenge_authorization_signer: Option<Box<dyn Signer + Send + Sync>>,
     strict_ref_counts: Option<bool>,
 }
 
@@ -833,12 +1107,37 @@ impl SplinterDaemonBuilder {
         self
     }
 
+    pub fn with_proposal_ttl(mut self, value: Option<Duration>) -> Self {
+        self.proposal_ttl = value;
+        self
+    }
+
+    pub fn with_durable_message_limit(mut self, value: u64) -> Self {
+        self.durable_message_limit = Some(value);
+        self
+    }
+
     #[cfg(feature = "rest-api-cors")]
     pub fn with_whitelist(mut self, value: Option<Vec<String>>) -> Self {
         self.whitelist = value;
         self
     }
 
+    #[cfg(feature = "tls-peer-identity")]
+    pub fn with_tls_peer_identity(mut self, value: Option<CertificateIdentityRule>) -> Self {
+        self.tls_peer_identity = value;
+        self
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    pub fn with_challenge_authorization_signer(
+        mut self,
+        value: Option<Box<dyn Signer + Send + Sync>>,
+    ) -> Self {
+        self.challenge_authorization_signer = value;
+        self
+    }
+
     pub fn with_strict_ref_counts(mut self, strict_ref_counts: bool) -> Self {
         self.strict_ref_counts = Some(strict_ref_counts);
         self
@@ -849,6 +1148,10 @@ impl SplinterDaemonBuilder {
             CreateError::MissingRequiredField("Missing field: heartbeat".to_string())
         })?;
 
+        let durable_message_limit = self.durable_message_limit.ok_or_else(|| {
+            CreateError::MissingRequiredField("Missing field: durable_message_limit".to_string())
+        })?;
+
         let node_id = self.node_id.ok_or_else(|| {
             CreateError::MissingRequiredField("Missing field: node_id".to_string())
         })?;
@@ -932,8 +1235,14 @@ impl SplinterDaemonBuilder {
             registry_forced_refresh,
             storage_type,
             admin_timeout: self.admin_timeout,
+            proposal_ttl: self.proposal_ttl,
+            durable_message_limit,
             #[cfg(feature = "rest-api-cors")]
             whitelist: self.whitelist,
+            #[cfg(feature = "tls-peer-identity")]
+            tls_peer_identity: self.tls_peer_identity,
+            #[cfg(feature = "challenge-authorization")]
+            challenge_authorization_signer: self.challenge_authorization_signer,
             heartbeat,
             strict_ref_counts,
         })
@@ -944,13 +1253,15 @@ fn set_up_network_dispatcher(
     network_sender: NetworkMessageSender,
     node_id: &str,
     circuit_sender: DispatchMessageSender<CircuitMessageType>,
+    connector: Connector,
+    pinger: NetworkPinger,
 ) -> Dispatcher<NetworkMessageType> {
     let mut dispatcher = Dispatcher::<NetworkMessageType>::new(Box::new(network_sender));
 
-    let network_echo_handler = NetworkEchoHandler::new(node_id.to_string());
+    let network_echo_handler = NetworkEchoHandler::new(node_id.to_string()).with_pinger(pinger);
     dispatcher.set_handler(Box::new(network_echo_handler));
 
-    let network_heartbeat_handler = NetworkHeartbeatHandler::new();
+    let network_heartbeat_handler = NetworkHeartbeatHandler::with_connector(connector);
     // do not add auth guard
     dispatcher.set_handler(Box::new(network_heartbeat_handler));
 
@@ -965,6 +1276,8 @@ fn set_up_circuit_dispatcher(
     node_id: &str,
     endpoints: &[String],
     state: SplinterState,
+    connected_peers: ConnectedPeers,
+    durable_message_queue: DurableMessageQueue,
 ) -> Dispatcher<CircuitMessageType> {
     let mut dispatcher = Dispatcher::<CircuitMessageType>::new(Box::new(network_sender));
 
@@ -976,19 +1289,58 @@ fn set_up_circuit_dispatcher(
     dispatcher.set_handler(Box::new(service_disconnect_request_handler));
 
     let direct_message_handler =
-        CircuitDirectMessageHandler::new(node_id.to_string(), state.clone());
+        CircuitDirectMessageHandler::new(node_id.to_string(), state.clone())
+            .with_connected_peers(connected_peers.clone())
+            .with_durable_message_queue(durable_message_queue.clone());
     dispatcher.set_handler(Box::new(direct_message_handler));
 
+    let relay_message_handler =
+        CircuitRelayMessageHandler::new(node_id.to_string(), state.clone(), connected_peers);
+    dispatcher.set_handler(Box::new(relay_message_handler));
+
     let circuit_error_handler = CircuitErrorHandler::new(node_id.to_string(), state.clone());
     dispatcher.set_handler(Box::new(circuit_error_handler));
 
     // Circuit Admin handlers
-    let admin_direct_message_handler = AdminDirectMessageHandler::new(node_id.to_string(), state);
+    let admin_direct_message_handler = AdminDirectMessageHandler::new(node_id.to_string(), state)
+        .with_durable_message_queue(durable_message_queue);
     dispatcher.set_handler(Box::new(admin_direct_message_handler));
 
     dispatcher
 }
 
+/// Creates the authorization manager for the node's connections.
+///
+/// If a challenge authorization signer is given, peers must prove the node ID they claim by
+/// signing a challenge with one of the keys that the registry lists for that node, and the signer
+/// is used to answer the challenges sent by peers.
+fn build_authorization_manager(
+    node_id: &str,
+    #[cfg(feature = "challenge-authorization")] challenge_authorization_signer: Option<
+        Box<dyn Signer + Send + Sync>,
+    >,
+    #[cfg(feature = "challenge-authorization")] registry: &dyn RwRegistry,
+) -> Result<AuthorizationManager, StartError> {
+    let authorization_manager = AuthorizationManager::new(node_id.to_string()).map_err(|err| {
+        StartError::NetworkError(format!("Unable to create authorization manager: {}", err))
+    })?;
+
+    #[cfg(feature = "challenge-authorization")]
+    {
+        if let Some(signer) = challenge_authorization_signer {
+            return Ok(authorization_manager.with_challenge_authorization(
+                ChallengeAuthorization::new(
+                    signer,
+                    Box::new(SawtoothSecp256k1SignatureVerifier::new()),
+                    registry.clone_box_as_reader(),
+                ),
+            ));
+        }
+    }
+
+    Ok(authorization_manager)
+}
+
 fn create_registry(
     state_dir: &str,
     registries: &[String],
@@ -1211,3 +1563,102 @@ impl From<SplinterStateError> for StartError {
         StartError::StateError(err.context())
     }
 }
+
+#[cfg(all(test, feature = "challenge-authorization"))]
+mod tests {
+    use super::*;
+
+    use std::sync::mpsc;
+
+    use protobuf::Message;
+    use splinter::network::auth::ConnectionAuthorizationState;
+    use splinter::protos::authorization::{
+        AuthorizationMessage, AuthorizationMessageType, TrustRequest,
+    };
+    use splinter::protos::network::NetworkMessage;
+    use splinter::signing::sawtooth::SawtoothSecp256k1Signer;
+    use splinter::transport::RecvError;
+    use tempdir::TempDir;
+
+    const NODE_PRIVATE_KEY: &str =
+        "2f1e7b7a130d7ba9da0068b3bb0ba1d79e7e77110302c9f746c3c2a63fe40088";
+
+    // test that a node started with a challenge authorization key rejects a peer that claims a
+    // node ID without signing a challenge
+    #[test]
+    fn test_challenge_authorization_rejects_unsigned_peer() {
+        let state_dir =
+            TempDir::new("test_challenge_authorization").expect("Unable to create temp dir");
+        let (registry, _registry_shutdown) = create_registry(
+            state_dir.path().to_str().expect("Invalid temp dir path"),
+            &[],
+            0,
+            0,
+        )
+        .expect("Unable to create registry");
+        let signer =
+            SawtoothSecp256k1Signer::from_hex(NODE_PRIVATE_KEY).expect("Unable to create signer");
+        let authorization_manager =
+            build_authorization_manager("test_node", Some(Box::new(signer)), &*registry)
+                .expect("Unable to create authorization manager");
+
+        let mut transport = InprocTransport::default();
+        let mut listener = transport
+            .listen("inproc://test_challenge_authorization")
+            .expect("Unable to listen");
+        let mut peer_connection = transport
+            .connect("inproc://test_challenge_authorization")
+            .expect("Unable to connect");
+        let node_connection = listener.accept().expect("Unable to accept connection");
+
+        let (tx, rx) = mpsc::channel();
+        authorization_manager
+            .authorization_connector()
+            .add_connection(
+                "test_connection".into(),
+                node_connection,
+                Box::new(move |state| {
+                    tx.send(state).expect("Unable to send authorization state");
+                    Ok(())
+                }),
+            )
+            .expect("Unable to add connection");
+
+        // the node starts the handshake with a connect request
+        loop {
+            match peer_connection.recv() {
+                Err(RecvError::WouldBlock) => thread::sleep(Duration::from_millis(10)),
+                res => {
+                    res.expect("Unable to receive connect request");
+                    break;
+                }
+            }
+        }
+
+        // claim a node ID without being challenged
+        let mut trust_request = TrustRequest::new();
+        trust_request.set_identity("unsigned_peer".into());
+        let mut auth_msg = AuthorizationMessage::new();
+        auth_msg.set_message_type(AuthorizationMessageType::TRUST_REQUEST);
+        auth_msg.set_payload(trust_request.write_to_bytes().unwrap());
+        let mut network_msg = NetworkMessage::new();
+        network_msg.set_message_type(NetworkMessageType::AUTHORIZATION);
+        network_msg.set_payload(auth_msg.write_to_bytes().unwrap());
+        peer_connection
+            .send(&network_msg.write_to_bytes().unwrap())
+            .expect("Unable to send trust request");
+
+        match rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("Authorization did not complete")
+        {
+            ConnectionAuthorizationState::Unauthorized { .. } => (),
+            ConnectionAuthorizationState::Authorized { identity, .. } => {
+                panic!("Peer should not have been authorized as {}", identity)
+            }
+        }
+
+        authorization_manager.shutdown_signaler().shutdown();
+        authorization_manager.wait_for_shutdown();
+    }
+}
@@ -36,6 +36,10 @@ use crate::config::{
 use crate::daemon::SplinterDaemonBuilder;
 use clap::{clap_app, crate_version};
 use clap::{Arg, ArgMatches};
+#[cfg(feature = "tls-peer-identity")]
+use splinter::network::connection_manager::authorizers::CertificateIdentityRule;
+#[cfg(feature = "challenge-authorization")]
+use splinter::signing::{sawtooth::SawtoothSecp256k1Signer, Signer};
 
 use std::env;
 use std::fs::{self, File};
@@ -142,6 +146,74 @@ fn find_node_id(config: &Config) -> Result<String, UserError> {
     }
 }
 
+#[cfg(feature = "tls-peer-identity")]
+fn parse_tls_peer_identity(config: &Config) -> Result<Option<CertificateIdentityRule>, UserError> {
+    let rule = match config.tls_peer_identity() {
+        Some("cn") => CertificateIdentityRule::CommonName,
+        Some("san") => CertificateIdentityRule::SubjectAltName,
+        Some(rule) => {
+            return Err(UserError::InvalidArgument(format!(
+                "tls_peer_identity must be either \"cn\" or \"san\", not {}",
+                rule
+            )))
+        }
+        None => return Ok(None),
+    };
+
+    // Peer certificates are only available if they are verified against the CA file
+    if config.no_tls() || config.tls_insecure() {
+        return Err(UserError::InvalidArgument(
+            "tls_peer_identity requires TLS with a CA file; it cannot be used with no_tls or \
+             tls_insecure"
+                .into(),
+        ));
+    }
+
+    // Only TLS connections carry a peer certificate; connections to and from other endpoints
+    // would not be checked
+    if let Some(endpoint) = config
+        .network_endpoints()
+        .iter()
+        .chain(config.peers())
+        .find(|endpoint| !endpoint.starts_with("tcps://"))
+    {
+        return Err(UserError::InvalidArgument(format!(
+            "tls_peer_identity requires every network endpoint and peer to use tcps://, not {}",
+            endpoint
+        )));
+    }
+
+    Ok(Some(rule))
+}
+
+#[cfg(feature = "challenge-authorization")]
+fn load_challenge_authorization_signer(
+    config: &Config,
+) -> Result<Option<Box<dyn Signer + Send + Sync>>, UserError> {
+    let key_file = match config.challenge_authorization_key() {
+        Some(key_file) => key_file,
+        None => return Ok(None),
+    };
+
+    let private_key = fs::read_to_string(key_file).map_err(|err| {
+        UserError::io_err_with_source(
+            &format!(
+                "Unable to read challenge authorization key file {}",
+                key_file
+            ),
+            Box::new(err),
+        )
+    })?;
+    let signer = SawtoothSecp256k1Signer::from_hex(private_key.trim()).map_err(|err| {
+        UserError::InvalidArgument(format!(
+            "Invalid challenge authorization key in {}: {}",
+            key_file, err
+        ))
+    })?;
+
+    Ok(Some(Box::new(signer)))
+}
+
 // format for logs
 pub fn log_format(
     w: &mut dyn std::io::Write,
@@ -181,6 +253,12 @@ fn main() {
         (@arg admin_timeout: --("admin-timeout") +takes_value
             "The coordinator timeout for admin service proposals (in seconds); default is \
              30 seconds")
+        (@arg proposal_ttl: --("proposal-ttl") +takes_value
+            "How long circuit proposals wait for votes before they expire (in seconds), unless \
+             the proposal specifies its own time-to-live; by default proposals do not expire")
+        (@arg durable_message_limit: --("durable-message-limit") +takes_value
+            "The maximum size of the messages stored for members of each durable circuit that \
+             are not connected (in bytes); default is 16777216 (16 MiB)")
         (@arg verbose: -v --verbose +multiple
           "Increase output verbosity"));
 
@@ -329,6 +407,30 @@ fn main() {
             .help("Whitelisted domains"),
     );
 
+    #[cfg(feature = "tls-peer-identity")]
+    let app = app.arg(
+        Arg::with_name("tls_peer_identity")
+            .long("tls-peer-identity")
+            .takes_value(true)
+            .possible_values(&["cn", "san"])
+            .help(
+                "Require peers to present a TLS certificate that holds their node ID, in either \
+                 the subject common name (cn) or a subject alternative name (san)",
+            ),
+    );
+
+    #[cfg(feature = "challenge-authorization")]
+    let app = app.arg(
+        Arg::with_name("challenge_authorization_key")
+            .long("challenge-authorization-key")
+            .takes_value(true)
+            .value_name("KEY-FILE")
+            .help(
+                "Require peers to sign a challenge with a key listed for them in the registry, \
+                 using the private key in the given file to answer the challenges of peers",
+            ),
+    );
+
     let matches = app.get_matches();
 
     let log_level = match matches.occurrences_of("verbose") {
@@ -413,6 +515,8 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
         .with_registry_forced_refresh(config.registry_forced_refresh())
         .with_heartbeat(config.heartbeat())
         .with_admin_timeout(admin_timeout)
+        .with_proposal_ttl(config.proposal_ttl())
+        .with_durable_message_limit(config.durable_message_limit())
         .with_strict_ref_counts(config.strict_ref_counts());
 
     #[cfg(feature = "service-endpoint")]
@@ -445,6 +549,17 @@ fn start_daemon(matches: ArgMatches) -> Result<(), UserError> {
         daemon_builder = daemon_builder.with_whitelist(config.whitelist().map(ToOwned::to_owned));
     }
 
+    #[cfg(feature = "tls-peer-identity")]
+    {
+        daemon_builder = daemon_builder.with_tls_peer_identity(parse_tls_peer_identity(&config)?);
+    }
+
+    #[cfg(feature = "challenge-authorization")]
+    {
+        daemon_builder = daemon_builder
+            .with_challenge_authorization_signer(load_challenge_authorization_signer(&config)?);
+    }
+
     let mut node = daemon_builder.build().map_err(|err| {
         UserError::daemon_err_with_source("unable to build the Splinter daemon", Box::new(err))
     })?;
@@ -18,6 +18,8 @@ use std::path::Path;
 use splinter::transport::multi::MultiTransport;
 use splinter::transport::socket::TcpTransport;
 use splinter::transport::socket::TlsTransport;
+#[cfg(feature = "unix-transport")]
+use splinter::transport::socket::UnixTransport;
 use splinter::transport::tls::{TlsConfig, TlsConfigBuilder};
 #[cfg(feature = "ws-transport")]
 use splinter::transport::ws::WsTransport;
@@ -35,6 +37,11 @@ pub fn build_transport(config: &Config) -> Result<MultiTransport, GetTransportEr
     // this will be default for endpoints without a prefix
     transports.push(Box::new(TcpTransport::default()));
 
+    // add unix domain socket transport
+    // the socket files are only accessible by the owner and group of the splinterd process
+    #[cfg(feature = "unix-transport")]
+    transports.push(Box::new(UnixTransport::default().with_permissions(0o660)));
+
     // add web socket transport
 
     // add tls transport